@@ -0,0 +1,171 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use prost::Message;
+
+use uprotocol_sdk::cloudevent::serializer::{
+    CloudEventJsonSerializer, CloudEventProtobufSerializer, CloudEventSerializer,
+};
+use uprotocol_sdk::fixtures::{self, LARGE_PAYLOAD_SIZE, MEDIUM_PAYLOAD_SIZE, SMALL_PAYLOAD_SIZE};
+use uprotocol_sdk::transport::datamodel::{BufferPool, ListenerIndex};
+use uprotocol_sdk::transport::validator::{UAttributesValidator, Validators};
+use uprotocol_sdk::uri::serializer::{
+    LongUriSerializer, MicroUriSerializer, UUriRef, UriSerializer,
+};
+
+const PAYLOAD_SIZES: [usize; 3] = [SMALL_PAYLOAD_SIZE, MEDIUM_PAYLOAD_SIZE, LARGE_PAYLOAD_SIZE];
+
+fn uri_serializers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("uri");
+
+    let long_uri = fixtures::remote_topic();
+    let long_form = LongUriSerializer::serialize(&long_uri).unwrap();
+    group.bench_function("long/serialize", |b| {
+        b.iter(|| LongUriSerializer::serialize(black_box(&long_uri)))
+    });
+    group.bench_function("long/deserialize", |b| {
+        b.iter(|| LongUriSerializer::deserialize(black_box(long_form.clone())))
+    });
+    group.bench_function("long/borrow", |b| {
+        b.iter(|| UUriRef::from_long_form(black_box(&long_form)))
+    });
+
+    let micro_uri = fixtures::resolved_topic();
+    let micro_form = MicroUriSerializer::serialize(&micro_uri).unwrap();
+    let buffers = BufferPool::default();
+    group.bench_function("micro/serialize", |b| {
+        b.iter(|| MicroUriSerializer::serialize(black_box(&micro_uri)))
+    });
+    group.bench_function("micro/serialize_pooled", |b| {
+        b.iter(|| MicroUriSerializer::serialize_pooled(black_box(&micro_uri), &buffers))
+    });
+    group.bench_function("micro/deserialize", |b| {
+        b.iter(|| MicroUriSerializer::deserialize(black_box(micro_form.clone())))
+    });
+    group.bench_function("micro/borrow", |b| {
+        b.iter(|| UUriRef::from_micro_form(black_box(&micro_form)))
+    });
+
+    group.finish();
+}
+
+fn message_encoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("message");
+    let buffers = BufferPool::default();
+
+    for size in PAYLOAD_SIZES {
+        let message = fixtures::publish_message(fixtures::resolved_topic(), size);
+        let encoded = message.encode_to_vec();
+        group.throughput(Throughput::Bytes(encoded.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("encode", size), &message, |b, message| {
+            b.iter(|| message.encode_to_vec())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("encode_pooled", size),
+            &message,
+            |b, message| b.iter(|| buffers.encode(message)),
+        );
+        group.bench_with_input(BenchmarkId::new("decode", size), &encoded, |b, encoded| {
+            b.iter(|| uprotocol_sdk::uprotocol::UMessage::decode(black_box(encoded.as_slice())))
+        });
+    }
+
+    group.finish();
+}
+
+fn attributes_validation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("attributes");
+
+    let request = fixtures::request_attributes();
+    let response = fixtures::response_attributes(request.id.clone().unwrap());
+    for (name, attributes) in [
+        ("publish", fixtures::publish_attributes()),
+        ("request", request),
+        ("response", response),
+    ] {
+        group.bench_with_input(
+            BenchmarkId::new("validate", name),
+            &attributes,
+            |b, attributes| {
+                b.iter(|| Validators::get_validator(attributes).validate(black_box(attributes)))
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn cloud_event_serializers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cloudevent");
+    let buffers = BufferPool::default();
+    let serializers: [(&str, &dyn CloudEventSerializer); 2] = [
+        ("json", &CloudEventJsonSerializer),
+        ("protobuf", &CloudEventProtobufSerializer),
+    ];
+
+    for size in [SMALL_PAYLOAD_SIZE, MEDIUM_PAYLOAD_SIZE] {
+        let cloud_event = fixtures::publish_cloud_event(&fixtures::local_topic(), size);
+        for (name, serializer) in serializers {
+            let serialized = serializer.serialize(&cloud_event).unwrap();
+            group.bench_with_input(
+                BenchmarkId::new(format!("{name}/serialize"), size),
+                &cloud_event,
+                |b, cloud_event| b.iter(|| serializer.serialize(black_box(cloud_event))),
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("{name}/serialize_pooled"), size),
+                &cloud_event,
+                |b, cloud_event| {
+                    b.iter(|| serializer.serialize_pooled(black_box(cloud_event), &buffers))
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("{name}/deserialize"), size),
+                &serialized,
+                |b, serialized| b.iter(|| serializer.deserialize(black_box(serialized))),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn listener_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("listeners");
+
+    for count in [10, 100, 1000] {
+        let topics = fixtures::topics(count);
+        let mut index = ListenerIndex::new();
+        for (listener, topic) in topics.iter().enumerate() {
+            index.insert(listener.to_string(), topic, listener);
+        }
+        let topic = &topics[count / 2];
+        group.bench_with_input(BenchmarkId::new("matching", count), topic, |b, topic| {
+            b.iter(|| index.matching(black_box(topic)).count())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    uri_serializers,
+    message_encoding,
+    attributes_validation,
+    cloud_event_serializers,
+    listener_lookup
+);
+criterion_main!(benches);
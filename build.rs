@@ -70,6 +70,10 @@ fn get_and_build_protos(urls: &[&str]) -> core::result::Result<(), Box<dyn std::
 
     // Some proto files contain comments that will be interpreted as rustdoc comments (and fail to compile)
     config.disable_comments(["."]);
+    // HashMap is not available without std, which the uProtocol types must do without
+    config.btree_map([".uprotocol"]);
+    // descriptors of the types, for mapping them to and from their canonical JSON representation
+    config.file_descriptor_set_path(Path::new(&out_dir).join("file_descriptor_set.bin"));
 
     config.compile_protos(&proto_files, &[&out_dir])?;
 
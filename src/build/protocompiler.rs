@@ -0,0 +1,273 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use prost_build::Config;
+
+use crate::build::UServiceDefinition;
+
+const DESCRIPTOR_SET_FILE: &str = "uprotocol_file_descriptor_set.bin";
+
+/// Compiles proto files like `prost_build::compile_protos`, and additionally writes the uProtocol identifiers
+/// of the services declared in them to `$OUT_DIR/<package>.uprotocol.rs`, to be called from a build script.
+///
+/// For each service that declares a uEntity by means of the options of
+/// [`uprotocol_options.proto`](https://github.com/eclipse-uprotocol/uprotocol-core-api), the generated file
+/// contains a module named after the service, e.g. `door_service` for `DoorService`, with the constants
+///
+/// * `ENTITY_NAME`, `ENTITY_ID` (if declared), `VERSION_MAJOR` and `VERSION_MINOR` of the uEntity,
+/// * a constant in the `methods` module for the ID of each method with a `method_id` option, e.g. `OPEN_DOOR`,
+/// * a module in the `topics` module for each published topic, with its resource's `ID`, `NAME` and `MESSAGE`.
+///
+/// The files are included like the ones generated by prost-build:
+///
+/// ```ignore
+/// include!(concat!(env!("OUT_DIR"), "/example.door.v1.uprotocol.rs"));
+/// ```
+///
+/// # Arguments
+///
+/// * `protos` - The proto files to compile.
+/// * `includes` - The directories to search for imports, which need to contain `uprotocol_options.proto`.
+///
+/// # Errors
+///
+/// Returns an `io::Error` if the proto files cannot be compiled, or the generated files cannot be written.
+pub fn compile_protos(
+    protos: &[impl AsRef<Path>],
+    includes: &[impl AsRef<Path>],
+) -> io::Result<()> {
+    compile_protos_with_config(Config::new(), protos, includes)
+}
+
+/// Compiles proto files like [`compile_protos`], with a custom prost-build configuration.
+///
+/// The configuration's file descriptor set path is replaced, as the uProtocol identifiers are taken from the
+/// file descriptors. The generated uProtocol files are written to `$OUT_DIR`, regardless of the configuration's
+/// output directory.
+///
+/// # Arguments
+///
+/// * `config` - The prost-build configuration.
+/// * `protos` - The proto files to compile.
+/// * `includes` - The directories to search for imports, which need to contain `uprotocol_options.proto`.
+///
+/// # Errors
+///
+/// Returns an `io::Error` if the proto files cannot be compiled, or the generated files cannot be written.
+pub fn compile_protos_with_config(
+    mut config: Config,
+    protos: &[impl AsRef<Path>],
+    includes: &[impl AsRef<Path>],
+) -> io::Result<()> {
+    let out_dir = env::var_os("OUT_DIR").map(PathBuf::from).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "OUT_DIR is not set, which is only done for build scripts",
+        )
+    })?;
+    let descriptor_set_path = out_dir.join(DESCRIPTOR_SET_FILE);
+    config.file_descriptor_set_path(&descriptor_set_path);
+    config.compile_protos(protos, includes)?;
+
+    let descriptor_set = fs::read(&descriptor_set_path)?;
+    for (package, code) in generate_identifiers(&descriptor_set)? {
+        fs::write(out_dir.join(format!("{package}.uprotocol.rs")), code)?;
+    }
+    Ok(())
+}
+
+/// Generates the code of the uProtocol identifiers of the services declared in a set of file descriptors,
+/// as described for [`compile_protos`], e.g. for descriptors produced by other means than prost-build.
+///
+/// # Arguments
+///
+/// * `descriptor_set` - A `FileDescriptorSet` in its protobuf encoding.
+///
+/// # Returns
+///
+/// The generated code by proto package, where `_` stands for the default package. Packages without any
+/// uEntity are omitted.
+///
+/// # Errors
+///
+/// Returns an `io::Error` of kind `InvalidData` if the descriptor set cannot be decoded.
+pub fn generate_identifiers(descriptor_set: &[u8]) -> io::Result<BTreeMap<String, String>> {
+    let mut packages = BTreeMap::<String, String>::new();
+    for definition in UServiceDefinition::from_descriptor_set(descriptor_set)? {
+        let package = if definition.package.is_empty() {
+            "_".to_string()
+        } else {
+            definition.package.clone()
+        };
+        let code = packages.entry(package).or_insert_with(|| {
+            "// Generated by uprotocol_sdk::build from the uProtocol options of the package's services.\n"
+                .to_string()
+        });
+        write_service(code, &definition);
+    }
+    Ok(packages)
+}
+
+fn write_service(code: &mut String, definition: &UServiceDefinition) {
+    // writing to a String does not fail
+    let _ = writeln!(code);
+    let _ = writeln!(
+        code,
+        "/// The uProtocol identifiers of service `{}`.",
+        definition.service
+    );
+    let _ = writeln!(code, "pub mod {} {{", to_snake_case(&definition.service));
+    let _ = writeln!(
+        code,
+        "    pub const ENTITY_NAME: &str = {:?};",
+        definition.entity_name
+    );
+    if let Some(entity_id) = definition.entity_id {
+        let _ = writeln!(code, "    pub const ENTITY_ID: u32 = {entity_id:#x};");
+    }
+    let _ = writeln!(
+        code,
+        "    pub const VERSION_MAJOR: u32 = {};",
+        definition.version_major
+    );
+    let _ = writeln!(
+        code,
+        "    pub const VERSION_MINOR: u32 = {};",
+        definition.version_minor
+    );
+
+    let _ = writeln!(code);
+    let _ = writeln!(code, "    pub mod methods {{");
+    for (name, id) in &definition.methods {
+        let _ = writeln!(
+            code,
+            "        pub const {}: u32 = {id};",
+            to_snake_case(name).to_ascii_uppercase()
+        );
+    }
+    let _ = writeln!(code, "    }}");
+
+    let _ = writeln!(code);
+    let _ = writeln!(code, "    pub mod topics {{");
+    for topic in &definition.topics {
+        let _ = writeln!(code, "        pub mod {} {{", to_snake_case(&topic.name));
+        let _ = writeln!(code, "            pub const ID: u32 = {:#x};", topic.id);
+        let _ = writeln!(code, "            pub const NAME: &str = {:?};", topic.name);
+        let _ = writeln!(
+            code,
+            "            pub const MESSAGE: &str = {:?};",
+            topic.message
+        );
+        let _ = writeln!(code, "        }}");
+    }
+    let _ = writeln!(code, "    }}");
+    let _ = writeln!(code, "}}");
+}
+
+// Turns a proto name, e.g. `DoorService` or `door.front_left`, into a Rust identifier in snake case.
+fn to_snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase()
+                && previous.map_or(false, |p| p.is_ascii_lowercase() || p.is_ascii_digit())
+            {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else if !snake.is_empty() && !snake.ends_with('_') {
+            snake.push('_');
+        }
+        previous = Some(c);
+    }
+    if snake.is_empty() || snake.starts_with(|c: char| c.is_ascii_digit()) {
+        snake.insert(0, '_');
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    use crate::build::UServiceTopic;
+
+    #[test_case("DoorService", "door_service"; "camel case")]
+    #[test_case("door.front_left", "door_front_left"; "resource name")]
+    #[test_case("GetV2Status", "get_v2_status"; "digits")]
+    #[test_case("1door", "_1door"; "leading digit")]
+    fn test_to_snake_case(name: &str, expected: &str) {
+        assert_eq!(to_snake_case(name), expected);
+    }
+
+    #[test]
+    fn test_write_service() {
+        let definition = UServiceDefinition {
+            package: "example.door.v1".to_string(),
+            service: "DoorService".to_string(),
+            entity_name: "body.door".to_string(),
+            entity_id: Some(0x1234),
+            version_major: 1,
+            version_minor: 2,
+            methods: vec![("OpenDoor".to_string(), 1)],
+            topics: vec![UServiceTopic {
+                id: 0x8000,
+                name: "door.front_left".to_string(),
+                message: "Door".to_string(),
+            }],
+        };
+
+        let mut code = String::new();
+        write_service(&mut code, &definition);
+
+        assert_eq!(
+            code,
+            r#"
+/// The uProtocol identifiers of service `DoorService`.
+pub mod door_service {
+    pub const ENTITY_NAME: &str = "body.door";
+    pub const ENTITY_ID: u32 = 0x1234;
+    pub const VERSION_MAJOR: u32 = 1;
+    pub const VERSION_MINOR: u32 = 2;
+
+    pub mod methods {
+        pub const OPEN_DOOR: u32 = 1;
+    }
+
+    pub mod topics {
+        pub mod door_front_left {
+            pub const ID: u32 = 0x8000;
+            pub const NAME: &str = "door.front_left";
+            pub const MESSAGE: &str = "Door";
+        }
+    }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_generate_identifiers_skips_packages_without_entities() {
+        assert!(generate_identifiers(&[]).unwrap().is_empty());
+    }
+}
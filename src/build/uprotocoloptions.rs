@@ -0,0 +1,251 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::io;
+
+use prost::Message;
+
+// The parts of the protobuf descriptors that carry the uProtocol options defined by `uprotocol_options.proto`.
+// prost-types drops the extension fields that the options are encoded as, which is why the descriptors are
+// decoded by means of these messages instead.
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct FileDescriptorSet {
+    #[prost(message, repeated, tag = "1")]
+    pub(crate) file: Vec<FileDescriptor>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct FileDescriptor {
+    #[prost(string, optional, tag = "2")]
+    pub(crate) package: Option<String>,
+    #[prost(message, repeated, tag = "6")]
+    pub(crate) service: Vec<ServiceDescriptor>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ServiceDescriptor {
+    #[prost(string, optional, tag = "1")]
+    pub(crate) name: Option<String>,
+    #[prost(message, repeated, tag = "2")]
+    pub(crate) method: Vec<MethodDescriptor>,
+    #[prost(message, optional, tag = "3")]
+    pub(crate) options: Option<ServiceOptions>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct MethodDescriptor {
+    #[prost(string, optional, tag = "1")]
+    pub(crate) name: Option<String>,
+    #[prost(message, optional, tag = "4")]
+    pub(crate) options: Option<MethodOptions>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ServiceOptions {
+    #[prost(string, optional, tag = "51100")]
+    pub(crate) name: Option<String>,
+    #[prost(uint32, optional, tag = "51101")]
+    pub(crate) version_major: Option<u32>,
+    #[prost(uint32, optional, tag = "51102")]
+    pub(crate) version_minor: Option<u32>,
+    #[prost(uint32, optional, tag = "51103")]
+    pub(crate) id: Option<u32>,
+    #[prost(message, repeated, tag = "51104")]
+    pub(crate) publish_topic: Vec<ServiceTopic>,
+    #[prost(message, repeated, tag = "51105")]
+    pub(crate) notification_topic: Vec<ServiceTopic>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct MethodOptions {
+    #[prost(uint32, optional, tag = "51200")]
+    pub(crate) method_id: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct ServiceTopic {
+    #[prost(uint32, tag = "1")]
+    pub(crate) id: u32,
+    #[prost(string, tag = "2")]
+    pub(crate) name: String,
+    #[prost(string, tag = "3")]
+    pub(crate) message: String,
+}
+
+/// A proto service that is declared to be a uEntity by means of the uProtocol service options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UServiceDefinition {
+    /// The proto package that the service is declared in.
+    pub package: String,
+    /// The name of the service in its proto definition.
+    pub service: String,
+    /// The name of the uEntity.
+    pub entity_name: String,
+    /// The identifier of the uEntity, if declared.
+    pub entity_id: Option<u32>,
+    /// The major version of the uEntity's API.
+    pub version_major: u32,
+    /// The minor version of the uEntity's API.
+    pub version_minor: u32,
+    /// The methods that have a uProtocol method ID, by their names and IDs.
+    pub methods: Vec<(String, u32)>,
+    /// The topics that the uEntity publishes to, including its notification topics.
+    pub topics: Vec<UServiceTopic>,
+}
+
+/// A topic declared by the uProtocol service options.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UServiceTopic {
+    /// The resource identifier of the topic.
+    pub id: u32,
+    /// The resource name of the topic.
+    pub name: String,
+    /// The name of the message type published to the topic.
+    pub message: String,
+}
+
+impl UServiceDefinition {
+    /// Gets the services declared in a set of file descriptors that carry the uProtocol service options.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptor_set` - A `FileDescriptorSet` in its protobuf encoding, as written by `protoc` or prost-build.
+    ///
+    /// # Returns
+    ///
+    /// The services whose options declare a uEntity name or identifier. Other services are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `io::Error` of kind `InvalidData` if the descriptor set cannot be decoded.
+    pub fn from_descriptor_set(descriptor_set: &[u8]) -> io::Result<Vec<UServiceDefinition>> {
+        let descriptor_set = FileDescriptorSet::decode(descriptor_set)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let mut definitions = Vec::new();
+        for file in descriptor_set.file {
+            let package = file.package.unwrap_or_default();
+            for service in file.service {
+                let Some(options) = service.options else {
+                    continue;
+                };
+                if options.name.is_none() && options.id.is_none() {
+                    continue;
+                }
+                let service_name = service.name.unwrap_or_default();
+                definitions.push(UServiceDefinition {
+                    package: package.clone(),
+                    entity_name: options.name.unwrap_or_else(|| service_name.clone()),
+                    service: service_name,
+                    entity_id: options.id,
+                    version_major: options.version_major.unwrap_or_default(),
+                    version_minor: options.version_minor.unwrap_or_default(),
+                    methods: service
+                        .method
+                        .into_iter()
+                        .filter_map(|method| {
+                            let method_id = method.options?.method_id?;
+                            Some((method.name.unwrap_or_default(), method_id))
+                        })
+                        .collect(),
+                    topics: options
+                        .publish_topic
+                        .into_iter()
+                        .chain(options.notification_topic)
+                        .map(|topic| UServiceTopic {
+                            id: topic.id,
+                            name: topic.name,
+                            message: topic.message,
+                        })
+                        .collect(),
+                });
+            }
+        }
+        Ok(definitions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor_set() -> Vec<u8> {
+        FileDescriptorSet {
+            file: vec![FileDescriptor {
+                package: Some("example.door.v1".to_string()),
+                service: vec![
+                    ServiceDescriptor {
+                        name: Some("DoorService".to_string()),
+                        method: vec![
+                            MethodDescriptor {
+                                name: Some("OpenDoor".to_string()),
+                                options: Some(MethodOptions { method_id: Some(1) }),
+                            },
+                            MethodDescriptor {
+                                name: Some("Internal".to_string()),
+                                options: None,
+                            },
+                        ],
+                        options: Some(ServiceOptions {
+                            name: Some("body.door".to_string()),
+                            version_major: Some(1),
+                            version_minor: None,
+                            id: Some(0x1234),
+                            publish_topic: vec![ServiceTopic {
+                                id: 0x8000,
+                                name: "door.front_left".to_string(),
+                                message: "Door".to_string(),
+                            }],
+                            notification_topic: vec![],
+                        }),
+                    },
+                    ServiceDescriptor {
+                        name: Some("PlainService".to_string()),
+                        method: vec![],
+                        options: None,
+                    },
+                ],
+            }],
+        }
+        .encode_to_vec()
+    }
+
+    #[test]
+    fn test_service_definitions_from_descriptor_set() {
+        let definitions = UServiceDefinition::from_descriptor_set(&descriptor_set()).unwrap();
+        assert_eq!(
+            definitions,
+            vec![UServiceDefinition {
+                package: "example.door.v1".to_string(),
+                service: "DoorService".to_string(),
+                entity_name: "body.door".to_string(),
+                entity_id: Some(0x1234),
+                version_major: 1,
+                version_minor: 0,
+                methods: vec![("OpenDoor".to_string(), 1)],
+                topics: vec![UServiceTopic {
+                    id: 0x8000,
+                    name: "door.front_left".to_string(),
+                    message: "Door".to_string(),
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_service_definitions_from_invalid_descriptor_set() {
+        let error = UServiceDefinition::from_descriptor_set(&[0x0a, 0x05, 0x01]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,362 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use cloudevents::Event as CloudEvent;
+use http::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use http::{request, response, Request, Response};
+
+use crate::cloudevent::serializer::{
+    CloudEventSerializer, ContentMode, EncodedCloudEvent, SerializationError,
+};
+
+/// Prefix of the HTTP headers carrying `CloudEvent` attributes in binary content mode.
+const HEADER_PREFIX: &str = "ce-";
+
+/// Maps `CloudEvents` to and from HTTP messages as defined by the
+/// [CloudEvents HTTP Protocol Binding](https://github.com/cloudevents/spec/blob/v1.0.2/cloudevents/bindings/http-protocol-binding.md).
+///
+/// In binary content mode, each attribute is carried in a `ce-<attribute name>` header, and the `datacontenttype`
+/// attribute is carried in the `Content-Type` header. The values of the `ce-` headers are percent-encoded, whereas
+/// the `Content-Type` header is carried as is. In structured content mode, the `Content-Type` header identifies the
+/// event format used for serializing the whole event into the message body.
+pub struct CloudEventHttpBinding;
+
+impl CloudEventHttpBinding {
+    /// Creates the HTTP headers for an encoded `CloudEvent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `encoded` - The event, encoded in either of the content modes.
+    ///
+    /// # Returns
+    ///
+    /// The headers to put on the HTTP message. The body of the message is `encoded.data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if an attribute name is not a valid HTTP header name, or the content type is
+    /// not a valid HTTP header value.
+    pub fn to_headers(encoded: &EncodedCloudEvent) -> Result<HeaderMap, SerializationError> {
+        let mut headers = HeaderMap::new();
+        if let Some(content_type) = &encoded.content_type {
+            let value = HeaderValue::from_str(content_type)
+                .map_err(|e| SerializationError::other("Invalid content type", e))?;
+            headers.insert(CONTENT_TYPE, value);
+        }
+        for (name, value) in &encoded.attributes {
+            let header_name =
+                HeaderName::from_bytes(format!("{HEADER_PREFIX}{name}").as_bytes())
+                    .map_err(|e| SerializationError::other("Invalid attribute name", e))?;
+            headers.insert(header_name, Self::header_value(value)?);
+        }
+        Ok(headers)
+    }
+
+    /// Reads an encoded `CloudEvent` from HTTP headers and body.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the HTTP message.
+    /// * `body` - The body of the HTTP message.
+    ///
+    /// # Returns
+    ///
+    /// The encoded event. Headers not related to `CloudEvents` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if any of the relevant header values is not a valid string.
+    pub fn from_headers(
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<EncodedCloudEvent, SerializationError> {
+        let mut encoded = EncodedCloudEvent {
+            data: body.to_vec(),
+            ..Default::default()
+        };
+        for (name, value) in headers {
+            if name == CONTENT_TYPE {
+                let content_type = value
+                    .to_str()
+                    .map_err(|e| SerializationError::other("Invalid content type", e))?;
+                encoded.content_type = Some(content_type.to_string());
+            } else if let Some(attribute) = name.as_str().strip_prefix(HEADER_PREFIX) {
+                encoded
+                    .attributes
+                    .insert(attribute.to_string(), Self::header_string(value)?);
+            }
+        }
+        // attributes in headers are only meaningful in binary mode
+        if encoded.content_mode() == ContentMode::Structured {
+            encoded.attributes.clear();
+        }
+        Ok(encoded)
+    }
+
+    /// Creates an HTTP request carrying a `CloudEvent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - A builder that has already been configured with method, URI etc.
+    /// * `cloud_event` - The event to send.
+    /// * `mode` - The content mode to use.
+    /// * `serializer` - The serializer to use in structured mode.
+    ///
+    /// # Returns
+    ///
+    /// The request.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the event cannot be encoded, or if the request cannot be built.
+    pub fn to_request(
+        builder: request::Builder,
+        cloud_event: &CloudEvent,
+        mode: ContentMode,
+        serializer: &dyn CloudEventSerializer,
+    ) -> Result<Request<Vec<u8>>, SerializationError> {
+        let encoded = mode.encode(cloud_event, serializer)?;
+        let mut request = builder
+            .body(Vec::new())
+            .map_err(|e| SerializationError::other("Failed to build HTTP request", e))?;
+        request.headers_mut().extend(Self::to_headers(&encoded)?);
+        *request.body_mut() = encoded.data;
+        Ok(request)
+    }
+
+    /// Creates an HTTP response carrying a `CloudEvent`.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - A builder that has already been configured with status etc.
+    /// * `cloud_event` - The event to send.
+    /// * `mode` - The content mode to use.
+    /// * `serializer` - The serializer to use in structured mode.
+    ///
+    /// # Returns
+    ///
+    /// The response.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the event cannot be encoded, or if the response cannot be built.
+    pub fn to_response(
+        builder: response::Builder,
+        cloud_event: &CloudEvent,
+        mode: ContentMode,
+        serializer: &dyn CloudEventSerializer,
+    ) -> Result<Response<Vec<u8>>, SerializationError> {
+        let encoded = mode.encode(cloud_event, serializer)?;
+        let mut response = builder
+            .body(Vec::new())
+            .map_err(|e| SerializationError::other("Failed to build HTTP response", e))?;
+        response.headers_mut().extend(Self::to_headers(&encoded)?);
+        *response.body_mut() = encoded.data;
+        Ok(response)
+    }
+
+    /// Extracts the `CloudEvent` carried in an HTTP request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request.
+    /// * `serializer` - The serializer to use if the request is in structured mode.
+    ///
+    /// # Returns
+    ///
+    /// The event.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the request does not carry a valid `CloudEvent`.
+    pub fn from_request<B: AsRef<[u8]>>(
+        request: &Request<B>,
+        serializer: &dyn CloudEventSerializer,
+    ) -> Result<CloudEvent, SerializationError> {
+        Self::from_headers(request.headers(), request.body().as_ref())?.decode(serializer)
+    }
+
+    /// Extracts the `CloudEvent` carried in an HTTP response.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - The response.
+    /// * `serializer` - The serializer to use if the response is in structured mode.
+    ///
+    /// # Returns
+    ///
+    /// The event.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the response does not carry a valid `CloudEvent`.
+    pub fn from_response<B: AsRef<[u8]>>(
+        response: &Response<B>,
+        serializer: &dyn CloudEventSerializer,
+    ) -> Result<CloudEvent, SerializationError> {
+        Self::from_headers(response.headers(), response.body().as_ref())?.decode(serializer)
+    }
+
+    // Values of ce- headers are percent-encoded as required by section 3.1.3.2 of the binding spec
+    fn header_value(value: &str) -> Result<HeaderValue, SerializationError> {
+        let mut encoded = String::with_capacity(value.len());
+        for byte in value.bytes() {
+            if (0x21..=0x7e).contains(&byte) && byte != b'"' && byte != b'%' {
+                encoded.push(char::from(byte));
+            } else {
+                encoded.push_str(&format!("%{byte:02X}"));
+            }
+        }
+        HeaderValue::from_str(&encoded)
+            .map_err(|e| SerializationError::other("Invalid header value", e))
+    }
+
+    fn header_string(value: &HeaderValue) -> Result<String, SerializationError> {
+        let bytes = value.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'%' && i + 2 < bytes.len() {
+                if let Some(byte) = std::str::from_utf8(&bytes[i + 1..=i + 2])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    decoded.push(byte);
+                    i += 3;
+                    continue;
+                }
+            }
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+        String::from_utf8(decoded)
+            .map_err(|e| SerializationError::utf8("Invalid header value", e.utf8_error()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cloudevents::{AttributesReader, AttributesWriter, EventBuilder, EventBuilderV10};
+
+    use crate::cloudevent::builder::UCloudEventBuilder;
+    use crate::cloudevent::serializer::CloudEventJsonSerializer;
+    use crate::uprotocol::UMessageType;
+
+    fn build_cloud_event_for_test() -> CloudEvent {
+        EventBuilderV10::new()
+            .id("testme")
+            .ty(UMessageType::UmessageTypePublish)
+            .source("/body.access/1/door.front_left")
+            .subject("50% open \"door\"")
+            .extension("ttl", 3)
+            .data(UCloudEventBuilder::PROTOBUF_CONTENT_TYPE, vec![1, 2, 3])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_binary_mode_request_round_trip() {
+        let cloud_event = build_cloud_event_for_test();
+
+        let request = CloudEventHttpBinding::to_request(
+            Request::post("http://localhost/events"),
+            &cloud_event,
+            ContentMode::Binary,
+            &CloudEventJsonSerializer,
+        )
+        .unwrap();
+        assert_eq!(request.headers()["ce-id"], "testme");
+        assert_eq!(request.headers()["ce-subject"], "50%25%20open%20%22door%22");
+        assert_eq!(
+            request.headers()[CONTENT_TYPE],
+            UCloudEventBuilder::PROTOBUF_CONTENT_TYPE
+        );
+        assert_eq!(request.body(), &vec![1, 2, 3]);
+
+        let decoded =
+            CloudEventHttpBinding::from_request(&request, &CloudEventJsonSerializer).unwrap();
+        assert_eq!(cloud_event, decoded);
+    }
+
+    #[test]
+    fn test_structured_mode_response_round_trip() {
+        let cloud_event = build_cloud_event_for_test();
+
+        let response = CloudEventHttpBinding::to_response(
+            Response::builder().status(200),
+            &cloud_event,
+            ContentMode::Structured,
+            &CloudEventJsonSerializer,
+        )
+        .unwrap();
+        assert_eq!(
+            response.headers()[CONTENT_TYPE],
+            "application/cloudevents+json"
+        );
+        assert!(response.headers().get("ce-id").is_none());
+
+        let decoded =
+            CloudEventHttpBinding::from_response(&response, &CloudEventJsonSerializer).unwrap();
+        assert_eq!(cloud_event, decoded);
+        assert_eq!(decoded.subject(), Some("50% open \"door\""));
+    }
+
+    #[test]
+    fn test_content_type_is_not_percent_encoded() {
+        let mut cloud_event = build_cloud_event_for_test();
+        cloud_event.set_datacontenttype(Some("text/plain; charset=\"utf-8\""));
+
+        let request = CloudEventHttpBinding::to_request(
+            Request::post("http://localhost/events"),
+            &cloud_event,
+            ContentMode::Binary,
+            &CloudEventJsonSerializer,
+        )
+        .unwrap();
+        assert_eq!(
+            request.headers()[CONTENT_TYPE],
+            "text/plain; charset=\"utf-8\""
+        );
+
+        let decoded =
+            CloudEventHttpBinding::from_request(&request, &CloudEventJsonSerializer).unwrap();
+        assert_eq!(
+            decoded.datacontenttype(),
+            Some("text/plain; charset=\"utf-8\"")
+        );
+
+        // a percent sign in the content type is not decoded
+        let request = Request::post("http://localhost/events")
+            .header(CONTENT_TYPE, "text/plain; note=100%25")
+            .body(vec![])
+            .unwrap();
+        let encoded =
+            CloudEventHttpBinding::from_headers(request.headers(), request.body()).unwrap();
+        assert_eq!(
+            encoded.content_type.as_deref(),
+            Some("text/plain; note=100%25")
+        );
+    }
+
+    #[test]
+    fn test_from_request_fails_for_missing_attributes() {
+        let request = Request::post("http://localhost/events")
+            .header("ce-id", "testme")
+            .body(vec![])
+            .unwrap();
+
+        assert!(CloudEventHttpBinding::from_request(&request, &CloudEventJsonSerializer).is_err());
+    }
+}
@@ -17,9 +17,9 @@ use cloudevents::event::ExtensionValue;
 use cloudevents::{AttributesReader, Data, Event, EventBuilder, EventBuilderV10};
 use prost::Message;
 use prost_types::Any;
-use std::time::SystemTime;
 
 use crate::uprotocol::{UCode, Uuid};
+use crate::uuid::builder::{SystemClock, UClock};
 
 /// Code to extract information from a `CloudEvent`
 #[derive(Debug)]
@@ -265,7 +265,8 @@ impl UCloudEventUtils {
         false
     }
 
-    /// Calculates if an `Event` configured with a `UUIDv8` id and a `ttl` attribute is expired.
+    /// Calculates if an `Event` configured with a `UUIDv8` id and a `ttl` attribute is expired, according to the
+    /// system clock.
     ///
     /// The `ttl` attribute configures how long this event should live for after it was generated (in milliseconds).
     ///
@@ -276,6 +277,8 @@ impl UCloudEventUtils {
     /// # Returns
     ///
     /// Returns `true` if the `Event` was configured with a `ttl` greater than 0 and a `UUIDv8` id to compare for expiration.
+    /// An `Event` whose id carries a creation time later than the current system time (e.g. due to clock skew between
+    /// devices) is not considered expired.
     ///
     /// # Panics
     ///
@@ -284,6 +287,27 @@ impl UCloudEventUtils {
     /// The panic message will be "Time went backwards". This is an unusual scenario and typically indicates a significant
     /// system clock error.
     pub fn is_expired(event: &Event) -> bool {
+        UCloudEventUtils::is_expired_at(event, &SystemClock)
+    }
+
+    /// Calculates if an `Event` configured with a `UUIDv8` id and a `ttl` attribute is expired, according to the
+    /// given clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The `Event` to inspect for expiration.
+    /// * `clock` - The clock that provides the current time.
+    ///
+    /// # Returns
+    ///
+    /// Returns `true` if the `Event` was configured with a `ttl` greater than 0 and a `UUIDv8` id to compare for expiration,
+    /// as described for [`UCloudEventUtils::is_expired`].
+    ///
+    /// # Panics
+    ///
+    /// This function will panic with the message "Time went backwards" if the clock is set to a point in time before
+    /// the UNIX epoch.
+    pub fn is_expired_at(event: &Event, clock: &dyn UClock) -> bool {
         let maybe_ttl = UCloudEventUtils::get_ttl(event);
         match maybe_ttl {
             Some(ttl) if ttl > 0 => {
@@ -293,13 +317,10 @@ impl UCloudEventUtils {
                     .ok()
                     .and_then(|uuid| uuid.get_time())
                 {
-                    let now = SystemTime::now()
-                        .duration_since(SystemTime::UNIX_EPOCH)
-                        .expect("Time went backwards")
-                        .as_millis();
+                    let now = clock.now().expect("Time went backwards");
 
-                    let delta = now - u128::from(event_time);
-                    delta >= u128::from(ttl)
+                    let delta = now.saturating_sub(event_time);
+                    delta >= u64::from(ttl)
                 } else {
                     false
                 }
@@ -478,7 +499,7 @@ mod tests {
     use crate::proto::CloudEvent;
     use crate::uprotocol::{UEntity, UMessageType, UPriority, UResource, UUri};
     use crate::uri::serializer::{LongUriSerializer, UriSerializer};
-    use crate::uuid::builder::UUIDv8Builder;
+    use crate::uuid::builder::{SimulatedClock, SystemRandomSource, UUIDv8Builder};
 
     use chrono::{offset, TimeZone, Utc};
     use cloudevents::{Data, Event, EventBuilder, EventBuilderV10};
@@ -898,18 +919,58 @@ mod tests {
 
     #[test]
     fn test_cloudevent_is_expired_when_ttl_1_mili() {
-        use std::thread;
-        use std::time::Duration;
-
-        let uuid = UUIDv8Builder::new().build();
+        let clock = SimulatedClock::starting_now();
+        let uuid = UUIDv8Builder::with_sources(clock.clone(), &mut SystemRandomSource).build();
         let builder = build_base_cloud_event_for_test()
             .extension("ttl", 1)
             .id(uuid);
         let cloud_event = builder.build().unwrap();
 
-        thread::sleep(Duration::from_millis(800));
+        clock.advance(std::time::Duration::from_millis(800));
 
-        assert!(UCloudEventUtils::is_expired(&cloud_event));
+        assert!(UCloudEventUtils::is_expired_at(&cloud_event, &clock));
+    }
+
+    #[test]
+    fn test_cloudevent_is_expired_at_time_of_clock() {
+        struct FixedClock(u64);
+        impl UClock for FixedClock {
+            fn now(&self) -> Option<u64> {
+                Some(self.0)
+            }
+        }
+
+        let created = 0x18C684468F8; // Thu, 14 Dec 2023 12:19:23 GMT
+        let uuid =
+            UUIDv8Builder::with_sources(FixedClock(created), &mut SystemRandomSource).build();
+        let builder = build_base_cloud_event_for_test()
+            .extension("ttl", 100)
+            .id(uuid);
+        let cloud_event = builder.build().unwrap();
+
+        assert!(!UCloudEventUtils::is_expired_at(
+            &cloud_event,
+            &FixedClock(created + 99)
+        ));
+        assert!(UCloudEventUtils::is_expired_at(
+            &cloud_event,
+            &FixedClock(created + 100)
+        ));
+    }
+
+    #[test]
+    fn test_cloudevent_is_not_expired_when_created_in_the_future() {
+        // timestamp = 0xFFFFFFFFFFFF (year 10889), ver = 0b1000
+        let msb = 0xFFFF_FFFF_FFFF_8000u64;
+        // variant = 0b10
+        let lsb = 0x8000_0000_0000_0000u64;
+        let uuid = Uuid { msb, lsb };
+        let builder = build_base_cloud_event_for_test()
+            .extension("ttl", 1)
+            .id(uuid);
+        let cloud_event = builder.build().unwrap();
+
+        assert!(!UCloudEventUtils::is_expired(&cloud_event));
     }
 
     #[test]
@@ -14,21 +14,150 @@
 use cloudevents::Event as CloudEvent;
 
 use crate::cloudevent::serializer::{CloudEventSerializer, SerializationError};
+use crate::transport::datamodel::{BufferPool, PooledBuffer};
 
 ///  Serialize and deserialize `CloudEvents` to/from JSON format.
 pub struct CloudEventJsonSerializer;
+
+impl CloudEventJsonSerializer {
+    /// The media type of the [JSON batch format](https://github.com/cloudevents/spec/blob/v1.0.2/cloudevents/formats/json-format.md#4-json-batch-format).
+    pub const BATCH_CONTENT_TYPE: &'static str = "application/cloudevents-batch+json";
+
+    /// Serializes a number of `CloudEvents` into a single JSON batch, i.e. a JSON array of events.
+    ///
+    /// # Arguments
+    ///
+    /// * `cloud_events` - The events to serialize. An empty slice yields an empty JSON array.
+    ///
+    /// # Returns
+    ///
+    /// The serialized batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if any of the events cannot be serialized to JSON.
+    pub fn serialize_batch(
+        &self,
+        cloud_events: &[CloudEvent],
+    ) -> Result<Vec<u8>, SerializationError> {
+        serde_json::to_vec(cloud_events).map_err(|error| {
+            SerializationError::json("Failed to serialize CloudEvent batch", error)
+        })
+    }
+
+    /// Deserializes a JSON batch back into the contained `CloudEvents`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized batch.
+    ///
+    /// # Returns
+    ///
+    /// The events contained in the batch, in the order they appear in.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the bytes are not a JSON array, or if any of its elements is not a valid `CloudEvent`.
+    pub fn deserialize_batch(&self, bytes: &[u8]) -> Result<Vec<CloudEvent>, SerializationError> {
+        serde_json::from_slice::<Vec<CloudEvent>>(bytes)
+            .map_err(|error| SerializationError::json("Invalid JSON CloudEvent batch", error))
+    }
+}
+
 impl CloudEventSerializer for CloudEventJsonSerializer {
     fn serialize(&self, cloud_event: &CloudEvent) -> Result<Vec<u8>, SerializationError> {
         match serde_json::to_vec(cloud_event) {
             Ok(bytes) => Ok(bytes),
-            Err(error) => Err(SerializationError::new(error.to_string())),
+            Err(error) => Err(SerializationError::json(
+                "Failed to serialize CloudEvent",
+                error,
+            )),
         }
     }
 
+    fn serialize_pooled<'a>(
+        &self,
+        cloud_event: &CloudEvent,
+        buffers: &'a BufferPool,
+    ) -> Result<PooledBuffer<'a>, SerializationError> {
+        let mut buffer = buffers.acquire();
+        serde_json::to_writer(&mut *buffer, cloud_event)
+            .map_err(|error| SerializationError::json("Failed to serialize CloudEvent", error))?;
+        Ok(buffer)
+    }
+
     fn deserialize(&self, bytes: &[u8]) -> Result<CloudEvent, SerializationError> {
         match serde_json::from_slice::<CloudEvent>(bytes) {
             Ok(event) => Ok(event),
-            Err(error) => Err(SerializationError::new(error.to_string())),
+            Err(error) => Err(SerializationError::json("Invalid JSON CloudEvent", error)),
         }
     }
+
+    fn content_type(&self) -> &'static str {
+        "application/cloudevents+json"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use cloudevents::{EventBuilder, EventBuilderV10};
+
+    use crate::uprotocol::UMessageType;
+
+    fn build_cloud_event_for_test(id: &str) -> CloudEvent {
+        EventBuilderV10::new()
+            .id(id)
+            .ty(UMessageType::UmessageTypePublish)
+            .source("/body.access/1/door.front_left")
+            .extension("ttl", 3)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_serialize_and_deserialize_batch() {
+        let cloud_events = vec![
+            build_cloud_event_for_test("first"),
+            build_cloud_event_for_test("second"),
+        ];
+
+        let serializer = CloudEventJsonSerializer;
+        let bytes = serializer.serialize_batch(&cloud_events).unwrap();
+        let deserialized = serializer.deserialize_batch(&bytes).unwrap();
+
+        assert_eq!(cloud_events, deserialized);
+    }
+
+    #[test]
+    fn test_serialize_empty_batch() {
+        let serializer = CloudEventJsonSerializer;
+        let bytes = serializer.serialize_batch(&[]).unwrap();
+
+        assert_eq!(bytes, b"[]");
+        assert!(serializer.deserialize_batch(&bytes).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_batch_fails_for_single_event() {
+        let serializer = CloudEventJsonSerializer;
+        let bytes = serializer
+            .serialize(&build_cloud_event_for_test("single"))
+            .unwrap();
+
+        assert!(serializer.deserialize_batch(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_serialize_pooled() {
+        let serializer = CloudEventJsonSerializer;
+        let cloud_event = build_cloud_event_for_test("pooled");
+        let buffers = BufferPool::default();
+
+        let buffer = serializer.serialize_pooled(&cloud_event, &buffers).unwrap();
+        assert_eq!(*buffer, serializer.serialize(&cloud_event).unwrap());
+        drop(buffer);
+        assert_eq!(buffers.stats().returned, 1);
+    }
 }
@@ -16,6 +16,7 @@ use prost::Message;
 
 use crate::cloudevent::serializer::{CloudEventSerializer, SerializationError};
 use crate::proto::CloudEvent as CloudEventProto;
+use crate::transport::datamodel::{BufferPool, PooledBuffer};
 
 /// Serialize and deserialize `CloudEvents` to protobuf format.
 pub struct CloudEventProtobufSerializer;
@@ -25,12 +26,28 @@ impl CloudEventSerializer for CloudEventProtobufSerializer {
         Ok(proto_event.encode_to_vec())
     }
 
+    fn serialize_pooled<'a>(
+        &self,
+        cloud_event: &CloudEvent,
+        buffers: &'a BufferPool,
+    ) -> Result<PooledBuffer<'a>, SerializationError> {
+        let proto_event = CloudEventProto::from(cloud_event.clone());
+        Ok(buffers.encode(&proto_event))
+    }
+
     fn deserialize(&self, bytes: &[u8]) -> Result<CloudEvent, SerializationError> {
         match CloudEventProto::decode(bytes) {
-            Ok(proto_event) => Ok(CloudEvent::from(proto_event)),
-            Err(error) => Err(SerializationError::new(error.to_string())),
+            Ok(proto_event) => proto_event.try_into_cloud_event(),
+            Err(error) => Err(SerializationError::decode(
+                "Invalid protobuf CloudEvent",
+                error,
+            )),
         }
     }
+
+    fn content_type(&self) -> &'static str {
+        "application/cloudevents+protobuf"
+    }
 }
 
 #[cfg(test)]
@@ -12,6 +12,7 @@
  ********************************************************************************/
 
 use crate::cloudevent::serializer::SerializationError;
+use crate::transport::datamodel::{BufferPool, PooledBuffer};
 use cloudevents::Event as CloudEvent;
 
 /// A trait defining the functionality for serializing and deserializing `CloudEvents`.
@@ -34,6 +35,34 @@ pub trait CloudEventSerializer {
     /// invalid event data, failure in encoding the event into bytes, or other serialization-specific errors.
     fn serialize(&self, cloud_event: &CloudEvent) -> Result<Vec<u8>, SerializationError>;
 
+    /// Serializes a given `CloudEvent` into a buffer of a pool, which spares allocating a byte vector per event when
+    /// publishing continuously.
+    ///
+    /// The default implementation copies the byte vector created by [`CloudEventSerializer::serialize`] into the
+    /// buffer, which the serializers of this crate avoid by serializing into the buffer directly.
+    ///
+    /// # Arguments
+    ///
+    /// * `cloud_event` - A reference to the `CloudEvent` that needs to be serialized.
+    /// * `buffers` - The pool to take the buffer from.
+    ///
+    /// # Returns
+    ///
+    /// The buffer holding the serialized `CloudEvent`, which is put back into the pool when it is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(SerializationError)` if the serialization process fails, like [`CloudEventSerializer::serialize`].
+    fn serialize_pooled<'a>(
+        &self,
+        cloud_event: &CloudEvent,
+        buffers: &'a BufferPool,
+    ) -> Result<PooledBuffer<'a>, SerializationError> {
+        let mut buffer = buffers.acquire();
+        buffer.extend_from_slice(&self.serialize(cloud_event)?);
+        Ok(buffer)
+    }
+
     /// Deserializes a byte vector back into a `CloudEvent`.
     ///
     /// # Arguments
@@ -50,4 +79,13 @@ pub trait CloudEventSerializer {
     /// does not represent a valid `CloudEvent`, if necessary information is missing, if the data is corrupted,
     /// or if it fails to meet the expected format or schema.
     fn deserialize(&self, bytes: &[u8]) -> Result<CloudEvent, SerializationError>;
+
+    /// Gets the media type identifying this serializer's format when used in structured content mode,
+    /// e.g. `application/cloudevents+json`.
+    ///
+    /// The default implementation returns `application/cloudevents`, i.e. a structured mode media type without
+    /// format suffix, so that existing serializers keep compiling. Serializers of a specific format override it.
+    fn content_type(&self) -> &'static str {
+        "application/cloudevents"
+    }
 }
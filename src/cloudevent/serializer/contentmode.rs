@@ -0,0 +1,364 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use cloudevents::event::{Data, ExtensionValue, SpecVersion};
+use cloudevents::{AttributesReader, AttributesWriter, Event as CloudEvent, EventBuilder};
+use url::Url;
+
+use crate::cloudevent::builder::UCloudEventUtils;
+use crate::cloudevent::serializer::{CloudEventSerializer, SerializationError};
+
+/// Prefix shared by the content types of all structured mode `CloudEvent` formats.
+const STRUCTURED_CONTENT_TYPE_PREFIX: &str = "application/cloudevents";
+
+// the media type of a content type without its parameters, in lower case, e.g. `application/json` of
+// `Application/JSON; charset=utf-8`
+fn media_type_essence(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// uProtocol extension attributes that carry integer values, which need to be restored as such
+/// when an event is read back from binary mode (where all attributes are transported as strings).
+const INTEGER_EXTENSIONS: [&str; 2] = ["ttl", "commstatus"];
+
+/// The ways in which a `CloudEvent` can be mapped onto a protocol message, as described in the
+/// [CloudEvents spec](https://github.com/cloudevents/spec/blob/v1.0.2/cloudevents/spec.md#message).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentMode {
+    /// The entire event (attributes and data) is serialized into the message body,
+    /// using one of the [`CloudEventSerializer`]s.
+    Structured,
+    /// The event data is carried as the message body, the attributes are carried as message metadata.
+    Binary,
+}
+
+/// A `CloudEvent` that has been prepared for being put on the wire of a protocol binding.
+///
+/// Protocol bindings map the `content_type` and `attributes` onto their respective header/metadata
+/// concepts (e.g. HTTP headers or MQTT5 user properties), and `data` onto the message body.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodedCloudEvent {
+    /// The content type of `data`.
+    pub content_type: Option<String>,
+    /// The event's attributes as strings keyed by attribute name. Empty in structured mode.
+    pub attributes: HashMap<String, String>,
+    /// The message body.
+    pub data: Vec<u8>,
+}
+
+impl ContentMode {
+    /// Encodes a `CloudEvent` using this content mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `cloud_event` - The event to encode.
+    /// * `serializer` - The serializer to use in structured mode. Ignored in binary mode.
+    ///
+    /// # Returns
+    ///
+    /// The encoded event, ready to be mapped onto a protocol message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the event cannot be serialized in structured mode, or its data cannot be
+    /// serialized in binary mode.
+    pub fn encode(
+        &self,
+        cloud_event: &CloudEvent,
+        serializer: &dyn CloudEventSerializer,
+    ) -> Result<EncodedCloudEvent, SerializationError> {
+        match self {
+            ContentMode::Structured => Ok(EncodedCloudEvent {
+                content_type: Some(serializer.content_type().to_string()),
+                attributes: HashMap::new(),
+                data: serializer.serialize(cloud_event)?,
+            }),
+            ContentMode::Binary => {
+                let attributes = cloud_event
+                    .iter()
+                    .filter(|(name, _)| *name != "datacontenttype")
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect();
+                let data = match cloud_event.data() {
+                    Some(Data::Binary(bytes)) => bytes.clone(),
+                    Some(Data::String(text)) => text.as_bytes().to_vec(),
+                    Some(Data::Json(json)) => serde_json::to_vec(json)?,
+                    None => Vec::new(),
+                };
+                Ok(EncodedCloudEvent {
+                    content_type: cloud_event.datacontenttype().map(ToString::to_string),
+                    attributes,
+                    data,
+                })
+            }
+        }
+    }
+}
+
+impl EncodedCloudEvent {
+    /// Gets the content mode this event has been encoded with.
+    ///
+    /// An event is considered to be in structured mode if its content type is one of the
+    /// `application/cloudevents` media types, in any case, and in binary mode otherwise.
+    pub fn content_mode(&self) -> ContentMode {
+        match &self.content_type {
+            Some(content_type)
+                if media_type_essence(content_type).starts_with(STRUCTURED_CONTENT_TYPE_PREFIX) =>
+            {
+                ContentMode::Structured
+            }
+            _ => ContentMode::Binary,
+        }
+    }
+
+    /// Decodes the `CloudEvent` contained in this message.
+    ///
+    /// # Arguments
+    ///
+    /// * `serializer` - The serializer to use if this message is in structured mode. Ignored in binary mode.
+    ///
+    /// # Returns
+    ///
+    /// The decoded `CloudEvent`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if
+    /// - the message is in structured mode, but the media type of its content type does not match the serializer,
+    ///   ignoring parameters like `charset` and case, or the serializer fails to deserialize the message body,
+    /// - the message is in binary mode, but lacks a mandatory attribute or contains an attribute
+    ///   with an invalid value.
+    pub fn decode(
+        &self,
+        serializer: &dyn CloudEventSerializer,
+    ) -> Result<CloudEvent, SerializationError> {
+        match self.content_mode() {
+            ContentMode::Structured => {
+                let content_type = self.content_type.as_deref().unwrap_or_default();
+                if media_type_essence(content_type) != media_type_essence(serializer.content_type())
+                {
+                    return Err(SerializationError::new(format!(
+                        "Serializer does not support content type {content_type}"
+                    )));
+                }
+                serializer.deserialize(&self.data)
+            }
+            ContentMode::Binary => self.decode_binary(),
+        }
+    }
+
+    fn decode_binary(&self) -> Result<CloudEvent, SerializationError> {
+        let required = |name: &str| {
+            self.attributes
+                .get(name)
+                .ok_or_else(|| SerializationError::new(format!("Missing attribute {name}")))
+        };
+
+        let spec_version = required("specversion")?;
+        if !spec_version.eq(&SpecVersion::V10.to_string()) {
+            return Err(SerializationError::new(format!(
+                "Unsupported spec version {spec_version}"
+            )));
+        }
+
+        let mut cloud_event = cloudevents::EventBuilderV10::new()
+            .id(required("id")?)
+            .source(required("source")?.as_str())
+            .ty(required("type")?)
+            .build()
+            .map_err(|e| SerializationError::other("Invalid CloudEvent attributes", e))?;
+
+        for (name, value) in &self.attributes {
+            match name.as_str() {
+                "specversion" | "id" | "source" | "type" => {}
+                "subject" => {
+                    cloud_event.set_subject(Some(value));
+                }
+                "time" => {
+                    let time = DateTime::parse_from_rfc3339(value)
+                        .map_err(|e| SerializationError::other("Invalid time", e))?;
+                    cloud_event.set_time(Some(time.with_timezone(&Utc)));
+                }
+                "dataschema" => {
+                    let schema = Url::parse(value)
+                        .map_err(|e| SerializationError::other("Invalid dataschema", e))?;
+                    cloud_event.set_dataschema(Some(schema));
+                }
+                _ => {
+                    let extension = match value.parse::<i64>() {
+                        Ok(i) if INTEGER_EXTENSIONS.contains(&name.as_str()) => {
+                            ExtensionValue::Integer(i)
+                        }
+                        _ => ExtensionValue::String(value.clone()),
+                    };
+                    cloud_event.set_extension(name, extension);
+                }
+            }
+        }
+
+        if !self.data.is_empty() {
+            cloud_event.set_data_unchecked(self.data.clone());
+        }
+        cloud_event.set_datacontenttype(self.content_type.clone());
+
+        Ok(cloud_event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cloudevent::builder::UCloudEventBuilder;
+    use crate::cloudevent::datamodel::UCloudEventAttributes;
+    use crate::cloudevent::serializer::{CloudEventJsonSerializer, CloudEventProtobufSerializer};
+    use crate::uprotocol::UPriority;
+
+    use prost_types::Any;
+
+    fn build_cloud_event_for_test() -> CloudEvent {
+        let attributes = UCloudEventAttributes::builder()
+            .with_hash("somehash")
+            .with_priority(UPriority::UpriorityCs1)
+            .with_ttl(3)
+            .with_token("someOAuthToken")
+            .build();
+        let payload = Any {
+            type_url: "type.googleapis.com/example.demo".to_string(),
+            value: vec![1, 2, 3],
+        };
+        UCloudEventBuilder::publish("/body.access//door.front_left#Door", &payload, &attributes)
+    }
+
+    #[test]
+    fn test_structured_mode_round_trip() {
+        let cloud_event = build_cloud_event_for_test();
+        let serializer = CloudEventProtobufSerializer;
+
+        let encoded = ContentMode::Structured
+            .encode(&cloud_event, &serializer)
+            .unwrap();
+        assert_eq!(encoded.content_mode(), ContentMode::Structured);
+        assert_eq!(
+            encoded.content_type.as_deref(),
+            Some("application/cloudevents+protobuf")
+        );
+        assert!(encoded.attributes.is_empty());
+
+        let decoded = encoded.decode(&serializer).unwrap();
+        assert_eq!(cloud_event, decoded);
+    }
+
+    #[test]
+    fn test_structured_mode_fails_for_mismatched_serializer() {
+        let cloud_event = build_cloud_event_for_test();
+
+        let encoded = ContentMode::Structured
+            .encode(&cloud_event, &CloudEventJsonSerializer)
+            .unwrap();
+        assert!(encoded.decode(&CloudEventProtobufSerializer).is_err());
+    }
+
+    #[test]
+    fn test_structured_mode_ignores_content_type_parameters() {
+        let cloud_event = build_cloud_event_for_test();
+        let serializer = CloudEventJsonSerializer;
+
+        let mut encoded = ContentMode::Structured
+            .encode(&cloud_event, &serializer)
+            .unwrap();
+        encoded.content_type = Some("Application/CloudEvents+JSON; charset=utf-8".to_string());
+        assert_eq!(encoded.content_mode(), ContentMode::Structured);
+        assert_eq!(encoded.decode(&serializer).unwrap(), cloud_event);
+    }
+
+    // a serializer of another crate, which relies on the default content type
+    struct ForeignSerializer;
+    impl CloudEventSerializer for ForeignSerializer {
+        fn serialize(&self, cloud_event: &CloudEvent) -> Result<Vec<u8>, SerializationError> {
+            CloudEventJsonSerializer.serialize(cloud_event)
+        }
+
+        fn deserialize(&self, bytes: &[u8]) -> Result<CloudEvent, SerializationError> {
+            CloudEventJsonSerializer.deserialize(bytes)
+        }
+    }
+
+    #[test]
+    fn test_structured_mode_with_default_content_type() {
+        let cloud_event = build_cloud_event_for_test();
+
+        let encoded = ContentMode::Structured
+            .encode(&cloud_event, &ForeignSerializer)
+            .unwrap();
+        assert_eq!(
+            encoded.content_type.as_deref(),
+            Some("application/cloudevents")
+        );
+        assert_eq!(encoded.content_mode(), ContentMode::Structured);
+        assert_eq!(encoded.decode(&ForeignSerializer).unwrap(), cloud_event);
+    }
+
+    #[test]
+    fn test_binary_mode_round_trip() {
+        let cloud_event = build_cloud_event_for_test();
+        let serializer = CloudEventJsonSerializer;
+
+        let encoded = ContentMode::Binary
+            .encode(&cloud_event, &serializer)
+            .unwrap();
+        assert_eq!(encoded.content_mode(), ContentMode::Binary);
+        assert_eq!(
+            encoded.content_type.as_deref(),
+            Some(UCloudEventBuilder::PROTOBUF_CONTENT_TYPE)
+        );
+        assert_eq!(encoded.attributes.get("ttl").map(String::as_str), Some("3"));
+        assert_eq!(encoded.data, vec![1, 2, 3]);
+
+        let decoded = encoded.decode(&serializer).unwrap();
+        assert_eq!(cloud_event, decoded);
+        assert_eq!(UCloudEventUtils::get_ttl(&decoded), Some(3));
+    }
+
+    #[test]
+    fn test_binary_mode_fails_for_missing_id() {
+        let cloud_event = build_cloud_event_for_test();
+
+        let mut encoded = ContentMode::Binary
+            .encode(&cloud_event, &CloudEventJsonSerializer)
+            .unwrap();
+        encoded.attributes.remove("id");
+        assert!(encoded.decode(&CloudEventJsonSerializer).is_err());
+    }
+
+    #[test]
+    fn test_binary_mode_fails_for_invalid_time() {
+        let cloud_event = build_cloud_event_for_test();
+
+        let mut encoded = ContentMode::Binary
+            .encode(&cloud_event, &CloudEventJsonSerializer)
+            .unwrap();
+        encoded
+            .attributes
+            .insert("time".to_string(), "yesterday".to_string());
+        assert!(encoded.decode(&CloudEventJsonSerializer).is_err());
+    }
+}
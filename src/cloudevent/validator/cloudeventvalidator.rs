@@ -15,7 +15,7 @@ use cloudevents::event::SpecVersion;
 use cloudevents::{AttributesReader, Event};
 
 use crate::cloudevent::builder::UCloudEventUtils;
-use crate::cloudevent::validator::ValidationError;
+use crate::cloudevent::validator::{ValidationError, ValidationReport};
 use crate::uprotocol::{UMessageType, UResource, UUri};
 use crate::uri::serializer::{LongUriSerializer, UriSerializer};
 use crate::uri::validator::UriValidator;
@@ -46,7 +46,7 @@ pub trait CloudEventValidator: std::fmt::Display {
     ///
     /// If all validations pass, the function returns `Ok(())`, indicating no errors were found.
     fn validate(&self, cloud_event: &Event) -> Result<(), ValidationError> {
-        let error_message = vec![
+        let errors = [
             self.validate_version(cloud_event),
             self.validate_id(cloud_event),
             self.validate_source(cloud_event),
@@ -54,16 +54,23 @@ pub trait CloudEventValidator: std::fmt::Display {
             self.validate_sink(cloud_event),
         ]
         .into_iter()
-        .filter_map(Result::err)
-        .map(|e| e.to_string())
-        .collect::<Vec<_>>()
-        .join("; ");
+        .filter_map(Result::err);
 
-        if error_message.is_empty() {
-            Ok(())
-        } else {
-            Err(ValidationError::new(error_message))
-        }
+        ValidationError::combine(errors).map_or(Ok(()), Err)
+    }
+
+    /// Validates the `CloudEvent` like [`CloudEventValidator::validate`], reporting the outcome in the same way as the
+    /// other validators do.
+    ///
+    /// # Arguments
+    ///
+    /// * `cloud_event` - The `CloudEvent` to validate.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ValidationReport` listing each of the errors that [`CloudEventValidator::validate`] combines.
+    fn report(&self, cloud_event: &Event) -> ValidationReport {
+        ValidationReport::from(self.validate(cloud_event))
     }
 
     /// Validates the version attribute of a `CloudEvent`.
@@ -89,7 +96,8 @@ pub trait CloudEventValidator: std::fmt::Display {
         } else {
             Err(ValidationError::new(format!(
                 "Invalid CloudEvent version [{version}], CloudEvent version must be 1.0"
-            )))
+            ))
+            .with_field("specversion"))
         }
     }
 
@@ -115,7 +123,8 @@ pub trait CloudEventValidator: std::fmt::Display {
             Err(ValidationError::new(format!(
                 "Invalid CloudEvent Id [{}], CloudEvent Id must be of type UUIDv8",
                 cloud_event.id()
-            )))
+            ))
+            .with_field("id"))
         }
     }
 
@@ -175,13 +184,15 @@ pub trait CloudEventValidator: std::fmt::Display {
                 if let Err(e) = self.validate_entity_uri(&uri) {
                     return Err(ValidationError::new(format!(
                         "Invalid CloudEvent sink [{sink}] - {e}"
-                    )));
+                    ))
+                    .with_field("sink"));
                 }
             }
         } else {
-            return Err(ValidationError::new(
-                "Invalid CloudEvent sink, sink must be an uri",
-            ));
+            return Err(
+                ValidationError::new("Invalid CloudEvent sink, sink must be an uri")
+                    .with_field("sink"),
+            );
         }
         Ok(())
     }
@@ -230,12 +241,14 @@ pub trait CloudEventValidator: std::fmt::Display {
         let default = UResource::default();
         let resource = uri.resource.as_ref().unwrap_or(&default);
         if resource.name.trim().is_empty() {
-            return Err(ValidationError::new("UriPart is missing uResource name"));
+            return Err(ValidationError::new("UriPart is missing uResource name")
+                .with_field("resource.name"));
         }
         if resource.message.is_none() {
-            return Err(ValidationError::new(
-                "UriPart is missing Message information",
-            ));
+            return Err(
+                ValidationError::new("UriPart is missing Message information")
+                    .with_field("resource.message"),
+            );
         }
 
         Ok(())
@@ -263,7 +276,8 @@ pub trait CloudEventValidator: std::fmt::Display {
         if let Err(e) = self.validate_entity_uri(uri) {
             return Err(ValidationError::new(format!(
                 "Invalid RPC uri application response topic [{e}]"
-            )));
+            ))
+            .with_field("resource"));
         }
 
         if let Some(resource) = &uri.resource {
@@ -275,7 +289,8 @@ pub trait CloudEventValidator: std::fmt::Display {
             }
             return Err(ValidationError::new(
                 "Invalid RPC uri application response topic, UriPart is missing rpc.response",
-            ));
+            )
+            .with_field("resource"));
         }
         Ok(())
     }
@@ -300,13 +315,14 @@ pub trait CloudEventValidator: std::fmt::Display {
     /// - If the `UUri` is not recognized as a valid RPC method URI according to the `UriValidator::is_rpc_method` check.
     fn validate_rpc_method(&self, uri: &UUri) -> Result<(), ValidationError> {
         if let Err(e) = self.validate_entity_uri(uri) {
-            return Err(ValidationError::new(format!(
-                "Invalid RPC method uri [{e}]"
-            )));
+            return Err(
+                ValidationError::new(format!("Invalid RPC method uri [{e}]"))
+                    .with_field("resource"),
+            );
         }
 
         if !UriValidator::is_rpc_method(uri) {
-            return Err(ValidationError::new("Invalid RPC method uri - UriPart should be the method to be called, or method from response"));
+            return Err(ValidationError::new("Invalid RPC method uri - UriPart should be the method to be called, or method from response").with_field("resource"));
         }
         Ok(())
     }
@@ -356,12 +372,14 @@ impl CloudEventValidator for PublishValidator {
             if let Err(e) = self.validate_topic_uri(&source) {
                 return Err(ValidationError::new(format!(
                     "Invalid Publish type CloudEvent source [{source}] - {e}"
-                )));
+                ))
+                .with_field("source"));
             }
         } else {
             return Err(ValidationError::new(
                 "Invalid CloudEvent source, Publish CloudEvent source must be an uri",
-            ));
+            )
+            .with_field("source"));
         }
         Ok(())
     }
@@ -373,7 +391,7 @@ impl CloudEventValidator for PublishValidator {
         Err(ValidationError::new(format!(
             "Invalid CloudEvent type [{}] - CloudEvent of type Publish must have a type of 'pub.v1'",
             cloud_event.ty(),
-        )))
+        )).with_field("type"))
     }
 }
 
@@ -400,17 +418,20 @@ impl CloudEventValidator for NotificationValidator {
                 if let Err(e) = self.validate_entity_uri(&uri) {
                     return Err(ValidationError::new(format!(
                         "Invalid Notification type CloudEvent sink [{sink}] - {e}"
-                    )));
+                    ))
+                    .with_field("sink"));
                 }
             } else {
                 return Err(ValidationError::new(
                     "Invalid CloudEvent sink, Notification CloudEvent sink must be an uri",
-                ));
+                )
+                .with_field("sink"));
             }
         } else {
             return Err(ValidationError::new(
                 "Invalid CloudEvent sink, Notification CloudEvent sink must be an uri",
-            ));
+            )
+            .with_field("sink"));
         }
         Ok(())
     }
@@ -431,12 +452,13 @@ impl CloudEventValidator for RequestValidator {
             if let Err(e) = self.validate_rpc_topic_uri(&uri) {
                 return Err(ValidationError::new(format!(
                     "Invalid RPC Request CloudEvent source [{source}] - {e}"
-                )));
+                ))
+                .with_field("source"));
             }
         } else {
             return Err(ValidationError::new(
                 "Invalid RPC Request CloudEvent source, Request CloudEvent source must be uri for the method to be called",
-            ));
+            ).with_field("source"));
         }
         Ok(())
     }
@@ -447,17 +469,18 @@ impl CloudEventValidator for RequestValidator {
                 if let Err(e) = self.validate_rpc_method(&uri) {
                     return Err(ValidationError::new(format!(
                         "Invalid RPC Request CloudEvent sink [{sink}] - {e}"
-                    )));
+                    ))
+                    .with_field("sink"));
                 }
             } else {
                 return Err(ValidationError::new(
                     "Invalid RPC Request CloudEvent sink, Request CloudEvent sink must be uri for the method to be called",
-                ));
+                ).with_field("sink"));
             }
         } else {
             return Err(ValidationError::new(
                 "Invalid RPC Request CloudEvent sink, Request CloudEvent sink must be uri for the method to be called",
-            ));
+            ).with_field("sink"));
         }
         Ok(())
     }
@@ -469,7 +492,8 @@ impl CloudEventValidator for RequestValidator {
         Err(ValidationError::new(format!(
             "Invalid CloudEvent type [{}], CloudEvent of type Request must have a type of 'req.v1'",
             cloud_event.ty(),
-        )))
+        ))
+        .with_field("type"))
     }
 }
 
@@ -488,12 +512,13 @@ impl CloudEventValidator for ResponseValidator {
             if let Err(e) = self.validate_rpc_method(&uri) {
                 return Err(ValidationError::new(format!(
                     "Invalid RPC Response CloudEvent source [{source}] - {e}"
-                )));
+                ))
+                .with_field("source"));
             }
         } else {
             return Err(ValidationError::new(
                 "Invalid RPC Response CloudEvent source, Response CloudEvent source must be uri for the method to be called",
-            ));
+            ).with_field("source"));
         }
         Ok(())
     }
@@ -504,17 +529,18 @@ impl CloudEventValidator for ResponseValidator {
                 if let Err(e) = self.validate_rpc_topic_uri(&uri) {
                     return Err(ValidationError::new(format!(
                         "Invalid RPC Response CloudEvent sink [{sink}] - {e}"
-                    )));
+                    ))
+                    .with_field("sink"));
                 }
             } else {
                 return Err(ValidationError::new(
                     "Invalid RPC Response CloudEvent sink, Response CloudEvent sink must be uri for the method to be called",
-                ));
+                ).with_field("sink"));
             }
         } else {
             return Err(ValidationError::new(
                 "Invalid RPC Response CloudEvent sink, Response CloudEvent sink must be uri of the destination of the response",
-            ));
+            ).with_field("sink"));
         }
         Ok(())
     }
@@ -526,7 +552,7 @@ impl CloudEventValidator for ResponseValidator {
         Err(ValidationError::new(format!(
             "Invalid CloudEvent type [{}], CloudEvent of type Response must have a type of 'res.v1'",
             cloud_event.ty(),
-        )))
+        )).with_field("type"))
     }
 }
 
@@ -1158,6 +1184,26 @@ mod tests {
             status.as_ref().unwrap_err().to_string().contains("Invalid RPC Response CloudEvent sink, Response CloudEvent sink must be uri of the destination of the response"));
     }
 
+    #[test]
+    fn test_report_lists_each_error() {
+        let source =
+            LongUriSerializer::deserialize("//VCU.myvin/body.access/1/UpdateDoor".to_string())
+                .unwrap();
+        let event = build_base_cloud_event_builder_for_test()
+            .id(UUIDv8Builder::new().build())
+            .source(source.to_string())
+            .ty(UMessageType::UmessageTypeResponse)
+            .build()
+            .unwrap();
+
+        let report = CloudEventValidators::Response.validator().report(&event);
+        let fields = report
+            .errors()
+            .map(|violation| violation.field())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec![Some("source"), Some("sink")]);
+    }
+
     #[test]
     fn test_response_type_cloudevent_is_not_valid_invalid_source_not_rpc_command() {
         let uuid = UUIDv8Builder::new().build();
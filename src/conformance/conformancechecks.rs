@@ -0,0 +1,250 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::fmt::Debug;
+
+use crate::conformance::{
+    attributes_vectors, invalid_micro_uri_vectors, uri_vectors, uuid_vectors,
+};
+use crate::transport::validator::{UAttributesValidator, ValidationError, Validators};
+use crate::uprotocol::{Remote, UAttributes, UUri, Uuid};
+use crate::uri::serializer::{LongUriSerializer, MicroUriSerializer, UriSerializer};
+
+/// Asserts that the crate's [`LongUriSerializer`] and [`MicroUriSerializer`] serialize the URI vectors into their
+/// long and micro forms, and deserialize these forms into the names and IDs of the URIs respectively.
+///
+/// # Panics
+///
+/// - if a serializer fails a vector, naming the vector.
+pub fn assert_uri_serializers() {
+    for vector in uri_vectors() {
+        if let Some(long_form) = &vector.long_form {
+            assert_eq!(
+                LongUriSerializer::serialize(&vector.uri).ok().as_ref(),
+                Some(long_form),
+                "{}: long form",
+                vector.name
+            );
+            let deserialized = LongUriSerializer::deserialize(long_form.clone())
+                .unwrap_or_else(|e| panic!("{}: {e}", vector.name));
+            assert_eq!(
+                names(&deserialized),
+                names(&vector.uri),
+                "{}: names",
+                vector.name
+            );
+        }
+        if let Some(micro_form) = &vector.micro_form {
+            assert_eq!(
+                MicroUriSerializer::serialize(&vector.uri).ok().as_ref(),
+                Some(micro_form),
+                "{}: micro form",
+                vector.name
+            );
+            let deserialized = MicroUriSerializer::deserialize(micro_form.clone())
+                .unwrap_or_else(|e| panic!("{}: {e}", vector.name));
+            assert_eq!(ids(&deserialized), ids(&vector.uri), "{}: IDs", vector.name);
+        }
+    }
+    for vector in invalid_micro_uri_vectors() {
+        assert!(
+            MicroUriSerializer::deserialize(vector.micro_form).is_err(),
+            "{}",
+            vector.name
+        );
+    }
+}
+
+/// Asserts that the crate's conversions of [`Uuid`] agree with the UUID vectors.
+///
+/// # Panics
+///
+/// - if a conversion fails a vector, naming the vector.
+pub fn assert_uuid_conversions() {
+    for vector in uuid_vectors() {
+        assert_eq!(
+            vector.uuid.to_hyphenated_string(),
+            vector.hyphenated,
+            "{}",
+            vector.name
+        );
+        assert_eq!(
+            vector.hyphenated.parse::<Uuid>().ok(),
+            Some(vector.uuid.clone()),
+            "{}",
+            vector.name
+        );
+        assert_eq!(
+            vector.uuid.is_uprotocol_uuid(),
+            vector.is_uprotocol,
+            "{}",
+            vector.name
+        );
+        if vector.is_uprotocol {
+            assert_eq!(vector.uuid.get_time(), vector.time, "{}", vector.name);
+        }
+    }
+}
+
+/// Asserts that the crate's [`UAttributesValidator`]s accept the valid attributes vectors, and fail the invalid
+/// ones with errors for the expected fields.
+///
+/// # Panics
+///
+/// - if a validator fails a vector, naming the vector.
+pub fn assert_attributes_validators() {
+    for vector in attributes_vectors() {
+        let result = Validators::get_validator(&vector.attributes).validate(&vector.attributes);
+        let fields = result.err().as_ref().map_or_else(Vec::new, fields);
+        assert_eq!(fields, vector.errors, "{}", vector.name);
+    }
+}
+
+/// Asserts that a mapping of `UUri`s, e.g. of a transport, keeps the URI vectors, i.e. that every URI is equal to
+/// itself after being mapped and mapped back.
+///
+/// # Arguments
+///
+/// * `map` - Maps a `UUri` to the transport's representation.
+/// * `map_back` - Maps the transport's representation back to a `UUri`.
+///
+/// # Panics
+///
+/// - if the mapping fails or changes a vector, naming the vector.
+pub fn assert_uri_mapping<T, E, M, B>(map: M, map_back: B)
+where
+    E: Debug,
+    M: Fn(&UUri) -> Result<T, E>,
+    B: Fn(T) -> Result<UUri, E>,
+{
+    for vector in uri_vectors() {
+        let mapped = map(&vector.uri).unwrap_or_else(|e| panic!("{}: {e:?}", vector.name));
+        let uri = map_back(mapped).unwrap_or_else(|e| panic!("{}: {e:?}", vector.name));
+        assert_eq!(uri, vector.uri, "{}", vector.name);
+    }
+}
+
+/// Asserts that a mapping of `UAttributes`, e.g. of a transport, keeps the attributes vectors that are valid, i.e.
+/// that the attributes are equal to themselves after being mapped and mapped back.
+///
+/// # Arguments
+///
+/// * `map` - Maps `UAttributes` to the transport's representation.
+/// * `map_back` - Maps the transport's representation back to `UAttributes`.
+///
+/// # Panics
+///
+/// - if the mapping fails or changes a vector, naming the vector.
+pub fn assert_attributes_mapping<T, E, M, B>(map: M, map_back: B)
+where
+    E: Debug,
+    M: Fn(&UAttributes) -> Result<T, E>,
+    B: Fn(T) -> Result<UAttributes, E>,
+{
+    for vector in attributes_vectors()
+        .into_iter()
+        .filter(|vector| vector.is_valid())
+    {
+        let mapped = map(&vector.attributes).unwrap_or_else(|e| panic!("{}: {e:?}", vector.name));
+        let attributes = map_back(mapped).unwrap_or_else(|e| panic!("{}: {e:?}", vector.name));
+        assert_eq!(attributes, vector.attributes, "{}", vector.name);
+    }
+}
+
+// Gets the field paths of a validation error, in the order of the errors it combines
+fn fields(error: &ValidationError) -> Vec<String> {
+    let errors = if error.errors().is_empty() {
+        core::slice::from_ref(error)
+    } else {
+        error.errors()
+    };
+    errors
+        .iter()
+        .map(|error| error.field().unwrap_or_default().to_string())
+        .collect()
+}
+
+type Names<'a> = (
+    Option<&'a str>,
+    Option<&'a str>,
+    Option<u32>,
+    Option<&'a str>,
+    Option<&'a str>,
+    Option<&'a str>,
+);
+
+fn names(uri: &UUri) -> Names<'_> {
+    let authority = uri
+        .authority
+        .as_ref()
+        .and_then(|authority| match &authority.remote {
+            Some(Remote::Name(name)) => Some(name.as_str()),
+            _ => None,
+        });
+    let entity = uri.entity.as_ref();
+    let resource = uri.resource.as_ref();
+    (
+        authority,
+        entity.map(|entity| entity.name.as_str()),
+        entity.and_then(|entity| entity.version_major),
+        resource.map(|resource| resource.name.as_str()),
+        resource.and_then(|resource| resource.instance.as_deref()),
+        resource.and_then(|resource| resource.message.as_deref()),
+    )
+}
+
+fn ids(uri: &UUri) -> (Option<&Remote>, Option<u32>, Option<u32>, Option<u32>) {
+    let entity = uri.entity.as_ref();
+    (
+        uri.authority
+            .as_ref()
+            .and_then(|authority| authority.remote.as_ref()),
+        entity.and_then(|entity| entity.id),
+        entity.and_then(|entity| entity.version_major),
+        uri.resource.as_ref().and_then(|resource| resource.id),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prost::Message;
+
+    #[test]
+    fn test_uri_serializers() {
+        assert_uri_serializers();
+    }
+
+    #[test]
+    fn test_uuid_conversions() {
+        assert_uuid_conversions();
+    }
+
+    #[test]
+    fn test_attributes_validators() {
+        assert_attributes_validators();
+    }
+
+    #[test]
+    fn test_protobuf_mapping() {
+        assert_uri_mapping(
+            |uri| Ok::<_, prost::DecodeError>(uri.encode_to_vec()),
+            |bytes| UUri::decode(bytes.as_slice()),
+        );
+        assert_attributes_mapping(
+            |attributes| Ok::<_, prost::DecodeError>(attributes.encode_to_vec()),
+            |bytes| UAttributes::decode(bytes.as_slice()),
+        );
+    }
+}
@@ -0,0 +1,309 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use serde::Deserialize;
+
+use crate::uprotocol::{
+    Remote, UAttributes, UAuthority, UEntity, UMessageType, UPriority, UResource, UUri, Uuid,
+};
+
+// The test vectors shared by the uProtocol SDKs, see `testvectors.json`
+const TEST_VECTORS: &str = include_str!("testvectors.json");
+
+/// A URI together with its serialized forms.
+///
+/// The URI is resolved, i.e. it has both names and IDs. A URI whose authority has a name only has a long form,
+/// whereas a URI whose authority has an IP address or an ID only has a micro form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UriVector {
+    /// The name of the vector, for reporting failures.
+    pub name: String,
+    /// The URI.
+    pub uri: UUri,
+    /// The URI in long form, if it has one.
+    pub long_form: Option<String>,
+    /// The URI in micro form, if it has one.
+    pub micro_form: Option<Vec<u8>>,
+}
+
+/// Data that is not a valid URI in micro form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidMicroUriVector {
+    /// The name of the vector, for reporting failures.
+    pub name: String,
+    /// The invalid data.
+    pub micro_form: Vec<u8>,
+}
+
+/// A UUID together with its string representation and properties.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UuidVector {
+    /// The name of the vector, for reporting failures.
+    pub name: String,
+    /// The UUID.
+    pub uuid: Uuid,
+    /// The UUID as hyphenated string.
+    pub hyphenated: String,
+    /// Whether the UUID is a valid uProtocol UUID.
+    pub is_uprotocol: bool,
+    /// The creation time of a uProtocol UUID, in milliseconds since UNIX epoch.
+    pub time: Option<u64>,
+}
+
+/// A set of attributes together with the outcome of validating them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributesVector {
+    /// The name of the vector, for reporting failures.
+    pub name: String,
+    /// The attributes.
+    pub attributes: UAttributes,
+    /// The field paths of the validation errors, in the order they are reported, like `sink.entity.name`.
+    pub errors: Vec<String>,
+}
+
+impl AttributesVector {
+    /// Checks if the attributes are expected to pass validation.
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Gets the URI vectors.
+pub fn uri_vectors() -> Vec<UriVector> {
+    test_vectors()
+        .uris
+        .into_iter()
+        .map(|vector| UriVector {
+            name: vector.name,
+            uri: vector.uri.into(),
+            long_form: vector.long_form,
+            micro_form: vector.micro_form.as_deref().map(hex),
+        })
+        .collect()
+}
+
+/// Gets the vectors of data that fails deserialization from micro form.
+pub fn invalid_micro_uri_vectors() -> Vec<InvalidMicroUriVector> {
+    test_vectors()
+        .invalid_micro_uris
+        .into_iter()
+        .map(|vector| InvalidMicroUriVector {
+            name: vector.name,
+            micro_form: hex(&vector.micro_form),
+        })
+        .collect()
+}
+
+/// Gets the UUID vectors.
+pub fn uuid_vectors() -> Vec<UuidVector> {
+    test_vectors()
+        .uuids
+        .into_iter()
+        .map(|vector| UuidVector {
+            name: vector.name,
+            uuid: Uuid {
+                msb: u64_from_hex(&vector.msb),
+                lsb: u64_from_hex(&vector.lsb),
+            },
+            hyphenated: vector.hyphenated,
+            is_uprotocol: vector.is_uprotocol,
+            time: vector.time,
+        })
+        .collect()
+}
+
+/// Gets the attributes vectors, both valid and invalid ones.
+pub fn attributes_vectors() -> Vec<AttributesVector> {
+    test_vectors()
+        .attributes
+        .into_iter()
+        .map(|vector| AttributesVector {
+            name: vector.name,
+            attributes: vector.attributes.into(),
+            errors: vector.errors,
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct TestVectors {
+    uris: Vec<UriVectorData>,
+    invalid_micro_uris: Vec<InvalidMicroUriData>,
+    uuids: Vec<UuidVectorData>,
+    attributes: Vec<AttributesVectorData>,
+}
+
+#[derive(Deserialize)]
+struct UriVectorData {
+    name: String,
+    uri: UriData,
+    long_form: Option<String>,
+    micro_form: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct InvalidMicroUriData {
+    name: String,
+    micro_form: String,
+}
+
+#[derive(Deserialize)]
+struct UuidVectorData {
+    name: String,
+    hyphenated: String,
+    msb: String,
+    lsb: String,
+    is_uprotocol: bool,
+    time: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct AttributesVectorData {
+    name: String,
+    attributes: AttributesData,
+    #[serde(default)]
+    errors: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct UriData {
+    authority: Option<AuthorityData>,
+    entity: EntityData,
+    resource: ResourceData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AuthorityData {
+    Name(String),
+    Ip(String),
+    Id(String),
+}
+
+#[derive(Deserialize)]
+struct EntityData {
+    name: String,
+    id: Option<u32>,
+    version_major: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ResourceData {
+    name: String,
+    instance: Option<String>,
+    message: Option<String>,
+    id: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct AttributesData {
+    id: String,
+    r#type: String,
+    priority: String,
+    ttl: Option<i32>,
+    sink: Option<UriData>,
+    permission_level: Option<i32>,
+    commstatus: Option<i32>,
+    reqid: Option<String>,
+    token: Option<String>,
+}
+
+impl From<UriData> for UUri {
+    fn from(data: UriData) -> Self {
+        UUri {
+            authority: data.authority.map(|authority| UAuthority {
+                remote: Some(match authority {
+                    AuthorityData::Name(name) => Remote::Name(name),
+                    AuthorityData::Ip(ip) => Remote::Ip(hex(&ip)),
+                    AuthorityData::Id(id) => Remote::Id(hex(&id)),
+                }),
+            }),
+            entity: Some(UEntity {
+                name: data.entity.name,
+                id: data.entity.id,
+                version_major: data.entity.version_major,
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                name: data.resource.name,
+                instance: data.resource.instance,
+                message: data.resource.message,
+                id: data.resource.id,
+            }),
+        }
+    }
+}
+
+impl From<AttributesData> for UAttributes {
+    fn from(data: AttributesData) -> Self {
+        UAttributes {
+            id: Some(uuid(&data.id)),
+            r#type: UMessageType::from_str_name(&data.r#type)
+                .expect("test vector needs a valid message type")
+                .into(),
+            sink: data.sink.map(UUri::from),
+            priority: UPriority::from_str_name(&data.priority)
+                .expect("test vector needs a valid priority")
+                .into(),
+            ttl: data.ttl,
+            permission_level: data.permission_level,
+            commstatus: data.commstatus,
+            reqid: data.reqid.as_deref().map(uuid),
+            token: data.token,
+        }
+    }
+}
+
+fn test_vectors() -> TestVectors {
+    serde_json::from_str(TEST_VECTORS).expect("test vectors need to be valid")
+}
+
+fn hex(data: &str) -> Vec<u8> {
+    (0..data.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&data[index..index + 2], 16))
+        .collect::<Result<_, _>>()
+        .expect("test vector needs valid hex data")
+}
+
+fn u64_from_hex(data: &str) -> u64 {
+    u64::from_str_radix(data, 16).expect("test vector needs a valid hex number")
+}
+
+fn uuid(data: &str) -> Uuid {
+    data.parse().expect("test vector needs a valid UUID")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors_are_complete() {
+        assert!(!uri_vectors().is_empty());
+        assert!(!invalid_micro_uri_vectors().is_empty());
+        assert!(!uuid_vectors().is_empty());
+        assert!(attributes_vectors().iter().any(AttributesVector::is_valid));
+        assert!(!attributes_vectors().iter().all(AttributesVector::is_valid));
+    }
+
+    #[test]
+    fn test_uri_vector() {
+        let vector = &uri_vectors()[0];
+        assert_eq!(
+            vector.micro_form,
+            Some(vec![1, 0, 0x80, 0, 0x11, 0x02, 1, 0])
+        );
+        assert_eq!(vector.uri.entity.as_ref().unwrap().id, Some(0x1102));
+    }
+}
@@ -0,0 +1,139 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use alloc::string::ToString;
+
+use crate::uprotocol::uentity::{UEntityId, VersionMajor};
+use crate::uprotocol::uresource::UResourceId;
+use crate::uprotocol::{UEntity, UUri};
+use crate::uri::builder::resourcebuilder::UResourceBuilder;
+
+/// The name of the uDiscovery service.
+pub const UDISCOVERY_NAME: &str = "core.udiscovery";
+/// The major version of the uDiscovery service API.
+pub const UDISCOVERY_VERSION_MAJOR: VersionMajor = VersionMajor::new(3);
+/// The identifier of the uDiscovery service.
+pub const UDISCOVERY_ID: UEntityId = UEntityId::new(1);
+
+const METHODS: [UDiscoveryMethod; 9] = [
+    UDiscoveryMethod::LookupUri,
+    UDiscoveryMethod::UpdateNode,
+    UDiscoveryMethod::FindNodes,
+    UDiscoveryMethod::FindNodeProperties,
+    UDiscoveryMethod::DeleteNodes,
+    UDiscoveryMethod::AddNodes,
+    UDiscoveryMethod::UpdateProperty,
+    UDiscoveryMethod::RegisterForNotifications,
+    UDiscoveryMethod::UnregisterForNotifications,
+];
+
+/// The methods of the uDiscovery service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UDiscoveryMethod {
+    LookupUri = 1,
+    UpdateNode = 2,
+    FindNodes = 3,
+    FindNodeProperties = 4,
+    DeleteNodes = 5,
+    AddNodes = 6,
+    UpdateProperty = 7,
+    RegisterForNotifications = 8,
+    UnregisterForNotifications = 9,
+}
+
+impl UDiscoveryMethod {
+    /// Gets the name of the method, as declared in the service's proto definition.
+    pub fn name(&self) -> &'static str {
+        match self {
+            UDiscoveryMethod::LookupUri => "LookupUri",
+            UDiscoveryMethod::UpdateNode => "UpdateNode",
+            UDiscoveryMethod::FindNodes => "FindNodes",
+            UDiscoveryMethod::FindNodeProperties => "FindNodeProperties",
+            UDiscoveryMethod::DeleteNodes => "DeleteNodes",
+            UDiscoveryMethod::AddNodes => "AddNodes",
+            UDiscoveryMethod::UpdateProperty => "UpdateProperty",
+            UDiscoveryMethod::RegisterForNotifications => "RegisterForNotifications",
+            UDiscoveryMethod::UnregisterForNotifications => "UnregisterForNotifications",
+        }
+    }
+
+    /// Gets the method with the given name, as declared in the service's proto definition.
+    ///
+    /// # Returns
+    ///
+    /// The method, or `None` if the service has no method with the name.
+    pub fn from_name(name: &str) -> Option<UDiscoveryMethod> {
+        METHODS.into_iter().find(|method| method.name() == name)
+    }
+
+    /// Gets the URI of the method on the local uDiscovery service.
+    pub fn uri(&self) -> UUri {
+        UUri {
+            entity: Some(udiscovery_entity()),
+            resource: Some(UResourceBuilder::for_rpc_request(
+                Some(self.name().to_string()),
+                Some(UResourceId::new(*self as u16)),
+            )),
+            ..Default::default()
+        }
+    }
+}
+
+/// Gets the uEntity of the uDiscovery service.
+pub fn udiscovery_entity() -> UEntity {
+    UEntity {
+        name: UDISCOVERY_NAME.to_string(),
+        id: Some(UDISCOVERY_ID.into()),
+        version_major: Some(UDISCOVERY_VERSION_MAJOR.into()),
+        ..Default::default()
+    }
+}
+
+/// Gets the URI of a method of the local uDiscovery service.
+///
+/// # Arguments
+///
+/// * `name` - The name of the method, as declared in the service's proto definition.
+///
+/// # Returns
+///
+/// The URI, or `None` if the service has no method with the name.
+pub fn udiscovery_method_uri(name: &str) -> Option<UUri> {
+    UDiscoveryMethod::from_name(name).map(|method| method.uri())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_from_name() {
+        for method in METHODS {
+            assert_eq!(UDiscoveryMethod::from_name(method.name()), Some(method));
+        }
+        assert_eq!(UDiscoveryMethod::from_name("Unknown"), None);
+    }
+
+    #[test]
+    fn test_method_uri() {
+        let uri = udiscovery_method_uri("LookupUri").unwrap();
+        assert_eq!(uri, UDiscoveryMethod::LookupUri.uri());
+        assert_eq!(uri.entity.unwrap().id, Some(u32::from(UDISCOVERY_ID)));
+        let resource = uri.resource.unwrap();
+        assert_eq!(resource.name, "rpc");
+        assert_eq!(resource.instance.as_deref(), Some("LookupUri"));
+        assert_eq!(resource.id, Some(1));
+
+        assert_eq!(udiscovery_method_uri("Unknown"), None);
+    }
+}
@@ -0,0 +1,164 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use alloc::string::ToString;
+
+use crate::uprotocol::uentity::{UEntityId, VersionMajor};
+use crate::uprotocol::uresource::UResourceId;
+use crate::uprotocol::{UEntity, UResource, UUri};
+use crate::uri::builder::resourcebuilder::UResourceBuilder;
+
+/// The name of the uSubscription service.
+pub const USUBSCRIPTION_NAME: &str = "core.usubscription";
+/// The major version of the uSubscription service API.
+pub const USUBSCRIPTION_VERSION_MAJOR: VersionMajor = VersionMajor::new(3);
+/// The identifier of the uSubscription service.
+pub const USUBSCRIPTION_ID: UEntityId = UEntityId::new(0);
+
+/// The resource identifier of the topic that the uSubscription service publishes subscription change
+/// notifications to.
+pub const USUBSCRIPTION_NOTIFICATIONS_ID: UResourceId = UResourceId::new(0x8000);
+
+const METHODS: [USubscriptionMethod; 9] = [
+    USubscriptionMethod::Subscribe,
+    USubscriptionMethod::Unsubscribe,
+    USubscriptionMethod::FetchSubscriptions,
+    USubscriptionMethod::CreateTopic,
+    USubscriptionMethod::DeprecateTopic,
+    USubscriptionMethod::RegisterForNotifications,
+    USubscriptionMethod::UnregisterForNotifications,
+    USubscriptionMethod::FetchSubscribers,
+    USubscriptionMethod::Reset,
+];
+
+/// The methods of the uSubscription service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum USubscriptionMethod {
+    Subscribe = 1,
+    Unsubscribe = 2,
+    FetchSubscriptions = 3,
+    CreateTopic = 4,
+    DeprecateTopic = 5,
+    RegisterForNotifications = 6,
+    UnregisterForNotifications = 7,
+    FetchSubscribers = 8,
+    Reset = 9,
+}
+
+impl USubscriptionMethod {
+    /// Gets the name of the method, as declared in the service's proto definition.
+    pub fn name(&self) -> &'static str {
+        match self {
+            USubscriptionMethod::Subscribe => "Subscribe",
+            USubscriptionMethod::Unsubscribe => "Unsubscribe",
+            USubscriptionMethod::FetchSubscriptions => "FetchSubscriptions",
+            USubscriptionMethod::CreateTopic => "CreateTopic",
+            USubscriptionMethod::DeprecateTopic => "DeprecateTopic",
+            USubscriptionMethod::RegisterForNotifications => "RegisterForNotifications",
+            USubscriptionMethod::UnregisterForNotifications => "UnregisterForNotifications",
+            USubscriptionMethod::FetchSubscribers => "FetchSubscribers",
+            USubscriptionMethod::Reset => "Reset",
+        }
+    }
+
+    /// Gets the method with the given name, as declared in the service's proto definition.
+    ///
+    /// # Returns
+    ///
+    /// The method, or `None` if the service has no method with the name.
+    pub fn from_name(name: &str) -> Option<USubscriptionMethod> {
+        METHODS.into_iter().find(|method| method.name() == name)
+    }
+
+    /// Gets the URI of the method on the local uSubscription service.
+    pub fn uri(&self) -> UUri {
+        UUri {
+            entity: Some(usubscription_entity()),
+            resource: Some(UResourceBuilder::for_rpc_request(
+                Some(self.name().to_string()),
+                Some(UResourceId::new(*self as u16)),
+            )),
+            ..Default::default()
+        }
+    }
+}
+
+/// Gets the uEntity of the uSubscription service.
+pub fn usubscription_entity() -> UEntity {
+    UEntity {
+        name: USUBSCRIPTION_NAME.to_string(),
+        id: Some(USUBSCRIPTION_ID.into()),
+        version_major: Some(USUBSCRIPTION_VERSION_MAJOR.into()),
+        ..Default::default()
+    }
+}
+
+/// Gets the URI of a method of the local uSubscription service.
+///
+/// # Arguments
+///
+/// * `name` - The name of the method, as declared in the service's proto definition.
+///
+/// # Returns
+///
+/// The URI, or `None` if the service has no method with the name.
+pub fn usubscription_method_uri(name: &str) -> Option<UUri> {
+    USubscriptionMethod::from_name(name).map(|method| method.uri())
+}
+
+/// Gets the topic that the local uSubscription service publishes subscription change notifications to.
+pub fn usubscription_notifications_topic() -> UUri {
+    UUri {
+        entity: Some(usubscription_entity()),
+        resource: Some(UResource {
+            name: "subscriptions".to_string(),
+            instance: None,
+            message: Some("Update".to_string()),
+            id: Some(USUBSCRIPTION_NOTIFICATIONS_ID.into()),
+        }),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_from_name() {
+        for method in METHODS {
+            assert_eq!(USubscriptionMethod::from_name(method.name()), Some(method));
+        }
+        assert_eq!(USubscriptionMethod::from_name("Unknown"), None);
+    }
+
+    #[test]
+    fn test_method_uri() {
+        let uri = usubscription_method_uri("FetchSubscriptions").unwrap();
+        assert_eq!(uri, USubscriptionMethod::FetchSubscriptions.uri());
+        assert_eq!(uri.entity.unwrap().id, Some(u32::from(USUBSCRIPTION_ID)));
+        let resource = uri.resource.unwrap();
+        assert_eq!(resource.name, "rpc");
+        assert_eq!(resource.instance.as_deref(), Some("FetchSubscriptions"));
+        assert_eq!(resource.id, Some(3));
+
+        assert_eq!(usubscription_method_uri("Unknown"), None);
+    }
+
+    #[test]
+    fn test_notifications_topic() {
+        let topic = usubscription_notifications_topic();
+        assert_eq!(topic.entity, Some(usubscription_entity()));
+        assert_eq!(topic.resource.unwrap().id, Some(0x8000));
+    }
+}
@@ -0,0 +1,115 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use alloc::string::ToString;
+
+use crate::uprotocol::uentity::{UEntityId, VersionMajor};
+use crate::uprotocol::uresource::UResourceId;
+use crate::uprotocol::{UEntity, UUri};
+use crate::uri::builder::resourcebuilder::UResourceBuilder;
+
+/// The name of the uTwin service.
+pub const UTWIN_NAME: &str = "core.utwin";
+/// The major version of the uTwin service API.
+pub const UTWIN_VERSION_MAJOR: VersionMajor = VersionMajor::new(1);
+/// The identifier of the uTwin service.
+pub const UTWIN_ID: UEntityId = UEntityId::new(26);
+
+const METHODS: [UTwinMethod; 2] = [UTwinMethod::GetLastMessages, UTwinMethod::SetLastMessage];
+
+/// The methods of the uTwin service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UTwinMethod {
+    GetLastMessages = 1,
+    SetLastMessage = 2,
+}
+
+impl UTwinMethod {
+    /// Gets the name of the method, as declared in the service's proto definition.
+    pub fn name(&self) -> &'static str {
+        match self {
+            UTwinMethod::GetLastMessages => "GetLastMessages",
+            UTwinMethod::SetLastMessage => "SetLastMessage",
+        }
+    }
+
+    /// Gets the method with the given name, as declared in the service's proto definition.
+    ///
+    /// # Returns
+    ///
+    /// The method, or `None` if the service has no method with the name.
+    pub fn from_name(name: &str) -> Option<UTwinMethod> {
+        METHODS.into_iter().find(|method| method.name() == name)
+    }
+
+    /// Gets the URI of the method on the local uTwin service.
+    pub fn uri(&self) -> UUri {
+        UUri {
+            entity: Some(utwin_entity()),
+            resource: Some(UResourceBuilder::for_rpc_request(
+                Some(self.name().to_string()),
+                Some(UResourceId::new(*self as u16)),
+            )),
+            ..Default::default()
+        }
+    }
+}
+
+/// Gets the uEntity of the uTwin service.
+pub fn utwin_entity() -> UEntity {
+    UEntity {
+        name: UTWIN_NAME.to_string(),
+        id: Some(UTWIN_ID.into()),
+        version_major: Some(UTWIN_VERSION_MAJOR.into()),
+        ..Default::default()
+    }
+}
+
+/// Gets the URI of a method of the local uTwin service.
+///
+/// # Arguments
+///
+/// * `name` - The name of the method, as declared in the service's proto definition.
+///
+/// # Returns
+///
+/// The URI, or `None` if the service has no method with the name.
+pub fn utwin_method_uri(name: &str) -> Option<UUri> {
+    UTwinMethod::from_name(name).map(|method| method.uri())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_method_from_name() {
+        for method in METHODS {
+            assert_eq!(UTwinMethod::from_name(method.name()), Some(method));
+        }
+        assert_eq!(UTwinMethod::from_name("Unknown"), None);
+    }
+
+    #[test]
+    fn test_method_uri() {
+        let uri = utwin_method_uri("SetLastMessage").unwrap();
+        assert_eq!(uri, UTwinMethod::SetLastMessage.uri());
+        assert_eq!(uri.entity.unwrap().id, Some(u32::from(UTWIN_ID)));
+        let resource = uri.resource.unwrap();
+        assert_eq!(resource.name, "rpc");
+        assert_eq!(resource.instance.as_deref(), Some("SetLastMessage"));
+        assert_eq!(resource.id, Some(2));
+
+        assert_eq!(utwin_method_uri("Unknown"), None);
+    }
+}
@@ -0,0 +1,122 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::ffi::{c_char, CStr};
+use std::panic::AssertUnwindSafe;
+
+use crate::uprotocol::UCode;
+
+/// Runs the body of an exported function, so that a panic is reported to the caller instead of unwinding into
+/// C code, which is undefined behavior.
+///
+/// # Returns
+///
+/// The value returned by `body`, or `fallback` if `body` panics, e.g. `UCode::Internal`.
+pub(crate) fn catch_panic<T>(fallback: T, body: impl FnOnce() -> T) -> T {
+    // nothing that the body has touched is used after it has panicked, so no broken invariants can be observed
+    std::panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(fallback)
+}
+
+/// Copies data into a buffer provided by the caller.
+///
+/// `written` (if not null) is set to the length of the data, so that callers can retry with a larger
+/// buffer if the given one is too small.
+///
+/// # Safety
+///
+/// `buffer` must be null or valid for writes of `buffer_len` bytes, `written` must be null or valid for writes.
+pub(crate) unsafe fn write_bytes(
+    data: &[u8],
+    buffer: *mut u8,
+    buffer_len: usize,
+    written: *mut usize,
+) -> i32 {
+    if !written.is_null() {
+        *written = data.len();
+    }
+    if buffer.is_null() || buffer_len < data.len() {
+        return UCode::OutOfRange as i32;
+    }
+    std::ptr::copy_nonoverlapping(data.as_ptr(), buffer, data.len());
+    UCode::Ok as i32
+}
+
+/// Copies a string into a buffer provided by the caller, as a NUL-terminated C string.
+///
+/// `written` (if not null) is set to the length of the string including the terminating NUL.
+///
+/// # Safety
+///
+/// `buffer` must be null or valid for writes of `buffer_len` bytes, `written` must be null or valid for writes.
+pub(crate) unsafe fn write_str(
+    value: &str,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    written: *mut usize,
+) -> i32 {
+    let mut data = Vec::with_capacity(value.len() + 1);
+    data.extend_from_slice(value.as_bytes());
+    data.push(0);
+    write_bytes(&data, buffer.cast(), buffer_len, written)
+}
+
+/// Borrows a NUL-terminated C string as UTF-8 string slice.
+///
+/// # Errors
+///
+/// Returns `UCode::InvalidArgument` if the pointer is null or the string is not valid UTF-8.
+///
+/// # Safety
+///
+/// `value` must be null or point to a NUL-terminated string that outlives the returned slice.
+pub(crate) unsafe fn read_str<'a>(value: *const c_char) -> Result<&'a str, i32> {
+    if value.is_null() {
+        return Err(UCode::InvalidArgument as i32);
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .map_err(|_| UCode::InvalidArgument as i32)
+}
+
+/// Borrows a buffer provided by the caller as byte slice.
+///
+/// # Errors
+///
+/// Returns `UCode::InvalidArgument` if the pointer is null.
+///
+/// # Safety
+///
+/// `data` must be null or valid for reads of `len` bytes that outlive the returned slice.
+pub(crate) unsafe fn read_bytes<'a>(data: *const u8, len: usize) -> Result<&'a [u8], i32> {
+    if data.is_null() {
+        return Err(UCode::InvalidArgument as i32);
+    }
+    Ok(std::slice::from_raw_parts(data, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_panic_maps_panic_to_fallback() {
+        assert_eq!(
+            catch_panic(UCode::Internal as i32, || UCode::Ok as i32),
+            UCode::Ok as i32
+        );
+        assert_eq!(
+            catch_panic(UCode::Internal as i32, || -> i32 { panic!("boom") }),
+            UCode::Internal as i32
+        );
+    }
+}
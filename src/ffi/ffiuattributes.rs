@@ -0,0 +1,269 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::ffi::c_char;
+
+use prost::Message;
+
+use crate::ffi::ffibuffer::{catch_panic, read_str, write_bytes};
+use crate::ffi::ffiuuid::{uuid_builder, UpUuid};
+use crate::transport::validator::Validators;
+use crate::uprotocol::{UAttributes, UCode, UMessageType, UPriority};
+use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+
+/// The attributes of a message as seen from C.
+///
+/// Optional values that are not set are represented by `0`, a nil UUID or a null pointer, respectively.
+/// Strings are borrowed from the caller and need to outlive any call that the attributes are passed to.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct UpAttributes {
+    /// The message's ID.
+    pub id: UpUuid,
+    /// The message type, one of the values of `UMessageType`.
+    pub message_type: i32,
+    /// The message priority, one of the values of `UPriority`.
+    pub priority: i32,
+    /// The long form URI of the message's destination as NUL-terminated string.
+    pub sink: *const c_char,
+    /// The number of milliseconds that the message is valid for.
+    pub ttl: u32,
+    /// The permission level required to receive the message.
+    pub permission_level: u32,
+    /// The communication status of the message, one of the values of `UCode`.
+    pub commstatus: i32,
+    /// The ID of the request that a response message refers to.
+    pub reqid: UpUuid,
+    /// The access token as NUL-terminated string.
+    pub token: *const c_char,
+}
+
+unsafe fn to_uattributes(attributes: &UpAttributes) -> Result<UAttributes, i32> {
+    let sink = if attributes.sink.is_null() {
+        None
+    } else {
+        let sink = read_str(attributes.sink)?;
+        Some(
+            LongUriSerializer::deserialize(sink.to_string())
+                .map_err(|_| UCode::InvalidArgument as i32)?,
+        )
+    };
+    let token = if attributes.token.is_null() {
+        None
+    } else {
+        Some(read_str(attributes.token)?.to_string())
+    };
+    let optional = |value: u32| {
+        (value > 0)
+            .then(|| i32::try_from(value).map_err(|_| UCode::InvalidArgument as i32))
+            .transpose()
+    };
+
+    Ok(UAttributes {
+        id: Some(attributes.id.into()),
+        r#type: attributes.message_type,
+        sink,
+        priority: attributes.priority,
+        ttl: optional(attributes.ttl)?,
+        permission_level: optional(attributes.permission_level)?,
+        commstatus: (attributes.commstatus != 0).then_some(attributes.commstatus),
+        reqid: (!attributes.reqid.is_nil()).then(|| attributes.reqid.into()),
+        token,
+    })
+}
+
+/// Initializes the attributes of a new message, with a newly created ID and no optional values set.
+///
+/// # Returns
+///
+/// `UCode::Ok`, or `UCode::InvalidArgument` if the message type or priority is unknown or `attributes` is null.
+///
+/// # Safety
+///
+/// `attributes` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn up_attributes_init(
+    message_type: i32,
+    priority: i32,
+    attributes: *mut UpAttributes,
+) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        if attributes.is_null()
+            || UMessageType::try_from(message_type).is_err()
+            || UPriority::try_from(priority).is_err()
+        {
+            return UCode::InvalidArgument as i32;
+        }
+        *attributes = UpAttributes {
+            id: uuid_builder().build().into(),
+            message_type,
+            priority,
+            sink: std::ptr::null(),
+            ttl: 0,
+            permission_level: 0,
+            commstatus: 0,
+            reqid: UpUuid::default(),
+            token: std::ptr::null(),
+        };
+        UCode::Ok as i32
+    })
+}
+
+/// Validates attributes according to their message type, as done by the
+/// [`UAttributesValidator`](crate::transport::validator::UAttributesValidator)s.
+///
+/// # Returns
+///
+/// `UCode::Ok` if the attributes are valid, `UCode::InvalidArgument` otherwise.
+///
+/// # Safety
+///
+/// `attributes` must be null or valid for reads, and its strings must be null or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn up_attributes_validate(attributes: *const UpAttributes) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        let Some(attributes) = attributes.as_ref() else {
+            return UCode::InvalidArgument as i32;
+        };
+        match to_uattributes(attributes) {
+            Ok(attributes) => match Validators::get_validator(&attributes).validate(&attributes) {
+                Ok(()) => UCode::Ok as i32,
+                Err(e) => e.code() as i32,
+            },
+            Err(code) => code,
+        }
+    })
+}
+
+/// Validates attributes and writes their protobuf encoding into a buffer.
+///
+/// # Returns
+///
+/// `UCode::Ok`, or `UCode::OutOfRange` if the buffer is too small. In both cases, `written` is
+/// set to the length of the encoded attributes, if not null. `UCode::InvalidArgument` is returned
+/// if the attributes are invalid.
+///
+/// # Safety
+///
+/// `attributes` must be null or valid for reads, and its strings must be null or NUL-terminated.
+/// `buffer` must be null or valid for writes of `buffer_len` bytes, `written` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn up_attributes_serialize(
+    attributes: *const UpAttributes,
+    buffer: *mut u8,
+    buffer_len: usize,
+    written: *mut usize,
+) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        let Some(attributes) = attributes.as_ref() else {
+            return UCode::InvalidArgument as i32;
+        };
+        let attributes = match to_uattributes(attributes) {
+            Ok(attributes) => attributes,
+            Err(code) => return code,
+        };
+        if Validators::get_validator(&attributes)
+            .validate(&attributes)
+            .is_err()
+        {
+            return UCode::InvalidArgument as i32;
+        }
+        write_bytes(&attributes.encode_to_vec(), buffer, buffer_len, written)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ffi::CString;
+    use std::mem::MaybeUninit;
+
+    fn init(message_type: UMessageType) -> UpAttributes {
+        let mut attributes = MaybeUninit::<UpAttributes>::uninit();
+        let code = unsafe {
+            up_attributes_init(
+                message_type as i32,
+                UPriority::UpriorityCs4 as i32,
+                attributes.as_mut_ptr(),
+            )
+        };
+        assert_eq!(code, UCode::Ok as i32);
+        unsafe { attributes.assume_init() }
+    }
+
+    #[test]
+    fn test_serialize_publish_attributes() {
+        let attributes = init(UMessageType::UmessageTypePublish);
+        let mut buffer = [0u8; 64];
+        let mut written = 0usize;
+
+        let code = unsafe {
+            up_attributes_serialize(&attributes, buffer.as_mut_ptr(), buffer.len(), &mut written)
+        };
+
+        assert_eq!(code, UCode::Ok as i32);
+        let decoded = UAttributes::decode(&buffer[..written]).unwrap();
+        assert_eq!(decoded.id, Some(attributes.id.into()));
+        assert_eq!(decoded.r#type(), UMessageType::UmessageTypePublish);
+        assert_eq!(decoded.priority(), UPriority::UpriorityCs4);
+        assert!(decoded.sink.is_none());
+        assert!(decoded.ttl.is_none());
+    }
+
+    #[test]
+    fn test_serialize_request_attributes() {
+        let sink = CString::new("//vcu.vin/body.access/1/rpc.response").unwrap();
+        let token = CString::new("secret").unwrap();
+        let mut attributes = init(UMessageType::UmessageTypeRequest);
+        attributes.sink = sink.as_ptr();
+        attributes.ttl = 1000;
+        attributes.token = token.as_ptr();
+        let mut buffer = [0u8; 128];
+        let mut written = 0usize;
+
+        let code = unsafe {
+            up_attributes_serialize(&attributes, buffer.as_mut_ptr(), buffer.len(), &mut written)
+        };
+
+        assert_eq!(code, UCode::Ok as i32);
+        let decoded = UAttributes::decode(&buffer[..written]).unwrap();
+        assert_eq!(
+            LongUriSerializer::serialize(&decoded.sink.unwrap()).unwrap(),
+            "//vcu.vin/body.access/1/rpc.response"
+        );
+        assert_eq!(decoded.ttl, Some(1000));
+        assert_eq!(decoded.token, Some("secret".to_string()));
+    }
+
+    #[test]
+    fn test_validate_fails_for_request_without_sink() {
+        let mut attributes = init(UMessageType::UmessageTypeRequest);
+        attributes.ttl = 1000;
+
+        let code = unsafe { up_attributes_validate(&attributes) };
+
+        assert_eq!(code, UCode::InvalidArgument as i32);
+    }
+
+    #[test]
+    fn test_init_fails_for_unknown_message_type() {
+        let mut attributes = MaybeUninit::<UpAttributes>::uninit();
+
+        let code = unsafe {
+            up_attributes_init(42, UPriority::UpriorityCs4 as i32, attributes.as_mut_ptr())
+        };
+
+        assert_eq!(code, UCode::InvalidArgument as i32);
+    }
+}
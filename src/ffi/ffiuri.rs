@@ -0,0 +1,296 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::ffi::c_char;
+
+use prost::Message;
+
+use crate::ffi::ffibuffer::{catch_panic, read_bytes, read_str, write_bytes, write_str};
+use crate::uprotocol::{UCode, UUri};
+use crate::uri::serializer::{LongUriSerializer, MicroUriSerializer, UriSerializer};
+use crate::uri::validator::UriValidator;
+
+// URIs are exchanged with C code in their protobuf encoding, which the C side can handle by
+// means of any protobuf library, e.g. nanopb.
+unsafe fn read_uri(data: *const u8, len: usize) -> Result<UUri, i32> {
+    let data = read_bytes(data, len)?;
+    UUri::decode(data).map_err(|_| UCode::InvalidArgument as i32)
+}
+
+/// Parses a URI from its long form and writes its protobuf encoding into a buffer.
+///
+/// # Returns
+///
+/// `UCode::Ok`, or `UCode::OutOfRange` if the buffer is too small. In both cases, `written` is
+/// set to the length of the encoded URI, if not null. `UCode::InvalidArgument` is returned if the
+/// string is no valid long form URI.
+///
+/// # Safety
+///
+/// `uri` must be null or point to a NUL-terminated string, `buffer` must be null or valid for writes
+/// of `buffer_len` bytes, `written` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_from_long(
+    uri: *const c_char,
+    buffer: *mut u8,
+    buffer_len: usize,
+    written: *mut usize,
+) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        let uri = match read_str(uri) {
+            Ok(uri) => uri,
+            Err(code) => return code,
+        };
+        match LongUriSerializer::deserialize(uri.to_string()) {
+            Ok(uri) => write_bytes(&uri.encode_to_vec(), buffer, buffer_len, written),
+            Err(_) => UCode::InvalidArgument as i32,
+        }
+    })
+}
+
+/// Writes the long form of a protobuf encoded URI into a buffer, as a NUL-terminated string.
+///
+/// # Returns
+///
+/// `UCode::Ok`, or `UCode::OutOfRange` if the buffer is too small. In both cases, `written` is
+/// set to the size of the string including its terminating NUL, if not null. `UCode::InvalidArgument`
+/// is returned if the data is no URI or the URI cannot be represented in long form.
+///
+/// # Safety
+///
+/// `uri` must be null or valid for reads of `uri_len` bytes, `buffer` must be null or valid for writes
+/// of `buffer_len` bytes, `written` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_to_long(
+    uri: *const u8,
+    uri_len: usize,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    written: *mut usize,
+) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        let uri = match read_uri(uri, uri_len) {
+            Ok(uri) => uri,
+            Err(code) => return code,
+        };
+        match LongUriSerializer::serialize(&uri) {
+            Ok(long_uri) => write_str(&long_uri, buffer, buffer_len, written),
+            Err(_) => UCode::InvalidArgument as i32,
+        }
+    })
+}
+
+/// Parses a URI from its micro form and writes its protobuf encoding into a buffer.
+///
+/// # Returns
+///
+/// `UCode::Ok`, or `UCode::OutOfRange` if the buffer is too small. In both cases, `written` is
+/// set to the length of the encoded URI, if not null. `UCode::InvalidArgument` is returned if the
+/// data is no valid micro form URI.
+///
+/// # Safety
+///
+/// `uri` must be null or valid for reads of `uri_len` bytes, `buffer` must be null or valid for writes
+/// of `buffer_len` bytes, `written` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_from_micro(
+    uri: *const u8,
+    uri_len: usize,
+    buffer: *mut u8,
+    buffer_len: usize,
+    written: *mut usize,
+) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        let micro_uri = match read_bytes(uri, uri_len) {
+            Ok(micro_uri) => micro_uri,
+            Err(code) => return code,
+        };
+        match MicroUriSerializer::deserialize(micro_uri.to_vec()) {
+            Ok(uri) => write_bytes(&uri.encode_to_vec(), buffer, buffer_len, written),
+            Err(_) => UCode::InvalidArgument as i32,
+        }
+    })
+}
+
+/// Writes the micro form of a protobuf encoded URI into a buffer.
+///
+/// # Returns
+///
+/// `UCode::Ok`, or `UCode::OutOfRange` if the buffer is too small. In both cases, `written` is
+/// set to the length of the micro form, if not null. `UCode::InvalidArgument` is returned if the
+/// data is no URI or the URI cannot be represented in micro form.
+///
+/// # Safety
+///
+/// `uri` must be null or valid for reads of `uri_len` bytes, `buffer` must be null or valid for writes
+/// of `buffer_len` bytes, `written` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_to_micro(
+    uri: *const u8,
+    uri_len: usize,
+    buffer: *mut u8,
+    buffer_len: usize,
+    written: *mut usize,
+) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        let uri = match read_uri(uri, uri_len) {
+            Ok(uri) => uri,
+            Err(code) => return code,
+        };
+        match MicroUriSerializer::serialize(&uri) {
+            Ok(micro_uri) => write_bytes(&micro_uri, buffer, buffer_len, written),
+            Err(_) => UCode::InvalidArgument as i32,
+        }
+    })
+}
+
+/// Validates a protobuf encoded URI, as done by [`UriValidator::validate`].
+///
+/// # Returns
+///
+/// `UCode::Ok` if the URI is valid, `UCode::InvalidArgument` otherwise.
+///
+/// # Safety
+///
+/// `uri` must be null or valid for reads of `uri_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn up_uri_validate(uri: *const u8, uri_len: usize) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        match read_uri(uri, uri_len).map(|uri| UriValidator::validate(&uri)) {
+            Ok(Ok(())) => UCode::Ok as i32,
+            Ok(Err(_)) => UCode::InvalidArgument as i32,
+            Err(code) => code,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ffi::CString;
+
+    use crate::uprotocol::{UEntity, UResource};
+
+    fn encode(uri: &UUri) -> Vec<u8> {
+        uri.encode_to_vec()
+    }
+
+    #[test]
+    fn test_long_uri_round_trip() {
+        let long_uri = CString::new("/body.access/1/door.front_left#Door").unwrap();
+        let mut encoded = [0u8; 64];
+        let mut encoded_len = 0usize;
+
+        let code = unsafe {
+            up_uri_from_long(
+                long_uri.as_ptr(),
+                encoded.as_mut_ptr(),
+                encoded.len(),
+                &mut encoded_len,
+            )
+        };
+        assert_eq!(code, UCode::Ok as i32);
+        assert_eq!(
+            unsafe { up_uri_validate(encoded.as_ptr(), encoded_len) },
+            UCode::Ok as i32
+        );
+
+        let mut buffer = [0 as c_char; 64];
+        let mut written = 0usize;
+        let code = unsafe {
+            up_uri_to_long(
+                encoded.as_ptr(),
+                encoded_len,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut written,
+            )
+        };
+        assert_eq!(code, UCode::Ok as i32);
+        assert_eq!(written, long_uri.as_bytes_with_nul().len());
+        let result = unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) };
+        assert_eq!(result, long_uri.as_c_str());
+    }
+
+    #[test]
+    fn test_micro_uri_round_trip() {
+        let uri = UUri {
+            entity: Some(UEntity {
+                id: Some(29999),
+                version_major: Some(254),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                id: Some(19999),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let encoded = encode(&uri);
+        let mut micro_uri = [0u8; 8];
+        let mut micro_uri_len = 0usize;
+
+        let code = unsafe {
+            up_uri_to_micro(
+                encoded.as_ptr(),
+                encoded.len(),
+                micro_uri.as_mut_ptr(),
+                micro_uri.len(),
+                &mut micro_uri_len,
+            )
+        };
+        assert_eq!(code, UCode::Ok as i32);
+        assert_eq!(micro_uri_len, 8);
+
+        let mut buffer = [0u8; 64];
+        let mut written = 0usize;
+        let code = unsafe {
+            up_uri_from_micro(
+                micro_uri.as_ptr(),
+                micro_uri_len,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut written,
+            )
+        };
+        assert_eq!(code, UCode::Ok as i32);
+        assert_eq!(UUri::decode(&buffer[..written]).unwrap(), uri);
+    }
+
+    #[test]
+    fn test_invalid_uris_are_rejected() {
+        let long_uri = CString::new("").unwrap();
+        let mut buffer = [0u8; 64];
+
+        let code = unsafe {
+            up_uri_from_long(
+                long_uri.as_ptr(),
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                std::ptr::null_mut(),
+            )
+        };
+        assert_eq!(code, UCode::InvalidArgument as i32);
+
+        let encoded = encode(&UUri::default());
+        assert_eq!(
+            unsafe { up_uri_validate(encoded.as_ptr(), encoded.len()) },
+            UCode::InvalidArgument as i32
+        );
+        assert_eq!(
+            unsafe { up_uri_validate(std::ptr::null(), 0) },
+            UCode::InvalidArgument as i32
+        );
+    }
+}
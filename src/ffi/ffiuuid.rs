@@ -0,0 +1,246 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::ffi::c_char;
+use std::str::FromStr;
+
+use once_cell::sync::OnceCell;
+
+use crate::ffi::ffibuffer::{catch_panic, read_str, write_str};
+use crate::uprotocol::{UCode, Uuid};
+use crate::uuid::builder::UUIDv8Builder;
+
+/// A UUID as seen from C, made up of its most and least significant 64 bits.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UpUuid {
+    pub msb: u64,
+    pub lsb: u64,
+}
+
+impl UpUuid {
+    pub(crate) fn is_nil(&self) -> bool {
+        self.msb == 0 && self.lsb == 0
+    }
+}
+
+impl From<Uuid> for UpUuid {
+    fn from(value: Uuid) -> Self {
+        UpUuid {
+            msb: value.msb,
+            lsb: value.lsb,
+        }
+    }
+}
+
+impl From<UpUuid> for Uuid {
+    fn from(value: UpUuid) -> Self {
+        Uuid {
+            msb: value.msb,
+            lsb: value.lsb,
+        }
+    }
+}
+
+pub(crate) fn uuid_builder() -> &'static UUIDv8Builder {
+    static BUILDER: OnceCell<UUIDv8Builder> = OnceCell::new();
+    BUILDER.get_or_init(UUIDv8Builder::new)
+}
+
+/// Creates a new uProtocol UUID.
+///
+/// All UUIDs created by means of this function are monotonic, as they share the same
+/// [`UUIDv8Builder`].
+///
+/// # Returns
+///
+/// `UCode::Ok`, or `UCode::InvalidArgument` if `uuid` is null.
+///
+/// # Safety
+///
+/// `uuid` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn up_uuid_create(uuid: *mut UpUuid) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        if uuid.is_null() {
+            return UCode::InvalidArgument as i32;
+        }
+        *uuid = uuid_builder().build().into();
+        UCode::Ok as i32
+    })
+}
+
+/// Checks if a UUID is a valid uProtocol UUID.
+///
+/// # Returns
+///
+/// `true` if the UUID is a valid uProtocol UUID, `false` if it is not or if `uuid` is null.
+///
+/// # Safety
+///
+/// `uuid` must be null or valid for reads.
+#[no_mangle]
+pub unsafe extern "C" fn up_uuid_is_uprotocol(uuid: *const UpUuid) -> bool {
+    catch_panic(false, || {
+        uuid.as_ref()
+            .map_or(false, |uuid| Uuid::from(*uuid).is_uprotocol_uuid())
+    })
+}
+
+/// Gets the point in time that a uProtocol UUID has been created at.
+///
+/// # Returns
+///
+/// `UCode::Ok` if `time` has been set to the number of milliseconds since UNIX Epoch,
+/// `UCode::InvalidArgument` if `uuid` is no uProtocol UUID or one of the pointers is null.
+///
+/// # Safety
+///
+/// `uuid` must be null or valid for reads, `time` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn up_uuid_get_time(uuid: *const UpUuid, time: *mut u64) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        let (Some(uuid), false) = (uuid.as_ref(), time.is_null()) else {
+            return UCode::InvalidArgument as i32;
+        };
+        match Uuid::from(*uuid).get_time() {
+            Some(instant) => {
+                *time = instant;
+                UCode::Ok as i32
+            }
+            None => UCode::InvalidArgument as i32,
+        }
+    })
+}
+
+/// Writes the hyphenated string representation of a UUID into a buffer, as a NUL-terminated string.
+///
+/// # Returns
+///
+/// `UCode::Ok`, or `UCode::OutOfRange` if the buffer is too small to hold the string. In both cases,
+/// `written` is set to the size of the string including its terminating NUL, if not null.
+/// `UCode::InvalidArgument` is returned if `uuid` is null.
+///
+/// # Safety
+///
+/// `uuid` must be null or valid for reads, `buffer` must be null or valid for writes of `buffer_len` bytes,
+/// `written` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn up_uuid_to_string(
+    uuid: *const UpUuid,
+    buffer: *mut c_char,
+    buffer_len: usize,
+    written: *mut usize,
+) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        let Some(uuid) = uuid.as_ref() else {
+            return UCode::InvalidArgument as i32;
+        };
+        write_str(
+            &Uuid::from(*uuid).to_hyphenated_string(),
+            buffer,
+            buffer_len,
+            written,
+        )
+    })
+}
+
+/// Parses a UUID from its hyphenated string representation.
+///
+/// # Returns
+///
+/// `UCode::Ok`, or `UCode::InvalidArgument` if the string is no valid UUID or one of the pointers is null.
+///
+/// # Safety
+///
+/// `value` must be null or point to a NUL-terminated string, `uuid` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn up_uuid_from_string(value: *const c_char, uuid: *mut UpUuid) -> i32 {
+    catch_panic(UCode::Internal as i32, || {
+        if uuid.is_null() {
+            return UCode::InvalidArgument as i32;
+        }
+        let parsed = read_str(value)
+            .and_then(|value| Uuid::from_str(value).map_err(|_| UCode::InvalidArgument as i32));
+        match parsed {
+            Ok(parsed) => {
+                *uuid = parsed.into();
+                UCode::Ok as i32
+            }
+            Err(code) => code,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_uuid() {
+        let mut uuid = UpUuid::default();
+
+        let code = unsafe { up_uuid_create(&mut uuid) };
+
+        assert_eq!(code, UCode::Ok as i32);
+        assert!(unsafe { up_uuid_is_uprotocol(&uuid) });
+        let mut time = 0u64;
+        assert_eq!(
+            unsafe { up_uuid_get_time(&uuid, &mut time) },
+            UCode::Ok as i32
+        );
+        assert!(time > 0);
+    }
+
+    #[test]
+    fn test_uuid_string_round_trip() {
+        let uuid = UpUuid {
+            msb: 0x018C_6844_68F8_8000,
+            lsb: 0x8123_4567_89AB_CDEF,
+        };
+        let mut buffer = [0 as c_char; 37];
+        let mut written = 0usize;
+
+        let code =
+            unsafe { up_uuid_to_string(&uuid, buffer.as_mut_ptr(), buffer.len(), &mut written) };
+        assert_eq!(code, UCode::Ok as i32);
+        assert_eq!(written, 37);
+
+        let mut parsed = UpUuid::default();
+        let code = unsafe { up_uuid_from_string(buffer.as_ptr(), &mut parsed) };
+        assert_eq!(code, UCode::Ok as i32);
+        assert_eq!(parsed, uuid);
+    }
+
+    #[test]
+    fn test_uuid_to_string_fails_for_small_buffer() {
+        let uuid = UpUuid { msb: 1, lsb: 2 };
+        let mut buffer = [0 as c_char; 36];
+        let mut written = 0usize;
+
+        let code =
+            unsafe { up_uuid_to_string(&uuid, buffer.as_mut_ptr(), buffer.len(), &mut written) };
+
+        assert_eq!(code, UCode::OutOfRange as i32);
+        assert_eq!(written, 37);
+    }
+
+    #[test]
+    fn test_null_pointers_are_rejected() {
+        assert_eq!(
+            unsafe { up_uuid_create(std::ptr::null_mut()) },
+            UCode::InvalidArgument as i32
+        );
+        assert!(!unsafe { up_uuid_is_uprotocol(std::ptr::null()) });
+    }
+}
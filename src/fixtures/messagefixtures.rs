@@ -0,0 +1,182 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+#[cfg(feature = "cloudevents")]
+use cloudevents::Event as CloudEvent;
+
+#[cfg(feature = "cloudevents")]
+use crate::cloudevent::builder::UCloudEventBuilder;
+#[cfg(feature = "cloudevents")]
+use crate::cloudevent::datamodel::UCloudEventAttributesBuilder;
+use crate::fixtures::{rpc_method, rpc_response};
+use crate::transport::builder::{UAttributesBuilder, UPayloadBuilder};
+use crate::uprotocol::{UAttributes, UMessage, UPayload, UPriority, UUri, Uuid};
+#[cfg(feature = "cloudevents")]
+use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+
+/// The size of a small payload, like a signal value.
+pub const SMALL_PAYLOAD_SIZE: usize = 16;
+/// The size of a medium payload, like a structured status message.
+pub const MEDIUM_PAYLOAD_SIZE: usize = 1024;
+/// The size of a large payload, like a camera frame chunk or a diagnostic dump.
+pub const LARGE_PAYLOAD_SIZE: usize = 64 * 1024;
+
+/// The time-to-live of the request fixtures, in milliseconds.
+pub const FIXTURE_TTL: u32 = 1000;
+
+/// Gets the attributes of a publish message.
+pub fn publish_attributes() -> UAttributes {
+    UAttributesBuilder::publish(UPriority::UpriorityCs1).build()
+}
+
+/// Gets the attributes of a request to the fixtures' RPC method, with a time-to-live and a token.
+pub fn request_attributes() -> UAttributes {
+    UAttributesBuilder::request(UPriority::UpriorityCs4, rpc_method(), FIXTURE_TTL)
+        .with_token("fixture-token")
+        .build()
+}
+
+/// Gets the attributes of a response to a request.
+///
+/// # Arguments
+///
+/// * `reqid` - The ID of the request, e.g. of attributes from [`request_attributes`].
+pub fn response_attributes(reqid: Uuid) -> UAttributes {
+    UAttributesBuilder::response(UPriority::UpriorityCs4, rpc_response(), reqid).build()
+}
+
+/// Gets payload data of a given size.
+///
+/// The data is a repeating byte pattern rather than all zeros, so that it does not compress or encode any better
+/// than real data would.
+///
+/// # Arguments
+///
+/// * `size` - The size of the data in bytes, e.g. [`SMALL_PAYLOAD_SIZE`].
+pub fn payload_data(size: usize) -> Vec<u8> {
+    (0..size).map(|index| (index % 251) as u8).collect()
+}
+
+/// Gets a raw payload with data from [`payload_data`].
+///
+/// # Arguments
+///
+/// * `size` - The size of the payload data in bytes, e.g. [`SMALL_PAYLOAD_SIZE`].
+///
+/// # Panics
+///
+/// - if the size exceeds the maximum length of a `UPayload`.
+pub fn payload(size: usize) -> UPayload {
+    UPayloadBuilder::from_raw(payload_data(size))
+        .build()
+        .expect("payload size needs to fit into UPayload")
+}
+
+/// Gets a publish message to a topic.
+///
+/// # Arguments
+///
+/// * `topic` - The topic that the message is published to, e.g. [`resolved_topic`](crate::fixtures::resolved_topic).
+/// * `size` - The size of the payload data in bytes.
+pub fn publish_message(topic: UUri, size: usize) -> UMessage {
+    UMessage {
+        source: Some(topic),
+        attributes: Some(publish_attributes()),
+        payload: Some(payload(size)),
+    }
+}
+
+/// Gets a request message to the fixtures' RPC method.
+///
+/// # Arguments
+///
+/// * `size` - The size of the payload data in bytes.
+pub fn request_message(size: usize) -> UMessage {
+    UMessage {
+        source: Some(rpc_response()),
+        attributes: Some(request_attributes()),
+        payload: Some(payload(size)),
+    }
+}
+
+/// Gets a `CloudEvent` that publishes to a topic.
+///
+/// # Arguments
+///
+/// * `topic` - The topic that the event is published to, which needs to have a long form.
+/// * `size` - The size of the payload data in bytes.
+///
+/// # Panics
+///
+/// - if the topic cannot be serialized to its long form.
+#[cfg(feature = "cloudevents")]
+pub fn publish_cloud_event(topic: &UUri, size: usize) -> CloudEvent {
+    let source = LongUriSerializer::serialize(topic).expect("topic needs to have a long form");
+    let payload = prost_types::Any {
+        type_url: "type.googleapis.com/example.Door".to_string(),
+        value: payload_data(size),
+    };
+    let attributes = UCloudEventAttributesBuilder::new()
+        .with_priority(UPriority::UpriorityCs1)
+        .with_ttl(FIXTURE_TTL)
+        .build();
+    UCloudEventBuilder::publish(&source, &payload, &attributes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fixtures::{local_topic, resolved_topic};
+    use crate::transport::validator::{UAttributesValidator, Validators};
+    use crate::uprotocol::Data;
+
+    #[test]
+    fn test_attributes_are_valid() {
+        let request = request_attributes();
+        let response = response_attributes(request.id.clone().unwrap());
+        assert_eq!(response.reqid, request.id);
+
+        for attributes in [publish_attributes(), request, response] {
+            assert!(Validators::get_validator(&attributes)
+                .validate(&attributes)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_payload_has_size() {
+        let payload = payload(MEDIUM_PAYLOAD_SIZE);
+        assert_eq!(payload.length, Some(1024));
+        assert_eq!(
+            payload.data,
+            Some(Data::Value(payload_data(MEDIUM_PAYLOAD_SIZE)))
+        );
+    }
+
+    #[test]
+    fn test_messages() {
+        let message = publish_message(resolved_topic(), SMALL_PAYLOAD_SIZE);
+        assert_eq!(message.source, Some(resolved_topic()));
+        assert!(request_message(SMALL_PAYLOAD_SIZE).attributes.is_some());
+    }
+
+    #[cfg(feature = "cloudevents")]
+    #[test]
+    fn test_publish_cloud_event() {
+        use cloudevents::AttributesReader;
+
+        let event = publish_cloud_event(&local_topic(), SMALL_PAYLOAD_SIZE);
+        assert_eq!(event.source(), "/body.access/1/door.front_left#Door");
+    }
+}
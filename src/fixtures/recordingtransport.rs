@@ -0,0 +1,186 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use async_trait::async_trait;
+
+use crate::transport::datamodel::{UListener, UTransport};
+use crate::uprotocol::{UAttributes, UCode, UEntity, UMessage, UPayload, UStatus, UUri};
+
+type SharedListener = Arc<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>;
+
+/// A [`UTransport`] for testing the components that send messages and register listeners by means of a transport,
+/// which records the messages sent and the listeners registered instead of exchanging messages with other uEntities.
+///
+/// Tests check the messages that the component under test has sent with [`RecordingTransport::sent`], and pass
+/// messages to its listeners with [`RecordingTransport::deliver`].
+#[derive(Default)]
+pub struct RecordingTransport {
+    sent: Mutex<Vec<(UUri, UPayload, UAttributes)>>,
+    // the topic, identifier and listener of each registered listener, in the order of registration
+    listeners: Mutex<Vec<(UUri, String, SharedListener)>>,
+    registrations: AtomicUsize,
+}
+
+impl RecordingTransport {
+    /// Creates a transport that has neither sent messages nor registered listeners.
+    pub fn new() -> Self {
+        RecordingTransport::default()
+    }
+
+    /// Gets the messages sent by means of this transport.
+    ///
+    /// # Returns
+    ///
+    /// The topic, payload and attributes of each message, in the order they have been sent in.
+    pub fn sent(&self) -> Vec<(UUri, UPayload, UAttributes)> {
+        self.sent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Gets the topics of the listeners registered with this transport.
+    ///
+    /// # Returns
+    ///
+    /// The topic of each listener, in the order the listeners have been registered in.
+    pub fn topics(&self) -> Vec<UUri> {
+        self.listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(topic, _id, _listener)| topic.clone())
+            .collect()
+    }
+
+    /// Passes a message to the listeners registered for a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic, which the listeners have been registered for.
+    /// * `result` - The message, or the error to pass to the listeners.
+    pub fn deliver(&self, topic: &UUri, result: Result<UMessage, UStatus>) {
+        // the listeners are called without holding the lock, so that they may register or unregister listeners
+        let listeners: Vec<SharedListener> = self
+            .listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|(registered, _id, _listener)| registered == topic)
+            .map(|(_topic, _id, listener)| Arc::clone(listener))
+            .collect();
+        for listener in listeners {
+            listener(result.clone());
+        }
+    }
+}
+
+#[async_trait]
+impl UTransport for RecordingTransport {
+    async fn authenticate(&self, _entity: UEntity) -> Result<(), UStatus> {
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        topic: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+    ) -> Result<(), UStatus> {
+        self.sent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((topic, payload, attributes));
+        Ok(())
+    }
+
+    async fn register_listener(&self, topic: UUri, listener: UListener) -> Result<String, UStatus> {
+        let id = format!(
+            "listener-{}",
+            self.registrations.fetch_add(1, Ordering::SeqCst) + 1
+        );
+        self.listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((topic, id.clone(), Arc::from(listener)));
+        Ok(id)
+    }
+
+    async fn unregister_listener(&self, topic: UUri, listener: &str) -> Result<(), UStatus> {
+        let mut listeners = self
+            .listeners
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let index = listeners
+            .iter()
+            .position(|(registered, id, _listener)| *registered == topic && id == listener)
+            .ok_or_else(|| UStatus::fail_with_code(UCode::NotFound, "No such listener"))?;
+        listeners.remove(index);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fixtures::{local_topic, publish_message};
+
+    #[tokio::test]
+    async fn test_records_sent_messages_and_delivers_to_listeners() {
+        let transport = RecordingTransport::new();
+        let topic = local_topic();
+        let message = publish_message(topic.clone(), 16);
+        transport
+            .send(
+                topic.clone(),
+                message.payload.clone().unwrap(),
+                message.attributes.clone().unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(transport.sent()[0].0, topic);
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        let listener = transport
+            .register_listener(
+                topic.clone(),
+                Box::new(move |result| sink.lock().unwrap().push(result)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(transport.topics(), vec![topic.clone()]);
+
+        transport.deliver(&topic, Ok(message.clone()));
+        transport.deliver(&UUri::default(), Ok(message.clone()));
+        assert_eq!(*received.lock().unwrap(), vec![Ok(message)]);
+
+        transport
+            .unregister_listener(topic.clone(), &listener)
+            .await
+            .unwrap();
+        assert!(transport.topics().is_empty());
+        assert_eq!(
+            transport
+                .unregister_listener(topic, &listener)
+                .await
+                .unwrap_err()
+                .get_code(),
+            UCode::NotFound
+        );
+    }
+}
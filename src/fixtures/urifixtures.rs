@@ -0,0 +1,170 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::uprotocol::uresource::UResourceId;
+use crate::uprotocol::{Remote, UAuthority, UEntity, UResource, UUri};
+use crate::uri::builder::resourcebuilder::UResourceBuilder;
+
+/// The name of the uEntity that the URI fixtures refer to.
+pub const FIXTURE_ENTITY_NAME: &str = "body.access";
+/// The ID of the uEntity that the URI fixtures refer to.
+pub const FIXTURE_ENTITY_ID: u32 = 0x1102;
+/// The name of the remote uAuthority of the URI fixtures.
+pub const FIXTURE_AUTHORITY_NAME: &str = "vcu.my_car_vin";
+
+/// Gets a local topic URI in long form, i.e. with names only, like `/body.access/1/door.front_left#Door`.
+pub fn local_topic() -> UUri {
+    UUri {
+        authority: None,
+        entity: Some(UEntity {
+            name: FIXTURE_ENTITY_NAME.to_string(),
+            version_major: Some(1),
+            ..Default::default()
+        }),
+        resource: Some(UResource {
+            name: "door".to_string(),
+            instance: Some("front_left".to_string()),
+            message: Some("Door".to_string()),
+            id: None,
+        }),
+    }
+}
+
+/// Gets a remote topic URI in long form, like `//vcu.my_car_vin/body.access/1/door.front_left#Door`.
+pub fn remote_topic() -> UUri {
+    UUri {
+        authority: Some(UAuthority {
+            remote: Some(Remote::Name(FIXTURE_AUTHORITY_NAME.to_string())),
+        }),
+        ..local_topic()
+    }
+}
+
+/// Gets a resolved topic URI, i.e. with both names and IDs, whose authority has an IPv4 address.
+pub fn resolved_topic() -> UUri {
+    let mut uri = local_topic();
+    uri.authority = Some(UAuthority {
+        remote: Some(Remote::Ip(vec![192, 168, 1, 100])),
+    });
+    if let Some(entity) = uri.entity.as_mut() {
+        entity.id = Some(FIXTURE_ENTITY_ID);
+    }
+    if let Some(resource) = uri.resource.as_mut() {
+        resource.id = Some(0x8000);
+    }
+    uri
+}
+
+/// Gets a local topic URI in micro form, i.e. with IDs only.
+pub fn micro_topic() -> UUri {
+    UUri {
+        authority: None,
+        entity: Some(UEntity {
+            id: Some(FIXTURE_ENTITY_ID),
+            version_major: Some(1),
+            ..Default::default()
+        }),
+        resource: Some(UResourceBuilder::from_id(0x8000)),
+    }
+}
+
+/// Gets the URI of an RPC method of the fixtures' uEntity, like `/body.access/1/rpc.UpdateDoor`.
+pub fn rpc_method() -> UUri {
+    UUri {
+        authority: None,
+        entity: Some(UEntity {
+            name: FIXTURE_ENTITY_NAME.to_string(),
+            id: Some(FIXTURE_ENTITY_ID),
+            version_major: Some(1),
+            ..Default::default()
+        }),
+        resource: Some(UResourceBuilder::for_rpc_request(
+            Some("UpdateDoor".to_string()),
+            Some(UResourceId::new(1)),
+        )),
+    }
+}
+
+/// Gets the URI that responses of the fixtures' RPC method are sent to, like `/hartley/1/rpc.response`.
+pub fn rpc_response() -> UUri {
+    UUri {
+        authority: None,
+        entity: Some(UEntity {
+            name: "hartley".to_string(),
+            id: Some(0x2001),
+            version_major: Some(1),
+            ..Default::default()
+        }),
+        resource: Some(UResourceBuilder::for_rpc_response()),
+    }
+}
+
+/// Gets a number of distinct topic URIs of the fixtures' uEntity, e.g. for registering many listeners.
+///
+/// The URIs are resolved, with resource IDs counting up from `0x8000` and resource instances named after them.
+pub fn topics(count: usize) -> Vec<UUri> {
+    (0x8000_u32..)
+        .take(count)
+        .map(|id| {
+            let mut uri = resolved_topic();
+            uri.resource = Some(UResource {
+                name: "door".to_string(),
+                instance: Some(format!("door_{id:x}")),
+                message: Some("Door".to_string()),
+                id: Some(id),
+            });
+            uri
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uri::serializer::{LongUriSerializer, MicroUriSerializer, UriSerializer};
+    use crate::uri::validator::UriValidator;
+
+    #[test]
+    fn test_uris_are_valid() {
+        for uri in [
+            local_topic(),
+            remote_topic(),
+            resolved_topic(),
+            rpc_method(),
+            rpc_response(),
+        ] {
+            assert!(UriValidator::validate(&uri).is_ok(), "{uri:?}");
+        }
+        assert!(UriValidator::is_micro_form(&resolved_topic()));
+        assert!(UriValidator::is_micro_form(&micro_topic()));
+    }
+
+    #[test]
+    fn test_uris_serialize() {
+        assert_eq!(
+            LongUriSerializer::serialize(&remote_topic()).unwrap(),
+            "//vcu.my_car_vin/body.access/1/door.front_left#Door"
+        );
+        assert!(MicroUriSerializer::serialize(&resolved_topic()).is_ok());
+        assert!(MicroUriSerializer::serialize(&micro_topic()).is_ok());
+    }
+
+    #[test]
+    fn test_topics_are_distinct() {
+        let topics = topics(3);
+        assert_eq!(topics.len(), 3);
+        assert_ne!(topics[0], topics[1]);
+        assert_ne!(topics[1], topics[2]);
+    }
+}
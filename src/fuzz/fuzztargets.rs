@@ -0,0 +1,152 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use prost::Message;
+
+use crate::transport::validator::{UAttributesValidator, UMessageValidator, Validators};
+use crate::uprotocol::{UAttributes, UMessage, UPayload, UUri};
+use crate::uri::serializer::{LongUriSerializer, MicroUriSerializer, UUriRef, UriSerializer};
+use crate::uri::validator::UriValidator;
+
+/// Parses a URI in micro form, like the [`MicroUriSerializer`] and [`UUriRef::from_micro_form`] do.
+///
+/// # Panics
+///
+/// - if the `UUri` that the `MicroUriSerializer` deserializes differs from the one that `UUriRef` refers to.
+pub fn fuzz_micro_uri(data: &[u8]) {
+    let uri = MicroUriSerializer::deserialize(data.to_vec());
+    let uri_ref = UUriRef::from_micro_form(data);
+    assert_eq!(
+        uri.as_ref().ok(),
+        uri_ref.map(|uri_ref| uri_ref.to_uri()).ok().as_ref()
+    );
+    if let Ok(uri) = uri {
+        process_uri(&uri);
+    }
+}
+
+/// Parses a URI in long form, like the [`LongUriSerializer`] and [`UUriRef::from_long_form`] do.
+///
+/// # Panics
+///
+/// - if the `UUri` that the `LongUriSerializer` deserializes differs from the one that `UUriRef` refers to.
+pub fn fuzz_long_uri(data: &str) {
+    let uri = LongUriSerializer::deserialize(data.to_string());
+    let uri_ref = UUriRef::from_long_form(data);
+    assert_eq!(
+        uri.as_ref().ok(),
+        uri_ref.map(|uri_ref| uri_ref.to_uri()).ok().as_ref()
+    );
+    if let Ok(uri) = uri {
+        process_uri(&uri);
+    }
+}
+
+/// Decodes a protobuf `UMessage`, and validates and converts its source, attributes and payload.
+///
+/// # Panics
+///
+/// - if one of the URIs of the message makes [`fuzz_micro_uri`] or [`fuzz_long_uri`] panic.
+pub fn fuzz_umessage(data: &[u8]) {
+    let Ok(message) = UMessage::decode(data) else {
+        return;
+    };
+    let _ = UMessageValidator::report(&message);
+    if let Some(source) = &message.source {
+        process_uri(source);
+    }
+    if let Some(attributes) = &message.attributes {
+        process_attributes(attributes);
+    }
+    if let Some(payload) = message.payload {
+        process_payload(payload);
+    }
+}
+
+// Serializes a URI to both forms, and parses the results again
+fn process_uri(uri: &UUri) {
+    let _ = UriValidator::report(uri);
+    if let Ok(micro_form) = MicroUriSerializer::serialize(uri) {
+        let _ = UUriRef::from_micro_form(&micro_form);
+    }
+    if let Ok(long_form) = LongUriSerializer::serialize(uri) {
+        let _ = UUriRef::from_long_form(&long_form);
+    }
+}
+
+fn process_attributes(attributes: &UAttributes) {
+    let _ = Validators::get_validator(attributes).report(attributes);
+    for uuid in [&attributes.id, &attributes.reqid].into_iter().flatten() {
+        let _ = uuid.get_time();
+        let _ = uuid.to_hyphenated_string();
+    }
+    if let Some(sink) = &attributes.sink {
+        process_uri(sink);
+    }
+}
+
+fn process_payload(payload: UPayload) {
+    let _ = payload.as_str();
+    let _ = prost_types::Any::try_from(payload);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fixtures;
+
+    #[test]
+    fn test_fuzz_micro_uri() {
+        for data in [
+            &[][..],
+            &[0x1],
+            // ID-type URIs without or with a too short ID
+            &[0x1, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0],
+            &[0x1, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x3, 0x1],
+            &[0x1, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0],
+            &[0x1, 0x1, 0x80, 0x0, 0x11, 0x2, 0x1, 0x0, 192, 168, 1, 100],
+            &[0x1, 0x4, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0],
+        ] {
+            fuzz_micro_uri(data);
+        }
+    }
+
+    #[test]
+    fn test_fuzz_long_uri() {
+        for data in [
+            "",
+            "/",
+            "//",
+            "///",
+            "up:",
+            "\\\\vcu\\body.access",
+            "//vcu.my_car_vin/body.access/1/door.front_left#Door",
+            "/body.access/x",
+            "/body.access/1/rpc.#.#",
+            "/ü/1/ä.ö#ß",
+        ] {
+            fuzz_long_uri(data);
+        }
+    }
+
+    #[test]
+    fn test_fuzz_umessage() {
+        let message = fixtures::request_message(fixtures::SMALL_PAYLOAD_SIZE).encode_to_vec();
+        fuzz_umessage(&message);
+        for length in 0..message.len() {
+            fuzz_umessage(&message[..length]);
+        }
+        fuzz_umessage(&[0xff; 16]);
+    }
+}
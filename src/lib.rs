@@ -23,21 +23,93 @@
 //!
 //! ## This crate includes:
 //!
+//! - the [`build`] module, generating the uProtocol identifiers of services from their proto definitions in build scripts
 //! - the [`cloudevent`] module that offers a common way to represent uProtocol messages using the `CloudEvent` data model
+//! - the `conformance` module, with the test vectors shared by the uProtocol SDKs and checks of this crate's and
+//!   transports' implementations against them, if the `test-util` feature is enabled
+//! - the [`coreservices`] module with the reserved identifiers and method URIs of the uProtocol core services
+//! - the [`ffi`] module, exposing URI serialization, UUID generation and `UAttributes` construction to C code
+//! - the [`fixtures`] module, generating representative URIs, attributes and payloads for tests and benchmarks, and
+//!   providing a `RecordingTransport` for tests if the `test-util` feature is enabled
+//! - the `fuzz` module with entry points for fuzzing the deserializers of the crate, if the `fuzzing` feature is enabled
+//! - the [`policy`] module, authenticating the senders of requests, deciding which uEntities may send which
+//!   messages to which other uEntities, and auditing these decisions
+//! - the [`prelude`] module, re-exporting the most commonly used traits and types for a single glob import
+//! - the [`pubsub`] module which offers uP-L2 building blocks for publishing and subscribing to topics, for
+//!   caching the last message of topics, for detecting lost messages by their sequence numbers, and for publishing
+//!   the heartbeats of uEntities
 //! - the [`rpc`] module which offers wrappers for dealing with uProtocol payload in the context of RPC method invokation
+//! - the [`someip`] module, providing a codec for the SOME/IP TLV payload format
+//! - the [`state`] module with a key-value store for the state of stateful components, kept in memory or, if the
+//!   `file-store` feature is enabled, in a file
+//! - the `strategies` module with proptest strategies for valid and invalid URIs, attributes and payloads, if the
+//!   `proptest` feature is enabled
+//! - the [`streamer`] module with building blocks for forwarding messages between transports, as done by a uStreamer
 //! - the [`transport`] module as a set of abstractions for various transport-level concerns like status representation and serialization
+//! - the [`udiscovery`] module with a client for the uDiscovery core service, and a resolver caching its results
 //! - the [`uri`] module, providing convenience wrappers for creation and validation of uProtocol-style resource identifiers
+//! - the [`usubscription`] module with a client for the uSubscription core service, and helpers for implementing it
+//! - the [`utwin`] module with a client for the uTwin core service
 //! - the [`uuid`] module which generates and validates UUIDs as per the uProtocol specification
 //!
+//! Failures of the higher-level APIs, e.g. in the [`pubsub`] module and the clients of the core services,
+//! are reported as [`UProtocolError`]. The lower-level APIs, like the serializers, the validators and the
+//! `RpcMapper`, keep reporting their own error types, which all convert into [`UProtocolError`], so that the `?`
+//! operator can be used across layers.
+//!
 //! ## References
 //! - [Eclipse-uProtocol Specification](https://github.com/eclipse-uprotocol/uprotocol-spec/tree/main)
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+// Declares the modules of the encode and decode paths, in which Clippy denies panicking with the `no-panic` feature.
+macro_rules! no_panic {
+    ($($module:item)*) => {
+        $(
+            #[cfg_attr(
+                all(feature = "no-panic", not(test)),
+                deny(
+                    clippy::unwrap_used,
+                    clippy::expect_used,
+                    clippy::panic,
+                    clippy::indexing_slicing
+                )
+            )]
+            $module
+        )*
+    };
+}
+
 mod types {
     pub mod serializationerror;
+    #[cfg(feature = "std")]
+    pub mod uprotocolerror;
     pub mod validationerror;
+    pub mod validationreport;
 }
 
+#[cfg(feature = "std")]
+pub use types::uprotocolerror::UProtocolError;
+
+#[cfg(feature = "build")]
+pub mod build {
+    mod protocompiler;
+    mod uprotocoloptions;
+
+    pub use protocompiler::*;
+    pub use uprotocoloptions::{UServiceDefinition, UServiceTopic};
+}
+
+#[cfg(feature = "cloudevents")]
 pub mod cloudevent {
+    #[cfg(feature = "http")]
+    pub mod binding {
+        mod httpbinding;
+
+        pub use httpbinding::*;
+    }
     pub mod builder {
         mod ucloudeventbuilder;
         mod ucloudeventutils;
@@ -50,81 +122,465 @@ pub mod cloudevent {
 
         pub use ucloudeventattributes::*;
     }
-    pub mod serializer {
-        mod cloudeventjsonserializer;
-        mod cloudeventprotobufserializer;
-        mod cloudeventserializer;
+    no_panic! {
+        pub mod serializer {
+            mod cloudeventjsonserializer;
+            mod cloudeventprotobufserializer;
+            mod cloudeventserializer;
+            mod contentmode;
 
-        pub use crate::types::serializationerror::*;
-        pub use cloudeventjsonserializer::*;
-        pub use cloudeventprotobufserializer::*;
-        pub use cloudeventserializer::*;
+            pub use crate::types::serializationerror::*;
+            pub use cloudeventjsonserializer::*;
+            pub use cloudeventprotobufserializer::*;
+            pub use cloudeventserializer::*;
+            pub use contentmode::*;
+        }
     }
     pub mod validator {
         mod cloudeventvalidator;
 
         pub use crate::types::validationerror::*;
+        pub use crate::types::validationreport::*;
         pub use cloudeventvalidator::*;
     }
 }
 
+pub mod coreservices {
+    mod udiscoveryservice;
+    mod usubscriptionservice;
+    mod utwinservice;
+
+    pub use udiscoveryservice::*;
+    pub use usubscriptionservice::*;
+    pub use utwinservice::*;
+}
+
+#[cfg(feature = "ffi")]
+pub mod ffi {
+    mod ffibuffer;
+    mod ffiuattributes;
+    mod ffiuri;
+    mod ffiuuid;
+
+    pub use ffiuattributes::*;
+    pub use ffiuri::*;
+    pub use ffiuuid::*;
+}
+
+#[cfg(feature = "std")]
+pub mod fixtures {
+    mod messagefixtures;
+    #[cfg(any(test, feature = "test-util"))]
+    mod recordingtransport;
+    mod urifixtures;
+
+    pub use messagefixtures::*;
+    #[cfg(any(test, feature = "test-util"))]
+    pub use recordingtransport::*;
+    pub use urifixtures::*;
+}
+
+#[cfg(all(feature = "std", any(test, feature = "test-util")))]
+pub mod conformance {
+    mod conformancechecks;
+    mod testvectors;
+
+    pub use conformancechecks::*;
+    pub use testvectors::*;
+}
+
+/// Entry points for fuzzing the deserializers of the crate, e.g. from `cargo fuzz` targets like
+///
+/// ```ignore
+/// fuzz_target!(|data: &[u8]| uprotocol_sdk::fuzz::fuzz_micro_uri(data));
+/// ```
+///
+/// Each function parses its input with every parser of the input's format, and processes whatever the parsers
+/// return like a uEntity would, so that all parse paths are exercised. The functions do not panic for any input,
+/// unless they find a bug, e.g. the owned and the borrowing parsers of URIs disagreeing.
+#[cfg(all(feature = "std", any(test, feature = "fuzzing")))]
+pub mod fuzz {
+    mod fuzztargets;
+
+    pub use fuzztargets::*;
+}
+
+/// The most commonly used traits and types of the crate, to be imported at once by means of
+/// `use uprotocol_sdk::prelude::*;`.
+pub mod prelude {
+    #[cfg(feature = "std")]
+    pub use crate::pubsub::{Publisher, Subscriber};
+    #[cfg(feature = "std")]
+    pub use crate::rpc::{CallOptions, RpcClient, RpcClientResult, RpcMapper};
+    #[cfg(feature = "std")]
+    pub use crate::transport::builder::{
+        EntityProfile, UAttributesBuilder, UMessageBuilder, UPayloadBuilder,
+    };
+    #[cfg(feature = "std")]
+    pub use crate::transport::datamodel::{UListener, UTransport};
+    #[cfg(feature = "std")]
+    pub use crate::transport::validator::{UAttributesValidator, Validated, Validators};
+    pub use crate::uprotocol::uentity::{UEntityId, VersionMajor};
+    pub use crate::uprotocol::uresource::UResourceId;
+    pub use crate::uprotocol::{
+        UAttributes, UAuthority, UCode, UEntity, UMessage, UMessageType, UPayload, UPayloadFormat,
+        UPriority, UResource, UStatus, UUri, Uuid,
+    };
+    pub use crate::uri::builder::resourcebuilder::UResourceBuilder;
+    #[cfg(feature = "std")]
+    pub use crate::uri::serializer::LongUriSerializer;
+    pub use crate::uri::serializer::{MicroUriSerializer, UUriRef, UriSerializer};
+    pub use crate::uri::validator::UriValidator;
+    #[cfg(feature = "std")]
+    pub use crate::uuid::builder::{UClock, URng, UUIDv8Builder};
+    #[cfg(feature = "std")]
+    pub use crate::UProtocolError;
+}
+
+#[cfg(feature = "std")]
+pub mod policy {
+    mod auditsink;
+    mod authenticator;
+    #[cfg(feature = "jwt")]
+    mod jwtauthenticator;
+    mod policyengine;
+    mod replayguard;
+    mod staticallowlist;
+
+    pub use auditsink::*;
+    pub use authenticator::*;
+    #[cfg(feature = "jwt")]
+    pub use jwtauthenticator::*;
+    pub use policyengine::*;
+    pub use replayguard::*;
+    pub use staticallowlist::*;
+}
+
+#[cfg(feature = "std")]
+pub mod pubsub {
+    mod gapdetector;
+    mod healthmonitor;
+    mod lvcsubscriber;
+    mod publisher;
+    mod statedelta;
+    mod staticsubscriptions;
+    mod subscriber;
+    mod topicregistry;
+
+    pub use gapdetector::*;
+    pub use healthmonitor::*;
+    pub use lvcsubscriber::*;
+    pub use publisher::*;
+    pub use statedelta::*;
+    pub use staticsubscriptions::*;
+    pub use subscriber::*;
+    pub use topicregistry::*;
+}
+
+#[cfg(feature = "std")]
 pub mod rpc {
     mod calloptions;
+    #[cfg(feature = "grpc")]
+    mod grpcrpcclient;
+    #[cfg(feature = "grpc")]
+    mod grpcstatus;
+    mod requestcontext;
     mod rpcclient;
+    mod rpcinvoker;
     mod rpcmapper;
     mod rpcresult;
 
     pub use calloptions::*;
+    #[cfg(feature = "grpc")]
+    pub use grpcrpcclient::*;
+    pub use requestcontext::*;
     pub use rpcclient::*;
+    pub(crate) use rpcinvoker::*;
     pub use rpcmapper::*;
     pub use rpcresult::*;
 }
 
+no_panic! {
+    #[cfg(feature = "std")]
+    pub mod someip {
+        mod someiptlv;
+        #[cfg(feature = "transcoding")]
+        mod someiptlvmessage;
+
+        pub use crate::types::serializationerror::*;
+        pub use someiptlv::*;
+    }
+}
+
+#[cfg(feature = "std")]
+pub mod state {
+    #[cfg(feature = "file-store")]
+    mod filestatestore;
+    mod statestore;
+
+    #[cfg(feature = "file-store")]
+    pub use filestatestore::*;
+    pub use statestore::*;
+}
+
+#[cfg(feature = "proptest")]
+pub mod strategies {
+    mod attributesstrategies;
+    mod payloadstrategies;
+    mod uristrategies;
+
+    pub use attributesstrategies::*;
+    pub use payloadstrategies::*;
+    pub use uristrategies::*;
+}
+
+#[cfg(feature = "std")]
+pub mod streamer {
+    mod forwarder;
+    mod routingtable;
+
+    pub use forwarder::*;
+    pub use routingtable::*;
+}
+
+#[cfg(feature = "std")]
 pub mod transport {
     pub mod builder {
+        mod entityprofile;
         mod uattributesbuilder;
+        mod umessagebuilder;
+        mod upayloadbuilder;
 
+        pub use crate::types::serializationerror::*;
+        pub use entityprofile::*;
         pub use uattributesbuilder::*;
+        pub use umessagebuilder::*;
+        pub use upayloadbuilder::*;
     }
     pub mod datamodel {
+        mod bufferpool;
+        mod cancellationtoken;
+        mod deduplicator;
+        mod fragmentation;
+        mod latencymonitor;
+        mod lifecycle;
+        mod listenerindex;
+        mod logsink;
+        #[cfg(all(feature = "shm", target_os = "linux"))]
+        mod mmapregion;
+        #[cfg(feature = "checksum")]
+        mod payloadchecksum;
+        mod payloadlimits;
+        mod ratelimiter;
+        mod sharedmemoryregion;
+        mod ttlenforcinglistener;
+        mod umetrics;
         mod utransport;
 
+        pub use bufferpool::*;
+        pub use cancellationtoken::*;
+        pub use deduplicator::*;
+        pub use fragmentation::*;
+        pub use latencymonitor::*;
+        pub use lifecycle::*;
+        pub use listenerindex::*;
+        pub(crate) use logsink::emit;
+        #[cfg(feature = "log")]
+        pub use logsink::LogCrateSink;
+        pub use logsink::{set_log_sink, LogLevel, LogSink};
+        #[cfg(all(feature = "shm", target_os = "linux"))]
+        pub use mmapregion::*;
+        #[cfg(feature = "checksum")]
+        pub use payloadchecksum::*;
+        pub use payloadlimits::*;
+        pub use ratelimiter::*;
+        pub use sharedmemoryregion::*;
+        pub use ttlenforcinglistener::*;
+        pub use umetrics::*;
         pub use utransport::*;
     }
+    #[cfg(feature = "iceoryx2")]
+    pub mod iceoryx2 {
+        no_panic! {
+            mod iceoryx2sample;
+        }
+        mod iceoryx2transport;
+
+        pub use iceoryx2transport::*;
+    }
+    pub mod mqtt5 {
+        no_panic! {
+            mod mqtt5mapping;
+        }
+        #[cfg(feature = "mqtt5")]
+        mod mqtt5transport;
+
+        pub use crate::types::serializationerror::*;
+        pub use mqtt5mapping::*;
+        #[cfg(feature = "mqtt5")]
+        pub use mqtt5transport::*;
+    }
+    pub mod serializer {
+        no_panic! {
+            mod microattributesserializer;
+        }
+
+        pub use crate::types::serializationerror::*;
+        pub use microattributesserializer::*;
+    }
+    #[cfg(feature = "socket-transport")]
+    pub mod socket {
+        #[cfg(feature = "ipc-transport")]
+        mod ipctransport;
+        mod listenerregistry;
+        no_panic! {
+            mod messageframe;
+        }
+        mod sockettransport;
+
+        #[cfg(feature = "ipc-transport")]
+        pub use ipctransport::*;
+        pub use sockettransport::*;
+    }
+    #[cfg(feature = "someip-transport")]
+    pub mod someip {
+        no_panic! {
+            mod someipheader;
+            mod someipmapping;
+        }
+        mod someiptransport;
+
+        pub use crate::types::serializationerror::*;
+        pub use someipheader::*;
+        pub use someipmapping::*;
+        pub use someiptransport::*;
+    }
+    #[cfg(feature = "transcoding")]
+    pub mod transcoder {
+        mod schemaregistry;
+        mod upayloadtranscoder;
+
+        pub use crate::types::serializationerror::*;
+        pub use schemaregistry::*;
+        pub use upayloadtranscoder::*;
+    }
+    #[cfg(feature = "zenoh")]
+    pub mod zenoh {
+        no_panic! {
+            mod zenohmapping;
+        }
+        mod zenohtransport;
+
+        pub use crate::types::serializationerror::*;
+        pub use zenohmapping::*;
+        pub use zenohtransport::*;
+    }
     pub mod validator {
+        #[cfg(feature = "transcoding")]
+        mod payloadschemavalidator;
         mod uattributesvalidator;
+        mod umessagevalidator;
+        mod validated;
 
         pub use crate::types::validationerror::*;
+        pub use crate::types::validationreport::*;
+        #[cfg(feature = "transcoding")]
+        pub use payloadschemavalidator::*;
         pub use uattributesvalidator::*;
+        pub use umessagevalidator::*;
+        pub use validated::*;
     }
 }
 
-pub mod uri {
-    pub mod builder {
-        pub mod resourcebuilder;
-    }
-    pub mod validator {
-        mod urivalidator;
+#[cfg(feature = "std")]
+pub mod udiscovery {
+    mod udiscoveryclient;
+    mod uriresolver;
 
-        pub use crate::types::validationerror::*;
-        pub use urivalidator::*;
-    }
-    pub mod serializer {
-        mod longuriserializer;
-        mod microuriserializer;
-        mod uriserializer;
+    pub use crate::coreservices::{
+        UDiscoveryMethod, UDISCOVERY_ID, UDISCOVERY_NAME, UDISCOVERY_VERSION_MAJOR,
+    };
+    pub use udiscoveryclient::*;
+    pub use uriresolver::*;
+}
 
-        pub use crate::types::serializationerror::*;
-        pub use longuriserializer::*;
-        pub use microuriserializer::*;
-        pub use uriserializer::*;
+no_panic! {
+    pub mod uri {
+        pub mod builder {
+            pub mod resourcebuilder;
+        }
+        pub mod validator {
+            mod urivalidator;
+
+            pub use crate::types::validationerror::*;
+            pub use crate::types::validationreport::*;
+            pub use urivalidator::*;
+        }
+        pub mod serializer {
+            mod authorityidstrategy;
+            #[cfg(feature = "authority-hashing")]
+            mod hashedauthorityid;
+            #[cfg(feature = "std")]
+            mod longuriserializer;
+            mod microuriserializer;
+            #[cfg(feature = "std")]
+            mod urimetadata;
+            mod uriserializer;
+            mod uuriref;
+
+            pub use crate::types::serializationerror::*;
+            pub use authorityidstrategy::*;
+            #[cfg(feature = "authority-hashing")]
+            pub use hashedauthorityid::*;
+            #[cfg(feature = "std")]
+            pub use longuriserializer::*;
+            pub use microuriserializer::*;
+            #[cfg(feature = "std")]
+            pub use urimetadata::*;
+            pub use uriserializer::*;
+            pub use uuriref::*;
+        }
     }
 }
 
+#[cfg(feature = "std")]
+pub mod usubscription {
+    mod subscriptionchangelistener;
+    mod subscriptionstore;
+    mod usubscriptionclient;
+
+    pub use crate::coreservices::{
+        USubscriptionMethod, USUBSCRIPTION_ID, USUBSCRIPTION_NAME, USUBSCRIPTION_VERSION_MAJOR,
+    };
+    pub(crate) use subscriptionchangelistener::into_message_listener;
+    pub use subscriptionchangelistener::{SubscriptionChangeListener, TopicAvailability};
+    pub use subscriptionstore::*;
+    pub use usubscriptionclient::*;
+}
+
+#[cfg(feature = "std")]
+pub mod utwin {
+    mod utwinclient;
+
+    pub use crate::coreservices::{UTwinMethod, UTWIN_ID, UTWIN_NAME, UTWIN_VERSION_MAJOR};
+    pub use utwinclient::*;
+}
+
 pub mod uuid {
+    #[cfg(feature = "std")]
     pub mod builder {
+        mod clock;
+        mod randomsource;
+        mod shardeduuidbuilder;
+        #[cfg(any(test, feature = "test-util"))]
+        mod simulatedclock;
         mod uuidbuilder;
 
+        pub use clock::*;
+        pub use randomsource::*;
+        pub use shardeduuidbuilder::*;
+        #[cfg(any(test, feature = "test-util"))]
+        pub use simulatedclock::*;
         pub use uuidbuilder::*;
     }
 }
@@ -133,10 +589,18 @@ pub mod uprotocol {
     // protoc-generated stubs, see build.rs
     include!(concat!(env!("OUT_DIR"), "/uprotocol.v1.rs"));
 
+    pub use crate::proto::uprotocol::redacted;
+    #[cfg(feature = "std")]
+    pub use crate::proto::uprotocol::uattributes;
     pub use crate::proto::uprotocol::uauthority;
+    pub use crate::proto::uprotocol::ucode;
     pub use crate::proto::uprotocol::uentity;
+    pub use crate::proto::uprotocol::umessage;
     pub use crate::proto::uprotocol::umessagetype;
+    #[cfg(feature = "std")]
     pub use crate::proto::uprotocol::upayload;
+    pub use crate::proto::uprotocol::upayloadformat;
+    pub use crate::proto::uprotocol::upriority;
     pub use crate::proto::uprotocol::uresource;
     pub use crate::proto::uprotocol::ustatus;
     pub use crate::proto::uprotocol::uuid;
@@ -173,23 +637,46 @@ pub mod uprotocol {
     }
 }
 
-#[allow(non_snake_case)]
-pub mod proto {
-    // protoc-generated stubs, see build.rs
-    include!(concat!(env!("OUT_DIR"), "/io.cloudevents.v1.rs"));
+no_panic! {
+    #[allow(non_snake_case)]
+    pub mod proto {
+        // protoc-generated stubs, see build.rs
+        #[cfg(feature = "cloudevents")]
+        include!(concat!(env!("OUT_DIR"), "/io.cloudevents.v1.rs"));
 
-    pub mod cloudevents {
-        pub mod protocloudevent;
-    }
+        #[cfg(feature = "cloudevents")]
+        pub mod cloudevents {
+            pub mod protocloudevent;
+        }
+
+        #[cfg(feature = "protobuf")]
+        pub mod interop {
+            mod protobufmessage;
 
-    pub mod uprotocol {
-        pub mod uauthority;
-        pub mod uentity;
-        pub mod umessagetype;
-        pub mod upayload;
-        pub mod uresource;
-        pub mod ustatus;
-        pub mod uuid;
-        pub mod uuri;
+            pub use protobufmessage::*;
+        }
+
+        pub mod uprotocol {
+            #[cfg(feature = "payload-validation")]
+            mod payloadvalidation;
+            #[cfg(feature = "protojson")]
+            mod protojson;
+            pub mod redacted;
+            #[cfg(feature = "std")]
+            pub mod uattributes;
+            pub mod uauthority;
+            pub mod ucode;
+            pub mod uentity;
+            pub mod umessage;
+            pub mod umessagetype;
+            #[cfg(feature = "std")]
+            pub mod upayload;
+            pub mod upayloadformat;
+            pub mod upriority;
+            pub mod uresource;
+            pub mod ustatus;
+            pub mod uuid;
+            pub mod uuri;
+        }
     }
 }
@@ -0,0 +1,212 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::Arc;
+
+use crate::uprotocol::{UCode, UMessage, UMessageType, UStatus, UUri, Uuid};
+
+/// The message that an [`AuditEvent`] is about, identified by its ID and the uEntities involved.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditedMessage {
+    /// The ID of the message.
+    pub id: Option<Uuid>,
+    /// The type of the message.
+    pub message_type: Option<UMessageType>,
+    /// The sender of the message, i.e. its source.
+    pub source: Option<UUri>,
+    /// The receiver of the message, i.e. its sink, if it has one.
+    pub sink: Option<UUri>,
+}
+
+impl From<&UMessage> for AuditedMessage {
+    fn from(message: &UMessage) -> Self {
+        let attributes = message.attributes.as_ref();
+        AuditedMessage {
+            id: attributes.and_then(|attributes| attributes.id.clone()),
+            message_type: attributes
+                .and_then(|attributes| UMessageType::try_from(attributes.r#type).ok()),
+            source: message.source.clone(),
+            sink: attributes.and_then(|attributes| attributes.sink.clone()),
+        }
+    }
+}
+
+/// A security-relevant event, as recorded by an [`AuditSink`].
+///
+/// Events carry the identifying parts of the message they are about, but neither its payload nor its token.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum AuditEvent {
+    /// A [`PolicyEngine`](crate::policy::PolicyEngine) has denied a message, e.g. a request to an RPC method.
+    PolicyDenied { message: AuditedMessage },
+    /// The token of a request could not be authenticated.
+    AuthenticationFailed {
+        message: AuditedMessage,
+        reason: String,
+    },
+    /// The token of a request has expired.
+    TokenExpired {
+        message: AuditedMessage,
+        reason: String,
+    },
+    /// The payload of a message does not match its checksum or signature.
+    IntegrityCheckFailed {
+        message: AuditedMessage,
+        reason: String,
+    },
+    /// A [`ReplayGuard`](crate::policy::ReplayGuard) has rejected a request that has been received before, or
+    /// that is outside of its acceptance window.
+    ReplayRejected {
+        message: AuditedMessage,
+        reason: String,
+    },
+}
+
+impl AuditEvent {
+    /// Creates the event of a message that a policy engine has denied.
+    pub fn policy_denied(message: &UMessage) -> AuditEvent {
+        AuditEvent::PolicyDenied {
+            message: message.into(),
+        }
+    }
+
+    /// Creates the event of a request whose token could not be authenticated.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The request.
+    /// * `status` - The failure returned by the [`Authenticator`](crate::policy::Authenticator), which makes
+    ///              this a [`AuditEvent::TokenExpired`] event if its code is `UCode::DeadlineExceeded`.
+    pub fn authentication_failed(message: &UMessage, status: &UStatus) -> AuditEvent {
+        let message = message.into();
+        let reason = reason_of(status);
+        if status.get_code() == UCode::DeadlineExceeded {
+            AuditEvent::TokenExpired { message, reason }
+        } else {
+            AuditEvent::AuthenticationFailed { message, reason }
+        }
+    }
+
+    /// Creates the event of a message whose payload does not match its checksum or signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    /// * `status` - The failure of the verification, e.g. as returned by `PayloadChecksum::verify`.
+    pub fn integrity_check_failed(message: &UMessage, status: &UStatus) -> AuditEvent {
+        AuditEvent::IntegrityCheckFailed {
+            message: message.into(),
+            reason: reason_of(status),
+        }
+    }
+
+    /// Creates the event of a request that a replay guard has rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The request.
+    /// * `status` - The failure returned by the replay guard.
+    pub fn replay_rejected(message: &UMessage, status: &UStatus) -> AuditEvent {
+        AuditEvent::ReplayRejected {
+            message: message.into(),
+            reason: reason_of(status),
+        }
+    }
+
+    /// Gets the message that this event is about.
+    pub fn message(&self) -> &AuditedMessage {
+        match self {
+            AuditEvent::PolicyDenied { message }
+            | AuditEvent::AuthenticationFailed { message, .. }
+            | AuditEvent::TokenExpired { message, .. }
+            | AuditEvent::IntegrityCheckFailed { message, .. }
+            | AuditEvent::ReplayRejected { message, .. } => message,
+        }
+    }
+}
+
+fn reason_of(status: &UStatus) -> String {
+    status.message.clone().unwrap_or_default()
+}
+
+/// `AuditSink` receives the security-relevant events of the SDK's policy, authentication and integrity checks,
+/// e.g. for passing them on to the logging pipeline of a safety or security team.
+///
+/// Sinks are called from the threads that check the messages, so they need to return quickly.
+pub trait AuditSink: Send + Sync {
+    /// Records an event.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    fn record(&self, event: AuditEvent);
+}
+
+impl<S: AuditSink + ?Sized> AuditSink for Arc<S> {
+    fn record(&self, event: AuditEvent) {
+        (**self).record(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::UPriority;
+
+    fn build_request_for_test() -> UMessage {
+        UMessage {
+            source: Some(UUri::default()),
+            attributes: Some(
+                UAttributesBuilder::request(UPriority::UpriorityCs4, UUri::default(), 1000)
+                    .with_token("secret")
+                    .build(),
+            ),
+            payload: None,
+        }
+    }
+
+    #[test]
+    fn test_event_identifies_message() {
+        let request = build_request_for_test();
+        let event = AuditEvent::policy_denied(&request);
+
+        let message = event.message();
+        assert_eq!(message.id, request.attributes.as_ref().unwrap().id);
+        assert_eq!(
+            message.message_type,
+            Some(UMessageType::UmessageTypeRequest)
+        );
+        assert_eq!(message.source, Some(UUri::default()));
+        assert_eq!(message.sink, Some(UUri::default()));
+        assert!(!format!("{event:?}").contains("secret"));
+    }
+
+    #[test]
+    fn test_authentication_failed_for_expired_token() {
+        let request = build_request_for_test();
+
+        let status = UStatus::fail_with_code(UCode::DeadlineExceeded, "Token has expired");
+        assert!(matches!(
+            AuditEvent::authentication_failed(&request, &status),
+            AuditEvent::TokenExpired { reason, .. } if reason == "Token has expired"
+        ));
+
+        let status = UStatus::fail_with_code(UCode::Unauthenticated, "Invalid signature");
+        assert!(matches!(
+            AuditEvent::authentication_failed(&request, &status),
+            AuditEvent::AuthenticationFailed { .. }
+        ));
+    }
+}
@@ -0,0 +1,91 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use serde_json::{Map, Value};
+
+use crate::uprotocol::UStatus;
+
+/// The identity that an [`Authenticator`] has established for the sender of a request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Principal {
+    /// The identifier of the sender, e.g. the `sub` claim of a JWT.
+    pub subject: String,
+    /// Further claims about the sender, e.g. its roles, for authorization decisions.
+    pub claims: Map<String, Value>,
+}
+
+impl Principal {
+    /// Creates a principal without further claims.
+    ///
+    /// # Arguments
+    ///
+    /// * `subject` - The identifier of the sender.
+    pub fn new<T: Into<String>>(subject: T) -> Principal {
+        Principal {
+            subject: subject.into(),
+            claims: Map::new(),
+        }
+    }
+
+    /// Gets a claim about the sender.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the claim.
+    pub fn claim(&self, name: &str) -> Option<&Value> {
+        self.claims.get(name)
+    }
+}
+
+/// `Authenticator` validates the tokens that requests carry in their attributes, and establishes the identity of
+/// their senders from them.
+///
+/// RPC servers invoke the authenticator for requests that have a token, and make the resulting [`Principal`]
+/// available to the method through the [`RequestContext`](crate::rpc::RequestContext). A reference implementation
+/// for JSON Web Tokens is the `JwtAuthenticator`, if the `jwt` feature is enabled.
+pub trait Authenticator: Send + Sync {
+    /// Validates a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token, as found in the attributes of a request.
+    ///
+    /// # Returns
+    ///
+    /// The identity of the sender of the request.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::DeadlineExceeded` if the token has expired, or with code
+    /// `UCode::Unauthenticated` if it is invalid otherwise.
+    fn authenticate(&self, token: &str) -> Result<Principal, UStatus>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn test_principal_claims() {
+        let mut principal = Principal::new("hartley");
+        assert_eq!(principal.claim("roles"), None);
+
+        principal
+            .claims
+            .insert("roles".to_string(), json!(["door.control"]));
+        assert_eq!(principal.subject, "hartley");
+        assert_eq!(principal.claim("roles"), Some(&json!(["door.control"])));
+    }
+}
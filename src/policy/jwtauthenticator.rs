@@ -0,0 +1,131 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde_json::{Map, Value};
+
+use crate::policy::{Authenticator, Principal};
+use crate::uprotocol::{UCode, UStatus};
+
+/// `JwtAuthenticator` is an [`Authenticator`] for requests carrying a JSON Web Token.
+///
+/// A token is valid if its signature is verified with the authenticator's key, it has not expired, and it passes
+/// the further checks configured in the authenticator's [`Validation`], e.g. of its audience and issuer. The `sub`
+/// claim of a valid token becomes the subject of the [`Principal`], and all of its claims become the principal's
+/// claims.
+pub struct JwtAuthenticator {
+    key: DecodingKey,
+    validation: Validation,
+}
+
+impl JwtAuthenticator {
+    /// Creates an authenticator.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key to verify the signatures of tokens with.
+    /// * `validation` - The checks that tokens need to pass.
+    pub fn new(key: DecodingKey, validation: Validation) -> JwtAuthenticator {
+        JwtAuthenticator { key, validation }
+    }
+
+    /// Creates an authenticator for tokens signed with HMAC using SHA-256, and checked for expiry only.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The secret that the tokens are signed with.
+    pub fn with_hmac_secret(secret: &[u8]) -> JwtAuthenticator {
+        Self::new(
+            DecodingKey::from_secret(secret),
+            Validation::new(Algorithm::HS256),
+        )
+    }
+}
+
+impl Authenticator for JwtAuthenticator {
+    fn authenticate(&self, token: &str) -> Result<Principal, UStatus> {
+        let claims = jsonwebtoken::decode::<Map<String, Value>>(token, &self.key, &self.validation)
+            .map_err(|e| {
+                let code = match e.kind() {
+                    ErrorKind::ExpiredSignature => UCode::DeadlineExceeded,
+                    _ => UCode::Unauthenticated,
+                };
+                UStatus::from_code_and_source(code, e)
+            })?
+            .claims;
+        let subject = claims
+            .get("sub")
+            .and_then(Value::as_str)
+            .ok_or_else(|| UStatus::fail_with_code(UCode::Unauthenticated, "Token has no subject"))?
+            .to_string();
+        Ok(Principal { subject, claims })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    const SECRET: &[u8] = b"uprotocol-test-secret";
+    // Sat, 19 Oct 2096 09:46:40 GMT
+    const NOT_EXPIRED: u64 = 4_000_000_000;
+
+    fn token(claims: &Value, secret: &[u8]) -> String {
+        jsonwebtoken::encode(
+            &Header::default(),
+            claims,
+            &EncodingKey::from_secret(secret),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_valid_token() {
+        let authenticator = JwtAuthenticator::with_hmac_secret(SECRET);
+        let token = token(
+            &json!({ "sub": "hartley", "exp": NOT_EXPIRED, "roles": ["door.control"] }),
+            SECRET,
+        );
+
+        let principal = authenticator.authenticate(&token).unwrap();
+        assert_eq!(principal.subject, "hartley");
+        assert_eq!(principal.claim("roles"), Some(&json!(["door.control"])));
+    }
+
+    #[test]
+    fn test_invalid_tokens() {
+        let authenticator = JwtAuthenticator::with_hmac_secret(SECRET);
+
+        for token in [
+            token(&json!({ "sub": "hartley", "exp": NOT_EXPIRED }), b"other"),
+            token(&json!({ "exp": NOT_EXPIRED }), SECRET),
+            "not a token".to_string(),
+        ] {
+            let status = authenticator.authenticate(&token).unwrap_err();
+            assert_eq!(status.get_code(), UCode::Unauthenticated);
+        }
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let authenticator = JwtAuthenticator::with_hmac_secret(SECRET);
+        let token = token(&json!({ "sub": "hartley", "exp": 1000 }), SECRET);
+
+        let status = authenticator.authenticate(&token).unwrap_err();
+        assert_eq!(status.get_code(), UCode::DeadlineExceeded);
+    }
+}
@@ -0,0 +1,157 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use serde::{Deserialize, Serialize};
+
+use crate::uprotocol::{UCode, UMessage, UMessageType, UStatus, UUri};
+
+/// Whether a [`PolicyEngine`] lets a message pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyDecision {
+    Allow,
+    #[default]
+    Deny,
+}
+
+/// `PolicyEngine` decides which uEntities may send which messages to which other uEntities, so that access control
+/// is configured in one place rather than in each uEntity.
+///
+/// Policy engines are consulted by the components that pass messages on, like the
+/// [`Forwarder`](crate::streamer::Forwarder), and by RPC servers before invoking a method. A static implementation
+/// is the [`StaticAllowlist`](crate::policy::StaticAllowlist).
+pub trait PolicyEngine: Send + Sync {
+    /// Decides whether a uEntity may send a message to another uEntity.
+    ///
+    /// # Arguments
+    ///
+    /// * `caller` - The URI of the sender, i.e. the source of the message.
+    /// * `target` - The URI that the message is sent to, i.e. its sink, or its source for published messages.
+    /// * `message_type` - The type of the message.
+    fn decide(&self, caller: &UUri, target: &UUri, message_type: UMessageType) -> PolicyDecision;
+
+    /// Decides whether a message may pass.
+    ///
+    /// Messages without source are decided about as if they were sent by an empty URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    fn decide_message(&self, message: &UMessage) -> PolicyDecision {
+        let empty = UUri::default();
+        let caller = message.source.as_ref().unwrap_or(&empty);
+        let target = message
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.sink.as_ref())
+            .unwrap_or(caller);
+        let message_type = message
+            .attributes
+            .as_ref()
+            .and_then(|attributes| UMessageType::try_from(attributes.r#type).ok())
+            .unwrap_or(UMessageType::UmessageTypeUnspecified);
+        self.decide(caller, target, message_type)
+    }
+
+    /// Checks whether a message may pass, e.g. a request before an RPC server invokes the method.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::PermissionDenied` if the message must not pass.
+    fn authorize(&self, message: &UMessage) -> Result<(), UStatus> {
+        match self.decide_message(message) {
+            PolicyDecision::Allow => Ok(()),
+            PolicyDecision::Deny => Err(UStatus::fail_with_code(
+                UCode::PermissionDenied,
+                "Message is not allowed by policy",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::{UEntity, UPriority};
+
+    // allows requests to body.access only
+    struct BodyAccessRequests;
+    impl PolicyEngine for BodyAccessRequests {
+        fn decide(
+            &self,
+            _caller: &UUri,
+            target: &UUri,
+            message_type: UMessageType,
+        ) -> PolicyDecision {
+            let entity = target.entity.as_ref().map(|entity| entity.name.as_str());
+            if message_type == UMessageType::UmessageTypeRequest && entity == Some("body.access") {
+                PolicyDecision::Allow
+            } else {
+                PolicyDecision::Deny
+            }
+        }
+    }
+
+    fn build_uri_for_test(entity: &str) -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: entity.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_authorize_request() {
+        let message = UMessage {
+            source: Some(build_uri_for_test("hartley")),
+            attributes: Some(
+                UAttributesBuilder::request(
+                    UPriority::UpriorityCs4,
+                    build_uri_for_test("body.access"),
+                    1000,
+                )
+                .build(),
+            ),
+            payload: None,
+        };
+        assert!(BodyAccessRequests.authorize(&message).is_ok());
+    }
+
+    #[test]
+    fn test_authorize_publish_fails() {
+        let message = UMessage {
+            source: Some(build_uri_for_test("body.access")),
+            attributes: Some(UAttributesBuilder::publish(UPriority::UpriorityCs1).build()),
+            payload: None,
+        };
+        assert_eq!(
+            BodyAccessRequests
+                .authorize(&message)
+                .unwrap_err()
+                .get_code(),
+            UCode::PermissionDenied
+        );
+        assert_eq!(
+            BodyAccessRequests.decide_message(&UMessage::default()),
+            PolicyDecision::Deny
+        );
+    }
+}
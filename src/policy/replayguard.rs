@@ -0,0 +1,245 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::policy::{AuditEvent, AuditSink};
+use crate::transport::datamodel::Deduplicator;
+use crate::uprotocol::{UCode, UMessage, UStatus};
+use crate::uuid::builder::{SystemClock, UClock};
+
+const DEFAULT_CAPACITY: usize = 4096;
+
+/// `ReplayGuard` protects the methods of an RPC server against requests that are recorded and sent again, e.g. by
+/// a compromised node on a shared bus.
+///
+/// A request is accepted once, if its ID is a uProtocol UUID whose time is within the guard's acceptance window
+/// around the current time. Requests with an ID that has been accepted before, or with a time outside of the window,
+/// are rejected. The window thus needs to be wide enough to cover the skew between the clocks of the devices and the
+/// time it takes requests to arrive, while IDs are remembered for as long as they are within the window. Once the
+/// guard remembers as many IDs as it can, further requests are rejected until older IDs leave the window, so that
+/// no accepted ID is forgotten while it could still be replayed.
+pub struct ReplayGuard {
+    window: u64,
+    seen: Deduplicator,
+    clock: Arc<dyn UClock>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+}
+
+impl ReplayGuard {
+    /// Creates a guard that takes the current time from the platform, and remembers up to 4096 request IDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - The maximum time between the time of a request's ID and the current time, in either direction.
+    pub fn new(window: Duration) -> Self {
+        Self::with_clock(window, DEFAULT_CAPACITY, SystemClock)
+    }
+
+    /// Creates a guard that takes the current time from the given clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - The maximum time between the time of a request's ID and the current time, in either direction.
+    /// * `capacity` - The maximum number of request IDs to remember, which needs to exceed the number of requests
+    ///                received within the window, as requests beyond it are rejected.
+    /// * `clock` - The clock to check the time of the requests' IDs against.
+    pub fn with_clock<C>(window: Duration, capacity: usize, clock: C) -> Self
+    where
+        C: UClock + 'static,
+    {
+        let clock: Arc<dyn UClock> = Arc::new(clock);
+        ReplayGuard {
+            window: u64::try_from(window.as_millis()).unwrap_or(u64::MAX),
+            seen: Deduplicator::with_clock(window, capacity, Arc::clone(&clock))
+                .rejecting_at_capacity(),
+            clock,
+            audit_sink: None,
+        }
+    }
+
+    /// Sets a sink that rejected requests are recorded with.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit_sink` - The audit sink.
+    #[must_use]
+    pub fn with_audit_sink(mut self, audit_sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(audit_sink);
+        self
+    }
+
+    /// Checks a request before its method is invoked, and remembers its ID if it is accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::PermissionDenied`, which is to be sent as the response, if the request
+    /// has no uProtocol UUID as its ID, the time of its ID is outside of the acceptance window, or the request has
+    /// been accepted before, or with code `UCode::ResourceExhausted` if the guard cannot remember any more IDs
+    /// within the window.
+    pub fn check(&self, request: &UMessage) -> Result<(), UStatus> {
+        let result = self.validate(request);
+        if let (Err(status), Some(audit_sink)) = (&result, &self.audit_sink) {
+            audit_sink.record(AuditEvent::replay_rejected(request, status));
+        }
+        result
+    }
+
+    fn validate(&self, request: &UMessage) -> Result<(), UStatus> {
+        let denied = |message: &str| UStatus::fail_with_code(UCode::PermissionDenied, message);
+
+        let id = request
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.id.as_ref())
+            .filter(|id| id.is_uprotocol_uuid())
+            .ok_or_else(|| denied("Request has no uProtocol UUID"))?;
+        let time = id
+            .get_time()
+            .ok_or_else(|| denied("Request has no uProtocol UUID"))?;
+        let now = self
+            .clock
+            .now()
+            .ok_or_else(|| denied("Current time is unknown"))?;
+        if now.abs_diff(time) > self.window {
+            return Err(denied("Request is outside of the acceptance window"));
+        }
+        match self.seen.check_id(id) {
+            Ok(false) => Ok(()),
+            Ok(true) => Err(denied("Request has been received before")),
+            Err(_) => Err(UStatus::fail_with_code(
+                UCode::ResourceExhausted,
+                "Too many requests within the acceptance window",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Mutex;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::{UPriority, UUri, Uuid};
+    use crate::uuid::builder::{SystemRandomSource, UUIDv8Builder};
+
+    const NOW: u64 = 0x18C684468F8; // Thu, 14 Dec 2023 12:19:23 GMT
+
+    struct TestClock(AtomicU64);
+    impl UClock for TestClock {
+        fn now(&self) -> Option<u64> {
+            Some(self.0.load(Ordering::Relaxed))
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<AuditEvent>>);
+    impl AuditSink for RecordingSink {
+        fn record(&self, event: AuditEvent) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    fn uuid_at(instant: u64) -> Uuid {
+        UUIDv8Builder::with_sources(TestClock(AtomicU64::new(instant)), &mut SystemRandomSource)
+            .build()
+    }
+
+    fn request(id: Uuid) -> UMessage {
+        let mut attributes =
+            UAttributesBuilder::request(UPriority::UpriorityCs4, UUri::default(), 1000).build();
+        attributes.id = Some(id);
+        UMessage {
+            attributes: Some(attributes),
+            ..Default::default()
+        }
+    }
+
+    fn guard() -> (ReplayGuard, Arc<TestClock>) {
+        let clock = Arc::new(TestClock(AtomicU64::new(NOW)));
+        let guard = ReplayGuard::with_clock(Duration::from_secs(2), 16, Arc::clone(&clock));
+        (guard, clock)
+    }
+
+    #[test]
+    fn test_request_is_accepted_once() {
+        let (guard, _) = guard();
+        let request = request(uuid_at(NOW - 100));
+
+        assert!(guard.check(&request).is_ok());
+        let status = guard.check(&request).unwrap_err();
+        assert_eq!(status.get_code(), UCode::PermissionDenied);
+        assert_eq!(
+            status.message.as_deref(),
+            Some("Request has been received before")
+        );
+    }
+
+    #[test]
+    fn test_request_outside_of_window_is_rejected() {
+        let (guard, clock) = guard();
+        assert!(guard.check(&request(uuid_at(NOW + 2000))).is_ok());
+        assert!(guard.check(&request(uuid_at(NOW + 2001))).is_err());
+
+        let earlier = request(uuid_at(NOW));
+        clock.0.store(NOW + 2001, Ordering::Relaxed);
+        let status = guard.check(&earlier).unwrap_err();
+        assert_eq!(
+            status.message.as_deref(),
+            Some("Request is outside of the acceptance window")
+        );
+    }
+
+    #[test]
+    fn test_requests_beyond_capacity_are_rejected() {
+        let clock = Arc::new(TestClock(AtomicU64::new(NOW)));
+        let guard = ReplayGuard::with_clock(Duration::from_secs(2), 1, Arc::clone(&clock));
+        let first = request(uuid_at(NOW - 100));
+
+        assert!(guard.check(&first).is_ok());
+        let status = guard.check(&request(uuid_at(NOW))).unwrap_err();
+        assert_eq!(status.get_code(), UCode::ResourceExhausted);
+        // the accepted request is still remembered
+        assert_eq!(
+            guard.check(&first).unwrap_err().get_code(),
+            UCode::PermissionDenied
+        );
+
+        clock.0.store(NOW + 2000, Ordering::Relaxed);
+        assert!(guard.check(&request(uuid_at(NOW + 2000))).is_ok());
+    }
+
+    #[test]
+    fn test_request_without_uprotocol_uuid_is_rejected() {
+        let (guard, _) = guard();
+        let sink = Arc::new(RecordingSink::default());
+        let guard = guard.with_audit_sink(sink.clone());
+
+        assert!(guard
+            .check(&request(Uuid::from(uuid::Uuid::new_v4())))
+            .is_err());
+        assert!(guard.check(&UMessage::default()).is_err());
+        assert!(matches!(
+            sink.0.lock().unwrap()[0],
+            AuditEvent::ReplayRejected { .. }
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::policy::{PolicyDecision, PolicyEngine};
+use crate::types::serializationerror::SerializationError;
+use crate::uprotocol::{UAuthority, UMessageType, UUri};
+
+/// A pattern of URIs in an [`AllowlistEntry`], with criteria that are not set matching any name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UriPattern {
+    /// The name of the authority, or `""` for URIs on the local device.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authority: Option<String>,
+    /// The name of the uEntity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity: Option<String>,
+    /// The name of the resource, like `door`, or its name and instance, like `rpc.UpdateDoor`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+}
+
+impl UriPattern {
+    fn matches(&self, uri: &UUri) -> bool {
+        let authority = uri
+            .authority
+            .as_ref()
+            .and_then(UAuthority::get_name)
+            .unwrap_or_default();
+        let entity = uri
+            .entity
+            .as_ref()
+            .map(|entity| entity.name.as_str())
+            .unwrap_or_default();
+
+        self.authority.as_ref().map_or(true, |a| a == authority)
+            && self.entity.as_ref().map_or(true, |e| e == entity)
+            && self.resource.as_ref().map_or(true, |pattern| {
+                let Some(resource) = uri.resource.as_ref() else {
+                    return false;
+                };
+                match pattern.split_once('.') {
+                    Some((name, instance)) => {
+                        resource.name == name && resource.instance.as_deref() == Some(instance)
+                    }
+                    None => resource.name == *pattern,
+                }
+            })
+    }
+}
+
+/// An entry of a [`StaticAllowlist`], allowing callers matching a pattern to send messages to targets matching
+/// another pattern.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowlistEntry {
+    /// The callers that the entry applies to.
+    #[serde(default)]
+    pub caller: UriPattern,
+    /// The targets that the entry applies to.
+    #[serde(default)]
+    pub target: UriPattern,
+    /// The type of the messages that the entry applies to, as named in the uProtocol proto definitions,
+    /// e.g. `UMESSAGE_TYPE_REQUEST`, or any type if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_type: Option<String>,
+}
+
+/// The configuration of a [`StaticAllowlist`], e.g. as loaded from a JSON file.
+///
+/// ```json
+/// {
+///   "entries": [
+///     {
+///       "caller": { "entity": "hartley" },
+///       "target": { "entity": "body.access", "resource": "rpc.UpdateDoor" },
+///       "message_type": "UMESSAGE_TYPE_REQUEST"
+///     },
+///     { "caller": { "authority": "" }, "target": { "authority": "" } }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowlistConfig {
+    /// The entries.
+    #[serde(default)]
+    pub entries: Vec<AllowlistEntry>,
+}
+
+// An entry with its message type parsed.
+#[derive(Debug)]
+struct CompiledEntry {
+    entry: AllowlistEntry,
+    message_type: Option<UMessageType>,
+}
+
+/// `StaticAllowlist` is a [`PolicyEngine`] that allows the messages matching any of its entries, and denies all
+/// others.
+#[derive(Debug)]
+pub struct StaticAllowlist {
+    entries: Vec<CompiledEntry>,
+}
+
+impl StaticAllowlist {
+    /// Creates an allowlist.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration of the allowlist.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if an entry has an unknown message type.
+    pub fn new(config: AllowlistConfig) -> Result<StaticAllowlist, SerializationError> {
+        let entries = config
+            .entries
+            .into_iter()
+            .map(|entry| {
+                let message_type = entry
+                    .message_type
+                    .as_deref()
+                    .map(|name| {
+                        UMessageType::from_str_name(name).ok_or_else(|| {
+                            SerializationError::new(format!("Unknown message type [{name}]"))
+                        })
+                    })
+                    .transpose()?;
+                Ok(CompiledEntry {
+                    entry,
+                    message_type,
+                })
+            })
+            .collect::<Result<Vec<_>, SerializationError>>()?;
+        Ok(StaticAllowlist { entries })
+    }
+
+    /// Creates an allowlist from a JSON configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The configuration, as shown for [`AllowlistConfig`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the configuration is malformed or has an entry with an unknown message type.
+    pub fn from_json(json: &str) -> Result<StaticAllowlist, SerializationError> {
+        let config = serde_json::from_str(json)
+            .map_err(|e| SerializationError::json("Invalid allowlist", e))?;
+        Self::new(config)
+    }
+
+    /// Creates an allowlist from a JSON configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the file cannot be read, or any of the errors returned by
+    /// [`StaticAllowlist::from_json`].
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<StaticAllowlist, SerializationError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            SerializationError::other(format!("Failed to read allowlist [{}]", path.display()), e)
+        })?;
+        Self::from_json(&json)
+    }
+}
+
+impl PolicyEngine for StaticAllowlist {
+    fn decide(&self, caller: &UUri, target: &UUri, message_type: UMessageType) -> PolicyDecision {
+        let allowed = self.entries.iter().any(|compiled| {
+            compiled.message_type.map_or(true, |t| t == message_type)
+                && compiled.entry.caller.matches(caller)
+                && compiled.entry.target.matches(target)
+        });
+        if allowed {
+            PolicyDecision::Allow
+        } else {
+            PolicyDecision::Deny
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    use crate::uprotocol::{UEntity, UResource};
+    use crate::uri::builder::resourcebuilder::UResourceBuilder;
+
+    const CONFIG: &str = r#"{
+        "entries": [
+            {
+                "caller": { "entity": "hartley" },
+                "target": { "entity": "body.access", "resource": "rpc.UpdateDoor" },
+                "message_type": "UMESSAGE_TYPE_REQUEST"
+            },
+            { "caller": { "authority": "cloud" }, "target": { "entity": "body.access", "resource": "door" } }
+        ]
+    }"#;
+
+    fn build_uri_for_test(authority: Option<&str>, entity: &str, resource: UResource) -> UUri {
+        let mut uri = UUri {
+            entity: Some(UEntity {
+                name: entity.to_string(),
+                ..Default::default()
+            }),
+            resource: Some(resource),
+            ..Default::default()
+        };
+        if let Some(name) = authority {
+            uri.authority
+                .get_or_insert_with(Default::default)
+                .set_name(name);
+        }
+        uri
+    }
+
+    fn rpc(method: &str) -> UResource {
+        UResourceBuilder::for_rpc_request(Some(method.to_string()), None)
+    }
+
+    #[test_case(None, "hartley", rpc("UpdateDoor"), UMessageType::UmessageTypeRequest, PolicyDecision::Allow; "allowed method")]
+    #[test_case(None, "hartley", rpc("LockDoor"), UMessageType::UmessageTypeRequest, PolicyDecision::Deny; "other method")]
+    #[test_case(None, "hartley", rpc("UpdateDoor"), UMessageType::UmessageTypePublish, PolicyDecision::Deny; "other message type")]
+    #[test_case(None, "other", rpc("UpdateDoor"), UMessageType::UmessageTypeRequest, PolicyDecision::Deny; "other caller")]
+    #[test_case(Some("cloud"), "other", UResource::from("door.front_left"), UMessageType::UmessageTypePublish, PolicyDecision::Allow; "resource name")]
+    #[test_case(None, "other", UResource::from("door.front_left"), UMessageType::UmessageTypePublish, PolicyDecision::Deny; "local caller")]
+    fn test_decide(
+        authority: Option<&str>,
+        caller: &str,
+        target: UResource,
+        message_type: UMessageType,
+        expected: PolicyDecision,
+    ) {
+        let allowlist = StaticAllowlist::from_json(CONFIG).unwrap();
+        let caller = build_uri_for_test(authority, caller, UResourceBuilder::for_rpc_response());
+        let target = build_uri_for_test(None, "body.access", target);
+        assert_eq!(allowlist.decide(&caller, &target, message_type), expected);
+    }
+
+    #[test]
+    fn test_empty_allowlist_denies() {
+        let allowlist = StaticAllowlist::new(AllowlistConfig::default()).unwrap();
+        let uri = build_uri_for_test(None, "hartley", rpc("UpdateDoor"));
+        assert_eq!(
+            allowlist.decide(&uri, &uri, UMessageType::UmessageTypeRequest),
+            PolicyDecision::Deny
+        );
+    }
+
+    #[test]
+    fn test_from_file() {
+        let path = std::env::temp_dir().join(format!("allowlist-{}.json", std::process::id()));
+        std::fs::write(&path, CONFIG).unwrap();
+        let result = StaticAllowlist::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap().entries.len(), 2);
+        assert!(StaticAllowlist::from_file(&path).is_err());
+    }
+
+    #[test_case("{ \"entries\": [{ \"message_type\": \"REQUEST\" }] }"; "unknown message type")]
+    #[test_case("{ \"entries\": [{ \"caller\": \"hartley\" }] }"; "malformed pattern")]
+    fn test_from_json_fails_for_invalid_config(json: &str) {
+        assert!(StaticAllowlist::from_json(json).is_err());
+    }
+}
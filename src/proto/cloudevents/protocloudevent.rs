@@ -23,14 +23,22 @@ use crate::proto::cloud_event::cloud_event_attribute_value::Attr;
 use crate::proto::cloud_event::CloudEventAttributeValue;
 use crate::proto::cloud_event::Data as CloudEventData;
 use crate::proto::CloudEvent as CloudEventProto;
+use crate::types::serializationerror::SerializationError;
 
 impl Name for CloudEventProto {
     const NAME: &'static str = "CloudEvent";
     const PACKAGE: &'static str = "io.cloudevents.v1";
 }
 
-impl From<CloudEventProto> for cloudevents::Event {
-    fn from(source_event: CloudEventProto) -> Self {
+impl CloudEventProto {
+    /// Converts this protobuf `CloudEvent` into a `cloudevents::Event`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if one of the attributes of the `CloudEvent` has no value, or if its timestamp
+    /// is out of range.
+    pub fn try_into_cloud_event(self) -> Result<cloudevents::Event, SerializationError> {
+        let source_event = self;
         let mut subject: Option<String> = None;
         let mut dt: Option<DateTime<Utc>> = None;
         let mut dataschema: Option<Url> = None;
@@ -39,7 +47,12 @@ impl From<CloudEventProto> for cloudevents::Event {
         // extensions
         let mut extensions = HashMap::<String, ExtensionValue>::new();
         for (key, value) in &source_event.attributes {
-            match value.attr.as_ref().unwrap() {
+            let Some(attr) = value.attr.as_ref() else {
+                return Err(SerializationError::new(format!(
+                    "CloudEvent attribute [{key}] has no value"
+                )));
+            };
+            match attr {
                 Attr::CeBoolean(b) => {
                     extensions.insert(key.to_string(), ExtensionValue::Boolean(*b));
                 }
@@ -64,9 +77,14 @@ impl From<CloudEventProto> for cloudevents::Event {
                     // timestamp
                     // TODO how is this serialized by eg the Java libraries, considering cloudevent.proto is missing dedicated attributes for this?
                     if key.eq("timestamp") {
-                        #[allow(clippy::cast_sign_loss)]
-                        let naive =
-                            NaiveDateTime::from_timestamp_opt(ts.seconds, ts.nanos as u32).unwrap();
+                        let naive = u32::try_from(ts.nanos)
+                            .ok()
+                            .and_then(|nanos| NaiveDateTime::from_timestamp_opt(ts.seconds, nanos))
+                            .ok_or_else(|| {
+                                SerializationError::new(format!(
+                                    "CloudEvent timestamp [{ts}] is out of range"
+                                ))
+                            })?;
                         dt = Some(DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc));
                     } else {
                         extensions.insert(key.to_string(), ExtensionValue::String(ts.to_string()));
@@ -103,7 +121,9 @@ impl From<CloudEventProto> for cloudevents::Event {
         if let Some(time) = dt {
             event_builder = event_builder.time(time);
         }
-        let mut cloud_event = event_builder.build().unwrap();
+        let mut cloud_event = event_builder
+            .build()
+            .map_err(|e| SerializationError::other("Invalid CloudEvent", e))?;
 
         // Extract data - the proto serialization knows a protobuf.Any type!... something there?
         let event_data: Option<Data> = match source_event.data {
@@ -121,7 +141,22 @@ impl From<CloudEventProto> for cloudevents::Event {
             cloud_event.set_extension(key, value.clone());
         }
 
-        cloud_event
+        Ok(cloud_event)
+    }
+}
+
+impl From<CloudEventProto> for cloudevents::Event {
+    /// Converts a protobuf `CloudEvent` into a `cloudevents::Event`.
+    ///
+    /// # Panics
+    ///
+    /// - if the `CloudEvent` is malformed, see [`CloudEventProto::try_into_cloud_event`] for converting it without
+    ///   panicking.
+    #[allow(clippy::expect_used)]
+    fn from(source_event: CloudEventProto) -> Self {
+        source_event
+            .try_into_cloud_event()
+            .expect("protobuf CloudEvent needs to be well-formed")
     }
 }
 
@@ -140,9 +175,8 @@ impl From<cloudevents::Event> for CloudEventProto {
 
         // timestamp
         // TODO how is this serialized by eg the Java libraries, considering cloudevent.proto is missing dedicated attributes for this?
-        if source_event.time().is_some() {
-            let time = *source_event.time().unwrap();
-            let sys_time: std::time::SystemTime = time.into();
+        if let Some(time) = source_event.time() {
+            let sys_time: std::time::SystemTime = (*time).into();
 
             let timesstamp = CloudEventAttributeValue {
                 attr: Some(Attr::CeTimestamp(prost_types::Timestamp::from(sys_time))),
@@ -233,6 +267,35 @@ mod tests {
         assert_eq!(origin, dest);
     }
 
+    #[test]
+    fn test_try_into_cloud_event_fails_for_malformed_attributes() {
+        let mut proto = CloudEventProto::from(build_base_cloud_event_for_test().build().unwrap());
+        proto.attributes.insert(
+            "priority".to_string(),
+            CloudEventAttributeValue { attr: None },
+        );
+        assert_eq!(
+            proto
+                .clone()
+                .try_into_cloud_event()
+                .unwrap_err()
+                .to_string(),
+            "CloudEvent attribute [priority] has no value"
+        );
+
+        proto.attributes.insert(
+            "timestamp".to_string(),
+            CloudEventAttributeValue {
+                attr: Some(Attr::CeTimestamp(prost_types::Timestamp {
+                    seconds: i64::MAX,
+                    nanos: -1,
+                })),
+            },
+        );
+        proto.attributes.remove("priority");
+        assert!(proto.try_into_cloud_event().is_err());
+    }
+
     fn build_base_cloud_event_for_test() -> EventBuilderV10 {
         let uri = UUri {
             entity: Some(UEntity {
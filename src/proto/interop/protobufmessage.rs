@@ -0,0 +1,123 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::types::serializationerror::SerializationError;
+
+/// Converts a message generated by prost, like all of the uProtocol types of this crate, into the
+/// corresponding message generated for the [`protobuf`](https://docs.rs/protobuf) crate.
+///
+/// Both messages are expected to be generated from the same `.proto` definition. The conversion
+/// is done by means of the protobuf wire format, so no field of the message needs to be mapped by hand.
+pub trait IntoProtobuf: prost::Message + Sized {
+    /// Converts this message into a message of the `protobuf` crate.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SerializationError`] if the encoded message cannot be parsed as a `P`.
+    fn to_protobuf<P: protobuf::Message>(&self) -> Result<P, SerializationError> {
+        P::parse_from_bytes(&self.encode_to_vec()).map_err(|e| {
+            SerializationError::other(
+                format!("Failed to convert to protobuf message {}", P::NAME),
+                e,
+            )
+        })
+    }
+}
+
+impl<M: prost::Message> IntoProtobuf for M {}
+
+/// Creates a message generated by prost, like all of the uProtocol types of this crate, from the
+/// corresponding message generated for the [`protobuf`](https://docs.rs/protobuf) crate.
+pub trait FromProtobuf: prost::Message + Default {
+    /// Creates a message from a message of the `protobuf` crate.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SerializationError`] if the protobuf message cannot be encoded, or if the encoded
+    /// message cannot be decoded as `Self`.
+    fn from_protobuf<P: protobuf::Message>(message: &P) -> Result<Self, SerializationError> {
+        let bytes = message.write_to_bytes().map_err(|e| {
+            SerializationError::other(format!("Failed to encode protobuf message {}", P::NAME), e)
+        })?;
+        Self::decode(bytes.as_slice()).map_err(|e| {
+            SerializationError::decode(
+                format!("Failed to convert from protobuf message {}", P::NAME),
+                e,
+            )
+        })
+    }
+}
+
+impl<M: prost::Message + Default> FromProtobuf for M {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use protobuf::well_known_types::any::Any as ProtobufAny;
+    use protobuf::well_known_types::timestamp::Timestamp as ProtobufTimestamp;
+    use protobuf::well_known_types::wrappers::StringValue;
+
+    use crate::uprotocol::{UCode, UStatus};
+
+    #[test]
+    fn test_to_protobuf() {
+        let timestamp = prost_types::Timestamp {
+            seconds: 1_702_556_363,
+            nanos: 42,
+        };
+
+        let converted: ProtobufTimestamp = timestamp.to_protobuf().unwrap();
+
+        assert_eq!(converted.seconds, 1_702_556_363);
+        assert_eq!(converted.nanos, 42);
+    }
+
+    #[test]
+    fn test_from_protobuf() {
+        let mut timestamp = ProtobufTimestamp::new();
+        timestamp.seconds = 1_702_556_363;
+        timestamp.nanos = 42;
+
+        let converted = prost_types::Timestamp::from_protobuf(&timestamp).unwrap();
+
+        assert_eq!(converted.seconds, 1_702_556_363);
+        assert_eq!(converted.nanos, 42);
+    }
+
+    #[test]
+    fn test_round_trip_of_uprotocol_type_in_any() {
+        let status = UStatus::fail_with_code(UCode::Unavailable, "service is down");
+        let any = prost_types::Any::from_msg(&status).unwrap();
+
+        let converted: ProtobufAny = any.to_protobuf().unwrap();
+        assert_eq!(converted.type_url, any.type_url);
+
+        let any = prost_types::Any::from_protobuf(&converted).unwrap();
+        assert_eq!(any.to_msg::<UStatus>().unwrap(), status);
+    }
+
+    #[test]
+    fn test_from_protobuf_fails_for_different_message() {
+        let mut value = StringValue::new();
+        value.value = "not a status".to_string();
+
+        let result = UStatus::from_protobuf(&value);
+
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("Failed to convert from protobuf message StringValue"));
+    }
+}
@@ -0,0 +1,222 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use prost_reflect::{DynamicMessage, Kind, MessageDescriptor, ReflectMessage, Value};
+
+use crate::types::validationerror::ValidationError;
+use crate::uprotocol::{UPayload, UUri, Uuid};
+use crate::uri::validator::UriValidator;
+
+const TYPE_URL_PREFIX: &str = "type.googleapis.com/";
+
+const UURI: &str = "uprotocol.v1.UUri";
+const UUID: &str = "uprotocol.v1.UUID";
+
+impl UPayload {
+    /// Validates that this payload contains a well-formed protobuf message of the described type, e.g. for gateways to
+    /// reject malformed payloads instead of forwarding them into the vehicle.
+    ///
+    /// The payload is expected to contain the message wrapped in a `google.protobuf.Any`, as created by
+    /// [`UPayloadBuilder::from_protobuf`](crate::transport::builder::UPayloadBuilder::from_protobuf). Besides decoding,
+    /// the message and the messages nested in it must
+    /// - not contain fields that the descriptor does not know of,
+    /// - not contain enum values that the descriptor does not know of,
+    /// - contain `uprotocol.v1.UUri`s that are valid in long or in micro form, and
+    /// - contain `uprotocol.v1.UUID`s that are uProtocol UUIDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptor` - The descriptor of the message type that this payload is expected to contain.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` if the payload does not contain a message of the described type, combining the
+    /// violations of the conventions above with field paths like `topic.entity.name`.
+    pub fn validate_against(&self, descriptor: &MessageDescriptor) -> Result<(), ValidationError> {
+        let (type_url, bytes) = self
+            .message_bytes()
+            .map_err(|e| ValidationError::new(e.to_string()))?;
+        if type_url.strip_prefix(TYPE_URL_PREFIX) != Some(descriptor.full_name()) {
+            return Err(ValidationError::new(format!(
+                "Payload contains {type_url} instead of {}",
+                descriptor.full_name()
+            )));
+        }
+        let message = DynamicMessage::decode(descriptor.clone(), bytes).map_err(|e| {
+            ValidationError::new(format!("Invalid message {}: {e}", descriptor.full_name()))
+        })?;
+
+        let mut errors = Vec::new();
+        check_message(&message, &mut errors);
+        ValidationError::combine(errors).map_or(Ok(()), Err)
+    }
+}
+
+// collects the violations of the conventions, with field paths relative to the message
+fn check_message(message: &DynamicMessage, errors: &mut Vec<ValidationError>) {
+    if message.unknown_fields().next().is_some() {
+        errors.push(ValidationError::new(format!(
+            "Message {} contains unknown fields",
+            message.descriptor().full_name()
+        )));
+    }
+    match message.descriptor().full_name() {
+        UURI => match message.transcode_to::<UUri>() {
+            Ok(uri) => {
+                if let Err(error) = UriValidator::validate(&uri) {
+                    if UriValidator::validate_micro_form(&uri).is_err() {
+                        errors.push(error);
+                    }
+                }
+            }
+            Err(e) => errors.push(ValidationError::new(format!("Invalid UUri: {e}"))),
+        },
+        UUID => match message.transcode_to::<Uuid>() {
+            Ok(uuid) if uuid.is_uprotocol_uuid() => {}
+            _ => errors.push(ValidationError::new("UUID is not a uProtocol UUID")),
+        },
+        _ => {}
+    }
+
+    for (field, value) in message.fields() {
+        let mut field_errors = Vec::new();
+        match value {
+            Value::List(values) => values
+                .iter()
+                .for_each(|value| check_value(&field.kind(), value, &mut field_errors)),
+            Value::Map(entries) => {
+                if let Kind::Message(entry) = field.kind() {
+                    let kind = entry.map_entry_value_field().kind();
+                    entries
+                        .values()
+                        .for_each(|value| check_value(&kind, value, &mut field_errors));
+                }
+            }
+            value => check_value(&field.kind(), value, &mut field_errors),
+        }
+        errors.extend(
+            field_errors
+                .into_iter()
+                .map(|error| error.within(field.name())),
+        );
+    }
+}
+
+fn check_value(kind: &Kind, value: &Value, errors: &mut Vec<ValidationError>) {
+    match (kind, value) {
+        (_, Value::Message(message)) => check_message(message, errors),
+        (Kind::Enum(descriptor), Value::EnumNumber(number)) => {
+            if descriptor.get_value(*number).is_none() {
+                errors.push(ValidationError::new(format!(
+                    "Unknown value {number} of enum {}",
+                    descriptor.full_name()
+                )));
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prost::Message;
+    use prost_reflect::DescriptorPool;
+
+    use crate::uprotocol::{UAttributes, UEntity, UResource, UStatus};
+    use crate::uuid::builder::UUIDv8Builder;
+
+    // descriptors of the protoc-generated types, see build.rs
+    const FILE_DESCRIPTOR_SET: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/file_descriptor_set.bin"));
+
+    fn descriptor(name: &str) -> MessageDescriptor {
+        DescriptorPool::decode(FILE_DESCRIPTOR_SET)
+            .unwrap()
+            .get_message_by_name(name)
+            .unwrap()
+    }
+
+    fn build_attributes_for_test() -> UAttributes {
+        UAttributes {
+            id: Some(UUIDv8Builder::new().build()),
+            sink: Some(UUri {
+                entity: Some(UEntity {
+                    name: "body.access".to_string(),
+                    ..Default::default()
+                }),
+                resource: Some(UResource::from("door.front_left#Door")),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn build_payload_for_test(attributes: &UAttributes) -> UPayload {
+        UPayload::from_message_bytes(
+            "type.googleapis.com/uprotocol.v1.UAttributes",
+            &attributes.encode_to_vec(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_well_formed_payload() {
+        let payload = build_payload_for_test(&build_attributes_for_test());
+
+        assert!(payload
+            .validate_against(&descriptor("uprotocol.v1.UAttributes"))
+            .is_ok());
+        assert!(payload
+            .validate_against(&descriptor("uprotocol.v1.UStatus"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_validate_reports_violated_conventions() {
+        let mut attributes = build_attributes_for_test();
+        attributes.id = Some(Uuid { msb: 1, lsb: 2 });
+        attributes.sink = Some(UUri {
+            entity: Some(UEntity::default()),
+            ..Default::default()
+        });
+        attributes.commstatus = Some(1000);
+        let payload = build_payload_for_test(&attributes);
+
+        let error = payload
+            .validate_against(&descriptor("uprotocol.v1.UAttributes"))
+            .unwrap_err();
+        let mut fields = error
+            .errors()
+            .iter()
+            .filter_map(ValidationError::field)
+            .collect::<Vec<_>>();
+        fields.sort_unstable();
+        assert_eq!(fields, vec!["commstatus", "id", "sink.entity.name"]);
+    }
+
+    #[test]
+    fn test_validate_rejects_unknown_fields() {
+        let mut bytes = UStatus::ok().encode_to_vec();
+        // field 15 of type varint, which UStatus does not have
+        bytes.extend_from_slice(&[0x78, 0x01]);
+        let payload =
+            UPayload::from_message_bytes("type.googleapis.com/uprotocol.v1.UStatus", &bytes)
+                .unwrap();
+
+        assert!(payload
+            .validate_against(&descriptor("uprotocol.v1.UStatus"))
+            .is_err());
+    }
+}
@@ -0,0 +1,225 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use once_cell::sync::OnceCell;
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage};
+
+use crate::types::serializationerror::SerializationError;
+use crate::uprotocol::{UAttributes, UMessage, UStatus, UUri};
+
+// descriptors of the protoc-generated types, see build.rs
+const FILE_DESCRIPTOR_SET: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/file_descriptor_set.bin"));
+
+fn descriptor_pool() -> Result<&'static DescriptorPool, SerializationError> {
+    static POOL: OnceCell<Option<DescriptorPool>> = OnceCell::new();
+    POOL.get_or_init(|| DescriptorPool::decode(FILE_DESCRIPTOR_SET).ok())
+        .as_ref()
+        .ok_or_else(|| SerializationError::new("Invalid uProtocol file descriptor set"))
+}
+
+fn to_json_string<M: Message>(message: &M, name: &str) -> Result<String, SerializationError> {
+    let descriptor = descriptor_pool()?
+        .get_message_by_name(name)
+        .ok_or_else(|| SerializationError::new(format!("No descriptor for message {name}")))?;
+    let message = DynamicMessage::decode(descriptor, message.encode_to_vec().as_slice())
+        .map_err(|e| SerializationError::decode(format!("Invalid message {name}"), e))?;
+    serde_json::to_string(&message)
+        .map_err(|e| SerializationError::json(format!("Failed to serialize message {name}"), e))
+}
+
+fn from_json_str<M: Message + Default>(json: &str, name: &str) -> Result<M, SerializationError> {
+    let descriptor = descriptor_pool()?
+        .get_message_by_name(name)
+        .ok_or_else(|| SerializationError::new(format!("No descriptor for message {name}")))?;
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let message = DynamicMessage::deserialize(descriptor, &mut deserializer)
+        .map_err(|e| SerializationError::json(format!("Invalid JSON for message {name}"), e))?;
+    deserializer
+        .end()
+        .map_err(|e| SerializationError::json(format!("Invalid JSON for message {name}"), e))?;
+    message
+        .transcode_to()
+        .map_err(|e| SerializationError::decode(format!("Invalid message {name}"), e))
+}
+
+impl UMessage {
+    /// Serializes this message to its [canonical JSON mapping](https://protobuf.dev/programming-guides/proto3/#json),
+    /// i.e. with lowerCamelCase field names and enum values represented by their names.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the message cannot be represented as JSON.
+    pub fn to_json_string(&self) -> Result<String, SerializationError> {
+        to_json_string(self, "uprotocol.v1.UMessage")
+    }
+
+    /// Deserializes a message from its [canonical JSON mapping](https://protobuf.dev/programming-guides/proto3/#json).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the JSON string does not represent a `UMessage`.
+    pub fn from_json_str(json: &str) -> Result<Self, SerializationError> {
+        from_json_str(json, "uprotocol.v1.UMessage")
+    }
+}
+
+impl UAttributes {
+    /// Serializes these attributes to their [canonical JSON mapping](https://protobuf.dev/programming-guides/proto3/#json).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the attributes cannot be represented as JSON.
+    pub fn to_json_string(&self) -> Result<String, SerializationError> {
+        to_json_string(self, "uprotocol.v1.UAttributes")
+    }
+
+    /// Deserializes attributes from their [canonical JSON mapping](https://protobuf.dev/programming-guides/proto3/#json).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the JSON string does not represent `UAttributes`.
+    pub fn from_json_str(json: &str) -> Result<Self, SerializationError> {
+        from_json_str(json, "uprotocol.v1.UAttributes")
+    }
+}
+
+impl UUri {
+    /// Serializes this URI to its [canonical JSON mapping](https://protobuf.dev/programming-guides/proto3/#json).
+    ///
+    /// This is the protobuf structure of the URI, not its long form string representation.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the URI cannot be represented as JSON.
+    pub fn to_json_string(&self) -> Result<String, SerializationError> {
+        to_json_string(self, "uprotocol.v1.UUri")
+    }
+
+    /// Deserializes a URI from its [canonical JSON mapping](https://protobuf.dev/programming-guides/proto3/#json).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the JSON string does not represent a `UUri`.
+    pub fn from_json_str(json: &str) -> Result<Self, SerializationError> {
+        from_json_str(json, "uprotocol.v1.UUri")
+    }
+}
+
+impl UStatus {
+    /// Serializes this status to its [canonical JSON mapping](https://protobuf.dev/programming-guides/proto3/#json).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the status cannot be represented as JSON, e.g. because
+    /// one of its details is of a type unknown to the SDK.
+    pub fn to_json_string(&self) -> Result<String, SerializationError> {
+        to_json_string(self, "uprotocol.v1.UStatus")
+    }
+
+    /// Deserializes a status from its [canonical JSON mapping](https://protobuf.dev/programming-guides/proto3/#json).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the JSON string does not represent a `UStatus`.
+    pub fn from_json_str(json: &str) -> Result<Self, SerializationError> {
+        from_json_str(json, "uprotocol.v1.UStatus")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uprotocol::{
+        Data, UCode, UEntity, UMessageType, UPayload, UPayloadFormat, UPriority, UResource, Uuid,
+    };
+
+    fn build_uri() -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                name: "door".to_string(),
+                instance: Some("front_left".to_string()),
+                message: Some("Door".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_uri_to_json_string() {
+        let json = build_uri().to_json_string().unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"entity":{"name":"body.access","versionMajor":1},"resource":{"name":"door","instance":"front_left","message":"Door"}}"#
+        );
+    }
+
+    #[test]
+    fn test_uri_from_json_str() {
+        let json = r#"{"entity":{"name":"body.access","versionMajor":1},"resource":{"name":"door","instance":"front_left","message":"Door"}}"#;
+
+        assert_eq!(UUri::from_json_str(json).unwrap(), build_uri());
+    }
+
+    #[test]
+    fn test_status_to_json_string_uses_enum_names() {
+        let status = UStatus::fail_with_code(UCode::NotFound, "no such topic");
+
+        let json = status.to_json_string().unwrap();
+
+        assert_eq!(json, r#"{"code":"NOT_FOUND","message":"no such topic"}"#);
+        assert_eq!(UStatus::from_json_str(&json).unwrap(), status);
+    }
+
+    #[test]
+    fn test_message_round_trip() {
+        let message = UMessage {
+            attributes: Some(UAttributes {
+                id: Some(Uuid { msb: 1, lsb: 2 }),
+                r#type: UMessageType::UmessageTypePublish.into(),
+                priority: UPriority::UpriorityCs1.into(),
+                ttl: Some(1000),
+                ..Default::default()
+            }),
+            payload: Some(UPayload {
+                data: Some(Data::Value(b"hello".to_vec())),
+                format: UPayloadFormat::UpayloadFormatText.into(),
+                ..Default::default()
+            }),
+            source: Some(build_uri()),
+        };
+
+        let json = message.to_json_string().unwrap();
+        assert!(json.contains(r#""type":"UMESSAGE_TYPE_PUBLISH""#));
+        assert!(json.contains(r#""priority":"UPRIORITY_CS1""#));
+        assert!(json.contains(r#""id":{"msb":"1","lsb":"2"}"#));
+
+        assert_eq!(UMessage::from_json_str(&json).unwrap(), message);
+    }
+
+    #[test]
+    fn test_attributes_from_json_str_fails_for_unknown_field() {
+        let result = UAttributes::from_json_str(r#"{"timeToLive":1000}"#);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,223 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use core::fmt::{self, Debug, Display, Formatter};
+
+use crate::uprotocol::{
+    UAttributes, UMessage, UMessageType, UPayload, UPayloadFormat, UPriority, Uuid,
+};
+
+const REDACTED: &str = "<redacted>";
+
+// Writes a value that is to be masked, showing only whether it is present.
+struct Masked(bool);
+
+impl Debug for Masked {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.0 {
+            f.write_str(REDACTED)
+        } else {
+            f.write_str("None")
+        }
+    }
+}
+
+// Writes a UUID in its hyphenated form, which is what ids are looked up by in other logs.
+struct Hyphenated<'a>(Option<&'a Uuid>);
+
+impl Debug for Hyphenated<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(uuid) => f.write_str(&uuid.to_hyphenated_string()),
+            None => f.write_str("None"),
+        }
+    }
+}
+
+/// Formats `UAttributes` for logs, masking the token and the permission level while keeping the IDs, the sink
+/// and the type of the message visible.
+///
+/// Both the `Debug` and the `Display` output are the same.
+pub struct RedactedAttributes<'a>(&'a UAttributes);
+
+impl Debug for RedactedAttributes<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let attributes = self.0;
+        let message_type = UMessageType::try_from(attributes.r#type)
+            .unwrap_or(UMessageType::UmessageTypeUnspecified);
+        let priority = UPriority::try_from(attributes.priority)
+            .map_or("UNKNOWN", |priority| priority.as_str_name());
+        f.debug_struct("UAttributes")
+            .field("id", &Hyphenated(attributes.id.as_ref()))
+            .field("type", &format_args!("{message_type}"))
+            .field("priority", &format_args!("{priority}"))
+            .field("sink", &attributes.sink)
+            .field("ttl", &attributes.ttl)
+            .field(
+                "permission_level",
+                &Masked(attributes.permission_level.is_some()),
+            )
+            .field("commstatus", &attributes.commstatus)
+            .field("reqid", &Hyphenated(attributes.reqid.as_ref()))
+            .field("token", &Masked(attributes.token.is_some()))
+            .finish()
+    }
+}
+
+impl Display for RedactedAttributes<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+/// Formats a `UPayload` for logs, masking its data while keeping its length and format visible.
+///
+/// Both the `Debug` and the `Display` output are the same.
+pub struct RedactedPayload<'a>(&'a UPayload);
+
+impl Debug for RedactedPayload<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let payload = self.0;
+        let format = UPayloadFormat::try_from(payload.format)
+            .map_or("UNKNOWN", |format| format.as_str_name());
+        f.debug_struct("UPayload")
+            .field("length", &payload.length)
+            .field("format", &format_args!("{format}"))
+            .field("data", &Masked(payload.data.is_some()))
+            .finish()
+    }
+}
+
+impl Display for RedactedPayload<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+/// Formats a `UMessage` for logs, with its attributes and payload redacted like by [`RedactedAttributes`] and
+/// [`RedactedPayload`].
+///
+/// Both the `Debug` and the `Display` output are the same.
+pub struct RedactedMessage<'a>(&'a UMessage);
+
+impl Debug for RedactedMessage<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let message = self.0;
+        f.debug_struct("UMessage")
+            .field("source", &message.source)
+            .field(
+                "attributes",
+                &message.attributes.as_ref().map(RedactedAttributes),
+            )
+            .field("payload", &message.payload.as_ref().map(RedactedPayload))
+            .finish()
+    }
+}
+
+impl Display for RedactedMessage<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl UAttributes {
+    /// Gets an adapter for writing these attributes to logs without their token and permission level.
+    pub fn redacted(&self) -> RedactedAttributes<'_> {
+        RedactedAttributes(self)
+    }
+}
+
+impl UPayload {
+    /// Gets an adapter for writing this payload to logs without its data.
+    pub fn redacted(&self) -> RedactedPayload<'_> {
+        RedactedPayload(self)
+    }
+}
+
+impl UMessage {
+    /// Gets an adapter for writing this message to logs without its token, permission level and payload data.
+    pub fn redacted(&self) -> RedactedMessage<'_> {
+        RedactedMessage(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::format;
+    use alloc::string::ToString;
+
+    use crate::uprotocol::{Data, UUri};
+
+    fn build_message_for_test() -> UMessage {
+        UMessage {
+            source: Some(UUri::default()),
+            attributes: Some(UAttributes {
+                id: Some(Uuid {
+                    msb: 0x018c_6844_68f8_8000,
+                    lsb: 0x8123_4567_89ab_cdef,
+                }),
+                r#type: UMessageType::UmessageTypeRequest.into(),
+                priority: UPriority::UpriorityCs4.into(),
+                ttl: Some(1000),
+                permission_level: Some(3),
+                token: Some("secret-token".to_string()),
+                ..Default::default()
+            }),
+            payload: Some(UPayload {
+                length: Some(6),
+                format: UPayloadFormat::UpayloadFormatRaw.into(),
+                data: Some(Data::Value(b"secret".to_vec())),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_redacted_attributes() {
+        let message = build_message_for_test();
+        let attributes = message.attributes.as_ref().unwrap();
+        let redacted = attributes.redacted().to_string();
+
+        assert!(redacted.contains("id: 018c6844-68f8-8000-8123-456789abcdef"));
+        assert!(redacted.contains("type: req.v1"));
+        assert!(redacted.contains("priority: UPRIORITY_CS4"));
+        assert!(redacted.contains("ttl: Some(1000)"));
+        assert!(redacted.contains("permission_level: <redacted>"));
+        assert!(redacted.contains("token: <redacted>"));
+        assert!(!redacted.contains("secret-token"));
+        assert!(format!("{attributes:?}").contains("secret-token"));
+    }
+
+    #[test]
+    fn test_redacted_message() {
+        let message = build_message_for_test();
+        let redacted = format!("{:?}", message.redacted());
+
+        assert!(redacted.contains("format: UPAYLOAD_FORMAT_RAW"));
+        assert!(redacted.contains("length: Some(6)"));
+        assert!(redacted.contains("data: <redacted>"));
+        assert!(!redacted.contains("secret"));
+        assert_eq!(redacted, message.redacted().to_string());
+    }
+
+    #[test]
+    fn test_absent_values_are_not_masked() {
+        let attributes = UAttributes::default();
+        let redacted = attributes.redacted().to_string();
+
+        assert!(redacted.contains("id: None"));
+        assert!(redacted.contains("token: None"));
+        assert!(redacted.contains("permission_level: None"));
+    }
+}
@@ -0,0 +1,308 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+
+use crate::types::serializationerror::SerializationError;
+use crate::uprotocol::{UAttributes, UMessageType, UPriority, UUri, Uuid};
+use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+
+/// The name of the header carrying the message's ID, as hyphenated UUID.
+pub const HEADER_ID: &str = "id";
+/// The name of the header carrying the message's type, like `pub.v1`.
+pub const HEADER_TYPE: &str = "type";
+/// The name of the header carrying the message's sink, as long form URI.
+pub const HEADER_SINK: &str = "sink";
+/// The name of the header carrying the message's priority, like `UPRIORITY_CS4`.
+pub const HEADER_PRIORITY: &str = "priority";
+/// The name of the header carrying the message's time-to-live in milliseconds.
+pub const HEADER_TTL: &str = "ttl";
+/// The name of the header carrying the permission level of the message's sender.
+pub const HEADER_PERMISSION_LEVEL: &str = "plevel";
+/// The name of the header carrying the communication status of a response.
+pub const HEADER_COMMSTATUS: &str = "commstatus";
+/// The name of the header carrying the ID of the request that a response refers to, as hyphenated UUID.
+pub const HEADER_REQID: &str = "reqid";
+/// The name of the header carrying the sender's access token.
+pub const HEADER_TOKEN: &str = "token";
+
+impl UAttributes {
+    /// Sets the ID of the message.
+    #[must_use]
+    pub fn with_id(mut self, id: Uuid) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Sets the type of the message.
+    #[must_use]
+    pub fn with_message_type(mut self, message_type: UMessageType) -> Self {
+        self.r#type = message_type.into();
+        self
+    }
+
+    /// Sets the sink of the message, i.e. the destination of a request or response.
+    #[must_use]
+    pub fn with_sink(mut self, sink: UUri) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Sets the priority of the message.
+    #[must_use]
+    pub fn with_priority(mut self, priority: UPriority) -> Self {
+        self.priority = priority.into();
+        self
+    }
+
+    /// Sets the time-to-live of the message in milliseconds, capped at `i32::MAX`.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: u32) -> Self {
+        self.ttl = Some(i32::try_from(ttl).unwrap_or(i32::MAX));
+        self
+    }
+
+    /// Sets the permission level of the message's sender, capped at `i32::MAX`.
+    #[must_use]
+    pub fn with_permission_level(mut self, permission_level: u32) -> Self {
+        self.permission_level = Some(i32::try_from(permission_level).unwrap_or(i32::MAX));
+        self
+    }
+
+    /// Sets the communication status of a response.
+    #[must_use]
+    pub fn with_commstatus(mut self, commstatus: i32) -> Self {
+        self.commstatus = Some(commstatus);
+        self
+    }
+
+    /// Sets the ID of the request that a response refers to.
+    #[must_use]
+    pub fn with_reqid(mut self, reqid: Uuid) -> Self {
+        self.reqid = Some(reqid);
+        self
+    }
+
+    /// Sets the sender's access token.
+    #[must_use]
+    pub fn with_token<T: Into<String>>(mut self, token: T) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Maps these attributes to headers, named as the attributes of uProtocol CloudEvents are, for transports that
+    /// carry attributes as string key-value pairs, like the headers of HTTP, the user properties of MQTT5 or the
+    /// application properties of AMQP.
+    ///
+    /// Attributes that are not set are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the sink cannot be serialized into long form.
+    pub fn to_header_map(&self) -> Result<HashMap<String, String>, SerializationError> {
+        let mut headers = HashMap::new();
+        let mut insert = |name: &str, value: String| {
+            headers.insert(name.to_string(), value);
+        };
+        if let Some(id) = &self.id {
+            insert(HEADER_ID, id.to_hyphenated_string());
+        }
+        if let Ok(message_type) = UMessageType::try_from(self.r#type) {
+            insert(HEADER_TYPE, message_type.to_string());
+        }
+        if let Some(sink) = &self.sink {
+            insert(HEADER_SINK, LongUriSerializer::serialize(sink)?);
+        }
+        if let Ok(priority) = UPriority::try_from(self.priority) {
+            insert(HEADER_PRIORITY, priority.as_str_name().to_string());
+        }
+        if let Some(ttl) = self.ttl {
+            insert(HEADER_TTL, ttl.to_string());
+        }
+        if let Some(permission_level) = self.permission_level {
+            insert(HEADER_PERMISSION_LEVEL, permission_level.to_string());
+        }
+        if let Some(commstatus) = self.commstatus {
+            insert(HEADER_COMMSTATUS, commstatus.to_string());
+        }
+        if let Some(reqid) = &self.reqid {
+            insert(HEADER_REQID, reqid.to_hyphenated_string());
+        }
+        if let Some(token) = &self.token {
+            insert(HEADER_TOKEN, token.clone());
+        }
+        Ok(headers)
+    }
+
+    /// Maps headers back to attributes, as created by [`UAttributes::to_header_map`].
+    ///
+    /// Header names are matched regardless of their case, as HTTP header names are case-insensitive. Headers that do
+    /// not represent a uProtocol attribute are ignored, so that transports can pass all headers of a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of a received message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if any of the uProtocol headers has an invalid value.
+    pub fn from_header_map(
+        headers: &HashMap<String, String>,
+    ) -> Result<UAttributes, SerializationError> {
+        let mut attributes = UAttributes::default();
+        for (name, value) in headers {
+            match name.to_ascii_lowercase().as_str() {
+                HEADER_ID => attributes.id = Some(parse_uuid(HEADER_ID, value)?),
+                HEADER_TYPE => attributes.r#type = UMessageType::from(value.as_str()).into(),
+                HEADER_SINK => {
+                    attributes.sink = Some(LongUriSerializer::deserialize(value.clone())?);
+                }
+                HEADER_PRIORITY => {
+                    attributes.priority = UPriority::from_str_name(value)
+                        .ok_or_else(|| {
+                            SerializationError::new(format!("Invalid priority [{value}]"))
+                        })?
+                        .into();
+                }
+                HEADER_TTL => attributes.ttl = Some(parse_i32(HEADER_TTL, value)?),
+                HEADER_PERMISSION_LEVEL => {
+                    attributes.permission_level = Some(parse_i32(HEADER_PERMISSION_LEVEL, value)?);
+                }
+                HEADER_COMMSTATUS => {
+                    attributes.commstatus = Some(parse_i32(HEADER_COMMSTATUS, value)?);
+                }
+                HEADER_REQID => attributes.reqid = Some(parse_uuid(HEADER_REQID, value)?),
+                HEADER_TOKEN => attributes.token = Some(value.clone()),
+                _ => {}
+            }
+        }
+        Ok(attributes)
+    }
+}
+
+fn parse_i32(name: &str, value: &str) -> Result<i32, SerializationError> {
+    value
+        .parse::<i32>()
+        .map_err(|_e| SerializationError::new(format!("Invalid {name} [{value}]")))
+}
+
+fn parse_uuid(name: &str, value: &str) -> Result<Uuid, SerializationError> {
+    value
+        .parse::<Uuid>()
+        .map_err(|e| SerializationError::other(format!("Invalid {name} [{value}]"), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::{UEntity, UResource};
+    use crate::uuid::builder::UUIDv8Builder;
+
+    fn build_sink_for_test() -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from("rpc.UpdateDoor")),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_request_attributes_round_trip() {
+        let attributes =
+            UAttributesBuilder::request(UPriority::UpriorityCs4, build_sink_for_test(), 2500)
+                .with_token("someOAuthToken")
+                .with_permission_level(3)
+                .build();
+
+        let headers = attributes.to_header_map().unwrap();
+        assert_eq!(headers.get(HEADER_TYPE).unwrap(), "req.v1");
+        assert_eq!(headers.get(HEADER_PRIORITY).unwrap(), "UPRIORITY_CS4");
+        assert_eq!(headers.get(HEADER_TTL).unwrap(), "2500");
+        assert_eq!(
+            headers.get(HEADER_SINK).unwrap(),
+            "/body.access/1/rpc.UpdateDoor"
+        );
+        assert!(!headers.contains_key(HEADER_REQID));
+
+        assert_eq!(UAttributes::from_header_map(&headers).unwrap(), attributes);
+    }
+
+    #[test]
+    fn test_response_attributes_round_trip() {
+        let reqid = UUIDv8Builder::new().build();
+        let attributes = UAttributesBuilder::response(
+            UPriority::UpriorityCs4,
+            build_sink_for_test(),
+            reqid.clone(),
+        )
+        .with_commstatus(5)
+        .build();
+
+        let headers = attributes.to_header_map().unwrap();
+        assert_eq!(
+            headers.get(HEADER_REQID).unwrap(),
+            &reqid.to_hyphenated_string()
+        );
+        assert_eq!(UAttributes::from_header_map(&headers).unwrap(), attributes);
+    }
+
+    #[test]
+    fn test_setters_match_builder() {
+        let reqid = UUIDv8Builder::new().build();
+        let built = UAttributesBuilder::response(
+            UPriority::UpriorityCs4,
+            build_sink_for_test(),
+            reqid.clone(),
+        )
+        .with_ttl(1000)
+        .with_commstatus(5)
+        .with_token("someOAuthToken")
+        .build();
+
+        let attributes = UAttributes::default()
+            .with_id(built.id.clone().unwrap())
+            .with_message_type(UMessageType::UmessageTypeResponse)
+            .with_priority(UPriority::UpriorityCs4)
+            .with_sink(build_sink_for_test())
+            .with_reqid(reqid)
+            .with_ttl(1000)
+            .with_commstatus(5)
+            .with_token("someOAuthToken");
+        assert_eq!(attributes, built);
+        assert_eq!(
+            UAttributes::default().with_ttl(u32::MAX).ttl,
+            Some(i32::MAX)
+        );
+    }
+
+    #[test]
+    fn test_from_header_map_ignores_case_and_other_headers() {
+        let headers = HashMap::from([
+            ("TTL".to_string(), "100".to_string()),
+            ("Content-Type".to_string(), "application/json".to_string()),
+        ]);
+        let attributes = UAttributes::from_header_map(&headers).unwrap();
+        assert_eq!(attributes.ttl, Some(100));
+
+        let headers = HashMap::from([(HEADER_TTL.to_string(), "soon".to_string())]);
+        assert!(UAttributes::from_header_map(&headers).is_err());
+        let headers = HashMap::from([(HEADER_REQID.to_string(), "1234".to_string())]);
+        assert!(UAttributes::from_header_map(&headers).is_err());
+    }
+}
@@ -11,8 +11,235 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+use alloc::string::String;
+use alloc::vec::Vec;
+
 use crate::uprotocol::{Remote, UAuthority};
 
+/// The maximum length of the ID of a uAuthority, as imposed by the micro form of URIs.
+pub const MAX_AUTHORITY_ID_LENGTH: usize = 255;
+
+/// The IP address of a remote uAuthority, held without heap allocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuthorityIp {
+    V4([u8; 4]),
+    V6([u8; 16]),
+}
+
+impl AuthorityIp {
+    /// Gets the octets of the address, in network byte order.
+    pub fn octets(&self) -> &[u8] {
+        match self {
+            AuthorityIp::V4(octets) => octets,
+            AuthorityIp::V6(octets) => octets,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for AuthorityIp {
+    type Error = ();
+
+    /// Fails for a slice that is neither 4 nor 16 bytes long.
+    fn try_from(octets: &[u8]) -> Result<Self, Self::Error> {
+        if let Ok(octets) = <[u8; 4]>::try_from(octets) {
+            Ok(AuthorityIp::V4(octets))
+        } else if let Ok(octets) = <[u8; 16]>::try_from(octets) {
+            Ok(AuthorityIp::V6(octets))
+        } else {
+            Err(())
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<AuthorityIp> for std::net::IpAddr {
+    fn from(ip: AuthorityIp) -> Self {
+        match ip {
+            AuthorityIp::V4(octets) => std::net::IpAddr::from(octets),
+            AuthorityIp::V6(octets) => std::net::IpAddr::from(octets),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::net::IpAddr> for AuthorityIp {
+    fn from(ip: std::net::IpAddr) -> Self {
+        match ip {
+            std::net::IpAddr::V4(ip) => AuthorityIp::V4(ip.octets()),
+            std::net::IpAddr::V6(ip) => AuthorityIp::V6(ip.octets()),
+        }
+    }
+}
+
+/// The ID of a remote uAuthority, held in a fixed-size buffer without heap allocation.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AuthorityId {
+    length: u8,
+    bytes: [u8; MAX_AUTHORITY_ID_LENGTH],
+}
+
+impl AuthorityId {
+    /// Gets the bytes of the ID.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes
+            .get(..usize::from(self.length))
+            .unwrap_or_default()
+    }
+}
+
+impl core::fmt::Debug for AuthorityId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("AuthorityId")
+            .field(&self.as_bytes())
+            .finish()
+    }
+}
+
+impl TryFrom<&[u8]> for AuthorityId {
+    type Error = ();
+
+    /// Fails for a slice that is longer than [`MAX_AUTHORITY_ID_LENGTH`].
+    fn try_from(id: &[u8]) -> Result<Self, Self::Error> {
+        let length = u8::try_from(id.len()).map_err(|_| ())?;
+        let mut bytes = [0u8; MAX_AUTHORITY_ID_LENGTH];
+        bytes.get_mut(..id.len()).ok_or(())?.copy_from_slice(id);
+        Ok(AuthorityId { length, bytes })
+    }
+}
+
+/// The reason why the ID of a uAuthority cannot be serialized into micro form, whose length byte only holds IDs of
+/// 1 to [`MAX_AUTHORITY_ID_LENGTH`] bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityIdError {
+    /// The ID is empty.
+    Empty,
+    /// The ID is longer than [`MAX_AUTHORITY_ID_LENGTH`] bytes, holding the length of the ID.
+    TooLong(usize),
+}
+
+impl core::fmt::Display for AuthorityIdError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AuthorityIdError::Empty => write!(f, "Authority ID is empty"),
+            AuthorityIdError::TooLong(length) => write!(
+                f,
+                "Authority ID of {length} bytes is longer than {MAX_AUTHORITY_ID_LENGTH} bytes"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AuthorityIdError {}
+
+/// The kind of address that a uAuthority is addressed by, as encoded in the second byte of a URI in micro form.
+///
+/// Transports take it from [`UAuthority::address_type`], e.g. for choosing between an IP socket and a bus that
+/// addresses its devices by ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum AddressType {
+    /// No address, for the uEntities on the local device.
+    Local = 0,
+    /// An IPv4 address of 4 bytes.
+    IPv4 = 1,
+    /// An IPv6 address of 16 bytes.
+    IPv6 = 2,
+    /// An ID of up to [`MAX_AUTHORITY_ID_LENGTH`] bytes, like a VIN.
+    ID = 3,
+}
+
+impl AddressType {
+    /// Gets the value of this address type in a URI in micro form.
+    pub const fn value(self) -> u8 {
+        self as u8
+    }
+
+    /// Gets the address type of a URI in micro form, without checking the rest of the URI.
+    ///
+    /// # Returns
+    ///
+    /// The address type, or `None` if the URI is too short or has an unknown address type.
+    pub fn of_micro_uri(micro_uri: &[u8]) -> Option<AddressType> {
+        micro_uri
+            .get(1)
+            .and_then(|value| AddressType::try_from(*value).ok())
+    }
+
+    /// Checks if this address type refers to an IP address.
+    pub fn is_ip(self) -> bool {
+        matches!(self, AddressType::IPv4 | AddressType::IPv6)
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            AddressType::Local => "local",
+            AddressType::IPv4 => "ipv4",
+            AddressType::IPv6 => "ipv6",
+            AddressType::ID => "id",
+        }
+    }
+}
+
+impl TryFrom<u8> for AddressType {
+    type Error = ();
+
+    /// Fails for a value that is not an address type of a URI in micro form.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AddressType::Local),
+            1 => Ok(AddressType::IPv4),
+            2 => Ok(AddressType::IPv6),
+            3 => Ok(AddressType::ID),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<i32> for AddressType {
+    type Error = ();
+
+    /// Fails for a value that is not an address type of a URI in micro form.
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        u8::try_from(value)
+            .map_err(|_| ())
+            .and_then(AddressType::try_from)
+    }
+}
+
+impl core::str::FromStr for AddressType {
+    type Err = ();
+
+    /// Parses the name of an address type, i.e. `local`, `ipv4`, `ipv6` or `id`, ignoring case, e.g. as given in the
+    /// configuration of a transport. Fails for other names.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        [
+            AddressType::Local,
+            AddressType::IPv4,
+            AddressType::IPv6,
+            AddressType::ID,
+        ]
+        .into_iter()
+        .find(|address_type| address_type.as_str().eq_ignore_ascii_case(name))
+        .ok_or(())
+    }
+}
+
+impl core::fmt::Display for AddressType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<AuthorityIp> for AddressType {
+    fn from(ip: AuthorityIp) -> Self {
+        match ip {
+            AuthorityIp::V4(_) => AddressType::IPv4,
+            AuthorityIp::V6(_) => AddressType::IPv6,
+        }
+    }
+}
+
 /// Helper functions to deal with `UAuthority::Remote` structure
 impl UAuthority {
     pub fn has_name(&self) -> bool {
@@ -48,6 +275,61 @@ impl UAuthority {
         }
     }
 
+    /// Gets the IP address of the uAuthority.
+    ///
+    /// # Returns
+    ///
+    /// The address, or `None` if the uAuthority has no IP address, or one of neither 4 nor 16 bytes.
+    pub fn get_authority_ip(&self) -> Option<AuthorityIp> {
+        self.get_ip().and_then(|ip| AuthorityIp::try_from(ip).ok())
+    }
+
+    /// Gets the ID of the uAuthority.
+    ///
+    /// # Returns
+    ///
+    /// The ID, or `None` if the uAuthority has no ID, or one longer than [`MAX_AUTHORITY_ID_LENGTH`].
+    pub fn get_authority_id(&self) -> Option<AuthorityId> {
+        self.get_id().and_then(|id| AuthorityId::try_from(id).ok())
+    }
+
+    /// Gets the kind of address that the uAuthority is addressed by.
+    ///
+    /// # Returns
+    ///
+    /// The address type, which is `AddressType::Local` for a uAuthority without address, or `None` if the uAuthority
+    /// is addressed by name, or has an IP address of neither 4 nor 16 bytes, or an ID that does not conform, see
+    /// [`UAuthority::remote_id_conforms`].
+    pub fn address_type(&self) -> Option<AddressType> {
+        match &self.remote {
+            None => Some(AddressType::Local),
+            Some(Remote::Name(_)) => None,
+            Some(Remote::Ip(_)) => self.get_authority_ip().map(AddressType::from),
+            Some(Remote::Id(_)) => self.remote_id_conforms().then_some(AddressType::ID),
+        }
+    }
+
+    /// Checks that the uAuthority has no ID, or an ID of 1 to [`MAX_AUTHORITY_ID_LENGTH`] bytes, so that it can be
+    /// serialized into micro form without truncating its length.
+    pub fn remote_id_conforms(&self) -> bool {
+        self.validate_remote_id().is_ok()
+    }
+
+    /// Validates the ID of the uAuthority, if it has one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `AuthorityIdError` if the ID is empty or longer than [`MAX_AUTHORITY_ID_LENGTH`] bytes.
+    pub fn validate_remote_id(&self) -> Result<(), AuthorityIdError> {
+        match self.get_id() {
+            Some([]) => Err(AuthorityIdError::Empty),
+            Some(id) if id.len() > MAX_AUTHORITY_ID_LENGTH => {
+                Err(AuthorityIdError::TooLong(id.len()))
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn set_name<T>(&mut self, name: T) -> &mut Self
     where
         T: Into<String>,
@@ -66,3 +348,99 @@ impl UAuthority {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn test_get_authority_ip() {
+        let mut authority = UAuthority::default();
+        assert_eq!(authority.get_authority_ip(), None);
+
+        authority.set_ip(vec![10, 0, 3, 3]);
+        assert_eq!(
+            authority.get_authority_ip(),
+            Some(AuthorityIp::V4([10, 0, 3, 3]))
+        );
+
+        authority.set_ip(vec![0; 16]);
+        assert_eq!(authority.get_authority_ip(), Some(AuthorityIp::V6([0; 16])));
+
+        authority.set_ip(vec![127, 1, 23, 123, 12, 6]);
+        assert_eq!(authority.get_authority_ip(), None);
+    }
+
+    #[test]
+    fn test_get_authority_id() {
+        let mut authority = UAuthority::default();
+        authority.set_id(vec![1, 2, 3]);
+        assert_eq!(authority.get_authority_id().unwrap().as_bytes(), &[1, 2, 3]);
+
+        authority.set_id(vec![0; MAX_AUTHORITY_ID_LENGTH]);
+        assert_eq!(
+            authority.get_authority_id().unwrap().as_bytes().len(),
+            MAX_AUTHORITY_ID_LENGTH
+        );
+
+        authority.set_id(vec![0; MAX_AUTHORITY_ID_LENGTH + 1]);
+        assert!(authority.get_authority_id().is_none());
+    }
+
+    #[test]
+    fn test_address_type() {
+        let mut authority = UAuthority::default();
+        assert_eq!(authority.address_type(), Some(AddressType::Local));
+
+        authority.set_ip(vec![10, 0, 3, 3]);
+        assert_eq!(authority.address_type(), Some(AddressType::IPv4));
+        authority.set_ip(vec![0; 16]);
+        assert_eq!(authority.address_type(), Some(AddressType::IPv6));
+        authority.set_ip(vec![0; 6]);
+        assert_eq!(authority.address_type(), None);
+
+        authority.set_id(vec![1, 2, 3]);
+        assert_eq!(authority.address_type(), Some(AddressType::ID));
+        authority.set_id(Vec::new());
+        assert_eq!(authority.address_type(), None);
+        authority.set_name("vcu.my_car_vin");
+        assert_eq!(authority.address_type(), None);
+    }
+
+    #[test]
+    fn test_remote_id_conforms() {
+        let mut authority = UAuthority::default();
+        assert!(authority.remote_id_conforms());
+
+        authority.set_id(vec![0; MAX_AUTHORITY_ID_LENGTH]);
+        assert!(authority.remote_id_conforms());
+        authority.set_id(Vec::new());
+        assert_eq!(authority.validate_remote_id(), Err(AuthorityIdError::Empty));
+        authority.set_id(vec![0; 300]);
+        assert_eq!(
+            authority.validate_remote_id(),
+            Err(AuthorityIdError::TooLong(300))
+        );
+        assert!(!authority.remote_id_conforms());
+    }
+
+    #[test]
+    fn test_parse_address_type() {
+        assert_eq!(AddressType::try_from(2u8), Ok(AddressType::IPv6));
+        assert_eq!(AddressType::try_from(4u8), Err(()));
+        assert_eq!(AddressType::try_from(-1), Err(()));
+        assert_eq!("IPv4".parse(), Ok(AddressType::IPv4));
+        assert_eq!("id".parse(), Ok(AddressType::ID));
+        assert_eq!("name".parse::<AddressType>(), Err(()));
+        assert_eq!(AddressType::ID.to_string(), "id");
+        assert_eq!(
+            AddressType::of_micro_uri(&[1, 3, 0, 0]),
+            Some(AddressType::ID)
+        );
+        assert_eq!(AddressType::of_micro_uri(&[1]), None);
+        assert!(AddressType::IPv4.is_ip() && !AddressType::Local.is_ip());
+    }
+}
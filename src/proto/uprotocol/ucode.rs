@@ -0,0 +1,152 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use alloc::format;
+
+use crate::types::validationerror::ValidationError;
+use crate::uprotocol::UCode;
+
+impl UCode {
+    /// Checks if a value is one of the codes defined by the uProtocol specification.
+    pub fn is_valid_code(value: i32) -> bool {
+        UCode::try_from(value).is_ok()
+    }
+
+    /// Converts a value into a code, failing for values that the uProtocol specification does not define,
+    /// instead of falling back to a default.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` with field `code` and code `UCode::InvalidArgument` if the value is not a
+    /// code.
+    pub fn try_from_value(value: i32) -> Result<UCode, ValidationError> {
+        UCode::try_from(value).map_err(|_e| {
+            ValidationError::new(format!("Unknown code [{value}]")).with_field("code")
+        })
+    }
+
+    /// Checks if the code reports a failure caused by the request or the caller, like `UCode::InvalidArgument`
+    /// or `UCode::PermissionDenied`, so that the request fails again unless it is changed.
+    pub fn is_client_error(&self) -> bool {
+        matches!(
+            self,
+            UCode::InvalidArgument
+                | UCode::NotFound
+                | UCode::AlreadyExists
+                | UCode::PermissionDenied
+                | UCode::Unauthenticated
+                | UCode::FailedPrecondition
+                | UCode::OutOfRange
+        )
+    }
+
+    /// Checks if the code reports a failure of the service or the transport, like `UCode::Internal` or
+    /// `UCode::Unavailable`, rather than of the request.
+    pub fn is_server_error(&self) -> bool {
+        matches!(
+            self,
+            UCode::Unknown
+                | UCode::DeadlineExceeded
+                | UCode::Unimplemented
+                | UCode::Internal
+                | UCode::Unavailable
+                | UCode::DataLoss
+        )
+    }
+
+    /// Checks if an operation that failed with the code may succeed when being retried, as is the case for
+    /// transient failures like `UCode::Unavailable`, `UCode::DeadlineExceeded`, `UCode::ResourceExhausted` and
+    /// `UCode::Aborted`.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            UCode::Unavailable
+                | UCode::DeadlineExceeded
+                | UCode::ResourceExhausted
+                | UCode::Aborted
+        )
+    }
+
+    /// Gets the HTTP status code that corresponds to the code, following the mapping of `google.rpc.Code`.
+    pub fn http_equivalent(&self) -> u16 {
+        match self {
+            UCode::Ok => 200,
+            UCode::InvalidArgument | UCode::FailedPrecondition | UCode::OutOfRange => 400,
+            UCode::Unauthenticated => 401,
+            UCode::PermissionDenied => 403,
+            UCode::NotFound => 404,
+            UCode::AlreadyExists | UCode::Aborted => 409,
+            UCode::ResourceExhausted => 429,
+            UCode::Cancelled => 499,
+            UCode::Unknown | UCode::Internal | UCode::DataLoss => 500,
+            UCode::Unimplemented => 501,
+            UCode::Unavailable => 503,
+            UCode::DeadlineExceeded => 504,
+        }
+    }
+
+    /// Gets the gRPC status code that corresponds to the code.
+    ///
+    /// The uProtocol codes are the gRPC codes, so that the numeric value of the code is returned.
+    pub fn grpc_equivalent(&self) -> i32 {
+        *self as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case(UCode::Ok, false, false, false, 200; "ok")]
+    #[test_case(UCode::Cancelled, false, false, false, 499; "cancelled")]
+    #[test_case(UCode::InvalidArgument, true, false, false, 400; "invalid argument")]
+    #[test_case(UCode::NotFound, true, false, false, 404; "not found")]
+    #[test_case(UCode::Unauthenticated, true, false, false, 401; "unauthenticated")]
+    #[test_case(UCode::ResourceExhausted, false, false, true, 429; "resource exhausted")]
+    #[test_case(UCode::Aborted, false, false, true, 409; "aborted")]
+    #[test_case(UCode::Internal, false, true, false, 500; "internal")]
+    #[test_case(UCode::Unavailable, false, true, true, 503; "unavailable")]
+    #[test_case(UCode::DeadlineExceeded, false, true, true, 504; "deadline exceeded")]
+    fn test_classification(
+        code: UCode,
+        client_error: bool,
+        server_error: bool,
+        retryable: bool,
+        http: u16,
+    ) {
+        assert_eq!(code.is_client_error(), client_error);
+        assert_eq!(code.is_server_error(), server_error);
+        assert_eq!(code.is_retryable(), retryable);
+        assert_eq!(code.http_equivalent(), http);
+    }
+
+    #[test]
+    fn test_grpc_equivalent() {
+        assert_eq!(UCode::Ok.grpc_equivalent(), 0);
+        assert_eq!(UCode::InvalidArgument.grpc_equivalent(), 3);
+        assert_eq!(UCode::Unauthenticated.grpc_equivalent(), 16);
+    }
+
+    #[test]
+    fn test_try_from_value() {
+        assert_eq!(UCode::try_from_value(14).unwrap(), UCode::Unavailable);
+        assert!(UCode::is_valid_code(16));
+        assert!(!UCode::is_valid_code(17));
+
+        let error = UCode::try_from_value(-1).unwrap_err();
+        assert_eq!(error.field(), Some("code"));
+        assert_eq!(error.message(), "Unknown code [-1]");
+    }
+}
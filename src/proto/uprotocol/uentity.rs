@@ -11,10 +11,311 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use crate::uprotocol::UEntity;
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use crate::types::validationerror::ValidationError;
+use crate::uprotocol::{UCode, UEntity};
+
+/// The ID of a uEntity, which the micro form of URIs limits to 16 bits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UEntityId(u16);
+
+impl UEntityId {
+    pub const fn new(id: u16) -> UEntityId {
+        UEntityId(id)
+    }
+
+    pub const fn value(self) -> u16 {
+        self.0
+    }
+}
+
+impl From<u16> for UEntityId {
+    fn from(id: u16) -> Self {
+        UEntityId(id)
+    }
+}
+
+impl From<UEntityId> for u32 {
+    fn from(id: UEntityId) -> Self {
+        u32::from(id.0)
+    }
+}
+
+impl TryFrom<u32> for UEntityId {
+    type Error = ValidationError;
+
+    /// Fails with a `ValidationError` with field `id` and code `UCode::OutOfRange` for IDs above 16 bits.
+    fn try_from(id: u32) -> Result<Self, Self::Error> {
+        u16::try_from(id).map(UEntityId).map_err(|_e| {
+            ValidationError::new(format!("uEntity ID [{id}] does not fit into micro form"))
+                .with_field("id")
+                .with_code(UCode::OutOfRange)
+        })
+    }
+}
+
+/// The major version of a uEntity, which the micro form of URIs limits to 8 bits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VersionMajor(u8);
+
+impl VersionMajor {
+    pub const fn new(version: u8) -> VersionMajor {
+        VersionMajor(version)
+    }
+
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+}
+
+impl From<u8> for VersionMajor {
+    fn from(version: u8) -> Self {
+        VersionMajor(version)
+    }
+}
+
+impl From<VersionMajor> for u32 {
+    fn from(version: VersionMajor) -> Self {
+        u32::from(version.0)
+    }
+}
+
+impl TryFrom<u32> for VersionMajor {
+    type Error = ValidationError;
+
+    /// Fails with a `ValidationError` with field `version_major` and code `UCode::OutOfRange` for versions above
+    /// 8 bits.
+    fn try_from(version: u32) -> Result<Self, Self::Error> {
+        u8::try_from(version).map(VersionMajor).map_err(|_e| {
+            ValidationError::new(format!(
+                "uEntity version [{version}] does not fit into micro form"
+            ))
+            .with_field("version_major")
+            .with_code(UCode::OutOfRange)
+        })
+    }
+}
 
 impl UEntity {
-    pub fn has_id(entity: &UEntity) -> bool {
-        entity.id.is_some()
+    pub fn has_id(&self) -> bool {
+        self.id.is_some()
+    }
+
+    /// Gets the name of this uEntity, like `body.access`.
+    ///
+    /// # Returns
+    ///
+    /// The name, or `None` if the uEntity is identified by its ID only.
+    pub fn get_name(&self) -> Option<&str> {
+        (!self.name.is_empty()).then_some(self.name.as_str())
+    }
+
+    /// Creates a uEntity that is identified by its ID, like in URIs in micro form.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the uEntity.
+    /// * `version_major` - The major version of the uEntity.
+    pub fn from_id(id: UEntityId, version_major: VersionMajor) -> UEntity {
+        UEntity {
+            id: Some(id.into()),
+            version_major: Some(version_major.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the name of this uEntity, like `body.access`.
+    #[must_use]
+    pub fn with_name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the ID of this uEntity.
+    #[must_use]
+    pub fn with_id(mut self, id: UEntityId) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the major version of this uEntity.
+    #[must_use]
+    pub fn with_version_major(mut self, version_major: VersionMajor) -> Self {
+        self.version_major = Some(version_major.into());
+        self
+    }
+
+    /// Sets the minor version of this uEntity.
+    #[must_use]
+    pub fn with_version_minor(mut self, version_minor: u32) -> Self {
+        self.version_minor = Some(version_minor);
+        self
+    }
+
+    /// Checks if this uEntity can be serialized into a micro form URI, which has 16 bits for its ID and 8 bits for
+    /// its major version.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` with field `id` and code `UCode::InvalidArgument` if the ID is missing, or a
+    /// `ValidationError` with code `UCode::OutOfRange` if the ID or the major version do not fit into the micro form.
+    pub fn validate_micro_form(&self) -> Result<(), ValidationError> {
+        self.micro_form().map(|_| ())
+    }
+
+    /// Gets the ID and the major version of this uEntity as they are serialized into a micro form URI, where a
+    /// missing major version is serialized as 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`UEntity::validate_micro_form`].
+    pub fn micro_form(&self) -> Result<(UEntityId, VersionMajor), ValidationError> {
+        let Some(id) = self.id else {
+            return Err(ValidationError::new("Missing uEntity ID").with_field("id"));
+        };
+        let id = UEntityId::try_from(id)?;
+        let version_major = self
+            .version_major
+            .map_or(Ok(VersionMajor::default()), VersionMajor::try_from)?;
+        Ok((id, version_major))
+    }
+}
+
+impl From<&str> for UEntity {
+    /// Parses a uEntity from its part of a URI in long form, like `body.access/1`, whose major version is left
+    /// unset if it is missing or not a number.
+    fn from(value: &str) -> Self {
+        let (name, version_major) = match value.split_once('/') {
+            Some((name, version)) => (name, version.parse::<u32>().ok()),
+            None => (value, None),
+        };
+
+        UEntity {
+            name: name.to_string(),
+            version_major,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<String> for UEntity {
+    fn from(value: String) -> Self {
+        Self::from(value.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_micro_form() {
+        let entity = UEntity {
+            id: Some(0xffff),
+            version_major: Some(0xff),
+            ..Default::default()
+        };
+        assert!(entity.validate_micro_form().is_ok());
+    }
+
+    #[test]
+    fn test_validate_micro_form_fails_for_missing_id() {
+        let entity = UEntity {
+            name: "body.access".to_string(),
+            ..Default::default()
+        };
+        let error = entity.validate_micro_form().unwrap_err();
+        assert_eq!(error.code(), UCode::InvalidArgument);
+        assert_eq!(error.field(), Some("id"));
+    }
+
+    #[test]
+    fn test_validate_micro_form_fails_for_oversized_id() {
+        let entity = UEntity {
+            id: Some(0x1_0000),
+            ..Default::default()
+        };
+        let error = entity.validate_micro_form().unwrap_err();
+        assert_eq!(error.code(), UCode::OutOfRange);
+        assert_eq!(error.field(), Some("id"));
+        assert_eq!(
+            error.message(),
+            "uEntity ID [65536] does not fit into micro form"
+        );
+    }
+
+    #[test]
+    fn test_validate_micro_form_fails_for_oversized_version() {
+        let entity = UEntity {
+            id: Some(0x1102),
+            version_major: Some(0x100),
+            ..Default::default()
+        };
+        let error = entity.validate_micro_form().unwrap_err();
+        assert_eq!(error.code(), UCode::OutOfRange);
+        assert_eq!(error.field(), Some("version_major"));
+    }
+
+    #[test]
+    fn test_setters() {
+        let entity = UEntity::default()
+            .with_name("body.access")
+            .with_id(UEntityId::new(0x1102))
+            .with_version_major(VersionMajor::new(1))
+            .with_version_minor(2);
+        assert_eq!(
+            entity,
+            UEntity {
+                name: "body.access".to_string(),
+                id: Some(0x1102),
+                version_major: Some(1),
+                version_minor: Some(2),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_accessors() {
+        let entity = UEntity::from_id(UEntityId::new(0x1102), VersionMajor::new(1));
+        assert!(entity.has_id());
+        assert_eq!(entity.get_name(), None);
+
+        let entity = entity.with_name("body.access");
+        assert_eq!(entity.get_name(), Some("body.access"));
+        assert!(!UEntity::default().has_id());
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            UEntity::from("body.access/1"),
+            UEntity::default()
+                .with_name("body.access")
+                .with_version_major(VersionMajor::new(1))
+        );
+        assert_eq!(
+            UEntity::from("body.access"),
+            UEntity::default().with_name("body.access")
+        );
+        assert_eq!(UEntity::from("body.access/x").version_major, None);
+        assert_eq!(
+            UEntity::from("body.access/2".to_string()).version_major,
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_micro_form_round_trip() {
+        let entity = UEntity::from_id(UEntityId::new(0x1102), VersionMajor::new(1));
+        assert_eq!(entity.id, Some(0x1102));
+        assert_eq!(
+            entity.micro_form().unwrap(),
+            (UEntityId::new(0x1102), VersionMajor::new(1))
+        );
+        assert!(UEntityId::try_from(0xffff).is_ok());
+        assert!(VersionMajor::try_from(0x100).is_err());
     }
 }
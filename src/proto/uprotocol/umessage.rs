@@ -0,0 +1,164 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+#[cfg(feature = "std")]
+use crate::uprotocol::UPayload;
+use crate::uprotocol::{UAttributes, UMessage, UPriority, UUri};
+
+impl UMessage {
+    /// Changes the attributes of this message, keeping its payload where it is.
+    ///
+    /// The message is taken by value, so that components like forwarders can change the header of a message they
+    /// own without cloning its payload, which can be megabytes large. Messages without attributes get default ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `change` - The function changing the attributes.
+    ///
+    /// # Returns
+    ///
+    /// The message.
+    #[must_use]
+    pub fn with_attributes<F>(mut self, change: F) -> Self
+    where
+        F: FnOnce(&mut UAttributes),
+    {
+        change(self.attributes.get_or_insert_with(UAttributes::default));
+        self
+    }
+
+    /// Changes the priority of this message, without copying its payload.
+    #[must_use]
+    pub fn with_priority(self, priority: UPriority) -> Self {
+        self.with_attributes(|attributes| attributes.priority = priority.into())
+    }
+
+    /// Changes the sink of this message, without copying its payload.
+    #[must_use]
+    pub fn with_sink(self, sink: UUri) -> Self {
+        self.with_attributes(|attributes| attributes.sink = Some(sink))
+    }
+
+    /// Changes the source of this message, without copying its payload.
+    #[must_use]
+    pub fn with_source(mut self, source: UUri) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Gets the size of the payload data of this message, for transports that send the data as it is and need to
+    /// know the size of a message before serializing it. The size of the message as protobuf message is its
+    /// `encoded_len()`.
+    ///
+    /// # Returns
+    ///
+    /// The [`UPayload::data_len`] of the payload, or 0 if the message has none.
+    #[cfg(feature = "std")]
+    pub fn payload_len(&self) -> usize {
+        self.payload.as_ref().map_or(0, UPayload::data_len)
+    }
+
+    /// Creates a copy of this message with other attributes, for sending a message to several destinations.
+    ///
+    /// The payload is cloned once per copy, as the generated `UPayload` owns its data; prefer the methods that take
+    /// the message by value for the last, or only, destination.
+    ///
+    /// # Arguments
+    ///
+    /// * `change` - The function changing the attributes of the copy.
+    ///
+    /// # Returns
+    ///
+    /// The copy.
+    #[must_use]
+    pub fn clone_with_attributes<F>(&self, change: F) -> Self
+    where
+        F: FnOnce(&mut UAttributes),
+    {
+        self.clone().with_attributes(change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::vec;
+
+    use prost::Message;
+
+    use crate::uprotocol::{Data, UPayload, UPayloadFormat, UResource};
+
+    fn build_message_for_test() -> UMessage {
+        UMessage {
+            source: Some(UUri {
+                resource: Some(UResource::from("door.front_left#Door")),
+                ..Default::default()
+            }),
+            attributes: Some(UAttributes {
+                priority: UPriority::UpriorityCs1.into(),
+                ttl: Some(1000),
+                ..Default::default()
+            }),
+            payload: Some(UPayload {
+                length: Some(4),
+                format: UPayloadFormat::UpayloadFormatRaw.into(),
+                data: Some(Data::Value(vec![1, 2, 3, 4])),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_with_priority_keeps_payload() {
+        let message = build_message_for_test();
+        let data = match message.payload.as_ref().and_then(|p| p.data.as_ref()) {
+            Some(Data::Value(data)) => data.as_ptr(),
+            _ => panic!("message has no data"),
+        };
+
+        let message = message
+            .with_priority(UPriority::UpriorityCs4)
+            .with_sink(UUri::default());
+        let attributes = message.attributes.as_ref().unwrap();
+        assert_eq!(attributes.priority, UPriority::UpriorityCs4.into());
+        assert_eq!(attributes.sink, Some(UUri::default()));
+        assert_eq!(attributes.ttl, Some(1000));
+        assert!(matches!(
+            message.payload.and_then(|p| p.data),
+            Some(Data::Value(moved)) if moved.as_ptr() == data
+        ));
+    }
+
+    #[test]
+    fn test_with_attributes_creates_missing_attributes() {
+        let message = UMessage::default().with_priority(UPriority::UpriorityCs2);
+        assert_eq!(
+            message.attributes.unwrap().priority,
+            UPriority::UpriorityCs2.into()
+        );
+
+        let original = build_message_for_test();
+        let copy = original.clone_with_attributes(|attributes| attributes.ttl = None);
+        assert_eq!(original.attributes.unwrap().ttl, Some(1000));
+        assert_eq!(copy.attributes.unwrap().ttl, None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_payload_len() {
+        let message = build_message_for_test();
+        assert_eq!(message.payload_len(), 4);
+        assert!(message.encoded_len() > message.payload_len());
+        assert_eq!(UMessage::default().payload_len(), 0);
+    }
+}
@@ -11,10 +11,34 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use std::fmt::Display;
+use alloc::format;
+use alloc::string::{String, ToString};
 
+use core::fmt::Display;
+
+use crate::types::validationerror::ValidationError;
 use crate::uprotocol::UMessageType;
 
+impl UMessageType {
+    /// Checks if a value is one of the message types defined by the uProtocol specification.
+    pub fn is_valid_message_type(value: i32) -> bool {
+        UMessageType::try_from(value).is_ok()
+    }
+
+    /// Converts a value into a message type, failing for values that the uProtocol specification does not define,
+    /// instead of falling back to a default.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` with field `type` and code `UCode::InvalidArgument` if the value is not a
+    /// message type.
+    pub fn try_from_value(value: i32) -> Result<UMessageType, ValidationError> {
+        UMessageType::try_from(value).map_err(|_e| {
+            ValidationError::new(format!("Unknown message type [{value}]")).with_field("type")
+        })
+    }
+}
+
 impl From<UMessageType> for String {
     fn from(value: UMessageType) -> Self {
         value.to_string()
@@ -33,7 +57,7 @@ impl From<&str> for UMessageType {
 }
 
 impl Display for UMessageType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             UMessageType::UmessageTypePublish => write!(f, "pub.v1"),
             UMessageType::UmessageTypeRequest => write!(f, "req.v1"),
@@ -42,3 +66,22 @@ impl Display for UMessageType {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_value() {
+        assert_eq!(
+            UMessageType::try_from_value(UMessageType::UmessageTypeRequest.into()).unwrap(),
+            UMessageType::UmessageTypeRequest
+        );
+        assert!(UMessageType::is_valid_message_type(0));
+        assert!(!UMessageType::is_valid_message_type(4));
+        assert_eq!(
+            UMessageType::try_from_value(4).unwrap_err().message(),
+            "Unknown message type [4]"
+        );
+    }
+}
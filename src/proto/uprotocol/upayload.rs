@@ -11,11 +11,19 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+#[cfg(feature = "shared-bytes")]
+use bytes::Bytes;
+use prost::encoding::{
+    decode_key, decode_varint, encode_key, encode_varint, encoded_len_varint, key_len, skip_field,
+    DecodeContext, WireType,
+};
 use prost::Message;
 use prost_types::Any;
+use serde_json::Value;
 
 use crate::{
-    cloudevent::serializer::SerializationError,
+    transport::builder::UPayloadBuilder,
+    types::serializationerror::SerializationError,
     uprotocol::{Data, UPayload, UPayloadFormat},
 };
 
@@ -47,10 +55,10 @@ impl TryFrom<UPayload> for Any {
     fn try_from(value: UPayload) -> Result<Self, Self::Error> {
         match value.format() {
             UPayloadFormat::UpayloadFormatProtobuf | UPayloadFormat::UpayloadFormatUnspecified => {
-                if let Some(bytes) = data_to_slice(&value) {
+                if let Some(bytes) = value.resolve_data(None)? {
                     if !bytes.is_empty() {
                         return Any::decode(bytes).map_err(|e| {
-                            SerializationError::new(format!("UPayload does not contain Any: {}", e))
+                            SerializationError::decode("UPayload does not contain Any", e)
                         });
                     }
                 }
@@ -63,30 +71,288 @@ impl TryFrom<UPayload> for Any {
     }
 }
 
-fn data_to_slice(payload: &UPayload) -> Option<&[u8]> {
-    if let Some(data) = &payload.data {
-        match data {
-            Data::Reference(bytes) => {
-                if let Some(length) = payload.length {
-                    return Some(unsafe { read_memory(*bytes, length) });
+impl UPayload {
+    /// Gets the size of the data of this payload, e.g. for transports with a limited frame size that need to know
+    /// the size of a message before serializing it. The size of the payload as protobuf message is its
+    /// `encoded_len()`.
+    ///
+    /// # Returns
+    ///
+    /// The length of the data carried by value, or the `length` of the data referred to.
+    pub fn data_len(&self) -> usize {
+        match &self.data {
+            Some(Data::Value(bytes)) => bytes.len(),
+            Some(Data::Reference(_)) => self
+                .length
+                .and_then(|length| usize::try_from(length).ok())
+                .unwrap_or_default(),
+            None => 0,
+        }
+    }
+
+    /// Creates a payload containing text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to put into the payload.
+    ///
+    /// # Returns
+    ///
+    /// A payload with format `UpayloadFormatText`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the text does not fit into a `UPayload`.
+    pub fn from_text(text: &str) -> Result<UPayload, SerializationError> {
+        UPayloadBuilder::from_text(text).build()
+    }
+
+    /// Creates a payload containing a JSON document.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The JSON document to put into the payload.
+    ///
+    /// # Returns
+    ///
+    /// A payload with format `UpayloadFormatJson`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the document does not fit into a `UPayload`.
+    pub fn from_json_value(value: &Value) -> Result<UPayload, SerializationError> {
+        UPayloadBuilder::from_json(value)?.build()
+    }
+
+    /// Creates a payload containing an encoded protobuf message, wrapped in a `google.protobuf.Any`.
+    ///
+    /// The payload equals the one created from an `Any` with the given type URL and value, but without
+    /// copying the message into an `Any` first, e.g. for passing on messages that are encoded already.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_url` - The type URL of the message, like `type.googleapis.com/uprotocol.v1.UStatus`.
+    /// * `message` - The encoded message.
+    ///
+    /// # Returns
+    ///
+    /// A payload with format `UpayloadFormatProtobuf`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the message does not fit into a `UPayload`.
+    pub fn from_message_bytes(
+        type_url: &str,
+        message: &[u8],
+    ) -> Result<UPayload, SerializationError> {
+        let fields = [
+            (ANY_TYPE_URL_TAG, type_url.as_bytes()),
+            (ANY_VALUE_TAG, message),
+        ];
+        let encoded_len = fields
+            .iter()
+            .filter(|(_tag, field)| !field.is_empty())
+            .map(|(tag, field)| {
+                key_len(*tag) + encoded_len_varint(field.len() as u64) + field.len()
+            })
+            .sum();
+        let length = i32::try_from(encoded_len)
+            .map_err(|_e| SerializationError::new("Message does not fit into UPayload"))?;
+
+        // empty fields are omitted, as they are when encoding an Any
+        let mut data = Vec::with_capacity(encoded_len);
+        for (tag, field) in fields.into_iter().filter(|(_tag, field)| !field.is_empty()) {
+            encode_key(tag, WireType::LengthDelimited, &mut data);
+            encode_varint(field.len() as u64, &mut data);
+            data.extend_from_slice(field);
+        }
+        Ok(UPayload {
+            data: Some(Data::Value(data)),
+            length: Some(length),
+            format: UPayloadFormat::UpayloadFormatProtobuf.into(),
+        })
+    }
+
+    /// Gets the protobuf message contained in this payload, without decoding the `google.protobuf.Any` that
+    /// wraps it into a copy.
+    ///
+    /// # Returns
+    ///
+    /// The type URL and the encoded message, both referring to the payload's data.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` for the payloads that `Any::try_from(UPayload)` fails for.
+    pub fn message_bytes(&self) -> Result<(&str, &[u8]), SerializationError> {
+        match self.format() {
+            UPayloadFormat::UpayloadFormatProtobuf | UPayloadFormat::UpayloadFormatUnspecified => {
+                match self.resolve_data(None)? {
+                    Some(bytes) if !bytes.is_empty() => decode_any_fields(bytes).map_err(|e| {
+                        SerializationError::decode("UPayload does not contain Any", e)
+                    }),
+                    _ => Err(SerializationError::new(
+                        "UPayload does not contain any data",
+                    )),
                 }
             }
-            Data::Value(bytes) => {
-                return Some(bytes.as_slice());
-            }
+            _ => Err(SerializationError::new("UPayload has incompatible format")),
+        }
+    }
+
+    /// Gets the text contained in this payload.
+    ///
+    /// # Returns
+    ///
+    /// The text, or an empty string if the payload does not contain any data.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload does not have format `UpayloadFormatText`,
+    /// refers to shared memory, or does not contain valid UTF-8.
+    pub fn as_str(&self) -> Result<&str, SerializationError> {
+        let data = self.data_with_format(UPayloadFormat::UpayloadFormatText)?;
+        std::str::from_utf8(data)
+            .map_err(|e| SerializationError::utf8("UPayload does not contain text", e))
+    }
+
+    /// Gets the JSON document contained in this payload.
+    ///
+    /// # Returns
+    ///
+    /// The parsed JSON document.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload does not have format `UpayloadFormatJson`,
+    /// refers to shared memory, or does not contain a valid JSON document.
+    pub fn to_json_value(&self) -> Result<Value, SerializationError> {
+        let data = self.data_with_format(UPayloadFormat::UpayloadFormatJson)?;
+        serde_json::from_slice(data)
+            .map_err(|e| SerializationError::json("UPayload does not contain JSON", e))
+    }
+
+    fn data_with_format(&self, format: UPayloadFormat) -> Result<&[u8], SerializationError> {
+        if self.format() != format {
+            return Err(SerializationError::new(format!(
+                "UPayload has format {} instead of {}",
+                self.format().as_str_name(),
+                format.as_str_name()
+            )));
+        }
+        Ok(self.resolve_data(None)?.unwrap_or_default())
+    }
+}
+
+const ANY_TYPE_URL_TAG: u32 = 1;
+const ANY_VALUE_TAG: u32 = 2;
+
+// Decodes the fields of an encoded `google.protobuf.Any` in place. Like prost does, fields of other tags are
+// skipped and fields that occur repeatedly take their last value.
+fn decode_any_fields(mut buf: &[u8]) -> Result<(&str, &[u8]), prost::DecodeError> {
+    let mut type_url = "";
+    let mut value: &[u8] = &[];
+    while !buf.is_empty() {
+        let (tag, wire_type) = decode_key(&mut buf)?;
+        if wire_type != WireType::LengthDelimited
+            || (tag != ANY_TYPE_URL_TAG && tag != ANY_VALUE_TAG)
+        {
+            skip_field(wire_type, tag, &mut buf, DecodeContext::default())?;
+            continue;
+        }
+        let length = usize::try_from(decode_varint(&mut buf)?)
+            .ok()
+            .filter(|length| *length <= buf.len())
+            .ok_or_else(|| prost::DecodeError::new("buffer underflow"))?;
+        let (field, rest) = buf.split_at(length);
+        buf = rest;
+        if tag == ANY_TYPE_URL_TAG {
+            type_url = std::str::from_utf8(field).map_err(|_e| {
+                prost::DecodeError::new("invalid string value: data is not UTF-8 encoded")
+            })?;
+        } else {
+            value = field;
         }
     }
-    None
+    Ok((type_url, value))
+}
+
+/// A `google.protobuf.Any` whose value refers to the buffer it has been decoded from, instead of holding a copy of it.
+#[cfg(feature = "shared-bytes")]
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct AnyView {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "bytes", tag = "2")]
+    pub value: Bytes,
 }
 
-// Please no one use this...
-unsafe fn read_memory(_address: u64, _length: i32) -> &'static [u8] {
-    // Convert the raw address to a pointer
-    // let ptr = address as *const u8;
-    // Create a slice from the pointer and the length
-    // slice::from_raw_parts(ptr, length as usize)
+#[cfg(feature = "shared-bytes")]
+impl UPayload {
+    /// Creates a payload from data held in `Bytes`, as a convenience for code that receives its data that way.
+    ///
+    /// A payload holds its data in a `Vec<u8>`, so `data` is copied into a new vector, unless it is the only
+    /// reference to its buffer, which the payload then takes over.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The payload data.
+    /// * `format` - The format of the payload data.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the data does not fit into a `UPayload`.
+    pub fn copy_from_bytes(
+        data: Bytes,
+        format: UPayloadFormat,
+    ) -> Result<UPayload, SerializationError> {
+        let length = i32::try_from(data.len())
+            .map_err(|_e| SerializationError::new("Data does not fit into UPayload"))?;
+        Ok(UPayload {
+            data: Some(Data::Value(Vec::from(data))),
+            length: Some(length),
+            format: format.into(),
+        })
+    }
+
+    /// Consumes this payload and returns its data as `Bytes`, which take over the payload's vector without copying
+    /// it, as a convenience for code that passes its data on that way.
+    ///
+    /// # Returns
+    ///
+    /// The payload's data, or `None` if the payload does not contain any data by value.
+    pub fn into_data_bytes(self) -> Option<Bytes> {
+        match self.data {
+            Some(Data::Value(bytes)) => Some(Bytes::from(bytes)),
+            _ => None,
+        }
+    }
 
-    todo!("This is not implemented yet")
+    /// Consumes this payload and decodes the `Any` contained in it, without copying the `Any`'s value.
+    ///
+    /// # Returns
+    ///
+    /// The decoded `Any`, and the buffer it refers to.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload does not contain a protobuf encoded `Any`.
+    pub(crate) fn into_any_view(self) -> Result<(AnyView, Bytes), SerializationError> {
+        match self.format() {
+            UPayloadFormat::UpayloadFormatProtobuf | UPayloadFormat::UpayloadFormatUnspecified => {
+                match self.into_data_bytes() {
+                    Some(bytes) if !bytes.is_empty() => AnyView::decode(bytes.clone())
+                        .map(|any| (any, bytes))
+                        .map_err(|e| {
+                            SerializationError::decode("UPayload does not contain Any", e)
+                        }),
+                    _ => Err(SerializationError::new(
+                        "UPayload does not contain any data",
+                    )),
+                }
+            }
+            _ => Err(SerializationError::new("UPayload has incompatible format")),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -132,6 +398,87 @@ mod tests {
         assert!(any.is_err());
     }
 
+    #[test]
+    fn test_into_any_fails_for_shared_memory_reference() {
+        let payload = UPayload {
+            format: UPayloadFormat::UpayloadFormatProtobuf as i32,
+            data: Some(Data::Reference(0x1000)),
+            length: Some(4),
+        };
+
+        let any = Any::try_from(payload);
+        assert!(any.is_err());
+    }
+
+    #[cfg(feature = "shared-bytes")]
+    #[test]
+    fn test_data_bytes_round_trip() {
+        let payload = UPayload::copy_from_bytes(
+            Bytes::from_static(&[1, 2, 3]),
+            UPayloadFormat::UpayloadFormatRaw,
+        )
+        .unwrap();
+        assert_eq!(payload.length, Some(3));
+        assert_eq!(payload.format(), UPayloadFormat::UpayloadFormatRaw);
+
+        let bytes = payload.into_data_bytes().unwrap();
+        assert_eq!(bytes, Bytes::from_static(&[1, 2, 3]));
+    }
+
+    #[cfg(feature = "shared-bytes")]
+    #[test]
+    fn test_into_any_view_refers_to_payload_data() {
+        let timestamp = Timestamp {
+            seconds: 1_000_000,
+            nanos: 42,
+        };
+        let any = Any::from_msg(&timestamp).unwrap();
+        let payload = UPayload::try_from(&any).unwrap();
+
+        let (view, bytes) = payload.into_any_view().unwrap();
+        assert_eq!(view.type_url, any.type_url);
+        assert_eq!(view.value, any.value);
+        assert!(bytes.as_ptr_range().contains(&view.value.as_ptr()));
+        assert_eq!(Timestamp::decode(view.value).unwrap(), timestamp);
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let payload = UPayload::from_text("hällo").unwrap();
+        assert_eq!(payload.format(), UPayloadFormat::UpayloadFormatText);
+        assert_eq!(payload.as_str().unwrap(), "hällo");
+        assert!(payload.to_json_value().is_err());
+    }
+
+    #[test]
+    fn test_as_str_fails_for_invalid_utf8() {
+        let payload = UPayload {
+            format: UPayloadFormat::UpayloadFormatText as i32,
+            data: Some(Data::Value(vec![0xc3, 0x28])),
+            length: Some(2),
+        };
+        assert!(payload.as_str().is_err());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let value = serde_json::json!({"door": "front_left", "open": true});
+        let payload = UPayload::from_json_value(&value).unwrap();
+        assert_eq!(payload.format(), UPayloadFormat::UpayloadFormatJson);
+        assert_eq!(payload.to_json_value().unwrap(), value);
+        assert!(payload.as_str().is_err());
+    }
+
+    #[test]
+    fn test_to_json_value_fails_for_invalid_json() {
+        let payload = UPayload {
+            format: UPayloadFormat::UpayloadFormatJson as i32,
+            data: Some(Data::Value(b"{open".to_vec())),
+            length: Some(5),
+        };
+        assert!(payload.to_json_value().is_err());
+    }
+
     #[test]
     fn test_from_any() {
         let timestamp = Timestamp::default();
@@ -144,4 +491,83 @@ mod tests {
         );
         assert_eq!(payload.data.unwrap(), Data::Value(any.encode_to_vec()));
     }
+
+    #[test_case("type.googleapis.com/google.protobuf.Timestamp", true; "with type URL and value")]
+    #[test_case("", true; "without type URL")]
+    #[test_case("type.googleapis.com/google.protobuf.Timestamp", false; "without value")]
+    fn test_from_message_bytes_equals_any(type_url: &str, with_value: bool) {
+        let value = if with_value {
+            Timestamp {
+                seconds: 1_000_000,
+                nanos: 5,
+            }
+            .encode_to_vec()
+        } else {
+            vec![]
+        };
+        let any = Any {
+            type_url: type_url.to_string(),
+            value: value.clone(),
+        };
+
+        let payload = UPayload::from_message_bytes(type_url, &value).unwrap();
+        assert_eq!(payload.format(), UPayloadFormat::UpayloadFormatProtobuf);
+        assert_eq!(payload.data, Some(Data::Value(any.encode_to_vec())));
+        assert_eq!(
+            payload.length,
+            Some(i32::try_from(any.encoded_len()).unwrap())
+        );
+        assert_eq!(Any::try_from(payload).unwrap(), any);
+    }
+
+    #[test]
+    fn test_message_bytes() {
+        let timestamp = Timestamp {
+            seconds: 1_000_000,
+            nanos: 5,
+        };
+        let any = Any::from_msg(&timestamp).unwrap();
+        let payload = UPayload::try_from(&any).unwrap();
+
+        let (type_url, message) = payload.message_bytes().unwrap();
+        assert_eq!(type_url, any.type_url);
+        assert_eq!(Timestamp::decode(message).unwrap(), timestamp);
+    }
+
+    #[test]
+    fn test_message_bytes_skips_unknown_fields() {
+        let mut data = Any {
+            type_url: "first".to_string(),
+            value: vec![1],
+        }
+        .encode_to_vec();
+        // an unknown varint field, and the type URL once more
+        data.extend_from_slice(&[0x18, 0x2a]);
+        data.extend(
+            Any {
+                type_url: "second".to_string(),
+                value: vec![],
+            }
+            .encode_to_vec(),
+        );
+        let payload = UPayload {
+            format: UPayloadFormat::UpayloadFormatProtobuf as i32,
+            data: Some(Data::Value(data)),
+            length: None,
+        };
+
+        assert_eq!(payload.message_bytes().unwrap(), ("second", [1].as_slice()));
+    }
+
+    #[test_case(UPayloadFormat::UpayloadFormatJson, vec![0x0a, 0x01, b'a'], "UPayload has incompatible format"; "json")]
+    #[test_case(UPayloadFormat::UpayloadFormatProtobuf, vec![], "UPayload does not contain any data"; "empty")]
+    #[test_case(UPayloadFormat::UpayloadFormatProtobuf, vec![0x0a, 0x05, b'a'], "UPayload does not contain Any"; "truncated")]
+    fn test_message_bytes_fails(format: UPayloadFormat, data: Vec<u8>, error: &str) {
+        let payload = UPayload {
+            format: format.into(),
+            data: Some(Data::Value(data)),
+            length: None,
+        };
+        assert_eq!(payload.message_bytes().unwrap_err().to_string(), error);
+    }
 }
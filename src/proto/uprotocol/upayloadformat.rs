@@ -0,0 +1,250 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::types::validationerror::ValidationError;
+use crate::uprotocol::UPayloadFormat;
+
+impl UPayloadFormat {
+    /// Checks if a value is one of the payload formats defined by the uProtocol specification.
+    pub fn is_valid_payload_format(value: i32) -> bool {
+        UPayloadFormat::try_from(value).is_ok()
+    }
+
+    /// Converts a value into a payload format, failing for values that the uProtocol specification does not define,
+    /// instead of falling back to a default.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` with field `format` and code `UCode::InvalidArgument` if the value is not a
+    /// payload format.
+    pub fn try_from_value(value: i32) -> Result<UPayloadFormat, ValidationError> {
+        UPayloadFormat::try_from(value).map_err(|_e| {
+            ValidationError::new(format!("Unknown payload format [{value}]")).with_field("format")
+        })
+    }
+
+    /// Gets the MIME type corresponding to this payload format.
+    ///
+    /// # Returns
+    ///
+    /// The MIME type, or `None` for `UpayloadFormatUnspecified`.
+    pub fn to_mime_type(&self) -> Option<&'static str> {
+        match self {
+            UPayloadFormat::UpayloadFormatProtobuf => Some("application/x-protobuf"),
+            UPayloadFormat::UpayloadFormatJson => Some("application/json"),
+            UPayloadFormat::UpayloadFormatSomeip => Some("application/x-someip"),
+            UPayloadFormat::UpayloadFormatSomeipTlv => Some("application/x-someip_tlv"),
+            UPayloadFormat::UpayloadFormatRaw => Some("application/octet-stream"),
+            UPayloadFormat::UpayloadFormatText => Some("text/plain"),
+            UPayloadFormat::UpayloadFormatUnspecified => None,
+        }
+    }
+
+    /// Gets the MIME type corresponding to this payload format, including parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - The parameters to append, as (name, value) pairs. Values that are not tokens
+    ///   as defined by [RFC 2045](https://www.rfc-editor.org/rfc/rfc2045#section-5.1) are quoted.
+    ///
+    /// # Returns
+    ///
+    /// The MIME type, e.g. `text/plain; charset=utf-8`, or `None` for `UpayloadFormatUnspecified`.
+    pub fn to_mime_type_with_params(&self, params: &[(&str, &str)]) -> Option<String> {
+        let mut mime_type = self.to_mime_type()?.to_string();
+        for (name, value) in params {
+            mime_type.push_str("; ");
+            mime_type.push_str(name);
+            mime_type.push('=');
+            if !value.is_empty() && value.chars().all(is_token_char) {
+                mime_type.push_str(value);
+            } else {
+                mime_type.push('"');
+                for c in value.chars() {
+                    if c == '"' || c == '\\' {
+                        mime_type.push('\\');
+                    }
+                    mime_type.push(c);
+                }
+                mime_type.push('"');
+            }
+        }
+        Some(mime_type)
+    }
+
+    /// Gets the payload format corresponding to a MIME type.
+    ///
+    /// Parameters of the MIME type are ignored, use [`UPayloadFormat::from_mime_type_with_params`] to retain them.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type, e.g. `application/json` or `text/plain; charset=utf-8`.
+    ///
+    /// # Returns
+    ///
+    /// The payload format, or `None` if the MIME type does not correspond to any of the payload formats.
+    pub fn from_mime_type(mime_type: &str) -> Option<UPayloadFormat> {
+        Self::from_mime_type_with_params(mime_type).map(|(format, _params)| format)
+    }
+
+    /// Gets the payload format and parameters of a MIME type.
+    ///
+    /// # Arguments
+    ///
+    /// * `mime_type` - The MIME type, e.g. `text/plain; charset=utf-8`.
+    ///
+    /// # Returns
+    ///
+    /// The payload format along with the MIME type's parameters as (name, value) pairs, with names in lower case
+    /// and quoted values unquoted. `None` if the MIME type does not correspond to any of the payload formats, or
+    /// if its parameters are malformed.
+    pub fn from_mime_type_with_params(
+        mime_type: &str,
+    ) -> Option<(UPayloadFormat, Vec<(String, String)>)> {
+        let (essence, mut rest) = mime_type.split_once(';').unwrap_or((mime_type, ""));
+        let essence = essence.trim().to_ascii_lowercase();
+        let format = [
+            UPayloadFormat::UpayloadFormatProtobuf,
+            UPayloadFormat::UpayloadFormatJson,
+            UPayloadFormat::UpayloadFormatSomeip,
+            UPayloadFormat::UpayloadFormatSomeipTlv,
+            UPayloadFormat::UpayloadFormatRaw,
+            UPayloadFormat::UpayloadFormatText,
+        ]
+        .into_iter()
+        .find(|format| format.to_mime_type() == Some(essence.as_str()))?;
+
+        let mut params = Vec::new();
+        loop {
+            rest = rest.trim_start_matches(|c: char| c == ';' || c.is_whitespace());
+            if rest.is_empty() {
+                break;
+            }
+            let (name, tail) = rest.split_once('=')?;
+            let name = name.trim();
+            if name.is_empty() || !name.chars().all(is_token_char) {
+                return None;
+            }
+            let tail = tail.trim_start();
+            let value = if let Some(quoted) = tail.strip_prefix('"') {
+                let mut value = String::new();
+                let mut chars = quoted.char_indices();
+                let end = loop {
+                    match chars.next()? {
+                        (_, '\\') => value.push(chars.next()?.1),
+                        (i, '"') => break i + 1,
+                        (_, c) => value.push(c),
+                    }
+                };
+                rest = quoted.get(end..)?;
+                value
+            } else {
+                let end = tail.find(';').unwrap_or(tail.len());
+                rest = tail.get(end..)?;
+                tail.get(..end)?.trim_end().to_string()
+            };
+            params.push((name.to_ascii_lowercase(), value));
+        }
+        Some((format, params))
+    }
+}
+
+// see https://www.rfc-editor.org/rfc/rfc2045#section-5.1
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_graphic() && !"()<>@,;:\\\"/[]?=".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case("application/x-protobuf", Some(UPayloadFormat::UpayloadFormatProtobuf); "protobuf")]
+    #[test_case("application/json", Some(UPayloadFormat::UpayloadFormatJson); "json")]
+    #[test_case("application/x-someip", Some(UPayloadFormat::UpayloadFormatSomeip); "someip")]
+    #[test_case("application/x-someip_tlv", Some(UPayloadFormat::UpayloadFormatSomeipTlv); "someip tlv")]
+    #[test_case("application/octet-stream", Some(UPayloadFormat::UpayloadFormatRaw); "raw")]
+    #[test_case("text/plain", Some(UPayloadFormat::UpayloadFormatText); "text")]
+    #[test_case("Text/Plain; charset=utf-8", Some(UPayloadFormat::UpayloadFormatText); "text with params")]
+    #[test_case("application/vnd.custom+json", None; "unknown type")]
+    #[test_case("", None; "empty type")]
+    fn test_from_mime_type(mime_type: &str, expected: Option<UPayloadFormat>) {
+        assert_eq!(UPayloadFormat::from_mime_type(mime_type), expected);
+    }
+
+    #[test]
+    fn test_from_mime_type_with_params() {
+        let (format, params) = UPayloadFormat::from_mime_type_with_params(
+            "text/plain; Charset=utf-8 ;format=\"flowed; \\\"x\\\"\"",
+        )
+        .unwrap();
+        assert_eq!(format, UPayloadFormat::UpayloadFormatText);
+        assert_eq!(
+            params,
+            vec![
+                ("charset".to_string(), "utf-8".to_string()),
+                ("format".to_string(), "flowed; \"x\"".to_string()),
+            ]
+        );
+    }
+
+    #[test_case("text/plain; charset"; "missing value")]
+    #[test_case("text/plain; =utf-8"; "missing name")]
+    #[test_case("text/plain; charset=\"utf-8"; "unterminated quoted value")]
+    fn test_from_mime_type_with_params_fails_for_malformed_params(mime_type: &str) {
+        assert!(UPayloadFormat::from_mime_type_with_params(mime_type).is_none());
+    }
+
+    #[test]
+    fn test_to_mime_type_with_params_round_trip() {
+        let mime_type = UPayloadFormat::UpayloadFormatText
+            .to_mime_type_with_params(&[("charset", "utf-8"), ("note", "a \"b\"; c")])
+            .unwrap();
+        assert_eq!(
+            mime_type,
+            "text/plain; charset=utf-8; note=\"a \\\"b\\\"; c\""
+        );
+
+        let (format, params) = UPayloadFormat::from_mime_type_with_params(&mime_type).unwrap();
+        assert_eq!(format, UPayloadFormat::UpayloadFormatText);
+        assert_eq!(params[1], ("note".to_string(), "a \"b\"; c".to_string()));
+    }
+
+    #[test]
+    fn test_unspecified_has_no_mime_type() {
+        assert!(UPayloadFormat::UpayloadFormatUnspecified
+            .to_mime_type()
+            .is_none());
+        assert!(UPayloadFormat::UpayloadFormatUnspecified
+            .to_mime_type_with_params(&[("charset", "utf-8")])
+            .is_none());
+    }
+
+    #[test]
+    fn test_try_from_value() {
+        assert_eq!(
+            UPayloadFormat::try_from_value(UPayloadFormat::UpayloadFormatJson.into()).unwrap(),
+            UPayloadFormat::UpayloadFormatJson
+        );
+        assert!(!UPayloadFormat::is_valid_payload_format(42));
+        assert_eq!(
+            UPayloadFormat::try_from_value(42).unwrap_err().field(),
+            Some("format")
+        );
+    }
+}
@@ -0,0 +1,59 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use alloc::format;
+
+use crate::types::validationerror::ValidationError;
+use crate::uprotocol::UPriority;
+
+impl UPriority {
+    /// Checks if a value is one of the prioritys defined by the uProtocol specification.
+    pub fn is_valid_priority(value: i32) -> bool {
+        UPriority::try_from(value).is_ok()
+    }
+
+    /// Converts a value into a priority, failing for values that the uProtocol specification does not define,
+    /// instead of falling back to a default.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` with field `priority` and code `UCode::InvalidArgument` if the value is not a
+    /// priority.
+    pub fn try_from_value(value: i32) -> Result<UPriority, ValidationError> {
+        UPriority::try_from(value).map_err(|_e| {
+            ValidationError::new(format!("Unknown priority [{value}]")).with_field("priority")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uprotocol::UCode;
+
+    #[test]
+    fn test_try_from_value() {
+        assert_eq!(
+            UPriority::try_from_value(UPriority::UpriorityCs4.into()).unwrap(),
+            UPriority::UpriorityCs4
+        );
+        assert!(UPriority::is_valid_priority(0));
+        assert!(!UPriority::is_valid_priority(99));
+
+        let error = UPriority::try_from_value(99).unwrap_err();
+        assert_eq!(error.code(), UCode::InvalidArgument);
+        assert_eq!(error.field(), Some("priority"));
+        assert_eq!(error.message(), "Unknown priority [99]");
+    }
+}
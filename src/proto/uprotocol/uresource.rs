@@ -11,13 +11,106 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use crate::uprotocol::UResource;
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use crate::types::validationerror::ValidationError;
+use crate::uprotocol::{UCode, UResource};
+
+/// The ID of a uResource, which the micro form of URIs limits to 16 bits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UResourceId(u16);
+
+impl UResourceId {
+    pub const fn new(id: u16) -> UResourceId {
+        UResourceId(id)
+    }
+
+    pub const fn value(self) -> u16 {
+        self.0
+    }
+}
+
+impl From<u16> for UResourceId {
+    fn from(id: u16) -> Self {
+        UResourceId(id)
+    }
+}
+
+impl From<UResourceId> for u32 {
+    fn from(id: UResourceId) -> Self {
+        u32::from(id.0)
+    }
+}
+
+impl TryFrom<u32> for UResourceId {
+    type Error = ValidationError;
+
+    /// Fails with a `ValidationError` with field `id` and code `UCode::OutOfRange` for IDs above 16 bits.
+    fn try_from(id: u32) -> Result<Self, Self::Error> {
+        u16::try_from(id).map(UResourceId).map_err(|_e| {
+            ValidationError::new(format!("uResource ID [{id}] does not fit into micro form"))
+                .with_field("id")
+                .with_code(UCode::OutOfRange)
+        })
+    }
+}
 
 impl UResource {
     pub fn has_id(&self) -> bool {
         self.id.is_some()
     }
 
+    /// Sets the name of this uResource, like `door`.
+    #[must_use]
+    pub fn with_name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the instance of this uResource, like `front_left` of a door.
+    #[must_use]
+    pub fn with_instance<T: Into<String>>(mut self, instance: T) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Sets the name of the protobuf message of this uResource, like `Door`.
+    #[must_use]
+    pub fn with_message<T: Into<String>>(mut self, message: T) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Sets the ID of this uResource.
+    #[must_use]
+    pub fn with_id(mut self, id: UResourceId) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Checks if this uResource can be serialized into a micro form URI, which has 16 bits for its ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` with field `id` and code `UCode::InvalidArgument` if the ID is missing, or code
+    /// `UCode::OutOfRange` if the ID does not fit into the micro form.
+    pub fn validate_micro_form(&self) -> Result<(), ValidationError> {
+        self.micro_id().map(|_| ())
+    }
+
+    /// Gets the ID of this uResource as it is serialized into a micro form URI.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`UResource::validate_micro_form`].
+    pub fn micro_id(&self) -> Result<UResourceId, ValidationError> {
+        match self.id {
+            None => Err(ValidationError::new("Missing uResource ID").with_field("id")),
+            Some(id) => UResourceId::try_from(id),
+        }
+    }
+
     pub fn get_message(&self) -> Option<&str> {
         self.message.as_deref()
     }
@@ -31,16 +124,14 @@ impl From<&str> for UResource {
     fn from(value: &str) -> Self {
         let mut parts = value.split('#');
         let name_and_instance = parts.next().unwrap_or_default();
-        let resource_message = parts.next().map(std::string::ToString::to_string);
+        let resource_message = parts.next().map(ToString::to_string);
 
         let mut name_and_instance_parts = name_and_instance.split('.');
         let resource_name = name_and_instance_parts
             .next()
             .unwrap_or_default()
             .to_string();
-        let resource_instance = name_and_instance_parts
-            .next()
-            .map(std::string::ToString::to_string);
+        let resource_instance = name_and_instance_parts.next().map(ToString::to_string);
 
         UResource {
             name: resource_name,
@@ -56,3 +147,62 @@ impl From<String> for UResource {
         Self::from(value.as_str())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setters() {
+        let resource = UResource::default()
+            .with_name("door")
+            .with_instance("front_left")
+            .with_message("Door")
+            .with_id(UResourceId::new(0x8000));
+        assert_eq!(
+            resource,
+            UResource {
+                id: Some(0x8000),
+                ..UResource::from("door.front_left#Door")
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_micro_form() {
+        assert!(UResource {
+            id: Some(0xffff),
+            ..Default::default()
+        }
+        .validate_micro_form()
+        .is_ok());
+
+        let missing = UResource::from("door.front_left#Door")
+            .validate_micro_form()
+            .unwrap_err();
+        assert_eq!(missing.code(), UCode::InvalidArgument);
+        assert_eq!(missing.field(), Some("id"));
+
+        let oversized = UResource {
+            id: Some(0x1_0000),
+            ..Default::default()
+        }
+        .validate_micro_form()
+        .unwrap_err();
+        assert_eq!(oversized.code(), UCode::OutOfRange);
+        assert_eq!(oversized.field(), Some("id"));
+        assert_eq!(
+            oversized.message(),
+            "uResource ID [65536] does not fit into micro form"
+        );
+        assert_eq!(
+            UResource {
+                id: Some(0x8000),
+                ..Default::default()
+            }
+            .micro_id()
+            .unwrap(),
+            UResourceId::new(0x8000)
+        );
+    }
+}
@@ -11,8 +11,13 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+use alloc::string::ToString;
+
 use prost::Name;
+use prost_types::Any;
 
+use crate::types::serializationerror::SerializationError;
+use crate::types::validationerror::ValidationError;
 use crate::uprotocol::{UCode, UStatus};
 
 impl Name for UStatus {
@@ -44,6 +49,76 @@ impl UStatus {
         }
     }
 
+    /// Creates a failed status from an error, keeping the error's chain of causes in the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The code of the status.
+    /// * `source` - The error, whose message and the messages of its sources are joined with `": "`.
+    #[cfg(feature = "std")]
+    pub fn from_code_and_source<E: std::error::Error>(code: UCode, source: E) -> Self {
+        let mut message = source.to_string();
+        let mut cause = source.source();
+        while let Some(error) = cause {
+            message.push_str(": ");
+            message.push_str(&error.to_string());
+            cause = error.source();
+        }
+        UStatus::fail_with_code(code, &message)
+    }
+
+    /// Adds a detail to this status.
+    ///
+    /// # Arguments
+    ///
+    /// * `detail` - The detail, packed into an `Any`.
+    ///
+    /// # Returns
+    ///
+    /// The status.
+    #[must_use]
+    pub fn with_detail(mut self, detail: Any) -> Self {
+        self.details.push(detail);
+        self
+    }
+
+    /// Adds a message as detail to this status.
+    ///
+    /// # Arguments
+    ///
+    /// * `detail` - The message.
+    ///
+    /// # Returns
+    ///
+    /// The status.
+    #[must_use]
+    pub fn with_message_detail<M: Name>(self, detail: &M) -> Self {
+        self.with_detail(Any {
+            type_url: M::type_url(),
+            value: detail.encode_to_vec(),
+        })
+    }
+
+    /// Gets the first detail of a given message type.
+    ///
+    /// # Returns
+    ///
+    /// The detail, or `None` if the status has no detail of the type, or it cannot be decoded.
+    pub fn detail<M: Name + Default>(&self) -> Option<M> {
+        let type_url = M::type_url();
+        self.details
+            .iter()
+            .find(|detail| detail.type_url == type_url)
+            .and_then(|detail| M::decode(detail.value.as_slice()).ok())
+    }
+
+    /// Checks if the operation that failed with this status may succeed when being retried,
+    /// as is the case for transient failures like `UCode::Unavailable` or `UCode::DeadlineExceeded`,
+    /// see [`UCode::is_retryable`].
+    pub fn is_retryable(&self) -> bool {
+        self.get_code().is_retryable()
+    }
+
     pub fn is_failed(&self) -> bool {
         self.code != UCode::Ok as i32
     }
@@ -60,3 +135,154 @@ impl UStatus {
         }
     }
 }
+
+impl core::fmt::Display for UStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: {}", self.get_code().as_str_name(), self.message())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UStatus {}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for UStatus {
+    fn from(error: std::io::Error) -> Self {
+        use std::io::ErrorKind;
+
+        let code = match error.kind() {
+            ErrorKind::NotFound => UCode::NotFound,
+            ErrorKind::PermissionDenied => UCode::PermissionDenied,
+            ErrorKind::AlreadyExists => UCode::AlreadyExists,
+            ErrorKind::InvalidInput | ErrorKind::InvalidData => UCode::InvalidArgument,
+            ErrorKind::TimedOut => UCode::DeadlineExceeded,
+            ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe
+            | ErrorKind::WouldBlock => UCode::Unavailable,
+            ErrorKind::Interrupted => UCode::Aborted,
+            ErrorKind::UnexpectedEof => UCode::DataLoss,
+            ErrorKind::OutOfMemory => UCode::ResourceExhausted,
+            ErrorKind::Unsupported => UCode::Unimplemented,
+            _ => UCode::Unknown,
+        };
+        UStatus::from_code_and_source(code, error)
+    }
+}
+
+impl From<SerializationError> for UStatus {
+    fn from(error: SerializationError) -> Self {
+        #[cfg(feature = "std")]
+        return UStatus::from_code_and_source(UCode::InvalidArgument, error);
+        #[cfg(not(feature = "std"))]
+        return UStatus::fail_with_code(UCode::InvalidArgument, &error.to_string());
+    }
+}
+
+impl From<ValidationError> for UStatus {
+    fn from(error: ValidationError) -> Self {
+        UStatus::fail_with_code(error.code(), &error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[derive(Debug)]
+    struct WrappingError(std::io::Error);
+
+    impl std::fmt::Display for WrappingError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Failed to connect")
+        }
+    }
+
+    impl std::error::Error for WrappingError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_from_code_and_source_keeps_causes() {
+        let error = WrappingError(std::io::Error::new(
+            std::io::ErrorKind::ConnectionRefused,
+            "broker is down",
+        ));
+        let status = UStatus::from_code_and_source(UCode::Unavailable, error);
+        assert_eq!(status.get_code(), UCode::Unavailable);
+        assert_eq!(status.message(), "Failed to connect: broker is down");
+    }
+
+    #[test_case(std::io::ErrorKind::NotFound, UCode::NotFound; "not found")]
+    #[test_case(std::io::ErrorKind::TimedOut, UCode::DeadlineExceeded; "timed out")]
+    #[test_case(std::io::ErrorKind::BrokenPipe, UCode::Unavailable; "broken pipe")]
+    #[test_case(std::io::ErrorKind::Other, UCode::Unknown; "other")]
+    fn test_from_io_error(kind: std::io::ErrorKind, expected: UCode) {
+        let status = UStatus::from(std::io::Error::new(kind, "failed"));
+        assert_eq!(status.get_code(), expected);
+        assert_eq!(status.message(), "failed");
+    }
+
+    #[test]
+    fn test_from_serialization_error() {
+        let status = UStatus::from(SerializationError::new("Invalid micro URI"));
+        assert_eq!(status.get_code(), UCode::InvalidArgument);
+        assert_eq!(status.message(), "Invalid micro URI");
+    }
+
+    #[test]
+    fn test_from_serialization_error_keeps_causes() {
+        let bytes = vec![0xc3, 0x28];
+        let source = std::str::from_utf8(&bytes).unwrap_err();
+        let status = UStatus::from(SerializationError::utf8(
+            "Topic name is not valid UTF-8",
+            source,
+        ));
+        assert_eq!(status.get_code(), UCode::InvalidArgument);
+        assert_eq!(
+            status.message(),
+            format!("Topic name is not valid UTF-8: {source}")
+        );
+    }
+
+    #[test]
+    fn test_details() {
+        let cause = UStatus::fail_with_code(UCode::NotFound, "no such topic");
+        let status = UStatus::fail_with_code(UCode::Aborted, "Failed to subscribe")
+            .with_message_detail(&cause);
+        assert_eq!(status.details.len(), 1);
+        assert_eq!(status.detail::<UStatus>(), Some(cause));
+
+        let status = UStatus::fail("failed").with_detail(Any {
+            type_url: "type.googleapis.com/other".to_string(),
+            value: vec![1],
+        });
+        assert_eq!(status.detail::<UStatus>(), None);
+    }
+
+    #[test_case(UCode::Unavailable, true; "unavailable")]
+    #[test_case(UCode::DeadlineExceeded, true; "deadline exceeded")]
+    #[test_case(UCode::InvalidArgument, false; "invalid argument")]
+    #[test_case(UCode::PermissionDenied, false; "permission denied")]
+    fn test_is_retryable(code: UCode, expected: bool) {
+        assert_eq!(
+            UStatus::fail_with_code(code, "failed").is_retryable(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_from_validation_error_keeps_code() {
+        let status = UStatus::from(
+            ValidationError::new("Payload is expired").with_code(UCode::DeadlineExceeded),
+        );
+        assert_eq!(status.get_code(), UCode::DeadlineExceeded);
+        assert_eq!(status.message(), "Payload is expired");
+    }
+}
@@ -11,7 +11,9 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use std::str::FromStr;
+use alloc::string::{String, ToString};
+
+use core::str::FromStr;
 use uuid::{Uuid, Variant, Version};
 
 use crate::uprotocol::Uuid as uproto_Uuid;
@@ -19,24 +21,128 @@ use crate::uprotocol::Uuid as uproto_Uuid;
 #[derive(Debug)]
 pub struct UuidConversionError {
     message: String,
+    parse_error: Option<UuidParseError>,
 }
 
 impl UuidConversionError {
     pub fn new<T: Into<String>>(message: T) -> UuidConversionError {
         UuidConversionError {
             message: message.into(),
+            parse_error: None,
         }
     }
+
+    /// Gets the reason why a string is not a UUID, if the conversion has failed for parsing a string.
+    pub fn parse_error(&self) -> Option<UuidParseError> {
+        self.parse_error
+    }
 }
 
-impl std::fmt::Display for UuidConversionError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for UuidConversionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "Error converting Uuid: {}", self.message)
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for UuidConversionError {}
 
+impl From<UuidParseError> for UuidConversionError {
+    fn from(error: UuidParseError) -> Self {
+        UuidConversionError {
+            message: error.to_string(),
+            parse_error: Some(error),
+        }
+    }
+}
+
+/// The reason why a string is not a UUID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UuidParseError {
+    /// The string does not have the length of any of the accepted forms, holding its length in bytes.
+    InvalidLength(usize),
+    /// The string contains a character that is neither a hexadecimal digit nor a separator at its position.
+    InvalidCharacter { character: char, index: usize },
+    /// The string is in simple or braced form, which strict parsing does not accept.
+    NotHyphenated,
+}
+
+impl core::fmt::Display for UuidParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            UuidParseError::InvalidLength(length) => {
+                write!(f, "UUID string has invalid length {length}")
+            }
+            UuidParseError::InvalidCharacter { character, index } => {
+                write!(
+                    f,
+                    "UUID string has invalid character '{character}' at index {index}"
+                )
+            }
+            UuidParseError::NotHyphenated => write!(f, "UUID string is not in hyphenated form"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UuidParseError {}
+
+// the length of a UUID in hyphenated form, like `a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8`
+const HYPHENATED_LENGTH: usize = 36;
+// the length of a UUID in simple form, like `a1a2a3a4b1b2c1c2d1d2d3d4d5d6d7d8`
+const SIMPLE_LENGTH: usize = 32;
+// the length of a UUID in braced form, like `{a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8}`
+const BRACED_LENGTH: usize = 38;
+const HYPHEN_INDICES: [usize; 4] = [8, 13, 18, 23];
+const URN_PREFIX: &str = "urn:uuid:";
+
+// parses a UUID in hyphenated form, or in any of the tolerated forms
+fn parse_uuid(input: &str, tolerant: bool) -> Result<uproto_Uuid, UuidParseError> {
+    let (digits, offset) = match input.len() {
+        HYPHENATED_LENGTH => (input, 0),
+        SIMPLE_LENGTH | BRACED_LENGTH if !tolerant => return Err(UuidParseError::NotHyphenated),
+        SIMPLE_LENGTH => (input, 0),
+        BRACED_LENGTH => {
+            let first = input.chars().next().unwrap_or_default();
+            if first != '{' {
+                return Err(UuidParseError::InvalidCharacter {
+                    character: first,
+                    index: 0,
+                });
+            }
+            let last = input.chars().next_back().unwrap_or_default();
+            if last != '}' {
+                return Err(UuidParseError::InvalidCharacter {
+                    character: last,
+                    index: input.len() - last.len_utf8(),
+                });
+            }
+            (input.get(1..input.len() - 1).unwrap_or_default(), 1)
+        }
+        _ if tolerant && input.starts_with(URN_PREFIX) => {
+            return parse_uuid(input.get(URN_PREFIX.len()..).unwrap_or_default(), false);
+        }
+        length => return Err(UuidParseError::InvalidLength(length)),
+    };
+
+    let hyphenated = digits.len() == HYPHENATED_LENGTH;
+    let mut value = 0u128;
+    for (index, character) in digits.char_indices() {
+        if hyphenated && HYPHEN_INDICES.contains(&index) && character == '-' {
+            continue;
+        }
+        let digit = character
+            .to_digit(16)
+            .filter(|_| !hyphenated || !HYPHEN_INDICES.contains(&index))
+            .ok_or(UuidParseError::InvalidCharacter {
+                character,
+                index: index + offset,
+            })?;
+        value = (value << 4) | u128::from(digit);
+    }
+    Ok(Uuid::from_u128(value).into())
+}
+
 impl uproto_Uuid {
     /// Returns a string representation of this UUID as defined by
     /// [RFC 4122, Section 3](https://www.rfc-editor.org/rfc/rfc4122.html#section-3).
@@ -44,6 +150,20 @@ impl uproto_Uuid {
         Uuid::from(self).as_hyphenated().to_string()
     }
 
+    /// Parses a UUID in the hyphenated form defined by
+    /// [RFC 4122, Section 3](https://www.rfc-editor.org/rfc/rfc4122.html#section-3), like
+    /// `a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8`, in upper or lower case.
+    ///
+    /// Unlike [`FromStr`], which also accepts the simple and braced forms, this only accepts what
+    /// [`to_hyphenated_string`](Self::to_hyphenated_string) writes, e.g. for identifiers that are compared as strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UuidParseError` if the string is not a UUID in hyphenated form.
+    pub fn parse_strict(input: &str) -> Result<uproto_Uuid, UuidParseError> {
+        parse_uuid(input, false)
+    }
+
     fn try_get_time(uuid: &uuid::Uuid) -> Result<u64, String> {
         match uuid.get_version() {
             Some(Version::Custom) => {
@@ -129,11 +249,15 @@ impl From<&uproto_Uuid> for String {
 impl FromStr for uproto_Uuid {
     type Err = UuidConversionError;
 
+    /// Parses a UUID in any of the forms that other tools write, in upper or lower case:
+    /// - hyphenated, like `a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8`,
+    /// - simple, like `a1a2a3a4b1b2c1c2d1d2d3d4d5d6d7d8`,
+    /// - braced, like `{a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8}`, or
+    /// - as URN, like `urn:uuid:a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8`.
+    ///
+    /// See [`uproto_Uuid::parse_strict`] for accepting the hyphenated form only.
     fn from_str(uuid_str: &str) -> Result<Self, Self::Err> {
-        match Uuid::from_str(uuid_str) {
-            Ok(uuid) => Ok(uuid.into()),
-            Err(err) => Err(UuidConversionError::new(err.to_string())),
-        }
+        parse_uuid(uuid_str, true).map_err(UuidConversionError::from)
     }
 }
 
@@ -221,6 +345,64 @@ mod tests {
         assert_eq!(uuid.lsb, lo);
     }
 
+    #[test]
+    fn test_parse_tolerated_forms() {
+        let uuid = uproto_Uuid {
+            msb: 0xa1a2a3a4b1b2c1c2u64,
+            lsb: 0xd1d2d3d4d5d6d7d8u64,
+        };
+        for form in [
+            "A1A2A3A4-B1B2-C1C2-D1D2-D3D4D5D6D7D8",
+            "a1a2a3a4b1b2c1c2d1d2d3d4d5d6d7d8",
+            "{a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8}",
+            "urn:uuid:a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8",
+        ] {
+            assert_eq!(form.parse::<uproto_Uuid>().unwrap(), uuid, "{form}");
+        }
+        assert_eq!(
+            uproto_Uuid::parse_strict("a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8"),
+            Ok(uuid)
+        );
+    }
+
+    #[test]
+    fn test_parse_fails_with_reason() {
+        let parse_error = |input: &str| input.parse::<uproto_Uuid>().unwrap_err().parse_error();
+        assert_eq!(
+            parse_error("a1a2a3a4-b1b2-c1c2-d1d2"),
+            Some(UuidParseError::InvalidLength(23))
+        );
+        assert_eq!(
+            parse_error("a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7dx"),
+            Some(UuidParseError::InvalidCharacter {
+                character: 'x',
+                index: 35
+            })
+        );
+        assert_eq!(
+            parse_error("a1a2a3a4b-1b2-c1c2-d1d2-d3d4d5d6d7d8"),
+            Some(UuidParseError::InvalidCharacter {
+                character: 'b',
+                index: 8
+            })
+        );
+        assert_eq!(
+            parse_error("{a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8]"),
+            Some(UuidParseError::InvalidCharacter {
+                character: ']',
+                index: 37
+            })
+        );
+        assert_eq!(
+            parse_error("a1a2a3a4-b1b2-c1c2-d1d2d3d4d5d6d7d8"),
+            Some(UuidParseError::InvalidLength(35))
+        );
+        assert_eq!(
+            uproto_Uuid::parse_strict("a1a2a3a4b1b2c1c2d1d2d3d4d5d6d7d8"),
+            Err(UuidParseError::NotHyphenated)
+        );
+    }
+
     #[test]
     fn test_from_bytes() {
         let bytes: [u8; 16] = [
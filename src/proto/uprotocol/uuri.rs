@@ -11,11 +11,17 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
 use std::fmt::Display;
 
 use crate::uprotocol::UUri as uproto_Uuri;
-use crate::uri::serializer::{LongUriSerializer, MicroUriSerializer, UriSerializer};
+#[cfg(feature = "std")]
+use crate::uri::serializer::LongUriSerializer;
+use crate::uri::serializer::{MicroUriSerializer, UriSerializer};
 
+#[cfg(feature = "std")]
 impl From<uproto_Uuri> for String {
     fn from(value: uproto_Uuri) -> Self {
         if let Ok(uri) = LongUriSerializer::serialize(&value) {
@@ -26,6 +32,7 @@ impl From<uproto_Uuri> for String {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<&str> for uproto_Uuri {
     fn from(value: &str) -> Self {
         if let Ok(uri) = LongUriSerializer::deserialize(value.to_string()) {
@@ -41,7 +48,7 @@ impl From<uproto_Uuri> for Vec<u8> {
         if let Ok(uri) = MicroUriSerializer::serialize(&value) {
             uri
         } else {
-            vec![]
+            Vec::new()
         }
     }
 }
@@ -56,6 +63,7 @@ impl From<Vec<u8>> for uproto_Uuri {
     }
 }
 
+#[cfg(feature = "std")]
 impl Display for uproto_Uuri {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let uri = LongUriSerializer::serialize(self).unwrap_or_default();
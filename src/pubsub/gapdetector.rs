@@ -0,0 +1,386 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use prost::Message;
+
+use crate::transport::builder::SerializationError;
+use crate::transport::datamodel::UListener;
+use crate::uprotocol::{Data, UCode, UMessage, UPayload, UStatus};
+
+type ErrorHandler = Box<dyn Fn(&UMessage, UStatus) + Send + Sync + 'static>;
+
+// how far back a sequence number may lie to be taken for a late message rather than a restarted publisher
+const REORDER_WINDOW: u32 = 1024;
+
+/// The number of a message among the messages that a [`Publisher`](crate::pubsub::Publisher) has published to a
+/// topic, counting from 0.
+///
+/// `UAttributes` have no room for a sequence number, so it is carried in front of the payload's data, as
+/// [`SequenceNumber::LENGTH`] bytes in big-endian order, in the same way as the counter of an end-to-end protection
+/// header. Subscribers take it off again with a [`GapDetector`], or with [`SequenceNumber::take`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SequenceNumber(pub u32);
+
+impl SequenceNumber {
+    /// The number of bytes that a sequence number takes in front of the payload's data.
+    pub const LENGTH: usize = 4;
+
+    /// Gets the sequence number that follows this one, which is 0 after `u32::MAX`.
+    #[must_use]
+    pub fn next(self) -> SequenceNumber {
+        SequenceNumber(self.0.wrapping_add(1))
+    }
+
+    /// Puts this sequence number in front of a payload's data.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to stamp.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload refers to shared memory instead of carrying its data by value.
+    pub fn stamp(self, mut payload: UPayload) -> Result<UPayload, SerializationError> {
+        let data = match payload.data.take() {
+            Some(Data::Value(data)) => data,
+            None => Vec::new(),
+            Some(Data::Reference(_)) => {
+                return Err(SerializationError::new(
+                    "Cannot stamp a sequence number on a UPayload that refers to shared memory",
+                ))
+            }
+        };
+        let mut stamped = Vec::with_capacity(Self::LENGTH + data.len());
+        stamped.extend_from_slice(&self.0.to_be_bytes());
+        stamped.extend_from_slice(&data);
+        payload.length = payload.length.map(|_| stamped.len() as i32);
+        payload.data = Some(Data::Value(stamped));
+        Ok(payload)
+    }
+
+    /// Takes the sequence number off a payload stamped with [`SequenceNumber::stamp`].
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The received payload.
+    ///
+    /// # Returns
+    ///
+    /// The sequence number and the payload as it has been published.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload's data is too short to carry a sequence number.
+    pub fn take(mut payload: UPayload) -> Result<(SequenceNumber, UPayload), SerializationError> {
+        let Some(Data::Value(mut data)) = payload.data.take() else {
+            return Err(SerializationError::new(
+                "UPayload carries no sequence number",
+            ));
+        };
+        if data.len() < Self::LENGTH {
+            return Err(SerializationError::new(
+                "UPayload carries no sequence number",
+            ));
+        }
+        let rest = data.split_off(Self::LENGTH);
+        let mut bytes = [0; Self::LENGTH];
+        bytes.copy_from_slice(&data);
+        payload.length = payload.length.map(|_| rest.len() as i32);
+        payload.data = Some(Data::Value(rest));
+        Ok((SequenceNumber(u32::from_be_bytes(bytes)), payload))
+    }
+}
+
+/// `GapDetector` wraps the listener of topics that are published with sequence numbers, so that subscribers notice
+/// when messages have been lost or arrive out of order, e.g. the consumers of periodic signals that would otherwise
+/// keep working with stale values.
+///
+/// The last sequence number is tracked per topic, i.e. per source of the received messages. Messages are passed on
+/// with their sequence number taken off. A message that skips sequence numbers is passed on, and the skipped ones are
+/// counted in [`GapDetector::lost`] and reported to the handler set with [`GapDetector::on_error`], if any, along with
+/// a `UStatus` with code `DATA_LOSS`. A message that arrives after a later one, or again, is dropped, counted in
+/// [`GapDetector::reordered`], and reported with code `ABORTED`. A sequence number of 0 that does not follow
+/// `u32::MAX` is taken as a restart of the publisher.
+///
+/// Messages without a sequence number are dropped and reported with code `INVALID_ARGUMENT`, while statuses about
+/// messages that cannot be delivered are passed on.
+pub struct GapDetector {
+    listener: UListener,
+    on_error: Option<ErrorHandler>,
+    // keyed by the encoded source of the messages
+    last: Mutex<HashMap<Vec<u8>, SequenceNumber>>,
+    lost: AtomicU64,
+    reordered: AtomicU64,
+}
+
+impl GapDetector {
+    /// Creates a gap detector.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to call with the messages that arrive in order.
+    pub fn new(listener: UListener) -> Self {
+        GapDetector {
+            listener,
+            on_error: None,
+            last: Mutex::new(HashMap::new()),
+            lost: AtomicU64::new(0),
+            reordered: AtomicU64::new(0),
+        }
+    }
+
+    /// Sets a handler that is called for each gap and each message that is dropped, e.g. for logging it or for
+    /// falling back to a safe state.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to call with the received message and the status describing the problem.
+    #[must_use]
+    pub fn on_error<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&UMessage, UStatus) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Box::new(handler));
+        self
+    }
+
+    /// Gets the number of messages that have been lost, as told by the skipped sequence numbers.
+    pub fn lost(&self) -> u64 {
+        self.lost.load(Ordering::Relaxed)
+    }
+
+    /// Gets the number of messages that have been dropped for arriving out of order or more than once.
+    pub fn reordered(&self) -> u64 {
+        self.reordered.load(Ordering::Relaxed)
+    }
+
+    /// Checks the sequence number of a received message and passes the message on to the wrapped listener, unless
+    /// it is to be dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The received message, or the status about a message that cannot be delivered.
+    pub fn on_receive(&self, result: Result<UMessage, UStatus>) {
+        let Ok(mut message) = result else {
+            (self.listener)(result);
+            return;
+        };
+        let sequence = match SequenceNumber::take(message.payload.take().unwrap_or_default()) {
+            Ok((sequence, payload)) => {
+                message.payload = Some(payload);
+                sequence
+            }
+            Err(error) => {
+                self.report(
+                    &message,
+                    UStatus::fail_with_code(UCode::InvalidArgument, error.to_string().as_str()),
+                );
+                return;
+            }
+        };
+
+        let key = message
+            .source
+            .as_ref()
+            .map(Message::encode_to_vec)
+            .unwrap_or_default();
+        let distance = {
+            let mut last = self.last.lock().unwrap();
+            let distance = last
+                .get(&key)
+                .copied()
+                .map(|previous| (previous, sequence.0.wrapping_sub(previous.0)));
+            // keep the later sequence number that has been received before
+            if !matches!(distance, Some((_, distance)) if is_late(sequence, distance)) {
+                last.insert(key, sequence);
+            }
+            distance
+        };
+        let Some((previous, distance)) = distance else {
+            (self.listener)(Ok(message));
+            return;
+        };
+        if is_late(sequence, distance) {
+            self.reordered.fetch_add(1, Ordering::Relaxed);
+            self.report(
+                &message,
+                UStatus::fail_with_code(
+                    UCode::Aborted,
+                    format!(
+                        "Message {} arrived after message {}",
+                        sequence.0, previous.0
+                    )
+                    .as_str(),
+                ),
+            );
+            return;
+        }
+        if distance > 1 && sequence.0 != 0 {
+            let lost = distance - 1;
+            self.lost.fetch_add(u64::from(lost), Ordering::Relaxed);
+            self.report(
+                &message,
+                UStatus::fail_with_code(
+                    UCode::DataLoss,
+                    format!("{lost} message(s) lost before message {}", sequence.0).as_str(),
+                ),
+            );
+        }
+        (self.listener)(Ok(message));
+    }
+
+    /// Converts this into a listener that can be registered with a [`UTransport`](crate::transport::datamodel::UTransport).
+    ///
+    /// The counts stay available through the `Arc`, if it is kept.
+    pub fn into_listener(self: Arc<Self>) -> UListener {
+        Box::new(move |result| self.on_receive(result))
+    }
+
+    fn report(&self, message: &UMessage, status: UStatus) {
+        if let Some(on_error) = &self.on_error {
+            on_error(message, status);
+        }
+    }
+}
+
+// whether a message is a duplicate, or older than the last one, given its distance to the last sequence number
+fn is_late(sequence: SequenceNumber, distance: u32) -> bool {
+    distance == 0 || (distance > u32::MAX - REORDER_WINDOW && sequence.0 != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uprotocol::{UEntity, UResource, UUri};
+
+    fn message(topic: &str, sequence: u32) -> UMessage {
+        let payload = UPayload {
+            data: Some(Data::Value(vec![0xAB])),
+            ..Default::default()
+        };
+        UMessage {
+            source: Some(UUri {
+                entity: Some(UEntity {
+                    name: "body.access".to_string(),
+                    version_major: Some(1),
+                    ..Default::default()
+                }),
+                resource: Some(UResource::from(topic)),
+                ..Default::default()
+            }),
+            payload: Some(SequenceNumber(sequence).stamp(payload).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    fn build_detector_for_test() -> (
+        GapDetector,
+        Arc<Mutex<Vec<Result<UMessage, UStatus>>>>,
+        Arc<Mutex<Vec<UCode>>>,
+    ) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let (received_sink, errors_sink) = (Arc::clone(&received), Arc::clone(&errors));
+        let listener: UListener =
+            Box::new(move |result| received_sink.lock().unwrap().push(result));
+        let detector = GapDetector::new(listener)
+            .on_error(move |_, status| errors_sink.lock().unwrap().push(status.get_code()));
+        (detector, received, errors)
+    }
+
+    #[test]
+    fn test_sequence_number_round_trip() {
+        let payload = UPayload {
+            length: Some(3),
+            data: Some(Data::Value(vec![1, 2, 3])),
+            ..Default::default()
+        };
+        let stamped = SequenceNumber(0x0102_0304).stamp(payload.clone()).unwrap();
+        assert_eq!(stamped.data, Some(Data::Value(vec![1, 2, 3, 4, 1, 2, 3])));
+        assert_eq!(stamped.length, Some(7));
+
+        assert_eq!(
+            SequenceNumber::take(stamped).unwrap(),
+            (SequenceNumber(0x0102_0304), payload)
+        );
+        assert!(SequenceNumber::take(UPayload::default()).is_err());
+        assert!(SequenceNumber(1)
+            .stamp(UPayload {
+                data: Some(Data::Reference(0x1000)),
+                ..Default::default()
+            })
+            .is_err());
+        assert_eq!(SequenceNumber(u32::MAX).next(), SequenceNumber(0));
+    }
+
+    #[test]
+    fn test_gaps_are_reported() {
+        let (detector, received, errors) = build_detector_for_test();
+
+        for sequence in [7, 8, 11] {
+            detector.on_receive(Ok(message("door.front_left#Door", sequence)));
+        }
+        detector.on_receive(Ok(message("door.front_right#Door", 0)));
+
+        assert_eq!(received.lock().unwrap().len(), 4);
+        assert_eq!(
+            received.lock().unwrap()[0]
+                .as_ref()
+                .unwrap()
+                .payload
+                .as_ref()
+                .unwrap()
+                .data,
+            Some(Data::Value(vec![0xAB]))
+        );
+        assert_eq!(detector.lost(), 2);
+        assert_eq!(*errors.lock().unwrap(), vec![UCode::DataLoss]);
+    }
+
+    #[test]
+    fn test_late_messages_are_dropped() {
+        let (detector, received, errors) = build_detector_for_test();
+
+        for sequence in [u32::MAX - 1, u32::MAX, u32::MAX - 1, u32::MAX, 0, 1] {
+            detector.on_receive(Ok(message("door.front_left#Door", sequence)));
+        }
+
+        assert_eq!(received.lock().unwrap().len(), 4);
+        assert_eq!(detector.reordered(), 2);
+        assert_eq!(detector.lost(), 0);
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec![UCode::Aborted, UCode::Aborted]
+        );
+    }
+
+    #[test]
+    fn test_publisher_restart_and_unstamped_messages() {
+        let (detector, received, errors) = build_detector_for_test();
+
+        detector.on_receive(Ok(message("door.front_left#Door", 5000)));
+        detector.on_receive(Ok(message("door.front_left#Door", 0)));
+        detector.on_receive(Ok(message("door.front_left#Door", 1)));
+        detector.on_receive(Ok(UMessage::default()));
+        detector.on_receive(Err(UStatus::fail_with_code(UCode::Internal, "failed")));
+
+        assert_eq!(received.lock().unwrap().len(), 3);
+        assert_eq!(detector.lost(), 0);
+        assert_eq!(*errors.lock().unwrap(), vec![UCode::InvalidArgument]);
+    }
+}
@@ -0,0 +1,222 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::pubsub::Publisher;
+use crate::transport::builder::UPayloadBuilder;
+use crate::transport::datamodel::{Lifecycle, UTransport};
+use crate::uprotocol::{UCode, UResource, UStatus, UUri};
+use crate::UProtocolError;
+
+/// The resource of the topic that a uEntity's heartbeats are published to, along with the type of their payload.
+pub const HEALTH_RESOURCE: &str = "health#UStatus";
+
+/// `HealthMonitor` publishes the heartbeats of a uEntity, so that platform supervisors can track the health of
+/// uEntities in the same way on any transport.
+///
+/// Heartbeats are published to the [`HealthMonitor::topic`] of the uEntity, which is its URI with the
+/// [`HEALTH_RESOURCE`]. Their payload is the `UStatus` that the uEntity has reported last, which is `UCode::Ok` once
+/// it is ready. A uEntity whose heartbeats stop is no longer alive. Until the uEntity reports its state, heartbeats
+/// carry a status with code `UCode::Unavailable`. Shutting the monitor down by means of [`Lifecycle::shutdown`]
+/// publishes a last heartbeat with code `UCode::Unavailable`, and stops [`HealthMonitor::run`].
+pub struct HealthMonitor<T: UTransport> {
+    publisher: Publisher<T>,
+    topic: UUri,
+    status: Mutex<UStatus>,
+}
+
+impl<T: UTransport> HealthMonitor<T> {
+    /// Creates a health monitor for a uEntity.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to publish heartbeats with.
+    /// * `entity` - The URI of the uEntity, whose authority and entity the topic of the heartbeats is made of.
+    pub fn new(transport: T, entity: &UUri) -> HealthMonitor<T> {
+        HealthMonitor {
+            publisher: Publisher::new(transport),
+            topic: Self::topic_of(entity),
+            status: Mutex::new(UStatus::fail_with_code(
+                UCode::Unavailable,
+                "uEntity is starting",
+            )),
+        }
+    }
+
+    /// Gets the topic that the heartbeats of a uEntity are published to, e.g. for a supervisor to subscribe to.
+    ///
+    /// # Arguments
+    ///
+    /// * `entity` - The URI of the uEntity.
+    pub fn topic_of(entity: &UUri) -> UUri {
+        UUri {
+            authority: entity.authority.clone(),
+            entity: entity.entity.clone(),
+            resource: Some(UResource::from(HEALTH_RESOURCE)),
+        }
+    }
+
+    /// Gets the topic that heartbeats are published to.
+    pub fn topic(&self) -> &UUri {
+        &self.topic
+    }
+
+    /// Gets the status that heartbeats currently carry.
+    pub fn status(&self) -> UStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Reports that the uEntity is ready to serve requests.
+    ///
+    /// The state is published with the next heartbeat.
+    pub fn report_ready(&self) {
+        *self.status.lock().unwrap() = UStatus::ok();
+    }
+
+    /// Reports that the uEntity is alive, but cannot serve requests as expected.
+    ///
+    /// The state is published with the next heartbeat.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The reason, e.g. with code `UCode::Unavailable` if a resource the uEntity depends on is missing.
+    pub fn report_degraded(&self, status: UStatus) {
+        *self.status.lock().unwrap() = status;
+    }
+
+    /// Publishes a heartbeat with the current status.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` if the heartbeat cannot be published, e.g. because the URI of the uEntity is
+    /// invalid or the transport fails.
+    pub async fn heartbeat(&self) -> Result<(), UProtocolError> {
+        let payload = UPayloadBuilder::from_protobuf(&self.status())?.build()?;
+        self.publisher.publish(self.topic.clone(), payload).await
+    }
+
+    /// Publishes heartbeats periodically.
+    ///
+    /// The returned future keeps publishing heartbeats for as long as it is polled, or until the monitor is shut
+    /// down. Failures to publish individual heartbeats are ignored. The SDK does not depend on a particular async
+    /// runtime, so the function to wait with is passed in, e.g. `tokio::time::sleep`.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - The time between heartbeats.
+    /// * `sleep` - The function returning a future that completes after the given time.
+    pub async fn run<S, F>(&self, interval: Duration, sleep: S)
+    where
+        S: Fn(Duration) -> F,
+        F: Future<Output = ()>,
+    {
+        while !self.publisher.is_shut_down() {
+            let _ = self.heartbeat().await;
+            sleep(interval).await;
+        }
+    }
+}
+
+#[async_trait]
+impl<T: UTransport + Send + Sync> Lifecycle for HealthMonitor<T> {
+    async fn shutdown(&self, deadline: Duration) -> Result<(), UStatus> {
+        self.report_degraded(UStatus::fail_with_code(
+            UCode::Unavailable,
+            "uEntity is shutting down",
+        ));
+        let _ = self.heartbeat().await;
+        self.publisher.shutdown(deadline).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::FutureExt;
+
+    use crate::fixtures::RecordingTransport;
+    use crate::rpc::RpcMapper;
+    use crate::uprotocol::UEntity;
+
+    fn build_monitor_for_test() -> HealthMonitor<RecordingTransport> {
+        let entity = UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        HealthMonitor::new(RecordingTransport::default(), &entity)
+    }
+
+    fn last_heartbeat(monitor: &HealthMonitor<RecordingTransport>) -> UStatus {
+        let sent = monitor.publisher.transport().sent();
+        let (topic, payload, _attributes) = sent.last().unwrap();
+        assert_eq!(topic, monitor.topic());
+        RpcMapper::unpack_payload(payload.clone()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_carries_reported_status() {
+        let monitor = build_monitor_for_test();
+        assert_eq!(
+            monitor.topic().resource,
+            Some(UResource::from("health#UStatus"))
+        );
+
+        monitor.heartbeat().await.unwrap();
+        assert_eq!(last_heartbeat(&monitor).get_code(), UCode::Unavailable);
+
+        monitor.report_ready();
+        monitor.heartbeat().await.unwrap();
+        assert_eq!(last_heartbeat(&monitor), UStatus::ok());
+
+        let degraded = UStatus::fail_with_code(UCode::Unavailable, "Door sensor missing");
+        monitor.report_degraded(degraded.clone());
+        monitor.heartbeat().await.unwrap();
+        assert_eq!(last_heartbeat(&monitor), degraded);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_publishes_last_heartbeat() {
+        let monitor = build_monitor_for_test();
+        monitor.report_ready();
+
+        monitor.shutdown(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(last_heartbeat(&monitor).get_code(), UCode::Unavailable);
+        assert!(monitor.heartbeat().await.is_err());
+        monitor.run(Duration::from_secs(1), |_| async {}).await;
+    }
+
+    #[test]
+    fn test_run_publishes_heartbeat_before_sleeping() {
+        let monitor = build_monitor_for_test();
+        let intervals = Mutex::new(Vec::new());
+
+        let run = monitor.run(Duration::from_secs(1), |interval| {
+            intervals.lock().unwrap().push(interval);
+            futures::future::pending()
+        });
+        assert!(run.now_or_never().is_none());
+
+        assert_eq!(monitor.publisher.transport().sent().len(), 1);
+        assert_eq!(*intervals.lock().unwrap(), vec![Duration::from_secs(1)]);
+    }
+}
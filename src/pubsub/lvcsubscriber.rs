@@ -0,0 +1,277 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use prost::Message;
+
+use crate::pubsub::Subscriber;
+use crate::transport::datamodel::{Lifecycle, UTransport};
+use crate::uprotocol::{UMessage, UStatus, UUri};
+use crate::utwin::UTwinClient;
+use crate::UProtocolError;
+
+/// `LvcSubscriber` keeps a last-value cache of the topics it subscribes to, so that consumers can read the current
+/// state of a topic at any time, instead of waiting for the next message to be published.
+///
+/// The cache holds the latest message received on each subscribed topic. If a uTwin service is set, the cache is
+/// seeded with the last message that the service knows of when subscribing, so that the state of a topic is also
+/// available before its next message has been published.
+pub struct LvcSubscriber<T: UTransport> {
+    subscriber: Subscriber<T>,
+    utwin: Option<UTwinClient>,
+    // keyed by the encoded topics
+    cache: Arc<RwLock<HashMap<Vec<u8>, UMessage>>>,
+}
+
+impl<T: UTransport> LvcSubscriber<T> {
+    /// Creates a last-value cache that is not seeded from uTwin.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscriber` - The subscriber to subscribe to topics with.
+    pub fn new(subscriber: Subscriber<T>) -> LvcSubscriber<T> {
+        LvcSubscriber {
+            subscriber,
+            utwin: None,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Sets the uTwin service to seed the cache from.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to invoke the uTwin service's methods with.
+    ///
+    /// # Returns
+    ///
+    /// The last-value cache.
+    #[must_use]
+    pub fn with_utwin(mut self, client: UTwinClient) -> LvcSubscriber<T> {
+        self.utwin = Some(client);
+        self
+    }
+
+    /// Gets the subscriber this cache subscribes to topics with.
+    pub fn subscriber(&self) -> &Subscriber<T> {
+        &self.subscriber
+    }
+
+    /// Subscribes to a topic, keeping the latest message received on it.
+    ///
+    /// If a uTwin service is set, the topic's last message is retrieved from the service once the listener has been
+    /// registered, unless a message has been received already. Failures to retrieve it are ignored, as the cache
+    /// fills with the next message published to the topic anyway.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to subscribe to.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the registered listener, to be passed to [`LvcSubscriber::unsubscribe`].
+    ///
+    /// # Errors
+    ///
+    /// Returns any of the errors returned by [`Subscriber::subscribe`].
+    pub async fn subscribe(&self, topic: UUri) -> Result<String, UProtocolError> {
+        let key = topic.encode_to_vec();
+        let cache = Arc::clone(&self.cache);
+        let listener_key = key.clone();
+        let listener = self
+            .subscriber
+            .subscribe(
+                topic.clone(),
+                Box::new(move |result| {
+                    if let Ok(message) = result {
+                        cache.write().unwrap().insert(listener_key.clone(), message);
+                    }
+                }),
+            )
+            .await?;
+
+        if let Some(utwin) = &self.utwin {
+            if let Ok(message) = utwin.get_last_message(topic).await {
+                // a message received in the meantime is more recent
+                self.cache.write().unwrap().entry(key).or_insert(message);
+            }
+        }
+        Ok(listener)
+    }
+
+    /// Unsubscribes from a topic, dropping its latest message from the cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to unsubscribe from.
+    /// * `listener` - The identifier of the listener, as returned by [`LvcSubscriber::subscribe`].
+    ///
+    /// # Errors
+    ///
+    /// Returns any of the errors returned by [`Subscriber::unsubscribe`].
+    pub async fn unsubscribe(&self, topic: UUri, listener: &str) -> Result<(), UProtocolError> {
+        let key = topic.encode_to_vec();
+        self.subscriber.unsubscribe(topic, listener).await?;
+        self.cache.write().unwrap().remove(&key);
+        Ok(())
+    }
+
+    /// Gets the latest message of a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic.
+    ///
+    /// # Returns
+    ///
+    /// The latest message received on the topic, or seeded from uTwin, or `None` if the topic has not been subscribed
+    /// to or no message is known yet.
+    pub fn get_last(&self, topic: &UUri) -> Option<UMessage> {
+        self.cache
+            .read()
+            .unwrap()
+            .get(&topic.encode_to_vec())
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl<T: UTransport + Send + Sync> Lifecycle for LvcSubscriber<T> {
+    async fn shutdown(&self, deadline: Duration) -> Result<(), UStatus> {
+        let result = self.subscriber.shutdown(deadline).await;
+        self.cache.write().unwrap().clear();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use prost_types::Any;
+
+    use crate::fixtures::RecordingTransport;
+    use crate::rpc::{RpcClient, RpcClientResult, RpcMapperError};
+    use crate::transport::builder::{UAttributesBuilder, UPayloadBuilder};
+    use crate::uprotocol::core::utwin::v1::{GetLastMessagesResponse, MessageResponse};
+    use crate::uprotocol::{UAttributes, UEntity, UPayload, UPriority, UResource, UUriBatch};
+
+    // Knows the last message of the `door.front_left` topic only.
+    struct UTwinRpcClient;
+
+    #[async_trait]
+    impl RpcClient for UTwinRpcClient {
+        async fn invoke_method(
+            _topic: UUri,
+            payload: UPayload,
+            _attributes: UAttributes,
+        ) -> RpcClientResult {
+            let Some(crate::uprotocol::Data::Value(data)) = payload.data else {
+                return Err(RpcMapperError::InvalidPayload("no data".to_string()));
+            };
+            let responses = UUriBatch::decode(data.as_slice())
+                .unwrap()
+                .uris
+                .into_iter()
+                .map(|topic| MessageResponse {
+                    message: (topic == build_topic_for_test("door.front_left#Door"))
+                        .then(|| build_message_for_test("door.front_left#Door", 1)),
+                    topic: Some(topic),
+                    status: None,
+                })
+                .collect();
+            Ok(Any {
+                type_url: "type.googleapis.com/uprotocol.core.utwin.v1.GetLastMessagesResponse"
+                    .to_string(),
+                value: GetLastMessagesResponse { responses }.encode_to_vec(),
+            }
+            .try_into()
+            .unwrap())
+        }
+    }
+
+    fn build_topic_for_test(resource: &str) -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from(resource)),
+            ..Default::default()
+        }
+    }
+
+    fn build_message_for_test(resource: &str, value: u8) -> UMessage {
+        UMessage {
+            source: Some(build_topic_for_test(resource)),
+            attributes: Some(UAttributesBuilder::publish(UPriority::UpriorityCs1).build()),
+            payload: Some(UPayloadBuilder::from_raw(vec![value]).build().unwrap()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_last_returns_latest_message() {
+        let lvc = LvcSubscriber::new(Subscriber::new(RecordingTransport::default()));
+        let topic = build_topic_for_test("door.front_right#Door");
+        let listener = lvc.subscribe(topic.clone()).await.unwrap();
+        assert!(lvc.get_last(&topic).is_none());
+
+        let transport = lvc.subscriber().transport();
+        transport.deliver(
+            &topic,
+            Ok(build_message_for_test("door.front_right#Door", 1)),
+        );
+        transport.deliver(
+            &topic,
+            Ok(build_message_for_test("door.front_right#Door", 2)),
+        );
+        assert_eq!(
+            lvc.get_last(&topic).unwrap().payload,
+            build_message_for_test("door.front_right#Door", 2).payload
+        );
+
+        lvc.unsubscribe(topic.clone(), &listener).await.unwrap();
+        assert!(lvc.get_last(&topic).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cache_is_seeded_from_utwin() {
+        let lvc = LvcSubscriber::new(Subscriber::new(RecordingTransport::default()))
+            .with_utwin(UTwinClient::new::<UTwinRpcClient>());
+        let known = build_topic_for_test("door.front_left#Door");
+        let unknown = build_topic_for_test("door.front_right#Door");
+
+        lvc.subscribe(known.clone()).await.unwrap();
+        lvc.subscribe(unknown.clone()).await.unwrap();
+        assert_eq!(
+            lvc.get_last(&known).unwrap().payload,
+            build_message_for_test("door.front_left#Door", 1).payload
+        );
+        assert!(lvc.get_last(&unknown).is_none());
+
+        lvc.subscriber().transport().deliver(
+            &known,
+            Ok(build_message_for_test("door.front_left#Door", 2)),
+        );
+        assert_eq!(
+            lvc.get_last(&known).unwrap().payload,
+            build_message_for_test("door.front_left#Door", 2).payload
+        );
+    }
+}
@@ -0,0 +1,387 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use prost::Message;
+
+use crate::pubsub::{DeltaEncoder, SequenceNumber, TopicRegistry};
+use crate::transport::builder::EntityProfile;
+use crate::transport::datamodel::{Drain, Lifecycle, UTransport};
+use crate::transport::validator::Validators;
+use crate::uprotocol::{UMessageType, UPayload, UPriority, UStatus, UUri};
+use crate::uri::validator::UriValidator;
+use crate::UProtocolError;
+
+/// `Publisher` is the uP-L2 building block for publishing messages to a topic.
+///
+/// It takes care of creating the attributes of published messages and validating them before handing the messages
+/// to the underlying [`UTransport`]. If a [`TopicRegistry`] is set, only topics declared for published messages
+/// can be published to. If sequence numbers are enabled, the payload of each message carries the
+/// [`SequenceNumber`] of the message on its topic, for subscribers to detect lost messages with a
+/// [`GapDetector`](crate::pubsub::GapDetector). If deltas are enabled, JSON payloads are published as
+/// [`StateUpdate`](crate::pubsub::StateUpdate)s, for subscribers to assemble with a
+/// [`DeltaAssembler`](crate::pubsub::DeltaAssembler). Shutting the publisher down by means of
+/// [`Lifecycle::shutdown`] lets the messages being published complete. For more information, please refer to the
+/// [uProtocol Specification](https://github.com/eclipse-uprotocol/uprotocol-spec/blob/main/up-l2/README.adoc).
+pub struct Publisher<T: UTransport> {
+    transport: T,
+    profile: EntityProfile,
+    topic_registry: Option<Arc<TopicRegistry>>,
+    // the next sequence number per encoded topic, if enabled
+    sequence_numbers: Option<Mutex<HashMap<Vec<u8>, SequenceNumber>>>,
+    deltas: Option<DeltaEncoder>,
+    drain: Drain,
+}
+
+impl<T: UTransport> Publisher<T> {
+    /// Creates a publisher that publishes messages with the default priority `UPriority::UpriorityCs1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to send messages with.
+    pub fn new(transport: T) -> Publisher<T> {
+        Publisher {
+            transport,
+            profile: EntityProfile::new(),
+            topic_registry: None,
+            sequence_numbers: None,
+            deltas: None,
+            drain: Drain::new(),
+        }
+    }
+
+    /// Sets the priority of published messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority.
+    ///
+    /// # Returns
+    ///
+    /// The publisher.
+    #[must_use]
+    pub fn with_priority(mut self, priority: UPriority) -> Publisher<T> {
+        self.profile = self
+            .profile
+            .with_priority(UMessageType::UmessageTypePublish, priority);
+        self
+    }
+
+    /// Sets the profile of the uEntity, whose defaults for published messages are applied to the messages.
+    ///
+    /// The priority of published messages set by [`Publisher::with_priority`] before is replaced by the one of the
+    /// profile, if it has one.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The profile.
+    ///
+    /// # Returns
+    ///
+    /// The publisher.
+    #[must_use]
+    pub fn with_profile(mut self, profile: EntityProfile) -> Publisher<T> {
+        let publish = UMessageType::UmessageTypePublish;
+        self.profile = match (profile.priority(publish), self.profile.priority(publish)) {
+            (None, Some(priority)) => profile.with_priority(publish, priority),
+            _ => profile,
+        };
+        self
+    }
+
+    /// Sets the registry of the topics that may be published to.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic_registry` - The registry.
+    ///
+    /// # Returns
+    ///
+    /// The publisher.
+    #[must_use]
+    pub fn with_topic_registry(mut self, topic_registry: Arc<TopicRegistry>) -> Publisher<T> {
+        self.topic_registry = Some(topic_registry);
+        self
+    }
+
+    /// Enables sequence numbers, which are counted per topic from 0.
+    ///
+    /// Subscribers to the publisher's topics need to take the sequence numbers off the payload, e.g. with a
+    /// [`GapDetector`](crate::pubsub::GapDetector), so they are meant for topics whose subscribers expect them.
+    ///
+    /// # Returns
+    ///
+    /// The publisher.
+    #[must_use]
+    pub fn with_sequence_numbers(mut self) -> Publisher<T> {
+        self.sequence_numbers = Some(Mutex::new(HashMap::new()));
+        self
+    }
+
+    /// Enables deltas, so that the JSON documents published to a topic are sent as merge patches against the document
+    /// published before, and only sent as a whole every `snapshot_interval` messages, cutting the bandwidth of large
+    /// state that changes slowly.
+    ///
+    /// Subscribers to the publisher's topics need to assemble the state from the patches, e.g. with
+    /// [`Subscriber::subscribe_to_deltas`](crate::pubsub::Subscriber::subscribe_to_deltas), so they are meant for
+    /// topics whose subscribers expect them.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot_interval` - The number of messages after which the whole document is sent again.
+    ///
+    /// # Returns
+    ///
+    /// The publisher.
+    #[must_use]
+    pub fn with_deltas(mut self, snapshot_interval: u32) -> Publisher<T> {
+        self.deltas = Some(DeltaEncoder::new(snapshot_interval));
+        self
+    }
+
+    /// Gets the transport this publisher sends messages with.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Checks if the publisher has been shut down.
+    pub fn is_shut_down(&self) -> bool {
+        self.drain.is_closed()
+    }
+
+    /// Publishes a message to a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to publish to.
+    /// * `payload` - The payload of the message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError::Validation` if the topic or the resulting attributes are invalid, or the topic
+    /// has not been declared with the publisher's topic registry, a `UProtocolError::Serialization` if a sequence
+    /// number is to be stamped on a payload that refers to shared memory or deltas are enabled and the payload does
+    /// not contain a JSON document, a `UStatus` with code `UCode::Unavailable`
+    /// or `UCode::Cancelled` if the publisher has been shut down, or the failure reported by the transport.
+    pub async fn publish(&self, topic: UUri, payload: UPayload) -> Result<(), UProtocolError> {
+        self.drain.run(self.publish_now(topic, payload)).await
+    }
+
+    async fn publish_now(&self, topic: UUri, payload: UPayload) -> Result<(), UProtocolError> {
+        UriValidator::validate(&topic)?;
+        if let Some(topic_registry) = &self.topic_registry {
+            topic_registry.check(&topic, UMessageType::UmessageTypePublish)?;
+        }
+        let attributes = self.profile.publish().build();
+        Validators::Publish.validator().validate(&attributes)?;
+        let payload = match &self.deltas {
+            Some(deltas) => deltas.encode(&topic, &payload)?,
+            None => payload,
+        };
+        let payload = match &self.sequence_numbers {
+            Some(sequence_numbers) => {
+                next_sequence_number(sequence_numbers, &topic).stamp(payload)?
+            }
+            None => payload,
+        };
+        Ok(self.transport.send(topic, payload, attributes).await?)
+    }
+}
+
+#[async_trait]
+impl<T: UTransport + Send + Sync> Lifecycle for Publisher<T> {
+    async fn shutdown(&self, deadline: Duration) -> Result<(), UStatus> {
+        self.drain.shutdown(deadline).await
+    }
+}
+
+fn next_sequence_number(
+    sequence_numbers: &Mutex<HashMap<Vec<u8>, SequenceNumber>>,
+    topic: &UUri,
+) -> SequenceNumber {
+    let mut sequence_numbers = sequence_numbers.lock().unwrap();
+    let next = sequence_numbers
+        .entry(topic.encode_to_vec())
+        .or_insert(SequenceNumber(0));
+    let sequence = *next;
+    *next = sequence.next();
+    sequence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fixtures::RecordingTransport;
+    use crate::pubsub::StateUpdate;
+    use crate::transport::builder::UPayloadBuilder;
+    use crate::uprotocol::{UCode, UEntity, UResource};
+
+    fn build_topic_for_test() -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from("door.front_left#Door")),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish() {
+        let publisher =
+            Publisher::new(RecordingTransport::default()).with_priority(UPriority::UpriorityCs2);
+        let payload = UPayloadBuilder::from_raw(vec![1, 2, 3]).build().unwrap();
+
+        publisher
+            .publish(build_topic_for_test(), payload.clone())
+            .await
+            .unwrap();
+
+        let sent = publisher.transport().sent();
+        let (topic, sent_payload, attributes) = &sent[0];
+        assert_eq!(topic, &build_topic_for_test());
+        assert_eq!(sent_payload, &payload);
+        assert_eq!(attributes.r#type, UMessageType::UmessageTypePublish as i32);
+        assert_eq!(attributes.priority, UPriority::UpriorityCs2 as i32);
+        assert!(attributes.id.as_ref().unwrap().is_uprotocol_uuid());
+    }
+
+    #[tokio::test]
+    async fn test_publish_applies_profile() {
+        let profile = EntityProfile::new()
+            .with_ttl(UMessageType::UmessageTypePublish, 500)
+            .with_permission_level(2);
+        let publisher = Publisher::new(RecordingTransport::default())
+            .with_priority(UPriority::UpriorityCs3)
+            .with_profile(profile);
+
+        publisher
+            .publish(build_topic_for_test(), UPayload::default())
+            .await
+            .unwrap();
+
+        let sent = publisher.transport().sent();
+        let attributes = &sent[0].2;
+        assert_eq!(attributes.priority, UPriority::UpriorityCs3 as i32);
+        assert_eq!(attributes.ttl, Some(500));
+        assert_eq!(attributes.permission_level, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_publish_fails_for_undeclared_topic() {
+        let registry = Arc::new(TopicRegistry::new());
+        registry
+            .declare(
+                build_topic_for_test(),
+                UMessageType::UmessageTypePublish,
+                "example.Door",
+            )
+            .unwrap();
+        let publisher = Publisher::new(RecordingTransport::default()).with_topic_registry(registry);
+
+        assert!(publisher
+            .publish(build_topic_for_test(), UPayload::default())
+            .await
+            .is_ok());
+        let mut misspelled = build_topic_for_test();
+        misspelled.resource = Some(UResource::from("door.front_lfet#Door"));
+        let error = publisher
+            .publish(misspelled, UPayload::default())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), UCode::NotFound);
+        assert_eq!(publisher.transport().sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_publish_with_sequence_numbers() {
+        let publisher = Publisher::new(RecordingTransport::default()).with_sequence_numbers();
+        let mut other_topic = build_topic_for_test();
+        other_topic.resource = Some(UResource::from("door.front_right#Door"));
+        let payload = UPayloadBuilder::from_raw(vec![1, 2, 3]).build().unwrap();
+
+        for topic in [build_topic_for_test(), build_topic_for_test(), other_topic] {
+            publisher.publish(topic, payload.clone()).await.unwrap();
+        }
+
+        let sent = publisher.transport().sent();
+        let sequence_numbers: Vec<SequenceNumber> = sent
+            .iter()
+            .map(|(_, sent_payload, _)| {
+                let (sequence, unstamped) = SequenceNumber::take(sent_payload.clone()).unwrap();
+                assert_eq!(unstamped, payload);
+                sequence
+            })
+            .collect();
+        assert_eq!(
+            sequence_numbers,
+            vec![SequenceNumber(0), SequenceNumber(1), SequenceNumber(0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publish_with_deltas() {
+        let publisher = Publisher::new(RecordingTransport::default()).with_deltas(10);
+        for position in [0, 40] {
+            let document = serde_json::json!({"position": position, "model": "x".repeat(50)});
+            let payload = UPayload::from_json_value(&document).unwrap();
+            publisher
+                .publish(build_topic_for_test(), payload)
+                .await
+                .unwrap();
+        }
+
+        let sent = publisher.transport().sent();
+        assert!(matches!(
+            StateUpdate::from_payload(&sent[0].1).unwrap(),
+            StateUpdate::Snapshot(_)
+        ));
+        assert_eq!(
+            StateUpdate::from_payload(&sent[1].1).unwrap(),
+            StateUpdate::MergePatch(serde_json::json!({"position": 40}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publish_fails_after_shutdown() {
+        let publisher = Publisher::new(RecordingTransport::default());
+        publisher.shutdown(Duration::from_secs(1)).await.unwrap();
+
+        let error = publisher
+            .publish(build_topic_for_test(), UPayload::default())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), UCode::Unavailable);
+        assert!(publisher.is_shut_down());
+        assert!(publisher.transport().sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_publish_fails_for_invalid_topic() {
+        let publisher = Publisher::new(RecordingTransport::default());
+
+        let error = publisher
+            .publish(UUri::default(), UPayload::default())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), UCode::InvalidArgument);
+        assert!(publisher.transport().sent().is_empty());
+    }
+}
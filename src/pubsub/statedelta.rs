@@ -0,0 +1,451 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use prost::Message;
+use serde_json::{Map, Value};
+
+use crate::transport::builder::{SerializationError, UPayloadBuilder};
+use crate::transport::datamodel::UListener;
+use crate::uprotocol::{Data, UCode, UMessage, UPayload, UStatus, UUri};
+
+type ErrorHandler = Box<dyn Fn(&UMessage, UStatus) + Send + Sync + 'static>;
+
+const SNAPSHOT: u8 = 0x00;
+const MERGE_PATCH: u8 = 0x01;
+
+/// An update of the state of a topic, as published by a [`Publisher`](crate::pubsub::Publisher) with deltas
+/// enabled.
+///
+/// The state is a JSON document. An update carries either the whole document, or a
+/// [JSON merge patch](https://www.rfc-editor.org/rfc/rfc7396) against the document published before. In the payload,
+/// the update is a byte telling its kind, `0x00` for a snapshot and `0x01` for a merge patch, followed by the
+/// document, and has format `UpayloadFormatRaw`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateUpdate {
+    /// The whole state.
+    Snapshot(Value),
+    /// The changes to the state published before.
+    MergePatch(Value),
+}
+
+impl StateUpdate {
+    /// Puts this update into a payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the update does not fit into a `UPayload`.
+    pub fn to_payload(&self) -> Result<UPayload, SerializationError> {
+        let (kind, document) = match self {
+            StateUpdate::Snapshot(document) => (SNAPSHOT, document),
+            StateUpdate::MergePatch(document) => (MERGE_PATCH, document),
+        };
+        let mut data = vec![kind];
+        serde_json::to_writer(&mut data, document)
+            .map_err(|e| SerializationError::json("Failed to serialize state update", e))?;
+        UPayloadBuilder::from_raw(data).build()
+    }
+
+    /// Takes an update out of a payload created with [`StateUpdate::to_payload`].
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The received payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload does not carry a state update.
+    pub fn from_payload(payload: &UPayload) -> Result<StateUpdate, SerializationError> {
+        let Some(Data::Value(data)) = &payload.data else {
+            return Err(SerializationError::new("UPayload carries no state update"));
+        };
+        let Some((kind, document)) = data.split_first() else {
+            return Err(SerializationError::new("UPayload carries no state update"));
+        };
+        let document = serde_json::from_slice(document)
+            .map_err(|e| SerializationError::json("UPayload carries no state update", e))?;
+        match *kind {
+            SNAPSHOT => Ok(StateUpdate::Snapshot(document)),
+            MERGE_PATCH => Ok(StateUpdate::MergePatch(document)),
+            kind => Err(SerializationError::new(format!(
+                "Unknown kind of state update [{kind}]"
+            ))),
+        }
+    }
+}
+
+/// Applies a JSON merge patch to a document, as specified by [RFC 7396](https://www.rfc-editor.org/rfc/rfc7396).
+///
+/// # Arguments
+///
+/// * `document` - The document to change.
+/// * `patch` - The patch.
+pub fn apply_merge_patch(document: &mut Value, patch: &Value) {
+    let Value::Object(members) = patch else {
+        *document = patch.clone();
+        return;
+    };
+    if !document.is_object() {
+        *document = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = document {
+        for (name, value) in members {
+            if value.is_null() {
+                target.remove(name);
+            } else {
+                apply_merge_patch(target.entry(name.as_str()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Creates the JSON merge patch that turns one document into another.
+///
+/// Merge patches remove the members that they set to `null`, so members of objects whose value is `null` are
+/// missing from the document that results from applying the patch. Arrays are replaced as a whole.
+///
+/// # Arguments
+///
+/// * `from` - The document the patch is applied to.
+/// * `to` - The document that results from applying the patch.
+///
+/// # Returns
+///
+/// The patch, which is an empty object if the documents are equal.
+pub fn create_merge_patch(from: &Value, to: &Value) -> Value {
+    let (Value::Object(from), Value::Object(to)) = (from, to) else {
+        return to.clone();
+    };
+    let mut patch = Map::new();
+    for name in from.keys().filter(|name| !to.contains_key(*name)) {
+        patch.insert(name.clone(), Value::Null);
+    }
+    for (name, value) in to {
+        match from.get(name) {
+            Some(previous) if previous == value => {}
+            Some(previous) => {
+                patch.insert(name.clone(), create_merge_patch(previous, value));
+            }
+            None => {
+                patch.insert(name.clone(), value.clone());
+            }
+        }
+    }
+    Value::Object(patch)
+}
+
+/// `DeltaEncoder` turns the JSON documents published to topics into [`StateUpdate`]s, so that large state that
+/// changes slowly is only published as a whole now and then, and as merge patches otherwise.
+///
+/// The last document is kept per topic. A snapshot is published for the first document of a topic, every
+/// `snapshot_interval` documents after that, so that subscribers that have joined in the meantime or missed a patch
+/// catch up, and whenever the snapshot is not larger than the patch.
+pub struct DeltaEncoder {
+    snapshot_interval: u32,
+    // the last document and the number of patches published since the last snapshot, keyed by the encoded topic
+    last: Mutex<HashMap<Vec<u8>, (Value, u32)>>,
+}
+
+impl DeltaEncoder {
+    /// Creates an encoder.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot_interval` - The number of updates after which a snapshot is published, which is at least 1.
+    pub fn new(snapshot_interval: u32) -> Self {
+        DeltaEncoder {
+            snapshot_interval: snapshot_interval.max(1),
+            last: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Encodes the payload published to a topic as state update.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic.
+    /// * `payload` - The payload, which contains a JSON document.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload does not have format `UpayloadFormatJson`, or does not contain a
+    /// valid JSON document.
+    pub fn encode(&self, topic: &UUri, payload: &UPayload) -> Result<UPayload, SerializationError> {
+        let document = payload.to_json_value()?;
+        let key = topic.encode_to_vec();
+        let mut last = self.last.lock().unwrap();
+        let patch = match last.get(&key) {
+            Some((previous, patches)) if patches + 1 < self.snapshot_interval => Some((
+                StateUpdate::MergePatch(create_merge_patch(previous, &document)).to_payload()?,
+                patches + 1,
+            )),
+            _ => None,
+        };
+        let snapshot = StateUpdate::Snapshot(document.clone()).to_payload()?;
+        let (update, patches) = match patch {
+            Some((patch, patches)) if patch.data_len() < snapshot.data_len() => (patch, patches),
+            _ => (snapshot, 0),
+        };
+        last.insert(key, (document, patches));
+        Ok(update)
+    }
+}
+
+/// `DeltaAssembler` wraps the listener of topics that are published with deltas, so that the listener receives the
+/// whole state of a topic with each message, as a payload with format `UpayloadFormatJson`.
+///
+/// The state is kept per topic, i.e. per source of the received messages. A merge patch received before the first
+/// snapshot of its topic is dropped and reported to the handler set with [`DeltaAssembler::on_error`], if any,
+/// along with a `UStatus` with code `FAILED_PRECONDITION`, as are messages without a state update with code
+/// `INVALID_ARGUMENT`. A lost patch goes unnoticed until the next snapshot, so topics whose subscribers need to know
+/// are also published with sequence numbers and received through a [`GapDetector`](crate::pubsub::GapDetector)
+/// wrapping the assembler. Statuses about messages that cannot be delivered are passed on.
+pub struct DeltaAssembler {
+    listener: UListener,
+    on_error: Option<ErrorHandler>,
+    // keyed by the encoded source of the messages
+    states: Mutex<HashMap<Vec<u8>, Value>>,
+}
+
+impl DeltaAssembler {
+    /// Creates an assembler.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to call with the messages carrying the whole state.
+    pub fn new(listener: UListener) -> Self {
+        DeltaAssembler {
+            listener,
+            on_error: None,
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets a handler that is called for each message that is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to call with the received message and the status describing the problem.
+    #[must_use]
+    pub fn on_error<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&UMessage, UStatus) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Box::new(handler));
+        self
+    }
+
+    /// Gets the current state of a topic, if a snapshot of it has been received.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic.
+    pub fn state(&self, topic: &UUri) -> Option<Value> {
+        self.states
+            .lock()
+            .unwrap()
+            .get(&topic.encode_to_vec())
+            .cloned()
+    }
+
+    /// Applies the state update of a received message and passes the message on to the wrapped listener, with the
+    /// whole state as payload, unless it is to be dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The received message, or the status about a message that cannot be delivered.
+    pub fn on_receive(&self, result: Result<UMessage, UStatus>) {
+        let Ok(mut message) = result else {
+            (self.listener)(result);
+            return;
+        };
+        let update = match StateUpdate::from_payload(&message.payload.take().unwrap_or_default()) {
+            Ok(update) => update,
+            Err(error) => {
+                self.report(
+                    &message,
+                    UStatus::fail_with_code(UCode::InvalidArgument, error.to_string().as_str()),
+                );
+                return;
+            }
+        };
+
+        let key = message
+            .source
+            .as_ref()
+            .map(Message::encode_to_vec)
+            .unwrap_or_default();
+        let state = {
+            let mut states = self.states.lock().unwrap();
+            match update {
+                StateUpdate::Snapshot(document) => {
+                    states.insert(key, document.clone());
+                    Some(document)
+                }
+                StateUpdate::MergePatch(patch) => states.get_mut(&key).map(|state| {
+                    apply_merge_patch(state, &patch);
+                    state.clone()
+                }),
+            }
+        };
+        let Some(state) = state else {
+            self.report(
+                &message,
+                UStatus::fail_with_code(
+                    UCode::FailedPrecondition,
+                    "Merge patch received before the first snapshot",
+                ),
+            );
+            return;
+        };
+        match UPayload::from_json_value(&state) {
+            Ok(payload) => {
+                message.payload = Some(payload);
+                (self.listener)(Ok(message));
+            }
+            Err(error) => self.report(
+                &message,
+                UStatus::fail_with_code(UCode::ResourceExhausted, error.to_string().as_str()),
+            ),
+        }
+    }
+
+    /// Converts this into a listener that can be registered with a
+    /// [`UTransport`](crate::transport::datamodel::UTransport).
+    ///
+    /// The states stay available through the `Arc`, if it is kept.
+    pub fn into_listener(self: Arc<Self>) -> UListener {
+        Box::new(move |result| self.on_receive(result))
+    }
+
+    fn report(&self, message: &UMessage, status: UStatus) {
+        if let Some(on_error) = &self.on_error {
+            on_error(message, status);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use crate::uprotocol::{UEntity, UResource};
+
+    fn build_topic_for_test() -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from("door.front_left#Door")),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_patch_round_trip() {
+        let from =
+            json!({"open": false, "window": {"position": 0, "tinted": true}, "locks": [1, 2]});
+        let to = json!({"open": true, "window": {"position": 40, "tinted": true}, "locks": [1]});
+
+        let patch = create_merge_patch(&from, &to);
+        assert_eq!(
+            patch,
+            json!({"open": true, "window": {"position": 40}, "locks": [1]})
+        );
+        let mut document = from.clone();
+        apply_merge_patch(&mut document, &patch);
+        assert_eq!(document, to);
+
+        let patch = create_merge_patch(&to, &json!({"open": true}));
+        assert_eq!(patch, json!({"window": null, "locks": null}));
+        assert_eq!(create_merge_patch(&to, &to), json!({}));
+    }
+
+    #[test]
+    fn test_encoder_publishes_snapshots_and_patches() {
+        let encoder = DeltaEncoder::new(3);
+        let topic = build_topic_for_test();
+        let large = "x".repeat(100);
+        let updates: Vec<StateUpdate> = (0..4)
+            .map(|position| {
+                let document = json!({"position": position, "model": large});
+                let payload = UPayload::from_json_value(&document).unwrap();
+                StateUpdate::from_payload(&encoder.encode(&topic, &payload).unwrap()).unwrap()
+            })
+            .collect();
+
+        assert!(matches!(updates[0], StateUpdate::Snapshot(_)));
+        assert_eq!(updates[1], StateUpdate::MergePatch(json!({"position": 1})));
+        assert_eq!(updates[2], StateUpdate::MergePatch(json!({"position": 2})));
+        assert!(matches!(updates[3], StateUpdate::Snapshot(_)));
+
+        let payload = UPayload::from_json_value(&json!(1)).unwrap();
+        assert!(matches!(
+            StateUpdate::from_payload(&encoder.encode(&topic, &payload).unwrap()).unwrap(),
+            StateUpdate::Snapshot(_)
+        ));
+        assert!(encoder.encode(&topic, &UPayload::default()).is_err());
+    }
+
+    #[test]
+    fn test_assembler_passes_on_whole_state() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let (received_sink, errors_sink) = (Arc::clone(&received), Arc::clone(&errors));
+        let assembler = Arc::new(
+            DeltaAssembler::new(Box::new(move |result: Result<UMessage, UStatus>| {
+                let payload = result.unwrap().payload.unwrap();
+                received_sink
+                    .lock()
+                    .unwrap()
+                    .push(payload.to_json_value().unwrap());
+            }))
+            .on_error(move |_message, status| errors_sink.lock().unwrap().push(status.get_code())),
+        );
+        let listener = Arc::clone(&assembler).into_listener();
+        let message = |update: StateUpdate| UMessage {
+            source: Some(build_topic_for_test()),
+            payload: Some(update.to_payload().unwrap()),
+            ..Default::default()
+        };
+
+        listener(Ok(message(StateUpdate::MergePatch(json!({"open": true})))));
+        listener(Ok(message(StateUpdate::Snapshot(
+            json!({"open": false, "position": 0}),
+        ))));
+        listener(Ok(message(StateUpdate::MergePatch(json!({"open": true})))));
+        listener(Ok(UMessage::default()));
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                json!({"open": false, "position": 0}),
+                json!({"open": true, "position": 0})
+            ]
+        );
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec![UCode::FailedPrecondition, UCode::InvalidArgument]
+        );
+        assert_eq!(
+            assembler.state(&build_topic_for_test()),
+            Some(json!({"open": true, "position": 0}))
+        );
+    }
+}
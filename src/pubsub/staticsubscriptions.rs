@@ -0,0 +1,399 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pubsub::Subscriber;
+use crate::transport::datamodel::UTransport;
+use crate::types::serializationerror::SerializationError;
+use crate::uprotocol::{UCode, UMessage, UStatus, UUri};
+use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+use crate::UProtocolError;
+
+type SharedListener = Arc<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>;
+
+fn default_retries() -> u32 {
+    3
+}
+
+fn default_retry_delay_ms() -> u64 {
+    1000
+}
+
+/// A subscription of a [`StaticSubscriptionsConfig`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticSubscription {
+    /// The topic to subscribe to, in long form.
+    pub topic: String,
+    /// The name that the listener for the topic has been added to the [`StaticSubscriptions`] with.
+    pub listener: String,
+}
+
+/// The configuration of [`StaticSubscriptions`], e.g. as loaded from a JSON file.
+///
+/// ```json
+/// {
+///   "subscriptions": [
+///     { "topic": "/body.access/1/door.front_left#Door", "listener": "doors" },
+///     { "topic": "/body.access/1/door.front_right#Door", "listener": "doors" }
+///   ],
+///   "retries": 5,
+///   "retry_delay_ms": 200
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticSubscriptionsConfig {
+    /// The subscriptions.
+    #[serde(default)]
+    pub subscriptions: Vec<StaticSubscription>,
+    /// The number of times a failed subscription is retried, 3 if not set.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// The time between retries in milliseconds, 1000 if not set.
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+}
+
+impl Default for StaticSubscriptionsConfig {
+    fn default() -> Self {
+        StaticSubscriptionsConfig {
+            subscriptions: Vec::new(),
+            retries: default_retries(),
+            retry_delay_ms: default_retry_delay_ms(),
+        }
+    }
+}
+
+/// The outcome of [`StaticSubscriptions::subscribe_all`].
+#[derive(Debug, Default)]
+pub struct SubscriptionReport {
+    /// The topics that have been subscribed to, along with the identifiers of their listeners.
+    pub subscribed: Vec<(UUri, String)>,
+    /// The topics, as configured, that could not be subscribed to, along with the last failure.
+    pub failed: Vec<(String, UProtocolError)>,
+}
+
+impl SubscriptionReport {
+    /// Checks if all configured topics have been subscribed to.
+    pub fn is_ready(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Gets the readiness as status, e.g. for reporting it with a
+    /// [`HealthMonitor`](crate::pubsub::HealthMonitor).
+    ///
+    /// # Returns
+    ///
+    /// `UStatus::ok()` if all configured topics have been subscribed to, or a status with the code of the first
+    /// failure and a message naming all topics that could not be subscribed to.
+    pub fn status(&self) -> UStatus {
+        let Some((_, first)) = self.failed.first() else {
+            return UStatus::ok();
+        };
+        let topics: Vec<&str> = self
+            .failed
+            .iter()
+            .map(|(topic, _)| topic.as_str())
+            .collect();
+        UStatus::fail_with_code(
+            first.code(),
+            &format!("Failed to subscribe to [{}]", topics.join(", ")),
+        )
+    }
+}
+
+/// `StaticSubscriptions` performs the subscriptions that an application needs at startup, as listed in its
+/// configuration, so that the application only needs to provide the listeners.
+///
+/// Listeners are added by name, and the configuration maps topics to these names. All subscriptions are performed
+/// by a [`Subscriber`], i.e. with the uSubscription service if the subscriber has one. Failed subscriptions are
+/// retried, except for topics that are malformed or refer to listeners that have not been added.
+pub struct StaticSubscriptions {
+    config: StaticSubscriptionsConfig,
+    listeners: HashMap<String, SharedListener>,
+}
+
+impl StaticSubscriptions {
+    /// Creates static subscriptions without listeners.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The subscriptions to perform.
+    pub fn new(config: StaticSubscriptionsConfig) -> StaticSubscriptions {
+        StaticSubscriptions {
+            config,
+            listeners: HashMap::new(),
+        }
+    }
+
+    /// Creates static subscriptions from a JSON configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The configuration, as shown for [`StaticSubscriptionsConfig`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the configuration is malformed.
+    pub fn from_json(json: &str) -> Result<StaticSubscriptions, SerializationError> {
+        let config = serde_json::from_str(json)
+            .map_err(|e| SerializationError::json("Invalid subscriptions", e))?;
+        Ok(Self::new(config))
+    }
+
+    /// Creates static subscriptions from a JSON configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the file cannot be read, or any of the errors returned by
+    /// [`StaticSubscriptions::from_json`].
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<StaticSubscriptions, SerializationError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            SerializationError::other(
+                format!("Failed to read subscriptions [{}]", path.display()),
+                e,
+            )
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Adds a listener that subscriptions can refer to.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the listener, as used in the configuration.
+    /// * `listener` - The listener, which is registered for every topic that refers to it.
+    ///
+    /// # Returns
+    ///
+    /// The static subscriptions.
+    #[must_use]
+    pub fn with_listener<N, F>(mut self, name: N, listener: F) -> StaticSubscriptions
+    where
+        N: Into<String>,
+        F: Fn(Result<UMessage, UStatus>) + Send + Sync + 'static,
+    {
+        self.listeners.insert(name.into(), Arc::new(listener));
+        self
+    }
+
+    /// Subscribes to all configured topics.
+    ///
+    /// The SDK does not depend on a particular async runtime, so the function to wait between retries with is
+    /// passed in, e.g. `tokio::time::sleep`.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscriber` - The subscriber to subscribe with.
+    /// * `sleep` - The function returning a future that completes after the given time.
+    ///
+    /// # Returns
+    ///
+    /// The topics that have been subscribed to, and the ones that could not be subscribed to.
+    pub async fn subscribe_all<T, S, F>(
+        &self,
+        subscriber: &Subscriber<T>,
+        sleep: S,
+    ) -> SubscriptionReport
+    where
+        T: UTransport,
+        S: Fn(Duration) -> F,
+        F: Future<Output = ()>,
+    {
+        let mut report = SubscriptionReport::default();
+        for subscription in &self.config.subscriptions {
+            match self.subscribe(subscriber, subscription, &sleep).await {
+                Ok(subscribed) => report.subscribed.push(subscribed),
+                Err(e) => report.failed.push((subscription.topic.clone(), e)),
+            }
+        }
+        report
+    }
+
+    async fn subscribe<T, S, F>(
+        &self,
+        subscriber: &Subscriber<T>,
+        subscription: &StaticSubscription,
+        sleep: &S,
+    ) -> Result<(UUri, String), UProtocolError>
+    where
+        T: UTransport,
+        S: Fn(Duration) -> F,
+        F: Future<Output = ()>,
+    {
+        let topic = LongUriSerializer::deserialize(subscription.topic.clone())?;
+        let listener = self.listeners.get(&subscription.listener).ok_or_else(|| {
+            UStatus::fail_with_code(
+                UCode::NotFound,
+                &format!("Listener [{}] has not been added", subscription.listener),
+            )
+        })?;
+
+        let mut attempt = 0;
+        loop {
+            let listener = Arc::clone(listener);
+            let result = subscriber
+                .subscribe(topic.clone(), Box::new(move |message| listener(message)))
+                .await;
+            match result {
+                Ok(id) => return Ok((topic, id)),
+                Err(e @ UProtocolError::Validation(_)) => return Err(e),
+                Err(e) if attempt >= self.config.retries => return Err(e),
+                Err(_) => {
+                    attempt += 1;
+                    sleep(Duration::from_millis(self.config.retry_delay_ms)).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use crate::uprotocol::{UAttributes, UEntity, UPayload};
+
+    // Fails to register the first listeners.
+    struct FlakyTransport {
+        failures: Mutex<usize>,
+        listeners: Mutex<Vec<UUri>>,
+    }
+
+    impl FlakyTransport {
+        fn new(failures: usize) -> FlakyTransport {
+            FlakyTransport {
+                failures: Mutex::new(failures),
+                listeners: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl UTransport for FlakyTransport {
+        async fn authenticate(&self, _entity: UEntity) -> Result<(), UStatus> {
+            Ok(())
+        }
+
+        async fn send(
+            &self,
+            _topic: UUri,
+            _payload: UPayload,
+            _attributes: UAttributes,
+        ) -> Result<(), UStatus> {
+            Err(UStatus::fail_with_code(
+                UCode::Unimplemented,
+                "not supported",
+            ))
+        }
+
+        async fn register_listener(
+            &self,
+            topic: UUri,
+            _listener: Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>,
+        ) -> Result<String, UStatus> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(UStatus::fail_with_code(UCode::Unavailable, "not connected"));
+            }
+            let mut listeners = self.listeners.lock().unwrap();
+            listeners.push(topic);
+            Ok(format!("listener-{}", listeners.len()))
+        }
+
+        async fn unregister_listener(&self, _topic: UUri, _listener: &str) -> Result<(), UStatus> {
+            Ok(())
+        }
+    }
+
+    const CONFIG: &str = r#"{
+        "subscriptions": [
+            { "topic": "/body.access/1/door.front_left#Door", "listener": "doors" },
+            { "topic": "/body.access/1/door.front_right#Door", "listener": "doors" }
+        ],
+        "retries": 2,
+        "retry_delay_ms": 200
+    }"#;
+
+    #[tokio::test]
+    async fn test_subscribe_all_retries_failed_subscriptions() {
+        let subscriptions = StaticSubscriptions::from_json(CONFIG)
+            .unwrap()
+            .with_listener("doors", |_| {});
+        let subscriber = Subscriber::new(FlakyTransport::new(2));
+        let delays = Mutex::new(Vec::new());
+
+        let report = subscriptions
+            .subscribe_all(&subscriber, |delay| {
+                delays.lock().unwrap().push(delay);
+                async {}
+            })
+            .await;
+
+        assert!(report.is_ready());
+        assert_eq!(report.status(), UStatus::ok());
+        assert_eq!(report.subscribed.len(), 2);
+        assert_eq!(report.subscribed[1].1, "listener-2");
+        assert_eq!(subscriber.transport().listeners.lock().unwrap().len(), 2);
+        assert_eq!(*delays.lock().unwrap(), vec![Duration::from_millis(200); 2]);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_all_reports_failures() {
+        let subscriptions = StaticSubscriptions::new(StaticSubscriptionsConfig {
+            subscriptions: vec![
+                StaticSubscription {
+                    topic: "/body.access/1/door.front_left#Door".to_string(),
+                    listener: "windows".to_string(),
+                },
+                StaticSubscription {
+                    topic: "/body.access/1/door.front_right#Door".to_string(),
+                    listener: "doors".to_string(),
+                },
+            ],
+            retries: 1,
+            ..Default::default()
+        })
+        .with_listener("doors", |_| {});
+        let subscriber = Subscriber::new(FlakyTransport::new(2));
+
+        let report = subscriptions.subscribe_all(&subscriber, |_| async {}).await;
+
+        assert!(!report.is_ready());
+        assert!(report.subscribed.is_empty());
+        assert_eq!(report.failed[0].1.code(), UCode::NotFound);
+        assert_eq!(report.failed[1].1.code(), UCode::Unavailable);
+        let status = report.status();
+        assert_eq!(status.get_code(), UCode::NotFound);
+        assert_eq!(
+            status.message.as_deref(),
+            Some("Failed to subscribe to [/body.access/1/door.front_left#Door, /body.access/1/door.front_right#Door]")
+        );
+    }
+}
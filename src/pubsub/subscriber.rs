@@ -0,0 +1,717 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::pubsub::DeltaAssembler;
+use crate::transport::datamodel::{CancellationToken, Drain, Lifecycle, UTransport};
+use crate::uprotocol::core::usubscription::v3::{
+    subscription_status::State, NotificationsRequest, SubscriberInfo, SubscriptionRequest,
+    UnsubscribeRequest,
+};
+use crate::uprotocol::{UCode, UMessage, UStatus, UUri};
+use crate::uri::validator::UriValidator;
+use crate::usubscription::{
+    into_message_listener, SubscriptionChangeListener, USubscriptionClient,
+};
+use crate::UProtocolError;
+
+// The uSubscription service along with the uEntity that subscribes to topics.
+struct SubscriptionService {
+    client: USubscriptionClient,
+    subscriber: SubscriberInfo,
+}
+
+// A listener that the subscriber has registered with the transport.
+struct Registration {
+    topic: UUri,
+    listener: String,
+    // whether the listener is for changes to the subscriptions to the topic, rather than for its messages
+    change_listener: bool,
+}
+
+/// `Subscriber` is the uP-L2 building block for subscribing to topics.
+///
+/// When a uSubscription service is configured, subscribing to a topic first asks the service to subscribe
+/// to the topic, and only then registers the listener with the underlying [`UTransport`]. Unsubscribing
+/// reverts both steps. Without a uSubscription service, only the listener is (un)registered, which is
+/// sufficient for topics published by uEntities on the local device. Shutting the subscriber down by means of
+/// [`Lifecycle::shutdown`] unsubscribes from all topics it has subscribed to. For more information, please refer
+/// to the [uProtocol Specification](https://github.com/eclipse-uprotocol/uprotocol-spec/blob/main/up-l2/README.adoc).
+pub struct Subscriber<T: UTransport> {
+    transport: T,
+    subscription_service: Option<SubscriptionService>,
+    registrations: Mutex<Vec<Registration>>,
+    drain: Drain,
+}
+
+impl<T: UTransport> Subscriber<T> {
+    /// Creates a subscriber without a uSubscription service.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to register listeners with.
+    pub fn new(transport: T) -> Subscriber<T> {
+        Subscriber {
+            transport,
+            subscription_service: None,
+            registrations: Mutex::new(Vec::new()),
+            drain: Drain::new(),
+        }
+    }
+
+    /// Sets the uSubscription service to perform subscriptions with.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to invoke the uSubscription service's methods with.
+    /// * `subscriber` - The URI of the uEntity that subscribes to topics.
+    ///
+    /// # Returns
+    ///
+    /// The subscriber.
+    #[must_use]
+    pub fn with_subscription_service(
+        mut self,
+        client: USubscriptionClient,
+        subscriber: UUri,
+    ) -> Subscriber<T> {
+        self.subscription_service = Some(SubscriptionService {
+            client,
+            subscriber: SubscriberInfo {
+                uri: Some(subscriber),
+                ..Default::default()
+            },
+        });
+        self
+    }
+
+    /// Gets the transport this subscriber registers listeners with.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Subscribes to a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to subscribe to.
+    /// * `listener` - The listener to invoke with the messages published to the topic.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the registered listener, to be passed to [`Subscriber::unsubscribe`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError::Validation` if the topic is invalid, the failure reported
+    /// by the uSubscription service if it rejects the subscription, a `UStatus` with code `UCode::Unavailable` or
+    /// `UCode::Cancelled` if the subscriber has been shut down, or the failure reported by the transport.
+    pub async fn subscribe(
+        &self,
+        topic: UUri,
+        listener: Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>,
+    ) -> Result<String, UProtocolError> {
+        self.drain.run(self.subscribe_now(topic, listener)).await
+    }
+
+    async fn subscribe_now(
+        &self,
+        topic: UUri,
+        listener: Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>,
+    ) -> Result<String, UProtocolError> {
+        UriValidator::validate(&topic)?;
+
+        let Some(service) = &self.subscription_service else {
+            let listener = self
+                .transport
+                .register_listener(topic.clone(), listener)
+                .await?;
+            self.record(topic, &listener, false);
+            return Ok(listener);
+        };
+        let request = SubscriptionRequest {
+            topic: Some(topic.clone()),
+            subscriber: Some(service.subscriber.clone()),
+            ..Default::default()
+        };
+        let response = service.client.subscribe(request).await?;
+        let status = response.status.unwrap_or_default();
+        match State::try_from(status.state) {
+            Ok(State::Subscribed | State::SubscribePending) => {}
+            _ => {
+                let code = UCode::try_from(status.code)
+                    .ok()
+                    .filter(|code| *code != UCode::Ok)
+                    .unwrap_or(UCode::FailedPrecondition);
+                return Err(UStatus::fail_with_code(
+                    code,
+                    &format!("Subscription to topic was rejected: {}", status.message),
+                )
+                .into());
+            }
+        }
+
+        match self
+            .transport
+            .register_listener(topic.clone(), listener)
+            .await
+        {
+            Ok(listener) => {
+                self.record(topic, &listener, false);
+                Ok(listener)
+            }
+            Err(status) => {
+                // do not leave behind a subscription that nobody listens to
+                let _ = service.unsubscribe(topic).await;
+                Err(status.into())
+            }
+        }
+    }
+
+    /// Subscribes to a topic that is published with deltas, i.e. by a [`Publisher`](crate::pubsub::Publisher) with
+    /// [`Publisher::with_deltas`](crate::pubsub::Publisher::with_deltas), so that the listener receives the whole
+    /// state of the topic with each message.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to subscribe to.
+    /// * `listener` - The listener to invoke with the messages carrying the whole state, as JSON document.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the registered listener, to be passed to [`Subscriber::unsubscribe`].
+    ///
+    /// # Errors
+    ///
+    /// Returns any of the errors returned by [`Subscriber::subscribe`].
+    pub async fn subscribe_to_deltas(
+        &self,
+        topic: UUri,
+        listener: Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>,
+    ) -> Result<String, UProtocolError> {
+        let assembler = Arc::new(DeltaAssembler::new(listener));
+        self.subscribe(topic, assembler.into_listener()).await
+    }
+
+    /// Subscribes to a topic for as long as a token has not been cancelled.
+    ///
+    /// The returned future completes once the token is cancelled, after having unsubscribed from the topic. Other
+    /// than dropping the future of [`Subscriber::subscribe`], cancelling the token while the subscription is being
+    /// made does not leave a listener registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to subscribe to.
+    /// * `listener` - The listener to invoke with the messages published to the topic.
+    /// * `token` - The token that ends the subscription.
+    ///
+    /// # Errors
+    ///
+    /// Returns any of the errors returned by [`Subscriber::subscribe`] and [`Subscriber::unsubscribe`].
+    pub async fn subscribe_until_cancelled(
+        &self,
+        topic: UUri,
+        listener: Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>,
+        token: &CancellationToken,
+    ) -> Result<(), UProtocolError> {
+        let subscribe = self.subscribe(topic.clone(), listener);
+        futures::pin_mut!(subscribe);
+        let listener = match token.run_until_cancelled(&mut subscribe).await {
+            Some(subscribed) => subscribed?,
+            // let the subscription complete, so that it can be undone
+            None => subscribe.await?,
+        };
+        token.cancelled().await;
+        self.unsubscribe(topic, &listener).await
+    }
+
+    /// Unsubscribes from a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to unsubscribe from.
+    /// * `listener` - The identifier of the listener, as returned by [`Subscriber::subscribe`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::Unavailable` or `UCode::Cancelled` if the subscriber has been shut
+    /// down, or the failure reported by the transport, or by the uSubscription service.
+    pub async fn unsubscribe(&self, topic: UUri, listener: &str) -> Result<(), UProtocolError> {
+        self.drain.run(self.unsubscribe_now(topic, listener)).await
+    }
+
+    async fn unsubscribe_now(&self, topic: UUri, listener: &str) -> Result<(), UProtocolError> {
+        self.transport
+            .unregister_listener(topic.clone(), listener)
+            .await?;
+        self.forget(&topic, listener);
+        match &self.subscription_service {
+            Some(service) => service.unsubscribe(topic).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Registers a listener for changes to the subscriptions to a topic, e.g. because the topic's producer has
+    /// appeared or gone away.
+    ///
+    /// The uSubscription service is asked to notify this subscriber about the topic, and the listener is registered
+    /// with the underlying [`UTransport`] for the service's notifications topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to be informed about.
+    /// * `listener` - The listener to invoke with the updates about the topic.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the registered listener, to be passed to [`Subscriber::unregister_change_listener`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError::Validation` if the topic is invalid, a `UStatus` with code
+    /// `UCode::FailedPrecondition` if no uSubscription service is configured, with code `UCode::Unavailable` or
+    /// `UCode::Cancelled` if the subscriber has been shut down, the failure reported by the uSubscription service, or
+    /// the failure reported by the transport.
+    pub async fn register_change_listener(
+        &self,
+        topic: UUri,
+        listener: Box<dyn SubscriptionChangeListener>,
+    ) -> Result<String, UProtocolError> {
+        self.drain
+            .run(self.register_change_listener_now(topic, listener))
+            .await
+    }
+
+    async fn register_change_listener_now(
+        &self,
+        topic: UUri,
+        listener: Box<dyn SubscriptionChangeListener>,
+    ) -> Result<String, UProtocolError> {
+        UriValidator::validate(&topic)?;
+
+        let service = self.require_subscription_service()?;
+        service
+            .client
+            .register_for_notifications(service.notifications_request(topic.clone()))
+            .await?;
+
+        match self
+            .transport
+            .register_listener(
+                USubscriptionClient::notifications_topic(),
+                into_message_listener(topic.clone(), listener),
+            )
+            .await
+        {
+            Ok(listener) => {
+                self.record(topic, &listener, true);
+                Ok(listener)
+            }
+            Err(status) => {
+                // do not leave behind a registration that nobody listens to
+                let _ = service
+                    .client
+                    .unregister_for_notifications(service.notifications_request(topic))
+                    .await;
+                Err(status.into())
+            }
+        }
+    }
+
+    /// Unregisters a listener for changes to the subscriptions to a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic the listener has been registered for.
+    /// * `listener` - The identifier of the listener, as returned by [`Subscriber::register_change_listener`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::FailedPrecondition` if no uSubscription service is configured,
+    /// with code `UCode::Unavailable` or `UCode::Cancelled` if the subscriber has been shut down, the failure
+    /// reported by the transport, or by the uSubscription service.
+    pub async fn unregister_change_listener(
+        &self,
+        topic: UUri,
+        listener: &str,
+    ) -> Result<(), UProtocolError> {
+        self.drain
+            .run(self.unregister_change_listener_now(topic, listener))
+            .await
+    }
+
+    async fn unregister_change_listener_now(
+        &self,
+        topic: UUri,
+        listener: &str,
+    ) -> Result<(), UProtocolError> {
+        let service = self.require_subscription_service()?;
+        self.transport
+            .unregister_listener(USubscriptionClient::notifications_topic(), listener)
+            .await?;
+        self.forget(&topic, listener);
+        service
+            .client
+            .unregister_for_notifications(service.notifications_request(topic))
+            .await
+    }
+
+    fn record(&self, topic: UUri, listener: &str, change_listener: bool) {
+        self.registrations.lock().unwrap().push(Registration {
+            topic,
+            listener: listener.to_string(),
+            change_listener,
+        });
+    }
+
+    fn forget(&self, topic: &UUri, listener: &str) {
+        self.registrations.lock().unwrap().retain(|registration| {
+            registration.topic != *topic || registration.listener != listener
+        });
+    }
+
+    fn require_subscription_service(&self) -> Result<&SubscriptionService, UStatus> {
+        self.subscription_service.as_ref().ok_or_else(|| {
+            UStatus::fail_with_code(
+                UCode::FailedPrecondition,
+                "Subscription changes require a uSubscription service",
+            )
+        })
+    }
+}
+
+#[async_trait]
+impl<T: UTransport + Send + Sync> Lifecycle for Subscriber<T> {
+    async fn shutdown(&self, deadline: Duration) -> Result<(), UStatus> {
+        let mut result = self.drain.shutdown(deadline).await;
+        let registrations = std::mem::take(&mut *self.registrations.lock().unwrap());
+        for registration in registrations {
+            let unregistered = if registration.change_listener {
+                self.unregister_change_listener_now(registration.topic, &registration.listener)
+                    .await
+            } else {
+                self.unsubscribe_now(registration.topic, &registration.listener)
+                    .await
+            };
+            result = result.and(unregistered.map_err(UStatus::from));
+        }
+        result
+    }
+}
+
+impl SubscriptionService {
+    async fn unsubscribe(&self, topic: UUri) -> Result<(), UProtocolError> {
+        self.client
+            .unsubscribe(UnsubscribeRequest {
+                topic: Some(topic),
+                subscriber: Some(self.subscriber.clone()),
+            })
+            .await
+    }
+
+    fn notifications_request(&self, topic: UUri) -> NotificationsRequest {
+        NotificationsRequest {
+            topic: Some(topic),
+            subscriber: Some(self.subscriber.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use prost::Message;
+    use prost_types::Any;
+
+    use crate::fixtures::RecordingTransport;
+    use crate::pubsub::StateUpdate;
+    use crate::rpc::{RpcClient, RpcClientResult, RpcMapper, RpcMapperError};
+    use crate::uprotocol::core::usubscription::v3::{
+        SubscriptionResponse, SubscriptionStatus, Update,
+    };
+    use crate::uprotocol::{UAttributes, UEntity, UPayload, UResource};
+
+    // Accepts subscriptions to all topics except those with resource `denied`.
+    struct USubscriptionRpcClient;
+
+    #[async_trait]
+    impl RpcClient for USubscriptionRpcClient {
+        async fn invoke_method(
+            topic: UUri,
+            payload: UPayload,
+            attributes: UAttributes,
+        ) -> RpcClientResult {
+            assert_eq!(attributes.sink.as_ref(), Some(&topic));
+            let Some(crate::uprotocol::Data::Value(data)) = payload.data else {
+                return Err(RpcMapperError::InvalidPayload("no data".to_string()));
+            };
+            match topic.resource.unwrap().id {
+                Some(1) => {
+                    let request = SubscriptionRequest::decode(data.as_slice()).unwrap();
+                    let state = if request.topic.unwrap().resource.unwrap().name == "denied" {
+                        State::Unsubscribed
+                    } else {
+                        State::Subscribed
+                    };
+                    let response = SubscriptionResponse {
+                        status: Some(SubscriptionStatus {
+                            state: state.into(),
+                            code: UCode::PermissionDenied.into(),
+                            message: String::new(),
+                        }),
+                        ..Default::default()
+                    };
+                    Ok(Any {
+                        type_url: "type.googleapis.com/uprotocol.core.usubscription.v3.SubscriptionResponse"
+                            .to_string(),
+                        value: response.encode_to_vec(),
+                    }
+                    .try_into()
+                    .unwrap())
+                }
+                Some(2 | 6 | 7) => Ok(RpcMapper::pack_any(&UStatus::ok())?.try_into().unwrap()),
+                _ => Err(RpcMapperError::UnexpectedError(
+                    "unknown method".to_string(),
+                )),
+            }
+        }
+    }
+
+    fn build_topic_for_test(resource: &str) -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from(resource)),
+            ..Default::default()
+        }
+    }
+
+    fn build_subscriber_for_test() -> Subscriber<RecordingTransport> {
+        Subscriber::new(RecordingTransport::default()).with_subscription_service(
+            USubscriptionClient::new::<USubscriptionRpcClient>(),
+            build_topic_for_test("rpc.response"),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_without_subscription_service() {
+        let subscriber = Subscriber::new(RecordingTransport::default());
+        let topic = build_topic_for_test("door.front_left#Door");
+
+        let listener = subscriber
+            .subscribe(topic.clone(), Box::new(|_msg| {}))
+            .await
+            .unwrap();
+        assert_eq!(subscriber.transport().topics(), vec![topic.clone()]);
+
+        subscriber.unsubscribe(topic, &listener).await.unwrap();
+        assert!(subscriber.transport().topics().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_with_subscription_service() {
+        let subscriber = build_subscriber_for_test();
+        let topic = build_topic_for_test("door.front_left#Door");
+
+        let listener = subscriber
+            .subscribe(topic.clone(), Box::new(|_msg| {}))
+            .await
+            .unwrap();
+        assert_eq!(subscriber.transport().topics(), vec![topic.clone()]);
+
+        subscriber.unsubscribe(topic, &listener).await.unwrap();
+        assert!(subscriber.transport().topics().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_unsubscribes_from_all_topics() {
+        let subscriber = build_subscriber_for_test();
+        for resource in ["door.front_left#Door", "door.front_right#Door"] {
+            subscriber
+                .subscribe(build_topic_for_test(resource), Box::new(|_msg| {}))
+                .await
+                .unwrap();
+        }
+        assert_eq!(subscriber.transport().topics().len(), 2);
+
+        subscriber.shutdown(Duration::from_secs(1)).await.unwrap();
+        assert!(subscriber.transport().topics().is_empty());
+
+        let error = subscriber
+            .subscribe(
+                build_topic_for_test("door.front_left#Door"),
+                Box::new(|_msg| {}),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), UCode::Unavailable);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_to_deltas() {
+        let subscriber = Subscriber::new(RecordingTransport::default());
+        let topic = build_topic_for_test("door.front_left#Door");
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let states = received.clone();
+
+        subscriber
+            .subscribe_to_deltas(
+                topic.clone(),
+                Box::new(move |result: Result<UMessage, UStatus>| {
+                    let payload = result.unwrap().payload.unwrap();
+                    states
+                        .lock()
+                        .unwrap()
+                        .push(payload.to_json_value().unwrap());
+                }),
+            )
+            .await
+            .unwrap();
+        for update in [
+            StateUpdate::Snapshot(serde_json::json!({"open": false, "position": 0})),
+            StateUpdate::MergePatch(serde_json::json!({"position": 40})),
+        ] {
+            subscriber.transport().deliver(
+                &topic,
+                Ok(UMessage {
+                    source: Some(topic.clone()),
+                    payload: Some(update.to_payload().unwrap()),
+                    ..Default::default()
+                }),
+            );
+        }
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                serde_json::json!({"open": false, "position": 0}),
+                serde_json::json!({"open": false, "position": 40})
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_until_cancelled() {
+        let subscriber = build_subscriber_for_test();
+        let token = CancellationToken::new();
+        let subscription = subscriber.subscribe_until_cancelled(
+            build_topic_for_test("door.front_left#Door"),
+            Box::new(|_msg| {}),
+            &token,
+        );
+        futures::pin_mut!(subscription);
+
+        assert!(futures::poll!(&mut subscription).is_pending());
+        assert_eq!(subscriber.transport().topics().len(), 1);
+
+        token.cancel();
+        subscription.await.unwrap();
+        assert!(subscriber.transport().topics().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_fails_if_subscription_is_rejected() {
+        let subscriber = build_subscriber_for_test();
+
+        let error = subscriber
+            .subscribe(build_topic_for_test("denied"), Box::new(|_msg| {}))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), UCode::PermissionDenied);
+        assert!(subscriber.transport().topics().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_fails_for_invalid_topic() {
+        let subscriber = build_subscriber_for_test();
+
+        let error = subscriber
+            .subscribe(UUri::default(), Box::new(|_msg| {}))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), UCode::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_register_change_listener() {
+        let subscriber = build_subscriber_for_test();
+        let topic = build_topic_for_test("door.front_left#Door");
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let updates = received.clone();
+
+        let listener = subscriber
+            .register_change_listener(
+                topic.clone(),
+                Box::new(move |update: Update| updates.lock().unwrap().push(update)),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            subscriber.transport().topics(),
+            vec![USubscriptionClient::notifications_topic()]
+        );
+
+        let update = Update {
+            topic: Some(topic.clone()),
+            status: Some(SubscriptionStatus {
+                state: State::Unsubscribed.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let payload = Any {
+            type_url: "type.googleapis.com/uprotocol.core.usubscription.v3.Update".to_string(),
+            value: update.encode_to_vec(),
+        }
+        .try_into()
+        .unwrap();
+        subscriber.transport().deliver(
+            &USubscriptionClient::notifications_topic(),
+            Ok(UMessage {
+                payload: Some(payload),
+                ..Default::default()
+            }),
+        );
+        assert_eq!(*received.lock().unwrap(), vec![update]);
+
+        subscriber
+            .unregister_change_listener(topic, &listener)
+            .await
+            .unwrap();
+        assert!(subscriber.transport().topics().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_register_change_listener_fails_without_subscription_service() {
+        let subscriber = Subscriber::new(RecordingTransport::default());
+
+        let error = subscriber
+            .register_change_listener(
+                build_topic_for_test("door.front_left#Door"),
+                Box::new(|_update: Update| {}),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), UCode::FailedPrecondition);
+        assert!(subscriber.transport().topics().is_empty());
+    }
+}
@@ -0,0 +1,305 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use serde::Serialize;
+
+use crate::transport::builder::{SerializationError, UPayloadBuilder};
+use crate::types::validationerror::ValidationError;
+use crate::uprotocol::{UCode, UMessageType, UPayload, UResource, UUri};
+use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+use crate::uri::validator::UriValidator;
+
+/// The resource of the RPC method that lists the topics a uEntity has declared.
+pub const LIST_TOPICS_RESOURCE: &str = "rpc.ListTopics";
+
+/// A topic that a uEntity produces messages on, as declared with a [`TopicRegistry`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicDeclaration {
+    /// The topic.
+    pub topic: UUri,
+    /// The type of the messages sent to the topic.
+    pub message_type: UMessageType,
+    /// The identifier of the schema of the messages' payload, e.g. the full name of a protobuf message.
+    pub schema: String,
+}
+
+// A declaration as listed by the RPC method, with the topic in long form.
+#[derive(Serialize)]
+struct ListedTopic<'a> {
+    topic: &'a str,
+    message_type: &'a str,
+    schema: &'a str,
+}
+
+/// `TopicRegistry` holds the topics that a uEntity produces messages on, so that messages to undeclared topics,
+/// e.g. because of a typo in a topic's name, are caught before they are sent.
+///
+/// The topics are declared at startup. A [`Publisher`](crate::pubsub::Publisher) that has a registry set fails to
+/// publish to topics that have not been declared for published messages, and other uEntities can list the declared
+/// topics by means of the RPC method answered with [`TopicRegistry::list_topics_response`].
+#[derive(Debug, Default)]
+pub struct TopicRegistry {
+    // keyed by the topics' long form, which is what typos show up in
+    topics: RwLock<BTreeMap<String, TopicDeclaration>>,
+}
+
+impl TopicRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> TopicRegistry {
+        TopicRegistry::default()
+    }
+
+    /// Gets the URI of the RPC method that lists the topics of a uEntity.
+    ///
+    /// # Arguments
+    ///
+    /// * `entity` - The URI of the uEntity.
+    pub fn list_topics_method(entity: &UUri) -> UUri {
+        UUri {
+            authority: entity.authority.clone(),
+            entity: entity.entity.clone(),
+            resource: Some(UResource::from(LIST_TOPICS_RESOURCE)),
+        }
+    }
+
+    /// Declares a topic.
+    ///
+    /// Declaring a topic again with the same message type and schema has no effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic, with the names of its uEntity and resource set.
+    /// * `message_type` - The type of the messages sent to the topic.
+    /// * `schema` - The identifier of the schema of the messages' payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` if the topic is invalid or has no long form, or with code
+    /// `UCode::AlreadyExists` if the topic has been declared with a different message type or schema before.
+    pub fn declare<T: Into<String>>(
+        &self,
+        topic: UUri,
+        message_type: UMessageType,
+        schema: T,
+    ) -> Result<(), ValidationError> {
+        UriValidator::validate(&topic)?;
+        let key = long_form(&topic)?;
+        let declaration = TopicDeclaration {
+            topic,
+            message_type,
+            schema: schema.into(),
+        };
+
+        let mut topics = self.topics.write().unwrap();
+        match topics.get(&key) {
+            Some(declared)
+                if declared.message_type != declaration.message_type
+                    || declared.schema != declaration.schema =>
+            {
+                Err(
+                    ValidationError::new(format!("Topic [{key}] has been declared differently"))
+                        .with_field("topic")
+                        .with_code(UCode::AlreadyExists),
+                )
+            }
+            Some(_) => Ok(()),
+            None => {
+                topics.insert(key, declaration);
+                Ok(())
+            }
+        }
+    }
+
+    /// Gets the declaration of a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic.
+    pub fn get(&self, topic: &UUri) -> Option<TopicDeclaration> {
+        let key = long_form(topic).ok()?;
+        self.topics.read().unwrap().get(&key).cloned()
+    }
+
+    /// Gets all declarations, ordered by the long form of their topics.
+    pub fn topics(&self) -> Vec<TopicDeclaration> {
+        self.topics.read().unwrap().values().cloned().collect()
+    }
+
+    /// Checks that a topic has been declared for a type of messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic that a message is to be sent to.
+    /// * `message_type` - The type of the message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` with code `UCode::NotFound` if the topic has not been declared, or with code
+    /// `UCode::InvalidArgument` if it has been declared for another type of messages.
+    pub fn check(&self, topic: &UUri, message_type: UMessageType) -> Result<(), ValidationError> {
+        let key = long_form(topic)?;
+        let topics = self.topics.read().unwrap();
+        let Some(declared) = topics.get(&key) else {
+            return Err(
+                ValidationError::new(format!("Topic [{key}] has not been declared"))
+                    .with_field("topic")
+                    .with_code(UCode::NotFound),
+            );
+        };
+        if declared.message_type != message_type {
+            return Err(ValidationError::new(format!(
+                "Topic [{key}] has been declared for {} messages",
+                declared.message_type
+            ))
+            .with_field("topic"));
+        }
+        Ok(())
+    }
+
+    /// Creates the response of the RPC method listing the declared topics.
+    ///
+    /// The payload is a JSON array of objects with the `topic` in long form, its `message_type` as named in the
+    /// uProtocol proto definitions, and its `schema`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the declarations cannot be serialized.
+    pub fn list_topics_response(&self) -> Result<UPayload, SerializationError> {
+        let topics = self.topics.read().unwrap();
+        let listed: Vec<ListedTopic> = topics
+            .iter()
+            .map(|(topic, declaration)| ListedTopic {
+                topic,
+                message_type: declaration.message_type.as_str_name(),
+                schema: &declaration.schema,
+            })
+            .collect();
+        UPayloadBuilder::from_json(&listed)?.build()
+    }
+}
+
+fn long_form(topic: &UUri) -> Result<String, ValidationError> {
+    LongUriSerializer::serialize(topic)
+        .ok()
+        .filter(|long_form| !long_form.is_empty())
+        .ok_or_else(|| ValidationError::new("Topic has no long form").with_field("topic"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uprotocol::{Data, UEntity};
+
+    fn build_topic_for_test(resource: &str) -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from(resource)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_declared_topics() {
+        let registry = TopicRegistry::new();
+        let topic = build_topic_for_test("door.front_left#Door");
+        registry
+            .declare(
+                topic.clone(),
+                UMessageType::UmessageTypePublish,
+                "example.Door",
+            )
+            .unwrap();
+
+        assert!(registry
+            .check(&topic, UMessageType::UmessageTypePublish)
+            .is_ok());
+        assert_eq!(
+            registry
+                .check(&topic, UMessageType::UmessageTypeNotification)
+                .unwrap_err()
+                .code(),
+            UCode::InvalidArgument
+        );
+        assert_eq!(
+            registry
+                .check(
+                    &build_topic_for_test("door.front_lfet#Door"),
+                    UMessageType::UmessageTypePublish
+                )
+                .unwrap_err()
+                .code(),
+            UCode::NotFound
+        );
+    }
+
+    #[test]
+    fn test_declare_conflicting_topic_fails() {
+        let registry = TopicRegistry::new();
+        let topic = build_topic_for_test("door.front_left#Door");
+        let declare = |schema: &str| {
+            registry.declare(topic.clone(), UMessageType::UmessageTypePublish, schema)
+        };
+
+        assert!(declare("example.Door").is_ok());
+        assert!(declare("example.Door").is_ok());
+        assert_eq!(
+            declare("example.Window").unwrap_err().code(),
+            UCode::AlreadyExists
+        );
+        assert_eq!(registry.get(&topic).unwrap().schema, "example.Door");
+        assert!(registry
+            .declare(
+                UUri::default(),
+                UMessageType::UmessageTypePublish,
+                "example.Door"
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_list_topics_response() {
+        let registry = TopicRegistry::new();
+        registry
+            .declare(
+                build_topic_for_test("door.front_left#Door"),
+                UMessageType::UmessageTypePublish,
+                "example.Door",
+            )
+            .unwrap();
+
+        let payload = registry.list_topics_response().unwrap();
+        let Some(Data::Value(data)) = payload.data else {
+            panic!("payload has no data");
+        };
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&data).unwrap(),
+            serde_json::json!([{
+                "topic": "/body.access/1/door.front_left#Door",
+                "message_type": "UMESSAGE_TYPE_PUBLISH",
+                "schema": "example.Door"
+            }])
+        );
+        assert_eq!(
+            TopicRegistry::list_topics_method(&build_topic_for_test("door")).resource,
+            Some(UResource::from("rpc.ListTopics"))
+        );
+    }
+}
@@ -0,0 +1,230 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::{Buf, BufMut};
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::transport::Channel;
+
+use crate::rpc::{RpcClient, RpcClientResult, RpcMapper, RpcMapperError};
+use crate::uprotocol::{UAttributes, UPayload, UStatus, UUri};
+
+/// A gRPC service that methods can be invoked on by means of a [`GrpcRpcClient`].
+pub trait GrpcService {
+    /// Gets the channel to the server that provides the service.
+    ///
+    /// Channels are cheap to clone, so a channel that is created once and kept e.g. in a static is
+    /// usually returned here.
+    fn channel() -> Channel;
+
+    /// Gets the gRPC path of the method that a uProtocol method URI refers to.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The URI of the method to invoke.
+    ///
+    /// # Returns
+    ///
+    /// The path, which by default is made up of the entity name as service name and the
+    /// resource instance as method name, e.g. `/body.access/UpdateDoor` for `/body.access/1/rpc.UpdateDoor`.
+    /// `None` if the URI does not refer to a method.
+    fn path(method: &UUri) -> Option<String> {
+        let entity = method.entity.as_ref()?;
+        let resource = method.resource.as_ref()?;
+        let instance = resource.instance.as_ref()?;
+        Some(format!("/{}/{}", entity.name, instance))
+    }
+}
+
+/// `RpcClient` that invokes the methods of a native gRPC service, so that services in the cloud can be
+/// called by means of the same API as uProtocol services.
+///
+/// The request payload is expected to contain the encoded request message, as created by
+/// [`RpcMapper::pack_payload`], and is sent as is. The response message is returned in a `google.protobuf.Any`
+/// without type URL, as gRPC does not tell the response type. If the gRPC call fails, the payload
+/// contains the failed [`UStatus`] instead, which [`RpcMapper::map_response_to_result`] picks up.
+pub struct GrpcRpcClient<S: GrpcService> {
+    service: PhantomData<S>,
+}
+
+#[async_trait]
+impl<S: GrpcService + Send + Sync + 'static> RpcClient for GrpcRpcClient<S> {
+    async fn invoke_method(
+        topic: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+    ) -> RpcClientResult {
+        Self::check_payload(&payload)?;
+        let path = S::path(&topic)
+            .and_then(|path| PathAndQuery::try_from(path).ok())
+            .ok_or_else(|| {
+                RpcMapperError::UnexpectedError(format!("No gRPC method for URI {topic}"))
+            })?;
+        let data = payload
+            .resolve_data(None)
+            .map_err(|e| RpcMapperError::InvalidPayload(e.to_string()))?
+            .unwrap_or_default()
+            .to_vec();
+
+        let mut request = tonic::Request::new(data);
+        if let Some(ttl) = attributes.ttl.and_then(|ttl| u64::try_from(ttl).ok()) {
+            request.set_timeout(Duration::from_millis(ttl));
+        }
+
+        let mut grpc = tonic::client::Grpc::new(S::channel());
+        grpc.ready()
+            .await
+            .map_err(|e| RpcMapperError::UnexpectedError(e.to_string()))?;
+        map_grpc_response(grpc.unary(request, path, RawCodec).await)
+    }
+}
+
+fn map_grpc_response(response: Result<tonic::Response<Vec<u8>>, tonic::Status>) -> RpcClientResult {
+    let payload = match response {
+        Ok(response) => UPayload::from_message_bytes("", &response.into_inner()),
+        Err(status) => {
+            let any = RpcMapper::pack_any(&UStatus::from(status))?;
+            UPayload::try_from(&any)
+        }
+    };
+    payload.map_err(|e| RpcMapperError::InvalidPayload(e.to_string()))
+}
+
+// Passes encoded messages through, as they already are encoded in the request payload and
+// are decoded by the caller from the response payload.
+#[derive(Debug, Clone, Copy, Default)]
+struct RawCodec;
+
+impl Codec for RawCodec {
+    type Encode = Vec<u8>;
+    type Decode = Vec<u8>;
+    type Encoder = RawCodec;
+    type Decoder = RawCodec;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        RawCodec
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        RawCodec
+    }
+}
+
+impl Encoder for RawCodec {
+    type Item = Vec<u8>;
+    type Error = tonic::Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.put_slice(&item);
+        Ok(())
+    }
+}
+
+impl Decoder for RawCodec {
+    type Item = Vec<u8>;
+    type Error = tonic::Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(Some(src.copy_to_bytes(src.remaining()).to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prost_types::Any;
+
+    use crate::uprotocol::{UCode, UEntity, UResource};
+    use crate::uri::builder::resourcebuilder::UResourceBuilder;
+
+    struct TestService;
+
+    impl GrpcService for TestService {
+        fn channel() -> Channel {
+            Channel::from_static("http://localhost:50051").connect_lazy()
+        }
+    }
+
+    #[test]
+    fn test_default_path() {
+        let method = UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResourceBuilder::for_rpc_request(
+                Some("UpdateDoor".to_string()),
+                None,
+            )),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            TestService::path(&method),
+            Some("/body.access/UpdateDoor".to_string())
+        );
+    }
+
+    #[test]
+    fn test_default_path_requires_method_name() {
+        let method = UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                name: "rpc".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(TestService::path(&method), None);
+    }
+
+    #[test]
+    fn test_map_grpc_response() {
+        let response = map_grpc_response(Ok(tonic::Response::new(vec![8, 1]))).unwrap();
+
+        let any = Any::try_from(response).unwrap();
+        assert_eq!(any.value, vec![8, 1]);
+    }
+
+    #[test]
+    fn test_map_failed_grpc_response_to_status() {
+        let response = map_grpc_response(Err(tonic::Status::not_found("no such door")));
+
+        let result = RpcMapper::map_response_to_result(response).unwrap();
+        assert!(result.status.is_failed());
+        assert_eq!(result.status.get_code(), UCode::NotFound);
+        assert_eq!(result.status.message(), "no such door");
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_fails_for_uri_without_method() {
+        let result = GrpcRpcClient::<TestService>::invoke_method(
+            UUri::default(),
+            UPayload::default(),
+            UAttributes::default(),
+        )
+        .await;
+
+        assert!(matches!(result, Err(RpcMapperError::UnexpectedError(_))));
+    }
+}
@@ -0,0 +1,96 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use bytes::Bytes;
+use prost::Message;
+
+use crate::uprotocol::{UCode, UStatus};
+
+// The uProtocol status codes are the gRPC status codes, so they map one to one.
+impl From<UCode> for tonic::Code {
+    fn from(code: UCode) -> Self {
+        tonic::Code::from(code.grpc_equivalent())
+    }
+}
+
+impl From<tonic::Code> for UCode {
+    fn from(code: tonic::Code) -> Self {
+        UCode::try_from(code as i32).unwrap_or(UCode::Unknown)
+    }
+}
+
+// The details of a `tonic::Status` are an encoded `google.rpc.Status`, which is wire compatible with `UStatus`.
+impl From<UStatus> for tonic::Status {
+    fn from(status: UStatus) -> Self {
+        let code = tonic::Code::from(status.get_code());
+        let message = status.message().to_string();
+        if status.details.is_empty() {
+            tonic::Status::new(code, message)
+        } else {
+            tonic::Status::with_details(code, message, Bytes::from(status.encode_to_vec()))
+        }
+    }
+}
+
+impl From<tonic::Status> for UStatus {
+    fn from(status: tonic::Status) -> Self {
+        let details = UStatus::decode(status.details())
+            .map(|details| details.details)
+            .unwrap_or_default();
+        UStatus {
+            code: UCode::from(status.code()).into(),
+            message: (!status.message().is_empty()).then(|| status.message().to_string()),
+            details,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test_case(UCode::Ok, tonic::Code::Ok; "ok")]
+    #[test_case(UCode::InvalidArgument, tonic::Code::InvalidArgument; "invalid argument")]
+    #[test_case(UCode::NotFound, tonic::Code::NotFound; "not found")]
+    #[test_case(UCode::Unavailable, tonic::Code::Unavailable; "unavailable")]
+    #[test_case(UCode::Unauthenticated, tonic::Code::Unauthenticated; "unauthenticated")]
+    fn test_code_conversion(ucode: UCode, code: tonic::Code) {
+        assert_eq!(tonic::Code::from(ucode), code);
+        assert_eq!(UCode::from(code), ucode);
+    }
+
+    #[test]
+    fn test_status_round_trip() {
+        let status = UStatus::fail_with_code(UCode::PermissionDenied, "not allowed")
+            .with_message_detail(&UStatus::fail("root cause"));
+
+        let grpc_status = tonic::Status::from(status.clone());
+        assert_eq!(grpc_status.code(), tonic::Code::PermissionDenied);
+        assert_eq!(grpc_status.message(), "not allowed");
+
+        assert_eq!(UStatus::from(grpc_status), status);
+    }
+
+    #[test]
+    fn test_status_without_details() {
+        let grpc_status = tonic::Status::deadline_exceeded("too slow");
+
+        let status = UStatus::from(grpc_status);
+
+        assert_eq!(status.get_code(), UCode::DeadlineExceeded);
+        assert_eq!(status.message(), "too slow");
+        assert!(status.details.is_empty());
+    }
+}
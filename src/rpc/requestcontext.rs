@@ -0,0 +1,209 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::policy::{AuditEvent, AuditSink, Authenticator, Principal};
+use crate::uprotocol::{UCode, UMessage, UStatus, UUri, Uuid};
+
+/// The context of a request that an RPC server has received, which it passes to the method along with the
+/// request's payload.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestContext {
+    id: Option<Uuid>,
+    source: Option<UUri>,
+    principal: Option<Principal>,
+}
+
+impl RequestContext {
+    /// Creates the context of a request, authenticating its sender if the request carries a token.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request message.
+    /// * `authenticator` - The authenticator to validate the request's token with, if any. Tokens of requests
+    ///                     are ignored without an authenticator.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::Unauthenticated` if the request carries a token, but the
+    /// authenticator fails to validate it.
+    pub fn new(
+        request: &UMessage,
+        authenticator: Option<&dyn Authenticator>,
+    ) -> Result<RequestContext, UStatus> {
+        Self::create(request, authenticator, None)
+    }
+
+    /// Creates the context of a request like [`RequestContext::new`], recording failures to authenticate the
+    /// request's sender with an audit sink.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request message.
+    /// * `authenticator` - The authenticator to validate the request's token with, if any.
+    /// * `audit_sink` - The sink to record an [`AuditEvent`] with if the token is invalid or has expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::Unauthenticated` if the request carries a token, but the
+    /// authenticator fails to validate it.
+    pub fn audited(
+        request: &UMessage,
+        authenticator: Option<&dyn Authenticator>,
+        audit_sink: &dyn AuditSink,
+    ) -> Result<RequestContext, UStatus> {
+        Self::create(request, authenticator, Some(audit_sink))
+    }
+
+    fn create(
+        request: &UMessage,
+        authenticator: Option<&dyn Authenticator>,
+        audit_sink: Option<&dyn AuditSink>,
+    ) -> Result<RequestContext, UStatus> {
+        let attributes = request.attributes.as_ref();
+        let token = attributes
+            .and_then(|attributes| attributes.token.as_deref())
+            .filter(|token| !token.trim().is_empty());
+        let principal = match (token, authenticator) {
+            (Some(token), Some(authenticator)) => {
+                Some(authenticator.authenticate(token).map_err(|status| {
+                    if let Some(audit_sink) = audit_sink {
+                        audit_sink.record(AuditEvent::authentication_failed(request, &status));
+                    }
+                    unauthenticated(&status)
+                })?)
+            }
+            _ => None,
+        };
+        Ok(RequestContext {
+            id: attributes.and_then(|attributes| attributes.id.clone()),
+            source: request.source.clone(),
+            principal,
+        })
+    }
+
+    /// Gets the ID of the request, which the response refers to.
+    pub fn id(&self) -> Option<&Uuid> {
+        self.id.as_ref()
+    }
+
+    /// Gets the URI that the response is sent to.
+    pub fn source(&self) -> Option<&UUri> {
+        self.source.as_ref()
+    }
+
+    /// Gets the identity of the sender of the request, if the request has been authenticated.
+    pub fn principal(&self) -> Option<&Principal> {
+        self.principal.as_ref()
+    }
+}
+
+// Authenticators are to fail with UNAUTHENTICATED, which is enforced so that RPC servers can respond with it
+fn unauthenticated(status: &UStatus) -> UStatus {
+    if status.get_code() == UCode::Unauthenticated {
+        status.clone()
+    } else {
+        UStatus::fail_with_code(
+            UCode::Unauthenticated,
+            status.message.as_deref().unwrap_or("Invalid token"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::UPriority;
+
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<AuditEvent>>);
+    impl AuditSink for RecordingSink {
+        fn record(&self, event: AuditEvent) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    struct NameAuthenticator;
+    impl Authenticator for NameAuthenticator {
+        fn authenticate(&self, token: &str) -> Result<Principal, UStatus> {
+            token
+                .strip_prefix("name:")
+                .map(Principal::new)
+                .ok_or_else(|| UStatus::fail_with_code(UCode::InvalidArgument, "Unknown token"))
+        }
+    }
+
+    fn build_request_for_test(token: Option<&str>) -> UMessage {
+        let mut builder =
+            UAttributesBuilder::request(UPriority::UpriorityCs4, UUri::default(), 1000);
+        if let Some(token) = token {
+            builder.with_token(token);
+        }
+        UMessage {
+            source: Some(UUri::default()),
+            attributes: Some(builder.build()),
+            payload: None,
+        }
+    }
+
+    #[test]
+    fn test_request_with_token_is_authenticated() {
+        let request = build_request_for_test(Some("name:hartley"));
+        let context = RequestContext::new(&request, Some(&NameAuthenticator)).unwrap();
+
+        assert_eq!(context.principal(), Some(&Principal::new("hartley")));
+        assert_eq!(context.id(), request.attributes.unwrap().id.as_ref());
+        assert_eq!(context.source(), Some(&UUri::default()));
+    }
+
+    #[test]
+    fn test_request_with_invalid_token_fails() {
+        let request = build_request_for_test(Some("hartley"));
+        let status = RequestContext::new(&request, Some(&NameAuthenticator)).unwrap_err();
+
+        assert_eq!(status.get_code(), UCode::Unauthenticated);
+        assert_eq!(status.message.as_deref(), Some("Unknown token"));
+    }
+
+    #[test]
+    fn test_request_without_token_is_anonymous() {
+        let request = build_request_for_test(None);
+        let context = RequestContext::new(&request, Some(&NameAuthenticator)).unwrap();
+        assert_eq!(context.principal(), None);
+
+        let request = build_request_for_test(Some("name:hartley"));
+        let context = RequestContext::new(&request, None).unwrap();
+        assert_eq!(context.principal(), None);
+    }
+
+    #[test]
+    fn test_failed_authentication_is_audited() {
+        let sink = RecordingSink::default();
+        let request = build_request_for_test(Some("name:hartley"));
+        assert!(RequestContext::audited(&request, Some(&NameAuthenticator), &sink).is_ok());
+        assert!(sink.0.lock().unwrap().is_empty());
+
+        let request = build_request_for_test(Some("hartley"));
+        assert!(RequestContext::audited(&request, Some(&NameAuthenticator), &sink).is_err());
+        assert_eq!(
+            *sink.0.lock().unwrap(),
+            vec![AuditEvent::AuthenticationFailed {
+                message: (&request).into(),
+                reason: "Unknown token".to_string(),
+            }]
+        );
+    }
+}
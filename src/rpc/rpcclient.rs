@@ -14,6 +14,7 @@
 use async_trait::async_trait;
 
 use crate::rpc::rpcmapper::RpcMapperError;
+use crate::transport::datamodel::{CancellationToken, PayloadLimits};
 use crate::uprotocol::{UAttributes, UPayload, UUri};
 
 pub type RpcClientResult = Result<UPayload, RpcMapperError>;
@@ -31,6 +32,11 @@ pub type RpcClientResult = Result<UPayload, RpcMapperError>;
 pub trait RpcClient {
     /// Support for RPC method invocation.
     ///
+    /// Implementations reject request payloads that exceed [`RpcClient::payload_limits`] before sending them, by
+    /// means of [`RpcClient::check_payload`], so that callers that pack their requests with
+    /// [`RpcMapper::pack_payload`](crate::rpc::RpcMapper::pack_payload) rather than
+    /// [`RpcMapper::pack_request`](crate::rpc::RpcMapper::pack_request) get the same error.
+    ///
     /// # Arguments
     ///
     /// * `topic` - The topic to invoke the method on.
@@ -39,10 +45,75 @@ pub trait RpcClient {
     ///
     /// # Returns
     ///
-    /// Returns a Future with the result or error.
+    /// Returns a Future with the result or error. The error is an `RpcMapperError::ResourceExhausted` if the
+    /// payload exceeds [`RpcClient::payload_limits`].
     async fn invoke_method(
         topic: UUri,
         payload: UPayload,
         attributes: UAttributes,
     ) -> RpcClientResult;
+
+    /// Gets the limits that the underlying transport imposes on request payloads.
+    ///
+    /// Request payloads that exceed these limits are rejected with an `RpcMapperError::ResourceExhausted` by
+    /// [`RpcClient::invoke_method`] and [`RpcMapper::pack_request`](crate::rpc::RpcMapper::pack_request), as well as
+    /// by [`RpcClient::invoke_method_with_cancellation`],
+    /// [`UriResolver::invoke_method`](crate::udiscovery::UriResolver::invoke_method) and the clients of the
+    /// uProtocol core services before they hand the payloads to [`RpcClient::invoke_method`].
+    ///
+    /// # Returns
+    ///
+    /// The limits, which default to [`PayloadLimits::default`], i.e. no limits.
+    fn payload_limits() -> PayloadLimits {
+        PayloadLimits::default()
+    }
+
+    /// Checks a request payload against [`RpcClient::payload_limits`].
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to check.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `RpcMapperError::ResourceExhausted` if the payload exceeds the limits.
+    fn check_payload(payload: &UPayload) -> Result<(), RpcMapperError> {
+        Self::payload_limits().check(payload)?;
+        Ok(())
+    }
+
+    /// Support for RPC method invocation that the caller can abort by means of a [`CancellationToken`].
+    ///
+    /// The default implementation stops waiting for the response once the token is cancelled. Platforms that keep
+    /// state for outstanding requests, like the correlation of requests with responses, override this method to
+    /// remove that state as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to invoke the method on.
+    /// * `payload` - The payload to send.
+    /// * `attributes` - The attributes to send.
+    /// * `token` - The token to abort the invocation with.
+    ///
+    /// # Returns
+    ///
+    /// Returns a Future with the result or error. The error is an `RpcMapperError::Cancelled` if the token has
+    /// been cancelled before the response has been received, or an `RpcMapperError::ResourceExhausted` if the
+    /// payload exceeds [`RpcClient::payload_limits`].
+    async fn invoke_method_with_cancellation(
+        topic: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+        token: &CancellationToken,
+    ) -> RpcClientResult {
+        Self::check_payload(&payload)?;
+        token
+            .run_until_cancelled(Self::invoke_method(topic, payload, attributes))
+            .await
+            .unwrap_or_else(|| {
+                Err(RpcMapperError::Cancelled(
+                    "Invocation has been cancelled".to_string(),
+                ))
+            })
+    }
 }
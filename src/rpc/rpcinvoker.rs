@@ -0,0 +1,140 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::rpc::{RpcClient, RpcClientResult, RpcMapper};
+use crate::transport::builder::{EntityProfile, UAttributesBuilder};
+use crate::transport::datamodel::{emit, LogLevel, PayloadLimits};
+use crate::uprotocol::{UAttributes, UMessageType, UPayload, UPriority, UUri};
+use crate::UProtocolError;
+
+type InvokeMethod =
+    fn(UUri, UPayload, UAttributes) -> Pin<Box<dyn Future<Output = RpcClientResult> + Send>>;
+
+// Invokes the methods of a uProtocol service by means of an RpcClient chosen at construction,
+// which spares clients of the uProtocol core services from being generic over the RpcClient.
+#[derive(Clone, Copy)]
+pub(crate) struct RpcInvoker {
+    invoke_method: InvokeMethod,
+    limits: PayloadLimits,
+    profile: EntityProfile,
+    ttl: u32,
+}
+
+impl RpcInvoker {
+    // Requests to the core services are sent with priority CS4, as mandated for RPC requests, unless the profile
+    // of the uEntity sets a higher one.
+    pub(crate) fn new<R: RpcClient + 'static>(ttl: u32) -> RpcInvoker {
+        RpcInvoker {
+            invoke_method: |topic, payload, attributes| {
+                R::invoke_method(topic, payload, attributes)
+            },
+            limits: R::payload_limits(),
+            profile: EntityProfile::new(),
+            ttl,
+        }
+    }
+
+    pub(crate) fn set_ttl(&mut self, ttl: u32) {
+        self.ttl = ttl;
+    }
+
+    // Takes the profile's time-to-live of requests, if it has one, replacing the one set before.
+    pub(crate) fn set_profile(&mut self, profile: EntityProfile) {
+        if let Some(ttl) = profile.ttl(UMessageType::UmessageTypeRequest) {
+            self.ttl = ttl;
+        }
+        self.profile = profile;
+    }
+
+    pub(crate) async fn invoke<M: prost::Message>(
+        &self,
+        method: UUri,
+        request: &M,
+    ) -> RpcClientResult {
+        let payload = RpcMapper::pack_payload(request)?;
+        self.limits.check(&payload)?;
+        let priority = self
+            .profile
+            .priority(UMessageType::UmessageTypeRequest)
+            .unwrap_or(UPriority::UpriorityCs4);
+        let attributes = UAttributesBuilder::request(priority, method.clone(), self.ttl)
+            .with_profile(&self.profile)
+            .build();
+        let result = (self.invoke_method)(method.clone(), payload, attributes).await;
+        if let Err(e) = &result {
+            emit(
+                LogLevel::Debug,
+                module_path!(),
+                format_args!("Request to {method:?} has failed: {e}"),
+            );
+        }
+        result
+    }
+
+    // Invokes a method that responds with a UStatus, turning failed statuses into errors.
+    pub(crate) async fn invoke_for_status<M: prost::Message>(
+        &self,
+        method: UUri,
+        request: &M,
+    ) -> Result<(), UProtocolError> {
+        let response = self.invoke(method, request).await;
+        let result = RpcMapper::map_response_to_result(response)?;
+        if result.status.is_failed() {
+            return Err(result.status.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+
+    use crate::rpc::RpcMapperError;
+    use crate::uprotocol::{UCode, UStatus};
+
+    // Accepts requests with payloads of up to 8 bytes, and answers them with an OK status.
+    struct LimitedRpcClient;
+
+    #[async_trait]
+    impl RpcClient for LimitedRpcClient {
+        async fn invoke_method(
+            _topic: UUri,
+            _payload: UPayload,
+            _attributes: UAttributes,
+        ) -> RpcClientResult {
+            Ok(RpcMapper::pack_any(&UStatus::ok())?.try_into().unwrap())
+        }
+
+        fn payload_limits() -> PayloadLimits {
+            PayloadLimits::new(8)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invoke_rejects_payload_exceeding_limits() {
+        let invoker = RpcInvoker::new::<LimitedRpcClient>(1000);
+
+        let small = UStatus::fail_with_code(UCode::Aborted, "");
+        assert!(invoker.invoke(UUri::default(), &small).await.is_ok());
+
+        let large = UStatus::fail_with_code(UCode::Aborted, "exceeds the limits");
+        let result = invoker.invoke(UUri::default(), &large).await;
+        assert!(matches!(result, Err(RpcMapperError::ResourceExhausted(_))));
+    }
+}
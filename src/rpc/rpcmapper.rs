@@ -11,11 +11,12 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+use prost::Message;
 use prost_types::Any;
 use std::default::Default;
 use std::fmt;
 
-use crate::rpc::rpcclient::RpcClientResult;
+use crate::rpc::rpcclient::{RpcClient, RpcClientResult};
 use crate::uprotocol::{Data, UCode, UPayload, UPayloadFormat, UStatus};
 
 pub type RpcPayloadResult = Result<RpcPayload, RpcMapperError>;
@@ -32,6 +33,34 @@ pub enum RpcMapperError {
     InvalidPayload(String),
     UnknownType(String),
     ProtobufError(String),
+    ResourceExhausted(String),
+    Cancelled(String),
+}
+
+impl From<UStatus> for RpcMapperError {
+    fn from(status: UStatus) -> Self {
+        match status.get_code() {
+            UCode::ResourceExhausted => {
+                RpcMapperError::ResourceExhausted(status.message().to_string())
+            }
+            UCode::Cancelled => RpcMapperError::Cancelled(status.message().to_string()),
+            _ => RpcMapperError::UnexpectedError(status.message().to_string()),
+        }
+    }
+}
+
+impl From<RpcMapperError> for UStatus {
+    fn from(error: RpcMapperError) -> Self {
+        let code = match error {
+            RpcMapperError::ResourceExhausted(_) => UCode::ResourceExhausted,
+            RpcMapperError::Cancelled(_) => UCode::Cancelled,
+            RpcMapperError::InvalidPayload(_)
+            | RpcMapperError::UnknownType(_)
+            | RpcMapperError::ProtobufError(_) => UCode::DataLoss,
+            RpcMapperError::UnexpectedError(_) => UCode::Unavailable,
+        };
+        UStatus::fail_with_code(code, &error.to_string())
+    }
 }
 
 impl fmt::Display for RpcMapperError {
@@ -41,10 +70,14 @@ impl fmt::Display for RpcMapperError {
             RpcMapperError::InvalidPayload(msg) => write!(f, "Invalid payload: {msg}",),
             RpcMapperError::UnknownType(msg) => write!(f, "Unknown type: {msg}"),
             RpcMapperError::ProtobufError(msg) => write!(f, "Protobuf error: {msg}"),
+            RpcMapperError::ResourceExhausted(msg) => write!(f, "Resource exhausted: {msg}"),
+            RpcMapperError::Cancelled(msg) => write!(f, "Cancelled: {msg}"),
         }
     }
 }
 
+impl std::error::Error for RpcMapperError {}
+
 /// `RpcMapper` is a structure that provides static methods to wrap an RPC request with
 /// an RPC response (uP-L2). APIs that return a `Message` assume that the payload is
 /// protobuf-serialized `com.google.protobuf.Any` (USerializationHint.PROTOBUF), and will
@@ -82,14 +115,7 @@ impl RpcMapper {
         T: prost::Message + Default,
     {
         let payload = response?; // Directly returns in case of error
-        Any::try_from(payload)
-            .map_err(|_e| {
-                RpcMapperError::UnknownType("Couldn't decode payload into Any".to_string())
-            })
-            .and_then(|any| {
-                T::decode(any.value.as_slice())
-                    .map_err(|error| RpcMapperError::InvalidPayload(error.to_string()))
-            })
+        Self::decode_any_value(payload, "Couldn't decode payload into Any")
     }
 
     /// This function checks if a `RpcClientResult` contains a protobuf status type,
@@ -119,39 +145,24 @@ impl RpcMapper {
     // TODO This entire thing feels klunky and kludgy; this needs to be revisited...
     pub fn map_response_to_result(response: RpcClientResult) -> RpcPayloadResult {
         let payload = response?; // Directly returns in case of error
-        Any::try_from(payload)
-            .map_err(|_e| {
-                RpcMapperError::UnknownType("Couldn't decode payload into Any".to_string())
-            })
-            .and_then(|any| {
-                match Self::unpack_any::<UStatus>(&any) {
-                    Ok(proto_status) => {
-                        // in this branch, we have successfully unpacked a protobuf-status from the (now consumed) payload
-                        match UCode::try_from(proto_status.code).unwrap_or(UCode::Unknown) {
-                            UCode::Ok => Ok(RpcPayload {
-                                status: UStatus::ok(),
-                                payload: None,
-                            }),
-                            _ => Ok(RpcPayload {
-                                status: proto_status,
-                                payload: None,
-                            }),
-                        }
-                    }
-                    Err(_error) => {
-                        // in this branch, we couldn't decode the payload into a protobuf-status, but there is something else there to pass on
-                        UPayload::try_from(&any)
-                            .map_err(|e| RpcMapperError::InvalidPayload(e.to_string()))
-                            .map(|payload| RpcPayload {
-                                status: UStatus::fail(&format!(
-                                    "Unexpected any-payload type {}",
-                                    any.type_url
-                                )),
-                                payload: Some(payload), // get the original payload back to avoid having to .clone() payload, above
-                            })
-                    }
-                }
-            })
+        match Self::unpack_status(payload)? {
+            // in this branch, we have successfully unpacked a protobuf-status from the (now consumed) payload
+            Ok(proto_status) => match proto_status.get_code() {
+                UCode::Ok => Ok(RpcPayload {
+                    status: UStatus::ok(),
+                    payload: None,
+                }),
+                _ => Ok(RpcPayload {
+                    status: proto_status,
+                    payload: None,
+                }),
+            },
+            // in this branch, we couldn't decode the payload into a protobuf-status, but there is something else there to pass on
+            Err((type_url, payload)) => Ok(RpcPayload {
+                status: UStatus::fail(&format!("Unexpected any-payload type {type_url}")),
+                payload: Some(payload),
+            }),
+        }
     }
 
     /// Packs a protobuf message into a `UPayload` object.
@@ -190,6 +201,34 @@ impl RpcMapper {
         }
     }
 
+    /// Packs a protobuf message into a `UPayload` object for a request, checking it against the limits of the
+    /// `RpcClient` that the request is intended for.
+    ///
+    /// # Type Parameters
+    ///
+    /// * `R`: The `RpcClient` to invoke the method with.
+    /// * `T`: The type of the request message.
+    ///
+    /// # Parameters
+    ///
+    /// * `request`: The request message to pack.
+    ///
+    /// # Returns
+    ///
+    /// The payload containing the packed request message.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `RpcMapperError::ResourceExhausted` if the packed request exceeds [`RpcClient::payload_limits`],
+    /// or any of the errors returned by [`RpcMapper::pack_payload`].
+    pub fn pack_request<R: RpcClient, T: prost::Message>(
+        request: &T,
+    ) -> Result<UPayload, RpcMapperError> {
+        let payload = Self::pack_payload(request)?;
+        R::check_payload(&payload)?;
+        Ok(payload)
+    }
+
     /// Unpacks a given `UPayload` into a protobuf message.
     ///
     /// This function is used to extract strongly-typed data from a `UPayload` object, leveraging
@@ -215,12 +254,7 @@ impl RpcMapper {
     pub fn unpack_payload<T: prost::Message + std::default::Default>(
         payload: UPayload,
     ) -> Result<T, RpcMapperError> {
-        Any::try_from(payload)
-            .map_err(|_e| RpcMapperError::UnknownType("Couldn't decode payload".to_string()))
-            .and_then(|any| {
-                T::decode(any.value.as_slice())
-                    .map_err(|error| RpcMapperError::InvalidPayload(error.to_string()))
-            })
+        Self::decode_any_value(payload, "Couldn't decode payload")
     }
 
     /// Packs a given `data` of type `T` into a `prost_types::Any` object.
@@ -279,16 +313,87 @@ impl RpcMapper {
         any.to_msg()
             .map_err(|error| RpcMapperError::InvalidPayload(error.to_string()))
     }
+
+    // Decodes the value of the Any contained in a payload into a message of type T.
+    #[cfg(not(feature = "shared-bytes"))]
+    fn decode_any_value<T: prost::Message + Default>(
+        payload: UPayload,
+        any_error: &str,
+    ) -> Result<T, RpcMapperError> {
+        let (_type_url, message) = payload
+            .message_bytes()
+            .map_err(|_e| RpcMapperError::UnknownType(any_error.to_string()))?;
+        T::decode(message).map_err(|error| RpcMapperError::InvalidPayload(error.to_string()))
+    }
+
+    // Decodes the value of the Any contained in a payload into a message of type T,
+    // letting T refer to (instead of copy) the payload data where it uses `Bytes` fields.
+    #[cfg(feature = "shared-bytes")]
+    fn decode_any_value<T: prost::Message + Default>(
+        payload: UPayload,
+        any_error: &str,
+    ) -> Result<T, RpcMapperError> {
+        let (any, _bytes) = payload
+            .into_any_view()
+            .map_err(|_e| RpcMapperError::UnknownType(any_error.to_string()))?;
+        T::decode(any.value).map_err(|error| RpcMapperError::InvalidPayload(error.to_string()))
+    }
+
+    // Tries to unpack a UStatus from a payload. If the payload contains something else, returns
+    // the type URL of the contained Any along with the payload, which is passed on as is.
+    #[cfg(not(feature = "shared-bytes"))]
+    fn unpack_status(
+        payload: UPayload,
+    ) -> Result<Result<UStatus, (String, UPayload)>, RpcMapperError> {
+        let (type_url, message) = payload.message_bytes().map_err(|_e| {
+            RpcMapperError::UnknownType("Couldn't decode payload into Any".to_string())
+        })?;
+        if type_url == <UStatus as prost::Name>::type_url() {
+            if let Ok(proto_status) = UStatus::decode(message) {
+                return Ok(Ok(proto_status));
+            }
+        }
+        let type_url = type_url.to_string();
+        Ok(Err((type_url, payload)))
+    }
+
+    // Like the above, but hands back the original payload data instead of re-encoding the Any.
+    #[cfg(feature = "shared-bytes")]
+    fn unpack_status(
+        payload: UPayload,
+    ) -> Result<Result<UStatus, (String, UPayload)>, RpcMapperError> {
+        let format = payload.format();
+        let (any, bytes) = payload.into_any_view().map_err(|_e| {
+            RpcMapperError::UnknownType("Couldn't decode payload into Any".to_string())
+        })?;
+        if any.type_url == <UStatus as prost::Name>::type_url() {
+            if let Ok(proto_status) = UStatus::decode(any.value) {
+                return Ok(Ok(proto_status));
+            }
+        } else {
+            drop(any.value);
+        }
+        // the Any's value no longer refers to the data, so that the conversion back to a Vec usually doesn't copy
+        UPayload::copy_from_bytes(bytes, format)
+            .map_err(|e| RpcMapperError::InvalidPayload(e.to_string()))
+            .map(|payload| Err((any.type_url, payload)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use async_trait::async_trait;
     use bytes::{Buf, BufMut};
+    #[cfg(feature = "cloudevents")]
     use cloudevents::{Event, EventBuilder, EventBuilderV10};
 
+    #[cfg(feature = "cloudevents")]
     use crate::proto::CloudEvent as CloudEventProto;
+    use crate::transport::datamodel::PayloadLimits;
+    #[cfg(feature = "cloudevents")]
     use crate::uprotocol::UMessageType;
+    use crate::uprotocol::{UAttributes, UUri};
 
     fn build_status_response(code: UCode, msg: &str) -> RpcClientResult {
         let status = UStatus::fail_with_code(code, msg);
@@ -316,6 +421,7 @@ mod tests {
         Ok(any.try_into().unwrap())
     }
 
+    #[cfg(feature = "cloudevents")]
     fn build_cloud_event_for_test() -> Event {
         EventBuilderV10::new()
             .id("hello")
@@ -325,6 +431,7 @@ mod tests {
             .unwrap()
     }
 
+    #[cfg(feature = "cloudevents")]
     fn build_cloudevent_upayload_for_test() -> UPayload {
         let event = build_cloud_event_for_test();
         let proto_event = CloudEventProto::from(event);
@@ -372,6 +479,7 @@ mod tests {
     // fn test_compose_with_failure_transform_exception() {}
 
     #[test]
+    #[cfg(feature = "cloudevents")]
     fn test_success_invoke_method_happy_flow_using_map_response_to_rpc_response() {
         let response_payload = build_cloudevent_upayload_for_test();
 
@@ -409,6 +517,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "cloudevents")]
     fn test_success_invoke_method_happy_flow_using_map_response() {
         let response_payload = build_cloudevent_upayload_for_test();
         let e = RpcMapper::map_response::<CloudEventProto>(Ok(response_payload)).unwrap();
@@ -418,6 +527,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "cloudevents")]
     fn test_fail_invoke_method_when_invoke_method_returns_a_status_using_map_response() {
         let response = build_status_response(UCode::Aborted, "hello");
         let e = RpcMapper::map_response::<CloudEventProto>(response);
@@ -431,6 +541,7 @@ mod tests {
     // fn test_fail_invoke_method_when_invoke_method_threw_an_exception_using_map_response()
 
     #[test]
+    #[cfg(feature = "cloudevents")]
     fn test_fail_invoke_method_when_invoke_method_returns_a_bad_proto_using_map_response() {
         let response = build_number_response(42);
         let e = RpcMapper::map_response::<CloudEventProto>(response);
@@ -445,6 +556,7 @@ mod tests {
     // all these stub-using tests, what do they add?
 
     #[test]
+    #[cfg(feature = "cloudevents")]
     fn test_success_invoke_method_that_has_null_payload_map_response() {
         let response = Err(RpcMapperError::InvalidPayload(
             "not a CloudEvent".to_string(),
@@ -492,6 +604,40 @@ mod tests {
         assert_eq!(s.status.code, UCode::Ok as i32);
     }
 
+    // Accepts requests with payloads of up to 8 bytes.
+    struct LimitedRpcClient;
+
+    #[async_trait]
+    impl RpcClient for LimitedRpcClient {
+        async fn invoke_method(
+            _topic: UUri,
+            payload: UPayload,
+            _attributes: UAttributes,
+        ) -> RpcClientResult {
+            Self::check_payload(&payload)?;
+            Ok(payload)
+        }
+
+        fn payload_limits() -> PayloadLimits {
+            PayloadLimits::new(8)
+        }
+    }
+
+    #[test]
+    fn test_pack_request_checks_payload_limits() {
+        let small = UStatus::fail_with_code(UCode::Aborted, "");
+        assert!(RpcMapper::pack_request::<LimitedRpcClient, _>(&small).is_ok());
+
+        let large = UStatus::fail_with_code(UCode::Aborted, "exceeds the limits");
+        let result = RpcMapper::pack_request::<LimitedRpcClient, _>(&large);
+        assert!(matches!(result, Err(RpcMapperError::ResourceExhausted(_))));
+        let payload = RpcMapper::pack_payload(&large).unwrap();
+        assert!(matches!(
+            LimitedRpcClient::check_payload(&payload),
+            Err(RpcMapperError::ResourceExhausted(_))
+        ));
+    }
+
     #[test]
     fn test_unpack_payload_failed() {
         let payload = Any {
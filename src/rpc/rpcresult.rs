@@ -96,9 +96,7 @@ impl<T> RpcResult<T> {
     /// Returns the `Code` of the `Failure` variant if present, or `None` if the `RpcResult` is a `Success`.
     pub fn failure_value(&self) -> Option<UCode> {
         match self {
-            RpcResult::Failure(status) => {
-                Some(UCode::try_from(status.code).unwrap_or(UCode::Unknown))
-            }
+            RpcResult::Failure(status) => Some(status.get_code()),
             RpcResult::Success(_) => None,
         }
     }
@@ -0,0 +1,351 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::someip::SerializationError;
+use crate::transport::builder::UPayloadBuilder;
+use crate::uprotocol::{UPayload, UPayloadFormat};
+
+/// The maximum data ID that fits into the 12 bits of a TLV tag.
+pub const MAX_DATA_ID: u16 = 0x0FFF;
+
+/// The size of the length field used for members with wire type 4, whose length field size is not encoded in the
+/// tag but defined by the interface specification.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LengthFieldSize {
+    One,
+    Two,
+    #[default]
+    Four,
+}
+
+/// The value of a TLV encoded struct member.
+///
+/// SOME/IP TLV only encodes the size of fixed-size values, not their type, so fixed-size values are represented
+/// by unsigned integers of the respective width. All other values (strings, arrays, structs) are represented by
+/// their encoded bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TlvValue {
+    /// An 8 bit value (wire type 0).
+    Fixed8(u8),
+    /// A 16 bit value (wire type 1).
+    Fixed16(u16),
+    /// A 32 bit value (wire type 2).
+    Fixed32(u32),
+    /// A 64 bit value (wire type 3).
+    Fixed64(u64),
+    /// A value preceded by a length field (wire types 4 to 7).
+    LengthDelimited(Vec<u8>),
+}
+
+/// A member of a TLV encoded struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlvMember {
+    /// The member's data ID, at most [`MAX_DATA_ID`].
+    pub data_id: u16,
+    /// The member's value.
+    pub value: TlvValue,
+}
+
+/// Encodes and decodes structs using the tag-length-value encoding defined by the
+/// [SOME/IP Protocol Specification](https://www.autosar.org/fileadmin/standards/R22-11/FO/AUTOSAR_PRS_SOMEIPProtocol.pdf).
+///
+/// Each member is preceded by a 16 bit tag, consisting of a reserved bit, a 3 bit wire type and a 12 bit data ID.
+/// All values are encoded in network byte order. Length-delimited values are encoded with the smallest of the
+/// explicit length field sizes (wire types 5 to 7) that can hold their length.
+pub struct SomeIpTlv;
+
+impl SomeIpTlv {
+    /// Encodes struct members.
+    ///
+    /// # Arguments
+    ///
+    /// * `members` - The members to encode.
+    ///
+    /// # Returns
+    ///
+    /// The encoded members.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if a member's data ID exceeds [`MAX_DATA_ID`], or if a value is
+    /// too long to be encoded.
+    pub fn encode(members: &[TlvMember]) -> Result<Vec<u8>, SerializationError> {
+        let mut buf = Vec::new();
+        for member in members {
+            if member.data_id > MAX_DATA_ID {
+                return Err(SerializationError::new(format!(
+                    "Data ID {} exceeds maximum of {MAX_DATA_ID}",
+                    member.data_id
+                )));
+            }
+            match &member.value {
+                TlvValue::Fixed8(value) => {
+                    Self::put_tag(&mut buf, 0, member.data_id);
+                    buf.push(*value);
+                }
+                TlvValue::Fixed16(value) => {
+                    Self::put_tag(&mut buf, 1, member.data_id);
+                    buf.extend_from_slice(&value.to_be_bytes());
+                }
+                TlvValue::Fixed32(value) => {
+                    Self::put_tag(&mut buf, 2, member.data_id);
+                    buf.extend_from_slice(&value.to_be_bytes());
+                }
+                TlvValue::Fixed64(value) => {
+                    Self::put_tag(&mut buf, 3, member.data_id);
+                    buf.extend_from_slice(&value.to_be_bytes());
+                }
+                TlvValue::LengthDelimited(bytes) => {
+                    if let Ok(len) = u8::try_from(bytes.len()) {
+                        Self::put_tag(&mut buf, 5, member.data_id);
+                        buf.push(len);
+                    } else if let Ok(len) = u16::try_from(bytes.len()) {
+                        Self::put_tag(&mut buf, 6, member.data_id);
+                        buf.extend_from_slice(&len.to_be_bytes());
+                    } else if let Ok(len) = u32::try_from(bytes.len()) {
+                        Self::put_tag(&mut buf, 7, member.data_id);
+                        buf.extend_from_slice(&len.to_be_bytes());
+                    } else {
+                        return Err(SerializationError::new(format!(
+                            "Value of member {} is too long",
+                            member.data_id
+                        )));
+                    }
+                    buf.extend_from_slice(bytes);
+                }
+            }
+        }
+        Ok(buf)
+    }
+
+    /// Decodes struct members.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The encoded members.
+    /// * `length_field_size` - The size of the length field of members with wire type 4.
+    ///
+    /// # Returns
+    ///
+    /// The decoded members, in the order in which they have been encoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the bytes are not a valid TLV encoding.
+    pub fn decode(
+        bytes: &[u8],
+        length_field_size: LengthFieldSize,
+    ) -> Result<Vec<TlvMember>, SerializationError> {
+        let mut members = Vec::new();
+        let mut buf = bytes;
+        while !buf.is_empty() {
+            let tag = BigEndian::read_u16(Self::take(&mut buf, 2)?);
+            if tag & 0x8000 != 0 {
+                return Err(SerializationError::new(format!(
+                    "Reserved bit is set in tag {tag:#06x}"
+                )));
+            }
+            let data_id = tag & MAX_DATA_ID;
+            let value = match tag >> 12 {
+                0 => TlvValue::Fixed8(Self::take(&mut buf, 1)?[0]),
+                1 => TlvValue::Fixed16(BigEndian::read_u16(Self::take(&mut buf, 2)?)),
+                2 => TlvValue::Fixed32(BigEndian::read_u32(Self::take(&mut buf, 4)?)),
+                3 => TlvValue::Fixed64(BigEndian::read_u64(Self::take(&mut buf, 8)?)),
+                wire_type => {
+                    let len = match (wire_type, length_field_size) {
+                        (5, _) | (4, LengthFieldSize::One) => {
+                            usize::from(Self::take(&mut buf, 1)?[0])
+                        }
+                        (6, _) | (4, LengthFieldSize::Two) => {
+                            usize::from(BigEndian::read_u16(Self::take(&mut buf, 2)?))
+                        }
+                        _ => usize::try_from(BigEndian::read_u32(Self::take(&mut buf, 4)?))
+                            .map_err(|_e| SerializationError::new("Length field too large"))?,
+                    };
+                    TlvValue::LengthDelimited(Self::take(&mut buf, len)?.to_vec())
+                }
+            };
+            members.push(TlvMember { data_id, value });
+        }
+        Ok(members)
+    }
+
+    /// Creates a `UPayload` containing TLV encoded struct members.
+    ///
+    /// # Arguments
+    ///
+    /// * `members` - The members to encode.
+    ///
+    /// # Returns
+    ///
+    /// A payload with format `UpayloadFormatSomeipTlv`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the members cannot be encoded.
+    pub fn to_payload(members: &[TlvMember]) -> Result<UPayload, SerializationError> {
+        let payload = UPayloadBuilder::from_raw(Self::encode(members)?).build()?;
+        Ok(UPayload {
+            format: UPayloadFormat::UpayloadFormatSomeipTlv.into(),
+            ..payload
+        })
+    }
+
+    /// Decodes the struct members contained in a `UPayload`.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - A payload with format `UpayloadFormatSomeipTlv`.
+    /// * `length_field_size` - The size of the length field of members with wire type 4.
+    ///
+    /// # Returns
+    ///
+    /// The decoded members.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload has a different format, or does not contain a valid
+    /// TLV encoding.
+    pub fn from_payload(
+        payload: &UPayload,
+        length_field_size: LengthFieldSize,
+    ) -> Result<Vec<TlvMember>, SerializationError> {
+        if payload.format() != UPayloadFormat::UpayloadFormatSomeipTlv {
+            return Err(SerializationError::new("UPayload has incompatible format"));
+        }
+        Self::decode(
+            payload.resolve_data(None)?.unwrap_or_default(),
+            length_field_size,
+        )
+    }
+
+    fn put_tag(buf: &mut Vec<u8>, wire_type: u16, data_id: u16) {
+        buf.extend_from_slice(&((wire_type << 12) | data_id).to_be_bytes());
+    }
+
+    pub(super) fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8], SerializationError> {
+        if buf.len() < len {
+            return Err(SerializationError::new("Unexpected end of TLV data"));
+        }
+        let (head, tail) = buf.split_at(len);
+        *buf = tail;
+        Ok(head)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let members = vec![
+            TlvMember {
+                data_id: 1,
+                value: TlvValue::Fixed8(0x12),
+            },
+            TlvMember {
+                data_id: 2,
+                value: TlvValue::Fixed16(0x1234),
+            },
+            TlvMember {
+                data_id: 3,
+                value: TlvValue::Fixed32(0x1234_5678),
+            },
+            TlvMember {
+                data_id: MAX_DATA_ID,
+                value: TlvValue::Fixed64(0x1234_5678_9abc_def0),
+            },
+            TlvMember {
+                data_id: 5,
+                value: TlvValue::LengthDelimited(vec![0xaa; 300]),
+            },
+        ];
+
+        let bytes = SomeIpTlv::encode(&members).unwrap();
+        assert_eq!(&bytes[..3], &[0x00, 0x01, 0x12]);
+        assert_eq!(&bytes[3..7], &[0x10, 0x02, 0x12, 0x34]);
+        assert_eq!(
+            SomeIpTlv::decode(&bytes, LengthFieldSize::default()).unwrap(),
+            members
+        );
+    }
+
+    #[test]
+    fn test_encode_uses_smallest_length_field() {
+        let members = [TlvMember {
+            data_id: 0x123,
+            value: TlvValue::LengthDelimited(vec![1, 2]),
+        }];
+
+        let bytes = SomeIpTlv::encode(&members).unwrap();
+        assert_eq!(bytes, vec![0x51, 0x23, 0x02, 1, 2]);
+    }
+
+    #[test]
+    fn test_encode_fails_for_invalid_data_id() {
+        let members = [TlvMember {
+            data_id: MAX_DATA_ID + 1,
+            value: TlvValue::Fixed8(0),
+        }];
+
+        assert!(SomeIpTlv::encode(&members).is_err());
+    }
+
+    #[test_case(LengthFieldSize::One, &[0x40, 0x01, 0x02, 1, 2]; "one byte")]
+    #[test_case(LengthFieldSize::Two, &[0x40, 0x01, 0x00, 0x02, 1, 2]; "two bytes")]
+    #[test_case(LengthFieldSize::Four, &[0x40, 0x01, 0x00, 0x00, 0x00, 0x02, 1, 2]; "four bytes")]
+    fn test_decode_wire_type_4(length_field_size: LengthFieldSize, bytes: &[u8]) {
+        let members = SomeIpTlv::decode(bytes, length_field_size).unwrap();
+        assert_eq!(
+            members,
+            vec![TlvMember {
+                data_id: 1,
+                value: TlvValue::LengthDelimited(vec![1, 2]),
+            }]
+        );
+    }
+
+    #[test_case(&[0x00]; "truncated tag")]
+    #[test_case(&[0x20, 0x01, 0x00, 0x00]; "truncated value")]
+    #[test_case(&[0x50, 0x01, 0x03, 1, 2]; "truncated length-delimited value")]
+    #[test_case(&[0x80, 0x01, 0x00]; "reserved bit set")]
+    fn test_decode_fails_for_invalid_data(bytes: &[u8]) {
+        assert!(SomeIpTlv::decode(bytes, LengthFieldSize::default()).is_err());
+    }
+
+    #[test]
+    fn test_payload_round_trip() {
+        let members = vec![TlvMember {
+            data_id: 7,
+            value: TlvValue::Fixed32(42),
+        }];
+
+        let payload = SomeIpTlv::to_payload(&members).unwrap();
+        assert_eq!(payload.format(), UPayloadFormat::UpayloadFormatSomeipTlv);
+        assert_eq!(payload.length, Some(6));
+        assert_eq!(
+            SomeIpTlv::from_payload(&payload, LengthFieldSize::default()).unwrap(),
+            members
+        );
+
+        let raw = UPayloadBuilder::from_raw(vec![0x00, 0x01, 0x12])
+            .build()
+            .unwrap();
+        assert!(SomeIpTlv::from_payload(&raw, LengthFieldSize::default()).is_err());
+    }
+}
@@ -0,0 +1,332 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use byteorder::{BigEndian, ByteOrder};
+use prost_reflect::{DynamicMessage, Kind, MessageDescriptor, Value};
+
+use crate::someip::{LengthFieldSize, SerializationError, SomeIpTlv, TlvMember, TlvValue};
+
+/// Conversions between protobuf messages and TLV encoded SOME/IP structs.
+///
+/// Each set field of a message is mapped to a struct member whose data ID is the field number. Scalar fields are mapped
+/// to fixed-size members of the respective width (`bool` to 8 bits), `string` and `bytes` fields to length-delimited
+/// members containing the UTF-8 or raw bytes, and message fields to length-delimited members containing the nested,
+/// TLV encoded struct. Repeated fields are mapped to arrays: length-delimited members containing the elements without
+/// tags, where elements that are not fixed-size are prefixed with a 32 bit length field. Map fields are not supported.
+impl SomeIpTlv {
+    /// Maps a protobuf message to TLV struct members.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to map.
+    ///
+    /// # Returns
+    ///
+    /// The struct members, ordered by data ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the message contains a field number exceeding
+    /// [`MAX_DATA_ID`](crate::someip::MAX_DATA_ID), or a map field.
+    pub fn from_message(message: &DynamicMessage) -> Result<Vec<TlvMember>, SerializationError> {
+        let mut members = message
+            .fields()
+            .map(|(field, value)| {
+                let data_id = u16::try_from(field.number())
+                    .map_err(|_e| SerializationError::new("Field number exceeds data ID range"))?;
+                Ok(TlvMember {
+                    data_id,
+                    value: Self::value_to_tlv(value)?,
+                })
+            })
+            .collect::<Result<Vec<_>, SerializationError>>()?;
+        members.sort_by_key(|member| member.data_id);
+        Ok(members)
+    }
+
+    /// Maps TLV struct members to a protobuf message.
+    ///
+    /// Members whose data ID does not correspond to a field of the message type are skipped, as the TLV
+    /// encoding allows receivers to ignore members they do not know.
+    ///
+    /// # Arguments
+    ///
+    /// * `members` - The struct members.
+    /// * `descriptor` - The descriptor of the message type to map to.
+    /// * `length_field_size` - The size of the length field of nested members with wire type 4.
+    ///
+    /// # Returns
+    ///
+    /// The message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if a member's wire type does not match the type of the corresponding field, or
+    /// a member's value is invalid for the field's type.
+    pub fn to_message(
+        members: &[TlvMember],
+        descriptor: &MessageDescriptor,
+        length_field_size: LengthFieldSize,
+    ) -> Result<DynamicMessage, SerializationError> {
+        let mut message = DynamicMessage::new(descriptor.clone());
+        for member in members {
+            let Some(field) = descriptor.get_field(u32::from(member.data_id)) else {
+                continue;
+            };
+            let value = if field.is_map() {
+                return Err(SerializationError::new(format!(
+                    "Map field {} is not supported",
+                    field.full_name()
+                )));
+            } else if field.is_list() {
+                let TlvValue::LengthDelimited(bytes) = &member.value else {
+                    return Err(Self::mismatch(&field.kind()));
+                };
+                Value::List(Self::decode_array(bytes, &field.kind(), length_field_size)?)
+            } else {
+                Self::tlv_to_value(member.value.clone(), &field.kind(), length_field_size)?
+            };
+            message.set_field(&field, value);
+        }
+        Ok(message)
+    }
+
+    fn value_to_tlv(value: &Value) -> Result<TlvValue, SerializationError> {
+        Ok(match value {
+            Value::Bool(b) => TlvValue::Fixed8(u8::from(*b)),
+            Value::I32(i) | Value::EnumNumber(i) => TlvValue::Fixed32(*i as u32),
+            Value::U32(u) => TlvValue::Fixed32(*u),
+            Value::F32(f) => TlvValue::Fixed32(f.to_bits()),
+            Value::I64(i) => TlvValue::Fixed64(*i as u64),
+            Value::U64(u) => TlvValue::Fixed64(*u),
+            Value::F64(f) => TlvValue::Fixed64(f.to_bits()),
+            Value::String(s) => TlvValue::LengthDelimited(s.as_bytes().to_vec()),
+            Value::Bytes(b) => TlvValue::LengthDelimited(b.to_vec()),
+            Value::Message(m) => TlvValue::LengthDelimited(Self::encode(&Self::from_message(m)?)?),
+            Value::List(values) => TlvValue::LengthDelimited(Self::encode_array(values)?),
+            Value::Map(_) => return Err(SerializationError::new("Map fields are not supported")),
+        })
+    }
+
+    fn tlv_to_value(
+        value: TlvValue,
+        kind: &Kind,
+        length_field_size: LengthFieldSize,
+    ) -> Result<Value, SerializationError> {
+        Ok(match (value, kind) {
+            (TlvValue::Fixed8(b), Kind::Bool) => Value::Bool(b != 0),
+            (TlvValue::Fixed32(u), Kind::Int32 | Kind::Sint32 | Kind::Sfixed32) => {
+                Value::I32(u as i32)
+            }
+            (TlvValue::Fixed32(u), Kind::Uint32 | Kind::Fixed32) => Value::U32(u),
+            (TlvValue::Fixed32(u), Kind::Float) => Value::F32(f32::from_bits(u)),
+            (TlvValue::Fixed32(u), Kind::Enum(_)) => Value::EnumNumber(u as i32),
+            (TlvValue::Fixed64(u), Kind::Int64 | Kind::Sint64 | Kind::Sfixed64) => {
+                Value::I64(u as i64)
+            }
+            (TlvValue::Fixed64(u), Kind::Uint64 | Kind::Fixed64) => Value::U64(u),
+            (TlvValue::Fixed64(u), Kind::Double) => Value::F64(f64::from_bits(u)),
+            (TlvValue::LengthDelimited(bytes), Kind::String) => {
+                Value::String(String::from_utf8(bytes).map_err(|e| {
+                    SerializationError::utf8("Invalid string value", e.utf8_error())
+                })?)
+            }
+            (TlvValue::LengthDelimited(bytes), Kind::Bytes) => Value::Bytes(bytes.into()),
+            (TlvValue::LengthDelimited(bytes), Kind::Message(descriptor)) => {
+                let members = Self::decode(&bytes, length_field_size)?;
+                Value::Message(Self::to_message(&members, descriptor, length_field_size)?)
+            }
+            (_, kind) => return Err(Self::mismatch(kind)),
+        })
+    }
+
+    fn encode_array(values: &[Value]) -> Result<Vec<u8>, SerializationError> {
+        let mut buf = Vec::new();
+        for value in values {
+            match Self::value_to_tlv(value)? {
+                TlvValue::Fixed8(b) => buf.push(b),
+                TlvValue::Fixed16(u) => buf.extend_from_slice(&u.to_be_bytes()),
+                TlvValue::Fixed32(u) => buf.extend_from_slice(&u.to_be_bytes()),
+                TlvValue::Fixed64(u) => buf.extend_from_slice(&u.to_be_bytes()),
+                TlvValue::LengthDelimited(bytes) => {
+                    let len = u32::try_from(bytes.len())
+                        .map_err(|_e| SerializationError::new("Array element is too long"))?;
+                    buf.extend_from_slice(&len.to_be_bytes());
+                    buf.extend_from_slice(&bytes);
+                }
+            }
+        }
+        Ok(buf)
+    }
+
+    fn decode_array(
+        bytes: &[u8],
+        kind: &Kind,
+        length_field_size: LengthFieldSize,
+    ) -> Result<Vec<Value>, SerializationError> {
+        let mut values = Vec::new();
+        let mut buf = bytes;
+        while !buf.is_empty() {
+            let element = match kind {
+                Kind::Bool => TlvValue::Fixed8(Self::take(&mut buf, 1)?[0]),
+                Kind::Int32
+                | Kind::Sint32
+                | Kind::Sfixed32
+                | Kind::Uint32
+                | Kind::Fixed32
+                | Kind::Float
+                | Kind::Enum(_) => TlvValue::Fixed32(BigEndian::read_u32(Self::take(&mut buf, 4)?)),
+                Kind::Int64
+                | Kind::Sint64
+                | Kind::Sfixed64
+                | Kind::Uint64
+                | Kind::Fixed64
+                | Kind::Double => TlvValue::Fixed64(BigEndian::read_u64(Self::take(&mut buf, 8)?)),
+                Kind::String | Kind::Bytes | Kind::Message(_) => {
+                    let len = usize::try_from(BigEndian::read_u32(Self::take(&mut buf, 4)?))
+                        .map_err(|_e| SerializationError::new("Length field too large"))?;
+                    TlvValue::LengthDelimited(Self::take(&mut buf, len)?.to_vec())
+                }
+            };
+            values.push(Self::tlv_to_value(element, kind, length_field_size)?);
+        }
+        Ok(values)
+    }
+
+    fn mismatch(kind: &Kind) -> SerializationError {
+        SerializationError::new(format!("Wire type does not match field type {kind:?}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prost::Message;
+    use prost_reflect::DescriptorPool;
+    use prost_types::{field, Field, Option as ProtoOption, SourceContext, Syntax, Type};
+
+    fn descriptor(name: &str) -> MessageDescriptor {
+        DescriptorPool::global().get_message_by_name(name).unwrap()
+    }
+
+    fn to_dynamic<T: Message>(message: &T, name: &str) -> DynamicMessage {
+        DynamicMessage::decode(descriptor(name), message.encode_to_vec().as_slice()).unwrap()
+    }
+
+    #[test]
+    fn test_message_round_trip() {
+        let message = Type {
+            name: "Door".to_string(),
+            fields: vec![Field {
+                kind: field::Kind::TypeBool.into(),
+                number: 1,
+                name: "open".to_string(),
+                packed: true,
+                ..Default::default()
+            }],
+            oneofs: vec!["state".to_string(), "position".to_string()],
+            options: vec![ProtoOption::default()],
+            source_context: Some(SourceContext {
+                file_name: "door.proto".to_string(),
+            }),
+            syntax: Syntax::Proto3.into(),
+        };
+        let dynamic = to_dynamic(&message, "google.protobuf.Type");
+
+        let members = SomeIpTlv::from_message(&dynamic).unwrap();
+        assert_eq!(
+            members[0],
+            TlvMember {
+                data_id: 1,
+                value: TlvValue::LengthDelimited(b"Door".to_vec()),
+            }
+        );
+        let bytes = SomeIpTlv::encode(&members).unwrap();
+
+        let decoded = SomeIpTlv::to_message(
+            &SomeIpTlv::decode(&bytes, LengthFieldSize::default()).unwrap(),
+            &descriptor("google.protobuf.Type"),
+            LengthFieldSize::default(),
+        )
+        .unwrap();
+        assert_eq!(decoded.transcode_to::<Type>().unwrap(), message);
+    }
+
+    #[test]
+    fn test_scalar_fields_use_fixed_size_members() {
+        let message = prost_types::Duration {
+            seconds: -1,
+            nanos: 7,
+        };
+        let dynamic = to_dynamic(&message, "google.protobuf.Duration");
+
+        let members = SomeIpTlv::from_message(&dynamic).unwrap();
+        assert_eq!(
+            members,
+            vec![
+                TlvMember {
+                    data_id: 1,
+                    value: TlvValue::Fixed64(u64::MAX),
+                },
+                TlvMember {
+                    data_id: 2,
+                    value: TlvValue::Fixed32(7),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_message_skips_unknown_members() {
+        let members = [
+            TlvMember {
+                data_id: 2,
+                value: TlvValue::Fixed32(7),
+            },
+            TlvMember {
+                data_id: 100,
+                value: TlvValue::Fixed16(1),
+            },
+        ];
+
+        let message = SomeIpTlv::to_message(
+            &members,
+            &descriptor("google.protobuf.Duration"),
+            LengthFieldSize::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            message.transcode_to::<prost_types::Duration>().unwrap(),
+            prost_types::Duration {
+                seconds: 0,
+                nanos: 7
+            }
+        );
+    }
+
+    #[test]
+    fn test_to_message_fails_for_mismatched_wire_type() {
+        let members = [TlvMember {
+            data_id: 1,
+            value: TlvValue::Fixed32(7),
+        }];
+
+        let result = SomeIpTlv::to_message(
+            &members,
+            &descriptor("google.protobuf.Duration"),
+            LengthFieldSize::default(),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,273 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::statestore::{scan_entries, Entry};
+use crate::state::StateStore;
+use crate::uprotocol::{UCode, UStatus};
+use crate::uuid::builder::{SystemClock, UClock};
+
+const MAGIC: &[u8; 4] = b"USS1";
+
+/// `FileStateStore` keeps its entries in a file, so that they survive restarts of the process.
+///
+/// The entries are held in memory as well, and the file is rewritten on each change. The new content is written to a
+/// temporary file next to it first, which then replaces the file, so that a crash while writing leaves the previous
+/// content in place. This suits the small amounts of state that components keep, like subscriptions, rather than
+/// large or frequently changing data. Expiry times are stored as wall-clock times, so that entries also expire while
+/// the process is not running; expired entries are dropped when the file is opened and whenever it is rewritten.
+pub struct FileStateStore {
+    path: PathBuf,
+    entries: Mutex<BTreeMap<Vec<u8>, Entry>>,
+    clock: Box<dyn UClock>,
+}
+
+impl FileStateStore {
+    /// Opens a store that takes the current time from the platform.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, which is created with the first change if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the file cannot be read, or with code [`UCode::DataLoss`] if its content is corrupt.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, UStatus> {
+        Self::with_clock(path, SystemClock)
+    }
+
+    /// Opens a store that takes the current time from the given clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, which is created with the first change if it does not exist.
+    /// * `clock` - The clock that the expiry of entries is measured with.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the file cannot be read, or with code [`UCode::DataLoss`] if its content is corrupt.
+    pub fn with_clock<P, C>(path: P, clock: C) -> Result<Self, UStatus>
+    where
+        P: AsRef<Path>,
+        C: UClock + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        let now = clock.now().unwrap_or(0);
+        let mut entries = match fs::read(&path) {
+            Ok(content) => decode(&content)?,
+            Err(error) if error.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(error) => return Err(error.into()),
+        };
+        entries.retain(|_, entry| entry.is_live(now));
+        Ok(FileStateStore {
+            path,
+            entries: Mutex::new(entries),
+            clock: Box::new(clock),
+        })
+    }
+
+    /// Gets the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn now(&self) -> u64 {
+        self.clock.now().unwrap_or(0)
+    }
+
+    // Replaces the file with the entries that have not expired, keeping the entries unchanged if that fails.
+    fn write(
+        &self,
+        entries: &mut BTreeMap<Vec<u8>, Entry>,
+        change: impl FnOnce(&mut BTreeMap<Vec<u8>, Entry>),
+    ) -> Result<(), UStatus> {
+        let now = self.now();
+        let mut changed = entries.clone();
+        change(&mut changed);
+        changed.retain(|_, entry| entry.is_live(now));
+
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        let temporary = PathBuf::from(temporary);
+        let mut file = fs::File::create(&temporary)?;
+        file.write_all(&encode(&changed))?;
+        file.sync_all()?;
+        fs::rename(&temporary, &self.path)?;
+
+        *entries = changed;
+        Ok(())
+    }
+}
+
+impl StateStore for FileStateStore {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, UStatus> {
+        let now = self.now();
+        Ok(self
+            .entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .filter(|entry| entry.is_live(now))
+            .map(|entry| entry.value.clone()))
+    }
+
+    fn put(&self, key: &[u8], value: &[u8], ttl: Option<Duration>) -> Result<(), UStatus> {
+        let entry = Entry::new(value, ttl, self.now());
+        let mut entries = self.entries.lock().unwrap();
+        self.write(&mut entries, |entries| {
+            entries.insert(key.to_vec(), entry);
+        })
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<(), UStatus> {
+        let mut entries = self.entries.lock().unwrap();
+        if !entries.contains_key(key) {
+            return Ok(());
+        }
+        self.write(&mut entries, |entries| {
+            entries.remove(key);
+        })
+    }
+
+    fn scan(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>, UStatus> {
+        let now = self.now();
+        Ok(scan_entries(&self.entries.lock().unwrap(), prefix, now))
+    }
+}
+
+// The file starts with the magic bytes, followed by the entries, each consisting of the expiry time in milliseconds
+// since UNIX Epoch, 0 for entries that do not expire, and of the length-prefixed key and value.
+fn encode(entries: &BTreeMap<Vec<u8>, Entry>) -> Vec<u8> {
+    let mut content = MAGIC.to_vec();
+    for (key, entry) in entries {
+        content.extend_from_slice(&entry.expires.unwrap_or(0).to_be_bytes());
+        for bytes in [key, &entry.value] {
+            // keys and values are held in memory, so their lengths fit into the 64 bits of the length prefix
+            content.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+            content.extend_from_slice(bytes);
+        }
+    }
+    content
+}
+
+fn decode(content: &[u8]) -> Result<BTreeMap<Vec<u8>, Entry>, UStatus> {
+    let corrupt = || UStatus::fail_with_code(UCode::DataLoss, "State file is corrupt");
+    let mut rest = content.strip_prefix(MAGIC).ok_or_else(corrupt)?;
+    let mut entries = BTreeMap::new();
+    while !rest.is_empty() {
+        let expires = read_u64(&mut rest).ok_or_else(corrupt)?;
+        let key = read_bytes(&mut rest).ok_or_else(corrupt)?;
+        let value = read_bytes(&mut rest).ok_or_else(corrupt)?;
+        entries.insert(
+            key,
+            Entry {
+                value,
+                expires: (expires != 0).then_some(expires),
+            },
+        );
+    }
+    Ok(entries)
+}
+
+fn read_u64(rest: &mut &[u8]) -> Option<u64> {
+    let bytes = rest.get(..8)?.try_into().ok()?;
+    *rest = &rest[8..];
+    Some(u64::from_be_bytes(bytes))
+}
+
+fn read_bytes(rest: &mut &[u8]) -> Option<Vec<u8>> {
+    let length = usize::try_from(read_u64(rest)?).ok()?;
+    if rest.len() < length {
+        return None;
+    }
+    let (bytes, remainder) = rest.split_at(length);
+    *rest = remainder;
+    Some(bytes.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    struct TestClock(AtomicU64);
+    impl UClock for TestClock {
+        fn now(&self) -> Option<u64> {
+            Some(self.0.load(Ordering::Relaxed))
+        }
+    }
+
+    fn path_for_test(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("uprotocol-{}-{name}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_entries_survive_reopening() {
+        let path = path_for_test("reopen");
+        let store = FileStateStore::open(&path).unwrap();
+        store.put(b"sub/a", b"1", None).unwrap();
+        store.put(b"sub/b", b"2", None).unwrap();
+        store.put(b"sub/c", b"3", None).unwrap();
+        store.delete(b"sub/b").unwrap();
+        drop(store);
+
+        let store = FileStateStore::open(&path).unwrap();
+        assert_eq!(
+            store.scan(b"sub/").unwrap(),
+            vec![
+                (b"sub/a".to_vec(), b"1".to_vec()),
+                (b"sub/c".to_vec(), b"3".to_vec())
+            ]
+        );
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_entries_expire_while_closed() {
+        let path = path_for_test("expire");
+        let clock = Arc::new(TestClock(AtomicU64::new(1000)));
+        let store = FileStateStore::with_clock(&path, Arc::clone(&clock)).unwrap();
+        store
+            .put(b"lvc/a", b"1", Some(Duration::from_millis(100)))
+            .unwrap();
+        store.put(b"lvc/b", b"2", None).unwrap();
+        drop(store);
+
+        clock.0.store(1100, Ordering::Relaxed);
+        let store = FileStateStore::with_clock(&path, Arc::clone(&clock)).unwrap();
+        assert_eq!(store.get(b"lvc/a").unwrap(), None);
+        assert_eq!(store.get(b"lvc/b").unwrap(), Some(b"2".to_vec()));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_open_fails_for_corrupt_file() {
+        let path = path_for_test("corrupt");
+        fs::write(&path, b"USS1\x00\x00").unwrap();
+        assert_eq!(
+            FileStateStore::open(&path).err().unwrap().get_code(),
+            UCode::DataLoss
+        );
+        fs::remove_file(&path).unwrap();
+    }
+}
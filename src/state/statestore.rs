@@ -0,0 +1,277 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::uprotocol::UStatus;
+use crate::uuid::builder::{SystemClock, UClock};
+
+/// `StateStore` is a key-value store for the state of stateful components, like subscription stores, deduplicators
+/// or last-value caches, so that the state can be kept in memory or survive restarts of the process, depending on the
+/// implementation the component is given.
+///
+/// Keys and values are bytes, typically the protobuf encoding of a `UUri` and of a message. Entries can be given a
+/// time to live, after which they are no longer returned. Implementations are shared between the tasks of a
+/// component, so they synchronize access themselves.
+pub trait StateStore: Send + Sync {
+    /// Gets the value of a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key.
+    ///
+    /// # Returns
+    ///
+    /// The value, or `None` if the key is not stored or its entry has expired.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the store cannot be read.
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, UStatus>;
+
+    /// Stores the value of a key, replacing the value stored before.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key.
+    /// * `value` - The value.
+    /// * `ttl` - The time after which the entry expires, or `None` if it does not expire.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the store cannot be written.
+    fn put(&self, key: &[u8], value: &[u8], ttl: Option<Duration>) -> Result<(), UStatus>;
+
+    /// Removes a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key, which need not be stored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the store cannot be written.
+    fn delete(&self, key: &[u8]) -> Result<(), UStatus>;
+
+    /// Gets the entries whose keys start with a prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix, which is empty for getting all entries.
+    ///
+    /// # Returns
+    ///
+    /// The keys and values of the entries that have not expired, ordered by key.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the store cannot be read.
+    fn scan(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>, UStatus>;
+}
+
+// A value, and the time in milliseconds since UNIX Epoch at which it expires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Entry {
+    pub(super) value: Vec<u8>,
+    pub(super) expires: Option<u64>,
+}
+
+impl Entry {
+    pub(super) fn new(value: &[u8], ttl: Option<Duration>, now: u64) -> Entry {
+        Entry {
+            value: value.to_vec(),
+            expires: ttl
+                .map(|ttl| now.saturating_add(u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX))),
+        }
+    }
+
+    pub(super) fn is_live(&self, now: u64) -> bool {
+        self.expires.map_or(true, |expires| expires > now)
+    }
+}
+
+pub(super) fn scan_entries(
+    entries: &BTreeMap<Vec<u8>, Entry>,
+    prefix: &[u8],
+    now: u64,
+) -> Vec<(Vec<u8>, Vec<u8>)> {
+    entries
+        .range(prefix.to_vec()..)
+        .take_while(|(key, _)| key.starts_with(prefix))
+        .filter(|(_, entry)| entry.is_live(now))
+        .map(|(key, entry)| (key.clone(), entry.value.clone()))
+        .collect()
+}
+
+/// `InMemoryStateStore` keeps its entries in memory, so that they are lost when the process ends.
+///
+/// Expired entries are not returned anymore, and are dropped by [`InMemoryStateStore::purge_expired`].
+pub struct InMemoryStateStore {
+    entries: Mutex<BTreeMap<Vec<u8>, Entry>>,
+    clock: Box<dyn UClock>,
+}
+
+impl Default for InMemoryStateStore {
+    fn default() -> Self {
+        InMemoryStateStore::new()
+    }
+}
+
+impl InMemoryStateStore {
+    /// Creates an empty store that takes the current time from the platform.
+    pub fn new() -> Self {
+        Self::with_clock(SystemClock)
+    }
+
+    /// Creates an empty store that takes the current time from the given clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - The clock that the expiry of entries is measured with.
+    pub fn with_clock<C>(clock: C) -> Self
+    where
+        C: UClock + 'static,
+    {
+        InMemoryStateStore {
+            entries: Mutex::new(BTreeMap::new()),
+            clock: Box::new(clock),
+        }
+    }
+
+    /// Drops the entries that have expired.
+    ///
+    /// # Returns
+    ///
+    /// The number of dropped entries.
+    pub fn purge_expired(&self) -> usize {
+        let now = self.now();
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|_, entry| entry.is_live(now));
+        before - entries.len()
+    }
+
+    /// Gets the number of entries, including the expired ones that have not been purged yet.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Checks if the store has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn now(&self) -> u64 {
+        self.clock.now().unwrap_or(0)
+    }
+}
+
+impl StateStore for InMemoryStateStore {
+    fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, UStatus> {
+        let now = self.now();
+        Ok(self
+            .entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .filter(|entry| entry.is_live(now))
+            .map(|entry| entry.value.clone()))
+    }
+
+    fn put(&self, key: &[u8], value: &[u8], ttl: Option<Duration>) -> Result<(), UStatus> {
+        let entry = Entry::new(value, ttl, self.now());
+        self.entries.lock().unwrap().insert(key.to_vec(), entry);
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<(), UStatus> {
+        self.entries.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn scan(&self, prefix: &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>, UStatus> {
+        let now = self.now();
+        Ok(scan_entries(&self.entries.lock().unwrap(), prefix, now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    struct TestClock(AtomicU64);
+    impl UClock for TestClock {
+        fn now(&self) -> Option<u64> {
+            Some(self.0.load(Ordering::Relaxed))
+        }
+    }
+
+    #[test]
+    fn test_put_get_delete() {
+        let store = InMemoryStateStore::new();
+        assert_eq!(store.get(b"topic").unwrap(), None);
+
+        store.put(b"topic", b"first", None).unwrap();
+        store.put(b"topic", b"second", None).unwrap();
+        assert_eq!(store.get(b"topic").unwrap(), Some(b"second".to_vec()));
+
+        store.delete(b"topic").unwrap();
+        store.delete(b"unknown").unwrap();
+        assert_eq!(store.get(b"topic").unwrap(), None);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_scan_returns_entries_with_prefix_in_order() {
+        let store = InMemoryStateStore::new();
+        store.put(b"sub/b", b"2", None).unwrap();
+        store.put(b"sub/a", b"1", None).unwrap();
+        store.put(b"lvc/a", b"3", None).unwrap();
+        store.put(b"suc", b"4", None).unwrap();
+
+        assert_eq!(
+            store.scan(b"sub/").unwrap(),
+            vec![
+                (b"sub/a".to_vec(), b"1".to_vec()),
+                (b"sub/b".to_vec(), b"2".to_vec())
+            ]
+        );
+        assert_eq!(store.scan(b"").unwrap().len(), 4);
+        assert!(store.scan(b"none").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let clock = Arc::new(TestClock(AtomicU64::new(1000)));
+        let store = InMemoryStateStore::with_clock(Arc::clone(&clock));
+        store
+            .put(b"dedup/1", b"", Some(Duration::from_millis(100)))
+            .unwrap();
+        store.put(b"dedup/2", b"", None).unwrap();
+
+        clock.0.store(1099, Ordering::Relaxed);
+        assert!(store.get(b"dedup/1").unwrap().is_some());
+        clock.0.store(1100, Ordering::Relaxed);
+        assert!(store.get(b"dedup/1").unwrap().is_none());
+        assert_eq!(store.scan(b"dedup/").unwrap().len(), 1);
+
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.purge_expired(), 1);
+        assert_eq!(store.len(), 1);
+    }
+}
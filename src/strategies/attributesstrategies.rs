@@ -0,0 +1,250 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use proptest::option;
+use proptest::prelude::*;
+use proptest::sample::select;
+
+use crate::strategies::{rpc_method_uri, rpc_response_uri};
+use crate::uprotocol::{UAttributes, UMessageType, UPriority, Uuid};
+
+// The highest timestamp that fits into the 48 bits of a uProtocol UUID
+const MAX_UUID_TIME: u64 = 0xffff_ffff_ffff;
+const UUID_VERSION_8: u64 = 0x8000;
+const UUID_VERSION_4: u64 = 0x4000;
+const UUID_VERSION_MASK: u64 = 0xf000;
+const UUID_VARIANT_RFC4122: u64 = 0x8000_0000_0000_0000;
+const UUID_RANDOM_MASK: u64 = 0x3fff_ffff_ffff_ffff;
+// The values of UCode, which are the only valid communication status codes
+const MAX_UCODE: i32 = 16;
+
+/// Gets a strategy for uProtocol UUIDs, with timestamps anywhere between UNIX epoch and the latest time that fits
+/// into a UUID.
+pub fn uprotocol_uuid() -> impl Strategy<Value = Uuid> {
+    (0..=MAX_UUID_TIME, 0..=0xfff_u64, any::<u64>()).prop_map(|(time, counter, random)| Uuid {
+        msb: (time << 16) | UUID_VERSION_8 | counter,
+        lsb: UUID_VARIANT_RFC4122 | (random & UUID_RANDOM_MASK),
+    })
+}
+
+/// Gets a strategy for UUIDs that are not uProtocol UUIDs, but version 4 UUIDs.
+pub fn invalid_uuid() -> impl Strategy<Value = Uuid> {
+    uprotocol_uuid().prop_map(|uuid| Uuid {
+        msb: (uuid.msb & !UUID_VERSION_MASK) | UUID_VERSION_4,
+        lsb: uuid.lsb,
+    })
+}
+
+/// Gets a strategy for the priorities of messages.
+pub fn priority() -> impl Strategy<Value = UPriority> {
+    select(vec![
+        UPriority::UpriorityUnspecified,
+        UPriority::UpriorityCs0,
+        UPriority::UpriorityCs1,
+        UPriority::UpriorityCs2,
+        UPriority::UpriorityCs3,
+        UPriority::UpriorityCs4,
+        UPriority::UpriorityCs5,
+        UPriority::UpriorityCs6,
+    ])
+}
+
+/// Gets a strategy for the priorities of RPC messages, i.e. `UPRIORITY_CS4` or higher.
+pub fn rpc_priority() -> impl Strategy<Value = UPriority> {
+    select(vec![
+        UPriority::UpriorityCs4,
+        UPriority::UpriorityCs5,
+        UPriority::UpriorityCs6,
+    ])
+}
+
+fn token() -> impl Strategy<Value = Option<String>> {
+    option::of("[A-Za-z0-9_-]{1,32}")
+}
+
+/// Gets a strategy for the attributes of publish messages that pass validation.
+pub fn publish_attributes() -> impl Strategy<Value = UAttributes> {
+    (
+        uprotocol_uuid(),
+        priority(),
+        option::of(1..=i32::MAX),
+        token(),
+        option::of(1..=i32::MAX),
+    )
+        .prop_map(|(id, priority, ttl, token, permission_level)| UAttributes {
+            id: Some(id),
+            r#type: UMessageType::UmessageTypePublish.into(),
+            priority: priority.into(),
+            ttl,
+            token,
+            permission_level,
+            ..Default::default()
+        })
+}
+
+/// Gets a strategy for the attributes of requests that pass validation, without warnings.
+pub fn request_attributes() -> impl Strategy<Value = UAttributes> {
+    (
+        uprotocol_uuid(),
+        rpc_priority(),
+        rpc_method_uri(),
+        1..=i32::MAX,
+        token(),
+        option::of(1..=i32::MAX),
+    )
+        .prop_map(
+            |(id, priority, sink, ttl, token, permission_level)| UAttributes {
+                id: Some(id),
+                r#type: UMessageType::UmessageTypeRequest.into(),
+                priority: priority.into(),
+                sink: Some(sink),
+                ttl: Some(ttl),
+                token,
+                permission_level,
+                ..Default::default()
+            },
+        )
+}
+
+/// Gets a strategy for the attributes of responses that pass validation, without warnings.
+pub fn response_attributes() -> impl Strategy<Value = UAttributes> {
+    (
+        uprotocol_uuid(),
+        rpc_priority(),
+        rpc_response_uri(),
+        uprotocol_uuid(),
+        option::of(0..=MAX_UCODE),
+        option::of(1..=i32::MAX),
+    )
+        .prop_map(|(id, priority, sink, reqid, commstatus, ttl)| UAttributes {
+            id: Some(id),
+            r#type: UMessageType::UmessageTypeResponse.into(),
+            priority: priority.into(),
+            sink: Some(sink),
+            reqid: Some(reqid),
+            commstatus,
+            ttl,
+            ..Default::default()
+        })
+}
+
+/// Gets a strategy for `UAttributes` that fail validation with a single error, along with the path of the field
+/// that the error refers to.
+///
+/// Each of the attributes is valid but for one violation, e.g. a request without time-to-live or a response whose
+/// request ID is not a uProtocol UUID.
+pub fn invalid_attributes() -> impl Strategy<Value = (UAttributes, &'static str)> {
+    prop_oneof![
+        (publish_attributes(), i32::MIN..=0).prop_map(|(attributes, ttl)| (
+            UAttributes {
+                ttl: Some(ttl),
+                ..attributes
+            },
+            "ttl"
+        )),
+        (publish_attributes(), i32::MIN..=0).prop_map(|(attributes, permission_level)| (
+            UAttributes {
+                permission_level: Some(permission_level),
+                ..attributes
+            },
+            "permission_level"
+        )),
+        (
+            response_attributes(),
+            prop_oneof![i32::MIN..0, MAX_UCODE + 1..=i32::MAX]
+        )
+            .prop_map(|(attributes, commstatus)| (
+                UAttributes {
+                    commstatus: Some(commstatus),
+                    ..attributes
+                },
+                "commstatus"
+            )),
+        request_attributes().prop_map(|attributes| (
+            UAttributes {
+                ttl: None,
+                ..attributes
+            },
+            "ttl"
+        )),
+        request_attributes().prop_map(|attributes| (
+            UAttributes {
+                sink: None,
+                ..attributes
+            },
+            "sink"
+        )),
+        (response_attributes(), invalid_uuid()).prop_map(|(attributes, reqid)| (
+            UAttributes {
+                reqid: Some(reqid),
+                ..attributes
+            },
+            "reqid"
+        )),
+    ]
+}
+
+impl Arbitrary for Uuid {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Uuid>;
+
+    /// Generates uProtocol UUIDs.
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        uprotocol_uuid().boxed()
+    }
+}
+
+impl Arbitrary for UAttributes {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<UAttributes>;
+
+    /// Generates the attributes of publish messages, requests and responses that pass validation.
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            publish_attributes(),
+            request_attributes(),
+            response_attributes()
+        ]
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::validator::{UAttributesValidator, Validators};
+
+    proptest! {
+        #[test]
+        fn test_uuids(uuid in any::<Uuid>(), invalid in invalid_uuid()) {
+            prop_assert!(uuid.is_uprotocol_uuid());
+            prop_assert!(uuid.get_time().unwrap() <= MAX_UUID_TIME);
+            prop_assert!(!invalid.is_uprotocol_uuid());
+        }
+
+        #[test]
+        fn test_attributes_are_valid(attributes in any::<UAttributes>()) {
+            let report = Validators::get_validator(&attributes).report(&attributes);
+            prop_assert!(report.violations().is_empty(), "{:?}", report);
+        }
+
+        #[test]
+        fn test_invalid_attributes_fail((attributes, field) in invalid_attributes()) {
+            let error = Validators::get_validator(&attributes)
+                .validate(&attributes)
+                .unwrap_err();
+            prop_assert_eq!(error.field(), Some(field));
+        }
+    }
+}
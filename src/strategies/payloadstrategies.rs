@@ -0,0 +1,104 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use proptest::collection::{vec, SizeRange};
+use proptest::prelude::*;
+
+use crate::uprotocol::{Data, UPayload, UPayloadFormat};
+
+/// The maximum size of the data of the payloads that are generated by means of [`Arbitrary`].
+pub const MAX_ARBITRARY_PAYLOAD_SIZE: usize = 1024;
+
+fn payload_of(data: Vec<u8>, format: UPayloadFormat) -> UPayload {
+    UPayload {
+        length: i32::try_from(data.len()).ok(),
+        format: format.into(),
+        data: Some(Data::Value(data)),
+    }
+}
+
+/// Gets a strategy for raw payloads, i.e. with arbitrary bytes.
+///
+/// # Arguments
+///
+/// * `size` - The sizes of the payload data in bytes, e.g. `0..=1024`.
+pub fn raw_payload(size: impl Into<SizeRange>) -> impl Strategy<Value = UPayload> {
+    vec(any::<u8>(), size).prop_map(|data| payload_of(data, UPayloadFormat::UpayloadFormatRaw))
+}
+
+/// Gets a strategy for text payloads, i.e. with UTF-8 data that [`UPayload::as_str`] returns.
+///
+/// # Arguments
+///
+/// * `chars` - The numbers of characters of the text.
+pub fn text_payload(chars: impl Into<SizeRange>) -> impl Strategy<Value = UPayload> {
+    vec(any::<char>(), chars).prop_map(|chars| {
+        payload_of(
+            chars.into_iter().collect::<String>().into_bytes(),
+            UPayloadFormat::UpayloadFormatText,
+        )
+    })
+}
+
+/// Gets a strategy for payloads whose data exceeds a maximum size, e.g. to check that a transport rejects them
+/// according to its [`PayloadLimits`](crate::transport::datamodel::PayloadLimits).
+///
+/// # Arguments
+///
+/// * `max_size` - The maximum size of payload data in bytes, which the generated payloads exceed by up to 1024 bytes.
+pub fn oversized_payload(max_size: usize) -> impl Strategy<Value = UPayload> {
+    raw_payload(max_size + 1..=max_size + MAX_ARBITRARY_PAYLOAD_SIZE)
+}
+
+impl Arbitrary for UPayload {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<UPayload>;
+
+    /// Generates raw and text payloads with up to [`MAX_ARBITRARY_PAYLOAD_SIZE`] bytes of data.
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            raw_payload(0..=MAX_ARBITRARY_PAYLOAD_SIZE),
+            // characters take up to 4 bytes in UTF-8
+            text_payload(0..=MAX_ARBITRARY_PAYLOAD_SIZE / 4),
+        ]
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::datamodel::PayloadLimits;
+
+    proptest! {
+        #[test]
+        fn test_payloads_fit_limit(payload in any::<UPayload>()) {
+            prop_assert!(PayloadLimits::new(MAX_ARBITRARY_PAYLOAD_SIZE).check(&payload).is_ok());
+            let Some(Data::Value(data)) = &payload.data else {
+                panic!("payload has no data");
+            };
+            prop_assert_eq!(payload.length, i32::try_from(data.len()).ok());
+        }
+
+        #[test]
+        fn test_text_payloads_are_text(payload in text_payload(0..64)) {
+            prop_assert!(payload.as_str().is_ok());
+        }
+
+        #[test]
+        fn test_oversized_payloads_exceed_limit(payload in oversized_payload(16)) {
+            prop_assert!(PayloadLimits::new(16).check(&payload).is_err());
+        }
+    }
+}
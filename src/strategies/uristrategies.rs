@@ -0,0 +1,216 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+
+use crate::uprotocol::uresource::UResourceId;
+use crate::uprotocol::{Remote, UAuthority, UEntity, UResource, UUri};
+use crate::uri::builder::resourcebuilder::UResourceBuilder;
+
+// Names that survive the long form, i.e. without the separators `/`, `.` and `#`
+const NAME_PATTERN: &str = "[a-z][a-z0-9_]{0,15}";
+// The names of authorities may consist of several labels, like `vcu.my_car_vin`
+const AUTHORITY_NAME_PATTERN: &str = "[a-z][a-z0-9_]{0,15}(\\.[a-z][a-z0-9_]{0,15}){0,2}";
+
+fn name() -> impl Strategy<Value = String> {
+    NAME_PATTERN.prop_filter("resource name rpc is reserved for RPC methods", |name| {
+        name != "rpc"
+    })
+}
+
+fn entity_name() -> impl Strategy<Value = String> {
+    (name(), option::of(name())).prop_map(|(name, suffix)| match suffix {
+        Some(suffix) => format!("{name}_{suffix}"),
+        None => name,
+    })
+}
+
+/// Gets a strategy for `UAuthority` values with an address or ID that fits into the micro form of a `UUri`, i.e.
+/// an IPv4 address, an IPv6 address or an ID of up to 255 bytes.
+pub fn micro_form_authority() -> impl Strategy<Value = UAuthority> {
+    prop_oneof![
+        vec(any::<u8>(), 4).prop_map(Remote::Ip),
+        vec(any::<u8>(), 16).prop_map(Remote::Ip),
+        vec(any::<u8>(), 1..=32).prop_map(Remote::Id),
+    ]
+    .prop_map(|remote| UAuthority {
+        remote: Some(remote),
+    })
+}
+
+/// Gets a strategy for `UUri` values with names only, which can be serialized to and deserialized from their long
+/// form without changing, like `//vcu.my_car_vin/body.access/1/door.front_left#Door`.
+pub fn long_form_uri() -> impl Strategy<Value = UUri> {
+    (
+        option::of(AUTHORITY_NAME_PATTERN),
+        entity_name(),
+        1..=u32::from(u8::MAX),
+        name(),
+        option::of(name()),
+        option::of(name()),
+    )
+        .prop_map(
+            |(authority, entity, version, resource, instance, message)| UUri {
+                authority: authority.map(|name| UAuthority {
+                    remote: Some(Remote::Name(name)),
+                }),
+                entity: Some(UEntity {
+                    name: entity,
+                    version_major: Some(version),
+                    ..Default::default()
+                }),
+                resource: Some(UResource {
+                    name: resource,
+                    instance,
+                    message,
+                    id: None,
+                }),
+            },
+        )
+}
+
+/// Gets a strategy for `UUri` values with IDs only, which can be serialized to and deserialized from their micro
+/// form without changing.
+pub fn micro_form_uri() -> impl Strategy<Value = UUri> {
+    (
+        option::of(micro_form_authority()),
+        0..=u32::from(u16::MAX),
+        0..=u32::from(u8::MAX),
+        0..=u32::from(u16::MAX),
+    )
+        .prop_map(|(authority, entity, version, resource)| UUri {
+            authority,
+            entity: Some(UEntity {
+                id: Some(entity),
+                version_major: Some(version),
+                ..Default::default()
+            }),
+            resource: Some(UResourceBuilder::from_id(resource)),
+        })
+}
+
+/// Gets a strategy for resolved `UUri` values, i.e. with both names and IDs, which can be serialized to either
+/// form. Remote URIs have the address of their authority, as needed for the micro form.
+pub fn resolved_uri() -> impl Strategy<Value = UUri> {
+    (
+        long_form_uri(),
+        option::of(micro_form_authority()),
+        0..=u32::from(u16::MAX),
+        0..=u32::from(u16::MAX),
+    )
+        .prop_map(|(mut uri, authority, entity_id, resource_id)| {
+            uri.authority = authority;
+            if let Some(entity) = uri.entity.as_mut() {
+                entity.id = Some(entity_id);
+            }
+            if let Some(resource) = uri.resource.as_mut() {
+                resource.id = Some(resource_id);
+            }
+            uri
+        })
+}
+
+/// Gets a strategy for URIs of RPC methods, like `/body.access/1/rpc.UpdateDoor`, with both names and IDs.
+pub fn rpc_method_uri() -> impl Strategy<Value = UUri> {
+    (resolved_uri(), "[A-Z][A-Za-z0-9]{0,15}", 1..1000_u16).prop_map(|(mut uri, method, id)| {
+        uri.resource = Some(UResourceBuilder::for_rpc_request(
+            Some(method),
+            Some(UResourceId::new(id)),
+        ));
+        uri
+    })
+}
+
+/// Gets a strategy for URIs that responses to RPC methods are sent to, like `/hartley/1/rpc.response`.
+pub fn rpc_response_uri() -> impl Strategy<Value = UUri> {
+    resolved_uri().prop_map(|mut uri| {
+        uri.resource = Some(UResourceBuilder::for_rpc_response());
+        uri
+    })
+}
+
+/// Gets a strategy for `UUri` values that fail [`UriValidator::validate`](crate::uri::validator::UriValidator::validate),
+/// along with the path of the field that is invalid.
+pub fn invalid_uri() -> impl Strategy<Value = (UUri, &'static str)> {
+    prop_oneof![
+        Just((UUri::default(), "")),
+        long_form_uri().prop_map(|uri| (
+            UUri {
+                authority: Some(UAuthority::default()),
+                ..uri
+            },
+            "authority"
+        )),
+        (long_form_uri(), "[ ]{0,3}").prop_map(|(mut uri, name)| {
+            if let Some(entity) = uri.entity.as_mut() {
+                entity.name = name;
+            }
+            (uri, "entity.name")
+        }),
+    ]
+}
+
+impl Arbitrary for UUri {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<UUri>;
+
+    /// Generates valid `UUri` values in long form, in micro form and resolved.
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        prop_oneof![long_form_uri(), micro_form_uri(), resolved_uri()].boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uri::serializer::{LongUriSerializer, MicroUriSerializer, UriSerializer};
+    use crate::uri::validator::UriValidator;
+
+    proptest! {
+        #[test]
+        fn test_long_form_uris_round_trip(uri in long_form_uri()) {
+            prop_assert!(UriValidator::validate(&uri).is_ok());
+            let long_form = LongUriSerializer::serialize(&uri).unwrap();
+            prop_assert_eq!(LongUriSerializer::deserialize(long_form).unwrap(), uri);
+        }
+
+        #[test]
+        fn test_micro_form_uris_round_trip(uri in micro_form_uri()) {
+            let micro_form = MicroUriSerializer::serialize(&uri).unwrap();
+            prop_assert_eq!(MicroUriSerializer::deserialize(micro_form).unwrap(), uri);
+        }
+
+        #[test]
+        fn test_resolved_uris_serialize(uri in resolved_uri()) {
+            prop_assert!(UriValidator::validate(&uri).is_ok());
+            prop_assert!(UriValidator::is_micro_form(&uri));
+            prop_assert!(LongUriSerializer::serialize(&uri).is_ok());
+            prop_assert!(MicroUriSerializer::serialize(&uri).is_ok());
+        }
+
+        #[test]
+        fn test_rpc_uris_are_valid(method in rpc_method_uri(), response in rpc_response_uri()) {
+            prop_assert!(UriValidator::validate_rpc_method(&method).is_ok());
+            prop_assert!(UriValidator::validate_rpc_response(&response).is_ok());
+        }
+
+        #[test]
+        fn test_invalid_uris_fail((uri, field) in invalid_uri()) {
+            let error = UriValidator::validate(&uri).unwrap_err();
+            prop_assert_eq!(error.field().unwrap_or_default(), field);
+        }
+    }
+}
@@ -0,0 +1,800 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::StreamExt;
+
+use crate::policy::{AuditEvent, AuditSink, PolicyDecision, PolicyEngine};
+use crate::streamer::RoutingTable;
+use crate::transport::datamodel::{CancellationToken, Drain, Lifecycle, UTransport};
+use crate::uprotocol::{UAuthority, UCode, UMessage, UMessageType, UStatus, UUri, Uuid};
+
+// The number of forwarded message IDs to remember for detecting messages that loop back.
+const RECENT_IDS_CAPACITY: usize = 1024;
+
+/// The direction in which a [`Forwarder`] forwards a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForwardingDirection {
+    /// From the transport of the local device to the transport connecting to remote devices.
+    LocalToRemote,
+    /// From the transport connecting to remote devices to the transport of the local device.
+    RemoteToLocal,
+}
+
+/// A rule selecting the messages that a [`Forwarder`] forwards.
+///
+/// Rules match against the destination of a message, which is its sink, or its source for published messages
+/// without sink. Criteria that are not set match any message.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ForwardingRule {
+    direction: Option<ForwardingDirection>,
+    authority: Option<String>,
+    entity: Option<String>,
+    message_type: Option<UMessageType>,
+}
+
+impl ForwardingRule {
+    /// Creates a rule that matches all messages.
+    pub fn new() -> ForwardingRule {
+        ForwardingRule::default()
+    }
+
+    /// Restricts the rule to messages forwarded in a direction.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - The direction.
+    ///
+    /// # Returns
+    ///
+    /// The rule.
+    #[must_use]
+    pub fn with_direction(mut self, direction: ForwardingDirection) -> ForwardingRule {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Restricts the rule to messages destined for an authority.
+    ///
+    /// # Arguments
+    ///
+    /// * `authority` - The name of the authority.
+    ///
+    /// # Returns
+    ///
+    /// The rule.
+    #[must_use]
+    pub fn with_authority<T: Into<String>>(mut self, authority: T) -> ForwardingRule {
+        self.authority = Some(authority.into());
+        self
+    }
+
+    /// Restricts the rule to messages destined for a uEntity.
+    ///
+    /// # Arguments
+    ///
+    /// * `entity` - The name of the uEntity.
+    ///
+    /// # Returns
+    ///
+    /// The rule.
+    #[must_use]
+    pub fn with_entity<T: Into<String>>(mut self, entity: T) -> ForwardingRule {
+        self.entity = Some(entity.into());
+        self
+    }
+
+    /// Restricts the rule to messages of a type.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_type` - The type of message.
+    ///
+    /// # Returns
+    ///
+    /// The rule.
+    #[must_use]
+    pub fn with_message_type(mut self, message_type: UMessageType) -> ForwardingRule {
+        self.message_type = Some(message_type);
+        self
+    }
+
+    /// Checks if the rule matches a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - The direction that the message is to be forwarded in.
+    /// * `message` - The message.
+    pub fn matches(&self, direction: ForwardingDirection, message: &UMessage) -> bool {
+        let destination = destination_of(message);
+        let message_type = message
+            .attributes
+            .as_ref()
+            .and_then(|attributes| UMessageType::try_from(attributes.r#type).ok());
+
+        self.direction.map_or(true, |d| d == direction)
+            && self.authority.as_ref().map_or(true, |authority| {
+                destination
+                    .and_then(|uri| uri.authority.as_ref())
+                    .and_then(UAuthority::get_name)
+                    == Some(authority.as_str())
+            })
+            && self.entity.as_ref().map_or(true, |entity| {
+                destination
+                    .and_then(|uri| uri.entity.as_ref())
+                    .map(|e| e.name.as_str())
+                    == Some(entity.as_str())
+            })
+            && self.message_type.map_or(true, |t| message_type == Some(t))
+    }
+}
+
+/// `Forwarder` bridges the transport of the local device and a transport connecting to remote devices,
+/// which makes it the core building block of a uStreamer.
+///
+/// Messages received by the listeners registered with [`Forwarder::listen`] are forwarded by [`Forwarder::run`]
+/// if they match any of the forwarder's rules and are allowed by its policy engine, if set. While forwarding, the URIs of messages are re-resolved so that
+/// they are meaningful on the other side: local sources get the local authority when leaving the device, and
+/// sinks addressing the local authority become local when entering it. Messages that have already been forwarded,
+/// or that would return to the side they originate from, are dropped to prevent loops.
+///
+/// Shutting the forwarder down by means of [`Lifecycle::shutdown`] unregisters its listeners, and lets
+/// [`Forwarder::run`] forward the messages that have been received already before it completes.
+pub struct Forwarder<L: UTransport, R: UTransport> {
+    local: L,
+    remote: R,
+    local_authority: UAuthority,
+    rules: Vec<ForwardingRule>,
+    routing_table: Option<Arc<RoutingTable>>,
+    policy_engine: Option<Arc<dyn PolicyEngine>>,
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    recent_ids: Mutex<VecDeque<Uuid>>,
+    sender: UnboundedSender<(ForwardingDirection, UMessage)>,
+    receiver: Mutex<Option<UnboundedReceiver<(ForwardingDirection, UMessage)>>>,
+    listeners: Mutex<Vec<(ForwardingDirection, UUri, String)>>,
+    drain: Drain,
+}
+
+impl<L: UTransport, R: UTransport> Forwarder<L, R> {
+    /// Creates a forwarder without rules, which does not forward any messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `local` - The transport of the local device.
+    /// * `remote` - The transport connecting to remote devices.
+    /// * `local_authority` - The authority of the local device, as known to remote devices.
+    pub fn new(local: L, remote: R, local_authority: UAuthority) -> Forwarder<L, R> {
+        let (sender, receiver) = mpsc::unbounded();
+        Forwarder {
+            local,
+            remote,
+            local_authority,
+            rules: Vec::new(),
+            routing_table: None,
+            policy_engine: None,
+            audit_sink: None,
+            recent_ids: Mutex::new(VecDeque::with_capacity(RECENT_IDS_CAPACITY)),
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+            listeners: Mutex::new(Vec::new()),
+            drain: Drain::new(),
+        }
+    }
+
+    /// Adds a rule selecting messages to forward.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The rule.
+    ///
+    /// # Returns
+    ///
+    /// The forwarder.
+    #[must_use]
+    pub fn with_rule(mut self, rule: ForwardingRule) -> Forwarder<L, R> {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Sets a routing table that messages matching the forwarder's rules must also pass.
+    ///
+    /// The table is shared, so that it can be reloaded while the forwarder is running. Its priority rewrites
+    /// are applied to the forwarded messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `routing_table` - The routing table.
+    ///
+    /// # Returns
+    ///
+    /// The forwarder.
+    #[must_use]
+    pub fn with_routing_table(mut self, routing_table: Arc<RoutingTable>) -> Forwarder<L, R> {
+        self.routing_table = Some(routing_table);
+        self
+    }
+
+    /// Sets a policy engine that decides which uEntities may send messages to which other uEntities through the
+    /// forwarder. Messages that the policy engine denies are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy_engine` - The policy engine.
+    ///
+    /// # Returns
+    ///
+    /// The forwarder.
+    #[must_use]
+    pub fn with_policy_engine(mut self, policy_engine: Arc<dyn PolicyEngine>) -> Forwarder<L, R> {
+        self.policy_engine = Some(policy_engine);
+        self
+    }
+
+    /// Sets a sink that the messages denied by the forwarder's policy engine are recorded with.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit_sink` - The audit sink.
+    ///
+    /// # Returns
+    ///
+    /// The forwarder.
+    #[must_use]
+    pub fn with_audit_sink(mut self, audit_sink: Arc<dyn AuditSink>) -> Forwarder<L, R> {
+        self.audit_sink = Some(audit_sink);
+        self
+    }
+
+    /// Gets the transport of the local device.
+    pub fn local(&self) -> &L {
+        &self.local
+    }
+
+    /// Gets the transport connecting to remote devices.
+    pub fn remote(&self) -> &R {
+        &self.remote
+    }
+
+    /// Registers a listener for messages to forward.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - The direction to forward the messages in, which determines the transport to listen on.
+    /// * `topic` - The topic to listen to.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the listener, as returned by the transport.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::Unavailable` if the forwarder has been shut down, or the failure
+    /// reported by the transport.
+    pub async fn listen(
+        &self,
+        direction: ForwardingDirection,
+        topic: UUri,
+    ) -> Result<String, UStatus> {
+        self.drain.run(self.listen_now(direction, topic)).await
+    }
+
+    async fn listen_now(
+        &self,
+        direction: ForwardingDirection,
+        topic: UUri,
+    ) -> Result<String, UStatus> {
+        let sender = self.sender.clone();
+        let listener = Box::new(move |result: Result<UMessage, UStatus>| {
+            if let Ok(message) = result {
+                // the receiver only goes away along with the forwarder
+                let _ = sender.unbounded_send((direction, message));
+            }
+        });
+        let listener = match direction {
+            ForwardingDirection::LocalToRemote => {
+                self.local
+                    .register_listener(topic.clone(), listener)
+                    .await?
+            }
+            ForwardingDirection::RemoteToLocal => {
+                self.remote
+                    .register_listener(topic.clone(), listener)
+                    .await?
+            }
+        };
+        self.listeners
+            .lock()
+            .unwrap()
+            .push((direction, topic, listener.clone()));
+        Ok(listener)
+    }
+
+    /// Forwards the messages received by the forwarder's listeners.
+    ///
+    /// The returned future keeps forwarding messages for as long as it is polled, or until the forwarder has been
+    /// shut down and has forwarded the messages received before. Unless stopped by means of
+    /// [`Forwarder::run_until_cancelled`], the forwarder can be run only once. Failures to forward individual messages
+    /// are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::FailedPrecondition` if the forwarder is running already, or has been run
+    /// without being cancelled before, with code `UCode::Unavailable` if it has been shut down before, or with code
+    /// `UCode::Cancelled` if the received messages could not be forwarded by the deadline of shutting down.
+    pub async fn run(&self) -> Result<(), UStatus> {
+        self.run_until_cancelled(&CancellationToken::new()).await
+    }
+
+    /// Forwards the messages received by the forwarder's listeners until a token is cancelled.
+    ///
+    /// Other than dropping the future of [`Forwarder::run`], cancelling the token keeps the messages that have been
+    /// received but not forwarded yet, and allows for running the forwarder again. A message that is being forwarded
+    /// when the token is cancelled is forwarded before the returned future completes.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token that stops forwarding.
+    ///
+    /// # Errors
+    ///
+    /// Returns any of the errors returned by [`Forwarder::run`].
+    pub async fn run_until_cancelled(&self, token: &CancellationToken) -> Result<(), UStatus> {
+        self.drain
+            .run(async {
+                let mut receiver = self.receiver.lock().unwrap().take().ok_or_else(|| {
+                    UStatus::fail_with_code(
+                        UCode::FailedPrecondition,
+                        "Forwarder is running already",
+                    )
+                })?;
+                while let Some(received) = token.run_until_cancelled(receiver.next()).await {
+                    let Some((direction, message)) = received else {
+                        return Ok(());
+                    };
+                    let _ = self.forward(direction, message).await;
+                }
+                *self.receiver.lock().unwrap() = Some(receiver);
+                Ok(())
+            })
+            .await
+    }
+
+    /// Forwards a message, if it matches any of the forwarder's rules and passes its policy engine and routing table,
+    /// if set.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - The direction to forward the message in.
+    /// * `message` - The message.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the message has been forwarded, `false` if it has been dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the failure reported by the transport that the message has been forwarded to.
+    pub async fn forward(
+        &self,
+        direction: ForwardingDirection,
+        mut message: UMessage,
+    ) -> Result<bool, UStatus> {
+        if !self
+            .rules
+            .iter()
+            .any(|rule| rule.matches(direction, &message))
+            || self.is_denied(&message)
+            || !self
+                .routing_table
+                .as_ref()
+                .map_or(true, |table| table.apply(&mut message))
+            || self.is_looping(direction, &message)
+        {
+            return Ok(false);
+        }
+        self.re_resolve(direction, &mut message);
+
+        let topic = message.source.unwrap_or_default();
+        let payload = message.payload.unwrap_or_default();
+        let attributes = message.attributes.unwrap_or_default();
+        match direction {
+            ForwardingDirection::LocalToRemote => {
+                self.remote.send(topic, payload, attributes).await?;
+            }
+            ForwardingDirection::RemoteToLocal => {
+                self.local.send(topic, payload, attributes).await?;
+            }
+        }
+        Ok(true)
+    }
+
+    fn is_denied(&self, message: &UMessage) -> bool {
+        let denied = self.policy_engine.as_ref().map_or(false, |engine| {
+            engine.decide_message(message) == PolicyDecision::Deny
+        });
+        if denied {
+            if let Some(audit_sink) = &self.audit_sink {
+                audit_sink.record(AuditEvent::policy_denied(message));
+            }
+        }
+        denied
+    }
+
+    // Checks if a message has been forwarded before, or originates from the side it is to be forwarded to.
+    // Remembers the message's ID otherwise.
+    fn is_looping(&self, direction: ForwardingDirection, message: &UMessage) -> bool {
+        let source_authority = message
+            .source
+            .as_ref()
+            .and_then(|uri| uri.authority.as_ref());
+        let is_local_source =
+            source_authority.map_or(true, |authority| *authority == self.local_authority);
+        if is_local_source != (direction == ForwardingDirection::LocalToRemote) {
+            return true;
+        }
+
+        let Some(id) = message.attributes.as_ref().and_then(|a| a.id.as_ref()) else {
+            return false;
+        };
+        let mut recent_ids = self.recent_ids.lock().unwrap();
+        if recent_ids.contains(id) {
+            return true;
+        }
+        if recent_ids.len() == RECENT_IDS_CAPACITY {
+            recent_ids.pop_front();
+        }
+        recent_ids.push_back(id.clone());
+        false
+    }
+
+    fn re_resolve(&self, direction: ForwardingDirection, message: &mut UMessage) {
+        match direction {
+            ForwardingDirection::LocalToRemote => {
+                if let Some(source) = &mut message.source {
+                    source
+                        .authority
+                        .get_or_insert_with(|| self.local_authority.clone());
+                }
+            }
+            ForwardingDirection::RemoteToLocal => {
+                let sink = message
+                    .attributes
+                    .as_mut()
+                    .and_then(|attributes| attributes.sink.as_mut());
+                if let Some(sink) = sink {
+                    if sink.authority.as_ref() == Some(&self.local_authority) {
+                        sink.authority = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn destination_of(message: &UMessage) -> Option<&UUri> {
+    message
+        .attributes
+        .as_ref()
+        .and_then(|attributes| attributes.sink.as_ref())
+        .or(message.source.as_ref())
+}
+
+#[async_trait]
+impl<L: UTransport + Send + Sync, R: UTransport + Send + Sync> Lifecycle for Forwarder<L, R> {
+    async fn shutdown(&self, deadline: Duration) -> Result<(), UStatus> {
+        // stop receiving messages first, so that the queue runs dry
+        let listeners = std::mem::take(&mut *self.listeners.lock().unwrap());
+        let mut result = Ok(());
+        for (direction, topic, listener) in listeners {
+            let unregistered = match direction {
+                ForwardingDirection::LocalToRemote => {
+                    self.local.unregister_listener(topic, &listener).await
+                }
+                ForwardingDirection::RemoteToLocal => {
+                    self.remote.unregister_listener(topic, &listener).await
+                }
+            };
+            result = result.and(unregistered);
+        }
+        self.sender.close_channel();
+        self.drain.shutdown(deadline).await.and(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::FutureExt;
+
+    use crate::fixtures::RecordingTransport;
+    use crate::policy::StaticAllowlist;
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::{UEntity, UPriority, UResource};
+
+    #[derive(Default)]
+    struct RecordingAuditSink(Mutex<Vec<AuditEvent>>);
+
+    impl AuditSink for RecordingAuditSink {
+        fn record(&self, event: AuditEvent) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    fn build_authority_for_test(name: &str) -> UAuthority {
+        let mut authority = UAuthority::default();
+        authority.set_name(name);
+        authority
+    }
+
+    fn build_uri_for_test(authority: Option<&str>, entity: &str) -> UUri {
+        UUri {
+            authority: authority.map(build_authority_for_test),
+            entity: Some(UEntity {
+                name: entity.to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from("door.front_left#Door")),
+        }
+    }
+
+    fn build_publish_for_test(source: UUri) -> UMessage {
+        UMessage {
+            source: Some(source),
+            attributes: Some(UAttributesBuilder::publish(UPriority::UpriorityCs1).build()),
+            payload: None,
+        }
+    }
+
+    fn build_forwarder_for_test(
+        rule: ForwardingRule,
+    ) -> Forwarder<RecordingTransport, RecordingTransport> {
+        Forwarder::new(
+            RecordingTransport::default(),
+            RecordingTransport::default(),
+            build_authority_for_test("vcu.my_car"),
+        )
+        .with_rule(rule)
+    }
+
+    #[test]
+    fn test_rule_matches() {
+        let message = build_publish_for_test(build_uri_for_test(Some("cloud"), "body.access"));
+        let direction = ForwardingDirection::RemoteToLocal;
+
+        assert!(ForwardingRule::new().matches(direction, &message));
+        assert!(ForwardingRule::new()
+            .with_direction(direction)
+            .with_authority("cloud")
+            .with_entity("body.access")
+            .with_message_type(UMessageType::UmessageTypePublish)
+            .matches(direction, &message));
+        assert!(!ForwardingRule::new()
+            .with_direction(ForwardingDirection::LocalToRemote)
+            .matches(direction, &message));
+        assert!(!ForwardingRule::new()
+            .with_authority("vcu.other_car")
+            .matches(direction, &message));
+        assert!(!ForwardingRule::new()
+            .with_entity("hartley")
+            .matches(direction, &message));
+        assert!(!ForwardingRule::new()
+            .with_message_type(UMessageType::UmessageTypeRequest)
+            .matches(direction, &message));
+    }
+
+    #[tokio::test]
+    async fn test_forward_to_remote_adds_local_authority() {
+        let forwarder = build_forwarder_for_test(ForwardingRule::new());
+
+        let message = build_publish_for_test(build_uri_for_test(None, "body.access"));
+        assert!(forwarder
+            .forward(ForwardingDirection::LocalToRemote, message)
+            .await
+            .unwrap());
+
+        let sent = forwarder.remote().sent();
+        assert_eq!(
+            sent[0].0,
+            build_uri_for_test(Some("vcu.my_car"), "body.access")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forward_to_local_makes_sink_local() {
+        let forwarder = build_forwarder_for_test(ForwardingRule::new());
+
+        let sink = build_uri_for_test(Some("vcu.my_car"), "body.access");
+        let message = UMessage {
+            source: Some(build_uri_for_test(Some("cloud"), "hartley")),
+            attributes: Some(
+                UAttributesBuilder::notification(UPriority::UpriorityCs1, sink).build(),
+            ),
+            payload: None,
+        };
+        assert!(forwarder
+            .forward(ForwardingDirection::RemoteToLocal, message)
+            .await
+            .unwrap());
+
+        let sent = forwarder.local().sent();
+        assert_eq!(
+            sent[0].2.sink,
+            Some(build_uri_for_test(None, "body.access"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forward_drops_looping_messages() {
+        let forwarder = build_forwarder_for_test(ForwardingRule::new());
+
+        // a message from a remote device must not be sent back to remote devices
+        let message = build_publish_for_test(build_uri_for_test(Some("cloud"), "hartley"));
+        assert!(!forwarder
+            .forward(ForwardingDirection::LocalToRemote, message.clone())
+            .await
+            .unwrap());
+
+        // a message is forwarded only once
+        assert!(forwarder
+            .forward(ForwardingDirection::RemoteToLocal, message.clone())
+            .await
+            .unwrap());
+        assert!(!forwarder
+            .forward(ForwardingDirection::RemoteToLocal, message)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_forward_drops_messages_not_matching_rules() {
+        let forwarder = build_forwarder_for_test(ForwardingRule::new().with_entity("hartley"));
+
+        let message = build_publish_for_test(build_uri_for_test(None, "body.access"));
+        assert!(!forwarder
+            .forward(ForwardingDirection::LocalToRemote, message)
+            .await
+            .unwrap());
+        assert!(forwarder.remote().sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_forward_applies_routing_table() {
+        let table = RoutingTable::from_json(
+            r#"{ "routes": [{ "entity": "body.access", "action": "allow", "priority": "UPRIORITY_CS3" }] }"#,
+        )
+        .unwrap();
+        let forwarder =
+            build_forwarder_for_test(ForwardingRule::new()).with_routing_table(Arc::new(table));
+
+        let message = build_publish_for_test(build_uri_for_test(None, "hartley"));
+        assert!(!forwarder
+            .forward(ForwardingDirection::LocalToRemote, message)
+            .await
+            .unwrap());
+
+        let message = build_publish_for_test(build_uri_for_test(None, "body.access"));
+        assert!(forwarder
+            .forward(ForwardingDirection::LocalToRemote, message)
+            .await
+            .unwrap());
+        let sent = forwarder.remote().sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].2.priority, UPriority::UpriorityCs3 as i32);
+    }
+
+    #[tokio::test]
+    async fn test_forward_applies_policy_engine() {
+        let allowlist = StaticAllowlist::from_json(
+            r#"{ "entries": [{ "caller": { "entity": "body.access" } }] }"#,
+        )
+        .unwrap();
+        let audit_sink = Arc::new(RecordingAuditSink::default());
+        let forwarder = build_forwarder_for_test(ForwardingRule::new())
+            .with_policy_engine(Arc::new(allowlist))
+            .with_audit_sink(audit_sink.clone());
+
+        let message = build_publish_for_test(build_uri_for_test(None, "hartley"));
+        assert!(!forwarder
+            .forward(ForwardingDirection::LocalToRemote, message.clone())
+            .await
+            .unwrap());
+        assert_eq!(
+            *audit_sink.0.lock().unwrap(),
+            vec![AuditEvent::policy_denied(&message)]
+        );
+
+        let message = build_publish_for_test(build_uri_for_test(None, "body.access"));
+        assert!(forwarder
+            .forward(ForwardingDirection::LocalToRemote, message)
+            .await
+            .unwrap());
+        assert_eq!(forwarder.remote().sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_forwards_messages_received_by_listeners() {
+        let forwarder = build_forwarder_for_test(ForwardingRule::new());
+        forwarder
+            .listen(
+                ForwardingDirection::LocalToRemote,
+                build_uri_for_test(None, "body.access"),
+            )
+            .await
+            .unwrap();
+
+        let message = build_publish_for_test(build_uri_for_test(None, "body.access"));
+        forwarder
+            .local()
+            .deliver(&build_uri_for_test(None, "body.access"), Ok(message));
+
+        // the future does not complete, but forwards the queued message when being polled
+        assert!(forwarder.run().now_or_never().is_none());
+        assert_eq!(forwarder.remote().sent().len(), 1);
+        assert!(forwarder.run().now_or_never().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_run_until_cancelled_can_be_resumed() {
+        let forwarder = build_forwarder_for_test(ForwardingRule::new());
+        let token = CancellationToken::new();
+        token.cancel();
+        assert!(forwarder
+            .run_until_cancelled(&token)
+            .now_or_never()
+            .unwrap()
+            .is_ok());
+
+        // the receiver has been kept for running again
+        assert!(forwarder.run().now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_forwards_received_messages() {
+        let forwarder = build_forwarder_for_test(ForwardingRule::new());
+        forwarder
+            .listen(
+                ForwardingDirection::LocalToRemote,
+                build_uri_for_test(None, "body.access"),
+            )
+            .await
+            .unwrap();
+        let message = build_publish_for_test(build_uri_for_test(None, "body.access"));
+        forwarder
+            .local()
+            .deliver(&build_uri_for_test(None, "body.access"), Ok(message));
+
+        let (run, shutdown) =
+            futures::join!(forwarder.run(), forwarder.shutdown(Duration::from_secs(1)));
+        assert!(run.is_ok());
+        assert!(shutdown.is_ok());
+        assert_eq!(forwarder.remote().sent().len(), 1);
+        assert_eq!(
+            forwarder
+                .listen(
+                    ForwardingDirection::LocalToRemote,
+                    build_uri_for_test(None, "body.access"),
+                )
+                .await
+                .unwrap_err()
+                .get_code(),
+            UCode::Unavailable
+        );
+    }
+}
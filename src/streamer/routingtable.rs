@@ -0,0 +1,406 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use super::forwarder::destination_of;
+use crate::types::serializationerror::SerializationError;
+use crate::uprotocol::{UAuthority, UMessage, UPriority, UUri};
+
+/// Whether a route lets matching messages pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteAction {
+    Allow,
+    #[default]
+    Deny,
+}
+
+/// An entry of a [`RoutingTable`].
+///
+/// A route matches messages by the authority, uEntity and resource names of their destination, in this order,
+/// with criteria that are not set matching any name. Routes are prefixes of the destination: the more of these
+/// criteria up to the last one set, the longer the prefix.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Route {
+    /// The name of the authority, or `""` for destinations on the local device.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authority: Option<String>,
+    /// The name of the uEntity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity: Option<String>,
+    /// The name of the resource.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    /// Whether matching messages may pass.
+    pub action: RouteAction,
+    /// The priority to rewrite matching messages to, as named in the uProtocol proto definitions,
+    /// e.g. `UPRIORITY_CS4`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+}
+
+impl Route {
+    fn prefix_len(&self) -> usize {
+        [&self.authority, &self.entity, &self.resource]
+            .iter()
+            .rposition(|criterion| criterion.is_some())
+            .map_or(0, |index| index + 1)
+    }
+
+    fn matches(&self, destination: &UUri) -> bool {
+        let authority = destination
+            .authority
+            .as_ref()
+            .and_then(UAuthority::get_name)
+            .unwrap_or_default();
+        let entity = destination
+            .entity
+            .as_ref()
+            .map(|entity| entity.name.as_str())
+            .unwrap_or_default();
+        let resource = destination
+            .resource
+            .as_ref()
+            .map(|resource| resource.name.as_str())
+            .unwrap_or_default();
+
+        [
+            (&self.authority, authority),
+            (&self.entity, entity),
+            (&self.resource, resource),
+        ]
+        .iter()
+        .all(|(criterion, name)| criterion.as_ref().map_or(true, |c| c == name))
+    }
+}
+
+/// The configuration of a [`RoutingTable`], e.g. as loaded from a JSON file.
+///
+/// ```json
+/// {
+///   "default_action": "deny",
+///   "routes": [
+///     { "authority": "cloud", "action": "allow" },
+///     { "authority": "cloud", "entity": "body.access", "action": "allow", "priority": "UPRIORITY_CS2" },
+///     { "authority": "cloud", "entity": "body.access", "resource": "diagnostics", "action": "deny" }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingTableConfig {
+    /// The action for messages that no route matches.
+    #[serde(default)]
+    pub default_action: RouteAction,
+    /// The routes.
+    #[serde(default)]
+    pub routes: Vec<Route>,
+}
+
+/// The decision of a [`RoutingTable`] about a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutingDecision {
+    /// The message may pass, with its priority rewritten if set.
+    Forward(Option<UPriority>),
+    /// The message must be dropped.
+    Drop,
+}
+
+// A route with its priority parsed.
+#[derive(Debug)]
+struct CompiledRoute {
+    route: Route,
+    priority: Option<UPriority>,
+}
+
+#[derive(Debug)]
+struct CompiledTable {
+    default_action: RouteAction,
+    // ordered by descending prefix length, retaining the configured order on ties
+    routes: Vec<CompiledRoute>,
+}
+
+/// `RoutingTable` decides which messages a [`Forwarder`](crate::streamer::Forwarder) lets pass, based on
+/// declarative routes that can be loaded from configuration.
+///
+/// The route with the longest prefix matching a message's destination decides, with the first configured route
+/// winning among routes of the same length. The table can be reloaded while in use, so that policies can be changed
+/// without restarting, let alone recompiling, a uStreamer.
+#[derive(Debug)]
+pub struct RoutingTable {
+    table: RwLock<Arc<CompiledTable>>,
+}
+
+impl RoutingTable {
+    /// Creates a routing table.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration of the table.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if a route has an unknown priority.
+    pub fn new(config: RoutingTableConfig) -> Result<RoutingTable, SerializationError> {
+        Ok(RoutingTable {
+            table: RwLock::new(Arc::new(compile(config)?)),
+        })
+    }
+
+    /// Creates a routing table from a JSON configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The configuration, as shown for [`RoutingTableConfig`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the configuration is malformed or has a route with an unknown priority.
+    pub fn from_json(json: &str) -> Result<RoutingTable, SerializationError> {
+        Self::new(parse(json)?)
+    }
+
+    /// Creates a routing table from a JSON configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the file cannot be read, or any of the errors returned by
+    /// [`RoutingTable::from_json`].
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<RoutingTable, SerializationError> {
+        Self::from_json(&read(path.as_ref())?)
+    }
+
+    /// Replaces the routes of this table.
+    ///
+    /// Decisions made concurrently use either the previous or the new routes, never a mix of both. If the
+    /// configuration is invalid, the table keeps its previous routes.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The new configuration of the table.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if a route has an unknown priority.
+    pub fn reload(&self, config: RoutingTableConfig) -> Result<(), SerializationError> {
+        let table = Arc::new(compile(config)?);
+        *self.table.write().unwrap() = table;
+        Ok(())
+    }
+
+    /// Replaces the routes of this table with the ones from a JSON configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the file cannot be read, the configuration is malformed or has a route
+    /// with an unknown priority.
+    pub fn reload_from_file<P: AsRef<Path>>(&self, path: P) -> Result<(), SerializationError> {
+        self.reload(parse(&read(path.as_ref())?)?)
+    }
+
+    /// Decides about a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message, which is matched by its sink, or its source for published messages without sink.
+    pub fn route(&self, message: &UMessage) -> RoutingDecision {
+        let table = Arc::clone(&self.table.read().unwrap());
+        let matching = destination_of(message).and_then(|destination| {
+            table
+                .routes
+                .iter()
+                .find(|compiled| compiled.route.matches(destination))
+        });
+        match matching {
+            Some(compiled) if compiled.route.action == RouteAction::Allow => {
+                RoutingDecision::Forward(compiled.priority)
+            }
+            Some(_) => RoutingDecision::Drop,
+            None if table.default_action == RouteAction::Allow => RoutingDecision::Forward(None),
+            None => RoutingDecision::Drop,
+        }
+    }
+
+    /// Decides about a message, rewriting its priority if the route says so.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the message may pass.
+    pub fn apply(&self, message: &mut UMessage) -> bool {
+        match self.route(message) {
+            RoutingDecision::Forward(priority) => {
+                if let (Some(priority), Some(attributes)) = (priority, &mut message.attributes) {
+                    attributes.priority = priority.into();
+                }
+                true
+            }
+            RoutingDecision::Drop => false,
+        }
+    }
+}
+
+fn compile(config: RoutingTableConfig) -> Result<CompiledTable, SerializationError> {
+    let mut routes = config
+        .routes
+        .into_iter()
+        .map(|route| {
+            let priority = route
+                .priority
+                .as_deref()
+                .map(|name| {
+                    UPriority::from_str_name(name).ok_or_else(|| {
+                        SerializationError::new(format!("Unknown priority [{name}]"))
+                    })
+                })
+                .transpose()?;
+            Ok(CompiledRoute { route, priority })
+        })
+        .collect::<Result<Vec<_>, SerializationError>>()?;
+    routes.sort_by_key(|compiled| std::cmp::Reverse(compiled.route.prefix_len()));
+    Ok(CompiledTable {
+        default_action: config.default_action,
+        routes,
+    })
+}
+
+fn parse(json: &str) -> Result<RoutingTableConfig, SerializationError> {
+    serde_json::from_str(json).map_err(|e| SerializationError::json("Invalid routing table", e))
+}
+
+fn read(path: &Path) -> Result<String, SerializationError> {
+    std::fs::read_to_string(path).map_err(|e| {
+        SerializationError::other(
+            format!("Failed to read routing table [{}]", path.display()),
+            e,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::{UEntity, UResource};
+
+    const CONFIG: &str = r#"{
+        "default_action": "deny",
+        "routes": [
+            { "authority": "cloud", "entity": "body.access", "resource": "diagnostics", "action": "deny" },
+            { "authority": "cloud", "action": "allow" },
+            { "authority": "cloud", "entity": "body.access", "action": "allow", "priority": "UPRIORITY_CS2" },
+            { "entity": "hartley", "action": "allow" }
+        ]
+    }"#;
+
+    fn build_message_for_test(authority: Option<&str>, entity: &str, resource: &str) -> UMessage {
+        let mut uri = UUri {
+            entity: Some(UEntity {
+                name: entity.to_string(),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from(resource)),
+            ..Default::default()
+        };
+        if let Some(name) = authority {
+            uri.authority
+                .get_or_insert_with(Default::default)
+                .set_name(name);
+        }
+        UMessage {
+            source: Some(uri),
+            attributes: Some(UAttributesBuilder::publish(UPriority::UpriorityCs1).build()),
+            payload: None,
+        }
+    }
+
+    #[test_case(Some("cloud"), "body.access", "door", RoutingDecision::Forward(Some(UPriority::UpriorityCs2)); "entity route")]
+    #[test_case(Some("cloud"), "body.access", "diagnostics", RoutingDecision::Drop; "resource route")]
+    #[test_case(Some("cloud"), "other", "door", RoutingDecision::Forward(None); "authority route")]
+    #[test_case(Some("vcu.other_car"), "hartley", "door", RoutingDecision::Forward(None); "route with wildcard authority")]
+    #[test_case(None, "body.access", "door", RoutingDecision::Drop; "default action")]
+    fn test_route(
+        authority: Option<&str>,
+        entity: &str,
+        resource: &str,
+        expected: RoutingDecision,
+    ) {
+        let table = RoutingTable::from_json(CONFIG).unwrap();
+        let message = build_message_for_test(authority, entity, resource);
+        assert_eq!(table.route(&message), expected);
+    }
+
+    #[test]
+    fn test_apply_rewrites_priority() {
+        let table = RoutingTable::from_json(CONFIG).unwrap();
+
+        let mut message = build_message_for_test(Some("cloud"), "body.access", "door");
+        assert!(table.apply(&mut message));
+        assert_eq!(
+            message.attributes.unwrap().priority,
+            UPriority::UpriorityCs2 as i32
+        );
+    }
+
+    #[test]
+    fn test_reload() {
+        let table = RoutingTable::from_json(CONFIG).unwrap();
+        let message = build_message_for_test(None, "body.access", "door");
+        assert_eq!(table.route(&message), RoutingDecision::Drop);
+
+        let invalid = RoutingTableConfig {
+            routes: vec![Route {
+                priority: Some("URGENT".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(table.reload(invalid).is_err());
+        assert_eq!(table.route(&message), RoutingDecision::Drop);
+
+        let path = std::env::temp_dir().join(format!("routingtable-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{ "routes": [{ "authority": "", "action": "allow" }] }"#,
+        )
+        .unwrap();
+        let result = table.reload_from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        result.unwrap();
+        assert_eq!(table.route(&message), RoutingDecision::Forward(None));
+    }
+
+    #[test_case("{ \"routes\": [{ \"entity\": \"hartley\" }] }"; "missing action")]
+    #[test_case("{ \"routes\": [{ \"action\": \"maybe\" }] }"; "unknown action")]
+    #[test_case("{ \"routes\": [{ \"action\": \"allow\", \"priority\": \"CS9\" }] }"; "unknown priority")]
+    fn test_from_json_fails_for_invalid_config(json: &str) {
+        assert!(RoutingTable::from_json(json).is_err());
+    }
+}
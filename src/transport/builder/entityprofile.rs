@@ -0,0 +1,212 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::transport::builder::UAttributesBuilder;
+use crate::uprotocol::{UMessageType, UPriority, UUri, Uuid};
+
+const DEFAULT_REQUEST_TTL: u32 = 10_000;
+
+/// The defaults for the attributes of the messages that a uEntity sends, e.g. as mandated by the platform it runs on.
+///
+/// A profile is created once per uEntity, and handed to the components creating attributes, i.e.
+/// [`UAttributesBuilder::with_profile`], [`Publisher::with_profile`](crate::pubsub::Publisher::with_profile) and
+/// the clients of the core services, instead of repeating the defaults wherever messages are sent. Attributes that
+/// are set explicitly take precedence over the ones of the profile.
+///
+/// Without defaults, published messages have priority `UPriority::UpriorityCs1`, and requests and responses have
+/// priority `UPriority::UpriorityCs4`, the lowest priority that the uProtocol specification allows for RPC messages.
+/// Requests have a time-to-live of 10 seconds then.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EntityProfile {
+    // indexed by message type
+    priorities: [Option<UPriority>; 4],
+    ttls: [Option<u32>; 4],
+    permission_level: Option<u32>,
+}
+
+fn slot(message_type: UMessageType) -> usize {
+    match message_type {
+        UMessageType::UmessageTypeUnspecified => 0,
+        UMessageType::UmessageTypePublish => 1,
+        UMessageType::UmessageTypeRequest => 2,
+        UMessageType::UmessageTypeResponse => 3,
+    }
+}
+
+impl EntityProfile {
+    /// Creates a profile without defaults.
+    pub fn new() -> EntityProfile {
+        EntityProfile::default()
+    }
+
+    /// Sets the default priority of a type of messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_type` - The type of messages.
+    /// * `priority` - The priority.
+    ///
+    /// # Returns
+    ///
+    /// The profile.
+    #[must_use]
+    pub fn with_priority(
+        mut self,
+        message_type: UMessageType,
+        priority: UPriority,
+    ) -> EntityProfile {
+        self.priorities[slot(message_type)] = Some(priority);
+        self
+    }
+
+    /// Sets the default time-to-live of a type of messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_type` - The type of messages.
+    /// * `ttl` - The time-to-live in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// The profile.
+    #[must_use]
+    pub fn with_ttl(mut self, message_type: UMessageType, ttl: u32) -> EntityProfile {
+        self.ttls[slot(message_type)] = Some(ttl);
+        self
+    }
+
+    /// Sets the default permission level of all messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `permission_level` - The permission level.
+    ///
+    /// # Returns
+    ///
+    /// The profile.
+    #[must_use]
+    pub fn with_permission_level(mut self, permission_level: u32) -> EntityProfile {
+        self.permission_level = Some(permission_level);
+        self
+    }
+
+    /// Gets the default priority of a type of messages, if any.
+    pub fn priority(&self, message_type: UMessageType) -> Option<UPriority> {
+        self.priorities[slot(message_type)]
+    }
+
+    /// Gets the default time-to-live in milliseconds of a type of messages, if any.
+    pub fn ttl(&self, message_type: UMessageType) -> Option<u32> {
+        self.ttls[slot(message_type)]
+    }
+
+    /// Gets the default permission level of messages, if any.
+    pub fn permission_level(&self) -> Option<u32> {
+        self.permission_level
+    }
+
+    /// Gets a builder for creating a publish message with the defaults of this profile.
+    pub fn publish(&self) -> UAttributesBuilder {
+        let priority = self.priority_or(UMessageType::UmessageTypePublish, UPriority::UpriorityCs1);
+        self.apply(UAttributesBuilder::publish(priority))
+    }
+
+    /// Gets a builder for creating a notification message with the defaults of this profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - The destination URI.
+    pub fn notification(&self, sink: UUri) -> UAttributesBuilder {
+        let priority = self.priority_or(UMessageType::UmessageTypePublish, UPriority::UpriorityCs1);
+        self.apply(UAttributesBuilder::notification(priority, sink))
+    }
+
+    /// Gets a builder for creating a request message with the defaults of this profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - The URI of the method to invoke.
+    pub fn request(&self, sink: UUri) -> UAttributesBuilder {
+        let priority = self.priority_or(UMessageType::UmessageTypeRequest, UPriority::UpriorityCs4);
+        let ttl = self
+            .ttl(UMessageType::UmessageTypeRequest)
+            .unwrap_or(DEFAULT_REQUEST_TTL);
+        self.apply(UAttributesBuilder::request(priority, sink, ttl))
+    }
+
+    /// Gets a builder for creating a response message with the defaults of this profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `sink` - The URI of the uEntity that has sent the request.
+    /// * `reqid` - The ID of the request.
+    pub fn response(&self, sink: UUri, reqid: Uuid) -> UAttributesBuilder {
+        let priority =
+            self.priority_or(UMessageType::UmessageTypeResponse, UPriority::UpriorityCs4);
+        self.apply(UAttributesBuilder::response(priority, sink, reqid))
+    }
+
+    fn priority_or(&self, message_type: UMessageType, default: UPriority) -> UPriority {
+        self.priority(message_type).unwrap_or(default)
+    }
+
+    fn apply(&self, mut builder: UAttributesBuilder) -> UAttributesBuilder {
+        builder.apply_profile(self);
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builders_take_defaults_of_profile() {
+        let profile = EntityProfile::new()
+            .with_priority(UMessageType::UmessageTypePublish, UPriority::UpriorityCs2)
+            .with_ttl(UMessageType::UmessageTypePublish, 500)
+            .with_ttl(UMessageType::UmessageTypeRequest, 2000)
+            .with_permission_level(3);
+
+        let attributes = profile.publish().build();
+        assert_eq!(attributes.priority, UPriority::UpriorityCs2.into());
+        assert_eq!(attributes.ttl, Some(500));
+        assert_eq!(attributes.permission_level, Some(3));
+
+        let attributes = profile.request(UUri::default()).build();
+        assert_eq!(attributes.priority, UPriority::UpriorityCs4.into());
+        assert_eq!(attributes.ttl, Some(2000));
+
+        let attributes = profile.response(UUri::default(), Uuid::default()).build();
+        assert_eq!(attributes.ttl, None);
+        assert_eq!(attributes.permission_level, Some(3));
+    }
+
+    #[test]
+    fn test_explicit_attributes_take_precedence() {
+        let profile = EntityProfile::new()
+            .with_ttl(UMessageType::UmessageTypePublish, 500)
+            .with_permission_level(3);
+
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1)
+            .with_ttl(100)
+            .with_profile(&profile)
+            .build();
+        assert_eq!(attributes.ttl, Some(100));
+        assert_eq!(attributes.permission_level, Some(3));
+
+        let attributes = EntityProfile::new().request(UUri::default()).build();
+        assert_eq!(attributes.ttl, Some(10_000));
+        assert_eq!(attributes.permission_level, None);
+    }
+}
@@ -11,6 +11,7 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+use crate::transport::builder::EntityProfile;
 use crate::uprotocol::{UAttributes, UMessageType, UPriority, UUri, Uuid};
 use crate::uuid::builder::UUIDv8Builder;
 
@@ -218,6 +219,53 @@ impl UAttributesBuilder {
         self
     }
 
+    /// Sets the message's ID, replacing the one created for the current time of the system clock.
+    ///
+    /// This allows for IDs created by a [`UUIDv8Builder`] with another [`UClock`](crate::uuid::builder::UClock),
+    /// e.g. in simulations.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID.
+    ///
+    /// # Returns
+    ///
+    /// The builder.
+    #[must_use]
+    pub fn with_id(&mut self, id: Uuid) -> &mut UAttributesBuilder {
+        self.id = id;
+        self
+    }
+
+    /// Sets the message's time-to-live and permission level to the defaults of a profile, unless they have been set
+    /// already.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The profile of the uEntity sending the message.
+    ///
+    /// # Returns
+    ///
+    /// The builder.
+    #[must_use]
+    pub fn with_profile(&mut self, profile: &EntityProfile) -> &mut UAttributesBuilder {
+        self.apply_profile(profile);
+        self
+    }
+
+    pub(crate) fn apply_profile(&mut self, profile: &EntityProfile) {
+        if self.ttl.is_none() {
+            self.ttl = profile
+                .ttl(self.message_type)
+                .map(|ttl| i32::try_from(ttl).unwrap_or(i32::MAX));
+        }
+        if self.plevel.is_none() {
+            self.plevel = profile
+                .permission_level()
+                .map(|plevel| i32::try_from(plevel).unwrap_or(i32::MAX));
+        }
+    }
+
     /// Creates the attributes based on the builder's state.
     ///
     /// # Returns
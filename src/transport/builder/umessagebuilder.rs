@@ -0,0 +1,169 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::transport::datamodel::PayloadLimits;
+use crate::transport::validator::Validated;
+use crate::uprotocol::{UAttributes, UMessage, UPayload, UStatus, UUri};
+
+/// Builder for easy construction of the `UMessage` object.
+pub struct UMessageBuilder {
+    source: UUri,
+    attributes: UAttributes,
+    payload: Option<UPayload>,
+    limits: PayloadLimits,
+}
+
+impl UMessageBuilder {
+    /// Gets a builder for creating a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source address of the message.
+    /// * `attributes` - The attributes of the message.
+    ///
+    /// # Returns
+    ///
+    /// The builder initialized with the given values and without payload limits.
+    pub fn new(source: UUri, attributes: UAttributes) -> UMessageBuilder {
+        UMessageBuilder {
+            source,
+            attributes,
+            payload: None,
+            limits: PayloadLimits::default(),
+        }
+    }
+
+    /// Sets the message's payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload.
+    ///
+    /// # Returns
+    ///
+    /// The builder.
+    #[must_use]
+    pub fn with_payload(&mut self, payload: UPayload) -> &mut UMessageBuilder {
+        self.payload = Some(payload);
+        self
+    }
+
+    /// Sets the limits that the message's payload needs to comply with.
+    ///
+    /// # Arguments
+    ///
+    /// * `limits` - The limits of the transport that the message is intended for.
+    ///
+    /// # Returns
+    ///
+    /// The builder.
+    #[must_use]
+    pub fn with_payload_limits(&mut self, limits: PayloadLimits) -> &mut UMessageBuilder {
+        self.limits = limits;
+        self
+    }
+
+    /// Creates the message based on the builder's state.
+    ///
+    /// # Returns
+    ///
+    /// The message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::ResourceExhausted` if the payload exceeds the configured limits.
+    pub fn build(&self) -> Result<UMessage, UStatus> {
+        if let Some(payload) = &self.payload {
+            self.limits.check(payload)?;
+        }
+        Ok(UMessage {
+            source: Some(self.source.clone()),
+            attributes: Some(self.attributes.clone()),
+            payload: self.payload.clone(),
+        })
+    }
+
+    /// Creates the message based on the builder's state, and validates it once, so that it can be passed to
+    /// [`UTransport::send_validated`](crate::transport::datamodel::UTransport::send_validated) without being
+    /// validated again.
+    ///
+    /// # Returns
+    ///
+    /// The validated message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::ResourceExhausted` if the payload exceeds the configured limits, or
+    /// with code `UCode::InvalidArgument` if the attributes are invalid.
+    pub fn build_validated(&self) -> Result<Validated<UMessage>, UStatus> {
+        Validated::new(self.build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::builder::{UAttributesBuilder, UPayloadBuilder};
+    use crate::uprotocol::{UCode, UEntity, UPriority};
+
+    fn build_source_for_test() -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_message() {
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        let payload = UPayloadBuilder::from_raw(vec![1, 2, 3]).build().unwrap();
+
+        let message = UMessageBuilder::new(build_source_for_test(), attributes.clone())
+            .with_payload(payload.clone())
+            .with_payload_limits(PayloadLimits::new(3))
+            .build()
+            .unwrap();
+        assert_eq!(message.source, Some(build_source_for_test()));
+        assert_eq!(message.attributes, Some(attributes));
+        assert_eq!(message.payload, Some(payload));
+    }
+
+    #[test]
+    fn test_build_fails_for_oversized_payload() {
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        let payload = UPayloadBuilder::from_raw(vec![1, 2, 3]).build().unwrap();
+
+        let result = UMessageBuilder::new(build_source_for_test(), attributes)
+            .with_payload(payload)
+            .with_payload_limits(PayloadLimits::new(2))
+            .build();
+        assert_eq!(result.unwrap_err().get_code(), UCode::ResourceExhausted);
+    }
+
+    #[test]
+    fn test_build_validated_message() {
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        let message = UMessageBuilder::new(build_source_for_test(), attributes.clone())
+            .build_validated()
+            .unwrap();
+        assert_eq!(message.attributes, Some(attributes));
+
+        let result =
+            UMessageBuilder::new(build_source_for_test(), UAttributes::default()).build_validated();
+        assert_eq!(result.unwrap_err().get_code(), UCode::InvalidArgument);
+    }
+}
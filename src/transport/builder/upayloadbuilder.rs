@@ -0,0 +1,186 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use prost::Message;
+use prost_types::Any;
+use serde::Serialize;
+
+use crate::transport::builder::SerializationError;
+use crate::uprotocol::{Data, UPayload, UPayloadFormat};
+
+/// Builder for easy construction of the `UPayload` object.
+///
+/// Each constructor sets the payload format matching the way the data is encoded, and [`UPayloadBuilder::build`]
+/// sets the length of the data, so that both are always consistent with the payload's content.
+pub struct UPayloadBuilder {
+    data: Vec<u8>,
+    format: UPayloadFormat,
+}
+
+impl UPayloadBuilder {
+    /// Gets a builder for a payload containing a protobuf message.
+    ///
+    /// The message is wrapped in a `google.protobuf.Any`, as expected by `Any::try_from(UPayload)`
+    /// and the [`RpcMapper`](crate::rpc::RpcMapper).
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to put into the payload.
+    ///
+    /// # Returns
+    ///
+    /// The builder initialized with the encoded message and format `UpayloadFormatProtobuf`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the message cannot be wrapped in an `Any`.
+    pub fn from_protobuf<T: prost::Name>(
+        message: &T,
+    ) -> Result<UPayloadBuilder, SerializationError> {
+        let any = Any::from_msg(message).map_err(|e| {
+            SerializationError::encode(format!("Failed to encode message {}", T::full_name()), e)
+        })?;
+        Ok(UPayloadBuilder {
+            data: any.encode_to_vec(),
+            format: UPayloadFormat::UpayloadFormatProtobuf,
+        })
+    }
+
+    /// Gets a builder for a payload containing a JSON document.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to serialize into the payload.
+    ///
+    /// # Returns
+    ///
+    /// The builder initialized with the serialized value and format `UpayloadFormatJson`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the value cannot be serialized to JSON.
+    pub fn from_json<T: Serialize>(value: &T) -> Result<UPayloadBuilder, SerializationError> {
+        let data = serde_json::to_vec(value)
+            .map_err(|e| SerializationError::json("Failed to serialize value to JSON", e))?;
+        Ok(UPayloadBuilder {
+            data,
+            format: UPayloadFormat::UpayloadFormatJson,
+        })
+    }
+
+    /// Gets a builder for a payload containing text.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to put into the payload.
+    ///
+    /// # Returns
+    ///
+    /// The builder initialized with the UTF-8 encoded text and format `UpayloadFormatText`.
+    pub fn from_text(text: &str) -> UPayloadBuilder {
+        UPayloadBuilder {
+            data: text.as_bytes().to_vec(),
+            format: UPayloadFormat::UpayloadFormatText,
+        }
+    }
+
+    /// Gets a builder for a payload containing raw bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The bytes to put into the payload.
+    ///
+    /// # Returns
+    ///
+    /// The builder initialized with the given bytes and format `UpayloadFormatRaw`.
+    pub fn from_raw(data: Vec<u8>) -> UPayloadBuilder {
+        UPayloadBuilder {
+            data,
+            format: UPayloadFormat::UpayloadFormatRaw,
+        }
+    }
+
+    /// Creates the payload based on the builder's state.
+    ///
+    /// # Returns
+    ///
+    /// The payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the data exceeds the maximum length of a `UPayload` ([`i32::MAX`] bytes).
+    pub fn build(self) -> Result<UPayload, SerializationError> {
+        let length = i32::try_from(self.data.len())
+            .map_err(|_e| SerializationError::new("Data does not fit into UPayload"))?;
+        Ok(UPayload {
+            data: Some(Data::Value(self.data)),
+            length: Some(length),
+            format: self.format.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prost_types::Timestamp;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_from_protobuf() {
+        let timestamp = Timestamp {
+            seconds: 1_000_000,
+            nanos: 42,
+        };
+        let payload = UPayloadBuilder::from_protobuf(&timestamp)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(payload.format(), UPayloadFormat::UpayloadFormatProtobuf);
+        let any = Any::try_from(payload).unwrap();
+        assert_eq!(any.to_msg::<Timestamp>().unwrap(), timestamp);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let value = json!({"door": "front_left", "open": true});
+        let payload = UPayloadBuilder::from_json(&value).unwrap().build().unwrap();
+
+        assert_eq!(payload.format(), UPayloadFormat::UpayloadFormatJson);
+        let Some(Data::Value(data)) = payload.data else {
+            panic!("payload should contain data");
+        };
+        assert_eq!(payload.length, Some(data.len() as i32));
+        assert_eq!(serde_json::from_slice::<Value>(&data).unwrap(), value);
+    }
+
+    #[test]
+    fn test_from_text() {
+        let payload = UPayloadBuilder::from_text("hällo").build().unwrap();
+
+        assert_eq!(payload.format(), UPayloadFormat::UpayloadFormatText);
+        assert_eq!(payload.length, Some(6));
+        assert_eq!(payload.data, Some(Data::Value("hällo".as_bytes().to_vec())));
+    }
+
+    #[test]
+    fn test_from_raw() {
+        let payload = UPayloadBuilder::from_raw(vec![1, 2, 3]).build().unwrap();
+
+        assert_eq!(payload.format(), UPayloadFormat::UpayloadFormatRaw);
+        assert_eq!(payload.length, Some(3));
+        assert!(Any::try_from(payload).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+
+use prost::Message;
+
+const DEFAULT_MAX_BUFFERS: usize = 16;
+const DEFAULT_BUFFER_CAPACITY: usize = 1024;
+
+/// Statistics of a [`BufferPool`], e.g. for tuning its size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferPoolStats {
+    /// The number of buffers that have been handed out.
+    pub acquired: u64,
+    /// The number of buffers that have been handed out from the pool, rather than being allocated.
+    pub reused: u64,
+    /// The number of buffers that have been put back into the pool.
+    pub returned: u64,
+    /// The number of buffers that have been dropped, as the pool was full.
+    pub discarded: u64,
+    /// The number of buffers that are currently held by the pool.
+    pub pooled: usize,
+}
+
+struct PoolState {
+    buffers: Vec<Vec<u8>>,
+    stats: BufferPoolStats,
+}
+
+/// `BufferPool` hands out byte buffers for encoding messages, URIs and `CloudEvents`, and takes them back once they
+/// have been written, so that their allocations are reused rather than being freed.
+///
+/// Publishing at a steady rate then runs without allocating buffers, once the pool holds as many as are in use at
+/// the same time. Buffers that are given back while the pool is full are dropped, which [`BufferPool::stats`] tells
+/// about along with how many buffers have been reused.
+///
+/// The pool is optional: the encoders that take one also come in a variant that allocates a new buffer each time.
+pub struct BufferPool {
+    state: Mutex<PoolState>,
+    max_buffers: usize,
+    buffer_capacity: usize,
+}
+
+impl Default for BufferPool {
+    /// Creates a pool of up to 16 buffers of 1 KiB initial capacity.
+    fn default() -> Self {
+        BufferPool::new(DEFAULT_MAX_BUFFERS, DEFAULT_BUFFER_CAPACITY)
+    }
+}
+
+impl BufferPool {
+    /// Creates an empty pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_buffers` - The maximum number of buffers kept for reuse.
+    /// * `buffer_capacity` - The capacity that buffers are allocated with. Buffers grow beyond it as needed, and
+    ///                       keep their capacity when they are reused.
+    pub fn new(max_buffers: usize, buffer_capacity: usize) -> BufferPool {
+        BufferPool {
+            state: Mutex::new(PoolState {
+                buffers: Vec::with_capacity(max_buffers),
+                stats: BufferPoolStats::default(),
+            }),
+            max_buffers,
+            buffer_capacity,
+        }
+    }
+
+    /// Gets an empty buffer, which is put back into the pool when it is dropped.
+    pub fn acquire(&self) -> PooledBuffer<'_> {
+        let reused = {
+            let mut state = self.state.lock().unwrap();
+            let reused = state.buffers.pop();
+            state.stats.acquired += 1;
+            if reused.is_some() {
+                state.stats.reused += 1;
+                state.stats.pooled -= 1;
+            }
+            reused
+        };
+        PooledBuffer {
+            buffer: reused.unwrap_or_else(|| Vec::with_capacity(self.buffer_capacity)),
+            pool: self,
+        }
+    }
+
+    /// Encodes a protobuf message, e.g. a `UMessage`, into a buffer of the pool.
+    pub fn encode<M: Message>(&self, message: &M) -> PooledBuffer<'_> {
+        let mut buffer = self.acquire();
+        buffer.reserve(message.encoded_len());
+        // encoding into a Vec does not fail, as it grows as needed
+        let _ = message.encode(&mut *buffer);
+        buffer
+    }
+
+    /// Gets the statistics of the pool.
+    pub fn stats(&self) -> BufferPoolStats {
+        self.state.lock().unwrap().stats
+    }
+
+    fn release(&self, mut buffer: Vec<u8>) {
+        buffer.clear();
+        let mut state = self.state.lock().unwrap();
+        if state.buffers.len() < self.max_buffers {
+            state.buffers.push(buffer);
+            state.stats.returned += 1;
+            state.stats.pooled += 1;
+        } else {
+            state.stats.discarded += 1;
+        }
+    }
+}
+
+/// A buffer of a [`BufferPool`], which is put back into the pool when it is dropped.
+pub struct PooledBuffer<'a> {
+    buffer: Vec<u8>,
+    pool: &'a BufferPool,
+}
+
+impl PooledBuffer<'_> {
+    /// Takes the buffer out of the pool, e.g. for passing it on to an API that takes a `Vec<u8>`.
+    pub fn into_vec(mut self) -> Vec<u8> {
+        mem::take(&mut self.buffer)
+    }
+}
+
+impl Deref for PooledBuffer<'_> {
+    type Target = Vec<u8>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+impl DerefMut for PooledBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buffer
+    }
+}
+
+impl AsRef<[u8]> for PooledBuffer<'_> {
+    fn as_ref(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl std::fmt::Debug for PooledBuffer<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PooledBuffer").field(&self.buffer).finish()
+    }
+}
+
+impl Drop for PooledBuffer<'_> {
+    fn drop(&mut self) {
+        // a buffer that has been taken out of the pool leaves an unallocated one behind
+        if self.buffer.capacity() > 0 {
+            self.pool.release(mem::take(&mut self.buffer));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uprotocol::{UMessage, UUri};
+
+    #[test]
+    fn test_buffers_are_reused() {
+        let pool = BufferPool::new(2, 64);
+        {
+            let mut buffer = pool.acquire();
+            buffer.extend_from_slice(&[1, 2, 3]);
+        }
+        let buffer = pool.acquire();
+        assert!(buffer.is_empty());
+        assert!(buffer.capacity() >= 64);
+        drop(buffer);
+
+        assert_eq!(
+            pool.stats(),
+            BufferPoolStats {
+                acquired: 2,
+                reused: 1,
+                returned: 2,
+                discarded: 0,
+                pooled: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_buffers_are_discarded_when_pool_is_full() {
+        let pool = BufferPool::new(1, 64);
+        let first = pool.acquire();
+        let second = pool.acquire();
+        drop(first);
+        drop(second);
+
+        let stats = pool.stats();
+        assert_eq!(stats.returned, 1);
+        assert_eq!(stats.discarded, 1);
+        assert_eq!(stats.pooled, 1);
+    }
+
+    #[test]
+    fn test_buffer_taken_out_of_pool() {
+        let pool = BufferPool::new(1, 64);
+        let mut buffer = pool.acquire();
+        buffer.push(1);
+        assert_eq!(buffer.into_vec(), vec![1]);
+
+        let stats = pool.stats();
+        assert_eq!(stats.returned, 0);
+        assert_eq!(stats.pooled, 0);
+    }
+
+    #[test]
+    fn test_encode_message() {
+        let pool = BufferPool::default();
+        let message = UMessage {
+            source: Some(UUri::default()),
+            ..Default::default()
+        };
+        assert_eq!(*pool.encode(&message), message.encode_to_vec());
+    }
+}
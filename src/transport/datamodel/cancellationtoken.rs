@@ -0,0 +1,209 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, Weak};
+use std::task::{Context, Poll, Waker};
+
+use futures::future::{self, Either};
+
+#[derive(Default)]
+struct TokenState {
+    cancelled: bool,
+    next_waiter: u64,
+    waiters: HashMap<u64, Waker>,
+    children: Vec<Weak<Mutex<TokenState>>>,
+}
+
+/// `CancellationToken` lets callers abort long-running operations of the SDK, like waiting for the response to a
+/// request or forwarding messages, in a way that lets the operations clean up after themselves.
+///
+/// Dropping the future of an operation stops it as well, but leaves behind what the operation has registered
+/// elsewhere, like listeners or the correlation of requests with responses. Operations that take a token check it
+/// while waiting, and undo their registrations once it is cancelled. The token is the crate's equivalent of the
+/// `CancellationToken` of `tokio-util`, which works with any async runtime. Clones of a token share their state.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<Mutex<TokenState>>,
+}
+
+impl std::fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl CancellationToken {
+    /// Creates a token that has not been cancelled.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Creates a token that is cancelled along with this token, but can also be cancelled on its own.
+    pub fn child_token(&self) -> CancellationToken {
+        let child = CancellationToken::new();
+        let mut state = self.state.lock().unwrap();
+        if state.cancelled {
+            child.cancel();
+        } else {
+            state.children.retain(|child| child.strong_count() > 0);
+            state.children.push(Arc::downgrade(&child.state));
+        }
+        child
+    }
+
+    /// Cancels this token and its children, waking up the operations waiting for it.
+    pub fn cancel(&self) {
+        cancel(&self.state);
+    }
+
+    /// Checks if this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.lock().unwrap().cancelled
+    }
+
+    /// Waits for this token to be cancelled.
+    ///
+    /// # Returns
+    ///
+    /// A future that completes once the token is cancelled.
+    pub fn cancelled(&self) -> WaitForCancellation<'_> {
+        WaitForCancellation {
+            token: self,
+            waiter: None,
+        }
+    }
+
+    /// Runs an operation until it completes, or this token is cancelled.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation, which is dropped when the token is cancelled.
+    ///
+    /// # Returns
+    ///
+    /// The output of the operation, or `None` if the token has been cancelled first.
+    pub async fn run_until_cancelled<F: Future>(&self, operation: F) -> Option<F::Output> {
+        if self.is_cancelled() {
+            return None;
+        }
+        let cancelled = self.cancelled();
+        futures::pin_mut!(operation);
+        match future::select(operation, cancelled).await {
+            Either::Left((output, _)) => Some(output),
+            Either::Right(_) => None,
+        }
+    }
+}
+
+fn cancel(state: &Mutex<TokenState>) {
+    let children = {
+        let mut state = state.lock().unwrap();
+        if state.cancelled {
+            return;
+        }
+        state.cancelled = true;
+        state.waiters.drain().for_each(|(_, waker)| waker.wake());
+        std::mem::take(&mut state.children)
+    };
+    for child in children.iter().filter_map(Weak::upgrade) {
+        cancel(&child);
+    }
+}
+
+/// The future returned by [`CancellationToken::cancelled`].
+pub struct WaitForCancellation<'a> {
+    token: &'a CancellationToken,
+    waiter: Option<u64>,
+}
+
+impl Future for WaitForCancellation<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.token.state.lock().unwrap();
+        if state.cancelled {
+            return Poll::Ready(());
+        }
+        let waiter = match self.waiter {
+            Some(waiter) => waiter,
+            None => {
+                let waiter = state.next_waiter;
+                state.next_waiter += 1;
+                waiter
+            }
+        };
+        state.waiters.insert(waiter, cx.waker().clone());
+        drop(state);
+        self.waiter = Some(waiter);
+        Poll::Pending
+    }
+}
+
+impl Drop for WaitForCancellation<'_> {
+    fn drop(&mut self) {
+        if let Some(waiter) = self.waiter {
+            self.token.state.lock().unwrap().waiters.remove(&waiter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::FutureExt;
+
+    #[test]
+    fn test_cancel_wakes_waiting_operations() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        let cancelled = token.cancelled();
+        futures::pin_mut!(cancelled);
+        assert!((&mut cancelled).now_or_never().is_none());
+
+        clone.cancel();
+        assert!(token.is_cancelled());
+        assert!(cancelled.now_or_never().is_some());
+        assert!(token
+            .run_until_cancelled(future::ready(1))
+            .now_or_never()
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_child_token_is_cancelled_with_parent() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+        let sibling = parent.child_token();
+
+        child.cancel();
+        assert!(!parent.is_cancelled());
+        assert!(!sibling.is_cancelled());
+
+        parent.cancel();
+        assert!(sibling.is_cancelled());
+        assert!(parent.child_token().is_cancelled());
+        assert_eq!(
+            CancellationToken::new()
+                .run_until_cancelled(future::ready(1))
+                .now_or_never(),
+            Some(Some(1))
+        );
+    }
+}
@@ -0,0 +1,349 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::transport::datamodel::{emit, LogLevel, UListener};
+use crate::uprotocol::{UCode, UMessage, UStatus, Uuid};
+use crate::uuid::builder::{SystemClock, UClock};
+
+const DEFAULT_WINDOW: Duration = Duration::from_secs(10);
+const DEFAULT_CAPACITY: usize = 4096;
+
+/// `Deduplicator` detects messages that are delivered more than once, e.g. by a transport with at-least-once
+/// delivery or by devices that are connected by more than one route, by the IDs of the messages.
+///
+/// The IDs are uProtocol UUIDs, which start with the time they have been created at, so the deduplicator keeps
+/// them ordered by time and forgets the ones that are older than its window. It also remembers no more than
+/// `capacity` IDs, forgetting the oldest ones first, so that its memory is bounded even at high message rates.
+/// Deduplicators that guard against replays instead reject new IDs at capacity, see
+/// [`Deduplicator::rejecting_at_capacity`].
+///
+/// A message is only known as a duplicate as long as its ID is remembered, so the window needs to cover the time
+/// within which a transport redelivers messages. Messages whose IDs are older than the window, and messages without
+/// a uProtocol UUID as their ID, are never taken for duplicates.
+pub struct Deduplicator {
+    seen: Mutex<BTreeSet<(u64, u64)>>,
+    window: u64,
+    capacity: usize,
+    reject_at_capacity: bool,
+    clock: Box<dyn UClock>,
+}
+
+impl Default for Deduplicator {
+    /// Creates a deduplicator with a window of 10 seconds, remembering up to 4096 IDs.
+    fn default() -> Self {
+        Deduplicator::new(DEFAULT_WINDOW, DEFAULT_CAPACITY)
+    }
+}
+
+impl Deduplicator {
+    /// Creates a deduplicator that takes the current time from the platform.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - The time for which the ID of a message is remembered, counted from the time of its UUID.
+    /// * `capacity` - The maximum number of IDs to remember, at least 1.
+    pub fn new(window: Duration, capacity: usize) -> Self {
+        Self::with_clock(window, capacity, SystemClock)
+    }
+
+    /// Creates a deduplicator that takes the current time from the given clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - The time for which the ID of a message is remembered, counted from the time of its UUID.
+    /// * `capacity` - The maximum number of IDs to remember, at least 1.
+    /// * `clock` - The clock that the age of the IDs is measured with.
+    pub fn with_clock<C>(window: Duration, capacity: usize, clock: C) -> Self
+    where
+        C: UClock + 'static,
+    {
+        Deduplicator {
+            seen: Mutex::new(BTreeSet::new()),
+            window: u64::try_from(window.as_millis()).unwrap_or(u64::MAX),
+            capacity: capacity.max(1),
+            reject_at_capacity: false,
+            clock: Box::new(clock),
+        }
+    }
+
+    /// Makes the deduplicator reject new IDs while it remembers `capacity` IDs within its window, instead of
+    /// forgetting the oldest ones, so that no ID is forgotten while it could still be repeated, e.g. by a replay.
+    ///
+    /// The rejection is reported by [`Deduplicator::check_id`] only.
+    #[must_use]
+    pub fn rejecting_at_capacity(mut self) -> Self {
+        self.reject_at_capacity = true;
+        self
+    }
+
+    /// Checks if a message has been seen before, and remembers its ID if not.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the ID of the message is remembered from an earlier message, `false` if the message is new, has an
+    /// ID that is older than the window, or has no uProtocol UUID as its ID.
+    pub fn is_duplicate(&self, message: &UMessage) -> bool {
+        message
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.id.as_ref())
+            .map_or(false, |id| self.is_duplicate_id(id))
+    }
+
+    /// Checks if a message ID has been seen before, and remembers it if not.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the ID is remembered from an earlier call, `false` if the ID is new, is older than the window,
+    /// or is no uProtocol UUID.
+    pub fn is_duplicate_id(&self, id: &Uuid) -> bool {
+        self.check_id(id).unwrap_or(false)
+    }
+
+    /// Checks if a message ID has been seen before, and remembers it if not.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the ID is remembered from an earlier call, `false` if the ID is new, is older than the window,
+    /// or is no uProtocol UUID.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::ResourceExhausted` if the deduplicator is
+    /// [rejecting at capacity](Deduplicator::rejecting_at_capacity), and the ID is new while `capacity` IDs within
+    /// the window are remembered.
+    pub fn check_id(&self, id: &Uuid) -> Result<bool, UStatus> {
+        if !id.is_uprotocol_uuid() {
+            return Ok(false);
+        }
+        let Some(time) = id.get_time() else {
+            return Ok(false);
+        };
+        let horizon = self
+            .clock
+            .now()
+            .map_or(0, |now| now.saturating_sub(self.window));
+        if time < horizon {
+            return Ok(false);
+        }
+
+        let mut seen = self.seen.lock().unwrap();
+        // the time takes the highest 48 bits of the msb, so the set is ordered by time
+        while seen.first().map_or(false, |(msb, _)| (msb >> 16) < horizon) {
+            seen.pop_first();
+        }
+        if seen.contains(&(id.msb, id.lsb)) {
+            return Ok(true);
+        }
+        if seen.len() >= self.capacity {
+            if self.reject_at_capacity {
+                return Err(UStatus::fail_with_code(
+                    UCode::ResourceExhausted,
+                    "Too many message IDs within the window",
+                ));
+            }
+            seen.pop_first();
+        }
+        seen.insert((id.msb, id.lsb));
+        Ok(false)
+    }
+
+    /// Gets the number of IDs that are currently remembered.
+    pub fn len(&self) -> usize {
+        self.seen.lock().unwrap().len()
+    }
+
+    /// Checks if no IDs are currently remembered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets all IDs.
+    pub fn clear(&self) {
+        self.seen.lock().unwrap().clear();
+    }
+
+    /// Wraps a listener so that it is called with the first delivery of each message only.
+    ///
+    /// Statuses about messages that cannot be delivered are passed on to the listener as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to call with the messages that are not duplicates.
+    pub fn wrap(self: &Arc<Self>, listener: UListener) -> UListener {
+        let deduplicator = Arc::clone(self);
+        Box::new(move |result| {
+            if let Ok(message) = &result {
+                if deduplicator.is_duplicate(message) {
+                    emit(
+                        LogLevel::Debug,
+                        module_path!(),
+                        format_args!("Dropping duplicate of message {}", id_of(message)),
+                    );
+                    return;
+                }
+            }
+            listener(result);
+        })
+    }
+}
+
+fn id_of(message: &UMessage) -> String {
+    message
+        .attributes
+        .as_ref()
+        .and_then(|attributes| attributes.id.as_ref())
+        .map(Uuid::to_hyphenated_string)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::UPriority;
+    use crate::uuid::builder::{SystemRandomSource, UUIDv8Builder};
+
+    const NOW: u64 = 0x18C684468F8; // Thu, 14 Dec 2023 12:19:23 GMT
+
+    struct TestClock(AtomicU64);
+    impl UClock for TestClock {
+        fn now(&self) -> Option<u64> {
+            Some(self.0.load(Ordering::Relaxed))
+        }
+    }
+
+    struct InstantClock(u64);
+    impl UClock for InstantClock {
+        fn now(&self) -> Option<u64> {
+            Some(self.0)
+        }
+    }
+
+    // each UUID has a random part of its own, so that UUIDs for the same instant are distinct
+    fn uuid_at(instant: u64) -> Uuid {
+        UUIDv8Builder::with_sources(InstantClock(instant), &mut SystemRandomSource).build()
+    }
+
+    fn deduplicator(capacity: usize) -> (Deduplicator, Arc<TestClock>) {
+        let clock = Arc::new(TestClock(AtomicU64::new(NOW)));
+        let deduplicator =
+            Deduplicator::with_clock(Duration::from_secs(1), capacity, Arc::clone(&clock));
+        (deduplicator, clock)
+    }
+
+    fn message(id: Uuid) -> UMessage {
+        let mut attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        attributes.id = Some(id);
+        UMessage {
+            attributes: Some(attributes),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_repeated_id_is_duplicate() {
+        let (deduplicator, _) = deduplicator(16);
+        let first = uuid_at(NOW);
+        let second = uuid_at(NOW);
+
+        assert!(!deduplicator.is_duplicate(&message(first.clone())));
+        assert!(!deduplicator.is_duplicate(&message(second)));
+        assert!(deduplicator.is_duplicate(&message(first)));
+        assert_eq!(deduplicator.len(), 2);
+    }
+
+    #[test]
+    fn test_ids_older_than_window_are_forgotten() {
+        let (deduplicator, clock) = deduplicator(16);
+        let old = uuid_at(NOW - 500);
+        let recent = uuid_at(NOW);
+
+        assert!(!deduplicator.is_duplicate_id(&old));
+        assert!(!deduplicator.is_duplicate_id(&recent));
+
+        clock.0.store(NOW + 600, Ordering::Relaxed);
+        assert!(!deduplicator.is_duplicate_id(&old));
+        assert!(deduplicator.is_duplicate_id(&recent));
+        assert_eq!(deduplicator.len(), 1);
+    }
+
+    #[test]
+    fn test_capacity_forgets_oldest_ids() {
+        let (deduplicator, _) = deduplicator(2);
+        let ids = [NOW - 2, NOW - 1, NOW].map(uuid_at);
+
+        for id in &ids {
+            assert!(!deduplicator.is_duplicate_id(id));
+        }
+        assert_eq!(deduplicator.len(), 2);
+        assert!(!deduplicator.is_duplicate_id(&ids[0]));
+        assert!(deduplicator.is_duplicate_id(&ids[2]));
+    }
+
+    #[test]
+    fn test_rejecting_at_capacity_keeps_remembered_ids() {
+        let (deduplicator, clock) = deduplicator(2);
+        let deduplicator = deduplicator.rejecting_at_capacity();
+        let ids = [NOW - 2, NOW - 1, NOW].map(uuid_at);
+
+        assert_eq!(deduplicator.check_id(&ids[0]), Ok(false));
+        assert_eq!(deduplicator.check_id(&ids[1]), Ok(false));
+        assert_eq!(
+            deduplicator.check_id(&ids[2]).unwrap_err().get_code(),
+            UCode::ResourceExhausted
+        );
+        assert_eq!(deduplicator.len(), 2);
+        assert_eq!(deduplicator.check_id(&ids[0]), Ok(true));
+
+        // new IDs are remembered again once older ones have expired
+        clock.0.store(NOW + 999, Ordering::Relaxed);
+        assert_eq!(deduplicator.check_id(&ids[2]), Ok(false));
+    }
+
+    #[test]
+    fn test_messages_without_uprotocol_uuid_are_not_duplicates() {
+        let (deduplicator, _) = deduplicator(16);
+        let id = Uuid::from(uuid::Uuid::new_v4());
+
+        assert!(!deduplicator.is_duplicate_id(&id));
+        assert!(!deduplicator.is_duplicate_id(&id));
+        assert!(!deduplicator.is_duplicate(&UMessage::default()));
+        assert!(deduplicator.is_empty());
+    }
+
+    #[test]
+    fn test_wrapped_listener_drops_duplicates() {
+        let (deduplicator, _) = deduplicator(16);
+        let deduplicator = Arc::new(deduplicator);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let listener = deduplicator.wrap(Box::new(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }));
+
+        let id = uuid_at(NOW);
+        listener(Ok(message(id.clone())));
+        listener(Ok(message(id)));
+        listener(Err(UStatus::fail_with_code(UCode::Internal, "failed")));
+        listener(Err(UStatus::fail_with_code(UCode::Internal, "failed")));
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+    }
+}
@@ -0,0 +1,716 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::transport::datamodel::{emit, LogLevel, UListener, UTransport};
+use crate::uprotocol::{
+    Data, UAttributes, UCode, UEntity, UMessage, UPayload, UStatus, UUri, Uuid,
+};
+use crate::uuid::builder::{SystemClock, UClock};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_MAX_PENDING: usize = 64;
+const DEFAULT_MAX_BUFFERED: usize = 1024 * 1024;
+
+/// `Fragmenter` splits payloads that are too large for the frames of a transport into fragments.
+///
+/// `UAttributes` has no field for the number of a fragment, so each fragment's data starts with a header of
+/// [`Fragmenter::HEADER_LENGTH`] bytes: the index of the fragment and the number of fragments, as 16-bit big-endian
+/// integers. All fragments of a message are sent with the attributes of the message, whose ID identifies the
+/// fragments that belong together to the [`Reassembler`]. Payloads that fit into a single frame are sent as a single
+/// fragment, so that the receiving side can tell fragmented from unfragmented messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fragmenter {
+    max_fragment_size: usize,
+}
+
+impl Fragmenter {
+    /// The length of the header at the start of each fragment's data.
+    pub const HEADER_LENGTH: usize = 4;
+
+    /// Creates a fragmenter.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_fragment_size` - The maximum size of the data of a fragment in bytes, including its header. Sizes that
+    ///                         leave no room for data after the header are raised to `HEADER_LENGTH + 1`.
+    pub fn new(max_fragment_size: usize) -> Fragmenter {
+        Fragmenter {
+            max_fragment_size: max_fragment_size.max(Self::HEADER_LENGTH + 1),
+        }
+    }
+
+    /// Gets the maximum size of the data of a fragment in bytes, including its header.
+    pub fn max_fragment_size(&self) -> usize {
+        self.max_fragment_size
+    }
+
+    /// Splits a payload into fragments.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to split.
+    ///
+    /// # Returns
+    ///
+    /// The fragments, in the order of their indices, with the format of the payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code [`UCode::InvalidArgument`] if the payload refers to its data instead of holding
+    /// it, or with code [`UCode::ResourceExhausted`] if it needs more than `u16::MAX` fragments.
+    pub fn fragment(&self, payload: &UPayload) -> Result<Vec<UPayload>, UStatus> {
+        let data = match &payload.data {
+            Some(Data::Value(data)) => data.as_slice(),
+            Some(Data::Reference(_)) => {
+                return Err(UStatus::fail_with_code(
+                    UCode::InvalidArgument,
+                    "Payload with referenced data cannot be fragmented",
+                ))
+            }
+            None => &[],
+        };
+        let chunk_size = self.max_fragment_size - Self::HEADER_LENGTH;
+        let count = (data.len() / chunk_size + usize::from(data.len() % chunk_size != 0)).max(1);
+        let Ok(count) = u16::try_from(count) else {
+            return Err(UStatus::fail_with_code(
+                UCode::ResourceExhausted,
+                &format!(
+                    "Payload size of {} bytes needs more than {} fragments",
+                    data.len(),
+                    u16::MAX
+                ),
+            ));
+        };
+
+        let fragments = if data.is_empty() {
+            vec![build_fragment(payload.format, 0, count, &[])]
+        } else {
+            data.chunks(chunk_size)
+                .zip(0..count)
+                .map(|(chunk, index)| build_fragment(payload.format, index, count, chunk))
+                .collect()
+        };
+        Ok(fragments)
+    }
+}
+
+fn build_fragment(format: i32, index: u16, count: u16, chunk: &[u8]) -> UPayload {
+    let mut data = Vec::with_capacity(Fragmenter::HEADER_LENGTH + chunk.len());
+    data.extend_from_slice(&index.to_be_bytes());
+    data.extend_from_slice(&count.to_be_bytes());
+    data.extend_from_slice(chunk);
+    UPayload {
+        length: i32::try_from(data.len()).ok(),
+        format,
+        data: Some(Data::Value(data)),
+    }
+}
+
+// the messages that fragments are kept for, and the number of bytes of all of their fragments
+#[derive(Default)]
+struct Pending {
+    messages: HashMap<(u64, u64), PartialMessage>,
+    buffered: usize,
+}
+
+impl Pending {
+    fn remove(&mut self, id: &(u64, u64)) -> Option<PartialMessage> {
+        let partial = self.messages.remove(id)?;
+        self.buffered -= partial.size;
+        Some(partial)
+    }
+}
+
+// the fragments of a message that have been received so far
+struct PartialMessage {
+    deadline: u64,
+    fragments: Vec<Option<Vec<u8>>>,
+    missing: usize,
+    // the number of bytes of the fragments received so far
+    size: usize,
+    // the first fragment received, whose source and attributes the reassembled message gets
+    message: UMessage,
+}
+
+/// `Reassembler` puts the fragments created by a [`Fragmenter`] back together into the messages they belong to.
+///
+/// Fragments may arrive in any order, and duplicates of fragments are ignored. The fragments of a message are kept
+/// until all of them have been received, or until the timeout has passed since the first one of them has been
+/// received, so that the fragments of messages that are lost in part do not pile up.
+///
+/// The reassembler also limits the number of messages that it keeps fragments for, and the number of bytes of the
+/// fragments that it keeps in total, to 64 messages and 1 MiB by default. Fragments beyond these limits are rejected,
+/// so that a sender cannot exhaust the memory of the receiver by starting messages that it never completes.
+pub struct Reassembler {
+    pending: Mutex<Pending>,
+    timeout: u64,
+    max_pending: usize,
+    max_buffered: usize,
+    clock: Box<dyn UClock>,
+}
+
+impl Default for Reassembler {
+    /// Creates a reassembler that keeps the fragments of a message for 5 seconds.
+    fn default() -> Self {
+        Reassembler::new(DEFAULT_TIMEOUT)
+    }
+}
+
+impl Reassembler {
+    /// Creates a reassembler that takes the current time from the platform.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The time for which the fragments of an incomplete message are kept.
+    pub fn new(timeout: Duration) -> Self {
+        Self::with_clock(timeout, SystemClock)
+    }
+
+    /// Creates a reassembler that takes the current time from the given clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The time for which the fragments of an incomplete message are kept.
+    /// * `clock` - The clock that the age of the fragments is measured with.
+    pub fn with_clock<C>(timeout: Duration, clock: C) -> Self
+    where
+        C: UClock + 'static,
+    {
+        Reassembler {
+            pending: Mutex::new(Pending::default()),
+            timeout: u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX),
+            max_pending: DEFAULT_MAX_PENDING,
+            max_buffered: DEFAULT_MAX_BUFFERED,
+            clock: Box::new(clock),
+        }
+    }
+
+    /// Sets the limits beyond which fragments are rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_pending` - The maximum number of incomplete messages that fragments are kept for.
+    /// * `max_buffered` - The maximum number of bytes of the fragments that are kept, in total.
+    #[must_use]
+    pub fn with_limits(mut self, max_pending: usize, max_buffered: usize) -> Self {
+        self.max_pending = max_pending;
+        self.max_buffered = max_buffered;
+        self
+    }
+
+    /// Adds a fragment to the message it belongs to.
+    ///
+    /// # Arguments
+    ///
+    /// * `fragment` - The message carrying the fragment, as created by a [`Fragmenter`].
+    ///
+    /// # Returns
+    ///
+    /// The reassembled message if this fragment has completed it, or `None` if fragments are still missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code [`UCode::InvalidArgument`] if the message has no ID, or its data has no valid
+    /// fragment header, or the header contradicts the headers of earlier fragments of the message. In the latter
+    /// case, the earlier fragments are dropped. Returns a `UStatus` with code [`UCode::ResourceExhausted`] if the
+    /// fragment starts a message while fragments are kept for as many messages as allowed, or would exceed the
+    /// number of bytes allowed to be kept.
+    pub fn reassemble(&self, fragment: UMessage) -> Result<Option<UMessage>, UStatus> {
+        let Some(id) = fragment
+            .attributes
+            .as_ref()
+            .and_then(|attributes| attributes.id.as_ref())
+            .map(|id| (id.msb, id.lsb))
+        else {
+            return Err(UStatus::fail_with_code(
+                UCode::InvalidArgument,
+                "Fragment has no message ID",
+            ));
+        };
+        let (index, count, mut fragment) = split_header(fragment)?;
+        if count == 1 {
+            return Ok(Some(fragment));
+        }
+
+        let now = self.clock.now().unwrap_or(0);
+        let mut pending = self.pending.lock().unwrap();
+        if !pending.messages.contains_key(&id) && pending.messages.len() >= self.max_pending {
+            return Err(UStatus::fail_with_code(
+                UCode::ResourceExhausted,
+                &format!(
+                    "Fragments are kept for {} messages already",
+                    self.max_pending
+                ),
+            ));
+        }
+        let Pending { messages, buffered } = &mut *pending;
+        let partial = messages.entry(id).or_insert_with(|| PartialMessage {
+            deadline: now.saturating_add(self.timeout),
+            fragments: vec![None; count],
+            missing: count,
+            size: 0,
+            message: UMessage {
+                payload: None,
+                ..fragment.clone()
+            },
+        });
+        if partial.fragments.len() != count {
+            pending.remove(&id);
+            return Err(UStatus::fail_with_code(
+                UCode::InvalidArgument,
+                "Fragment count differs from earlier fragments of the message",
+            ));
+        }
+        if partial.fragments[index].is_none() {
+            let data = take_data(&mut fragment);
+            if buffered.saturating_add(data.len()) > self.max_buffered {
+                // a message that has been started by this fragment is not kept without any fragments
+                if partial.missing == count {
+                    messages.remove(&id);
+                }
+                return Err(UStatus::fail_with_code(
+                    UCode::ResourceExhausted,
+                    &format!("Fragments would exceed {} bytes kept", self.max_buffered),
+                ));
+            }
+            *buffered += data.len();
+            partial.size += data.len();
+            partial.fragments[index] = Some(data);
+            partial.missing -= 1;
+        }
+        if partial.missing > 0 {
+            return Ok(None);
+        }
+
+        let Some(partial) = pending.remove(&id) else {
+            return Ok(None);
+        };
+        let data: Vec<u8> = partial.fragments.into_iter().flatten().flatten().collect();
+        let format = fragment.payload.map_or(0, |payload| payload.format);
+        Ok(Some(UMessage {
+            payload: Some(UPayload {
+                length: i32::try_from(data.len()).ok(),
+                format,
+                data: Some(Data::Value(data)),
+            }),
+            ..partial.message
+        }))
+    }
+
+    /// Drops the fragments of the messages that have not been completed within the timeout.
+    ///
+    /// # Returns
+    ///
+    /// The IDs of the messages whose fragments have been dropped.
+    pub fn collect_garbage(&self) -> Vec<Uuid> {
+        let now = self.clock.now().unwrap_or(0);
+        let mut expired = Vec::new();
+        let mut pending = self.pending.lock().unwrap();
+        let Pending { messages, buffered } = &mut *pending;
+        messages.retain(|(msb, lsb), partial| {
+            if partial.deadline > now {
+                return true;
+            }
+            *buffered -= partial.size;
+            expired.push(Uuid {
+                msb: *msb,
+                lsb: *lsb,
+            });
+            false
+        });
+        expired
+    }
+
+    /// Gets the number of messages that fragments are kept for.
+    pub fn pending(&self) -> usize {
+        self.pending.lock().unwrap().messages.len()
+    }
+
+    /// Gets the number of bytes of the fragments that are kept, in total.
+    pub fn buffered(&self) -> usize {
+        self.pending.lock().unwrap().buffered
+    }
+
+    /// Wraps a listener so that it is called with reassembled messages instead of fragments.
+    ///
+    /// Incomplete messages are collected as garbage whenever a fragment arrives. The listener is called with a
+    /// `UStatus` with code [`UCode::DataLoss`] for each of them, and for fragments that cannot be reassembled.
+    /// Statuses about messages that cannot be delivered are passed on to the listener as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to call with the reassembled messages.
+    pub fn wrap(self: &Arc<Self>, listener: UListener) -> UListener {
+        let reassembler = Arc::clone(self);
+        Box::new(move |result| {
+            let Ok(fragment) = result else {
+                listener(result);
+                return;
+            };
+            for id in reassembler.collect_garbage() {
+                let status = UStatus::fail_with_code(
+                    UCode::DataLoss,
+                    &format!(
+                        "Fragments of message {} have not been received in time",
+                        id.to_hyphenated_string()
+                    ),
+                );
+                emit(LogLevel::Warn, module_path!(), format_args!("{status}"));
+                listener(Err(status));
+            }
+            match reassembler.reassemble(fragment) {
+                Ok(Some(message)) => listener(Ok(message)),
+                Ok(None) => {}
+                Err(status) => {
+                    emit(
+                        LogLevel::Warn,
+                        module_path!(),
+                        format_args!("Dropping fragment: {status}"),
+                    );
+                    listener(Err(UStatus::fail_with_code(
+                        UCode::DataLoss,
+                        status.message.as_deref().unwrap_or_default(),
+                    )));
+                }
+            }
+        })
+    }
+}
+
+// Removes the header from the data of a fragment, returning the fragment's index and the number of fragments.
+fn split_header(mut fragment: UMessage) -> Result<(usize, usize, UMessage), UStatus> {
+    let invalid =
+        || UStatus::fail_with_code(UCode::InvalidArgument, "Fragment has no valid header");
+    let Some(UPayload {
+        data: Some(Data::Value(data)),
+        length,
+        ..
+    }) = fragment.payload.as_mut()
+    else {
+        return Err(invalid());
+    };
+    if data.len() < Fragmenter::HEADER_LENGTH {
+        return Err(invalid());
+    }
+    let index = usize::from(u16::from_be_bytes([data[0], data[1]]));
+    let count = usize::from(u16::from_be_bytes([data[2], data[3]]));
+    if index >= count {
+        return Err(invalid());
+    }
+    data.drain(..Fragmenter::HEADER_LENGTH);
+    *length = i32::try_from(data.len()).ok();
+    Ok((index, count, fragment))
+}
+
+fn take_data(fragment: &mut UMessage) -> Vec<u8> {
+    match fragment
+        .payload
+        .as_mut()
+        .and_then(|payload| payload.data.take())
+    {
+        Some(Data::Value(data)) => data,
+        _ => Vec::new(),
+    }
+}
+
+/// `FragmentingTransport` is a decorator for transports with small maximum frame sizes, which sends the payloads of
+/// messages as fragments no larger than a frame, and reassembles the fragments that its listeners receive.
+///
+/// Both sides of a topic need to use the decorator, as each message is sent with a fragment header, see
+/// [`Fragmenter`].
+pub struct FragmentingTransport<T: UTransport> {
+    transport: T,
+    fragmenter: Fragmenter,
+    reassembler: Arc<Reassembler>,
+}
+
+impl<T: UTransport> FragmentingTransport<T> {
+    /// Creates a decorator that keeps the fragments of incomplete messages for 5 seconds.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to send and receive the fragments with.
+    /// * `max_fragment_size` - The maximum size of the payload data that the transport can send in a frame.
+    pub fn new(transport: T, max_fragment_size: usize) -> Self {
+        Self::with_reassembler(transport, max_fragment_size, Reassembler::default())
+    }
+
+    /// Creates a decorator that reassembles fragments with the given reassembler.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to send and receive the fragments with.
+    /// * `max_fragment_size` - The maximum size of the payload data that the transport can send in a frame.
+    /// * `reassembler` - The reassembler to reassemble the received fragments with.
+    pub fn with_reassembler(
+        transport: T,
+        max_fragment_size: usize,
+        reassembler: Reassembler,
+    ) -> Self {
+        FragmentingTransport {
+            transport,
+            fragmenter: Fragmenter::new(max_fragment_size),
+            reassembler: Arc::new(reassembler),
+        }
+    }
+
+    /// Gets the transport that the fragments are sent and received with.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Gets the reassembler that the received fragments are reassembled with.
+    pub fn reassembler(&self) -> &Reassembler {
+        &self.reassembler
+    }
+}
+
+#[async_trait]
+impl<T: UTransport + Send + Sync> UTransport for FragmentingTransport<T> {
+    async fn authenticate(&self, entity: UEntity) -> Result<(), UStatus> {
+        self.transport.authenticate(entity).await
+    }
+
+    /// Sends the fragments of a payload one after the other, stopping at the first one that fails to be sent.
+    async fn send(
+        &self,
+        topic: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+    ) -> Result<(), UStatus> {
+        for fragment in self.fragmenter.fragment(&payload)? {
+            self.transport
+                .send(topic.clone(), fragment, attributes.clone())
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn register_listener(&self, topic: UUri, listener: UListener) -> Result<String, UStatus> {
+        self.transport
+            .register_listener(topic, self.reassembler.wrap(listener))
+            .await
+    }
+
+    async fn unregister_listener(&self, topic: UUri, listener: &str) -> Result<(), UStatus> {
+        self.transport.unregister_listener(topic, listener).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::{UPayloadFormat, UPriority};
+
+    struct TestClock(AtomicU64);
+    impl UClock for TestClock {
+        fn now(&self) -> Option<u64> {
+            Some(self.0.load(Ordering::Relaxed))
+        }
+    }
+
+    #[derive(Default)]
+    struct LoopbackTransport {
+        listeners: Mutex<Vec<UListener>>,
+        sent: AtomicU64,
+    }
+
+    #[async_trait]
+    impl UTransport for LoopbackTransport {
+        async fn authenticate(&self, _entity: UEntity) -> Result<(), UStatus> {
+            Ok(())
+        }
+
+        async fn send(
+            &self,
+            topic: UUri,
+            payload: UPayload,
+            attributes: UAttributes,
+        ) -> Result<(), UStatus> {
+            self.sent.fetch_add(1, Ordering::Relaxed);
+            let message = UMessage {
+                source: Some(topic),
+                attributes: Some(attributes),
+                payload: Some(payload),
+            };
+            for listener in self.listeners.lock().unwrap().iter() {
+                listener(Ok(message.clone()));
+            }
+            Ok(())
+        }
+
+        async fn register_listener(
+            &self,
+            _topic: UUri,
+            listener: UListener,
+        ) -> Result<String, UStatus> {
+            self.listeners.lock().unwrap().push(listener);
+            Ok("listener".to_string())
+        }
+
+        async fn unregister_listener(&self, _topic: UUri, _listener: &str) -> Result<(), UStatus> {
+            self.listeners.lock().unwrap().clear();
+            Ok(())
+        }
+    }
+
+    fn build_payload_for_test(size: usize) -> UPayload {
+        let data: Vec<u8> = (0..size).map(|i| u8::try_from(i % 251).unwrap()).collect();
+        UPayload {
+            length: i32::try_from(size).ok(),
+            format: UPayloadFormat::UpayloadFormatRaw.into(),
+            data: Some(Data::Value(data)),
+        }
+    }
+
+    fn build_fragments_for_test(size: usize, fragmenter: Fragmenter) -> Vec<UMessage> {
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        fragmenter
+            .fragment(&build_payload_for_test(size))
+            .unwrap()
+            .into_iter()
+            .map(|payload| UMessage {
+                source: Some(UUri::default()),
+                attributes: Some(attributes.clone()),
+                payload: Some(payload),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fragment_splits_payload() {
+        let fragmenter = Fragmenter::new(14);
+        let fragments = fragmenter.fragment(&build_payload_for_test(25)).unwrap();
+        assert_eq!(fragments.len(), 3);
+        assert!(fragments.iter().all(|fragment| fragment.data_len() <= 14));
+        assert_eq!(fragments[2].data_len(), Fragmenter::HEADER_LENGTH + 5);
+
+        assert_eq!(fragmenter.fragment(&UPayload::default()).unwrap().len(), 1);
+        let referenced = UPayload {
+            data: Some(Data::Reference(0x1000)),
+            ..Default::default()
+        };
+        assert_eq!(
+            fragmenter.fragment(&referenced).unwrap_err().get_code(),
+            UCode::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_reassemble_in_any_order() {
+        let reassembler = Reassembler::new(Duration::from_secs(1));
+        let mut fragments = build_fragments_for_test(100, Fragmenter::new(20));
+        fragments.reverse();
+        let duplicate = fragments[0].clone();
+        let last = fragments.pop().unwrap();
+
+        for fragment in fragments {
+            assert!(reassembler.reassemble(fragment).unwrap().is_none());
+        }
+        assert!(reassembler.reassemble(duplicate).unwrap().is_none());
+        assert_eq!(reassembler.pending(), 1);
+
+        let message = reassembler.reassemble(last).unwrap().unwrap();
+        assert_eq!(message.payload, Some(build_payload_for_test(100)));
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn test_incomplete_messages_expire() {
+        let clock = Arc::new(TestClock(AtomicU64::new(1000)));
+        let reassembler = Reassembler::with_clock(Duration::from_millis(500), Arc::clone(&clock));
+        let fragments = build_fragments_for_test(100, Fragmenter::new(20));
+        reassembler.reassemble(fragments[0].clone()).unwrap();
+
+        clock.0.store(1499, Ordering::Relaxed);
+        assert!(reassembler.collect_garbage().is_empty());
+        clock.0.store(1500, Ordering::Relaxed);
+        let expired = reassembler.collect_garbage();
+        assert_eq!(
+            expired.first(),
+            fragments[0].attributes.as_ref().unwrap().id.as_ref()
+        );
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn test_fragments_beyond_limits_are_rejected() {
+        let reassembler = Reassembler::new(Duration::from_secs(1)).with_limits(2, 40);
+        let fragmenter = Fragmenter::new(20);
+        let first = build_fragments_for_test(100, fragmenter);
+        let second = build_fragments_for_test(100, fragmenter);
+        let third = build_fragments_for_test(100, fragmenter);
+
+        assert!(reassembler.reassemble(first[0].clone()).unwrap().is_none());
+        assert!(reassembler.reassemble(second[0].clone()).unwrap().is_none());
+        let status = reassembler.reassemble(third[0].clone()).unwrap_err();
+        assert_eq!(status.get_code(), UCode::ResourceExhausted);
+        assert_eq!(reassembler.pending(), 2);
+        assert_eq!(reassembler.buffered(), 32);
+
+        let status = reassembler.reassemble(first[1].clone()).unwrap_err();
+        assert_eq!(status.get_code(), UCode::ResourceExhausted);
+        assert_eq!(reassembler.buffered(), 32);
+
+        // buffered bytes are released when the messages expire
+        let clock = Arc::new(TestClock(AtomicU64::new(1000)));
+        let reassembler = Reassembler::with_clock(Duration::from_millis(500), Arc::clone(&clock))
+            .with_limits(1, 40);
+        assert!(reassembler.reassemble(first[0].clone()).unwrap().is_none());
+        clock.0.store(1500, Ordering::Relaxed);
+        assert_eq!(reassembler.collect_garbage().len(), 1);
+        assert_eq!(reassembler.buffered(), 0);
+        assert!(reassembler.reassemble(second[0].clone()).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transport_sends_fragments_and_delivers_messages() {
+        let transport = FragmentingTransport::new(LoopbackTransport::default(), 64);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        transport
+            .register_listener(
+                UUri::default(),
+                Box::new(move |result| sink.lock().unwrap().push(result)),
+            )
+            .await
+            .unwrap();
+
+        transport
+            .send(
+                UUri::default(),
+                build_payload_for_test(1000),
+                UAttributesBuilder::publish(UPriority::UpriorityCs1).build(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(transport.transport().sent.load(Ordering::Relaxed), 17);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(
+            received[0].as_ref().unwrap().payload,
+            Some(build_payload_for_test(1000))
+        );
+    }
+}
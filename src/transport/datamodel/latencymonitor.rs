@@ -0,0 +1,300 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use crate::transport::datamodel::{UListener, UMetrics};
+use crate::uprotocol::UMessage;
+use crate::uuid::builder::{SystemClock, UClock};
+
+const DEFAULT_MAX_SAMPLES: usize = 1024;
+
+/// The name of the metric with the number of latencies that a [`LatencyMonitor`] report is made of.
+pub const LATENCY_COUNT_METRIC: &str = "uprotocol.latency.count";
+/// The name of the metric with the median latency, in milliseconds.
+pub const LATENCY_P50_METRIC: &str = "uprotocol.latency.p50";
+/// The name of the metric with the 90th percentile of the latencies, in milliseconds.
+pub const LATENCY_P90_METRIC: &str = "uprotocol.latency.p90";
+/// The name of the metric with the 99th percentile of the latencies, in milliseconds.
+pub const LATENCY_P99_METRIC: &str = "uprotocol.latency.p99";
+/// The name of the metric with the highest latency, in milliseconds.
+pub const LATENCY_MAX_METRIC: &str = "uprotocol.latency.max";
+
+/// Percentiles of the latencies measured by a [`LatencyMonitor`], in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LatencyPercentiles {
+    /// The number of latencies that the percentiles are computed from.
+    pub count: usize,
+    /// The median latency.
+    pub p50: u64,
+    /// The latency that 90% of the messages have been received within.
+    pub p90: u64,
+    /// The latency that 99% of the messages have been received within.
+    pub p99: u64,
+    /// The highest latency.
+    pub max: u64,
+}
+
+/// `LatencyMonitor` measures the time it takes messages to get from their senders to the receiving uEntity.
+///
+/// The latency of a message is the time between the timestamp of its ID, which the sender has created it with,
+/// and the time it is received at, so measuring it does not need anything to be added to the payload. The clocks
+/// of the sending and the receiving devices need to be synchronized, though: a message that seems to have been
+/// sent in the future is taken to have a latency of 0. Messages without a uProtocol UUID are not measured.
+///
+/// The monitor keeps the latest latencies, up to its maximum number of samples, and [`LatencyMonitor::report`]
+/// passes their percentiles on to a [`UMetrics`] sink, e.g. periodically.
+pub struct LatencyMonitor {
+    samples: Mutex<VecDeque<u64>>,
+    max_samples: usize,
+    metrics: Box<dyn UMetrics>,
+    clock: Box<dyn UClock>,
+}
+
+impl LatencyMonitor {
+    /// Creates a monitor that keeps up to 1024 samples and takes the current time from the platform.
+    ///
+    /// # Arguments
+    ///
+    /// * `metrics` - The sink that the percentiles of the latencies are reported to.
+    pub fn new<M>(metrics: M) -> Self
+    where
+        M: UMetrics + 'static,
+    {
+        Self::with_clock(metrics, DEFAULT_MAX_SAMPLES, SystemClock)
+    }
+
+    /// Creates a monitor that takes the current time from the given clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `metrics` - The sink that the percentiles of the latencies are reported to.
+    /// * `max_samples` - The maximum number of latencies to keep between reports, at least 1.
+    /// * `clock` - The clock that the time of receiving messages is taken from.
+    pub fn with_clock<M, C>(metrics: M, max_samples: usize, clock: C) -> Self
+    where
+        M: UMetrics + 'static,
+        C: UClock + 'static,
+    {
+        let max_samples = max_samples.max(1);
+        LatencyMonitor {
+            samples: Mutex::new(VecDeque::with_capacity(max_samples)),
+            max_samples,
+            metrics: Box::new(metrics),
+            clock: Box::new(clock),
+        }
+    }
+
+    /// Measures the latency of a message that has just been received.
+    ///
+    /// # Returns
+    ///
+    /// The latency in milliseconds, or `None` if the message has no uProtocol UUID or the clock cannot tell the
+    /// current time.
+    pub fn measure(&self, message: &UMessage) -> Option<u64> {
+        let id = message.attributes.as_ref()?.id.as_ref()?;
+        if !id.is_uprotocol_uuid() {
+            return None;
+        }
+        let latency = self.clock.now()?.saturating_sub(id.get_time()?);
+
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == self.max_samples {
+            samples.pop_front();
+        }
+        samples.push_back(latency);
+        Some(latency)
+    }
+
+    /// Gets the percentiles of the latencies measured since the last report.
+    ///
+    /// # Returns
+    ///
+    /// The percentiles, or `None` if no latencies have been measured.
+    pub fn percentiles(&self) -> Option<LatencyPercentiles> {
+        let samples = self.samples.lock().unwrap();
+        percentiles(samples.iter().copied().collect())
+    }
+
+    /// Reports the percentiles of the latencies measured since the last report to the metrics sink, and starts
+    /// over with no latencies.
+    ///
+    /// Nothing is reported if no latencies have been measured.
+    ///
+    /// # Returns
+    ///
+    /// The reported percentiles.
+    pub fn report(&self) -> Option<LatencyPercentiles> {
+        let samples = self.samples.lock().unwrap().drain(..).collect();
+        let percentiles = percentiles(samples)?;
+        self.metrics
+            .record(LATENCY_COUNT_METRIC, percentiles.count as u64);
+        self.metrics.record(LATENCY_P50_METRIC, percentiles.p50);
+        self.metrics.record(LATENCY_P90_METRIC, percentiles.p90);
+        self.metrics.record(LATENCY_P99_METRIC, percentiles.p99);
+        self.metrics.record(LATENCY_MAX_METRIC, percentiles.max);
+        Some(percentiles)
+    }
+
+    /// Wraps a listener so that the latency of each message is measured before the listener is called with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to call with the received messages.
+    pub fn wrap(self: &Arc<Self>, listener: UListener) -> UListener {
+        let monitor = Arc::clone(self);
+        Box::new(move |result| {
+            if let Ok(message) = &result {
+                monitor.measure(message);
+            }
+            listener(result);
+        })
+    }
+}
+
+// Computes the percentiles with the nearest-rank method, i.e. as the smallest latency that at least the given
+// share of the latencies is not greater than.
+fn percentiles(mut samples: Vec<u64>) -> Option<LatencyPercentiles> {
+    let max = *samples.iter().max()?;
+    samples.sort_unstable();
+    let rank = |percent: usize| samples[((samples.len() * percent + 99) / 100).max(1) - 1];
+    Some(LatencyPercentiles {
+        count: samples.len(),
+        p50: rank(50),
+        p90: rank(90),
+        p99: rank(99),
+        max,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::{UPriority, Uuid};
+    use crate::uuid::builder::{SystemRandomSource, UUIDv8Builder};
+
+    const NOW: u64 = 0x18C684468F8; // Thu, 14 Dec 2023 12:19:23 GMT
+
+    struct FixedClock(u64);
+    impl UClock for FixedClock {
+        fn now(&self) -> Option<u64> {
+            Some(self.0)
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordedMetrics(Mutex<Vec<(String, u64)>>);
+    impl UMetrics for RecordedMetrics {
+        fn record(&self, name: &str, value: u64) {
+            self.0.lock().unwrap().push((name.to_string(), value));
+        }
+    }
+
+    fn message(sent: u64) -> UMessage {
+        let mut attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        attributes.id =
+            Some(UUIDv8Builder::with_sources(FixedClock(sent), &mut SystemRandomSource).build());
+        UMessage {
+            attributes: Some(attributes),
+            ..Default::default()
+        }
+    }
+
+    fn monitor(max_samples: usize) -> (LatencyMonitor, Arc<RecordedMetrics>) {
+        let metrics = Arc::new(RecordedMetrics::default());
+        let monitor =
+            LatencyMonitor::with_clock(Arc::clone(&metrics), max_samples, FixedClock(NOW));
+        (monitor, metrics)
+    }
+
+    #[test]
+    fn test_latency_is_taken_from_uuid() {
+        let (monitor, _) = monitor(16);
+
+        assert_eq!(monitor.measure(&message(NOW - 25)), Some(25));
+        // a sender whose clock is ahead of the receiver's
+        assert_eq!(monitor.measure(&message(NOW + 3)), Some(0));
+        assert_eq!(monitor.measure(&UMessage::default()), None);
+
+        let mut other = message(NOW);
+        if let Some(attributes) = other.attributes.as_mut() {
+            attributes.id = Some(Uuid::from(uuid::Uuid::new_v4()));
+        }
+        assert_eq!(monitor.measure(&other), None);
+        assert_eq!(monitor.percentiles().map(|p| p.count), Some(2));
+    }
+
+    #[test]
+    fn test_report_records_percentiles() {
+        let (monitor, metrics) = monitor(1000);
+        for latency in 1..=100 {
+            monitor.measure(&message(NOW - latency));
+        }
+
+        let expected = LatencyPercentiles {
+            count: 100,
+            p50: 50,
+            p90: 90,
+            p99: 99,
+            max: 100,
+        };
+        assert_eq!(monitor.report(), Some(expected));
+        assert_eq!(
+            *metrics.0.lock().unwrap(),
+            vec![
+                (LATENCY_COUNT_METRIC.to_string(), 100),
+                (LATENCY_P50_METRIC.to_string(), 50),
+                (LATENCY_P90_METRIC.to_string(), 90),
+                (LATENCY_P99_METRIC.to_string(), 99),
+                (LATENCY_MAX_METRIC.to_string(), 100),
+            ]
+        );
+
+        // the report starts over
+        assert_eq!(monitor.percentiles(), None);
+        assert_eq!(monitor.report(), None);
+        assert_eq!(metrics.0.lock().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_oldest_samples_are_dropped() {
+        let (monitor, _) = monitor(2);
+        for latency in [100, 1, 2] {
+            monitor.measure(&message(NOW - latency));
+        }
+
+        let percentiles = monitor.percentiles().unwrap();
+        assert_eq!(percentiles.count, 2);
+        assert_eq!(percentiles.max, 2);
+    }
+
+    #[test]
+    fn test_wrapped_listener_measures_messages() {
+        let (monitor, _) = monitor(16);
+        let monitor = Arc::new(monitor);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let listener = monitor.wrap(Box::new(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }));
+
+        listener(Ok(message(NOW - 7)));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(monitor.percentiles().map(|p| p.p50), Some(7));
+    }
+}
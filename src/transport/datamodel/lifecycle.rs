@@ -0,0 +1,252 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::task::{Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::future::{self, Either};
+
+use crate::uprotocol::{UCode, UStatus};
+
+/// `Lifecycle` is implemented by the components that have work in flight, or listeners registered, on behalf of a
+/// uEntity, so that the uEntity can stop them without losing messages, which dropping them would.
+#[async_trait]
+pub trait Lifecycle {
+    /// Shuts the component down.
+    ///
+    /// The component stops accepting new work, lets the work in flight complete, e.g. by flushing its outbound
+    /// queues, and unregisters its listeners. Work that has not completed within the given time fails with a
+    /// `UStatus` with code `UCode::Cancelled`. Once shut down, the component fails new work with a `UStatus` with
+    /// code `UCode::Unavailable`.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - The time that the work in flight is given to complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::DeadlineExceeded` if work had to be cancelled, or the failure to
+    /// unregister a listener.
+    async fn shutdown(&self, deadline: Duration) -> Result<(), UStatus>;
+}
+
+#[derive(Default)]
+struct DrainState {
+    closed: bool,
+    cancelled: bool,
+    next_operation: u64,
+    // the wakers of the operations in flight, which are woken when they are cancelled
+    operations: HashMap<u64, Option<Waker>>,
+    // the wakers of the callers of shutdown, which are woken when no operations are in flight anymore
+    shutdowns: Vec<Waker>,
+}
+
+/// `Drain` keeps track of the operations in flight in a component, for implementing [`Lifecycle::shutdown`].
+///
+/// Operations are run with [`Drain::run`], which fails them once the drain is shut down, and cancels them if they
+/// have not completed by the deadline passed to [`Drain::shutdown`]. The drain does not depend on a particular
+/// async runtime: the deadline is kept by a thread that is started when shutting down has to wait.
+#[derive(Default)]
+pub struct Drain {
+    state: Mutex<DrainState>,
+}
+
+impl Drain {
+    /// Creates a drain without operations in flight.
+    pub fn new() -> Drain {
+        Drain::default()
+    }
+
+    /// Checks if [`Drain::shutdown`] has been called.
+    pub fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+
+    /// Gets the number of operations in flight.
+    pub fn in_flight(&self) -> usize {
+        self.state.lock().unwrap().operations.len()
+    }
+
+    /// Runs an operation, unless the drain has been shut down.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation.
+    ///
+    /// # Returns
+    ///
+    /// The result of the operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::Unavailable` if the drain has been shut down before the operation has
+    /// started, or with code `UCode::Cancelled` if the operation has not completed by the deadline of shutting
+    /// down.
+    pub async fn run<F, T, E>(&self, operation: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: From<UStatus>,
+    {
+        let id = {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                return Err(UStatus::fail_with_code(
+                    UCode::Unavailable,
+                    "Component has been shut down",
+                )
+                .into());
+            }
+            let id = state.next_operation;
+            state.next_operation += 1;
+            state.operations.insert(id, None);
+            id
+        };
+        let _guard = InFlight { drain: self, id };
+
+        let cancelled = future::poll_fn(|cx| {
+            let mut state = self.state.lock().unwrap();
+            if state.cancelled {
+                return Poll::Ready(());
+            }
+            state.operations.insert(id, Some(cx.waker().clone()));
+            Poll::Pending
+        });
+        futures::pin_mut!(operation, cancelled);
+        match future::select(operation, cancelled).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => Err(UStatus::fail_with_code(
+                UCode::Cancelled,
+                "Operation has been cancelled by shutting down",
+            )
+            .into()),
+        }
+    }
+
+    /// Stops accepting operations, and waits for the operations in flight to complete.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - The time that the operations in flight are given to complete, after which they are cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::DeadlineExceeded` if operations had to be cancelled.
+    pub async fn shutdown(&self, deadline: Duration) -> Result<(), UStatus> {
+        let deadline = Instant::now() + deadline;
+        self.state.lock().unwrap().closed = true;
+        let mut timer_started = false;
+        future::poll_fn(|cx| {
+            let mut state = self.state.lock().unwrap();
+            if state.operations.is_empty() {
+                return Poll::Ready(Ok(()));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                state.cancelled = true;
+                state
+                    .operations
+                    .values_mut()
+                    .flat_map(Option::take)
+                    .for_each(Waker::wake);
+                return Poll::Ready(Err(UStatus::fail_with_code(
+                    UCode::DeadlineExceeded,
+                    &format!(
+                        "{} operation(s) have been cancelled",
+                        state.operations.len()
+                    ),
+                )));
+            }
+            state.shutdowns.push(cx.waker().clone());
+            if !timer_started {
+                timer_started = true;
+                let waker = cx.waker().clone();
+                thread::spawn(move || {
+                    thread::sleep(deadline - now);
+                    waker.wake();
+                });
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+// Counts an operation as in flight for as long as it lives.
+struct InFlight<'a> {
+    drain: &'a Drain,
+    id: u64,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        let mut state = self.drain.state.lock().unwrap();
+        state.operations.remove(&self.id);
+        if state.operations.is_empty() {
+            state.shutdowns.drain(..).for_each(Waker::wake);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::FutureExt;
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_operations_in_flight() {
+        let drain = Drain::new();
+        let (sender, receiver) = futures::channel::oneshot::channel::<()>();
+        let operation = drain.run(async {
+            receiver.await.unwrap();
+            Ok::<_, UStatus>(1)
+        });
+        futures::pin_mut!(operation);
+        assert!((&mut operation).now_or_never().is_none());
+        assert_eq!(drain.in_flight(), 1);
+
+        let shutdown = drain.shutdown(Duration::from_secs(10));
+        futures::pin_mut!(shutdown);
+        assert!((&mut shutdown).now_or_never().is_none());
+        assert_eq!(
+            drain
+                .run(async { Ok::<_, UStatus>(2) })
+                .await
+                .unwrap_err()
+                .get_code(),
+            UCode::Unavailable
+        );
+
+        sender.send(()).unwrap();
+        assert_eq!(operation.await.unwrap(), 1);
+        assert!(shutdown.await.is_ok());
+        assert!(drain.is_closed());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_cancels_operations_after_deadline() {
+        let drain = Drain::new();
+        let operation = drain.run(future::pending::<Result<(), UStatus>>());
+        let shutdown = drain.shutdown(Duration::from_millis(10));
+
+        let (result, shutdown) = futures::join!(operation, shutdown);
+        assert_eq!(result.unwrap_err().get_code(), UCode::Cancelled);
+        assert_eq!(shutdown.unwrap_err().get_code(), UCode::DeadlineExceeded);
+        assert_eq!(drain.in_flight(), 0);
+    }
+}
@@ -0,0 +1,263 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::iter;
+
+use crate::uprotocol::{UEntity, UResource, UUri};
+
+// The uEntity and resource IDs of a topic, where `None` stands for any ID.
+type ListenerKey = (Option<u32>, Option<u32>);
+
+struct IndexedListener<L> {
+    id: String,
+    topic: UUri,
+    listener: L,
+}
+
+/// `ListenerIndex` finds the listeners that a message is to be dispatched to without comparing it to each of them.
+///
+/// Listeners are kept in buckets by the uEntity ID and resource ID of the topic that they are registered for, with
+/// topics lacking one of the IDs kept in a wildcard bucket for it. A URI is looked up in at most four buckets: the
+/// one of its IDs, the ones with either ID as wildcard, and the one with both. The listeners of these buckets are
+/// then checked for the other parts that their topic specifies, i.e. its authority, names, version, instance and
+/// message, with parts that are not specified matching any URI.
+///
+/// Listeners for topics with resolved IDs are thus found by hash lookups only, whereas topics that are given by
+/// their names only end up in the same bucket and are compared one by one.
+pub struct ListenerIndex<L> {
+    buckets: HashMap<ListenerKey, Vec<IndexedListener<L>>>,
+    len: usize,
+}
+
+impl<L> Default for ListenerIndex<L> {
+    fn default() -> Self {
+        ListenerIndex {
+            buckets: HashMap::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<L> ListenerIndex<L> {
+    /// Creates an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a listener for a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the listener, which is needed for removing it.
+    /// * `topic` - The topic that the listener is registered for.
+    /// * `listener` - The listener.
+    pub fn insert(&mut self, id: impl Into<String>, topic: &UUri, listener: L) {
+        self.buckets
+            .entry(key_of(topic))
+            .or_default()
+            .push(IndexedListener {
+                id: id.into(),
+                topic: topic.clone(),
+                listener,
+            });
+        self.len += 1;
+    }
+
+    /// Removes a listener.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic that the listener has been added for.
+    /// * `id` - The identifier that the listener has been added with.
+    ///
+    /// # Returns
+    ///
+    /// The listener, or `None` if there is no such listener for the topic.
+    pub fn remove(&mut self, topic: &UUri, id: &str) -> Option<L> {
+        let key = key_of(topic);
+        let bucket = self.buckets.get_mut(&key)?;
+        let index = bucket
+            .iter()
+            .position(|indexed| indexed.id == id && indexed.topic == *topic)?;
+        let indexed = bucket.remove(index);
+        if bucket.is_empty() {
+            self.buckets.remove(&key);
+        }
+        self.len -= 1;
+        Some(indexed.listener)
+    }
+
+    /// Gets the listeners whose topic matches a URI, e.g. the source of a received message.
+    pub fn matching<'a>(&'a self, uri: &'a UUri) -> impl Iterator<Item = &'a L> + 'a {
+        let (entity_id, resource_id) = key_of(uri);
+        entity_id
+            .map(Some)
+            .into_iter()
+            .chain(iter::once(None))
+            .flat_map(move |entity_id| {
+                resource_id
+                    .map(Some)
+                    .into_iter()
+                    .chain(iter::once(None))
+                    .map(move |resource_id| (entity_id, resource_id))
+            })
+            .filter_map(move |key| self.buckets.get(&key))
+            .flatten()
+            .filter(move |indexed| covers(&indexed.topic, uri))
+            .map(|indexed| &indexed.listener)
+    }
+
+    /// Gets all listeners, e.g. for passing them a failure of the transport.
+    pub fn iter(&self) -> impl Iterator<Item = &L> {
+        self.buckets
+            .values()
+            .flatten()
+            .map(|indexed| &indexed.listener)
+    }
+
+    /// Gets the number of listeners.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if there are no listeners.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+fn key_of(uri: &UUri) -> ListenerKey {
+    (
+        uri.entity.as_ref().and_then(|entity| entity.id),
+        uri.resource.as_ref().and_then(|resource| resource.id),
+    )
+}
+
+// Checks the parts of a URI that its bucket does not account for against the ones that a topic specifies.
+fn covers(topic: &UUri, uri: &UUri) -> bool {
+    let authority = topic
+        .authority
+        .as_ref()
+        .map_or(true, |authority| uri.authority.as_ref() == Some(authority));
+    let entity = topic.entity.as_ref().map_or(true, |entity| {
+        uri.entity
+            .as_ref()
+            .map_or(false, |other| entity_covers(entity, other))
+    });
+    let resource = topic.resource.as_ref().map_or(true, |resource| {
+        uri.resource
+            .as_ref()
+            .map_or(false, |other| resource_covers(resource, other))
+    });
+    authority && entity && resource
+}
+
+fn entity_covers(entity: &UEntity, other: &UEntity) -> bool {
+    (entity.name.is_empty() || entity.name == other.name)
+        && entity
+            .version_major
+            .map_or(true, |version| other.version_major == Some(version))
+}
+
+fn resource_covers(resource: &UResource, other: &UResource) -> bool {
+    (resource.name.is_empty() || resource.name == other.name)
+        && resource
+            .instance
+            .as_ref()
+            .map_or(true, |instance| other.instance.as_ref() == Some(instance))
+        && resource
+            .message
+            .as_ref()
+            .map_or(true, |message| other.message.as_ref() == Some(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+
+    fn uri(uri: &str) -> UUri {
+        LongUriSerializer::deserialize(uri.to_string()).unwrap()
+    }
+
+    fn uri_with_ids(entity_id: Option<u32>, resource_id: Option<u32>) -> UUri {
+        let mut uri = uri("/body.access/1/door.front_left");
+        uri.entity.as_mut().unwrap().id = entity_id;
+        uri.resource.as_mut().unwrap().id = resource_id;
+        uri
+    }
+
+    fn matching(index: &ListenerIndex<&'static str>, uri: &UUri) -> Vec<&'static str> {
+        let mut listeners = index.matching(uri).copied().collect::<Vec<_>>();
+        listeners.sort_unstable();
+        listeners
+    }
+
+    #[test]
+    fn test_matching_by_ids_and_wildcards() {
+        let mut index = ListenerIndex::new();
+        index.insert("1", &uri_with_ids(Some(1), Some(0x8000)), "exact");
+        index.insert("2", &uri_with_ids(Some(1), None), "any resource");
+        index.insert("3", &uri_with_ids(None, Some(0x8000)), "any entity");
+        index.insert("4", &uri_with_ids(Some(2), Some(0x8000)), "other entity");
+        index.insert("5", &UUri::default(), "any");
+
+        assert_eq!(
+            matching(&index, &uri_with_ids(Some(1), Some(0x8000))),
+            vec!["any", "any entity", "any resource", "exact"]
+        );
+        assert_eq!(
+            matching(&index, &uri_with_ids(Some(1), Some(0x8001))),
+            vec!["any", "any resource"]
+        );
+        assert_eq!(matching(&index, &uri_with_ids(None, None)), vec!["any"]);
+    }
+
+    #[test]
+    fn test_matching_by_names() {
+        let mut index = ListenerIndex::new();
+        index.insert("1", &uri("/body.access/1/door.front_left"), "front left");
+        index.insert("2", &uri("/body.access/1/door.rear"), "rear");
+        index.insert("3", &uri("/body.access"), "body access");
+
+        assert_eq!(
+            matching(&index, &uri("/body.access/1/door.front_left#Door")),
+            vec!["body access", "front left"]
+        );
+        assert_eq!(
+            matching(&index, &uri("//vcu.my_car_vin/body.access/1/door.rear")),
+            vec!["body access", "rear"]
+        );
+        assert!(matching(&index, &uri("/body.access/2/door.rear")).contains(&"body access"));
+        assert!(matching(&index, &uri("/hartley/1/door.rear")).is_empty());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut index = ListenerIndex::new();
+        let topic = uri_with_ids(Some(1), Some(0x8000));
+        index.insert("1", &topic, "first");
+        index.insert("2", &topic, "second");
+        assert_eq!(index.len(), 2);
+
+        assert_eq!(index.remove(&uri_with_ids(Some(1), None), "1"), None);
+        assert_eq!(index.remove(&topic, "1"), Some("first"));
+        assert_eq!(index.remove(&topic, "1"), None);
+        assert_eq!(matching(&index, &topic), vec!["second"]);
+        assert_eq!(index.remove(&topic, "2"), Some("second"));
+        assert!(index.is_empty());
+        assert_eq!(index.iter().count(), 0);
+    }
+}
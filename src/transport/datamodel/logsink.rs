@@ -0,0 +1,195 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::fmt;
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+
+use crate::uprotocol::{UCode, UStatus};
+
+/// The severity of a diagnostic message of the SDK, ordered from the most to the least severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    /// A failure that the SDK cannot recover from, e.g. a message that is lost.
+    Error,
+    /// A failure that the SDK has handled, e.g. by dropping an expired message.
+    Warn,
+    /// A change of state, e.g. a connection being established.
+    Info,
+    /// Details for finding out why a message has not been delivered.
+    Debug,
+    /// Details of each message.
+    Trace,
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A sink for the diagnostic messages of the SDK, e.g. about messages that transport decorators drop, failed RPC
+/// requests or attributes that fail validation, which passes them on to the logging system of the platform.
+///
+/// The SDK does not depend on a particular logging crate, as some projects do not allow for `tracing` or `log`. The
+/// sink is installed once per process with [`set_log_sink`]. If none is installed and the `log` feature is enabled,
+/// the messages are passed on to the `log` crate by means of [`LogCrateSink`], otherwise they are discarded.
+///
+/// Messages are identified by their targets, which are the module paths of the code emitting them, like
+/// `uprotocol_sdk::transport::datamodel::deduplicator`. Implementations are called from the threads that send and
+/// receive messages, so they need to return quickly.
+pub trait LogSink: Send + Sync {
+    /// Checks if messages of a level and target are logged, so that they are only formatted if they are.
+    ///
+    /// The default implementation logs all messages.
+    fn enabled(&self, _level: LogLevel, _target: &str) -> bool {
+        true
+    }
+
+    /// Logs a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The severity of the message.
+    /// * `target` - The module path of the code emitting the message.
+    /// * `message` - The message.
+    fn log(&self, level: LogLevel, target: &str, message: &fmt::Arguments<'_>);
+}
+
+impl<S: LogSink + ?Sized> LogSink for Arc<S> {
+    fn enabled(&self, level: LogLevel, target: &str) -> bool {
+        (**self).enabled(level, target)
+    }
+
+    fn log(&self, level: LogLevel, target: &str, message: &fmt::Arguments<'_>) {
+        (**self).log(level, target, message);
+    }
+}
+
+/// A [`LogSink`] passing messages on to the `log` crate, with the level and target they have been emitted with.
+#[cfg(feature = "log")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogCrateSink;
+
+#[cfg(feature = "log")]
+impl LogCrateSink {
+    fn level(level: LogLevel) -> log::Level {
+        match level {
+            LogLevel::Error => log::Level::Error,
+            LogLevel::Warn => log::Level::Warn,
+            LogLevel::Info => log::Level::Info,
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Trace => log::Level::Trace,
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl LogSink for LogCrateSink {
+    fn enabled(&self, level: LogLevel, target: &str) -> bool {
+        log::log_enabled!(target: target, Self::level(level))
+    }
+
+    fn log(&self, level: LogLevel, target: &str, message: &fmt::Arguments<'_>) {
+        log::log!(target: target, Self::level(level), "{message}");
+    }
+}
+
+static SINK: OnceCell<Box<dyn LogSink>> = OnceCell::new();
+
+/// Installs the sink that the diagnostic messages of the SDK are passed to, for the rest of the process.
+///
+/// # Arguments
+///
+/// * `sink` - The sink.
+///
+/// # Errors
+///
+/// Returns a `UStatus` with code `UCode::AlreadyExists` if a sink has been installed before.
+pub fn set_log_sink<S: LogSink + 'static>(sink: S) -> Result<(), UStatus> {
+    SINK.set(Box::new(sink)).map_err(|_sink| {
+        UStatus::fail_with_code(
+            UCode::AlreadyExists,
+            "A log sink has been installed already",
+        )
+    })
+}
+
+// Passes a diagnostic message on to the installed sink, e.g. as
+// `emit(LogLevel::Debug, module_path!(), format_args!("..."))`. The message is only formatted if it is logged.
+pub(crate) fn emit(level: LogLevel, target: &str, message: fmt::Arguments<'_>) {
+    if let Some(sink) = SINK.get() {
+        if sink.enabled(level, target) {
+            sink.log(level, target, &message);
+        }
+        return;
+    }
+    #[cfg(feature = "log")]
+    if LogCrateSink.enabled(level, target) {
+        LogCrateSink.log(level, target, &message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    // Records the messages of this module's tests only, as other tests emit messages to the same sink.
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<(LogLevel, String)>>);
+
+    impl LogSink for RecordingSink {
+        fn enabled(&self, level: LogLevel, target: &str) -> bool {
+            level <= LogLevel::Info && target == module_path!()
+        }
+
+        fn log(&self, level: LogLevel, _target: &str, message: &fmt::Arguments<'_>) {
+            self.0.lock().unwrap().push((level, message.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_messages_are_passed_to_installed_sink() {
+        let sink = Arc::new(RecordingSink::default());
+        set_log_sink(Arc::clone(&sink)).unwrap();
+        assert_eq!(
+            set_log_sink(RecordingSink::default())
+                .unwrap_err()
+                .get_code(),
+            UCode::AlreadyExists
+        );
+
+        emit(
+            LogLevel::Warn,
+            module_path!(),
+            format_args!("dropped {}", 1),
+        );
+        emit(LogLevel::Debug, module_path!(), format_args!("filtered"));
+        emit(LogLevel::Error, "other", format_args!("filtered"));
+        assert_eq!(
+            *sink.0.lock().unwrap(),
+            vec![(LogLevel::Warn, "dropped 1".to_string())]
+        );
+        assert_eq!(LogLevel::Warn.to_string(), "WARN");
+    }
+}
@@ -0,0 +1,150 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::ffi::CString;
+use std::fs::File;
+use std::io::{self, Write};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd};
+
+use memmap2::{Mmap, MmapOptions};
+
+use crate::transport::datamodel::SharedMemoryRegion;
+
+/// A [`SharedMemoryRegion`] backed by a read-only memory mapping of a file.
+///
+/// Regions are typically created by the sending uEntity using [`MmapRegion::memfd`], and sealed against further
+/// modification before the file descriptor is passed to the receiving uEntity, which maps it using
+/// [`MmapRegion::from_file`].
+pub struct MmapRegion {
+    file: File,
+    mmap: Mmap,
+}
+
+impl MmapRegion {
+    /// Creates a new anonymous, sealed memory file containing the given data and maps it.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the memory file, for debugging purposes.
+    /// * `data` - The data to put into the region.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memory file cannot be created, written, sealed or mapped.
+    pub fn memfd(name: &str, data: &[u8]) -> io::Result<MmapRegion> {
+        let name =
+            CString::new(name).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        // SAFETY: `name` is a valid, nul-terminated string that outlives the call
+        let fd = unsafe { libc::memfd_create(name.as_ptr(), libc::MFD_ALLOW_SEALING) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `fd` has just been created and is not owned by anything else
+        let mut file = unsafe { File::from_raw_fd(fd) };
+        file.write_all(data)?;
+
+        let seals =
+            libc::F_SEAL_SEAL | libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_WRITE;
+        // SAFETY: `fd` is a valid file descriptor owned by `file`
+        if unsafe { libc::fcntl(fd, libc::F_ADD_SEALS, seals) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Self::from_file(file)
+    }
+
+    /// Maps an existing memory file, e.g. one received from another uEntity.
+    ///
+    /// The file must be sealed against shrinking and writing, like the ones created by [`MmapRegion::memfd`], so that
+    /// its contents cannot change or disappear while mapped.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The file to map.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with kind `io::ErrorKind::PermissionDenied` if the file is not sealed against shrinking and
+    /// writing, or another error if its seals cannot be read, e.g. because it is no memory file, or it cannot be
+    /// mapped.
+    pub fn from_file(file: File) -> io::Result<MmapRegion> {
+        // SAFETY: `file` owns a valid file descriptor
+        let seals = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GET_SEALS) };
+        if seals < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let required = libc::F_SEAL_SHRINK | libc::F_SEAL_WRITE;
+        if seals & required != required {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Memory file is not sealed against shrinking and writing",
+            ));
+        }
+        // SAFETY: the mapping is read-only, and the seals checked above ensure that the file can neither be
+        // modified nor truncated while mapped
+        let mmap = unsafe { MmapOptions::new().map(&file) }?;
+        Ok(MmapRegion { file, mmap })
+    }
+}
+
+impl AsFd for MmapRegion {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.file.as_fd()
+    }
+}
+
+impl SharedMemoryRegion for MmapRegion {
+    fn map(&self) -> &[u8] {
+        &self.mmap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uprotocol::{Data, UPayload};
+
+    #[test]
+    fn test_memfd_region_resolves_payload_reference() {
+        let region = MmapRegion::memfd("test", b"hello uprotocol").unwrap();
+        let payload = UPayload {
+            data: Some(Data::Reference(6)),
+            length: Some(9),
+            ..Default::default()
+        };
+
+        let data = payload.resolve_data(Some(&region)).unwrap();
+        assert_eq!(data, Some(b"uprotocol".as_slice()));
+    }
+
+    #[test]
+    fn test_memfd_region_is_sealed() {
+        let region = MmapRegion::memfd("test", b"sealed").unwrap();
+        let mut file = region.file.try_clone().unwrap();
+        assert!(file.write_all(b"modified").is_err());
+    }
+
+    #[test]
+    fn test_from_file_fails_for_unsealed_file() {
+        let name = CString::new("test").unwrap();
+        // SAFETY: `name` is a valid, nul-terminated string that outlives the call
+        let fd = unsafe { libc::memfd_create(name.as_ptr(), libc::MFD_ALLOW_SEALING) };
+        assert!(fd >= 0);
+        // SAFETY: `fd` has just been created and is not owned by anything else
+        let mut file = unsafe { File::from_raw_fd(fd) };
+        file.write_all(b"unsealed").unwrap();
+
+        let error = MmapRegion::from_file(file).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+    }
+}
@@ -0,0 +1,266 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use sha2::{Digest, Sha256};
+
+use crate::types::serializationerror::SerializationError;
+use crate::uprotocol::{UCode, UPayload, UStatus};
+
+/// The algorithms that can be used for computing payload checksums.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    /// CRC-32 (IEEE 802.3), suitable for detecting transmission errors.
+    Crc32,
+    /// SHA-256, for detecting corruption of large transfers with a negligible collision probability.
+    Sha256,
+}
+
+/// A checksum over the data of a `UPayload`.
+///
+/// Neither `UPayload` nor `UAttributes` have room for a checksum, so transports carry it in a header named
+/// [`PayloadChecksum::HEADER_NAME`], next to the headers created by
+/// [`UAttributes::to_header_map`](crate::uprotocol::UAttributes::to_header_map), using the string representation
+/// `<algorithm>:<hex digest>`, e.g. `crc32:cbf43926`. Senders decide per message whether to add a checksum, and
+/// receivers verify the payload of every message that has one by means of [`PayloadChecksum::verify_headers`].
+///
+/// This is mostly useful for `UpayloadFormatRaw` payloads, which have no inner structure that would reveal
+/// corruption when being decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayloadChecksum {
+    algorithm: ChecksumAlgorithm,
+    digest: Vec<u8>,
+}
+
+impl PayloadChecksum {
+    /// The name of the header carrying the checksum.
+    pub const HEADER_NAME: &'static str = "checksum";
+
+    /// Computes the checksum of a payload's data.
+    ///
+    /// # Arguments
+    ///
+    /// * `algorithm` - The algorithm to use.
+    /// * `payload` - The payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload refers to shared memory instead of carrying its data by value.
+    pub fn compute(
+        algorithm: ChecksumAlgorithm,
+        payload: &UPayload,
+    ) -> Result<PayloadChecksum, SerializationError> {
+        let data = payload.resolve_data(None)?.unwrap_or_default();
+        let digest = match algorithm {
+            ChecksumAlgorithm::Crc32 => crc32fast::hash(data).to_be_bytes().to_vec(),
+            ChecksumAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+        };
+        Ok(PayloadChecksum { algorithm, digest })
+    }
+
+    /// Gets the algorithm this checksum has been computed with.
+    pub fn algorithm(&self) -> ChecksumAlgorithm {
+        self.algorithm
+    }
+
+    /// Verifies that a payload's data matches this checksum.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to verify.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code [`UCode::DataLoss`] if the payload's data does not match the checksum,
+    /// or [`UCode::InvalidArgument`] if the checksum cannot be computed for the payload.
+    pub fn verify(&self, payload: &UPayload) -> Result<(), UStatus> {
+        let actual = Self::compute(self.algorithm, payload)?;
+        if actual != *self {
+            return Err(UStatus::fail_with_code(
+                UCode::DataLoss,
+                &format!("Payload checksum {actual} does not match expected checksum {self}"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Adds this checksum to the headers of a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers to add the checksum to, e.g. as created by `UAttributes::to_header_map`.
+    pub fn insert_into(&self, headers: &mut HashMap<String, String>) {
+        headers.insert(Self::HEADER_NAME.to_string(), self.to_string());
+    }
+
+    /// Gets the checksum from the headers of a received message.
+    ///
+    /// The header name is matched regardless of its case, like the names of the attribute headers.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the message.
+    ///
+    /// # Returns
+    ///
+    /// The checksum, or `None` if the message has been sent without one.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the checksum header has an invalid value.
+    pub fn from_headers(
+        headers: &HashMap<String, String>,
+    ) -> Result<Option<PayloadChecksum>, SerializationError> {
+        headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(Self::HEADER_NAME))
+            .map(|(_, value)| value.parse())
+            .transpose()
+    }
+
+    /// Verifies a received message's payload against the checksum in the message's headers.
+    ///
+    /// Messages without a checksum header are accepted as is.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers of the message.
+    /// * `payload` - The payload of the message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code [`UCode::DataLoss`] if the payload does not match the checksum, or if the
+    /// checksum header itself has been corrupted, i.e. has an invalid value.
+    pub fn verify_headers(
+        headers: &HashMap<String, String>,
+        payload: &UPayload,
+    ) -> Result<(), UStatus> {
+        match Self::from_headers(headers) {
+            Ok(Some(checksum)) => checksum.verify(payload),
+            Ok(None) => Ok(()),
+            Err(e) => Err(UStatus::fail_with_code(UCode::DataLoss, &e.to_string())),
+        }
+    }
+}
+
+impl Display for PayloadChecksum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let algorithm = match self.algorithm {
+            ChecksumAlgorithm::Crc32 => "crc32",
+            ChecksumAlgorithm::Sha256 => "sha256",
+        };
+        write!(f, "{algorithm}:")?;
+        self.digest
+            .iter()
+            .try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl FromStr for PayloadChecksum {
+    type Err = SerializationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || SerializationError::new(format!("Invalid payload checksum [{value}]"));
+
+        let (algorithm, digest) = value.split_once(':').ok_or_else(invalid)?;
+        let (algorithm, digest_len) = match algorithm {
+            "crc32" => (ChecksumAlgorithm::Crc32, 4),
+            "sha256" => (ChecksumAlgorithm::Sha256, 32),
+            _ => return Err(invalid()),
+        };
+        if !digest.is_ascii() || digest.len() != 2 * digest_len {
+            return Err(invalid());
+        }
+        let digest = (0..digest.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&digest[i..i + 2], 16).map_err(|_e| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(PayloadChecksum { algorithm, digest })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    use crate::transport::builder::UPayloadBuilder;
+
+    #[test_case(ChecksumAlgorithm::Crc32, "crc32:cbf43926"; "crc32")]
+    #[test_case(
+        ChecksumAlgorithm::Sha256,
+        "sha256:15e2b0d3c33891ebb0f1ef609ec419420c20e320ce94c65fbc8c3312448eb225";
+        "sha256"
+    )]
+    fn test_compute(algorithm: ChecksumAlgorithm, expected: &str) {
+        let payload = UPayloadBuilder::from_raw(b"123456789".to_vec())
+            .build()
+            .unwrap();
+
+        let checksum = PayloadChecksum::compute(algorithm, &payload).unwrap();
+        assert_eq!(checksum.to_string(), expected);
+        assert_eq!(expected.parse::<PayloadChecksum>().unwrap(), checksum);
+    }
+
+    #[test]
+    fn test_verify_detects_corruption() {
+        let payload = UPayloadBuilder::from_raw(vec![1, 2, 3]).build().unwrap();
+        let checksum = PayloadChecksum::compute(ChecksumAlgorithm::Crc32, &payload).unwrap();
+        assert!(checksum.verify(&payload).is_ok());
+
+        let corrupted = UPayloadBuilder::from_raw(vec![1, 2, 4]).build().unwrap();
+        let status = checksum.verify(&corrupted).unwrap_err();
+        assert_eq!(status.get_code(), UCode::DataLoss);
+    }
+
+    #[test]
+    fn test_verify_headers() {
+        let payload = UPayloadBuilder::from_raw(vec![1, 2, 3]).build().unwrap();
+        let mut headers = HashMap::new();
+        assert!(PayloadChecksum::verify_headers(&headers, &payload).is_ok());
+
+        let checksum = PayloadChecksum::compute(ChecksumAlgorithm::Sha256, &payload).unwrap();
+        checksum.insert_into(&mut headers);
+        assert_eq!(
+            PayloadChecksum::from_headers(&headers).unwrap(),
+            Some(checksum)
+        );
+        assert!(PayloadChecksum::verify_headers(&headers, &payload).is_ok());
+
+        let corrupted = UPayloadBuilder::from_raw(vec![1, 2, 4]).build().unwrap();
+        let status = PayloadChecksum::verify_headers(&headers, &corrupted).unwrap_err();
+        assert_eq!(status.get_code(), UCode::DataLoss);
+    }
+
+    #[test]
+    fn test_verify_headers_fails_for_corrupted_header() {
+        let payload = UPayloadBuilder::from_raw(vec![1, 2, 3]).build().unwrap();
+        let headers = HashMap::from([("Checksum".to_string(), "crc32:cbf4".to_string())]);
+
+        let status = PayloadChecksum::verify_headers(&headers, &payload).unwrap_err();
+        assert_eq!(status.get_code(), UCode::DataLoss);
+    }
+
+    #[test_case("cbf43926"; "missing algorithm")]
+    #[test_case("md5:cbf43926"; "unknown algorithm")]
+    #[test_case("crc32:cbf439"; "short digest")]
+    #[test_case("crc32:cbf4392g"; "invalid hex digit")]
+    #[test_case("crc32:cbf439ä"; "non-ascii digest")]
+    fn test_parse_fails_for_invalid_checksum(value: &str) {
+        assert!(value.parse::<PayloadChecksum>().is_err());
+    }
+}
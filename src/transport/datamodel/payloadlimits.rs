@@ -0,0 +1,105 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::uprotocol::{UCode, UPayload, UStatus};
+
+/// Limits imposed on the payloads of messages by a transport.
+///
+/// Checking payloads against these limits before handing them to a transport makes oversized payloads fail early,
+/// with a [`UCode::ResourceExhausted`] status, instead of with a transport specific error on the wire. The default
+/// limits accept payloads of any size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayloadLimits {
+    max_size: Option<usize>,
+}
+
+impl PayloadLimits {
+    /// Creates limits with a maximum payload size.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_size` - The maximum size of payload data in bytes.
+    pub fn new(max_size: usize) -> PayloadLimits {
+        PayloadLimits {
+            max_size: Some(max_size),
+        }
+    }
+
+    /// Gets the maximum size of payload data in bytes, if any.
+    pub fn max_size(&self) -> Option<usize> {
+        self.max_size
+    }
+
+    /// Checks if a payload complies with these limits.
+    ///
+    /// The size of a payload is its [`UPayload::data_len`].
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to check.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code [`UCode::ResourceExhausted`] if the payload exceeds the maximum size.
+    pub fn check(&self, payload: &UPayload) -> Result<(), UStatus> {
+        let Some(max_size) = self.max_size else {
+            return Ok(());
+        };
+        let size = payload.data_len();
+        if size > max_size {
+            return Err(UStatus::fail_with_code(
+                UCode::ResourceExhausted,
+                &format!("Payload size of {size} bytes exceeds maximum of {max_size} bytes"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uprotocol::Data;
+
+    use test_case::test_case;
+
+    fn payload_of_size(size: usize) -> UPayload {
+        UPayload {
+            data: Some(Data::Value(vec![0; size])),
+            ..Default::default()
+        }
+    }
+
+    #[test_case(PayloadLimits::default(), 10_000, true; "default accepts any size")]
+    #[test_case(PayloadLimits::new(16), 16, true; "accepts payload of maximum size")]
+    #[test_case(PayloadLimits::new(16), 17, false; "rejects payload exceeding maximum size")]
+    fn test_check(limits: PayloadLimits, size: usize, should_succeed: bool) {
+        let result = limits.check(&payload_of_size(size));
+        assert_eq!(result.is_ok(), should_succeed);
+        if let Err(status) = result {
+            assert_eq!(status.get_code(), UCode::ResourceExhausted);
+        }
+    }
+
+    #[test]
+    fn test_check_uses_length_of_referenced_data() {
+        let payload = UPayload {
+            data: Some(Data::Reference(0x1000)),
+            length: Some(17),
+            ..Default::default()
+        };
+        assert!(PayloadLimits::new(16).check(&payload).is_err());
+        assert!(PayloadLimits::new(17).check(&payload).is_ok());
+    }
+}
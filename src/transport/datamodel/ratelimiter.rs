@@ -0,0 +1,431 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::transport::datamodel::{emit, LogLevel, UListener};
+use crate::types::serializationerror::SerializationError;
+use crate::uprotocol::{UAuthority, UCode, UMessage, UStatus};
+use crate::uuid::builder::{SystemClock, UClock};
+
+// the maximum number of callers whose buckets are kept, the buckets of the least recently seen callers are
+// forgotten first
+const MAX_CALLERS: usize = 1024;
+
+fn default_rate() -> u32 {
+    100
+}
+
+fn default_burst() -> u32 {
+    100
+}
+
+/// The configuration of a [`RateLimiter`], e.g. as part of the JSON configuration of a transport.
+///
+/// ```json
+/// { "rate": 50, "burst": 200 }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// The number of messages per second that each caller may send in the long run, 100 if not set.
+    #[serde(default = "default_rate")]
+    pub rate: u32,
+    /// The number of messages that each caller may send at once, 100 if not set.
+    #[serde(default = "default_burst")]
+    pub burst: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            rate: default_rate(),
+            burst: default_burst(),
+        }
+    }
+}
+
+// the uAuthority that a caller is at, by whichever address it has, so that callers at authorities that are
+// addressed by IP address or ID are told apart as well as the ones addressed by name
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum AuthorityKey {
+    Local,
+    Name(String),
+    Ip(Vec<u8>),
+    Id(Vec<u8>),
+}
+
+impl From<Option<&UAuthority>> for AuthorityKey {
+    fn from(authority: Option<&UAuthority>) -> Self {
+        let Some(authority) = authority else {
+            return AuthorityKey::Local;
+        };
+        if let Some(name) = authority.get_name() {
+            AuthorityKey::Name(name.to_string())
+        } else if let Some(ip) = authority.get_ip() {
+            AuthorityKey::Ip(ip.to_vec())
+        } else if let Some(id) = authority.get_id() {
+            AuthorityKey::Id(id.to_vec())
+        } else {
+            AuthorityKey::Local
+        }
+    }
+}
+
+impl fmt::Display for AuthorityKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthorityKey::Local => Ok(()),
+            AuthorityKey::Name(name) => f.write_str(name),
+            AuthorityKey::Ip(address) | AuthorityKey::Id(address) => {
+                address.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+            }
+        }
+    }
+}
+
+type CallerKey = (AuthorityKey, String);
+
+// A token bucket, counting thousandths of tokens so that it can be refilled by the millisecond.
+#[derive(Debug)]
+struct Bucket {
+    millitokens: u64,
+    refilled_at: u64,
+    // the number of the check that has last used the bucket
+    used_at: u64,
+}
+
+// the buckets of the callers, along with the order in which they have last been used
+#[derive(Debug, Default)]
+struct Buckets {
+    by_caller: HashMap<CallerKey, Bucket>,
+    by_use: BTreeMap<u64, CallerKey>,
+    uses: u64,
+}
+
+/// `RateLimiter` keeps uEntities that send too many messages from starving the others, e.g. of the threads of
+/// an RPC server or of a listener.
+///
+/// The limiter has a token bucket for each caller, i.e. for each uAuthority (by name, IP address or ID) and uEntity
+/// name that the sources of the messages have, which holds up to `burst` tokens and is refilled with `rate` tokens
+/// per second. Each message takes a token from the bucket of its caller, and is rejected if there is none left.
+///
+/// The buckets of up to 1024 callers are kept. Once there are more callers, the buckets of the ones that have sent
+/// no message for the longest time are forgotten, so that those callers start over with a full bucket.
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<Buckets>,
+    clock: Box<dyn UClock>,
+    rejected: AtomicU64,
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter that takes the current time from the platform.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The rate and burst allowed for each caller.
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self::with_clock(config, SystemClock)
+    }
+
+    /// Creates a rate limiter that takes the current time from the given clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The rate and burst allowed for each caller.
+    /// * `clock` - The clock to refill the buckets by.
+    pub fn with_clock<C>(config: RateLimitConfig, clock: C) -> Self
+    where
+        C: UClock + 'static,
+    {
+        RateLimiter {
+            config,
+            buckets: Mutex::new(Buckets::default()),
+            clock: Box::new(clock),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// Creates a rate limiter from a JSON configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The configuration, as shown for [`RateLimitConfig`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the configuration is malformed.
+    pub fn from_json(json: &str) -> Result<Self, SerializationError> {
+        let config = serde_json::from_str(json)
+            .map_err(|e| SerializationError::json("Invalid rate limit configuration", e))?;
+        Ok(Self::new(config))
+    }
+
+    /// Gets the rate and burst allowed for each caller.
+    pub fn config(&self) -> RateLimitConfig {
+        self.config
+    }
+
+    /// Gets the number of messages that have been rejected so far.
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    /// Takes a token for a message from the bucket of its caller.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message, e.g. a request received by an RPC server.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::ResourceExhausted`, which is to be sent as the response to requests,
+    /// if the caller has exceeded its rate.
+    pub fn check(&self, message: &UMessage) -> Result<(), UStatus> {
+        let source = message.source.as_ref();
+        let authority = AuthorityKey::from(source.and_then(|source| source.authority.as_ref()));
+        let entity = source
+            .and_then(|source| source.entity.as_ref())
+            .map(|entity| entity.name.clone())
+            .unwrap_or_default();
+        let caller = (authority, entity);
+
+        if self.try_acquire(&caller) {
+            return Ok(());
+        }
+        let (authority, entity) = caller;
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+        let status = UStatus::fail_with_code(
+            UCode::ResourceExhausted,
+            &format!(
+                "Rate of {} messages per second exceeded by [{authority}/{entity}]",
+                self.config.rate
+            ),
+        );
+        emit(LogLevel::Debug, module_path!(), format_args!("{status}"));
+        Err(status)
+    }
+
+    fn try_acquire(&self, caller: &CallerKey) -> bool {
+        let now = self.clock.now().unwrap_or_default();
+        let capacity = u64::from(self.config.burst) * 1000;
+        let rate = u64::from(self.config.rate);
+        let refill = |bucket: &mut Bucket| {
+            let elapsed = now.saturating_sub(bucket.refilled_at);
+            bucket.millitokens = bucket
+                .millitokens
+                .saturating_add(elapsed.saturating_mul(rate))
+                .min(capacity);
+            bucket.refilled_at = now;
+        };
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let Buckets {
+            by_caller,
+            by_use,
+            uses,
+        } = &mut *buckets;
+        if let Some(bucket) = by_caller.get(caller) {
+            by_use.remove(&bucket.used_at);
+        } else if by_caller.len() >= MAX_CALLERS {
+            if let Some((_, least_recent)) = by_use.pop_first() {
+                by_caller.remove(&least_recent);
+            }
+        }
+        *uses += 1;
+        by_use.insert(*uses, caller.clone());
+        let bucket = by_caller.entry(caller.clone()).or_insert(Bucket {
+            millitokens: capacity,
+            refilled_at: now,
+            used_at: 0,
+        });
+        bucket.used_at = *uses;
+        refill(bucket);
+        if bucket.millitokens < 1000 {
+            return false;
+        }
+        bucket.millitokens -= 1000;
+        true
+    }
+
+    /// Wraps a listener so that it is not called with messages of callers that have exceeded their rate.
+    ///
+    /// Statuses about messages that cannot be delivered are passed on to the listener as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to call with the messages within the rate of their callers.
+    pub fn wrap(self: &Arc<Self>, listener: UListener) -> UListener {
+        let limiter = Arc::clone(self);
+        Box::new(move |result| {
+            if let Ok(message) = &result {
+                if limiter.check(message).is_err() {
+                    return;
+                }
+            }
+            listener(result);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::AtomicUsize;
+
+    use crate::uprotocol::{UEntity, UUri};
+
+    struct TestClock(AtomicU64);
+    impl UClock for TestClock {
+        fn now(&self) -> Option<u64> {
+            Some(self.0.load(Ordering::Relaxed))
+        }
+    }
+
+    fn message_from(entity: &str) -> UMessage {
+        UMessage {
+            source: Some(UUri {
+                entity: Some(UEntity {
+                    name: entity.to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn message_at(authority: UAuthority, entity: &str) -> UMessage {
+        let mut message = message_from(entity);
+        message.source.as_mut().unwrap().authority = Some(authority);
+        message
+    }
+
+    fn limiter(rate: u32, burst: u32) -> (RateLimiter, Arc<TestClock>) {
+        let clock = Arc::new(TestClock(AtomicU64::new(1_000_000)));
+        let limiter = RateLimiter::with_clock(RateLimitConfig { rate, burst }, Arc::clone(&clock));
+        (limiter, clock)
+    }
+
+    #[test]
+    fn test_caller_is_limited_to_burst_and_rate() {
+        let (limiter, clock) = limiter(10, 3);
+        let message = message_from("body.access");
+
+        for _ in 0..3 {
+            assert!(limiter.check(&message).is_ok());
+        }
+        let status = limiter.check(&message).unwrap_err();
+        assert_eq!(status.get_code(), UCode::ResourceExhausted);
+        assert_eq!(limiter.rejected(), 1);
+
+        // 10 messages per second refill a token every 100 ms
+        clock.0.fetch_add(99, Ordering::Relaxed);
+        assert!(limiter.check(&message).is_err());
+        clock.0.fetch_add(1, Ordering::Relaxed);
+        assert!(limiter.check(&message).is_ok());
+        assert!(limiter.check(&message).is_err());
+
+        clock.0.fetch_add(10_000, Ordering::Relaxed);
+        for _ in 0..3 {
+            assert!(limiter.check(&message).is_ok());
+        }
+        assert!(limiter.check(&message).is_err());
+    }
+
+    #[test]
+    fn test_callers_are_limited_independently() {
+        let (limiter, _) = limiter(1, 1);
+
+        assert!(limiter.check(&message_from("body.access")).is_ok());
+        assert!(limiter.check(&message_from("body.access")).is_err());
+        assert!(limiter.check(&message_from("hartley")).is_ok());
+    }
+
+    #[test]
+    fn test_callers_at_authorities_without_name_are_limited_independently() {
+        let (limiter, _) = limiter(1, 1);
+        let mut first = UAuthority::default();
+        first.set_ip(vec![192, 168, 1, 100]);
+        let mut second = UAuthority::default();
+        second.set_ip(vec![192, 168, 1, 101]);
+        let mut third = UAuthority::default();
+        third.set_id(vec![0x2A]);
+
+        assert!(limiter
+            .check(&message_at(first.clone(), "body.access"))
+            .is_ok());
+        let status = limiter
+            .check(&message_at(first, "body.access"))
+            .unwrap_err();
+        assert!(status.message.unwrap().contains("[c0a80164/body.access]"));
+        assert!(limiter.check(&message_at(second, "body.access")).is_ok());
+        assert!(limiter.check(&message_at(third, "body.access")).is_ok());
+        assert!(limiter.check(&message_from("body.access")).is_ok());
+    }
+
+    #[test]
+    fn test_least_recently_seen_callers_are_forgotten() {
+        let (limiter, _) = limiter(1, 1);
+
+        assert!(limiter.check(&message_from("caller-0")).is_ok());
+        for caller in 1..MAX_CALLERS {
+            assert!(limiter
+                .check(&message_from(&format!("caller-{caller}")))
+                .is_ok());
+        }
+        // caller-0 is seen again, so that caller-1 is the least recently seen caller
+        assert!(limiter.check(&message_from("caller-0")).is_err());
+        assert!(limiter.check(&message_from("newcomer")).is_ok());
+        assert_eq!(limiter.buckets.lock().unwrap().by_caller.len(), MAX_CALLERS);
+
+        assert!(limiter.check(&message_from("caller-0")).is_err());
+        assert!(limiter.check(&message_from("caller-1")).is_ok());
+    }
+
+    #[test]
+    fn test_wrapped_listener_drops_messages_exceeding_rate() {
+        let (limiter, _) = limiter(1, 2);
+        let limiter = Arc::new(limiter);
+        let received = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&received);
+        let listener = limiter.wrap(Box::new(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        }));
+
+        for _ in 0..3 {
+            listener(Ok(message_from("body.access")));
+        }
+        listener(Err(UStatus::fail_with_code(UCode::Internal, "failure")));
+        assert_eq!(received.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let limiter = RateLimiter::from_json(r#"{ "rate": 50 }"#).unwrap();
+        assert_eq!(
+            limiter.config(),
+            RateLimitConfig {
+                rate: 50,
+                burst: 100
+            }
+        );
+        assert!(RateLimiter::from_json(r#"{ "rate": -1 }"#).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::types::serializationerror::SerializationError;
+use crate::uprotocol::{Data, UPayload};
+
+/// A region of memory shared between uEntities, which `UPayload`s can refer to instead of carrying their data by value.
+///
+/// A `Data::Reference` is interpreted as an offset into the region, and the payload's `length` as the number of bytes
+/// starting at that offset. All views handed out by a region are bounds-checked and borrow from the region, so they
+/// cannot outlive the underlying mapping.
+pub trait SharedMemoryRegion {
+    /// Gets the full contents of the mapped region.
+    fn map(&self) -> &[u8];
+
+    /// Gets a view of a part of the region.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The offset into the region at which the view starts.
+    /// * `length` - The number of bytes in the view.
+    ///
+    /// # Returns
+    ///
+    /// The bytes in the given range.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the range does not lie within the region.
+    fn view(&self, offset: u64, length: usize) -> Result<&[u8], SerializationError> {
+        let bytes = self.map();
+        usize::try_from(offset)
+            .ok()
+            .and_then(|start| Some(start..start.checked_add(length)?))
+            .and_then(|range| bytes.get(range))
+            .ok_or_else(|| {
+                SerializationError::new(format!(
+                    "Range [{offset}, +{length}] exceeds shared memory region of {} bytes",
+                    bytes.len()
+                ))
+            })
+    }
+}
+
+impl UPayload {
+    /// Gets the data contained in this payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `region` - The shared memory region to resolve `Data::Reference`s against, if any.
+    ///
+    /// # Returns
+    ///
+    /// The payload's data, or `None` if the payload does not contain any data.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload refers to shared memory, but
+    /// - no region has been given,
+    /// - the payload does not have a valid length, or
+    /// - the referenced range does not lie within the region.
+    pub fn resolve_data<'a>(
+        &'a self,
+        region: Option<&'a dyn SharedMemoryRegion>,
+    ) -> Result<Option<&'a [u8]>, SerializationError> {
+        match &self.data {
+            Some(Data::Value(bytes)) => Ok(Some(bytes.as_slice())),
+            Some(Data::Reference(offset)) => {
+                let Some(region) = region else {
+                    return Err(SerializationError::new(
+                        "UPayload refers to shared memory, but no region is available",
+                    ));
+                };
+                let length = self
+                    .length
+                    .and_then(|length| usize::try_from(length).ok())
+                    .ok_or_else(|| {
+                        SerializationError::new("UPayload reference has no valid length")
+                    })?;
+                region.view(*offset, length).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct HeapRegion(Vec<u8>);
+
+    impl SharedMemoryRegion for HeapRegion {
+        fn map(&self) -> &[u8] {
+            &self.0
+        }
+    }
+
+    fn reference_payload(offset: u64, length: Option<i32>) -> UPayload {
+        UPayload {
+            data: Some(Data::Reference(offset)),
+            length,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_reference() {
+        let region = HeapRegion(vec![0, 1, 2, 3, 4, 5]);
+        let payload = reference_payload(2, Some(3));
+
+        let data = payload.resolve_data(Some(&region)).unwrap();
+        assert_eq!(data, Some([2, 3, 4].as_slice()));
+    }
+
+    #[test]
+    fn test_resolve_value_without_region() {
+        let payload = UPayload {
+            data: Some(Data::Value(vec![1, 2, 3])),
+            ..Default::default()
+        };
+
+        let data = payload.resolve_data(None).unwrap();
+        assert_eq!(data, Some([1, 2, 3].as_slice()));
+        assert_eq!(UPayload::default().resolve_data(None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_resolve_reference_fails_without_region() {
+        let payload = reference_payload(0, Some(1));
+        assert!(payload.resolve_data(None).is_err());
+    }
+
+    #[test]
+    fn test_resolve_reference_fails_for_invalid_length() {
+        let region = HeapRegion(vec![0, 1, 2, 3]);
+        assert!(reference_payload(0, None)
+            .resolve_data(Some(&region))
+            .is_err());
+        assert!(reference_payload(0, Some(-1))
+            .resolve_data(Some(&region))
+            .is_err());
+    }
+
+    #[test]
+    fn test_resolve_reference_fails_for_out_of_bounds_range() {
+        let region = HeapRegion(vec![0, 1, 2, 3]);
+        assert!(reference_payload(2, Some(3))
+            .resolve_data(Some(&region))
+            .is_err());
+        assert!(reference_payload(u64::MAX, Some(1))
+            .resolve_data(Some(&region))
+            .is_err());
+    }
+}
@@ -0,0 +1,209 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::transport::datamodel::{emit, LogLevel, UListener};
+use crate::transport::validator::{UAttributesValidator, Validators};
+use crate::uprotocol::{UCode, UMessage, UStatus};
+use crate::uuid::builder::{SystemClock, UClock};
+
+type ErrorHandler = Box<dyn Fn(&UMessage, UStatus) + Send + Sync + 'static>;
+
+/// `TtlEnforcingListener` wraps a listener so that it is not called with messages that have expired by the time
+/// they are received, e.g. commands that have been queued by a transport for longer than their time-to-live.
+///
+/// A message has expired if its attributes have a `ttl` greater than 0 and the time of its ID, plus the `ttl`,
+/// is not later than the current time, as checked by [`UAttributesValidator::is_expired_at`]. Messages without
+/// attributes, without a `ttl` or without a uProtocol UUID are passed on, as are statuses about messages that cannot
+/// be delivered.
+///
+/// Expired messages are dropped, counted in [`TtlEnforcingListener::expired`], and reported to the handler set with
+/// [`TtlEnforcingListener::on_error`], if any, along with a `UStatus` with code `DEADLINE_EXCEEDED`.
+pub struct TtlEnforcingListener {
+    listener: UListener,
+    clock: Box<dyn UClock>,
+    on_error: Option<ErrorHandler>,
+    expired: AtomicU64,
+}
+
+impl TtlEnforcingListener {
+    /// Creates a listener that takes the current time from the platform.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to call with the messages that have not expired.
+    pub fn new(listener: UListener) -> Self {
+        Self::with_clock(listener, SystemClock)
+    }
+
+    /// Creates a listener that takes the current time from the given clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to call with the messages that have not expired.
+    /// * `clock` - The clock that the expiry of messages is checked with.
+    pub fn with_clock<C>(listener: UListener, clock: C) -> Self
+    where
+        C: UClock + 'static,
+    {
+        TtlEnforcingListener {
+            listener,
+            clock: Box::new(clock),
+            on_error: None,
+            expired: AtomicU64::new(0),
+        }
+    }
+
+    /// Sets a handler that is called with each message that is dropped, e.g. for logging it or for sending an
+    /// error response to an expired request.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to call with the expired message and the reason for dropping it.
+    #[must_use]
+    pub fn on_error<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&UMessage, UStatus) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Box::new(handler));
+        self
+    }
+
+    /// Gets the number of messages that have been dropped as expired.
+    pub fn expired(&self) -> u64 {
+        self.expired.load(Ordering::Relaxed)
+    }
+
+    /// Passes a received message on to the wrapped listener, unless it has expired.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The received message, or the status about a message that cannot be delivered.
+    pub fn on_receive(&self, result: Result<UMessage, UStatus>) {
+        if let Ok(message) = &result {
+            if let Some(status) = self.expiry(message) {
+                self.expired.fetch_add(1, Ordering::Relaxed);
+                emit(
+                    LogLevel::Warn,
+                    module_path!(),
+                    format_args!("Dropping received message: {status}"),
+                );
+                if let Some(on_error) = &self.on_error {
+                    on_error(message, status);
+                }
+                return;
+            }
+        }
+        (self.listener)(result);
+    }
+
+    /// Converts this into a listener that can be registered with a [`UTransport`](crate::transport::datamodel::UTransport).
+    ///
+    /// The count of expired messages stays available through the `Arc`, if it is kept.
+    pub fn into_listener(self: Arc<Self>) -> UListener {
+        Box::new(move |result| self.on_receive(result))
+    }
+
+    fn expiry(&self, message: &UMessage) -> Option<UStatus> {
+        let attributes = message.attributes.as_ref()?;
+        match Validators::get_validator(attributes).is_expired_at(attributes, self.clock.as_ref()) {
+            // a clock that cannot tell the time does not make messages expire
+            Err(error) if error.code() == UCode::DeadlineExceeded => Some(UStatus::from(error)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::{UPriority, Uuid};
+    use crate::uuid::builder::{SystemRandomSource, UUIDv8Builder};
+
+    const NOW: u64 = 0x18C684468F8; // Thu, 14 Dec 2023 12:19:23 GMT
+
+    struct FixedClock(u64);
+    impl UClock for FixedClock {
+        fn now(&self) -> Option<u64> {
+            Some(self.0)
+        }
+    }
+
+    fn uuid_at(instant: u64) -> Uuid {
+        UUIDv8Builder::with_sources(FixedClock(instant), &mut SystemRandomSource).build()
+    }
+
+    fn message(sent: u64, ttl: Option<i32>) -> UMessage {
+        let mut attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        attributes.id = Some(uuid_at(sent));
+        attributes.ttl = ttl;
+        UMessage {
+            attributes: Some(attributes),
+            ..Default::default()
+        }
+    }
+
+    fn counting_listener() -> (UListener, Arc<Mutex<Vec<Result<UMessage, UStatus>>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        let listener: UListener = Box::new(move |result| sink.lock().unwrap().push(result));
+        (listener, received)
+    }
+
+    #[test]
+    fn test_expired_message_is_dropped() {
+        let (listener, received) = counting_listener();
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&errors);
+        let listener = TtlEnforcingListener::with_clock(listener, FixedClock(NOW))
+            .on_error(move |_, status| sink.lock().unwrap().push(status.get_code()));
+
+        listener.on_receive(Ok(message(NOW - 1000, Some(1000))));
+        assert!(received.lock().unwrap().is_empty());
+        assert_eq!(listener.expired(), 1);
+        assert_eq!(*errors.lock().unwrap(), vec![UCode::DeadlineExceeded]);
+    }
+
+    #[test]
+    fn test_live_messages_are_passed_on() {
+        let (listener, received) = counting_listener();
+        let listener = TtlEnforcingListener::with_clock(listener, FixedClock(NOW));
+
+        listener.on_receive(Ok(message(NOW - 999, Some(1000))));
+        listener.on_receive(Ok(message(NOW - 5000, None)));
+        listener.on_receive(Ok(message(NOW - 5000, Some(0))));
+        listener.on_receive(Ok(message(NOW + 5000, Some(1))));
+        listener.on_receive(Ok(UMessage::default()));
+        listener.on_receive(Err(UStatus::fail_with_code(UCode::Internal, "failed")));
+        assert_eq!(received.lock().unwrap().len(), 6);
+        assert_eq!(listener.expired(), 0);
+    }
+
+    #[test]
+    fn test_into_listener_keeps_count() {
+        let (listener, received) = counting_listener();
+        let enforcing = Arc::new(TtlEnforcingListener::with_clock(listener, FixedClock(NOW)));
+        let listener = Arc::clone(&enforcing).into_listener();
+
+        listener(Ok(message(NOW - 2000, Some(1000))));
+        listener(Ok(message(NOW, Some(1000))));
+        assert_eq!(received.lock().unwrap().len(), 1);
+        assert_eq!(enforcing.expired(), 1);
+    }
+}
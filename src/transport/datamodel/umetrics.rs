@@ -0,0 +1,36 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::Arc;
+
+/// A sink for the measurements taken by the SDK, e.g. by the [`LatencyMonitor`](crate::transport::datamodel::LatencyMonitor),
+/// which passes them on to the metrics system of the platform, like Prometheus or OpenTelemetry.
+///
+/// Metrics are identified by their names, which are dot-separated and start with `uprotocol.`, like
+/// `uprotocol.latency.p99`. Implementations are called from the threads that receive messages, so they need to
+/// return quickly.
+pub trait UMetrics: Send + Sync {
+    /// Records the current value of a metric.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the metric.
+    /// * `value` - The value of the metric, in the unit given by its name, e.g. milliseconds for latencies.
+    fn record(&self, name: &str, value: u64);
+}
+
+impl<M: UMetrics + ?Sized> UMetrics for Arc<M> {
+    fn record(&self, name: &str, value: u64) {
+        (**self).record(name, value);
+    }
+}
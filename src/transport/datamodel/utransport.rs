@@ -13,8 +13,13 @@
 
 use async_trait::async_trait;
 
+use crate::transport::validator::Validated;
 use crate::uprotocol::{UAttributes, UEntity, UMessage, UPayload, UStatus, UUri};
 
+/// A listener for the messages received by a [`UTransport`], which is called with the message, or with a
+/// `UStatus` if a message has been received but cannot be delivered.
+pub type UListener = Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>;
+
 /// `UTransport` is the uP-L1 interface that provides a common API for uE developers to send and receive messages.
 ///
 /// Implementations of `UTransport` contain the details for connecting to the underlying transport technology and
@@ -48,6 +53,27 @@ pub trait UTransport {
         attributes: UAttributes,
     ) -> Result<(), UStatus>;
 
+    /// Transmits a message that has been validated already, e.g. by the [`UMessageBuilder`](crate::transport::builder::UMessageBuilder)
+    /// that has built it, to the topic that is the message's source.
+    ///
+    /// Transports override this method to skip the validation of the message's attributes that [`UTransport::send`]
+    /// performs. The default implementation passes the message to [`UTransport::send`].
+    ///
+    /// # Arguments
+    /// * `message` - The validated message.
+    ///
+    /// # Returns
+    /// Returns () on success, otherwise an Err(UStatus) with the appropriate failure information.
+    async fn send_validated(&self, message: Validated<UMessage>) -> Result<(), UStatus> {
+        let message = message.into_inner();
+        self.send(
+            message.source.unwrap_or_default(),
+            message.payload.unwrap_or_default(),
+            message.attributes.unwrap_or_default(),
+        )
+        .await
+    }
+
     /// Registers a listener to be called asynchronously when `UMessage` is received for the specified topic.
     ///
     /// # Arguments
@@ -60,11 +86,7 @@ pub trait UTransport {
     /// Asynchronously returns a `Result<String, UStatus>`.
     /// On success, returns a `String` containing an identifier that can be used for unregistering the listener later.
     /// On failure, returns `Err(UStatus)` with the appropriate failure information.
-    async fn register_listener(
-        &self,
-        topic: UUri,
-        listener: Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>,
-    ) -> Result<String, UStatus>;
+    async fn register_listener(&self, topic: UUri, listener: UListener) -> Result<String, UStatus>;
 
     /// Unregister a listener for a given topic. Messages arriving on this topic will no longer be processed
     /// by this listener.
@@ -0,0 +1,118 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use prost::Message;
+
+use crate::transport::datamodel::SharedMemoryRegion;
+use crate::uprotocol::{Data, UAttributes, UCode, UMessage, UPayload, UStatus, UUri};
+
+const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// The contents of a sample received by means of iceoryx2, which the payloads of received messages refer to.
+pub(crate) struct SampleRegion<'a>(pub(crate) &'a [u8]);
+
+impl SharedMemoryRegion for SampleRegion<'_> {
+    fn map(&self) -> &[u8] {
+        self.0
+    }
+}
+
+/// Encodes the part of a sample that precedes the payload data: the length of the header as 32-bit big endian
+/// integer, followed by a `UMessage` without payload data in its protobuf encoding.
+pub(crate) fn encode_header(
+    topic: UUri,
+    attributes: UAttributes,
+    payload: &UPayload,
+) -> Result<Vec<u8>, UStatus> {
+    let header = UMessage {
+        source: Some(topic),
+        attributes: Some(attributes),
+        payload: Some(UPayload {
+            data: None,
+            length: payload.length,
+            format: payload.format,
+        }),
+    };
+    let length = header.encoded_len();
+    let prefix = u32::try_from(length).map_err(|_| {
+        UStatus::fail_with_code(UCode::ResourceExhausted, "Message header is too large")
+    })?;
+    let mut buffer = Vec::with_capacity(LENGTH_PREFIX_SIZE + length);
+    buffer.extend_from_slice(&prefix.to_be_bytes());
+    header
+        .encode(&mut buffer)
+        .map_err(|e| UStatus::fail_with_code(UCode::Internal, &e.to_string()))?;
+    Ok(buffer)
+}
+
+/// Decodes a sample into a message whose payload refers to the data following the header, by means of a
+/// `Data::Reference` to be resolved against a [`SampleRegion`] of the sample.
+pub(crate) fn decode_sample(sample: &[u8]) -> Result<UMessage, UStatus> {
+    let invalid = || UStatus::fail_with_code(UCode::InvalidArgument, "Malformed iceoryx2 sample");
+
+    let prefix = sample.get(..LENGTH_PREFIX_SIZE).ok_or_else(invalid)?;
+    let length = u32::from_be_bytes(prefix.try_into().map_err(|_| invalid())?) as usize;
+    let data_offset = LENGTH_PREFIX_SIZE.checked_add(length).ok_or_else(invalid)?;
+    let header = sample
+        .get(LENGTH_PREFIX_SIZE..data_offset)
+        .ok_or_else(invalid)?;
+    let mut message = UMessage::decode(header).map_err(|_| invalid())?;
+
+    let data_length = i32::try_from(sample.len() - data_offset).map_err(|_| invalid())?;
+    let payload = message.payload.take().unwrap_or_default();
+    message.payload = Some(UPayload {
+        data: Some(Data::Reference(data_offset as u64)),
+        length: Some(data_length),
+        ..payload
+    });
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::builder::{UAttributesBuilder, UPayloadBuilder};
+    use crate::uprotocol::UPriority;
+    use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+
+    #[test]
+    fn test_decode_encoded_sample() {
+        let topic =
+            LongUriSerializer::deserialize("/body.access/1/door.front_left#Door".to_string())
+                .unwrap();
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        let payload = UPayloadBuilder::from_text("open").build().unwrap();
+
+        let mut sample = encode_header(topic.clone(), attributes.clone(), &payload).unwrap();
+        sample.extend_from_slice(payload.resolve_data(None).unwrap().unwrap());
+        let message = decode_sample(&sample).unwrap();
+
+        assert_eq!(message.source, Some(topic));
+        assert_eq!(message.attributes, Some(attributes));
+        let received = message.payload.unwrap();
+        assert!(matches!(received.data, Some(Data::Reference(_))));
+        assert_eq!(received.format, payload.format);
+        assert_eq!(
+            received.resolve_data(Some(&SampleRegion(&sample))).unwrap(),
+            Some(b"open".as_slice())
+        );
+    }
+
+    #[test]
+    fn test_decode_sample_fails_for_truncated_header() {
+        assert!(decode_sample(&[0, 0]).is_err());
+        assert!(decode_sample(&[0, 0, 0, 8, 1, 2]).is_err());
+        assert!(decode_sample(&[0xff, 0xff, 0xff, 0xff]).is_err());
+    }
+}
@@ -0,0 +1,478 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use iceoryx2::node::Node;
+use iceoryx2::port::publisher::Publisher;
+use iceoryx2::port::subscriber::Subscriber;
+use iceoryx2::prelude::*;
+use iceoryx2::service::port_factory::publish_subscribe::PortFactory;
+
+use crate::transport::datamodel::{SharedMemoryRegion, UTransport};
+use crate::transport::iceoryx2::iceoryx2sample::{decode_sample, encode_header, SampleRegion};
+use crate::transport::validator::Validators;
+use crate::uprotocol::{Data, UAttributes, UCode, UEntity, UMessage, UPayload, UStatus, UUri};
+use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+use crate::uuid::builder::UUIDv8Builder;
+
+/// The maximum size of a sample, i.e. of a message's attributes and payload data, if not configured otherwise.
+pub const DEFAULT_MAX_SAMPLE_SIZE: usize = 64 * 1024;
+
+/// The interval in which receiving threads check for new samples.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The prefix of the names of the iceoryx2 services that topics are mapped to.
+const SERVICE_NAME_PREFIX: &str = "uprotocol";
+
+type Listener = Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>;
+
+/// A listener that is passed received messages along with the sample they have been received in.
+///
+/// The payload of each message refers to the sample by means of a `Data::Reference`, which is resolved by passing
+/// the region to [`UPayload::resolve_data`]. The region is only valid for the duration of the call, which allows
+/// processing payloads without copying them out of shared memory.
+pub type SampleListener =
+    Box<dyn Fn(Result<(UMessage, &dyn SharedMemoryRegion), UStatus>) + Send + Sync + 'static>;
+
+// A message to be published by the publishing thread, along with the channel to report the outcome to.
+struct Publication {
+    service: String,
+    header: Vec<u8>,
+    payload: UPayload,
+    result: mpsc::Sender<Result<(), UStatus>>,
+}
+
+/// `UTransport` for uEntities on the same host on top of [iceoryx2](https://iceoryx.io), which exchanges messages
+/// by means of shared memory.
+///
+/// Each topic is mapped to an iceoryx2 publish-subscribe service named after the topic's long URI. A message is sent
+/// as a single sample, which contains the message's attributes followed by its payload data. The payload data is
+/// written to the loaned sample directly, and receivers registered by means of
+/// [`Iceoryx2Transport::register_sample_listener`] read it from shared memory without copying it.
+///
+/// iceoryx2 ports cannot be shared between threads, so samples are published by a dedicated thread, and each
+/// registered listener is served by a thread of its own.
+pub struct Iceoryx2Transport {
+    publications: Mutex<mpsc::Sender<Publication>>,
+    registrations: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    max_sample_size: usize,
+    uuid_builder: UUIDv8Builder,
+}
+
+impl Iceoryx2Transport {
+    /// Creates a transport that sends samples of up to [`DEFAULT_MAX_SAMPLE_SIZE`] bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::Unavailable` if no iceoryx2 node can be created.
+    pub fn new() -> Result<Iceoryx2Transport, UStatus> {
+        Self::with_max_sample_size(DEFAULT_MAX_SAMPLE_SIZE)
+    }
+
+    /// Creates a transport with a maximum sample size.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_sample_size` - The maximum size of a message's attributes and payload data in bytes, which
+    ///   determines the amount of shared memory that each publisher allocates.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::Unavailable` if no iceoryx2 node can be created.
+    pub fn with_max_sample_size(max_sample_size: usize) -> Result<Iceoryx2Transport, UStatus> {
+        let (sender, receiver) = mpsc::channel::<Publication>();
+        let (ready_sender, ready_receiver) = mpsc::channel();
+
+        // the thread ends once the transport, and with it the sending end of the channel, has been dropped
+        thread::spawn(move || {
+            let node = match create_node() {
+                Ok(node) => {
+                    let _ = ready_sender.send(Ok(()));
+                    node
+                }
+                Err(status) => {
+                    let _ = ready_sender.send(Err(status));
+                    return;
+                }
+            };
+            let mut publishers = HashMap::new();
+            for publication in receiver {
+                let result = publish(&node, &mut publishers, max_sample_size, &publication);
+                let _ = publication.result.send(result);
+            }
+        });
+        ready_receiver
+            .recv()
+            .map_err(|_| unavailable("Publishing thread has ended"))??;
+
+        Ok(Iceoryx2Transport {
+            publications: Mutex::new(sender),
+            registrations: Mutex::new(HashMap::new()),
+            max_sample_size,
+            uuid_builder: UUIDv8Builder::new(),
+        })
+    }
+
+    /// Registers a listener that processes payloads in the shared memory they have been received in.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to receive messages from.
+    /// * `listener` - The listener to pass received messages to, along with the region their payloads refer to.
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the registered listener, to be passed to [`UTransport::unregister_listener`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code
+    /// - `UCode::InvalidArgument` if the topic cannot be mapped to a service name,
+    /// - `UCode::Unavailable` if the service cannot be opened or subscribed to.
+    pub fn register_sample_listener(
+        &self,
+        topic: &UUri,
+        listener: SampleListener,
+    ) -> Result<String, UStatus> {
+        let service = service_name(topic)?;
+        let closed = Arc::new(AtomicBool::new(false));
+        let (ready_sender, ready_receiver) = mpsc::channel();
+
+        let receiving_closed = closed.clone();
+        thread::spawn(move || {
+            let (_node, subscriber) = match create_node().and_then(|node| {
+                let subscriber = subscribe(&node, &service)?;
+                Ok((node, subscriber))
+            }) {
+                Ok(ports) => {
+                    let _ = ready_sender.send(Ok(()));
+                    ports
+                }
+                Err(status) => {
+                    let _ = ready_sender.send(Err(status));
+                    return;
+                }
+            };
+            while !receiving_closed.load(Ordering::Relaxed) {
+                loop {
+                    match subscriber.receive() {
+                        Ok(Some(sample)) => {
+                            let region = SampleRegion(sample.payload());
+                            listener(
+                                decode_sample(region.0)
+                                    .map(|message| (message, &region as &dyn SharedMemoryRegion)),
+                            );
+                        }
+                        Ok(None) => break,
+                        Err(e) => {
+                            listener(Err(unavailable(&format!("Failed to receive sample: {e}"))));
+                            break;
+                        }
+                    }
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+        });
+        ready_receiver
+            .recv()
+            .map_err(|_| unavailable("Receiving thread has ended"))??;
+
+        let id = self.uuid_builder.build().to_hyphenated_string();
+        self.registrations
+            .lock()
+            .unwrap()
+            .insert(id.clone(), closed);
+        Ok(id)
+    }
+}
+
+impl Drop for Iceoryx2Transport {
+    fn drop(&mut self) {
+        for closed in self.registrations.lock().unwrap().values() {
+            closed.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+#[async_trait]
+impl UTransport for Iceoryx2Transport {
+    // iceoryx2 restricts access to services by means of the permissions of the shared memory they use.
+    async fn authenticate(&self, _entity: UEntity) -> Result<(), UStatus> {
+        Err(UStatus::fail_with_code(
+            UCode::Unimplemented,
+            "Access to iceoryx2 services is controlled by shared memory permissions",
+        ))
+    }
+
+    async fn send(
+        &self,
+        topic: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+    ) -> Result<(), UStatus> {
+        Validators::get_validator(&attributes)
+            .validate(&attributes)
+            .map_err(UStatus::from)?;
+        let service = service_name(&topic)?;
+        // shared memory references of the sender are meaningless to receivers, so referenced data is rejected
+        let data_length = payload.resolve_data(None)?.map_or(0, <[u8]>::len);
+        let header = encode_header(topic, attributes, &payload)?;
+        if header.len() + data_length > self.max_sample_size {
+            return Err(UStatus::fail_with_code(
+                UCode::ResourceExhausted,
+                &format!(
+                    "Message of {} bytes exceeds maximum sample size of {} bytes",
+                    header.len() + data_length,
+                    self.max_sample_size
+                ),
+            ));
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        self.publications
+            .lock()
+            .unwrap()
+            .send(Publication {
+                service,
+                header,
+                payload,
+                result: sender,
+            })
+            .map_err(|_| unavailable("Publishing thread has ended"))?;
+        receiver
+            .recv()
+            .map_err(|_| unavailable("Publishing thread has ended"))?
+    }
+
+    async fn register_listener(&self, topic: UUri, listener: Listener) -> Result<String, UStatus> {
+        self.register_sample_listener(
+            &topic,
+            Box::new(move |received| {
+                listener(received.and_then(|(message, region)| into_owned(message, region)));
+            }),
+        )
+    }
+
+    async fn unregister_listener(&self, _topic: UUri, listener: &str) -> Result<(), UStatus> {
+        let Some(closed) = self.registrations.lock().unwrap().remove(listener) else {
+            return Err(UStatus::fail_with_code(
+                UCode::NotFound,
+                &format!("No listener with ID {listener}"),
+            ));
+        };
+        closed.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+fn unavailable(message: &str) -> UStatus {
+    UStatus::fail_with_code(UCode::Unavailable, message)
+}
+
+fn service_name(topic: &UUri) -> Result<String, UStatus> {
+    let uri = LongUriSerializer::serialize(topic)
+        .map_err(|e| UStatus::fail_with_code(UCode::InvalidArgument, &e.to_string()))?;
+    Ok(format!("{SERVICE_NAME_PREFIX}{uri}"))
+}
+
+fn create_node() -> Result<Node<ipc::Service>, UStatus> {
+    NodeBuilder::new()
+        .create::<ipc::Service>()
+        .map_err(|e| unavailable(&format!("Failed to create iceoryx2 node: {e}")))
+}
+
+fn open_service(
+    node: &Node<ipc::Service>,
+    name: &str,
+) -> Result<PortFactory<ipc::Service, [u8], ()>, UStatus> {
+    let name = ServiceName::new(name)
+        .map_err(|e| UStatus::fail_with_code(UCode::InvalidArgument, &e.to_string()))?;
+    node.service_builder(&name)
+        .publish_subscribe::<[u8]>()
+        .open_or_create()
+        .map_err(|e| unavailable(&format!("Failed to open iceoryx2 service: {e}")))
+}
+
+fn subscribe(
+    node: &Node<ipc::Service>,
+    name: &str,
+) -> Result<Subscriber<ipc::Service, [u8], ()>, UStatus> {
+    open_service(node, name)?
+        .subscriber_builder()
+        .create()
+        .map_err(|e| unavailable(&format!("Failed to create iceoryx2 subscriber: {e}")))
+}
+
+// Writes a publication's header and payload data into a sample loaned from the service's publisher, which is
+// created when publishing to the service for the first time.
+fn publish(
+    node: &Node<ipc::Service>,
+    publishers: &mut HashMap<String, Publisher<ipc::Service, [u8], ()>>,
+    max_sample_size: usize,
+    publication: &Publication,
+) -> Result<(), UStatus> {
+    if !publishers.contains_key(&publication.service) {
+        let publisher = open_service(node, &publication.service)?
+            .publisher_builder()
+            .max_slice_len(max_sample_size)
+            .create()
+            .map_err(|e| unavailable(&format!("Failed to create iceoryx2 publisher: {e}")))?;
+        publishers.insert(publication.service.clone(), publisher);
+    }
+    let publisher = &publishers[&publication.service];
+
+    let header = &publication.header;
+    let data = publication.payload.resolve_data(None)?.unwrap_or_default();
+    let sample = publisher
+        .loan_slice_uninit(header.len() + data.len())
+        .map_err(|e| unavailable(&format!("Failed to loan iceoryx2 sample: {e}")))?;
+    let sample = sample.write_from_fn(|index| match index.checked_sub(header.len()) {
+        None => header[index],
+        Some(index) => data[index],
+    });
+    sample
+        .send()
+        .map(|_| ())
+        .map_err(|e| unavailable(&format!("Failed to send iceoryx2 sample: {e}")))
+}
+
+// Copies the payload data of a received message out of the sample.
+fn into_owned(mut message: UMessage, region: &dyn SharedMemoryRegion) -> Result<UMessage, UStatus> {
+    if let Some(payload) = message.payload.as_mut() {
+        let data = payload.resolve_data(Some(region))?.map(<[u8]>::to_vec);
+        payload.data = data.map(Data::Value);
+    }
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::builder::{UAttributesBuilder, UPayloadBuilder};
+    use crate::uprotocol::UPriority;
+
+    // Each test uses topics of its own, as iceoryx2 services are visible to all processes on the host.
+    fn topic(resource: &str) -> UUri {
+        LongUriSerializer::deserialize(format!(
+            "/body.access/1/{resource}.{}",
+            UUIDv8Builder::new().build().to_hyphenated_string()
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_publish_and_receive() {
+        let transport = Iceoryx2Transport::new().unwrap();
+        let topic = topic("door");
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let listener = transport
+            .register_listener(
+                topic.clone(),
+                Box::new(move |message| sender.lock().unwrap().send(message).unwrap()),
+            )
+            .await
+            .unwrap();
+
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        let payload = UPayloadBuilder::from_text("open").build().unwrap();
+        transport
+            .send(topic.clone(), payload.clone(), attributes.clone())
+            .await
+            .unwrap();
+
+        let message = receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.source, Some(topic.clone()));
+        assert_eq!(message.attributes, Some(attributes));
+        assert_eq!(message.payload.unwrap().data, payload.data);
+
+        transport
+            .unregister_listener(topic.clone(), &listener)
+            .await
+            .unwrap();
+        assert_eq!(
+            transport
+                .unregister_listener(topic, &listener)
+                .await
+                .unwrap_err()
+                .get_code(),
+            UCode::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sample_listener_reads_payload_from_shared_memory() {
+        let transport = Iceoryx2Transport::new().unwrap();
+        let topic = topic("window");
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        transport
+            .register_sample_listener(
+                &topic,
+                Box::new(move |received| {
+                    let (message, region) = received.unwrap();
+                    let payload = message.payload.unwrap();
+                    let is_reference = matches!(payload.data, Some(Data::Reference(_)));
+                    let data = payload
+                        .resolve_data(Some(region))
+                        .unwrap()
+                        .unwrap()
+                        .to_vec();
+                    sender.lock().unwrap().send((is_reference, data)).unwrap();
+                }),
+            )
+            .unwrap();
+
+        let payload = UPayloadBuilder::from_text("closed").build().unwrap();
+        transport
+            .send(
+                topic,
+                payload,
+                UAttributesBuilder::publish(UPriority::UpriorityCs1).build(),
+            )
+            .await
+            .unwrap();
+
+        let (is_reference, data) = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(is_reference);
+        assert_eq!(data, b"closed");
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_for_oversized_message() {
+        let transport = Iceoryx2Transport::with_max_sample_size(16).unwrap();
+        let payload = UPayloadBuilder::from_text("this does not fit into a sample")
+            .build()
+            .unwrap();
+
+        let status = transport
+            .send(
+                topic("trunk"),
+                payload,
+                UAttributesBuilder::publish(UPriority::UpriorityCs1).build(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(status.get_code(), UCode::ResourceExhausted);
+    }
+}
@@ -0,0 +1,442 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+
+use crate::transport::mqtt5::SerializationError;
+use crate::uprotocol::uattributes::HEADER_REQID;
+use crate::uprotocol::{Remote, UAttributes, UAuthority, UEntity, UResource, UUri, Uuid};
+
+/// The MQTT5 specific representation of a message's `UAttributes`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mqtt5Properties {
+    /// MQTT5 user properties, as (key, value) pairs.
+    pub user_properties: Vec<(String, String)>,
+    /// MQTT5 correlation data, carrying the request ID of RPC responses (the UUID's 16 bytes in big endian order).
+    pub correlation_data: Option<Vec<u8>>,
+    /// MQTT5 message expiry interval, in seconds, derived from the message's time-to-live.
+    pub message_expiry_interval: Option<u32>,
+}
+
+/// Maps uProtocol message attributes and addresses to MQTT5 concepts, independent of any particular MQTT client library.
+///
+/// `UAttributes` are carried in MQTT5 user properties, named as by [`UAttributes::to_header_map`], except for the
+/// request ID which is carried in the correlation data. The time-to-live is additionally reflected in the message
+/// expiry interval, so that brokers can discard stale messages.
+///
+/// `UUri`s are mapped to topic names consisting of the authority, entity, entity version and resource segments,
+/// e.g. `vcu.vin/body.access/1/door.front_left` for a remote and `/body.access/1/door.front_left` for a local `UUri`.
+pub struct Mqtt5Mapping;
+
+impl Mqtt5Mapping {
+    /// Maps `UAttributes` to MQTT5 properties.
+    ///
+    /// # Arguments
+    ///
+    /// * `attributes` - The attributes to map.
+    ///
+    /// # Returns
+    ///
+    /// The MQTT5 properties to publish the message with.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the attributes contain a sink that cannot be serialized.
+    pub fn to_properties(attributes: &UAttributes) -> Result<Mqtt5Properties, SerializationError> {
+        let mut headers = attributes.to_header_map()?;
+        headers.remove(HEADER_REQID);
+        let mut user_properties: Vec<(String, String)> = headers.into_iter().collect();
+        user_properties.sort();
+
+        Ok(Mqtt5Properties {
+            user_properties,
+            correlation_data: attributes
+                .reqid
+                .as_ref()
+                .map(|reqid| <[u8; 16]>::from(reqid).to_vec()),
+            message_expiry_interval: attributes
+                .ttl
+                .and_then(|ttl| u32::try_from(ttl).ok())
+                .filter(|ttl| *ttl > 0)
+                .map(|ttl| ttl / 1000 + u32::from(ttl % 1000 != 0)),
+        })
+    }
+
+    /// Maps MQTT5 properties back to `UAttributes`.
+    ///
+    /// User properties that do not represent a uProtocol attribute are ignored, and so is the message expiry interval
+    /// (the time-to-live is restored from the user properties, in its original millisecond resolution).
+    ///
+    /// # Arguments
+    ///
+    /// * `properties` - The properties of a received MQTT5 message.
+    ///
+    /// # Returns
+    ///
+    /// The message's attributes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if any of the uProtocol properties has an invalid value.
+    pub fn from_properties(
+        properties: &Mqtt5Properties,
+    ) -> Result<UAttributes, SerializationError> {
+        let headers: HashMap<String, String> = properties.user_properties.iter().cloned().collect();
+        let mut attributes = UAttributes::from_header_map(&headers)?;
+
+        if let Some(correlation_data) = &properties.correlation_data {
+            let bytes = <[u8; 16]>::try_from(correlation_data.as_slice()).map_err(|_e| {
+                SerializationError::new("Correlation data is not a valid request ID")
+            })?;
+            attributes.reqid = Some(Uuid::from(bytes));
+        }
+
+        Ok(attributes)
+    }
+
+    /// Maps a `UUri` to an MQTT5 topic name.
+    ///
+    /// The topic name consists of four segments, the authority name, the entity, the entity's major version and
+    /// the resource, e.g. `vcu.vin/body.access:1234/1/door.front_left~Door:8001`. The entity and resource segments
+    /// carry their names, followed by `:` and their ID in hex if they have one, so that URIs in micro form, which
+    /// have IDs only, are mapped as well. The resource segment carries the resource's message after a `~`.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The `UUri` to map.
+    ///
+    /// # Returns
+    ///
+    /// The topic name, which [`Mqtt5Mapping::from_topic`] maps back to the same `UUri`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if
+    /// - the `UUri` does not contain an entity with a name or ID, or refers to a remote authority by other means
+    ///   than its name,
+    /// - any of the names contains a character that is not allowed in MQTT5 topic names (`/`, `+`, `#`), or one of
+    ///   the separators `:` and `~`.
+    pub fn to_topic(uri: &UUri) -> Result<String, SerializationError> {
+        let authority = match &uri.authority {
+            Some(UAuthority {
+                remote: Some(Remote::Name(name)),
+            }) => name.as_str(),
+            Some(UAuthority { remote: Some(_) }) => {
+                return Err(SerializationError::new(
+                    "Topic names require the authority to have a name",
+                ))
+            }
+            _ => "",
+        };
+        let Some(entity) = uri
+            .entity
+            .as_ref()
+            .filter(|entity| !entity.name.is_empty() || entity.id.is_some())
+        else {
+            return Err(SerializationError::new("UUri has no entity name or ID"));
+        };
+        let mut names = vec![authority, entity.name.as_str()];
+        if let Some(resource) = &uri.resource {
+            names.push(&resource.name);
+            names.extend(resource.instance.as_deref());
+            names.extend(resource.message.as_deref());
+        }
+        if names.iter().any(|name| {
+            name.contains(|c| matches!(c, '/' | '+' | '#' | ID_SEPARATOR | MESSAGE_SEPARATOR))
+        }) {
+            return Err(SerializationError::new(
+                "UUri contains characters not allowed in topic names",
+            ));
+        }
+
+        let version = entity
+            .version_major
+            .map(|version| version.to_string())
+            .unwrap_or_default();
+        let resource = uri
+            .resource
+            .as_ref()
+            .map(|resource| {
+                let mut segment = resource.name.clone();
+                if let Some(instance) = &resource.instance {
+                    segment.push('.');
+                    segment.push_str(instance);
+                }
+                if let Some(message) = &resource.message {
+                    segment.push(MESSAGE_SEPARATOR);
+                    segment.push_str(message);
+                }
+                with_id(segment, resource.id)
+            })
+            .unwrap_or_default();
+        Ok([
+            authority.to_string(),
+            with_id(entity.name.clone(), entity.id),
+            version,
+            resource,
+        ]
+        .join("/"))
+    }
+
+    /// Maps an MQTT5 topic name back to a `UUri`.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic name, as created by [`Mqtt5Mapping::to_topic`].
+    ///
+    /// # Returns
+    ///
+    /// The `UUri`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the topic name does not consist of four segments, does not contain an
+    /// entity name or ID, or contains an invalid entity version or ID.
+    pub fn from_topic(topic: &str) -> Result<UUri, SerializationError> {
+        let segments = topic.split('/').collect::<Vec<_>>();
+        let [authority, entity, version, resource] = segments.as_slice() else {
+            return Err(SerializationError::new(format!(
+                "Invalid topic name [{topic}]"
+            )));
+        };
+        let (entity_name, entity_id) = split_id(entity)?;
+        if entity_name.is_empty() && entity_id.is_none() {
+            return Err(SerializationError::new(format!(
+                "Topic name has no entity [{topic}]"
+            )));
+        }
+        let version_major = if version.is_empty() {
+            None
+        } else {
+            Some(version.parse::<u32>().map_err(|_e| {
+                SerializationError::new(format!("Invalid entity version [{version}]"))
+            })?)
+        };
+        let resource = if resource.is_empty() {
+            None
+        } else {
+            let (segment, id) = split_id(resource)?;
+            let (name_and_instance, message) = match segment.split_once(MESSAGE_SEPARATOR) {
+                Some((name_and_instance, message)) => (name_and_instance, Some(message)),
+                None => (segment, None),
+            };
+            Some(UResource {
+                id,
+                message: message.map(ToString::to_string),
+                ..UResource::from(name_and_instance)
+            })
+        };
+
+        Ok(UUri {
+            authority: (!authority.is_empty()).then(|| UAuthority {
+                remote: Some(Remote::Name(authority.to_string())),
+            }),
+            entity: Some(UEntity {
+                name: entity_name.to_string(),
+                id: entity_id,
+                version_major,
+                ..Default::default()
+            }),
+            resource,
+        })
+    }
+}
+
+// the separator of the name and the ID in the entity and resource segments of topic names
+const ID_SEPARATOR: char = ':';
+// the separator of the name and instance from the message in the resource segment of topic names
+const MESSAGE_SEPARATOR: char = '~';
+
+fn with_id(mut segment: String, id: Option<u32>) -> String {
+    if let Some(id) = id {
+        segment.push(ID_SEPARATOR);
+        segment.push_str(&format!("{id:x}"));
+    }
+    segment
+}
+
+fn split_id(segment: &str) -> Result<(&str, Option<u32>), SerializationError> {
+    let Some((name, id)) = segment.split_once(ID_SEPARATOR) else {
+        return Ok((segment, None));
+    };
+    let id = u32::from_str_radix(id, 16)
+        .map_err(|_e| SerializationError::new(format!("Invalid ID [{id}]")))?;
+    Ok((name, Some(id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::uattributes::HEADER_TTL;
+    use crate::uprotocol::UPriority;
+    use crate::uuid::builder::UUIDv8Builder;
+
+    fn build_uri_for_test(authority: Option<&str>) -> UUri {
+        UUri {
+            authority: authority.map(|name| UAuthority {
+                remote: Some(Remote::Name(name.to_string())),
+            }),
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                name: "door".to_string(),
+                instance: Some("front_left".to_string()),
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn test_request_attributes_round_trip() {
+        let attributes = UAttributesBuilder::request(
+            UPriority::UpriorityCs4,
+            build_uri_for_test(Some("vcu.vin")),
+            2500,
+        )
+        .with_token("someOAuthToken")
+        .with_permission_level(3)
+        .build();
+
+        let properties = Mqtt5Mapping::to_properties(&attributes).unwrap();
+        assert_eq!(properties.message_expiry_interval, Some(3));
+        assert!(properties.correlation_data.is_none());
+
+        let mapped = Mqtt5Mapping::from_properties(&properties).unwrap();
+        assert_eq!(attributes, mapped);
+    }
+
+    #[test]
+    fn test_response_attributes_carry_reqid_in_correlation_data() {
+        let reqid = UUIDv8Builder::new().build();
+        let attributes = UAttributesBuilder::response(
+            UPriority::UpriorityCs4,
+            build_uri_for_test(None),
+            reqid.clone(),
+        )
+        .with_commstatus(5)
+        .build();
+
+        let properties = Mqtt5Mapping::to_properties(&attributes).unwrap();
+        assert_eq!(
+            properties.correlation_data,
+            Some(<[u8; 16]>::from(&reqid).to_vec())
+        );
+        assert!(properties.message_expiry_interval.is_none());
+
+        let mapped = Mqtt5Mapping::from_properties(&properties).unwrap();
+        assert_eq!(attributes, mapped);
+    }
+
+    #[test]
+    fn test_from_properties_fails_for_invalid_values() {
+        let properties = Mqtt5Properties {
+            user_properties: vec![(HEADER_TTL.to_string(), "soon".to_string())],
+            ..Default::default()
+        };
+        assert!(Mqtt5Mapping::from_properties(&properties).is_err());
+
+        let properties = Mqtt5Properties {
+            correlation_data: Some(vec![1, 2, 3]),
+            ..Default::default()
+        };
+        assert!(Mqtt5Mapping::from_properties(&properties).is_err());
+    }
+
+    #[test]
+    fn test_topic_round_trip() {
+        for uri in [
+            build_uri_for_test(Some("vcu.vin")),
+            build_uri_for_test(None),
+        ] {
+            let topic = Mqtt5Mapping::to_topic(&uri).unwrap();
+            assert_eq!(Mqtt5Mapping::from_topic(&topic).unwrap(), uri);
+        }
+        assert_eq!(
+            Mqtt5Mapping::to_topic(&build_uri_for_test(Some("vcu.vin"))).unwrap(),
+            "vcu.vin/body.access/1/door.front_left"
+        );
+        assert_eq!(
+            Mqtt5Mapping::to_topic(&build_uri_for_test(None)).unwrap(),
+            "/body.access/1/door.front_left"
+        );
+    }
+
+    #[test]
+    fn test_topic_round_trip_with_ids_and_messages() {
+        let mut resolved = build_uri_for_test(Some("vcu.vin"));
+        resolved.entity.as_mut().unwrap().id = Some(0x1234);
+        resolved.resource = Some(UResource {
+            id: Some(0x8001),
+            ..UResource::from("door.front_left#Door")
+        });
+        let micro = UUri {
+            authority: None,
+            entity: Some(UEntity {
+                id: Some(0x1234),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                id: Some(0x8001),
+                ..Default::default()
+            }),
+        };
+        for uri in [resolved.clone(), micro.clone()] {
+            let topic = Mqtt5Mapping::to_topic(&uri).unwrap();
+            assert_eq!(Mqtt5Mapping::from_topic(&topic).unwrap(), uri);
+        }
+        assert_eq!(
+            Mqtt5Mapping::to_topic(&resolved).unwrap(),
+            "vcu.vin/body.access:1234/1/door.front_left~Door:8001"
+        );
+        assert_eq!(Mqtt5Mapping::to_topic(&micro).unwrap(), "/:1234/1/:8001");
+
+        let door = build_uri_for_test(None);
+        let mut window = door.clone();
+        window.resource.as_mut().unwrap().message = Some("Window".to_string());
+        assert_ne!(
+            Mqtt5Mapping::to_topic(&door).unwrap(),
+            Mqtt5Mapping::to_topic(&window).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_topic_fails_for_wildcards() {
+        let mut uri = build_uri_for_test(None);
+        uri.resource = Some(UResource::from("door.+"));
+        assert!(Mqtt5Mapping::to_topic(&uri).is_err());
+    }
+
+    #[test]
+    fn test_to_topic_fails_for_authority_without_name() {
+        let mut uri = build_uri_for_test(None);
+        uri.authority = Some(UAuthority {
+            remote: Some(Remote::Ip(vec![192, 168, 1, 100])),
+        });
+        assert!(Mqtt5Mapping::to_topic(&uri).is_err());
+    }
+
+    #[test]
+    fn test_from_topic_fails_for_invalid_topics() {
+        assert!(Mqtt5Mapping::from_topic("body.access/1/door").is_err());
+        assert!(Mqtt5Mapping::from_topic("//1/door").is_err());
+        assert!(Mqtt5Mapping::from_topic("/body.access/one/door").is_err());
+        assert!(Mqtt5Mapping::from_topic("/body.access:xyz/1/door").is_err());
+        assert!(Mqtt5Mapping::from_topic("/:/1/door").is_err());
+    }
+}
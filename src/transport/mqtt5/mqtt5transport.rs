@@ -0,0 +1,793 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use rumqttc::v5::mqttbytes::v5::{Packet, Publish, PublishProperties};
+use rumqttc::v5::mqttbytes::QoS;
+use rumqttc::v5::{AsyncClient, Event, EventLoop, MqttOptions};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+#[cfg(feature = "checksum")]
+use crate::policy::{AuditEvent, AuditSink};
+use crate::transport::datamodel::UTransport;
+#[cfg(feature = "checksum")]
+use crate::transport::datamodel::{ChecksumAlgorithm, PayloadChecksum};
+use crate::transport::mqtt5::{Mqtt5Mapping, Mqtt5Properties, SerializationError};
+use crate::transport::validator::{Validated, Validators};
+use crate::uprotocol::{
+    Data, UAttributes, UCode, UEntity, UMessage, UMessageType, UPayload, UPayloadFormat, UStatus,
+    UUri, Uuid,
+};
+use crate::uuid::builder::{SystemClock, SystemRandomSource, UClock, UUIDv8Builder};
+
+type Listener = Arc<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>;
+
+const REQUEST_CHANNEL_CAPACITY: usize = 64;
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// `UTransport` on top of an MQTT5 broker, based on the [rumqttc](https://github.com/bytebeamio/rumqtt) client.
+///
+/// Topics and attributes are mapped as described for [`Mqtt5Mapping`], with the payload format carried in the
+/// content type of MQTT5 messages. RPC methods are invoked by means of [`MqttTransport::invoke`], which awaits
+/// the response that refers to the request by the correlation data.
+///
+/// With the `checksum` feature, messages can be sent with a checksum over their payload by means of
+/// [`MqttTransport::send_with_checksum`]. Received messages whose payload does not match their checksum are passed
+/// to the listeners as a `UStatus` with code `UCode::DataLoss`, and recorded with the audit sink set by means of
+/// [`MqttTransport::with_audit_sink`], if any.
+///
+/// The connection to the broker is kept by a background task, which reconnects whenever the connection is lost.
+/// If the broker resumes the session, e.g. because a session expiry interval has been configured in the
+/// connect properties and has not passed yet, the subscriptions are kept by the broker. Otherwise, the task
+/// restores the subscriptions of all registered listeners.
+pub struct MqttTransport {
+    client: AsyncClient,
+    dispatcher: Arc<Dispatcher>,
+    event_loop: JoinHandle<()>,
+    uuid_builder: UUIDv8Builder,
+    clock: Arc<dyn UClock>,
+}
+
+impl MqttTransport {
+    /// Creates a transport that connects to an MQTT5 broker.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options to connect to the broker with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called from within a Tokio runtime, which the connection is kept in.
+    pub fn new(options: MqttOptions) -> MqttTransport {
+        Self::with_clock(options, SystemClock)
+    }
+
+    /// Creates a transport that connects to an MQTT5 broker, and that determines the time left for RPC
+    /// invocations by means of the given clock, e.g. in simulations.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options to connect to the broker with.
+    /// * `clock` - The clock that provides the current time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if not called from within a Tokio runtime, which the connection is kept in.
+    pub fn with_clock<C: UClock + 'static>(options: MqttOptions, clock: C) -> MqttTransport {
+        let (client, event_loop) = AsyncClient::new(options, REQUEST_CHANNEL_CAPACITY);
+        let dispatcher = Arc::new(Dispatcher::default());
+        let event_loop = tokio::spawn(Self::run(event_loop, client.clone(), dispatcher.clone()));
+        let clock: Arc<dyn UClock> = Arc::new(clock);
+        MqttTransport {
+            client,
+            dispatcher,
+            event_loop,
+            uuid_builder: UUIDv8Builder::with_sources(clock.clone(), &mut SystemRandomSource),
+            clock,
+        }
+    }
+
+    /// Sets a sink to record an [`AuditEvent::IntegrityCheckFailed`] with for each received message whose payload
+    /// does not match its checksum.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit_sink` - The audit sink.
+    #[cfg(feature = "checksum")]
+    #[must_use]
+    pub fn with_audit_sink(self, audit_sink: Arc<dyn AuditSink>) -> MqttTransport {
+        *self.dispatcher.audit_sink.lock().unwrap() = Some(audit_sink);
+        self
+    }
+
+    /// Invokes an RPC method.
+    ///
+    /// The request is published to the method's topic, with the topic of the attributes' sink as response topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The URI of the method to invoke.
+    /// * `payload` - The request payload.
+    /// * `attributes` - The request attributes. The invocation times out once the attributes' time-to-live has
+    ///   passed since the creation time of their ID.
+    ///
+    /// # Returns
+    ///
+    /// The response message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code
+    /// - `UCode::InvalidArgument` if the attributes are no valid request attributes, or cannot be mapped,
+    /// - `UCode::DeadlineExceeded` if no response has been received within the time-to-live,
+    /// - `UCode::Unavailable` if the request cannot be published.
+    pub async fn invoke(
+        &self,
+        method: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+    ) -> Result<UMessage, UStatus> {
+        if attributes.r#type() != UMessageType::UmessageTypeRequest {
+            return Err(UStatus::fail_with_code(
+                UCode::InvalidArgument,
+                "Methods can only be invoked with request attributes",
+            ));
+        }
+        Self::validate(&attributes)?;
+        let response_topic = attributes
+            .sink
+            .as_ref()
+            .map(Mqtt5Mapping::to_topic)
+            .transpose()?
+            .unwrap_or_default();
+        if self
+            .dispatcher
+            .response_topics
+            .lock()
+            .unwrap()
+            .insert(response_topic.clone())
+        {
+            self.subscribe(&response_topic).await?;
+        }
+
+        let request_id = attributes
+            .id
+            .as_ref()
+            .map(|id| id.to_hyphenated_string())
+            .unwrap_or_default();
+        let (sender, receiver) = oneshot::channel();
+        self.dispatcher
+            .pending_requests
+            .lock()
+            .unwrap()
+            .insert(request_id.clone(), sender);
+
+        let timeout = time_to_expiry(&attributes, self.clock.as_ref());
+        let result = match self
+            .publish(
+                &method,
+                &payload,
+                &attributes,
+                Some(response_topic),
+                HashMap::new(),
+            )
+            .await
+        {
+            Ok(()) => tokio::time::timeout(timeout, receiver)
+                .await
+                .ok()
+                .and_then(Result::ok)
+                .ok_or_else(|| {
+                    UStatus::fail_with_code(
+                        UCode::DeadlineExceeded,
+                        "No response received for request",
+                    )
+                }),
+            Err(status) => Err(status),
+        };
+        self.dispatcher
+            .pending_requests
+            .lock()
+            .unwrap()
+            .remove(&request_id);
+        result
+    }
+
+    /// Sends a message along with a checksum over its payload, which the receiver verifies the payload against.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic to send the message to.
+    /// * `payload` - The payload of the message.
+    /// * `attributes` - The attributes of the message.
+    /// * `algorithm` - The algorithm to compute the checksum with.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code
+    /// - `UCode::InvalidArgument` if the attributes are invalid, or if the checksum cannot be computed for the payload,
+    /// - `UCode::Unavailable` if the message cannot be published.
+    #[cfg(feature = "checksum")]
+    pub async fn send_with_checksum(
+        &self,
+        topic: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<(), UStatus> {
+        Self::validate(&attributes)?;
+        let mut headers = HashMap::new();
+        PayloadChecksum::compute(algorithm, &payload)?.insert_into(&mut headers);
+        self.publish(&topic, &payload, &attributes, None, headers)
+            .await
+    }
+
+    async fn run(mut event_loop: EventLoop, client: AsyncClient, dispatcher: Arc<Dispatcher>) {
+        loop {
+            match event_loop.poll().await {
+                Ok(Event::Incoming(Packet::Publish(publish))) => dispatcher.dispatch(&publish),
+                Ok(Event::Incoming(Packet::ConnAck(connack))) if !connack.session_present => {
+                    // the request channel is drained by this very loop, so subscribing must not wait for it
+                    for topic in dispatcher.subscribed_topics() {
+                        let _ = client.try_subscribe(topic, QoS::AtLeastOnce);
+                    }
+                }
+                Ok(_) => {}
+                // polling again reconnects to the broker
+                Err(_) => tokio::time::sleep(RECONNECT_DELAY).await,
+            }
+        }
+    }
+
+    fn validate(attributes: &UAttributes) -> Result<(), UStatus> {
+        Validators::get_validator(attributes)
+            .validate(attributes)
+            .map_err(UStatus::from)
+    }
+
+    async fn subscribe(&self, topic: &str) -> Result<(), UStatus> {
+        self.client
+            .subscribe(topic, QoS::AtLeastOnce)
+            .await
+            .map_err(|e| {
+                UStatus::fail_with_code(
+                    UCode::Unavailable,
+                    &format!("Failed to subscribe to {topic}: {e}"),
+                )
+            })
+    }
+
+    async fn publish(
+        &self,
+        topic: &UUri,
+        payload: &UPayload,
+        attributes: &UAttributes,
+        response_topic: Option<String>,
+        headers: HashMap<String, String>,
+    ) -> Result<(), UStatus> {
+        let topic = Mqtt5Mapping::to_topic(topic)?;
+        let mut properties = to_publish_properties(attributes, payload.format())?;
+        properties.response_topic = response_topic;
+        properties.user_properties.extend(headers);
+        let data = payload.resolve_data(None)?.unwrap_or_default().to_vec();
+        self.client
+            .publish_with_properties(topic, QoS::AtLeastOnce, false, data, properties)
+            .await
+            .map_err(|e| {
+                UStatus::fail_with_code(
+                    UCode::Unavailable,
+                    &format!("Failed to publish message: {e}"),
+                )
+            })
+    }
+}
+
+impl Drop for MqttTransport {
+    fn drop(&mut self) {
+        self.event_loop.abort();
+    }
+}
+
+#[async_trait]
+impl UTransport for MqttTransport {
+    // Clients authenticate with the credentials in the options that the transport has been created with.
+    async fn authenticate(&self, _entity: UEntity) -> Result<(), UStatus> {
+        Err(UStatus::fail_with_code(
+            UCode::Unimplemented,
+            "Authentication is configured in the MQTT options",
+        ))
+    }
+
+    async fn send(
+        &self,
+        topic: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+    ) -> Result<(), UStatus> {
+        Self::validate(&attributes)?;
+        self.publish(&topic, &payload, &attributes, None, HashMap::new())
+            .await
+    }
+
+    async fn send_validated(&self, message: Validated<UMessage>) -> Result<(), UStatus> {
+        let message = message.into_inner();
+        self.publish(
+            &message.source.unwrap_or_default(),
+            &message.payload.unwrap_or_default(),
+            &message.attributes.unwrap_or_default(),
+            None,
+            HashMap::new(),
+        )
+        .await
+    }
+
+    async fn register_listener(
+        &self,
+        topic: UUri,
+        listener: Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>,
+    ) -> Result<String, UStatus> {
+        let topic = Mqtt5Mapping::to_topic(&topic)?;
+        let id = self.uuid_builder.build().to_hyphenated_string();
+        let first = {
+            let mut listeners = self.dispatcher.listeners.lock().unwrap();
+            let registered = listeners.entry(topic.clone()).or_default();
+            registered.push((id.clone(), Arc::from(listener)));
+            registered.len() == 1
+        };
+        if first {
+            self.subscribe(&topic).await?;
+        }
+        Ok(id)
+    }
+
+    async fn unregister_listener(&self, topic: UUri, listener: &str) -> Result<(), UStatus> {
+        let topic = Mqtt5Mapping::to_topic(&topic)?;
+        let last = {
+            let mut listeners = self.dispatcher.listeners.lock().unwrap();
+            let registered = listeners.get_mut(&topic).ok_or_else(|| {
+                UStatus::fail_with_code(UCode::NotFound, &format!("No listener for {topic}"))
+            })?;
+            let count = registered.len();
+            registered.retain(|(id, _)| id != listener);
+            if registered.len() == count {
+                return Err(UStatus::fail_with_code(
+                    UCode::NotFound,
+                    &format!("No listener with ID {listener}"),
+                ));
+            }
+            if registered.is_empty() {
+                listeners.remove(&topic);
+                true
+            } else {
+                false
+            }
+        };
+        if last
+            && !self
+                .dispatcher
+                .response_topics
+                .lock()
+                .unwrap()
+                .contains(&topic)
+        {
+            self.client.unsubscribe(&topic).await.map_err(|e| {
+                UStatus::fail_with_code(
+                    UCode::Unavailable,
+                    &format!("Failed to unsubscribe from {topic}: {e}"),
+                )
+            })?;
+        }
+        Ok(())
+    }
+}
+
+// Passes received messages to the listeners of their topic, or to the invocation awaiting them.
+#[derive(Default)]
+struct Dispatcher {
+    listeners: Mutex<HashMap<String, Vec<(String, Listener)>>>,
+    response_topics: Mutex<HashSet<String>>,
+    pending_requests: Mutex<HashMap<String, oneshot::Sender<UMessage>>>,
+    #[cfg(feature = "checksum")]
+    audit_sink: Mutex<Option<Arc<dyn AuditSink>>>,
+}
+
+impl Dispatcher {
+    fn subscribed_topics(&self) -> Vec<String> {
+        let mut topics = self
+            .listeners
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<HashSet<_>>();
+        topics.extend(self.response_topics.lock().unwrap().iter().cloned());
+        topics.into_iter().collect()
+    }
+
+    fn dispatch(&self, publish: &Publish) {
+        let topic = String::from_utf8_lossy(&publish.topic).into_owned();
+        let message = to_message(publish);
+        #[cfg(feature = "checksum")]
+        let message = message.and_then(|message| self.verify_checksum(publish, message));
+
+        if let Ok(UMessage {
+            attributes: Some(attributes),
+            ..
+        }) = &message
+        {
+            let pending = attributes
+                .reqid
+                .as_ref()
+                .filter(|_| attributes.r#type() == UMessageType::UmessageTypeResponse)
+                .and_then(|reqid| {
+                    self.pending_requests
+                        .lock()
+                        .unwrap()
+                        .remove(&reqid.to_hyphenated_string())
+                });
+            if let (Some(sender), Ok(message)) = (pending, message.clone()) {
+                let _ = sender.send(message);
+                return;
+            }
+        }
+
+        // listeners are called without holding the lock, so that they can (un)register listeners themselves
+        let listeners = self
+            .listeners
+            .lock()
+            .unwrap()
+            .get(&topic)
+            .map(|registered| {
+                registered
+                    .iter()
+                    .map(|(_, listener)| listener.clone())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        for listener in listeners {
+            listener(message.clone());
+        }
+    }
+
+    // Fails a received message whose payload does not match the checksum in its user properties, if it has one.
+    #[cfg(feature = "checksum")]
+    fn verify_checksum(&self, publish: &Publish, message: UMessage) -> Result<UMessage, UStatus> {
+        let headers = publish
+            .properties
+            .iter()
+            .flat_map(|properties| properties.user_properties.iter().cloned())
+            .collect::<HashMap<_, _>>();
+        if let Some(payload) = &message.payload {
+            PayloadChecksum::verify_headers(&headers, payload).map_err(|status| {
+                if let Some(audit_sink) = self.audit_sink.lock().unwrap().as_ref() {
+                    audit_sink.record(AuditEvent::integrity_check_failed(&message, &status));
+                }
+                status
+            })?;
+        }
+        Ok(message)
+    }
+}
+
+// The time left until a request expires, i.e. its time-to-live less the time passed since the creation of its ID.
+fn time_to_expiry(attributes: &UAttributes, clock: &dyn UClock) -> Duration {
+    let ttl = attributes
+        .ttl
+        .and_then(|ttl| u64::try_from(ttl).ok())
+        .unwrap_or_default();
+    let elapsed = attributes
+        .id
+        .as_ref()
+        .and_then(Uuid::get_time)
+        .zip(clock.now())
+        .map(|(created, now)| now.saturating_sub(created))
+        .unwrap_or_default();
+    Duration::from_millis(ttl.saturating_sub(elapsed))
+}
+
+fn to_publish_properties(
+    attributes: &UAttributes,
+    format: UPayloadFormat,
+) -> Result<PublishProperties, SerializationError> {
+    let properties = Mqtt5Mapping::to_properties(attributes)?;
+    Ok(PublishProperties {
+        message_expiry_interval: properties.message_expiry_interval,
+        correlation_data: properties.correlation_data.map(Bytes::from),
+        user_properties: properties.user_properties,
+        content_type: Some(format.as_str_name().to_string()),
+        ..Default::default()
+    })
+}
+
+fn to_message(publish: &Publish) -> Result<UMessage, UStatus> {
+    let topic = std::str::from_utf8(&publish.topic)
+        .map_err(|e| SerializationError::utf8("Topic name is not valid UTF-8", e))?;
+    let source = Mqtt5Mapping::from_topic(topic)?;
+    let properties = publish.properties.clone().unwrap_or_default();
+    let attributes = Mqtt5Mapping::from_properties(&Mqtt5Properties {
+        user_properties: properties.user_properties,
+        correlation_data: properties.correlation_data.map(|data| data.to_vec()),
+        message_expiry_interval: properties.message_expiry_interval,
+    })?;
+    let format = properties
+        .content_type
+        .as_deref()
+        .and_then(UPayloadFormat::from_str_name)
+        .unwrap_or_default();
+
+    Ok(UMessage {
+        source: Some(source),
+        attributes: Some(attributes),
+        payload: Some(UPayload {
+            length: i32::try_from(publish.payload.len()).ok(),
+            data: Some(Data::Value(publish.payload.to_vec())),
+            format: format.into(),
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc;
+
+    use crate::transport::builder::{UAttributesBuilder, UPayloadBuilder};
+    use crate::uprotocol::UPriority;
+    use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+
+    #[cfg(feature = "checksum")]
+    #[derive(Default)]
+    struct RecordingAuditSink(Mutex<Vec<AuditEvent>>);
+
+    #[cfg(feature = "checksum")]
+    impl AuditSink for RecordingAuditSink {
+        fn record(&self, event: AuditEvent) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    fn uri(uri: &str) -> UUri {
+        LongUriSerializer::deserialize(uri.to_string()).unwrap()
+    }
+
+    fn publish(topic: &str, attributes: &UAttributes, payload: &UPayload) -> Publish {
+        let mut publish = Publish::new(
+            topic,
+            QoS::AtLeastOnce,
+            payload.resolve_data(None).unwrap().unwrap().to_vec(),
+            Some(to_publish_properties(attributes, payload.format()).unwrap()),
+        );
+        publish.pkid = 1;
+        publish
+    }
+
+    #[test]
+    fn test_message_round_trip() {
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1)
+            .with_ttl(1500)
+            .build();
+        let payload = UPayloadBuilder::from_text("open").build().unwrap();
+
+        let message = to_message(&publish(
+            "/body.access/1/door.front_left",
+            &attributes,
+            &payload,
+        ))
+        .unwrap();
+
+        assert_eq!(message.attributes, Some(attributes));
+        assert_eq!(message.payload, Some(payload));
+        assert_eq!(
+            LongUriSerializer::serialize(&message.source.unwrap()).unwrap(),
+            "/body.access/1/door.front_left"
+        );
+    }
+
+    #[test]
+    fn test_message_without_properties_has_no_attributes() {
+        let publish = Publish::new("/body.access/1/door", QoS::AtMostOnce, vec![1], None);
+
+        let message = to_message(&publish).unwrap();
+
+        assert_eq!(message.attributes, Some(UAttributes::default()));
+        assert_eq!(
+            message.payload.unwrap().format(),
+            UPayloadFormat::UpayloadFormatUnspecified
+        );
+    }
+
+    #[test]
+    fn test_dispatch_to_listeners_of_topic() {
+        let dispatcher = Dispatcher::default();
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        dispatcher.listeners.lock().unwrap().insert(
+            "/body.access/1/door.front_left".to_string(),
+            vec![(
+                "listener".to_string(),
+                Arc::new(move |message| sender.lock().unwrap().send(message).unwrap()),
+            )],
+        );
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        let payload = UPayloadBuilder::from_text("open").build().unwrap();
+
+        dispatcher.dispatch(&publish("/body.access/1/door.rear", &attributes, &payload));
+        dispatcher.dispatch(&publish(
+            "/body.access/1/door.front_left",
+            &attributes,
+            &payload,
+        ));
+
+        let message = receiver.try_recv().unwrap().unwrap();
+        assert_eq!(message.attributes, Some(attributes));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn test_dispatch_fails_message_not_matching_checksum() {
+        let audit_sink = Arc::new(RecordingAuditSink::default());
+        let dispatcher = Dispatcher::default();
+        *dispatcher.audit_sink.lock().unwrap() = Some(audit_sink.clone());
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        dispatcher.listeners.lock().unwrap().insert(
+            "/body.access/1/door.front_left".to_string(),
+            vec![(
+                "listener".to_string(),
+                Arc::new(move |message| sender.lock().unwrap().send(message).unwrap()),
+            )],
+        );
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        let payload = UPayloadBuilder::from_raw(vec![1, 2, 3]).build().unwrap();
+        let checksum = PayloadChecksum::compute(ChecksumAlgorithm::Crc32, &payload).unwrap();
+
+        for data in [vec![1, 2, 3], vec![1, 2, 4]] {
+            let received = UPayloadBuilder::from_raw(data).build().unwrap();
+            let mut packet = publish("/body.access/1/door.front_left", &attributes, &received);
+            if let Some(properties) = packet.properties.as_mut() {
+                properties.user_properties.push((
+                    PayloadChecksum::HEADER_NAME.to_string(),
+                    checksum.to_string(),
+                ));
+            }
+            dispatcher.dispatch(&packet);
+        }
+
+        let message = receiver.try_recv().unwrap().unwrap();
+        assert_eq!(message.payload, Some(payload));
+        let status = receiver.try_recv().unwrap().unwrap_err();
+        assert_eq!(status.get_code(), UCode::DataLoss);
+
+        let events = audit_sink.0.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            AuditEvent::IntegrityCheckFailed { message, .. } if message.id == attributes.id
+        ));
+    }
+
+    #[test]
+    fn test_dispatch_response_to_pending_request() {
+        let dispatcher = Dispatcher::default();
+        let reqid = UUIDv8Builder::new().build();
+        let (sender, mut receiver) = oneshot::channel();
+        dispatcher
+            .pending_requests
+            .lock()
+            .unwrap()
+            .insert(reqid.to_hyphenated_string(), sender);
+        let attributes = UAttributesBuilder::response(
+            UPriority::UpriorityCs4,
+            uri("/body.access/1/rpc.UpdateDoor"),
+            reqid,
+        )
+        .build();
+        let payload = UPayloadBuilder::from_text("done").build().unwrap();
+
+        dispatcher.dispatch(&publish(
+            "/door.client/1/rpc.response",
+            &attributes,
+            &payload,
+        ));
+
+        let response = receiver.try_recv().unwrap();
+        assert_eq!(response.attributes, Some(attributes));
+        assert!(dispatcher.pending_requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_time_to_expiry_of_request() {
+        struct FixedClock(u64);
+        impl UClock for FixedClock {
+            fn now(&self) -> Option<u64> {
+                Some(self.0)
+            }
+        }
+
+        let created = 0x18C684468F8; // Thu, 14 Dec 2023 12:19:23 GMT
+        let id = UUIDv8Builder::with_sources(FixedClock(created), &mut SystemRandomSource).build();
+        let attributes = UAttributesBuilder::request(
+            UPriority::UpriorityCs4,
+            uri("/door.client/1/rpc.response"),
+            1000,
+        )
+        .with_id(id)
+        .build();
+
+        assert_eq!(
+            time_to_expiry(&attributes, &FixedClock(created + 400)),
+            Duration::from_millis(600)
+        );
+        assert_eq!(
+            time_to_expiry(&attributes, &FixedClock(created + 1500)),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_subscribed_topics_include_response_topics() {
+        let dispatcher = Dispatcher::default();
+        dispatcher
+            .listeners
+            .lock()
+            .unwrap()
+            .insert("/body.access/1/door".to_string(), Vec::new());
+        dispatcher
+            .response_topics
+            .lock()
+            .unwrap()
+            .insert("/door.client/1/rpc.response".to_string());
+
+        let mut topics = dispatcher.subscribed_topics();
+        topics.sort();
+
+        assert_eq!(
+            topics,
+            vec!["/body.access/1/door", "/door.client/1/rpc.response"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_for_invalid_attributes() {
+        let transport = MqttTransport::new(MqttOptions::new("test", "localhost", 1883));
+
+        let result = transport
+            .send(
+                uri("/body.access/1/door"),
+                UPayload::default(),
+                UAttributes::default(),
+            )
+            .await;
+
+        assert_eq!(result.unwrap_err().get_code(), UCode::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_unregister_unknown_listener_fails() {
+        let transport = MqttTransport::new(MqttOptions::new("test", "localhost", 1883));
+
+        let result = transport
+            .unregister_listener(uri("/body.access/1/door"), "unknown")
+            .await;
+
+        assert_eq!(result.unwrap_err().get_code(), UCode::NotFound);
+    }
+}
@@ -0,0 +1,413 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::transport::serializer::SerializationError;
+use crate::uprotocol::{UAttributes, UCode, UMessageType, UPriority, Uuid};
+use crate::uri::serializer::{MicroUriSerializer, UriSerializer};
+
+const VERSION: u8 = 0x1;
+
+const TTL: u8 = 0x01;
+const SINK: u8 = 0x02;
+const PERMISSION_LEVEL: u8 = 0x04;
+const COMMSTATUS: u8 = 0x08;
+const REQID: u8 = 0x10;
+const TOKEN: u8 = 0x20;
+
+const UUID_LENGTH: usize = 16;
+
+/// Serializes `UAttributes` into a compact binary form, the micro form of attributes, for transports with small frames
+/// that cannot afford the protobuf encoding of the attributes of each message, like CAN or SOME/IP.
+///
+/// Like a URI in micro form, the attributes start with a header of [`MicroAttributesSerializer::HEADER_LENGTH`] bytes,
+/// followed by the attributes that are set, in this order. Integers are big-endian.
+///
+/// | Bytes | Content                                                                          |
+/// |-------|----------------------------------------------------------------------------------|
+/// | 1     | version of the micro form, `0x01`                                                |
+/// | 1     | message type                                                                     |
+/// | 1     | priority                                                                         |
+/// | 1     | flags of the attributes that follow, see below                                   |
+/// | 16    | ID, its most significant 64 bits first                                           |
+/// | 4     | ttl                                                                              |
+/// | 1 + n | sink, as the length of the URI in micro form followed by the URI                 |
+/// | 4     | permission level                                                                 |
+/// | 1     | commstatus                                                                       |
+/// | 16    | reqid                                                                            |
+/// | 2 + n | token, as the length of the token in bytes followed by its UTF-8 encoding        |
+///
+/// The flags are `0x01` for ttl, `0x02` for sink, `0x04` for permission level, `0x08` for commstatus, `0x10` for
+/// reqid and `0x20` for token.
+///
+/// A request in micro form that carries its sink as local URI takes 33 bytes, compared to 70 bytes or more as
+/// protobuf message.
+pub struct MicroAttributesSerializer;
+
+impl MicroAttributesSerializer {
+    /// The length of the header of attributes in micro form.
+    pub const HEADER_LENGTH: usize = 4 + UUID_LENGTH;
+
+    /// Gets the length of attributes in micro form, e.g. for checking if a message fits into a frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` for the attributes that [`MicroAttributesSerializer::serialize`] fails for.
+    pub fn serialized_len(attributes: &UAttributes) -> Result<usize, SerializationError> {
+        let mut buffer = Vec::new();
+        Self::serialize_into(attributes, &mut buffer)
+    }
+
+    /// Serializes attributes into their micro form.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the attributes have no ID, an unknown message type, priority or commstatus,
+    /// a negative ttl or permission level, a sink that cannot be serialized into micro form, or a token longer than
+    /// `u16::MAX` bytes.
+    pub fn serialize(attributes: &UAttributes) -> Result<Vec<u8>, SerializationError> {
+        let mut buffer = Vec::with_capacity(Self::HEADER_LENGTH);
+        Self::serialize_into(attributes, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Serializes attributes into their micro form at the end of a buffer, e.g. ahead of the payload of a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `attributes` - The attributes.
+    /// * `buffer` - The buffer to append the attributes to, which is left unchanged if serializing fails.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes appended to the buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` for the attributes that [`MicroAttributesSerializer::serialize`] fails for.
+    pub fn serialize_into(
+        attributes: &UAttributes,
+        buffer: &mut Vec<u8>,
+    ) -> Result<usize, SerializationError> {
+        let start = buffer.len();
+        let result = write_attributes(attributes, buffer);
+        if result.is_err() {
+            buffer.truncate(start);
+        }
+        result.map(|()| buffer.len() - start)
+    }
+
+    /// Deserializes attributes from their micro form.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the bytes are not attributes in micro form, or are followed by other bytes.
+    pub fn deserialize(bytes: &[u8]) -> Result<UAttributes, SerializationError> {
+        let (attributes, length) = Self::deserialize_prefix(bytes)?;
+        if length != bytes.len() {
+            return Err(SerializationError::new(
+                "Micro attributes are followed by other bytes",
+            ));
+        }
+        Ok(attributes)
+    }
+
+    /// Deserializes attributes from the micro form at the start of some bytes, e.g. of a frame that carries a
+    /// payload after them.
+    ///
+    /// # Returns
+    ///
+    /// The attributes, and the number of bytes they have taken.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the bytes do not start with attributes in micro form.
+    pub fn deserialize_prefix(bytes: &[u8]) -> Result<(UAttributes, usize), SerializationError> {
+        let mut reader = Reader { bytes, offset: 0 };
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(SerializationError::new(format!(
+                "Unsupported micro attributes version [{version}]"
+            )));
+        }
+        let message_type = UMessageType::try_from_value(i32::from(reader.u8()?))
+            .map_err(|e| SerializationError::other("Invalid message type", e))?;
+        let priority = UPriority::try_from_value(i32::from(reader.u8()?))
+            .map_err(|e| SerializationError::other("Invalid priority", e))?;
+        let flags = reader.u8()?;
+        let mut attributes = UAttributes {
+            id: Some(reader.uuid()?),
+            r#type: message_type.into(),
+            priority: priority.into(),
+            ..Default::default()
+        };
+
+        if flags & TTL != 0 {
+            attributes.ttl = Some(reader.i32("ttl")?);
+        }
+        if flags & SINK != 0 {
+            let length = usize::from(reader.u8()?);
+            attributes.sink = Some(MicroUriSerializer::deserialize(
+                reader.take(length)?.to_vec(),
+            )?);
+        }
+        if flags & PERMISSION_LEVEL != 0 {
+            attributes.permission_level = Some(reader.i32("permission level")?);
+        }
+        if flags & COMMSTATUS != 0 {
+            let commstatus = UCode::try_from_value(i32::from(reader.u8()?))
+                .map_err(|e| SerializationError::other("Invalid commstatus", e))?;
+            attributes.commstatus = Some(commstatus.into());
+        }
+        if flags & REQID != 0 {
+            attributes.reqid = Some(reader.uuid()?);
+        }
+        if flags & TOKEN != 0 {
+            let length = usize::from(u16::from_be_bytes(reader.array()?));
+            let token = core::str::from_utf8(reader.take(length)?)
+                .map_err(|_| SerializationError::new("Token is not valid UTF-8"))?;
+            attributes.token = Some(token.to_string());
+        }
+        Ok((attributes, reader.offset))
+    }
+}
+
+fn write_attributes(
+    attributes: &UAttributes,
+    buffer: &mut Vec<u8>,
+) -> Result<(), SerializationError> {
+    let id = attributes
+        .id
+        .as_ref()
+        .ok_or_else(|| SerializationError::new("Attributes have no ID"))?;
+    let message_type = u8::try_from(attributes.r#type)
+        .ok()
+        .filter(|_| UMessageType::is_valid_message_type(attributes.r#type))
+        .ok_or_else(|| {
+            SerializationError::new(format!("Unknown message type [{}]", attributes.r#type))
+        })?;
+    let priority = u8::try_from(attributes.priority)
+        .ok()
+        .filter(|_| UPriority::is_valid_priority(attributes.priority))
+        .ok_or_else(|| {
+            SerializationError::new(format!("Unknown priority [{}]", attributes.priority))
+        })?;
+    let sink = attributes
+        .sink
+        .as_ref()
+        .map(MicroUriSerializer::serialize)
+        .transpose()?;
+    let token = attributes.token.as_deref().map(str::as_bytes);
+    let flags = [
+        (attributes.ttl.is_some(), TTL),
+        (sink.is_some(), SINK),
+        (attributes.permission_level.is_some(), PERMISSION_LEVEL),
+        (attributes.commstatus.is_some(), COMMSTATUS),
+        (attributes.reqid.is_some(), REQID),
+        (token.is_some(), TOKEN),
+    ]
+    .into_iter()
+    .filter(|(set, _)| *set)
+    .fold(0, |flags, (_, flag)| flags | flag);
+
+    buffer.extend_from_slice(&[VERSION, message_type, priority, flags]);
+    write_uuid(id, buffer);
+    if let Some(ttl) = attributes.ttl {
+        write_u32(ttl, "ttl", buffer)?;
+    }
+    if let Some(sink) = sink {
+        // the micro form of a URI takes no more than MAX_MICRO_URI_LENGTH bytes, which fits into a byte
+        let length = u8::try_from(sink.len())
+            .map_err(|_| SerializationError::new("Sink is too long for micro attributes"))?;
+        buffer.push(length);
+        buffer.extend_from_slice(&sink);
+    }
+    if let Some(permission_level) = attributes.permission_level {
+        write_u32(permission_level, "permission level", buffer)?;
+    }
+    if let Some(commstatus) = attributes.commstatus {
+        let code = u8::try_from(commstatus)
+            .ok()
+            .filter(|_| UCode::is_valid_code(commstatus))
+            .ok_or_else(|| SerializationError::new(format!("Unknown commstatus [{commstatus}]")))?;
+        buffer.push(code);
+    }
+    if let Some(reqid) = &attributes.reqid {
+        write_uuid(reqid, buffer);
+    }
+    if let Some(token) = token {
+        let length = u16::try_from(token.len())
+            .map_err(|_| SerializationError::new("Token is too long for micro attributes"))?;
+        buffer.extend_from_slice(&length.to_be_bytes());
+        buffer.extend_from_slice(token);
+    }
+    Ok(())
+}
+
+fn write_uuid(uuid: &Uuid, buffer: &mut Vec<u8>) {
+    buffer.extend_from_slice(&uuid.msb.to_be_bytes());
+    buffer.extend_from_slice(&uuid.lsb.to_be_bytes());
+}
+
+fn write_u32(value: i32, name: &str, buffer: &mut Vec<u8>) -> Result<(), SerializationError> {
+    let value = u32::try_from(value)
+        .map_err(|_| SerializationError::new(format!("Negative {name} [{value}]")))?;
+    buffer.extend_from_slice(&value.to_be_bytes());
+    Ok(())
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], SerializationError> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + length)
+            .ok_or_else(|| SerializationError::new("Micro attributes are truncated"))?;
+        self.offset += length;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SerializationError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8, SerializationError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn i32(&mut self, name: &str) -> Result<i32, SerializationError> {
+        let value = u32::from_be_bytes(self.array()?);
+        i32::try_from(value)
+            .map_err(|_| SerializationError::new(format!("{name} [{value}] is out of range")))
+    }
+
+    fn uuid(&mut self) -> Result<Uuid, SerializationError> {
+        Ok(Uuid {
+            msb: u64::from_be_bytes(self.array()?),
+            lsb: u64::from_be_bytes(self.array()?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prost::Message;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::{UEntity, UResource, UUri};
+
+    fn build_sink_for_test() -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                id: Some(0x1234),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                id: Some(0x8001),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_serialize_and_deserialize_request() {
+        let attributes =
+            UAttributesBuilder::request(UPriority::UpriorityCs4, build_sink_for_test(), 1000)
+                .with_permission_level(2)
+                .with_token("token")
+                .build();
+
+        let bytes = MicroAttributesSerializer::serialize(&attributes).unwrap();
+        assert_eq!(
+            bytes.len(),
+            MicroAttributesSerializer::HEADER_LENGTH + 4 + 9 + 4 + 2 + 5
+        );
+        assert_eq!(
+            MicroAttributesSerializer::serialized_len(&attributes).unwrap(),
+            bytes.len()
+        );
+        assert!(bytes.len() < attributes.encoded_len());
+        assert_eq!(
+            MicroAttributesSerializer::deserialize(&bytes).unwrap(),
+            attributes
+        );
+    }
+
+    #[test]
+    fn test_deserialize_prefix_of_frame() {
+        let attributes = UAttributesBuilder::response(
+            UPriority::UpriorityCs4,
+            build_sink_for_test(),
+            Uuid { msb: 1, lsb: 2 },
+        )
+        .with_commstatus(UCode::NotFound.into())
+        .build();
+        let mut frame = Vec::new();
+        let length = MicroAttributesSerializer::serialize_into(&attributes, &mut frame).unwrap();
+        frame.extend_from_slice(&[1, 2, 3]);
+
+        let (deserialized, taken) = MicroAttributesSerializer::deserialize_prefix(&frame).unwrap();
+        assert_eq!(deserialized, attributes);
+        assert_eq!(taken, length);
+        assert!(MicroAttributesSerializer::deserialize(&frame).is_err());
+    }
+
+    #[test]
+    fn test_serialize_fails_for_invalid_attributes() {
+        let mut buffer = vec![0xff];
+        let attributes = UAttributes {
+            id: Some(Uuid::default()),
+            ttl: Some(-1),
+            ..Default::default()
+        };
+        assert!(MicroAttributesSerializer::serialize_into(&attributes, &mut buffer).is_err());
+        assert_eq!(buffer, vec![0xff]);
+        assert!(MicroAttributesSerializer::serialize(&UAttributes::default()).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_fails_for_truncated_bytes() {
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1)
+            .with_ttl(100)
+            .build();
+        let bytes = MicroAttributesSerializer::serialize(&attributes).unwrap();
+        for length in 0..bytes.len() {
+            assert!(MicroAttributesSerializer::deserialize(&bytes[..length]).is_err());
+        }
+    }
+    #[test]
+    fn test_deserialize_keeps_validation_error_as_source() {
+        use std::error::Error;
+
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        let mut bytes = MicroAttributesSerializer::serialize(&attributes).unwrap();
+        // the message type follows the version
+        bytes[1] = 0xff;
+
+        let error = MicroAttributesSerializer::deserialize(&bytes).unwrap_err();
+        assert_eq!(error.message(), "Invalid message type");
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            UMessageType::try_from_value(0xff).unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,540 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+
+use crate::transport::datamodel::{BufferPool, BufferPoolStats, UTransport};
+use crate::transport::socket::listenerregistry::ListenerRegistry;
+use crate::transport::socket::messageframe::{
+    to_frame_message, to_unvalidated_frame_message, write_frame,
+};
+use crate::transport::validator::Validated;
+use crate::uprotocol::{UAttributes, UCode, UEntity, UMessage, UPayload, UStatus, UUri};
+
+type Authenticator = Box<dyn Fn(&PeerCredentials) -> Result<(), UStatus> + Send + Sync + 'static>;
+
+/// The identity of the process at the other end of an IPC connection, as far as the platform tells it.
+///
+/// Linux provides all of the process, user and group ID, other Unix platforms the user and group ID,
+/// and Windows the process ID.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PeerCredentials {
+    /// The ID of the peer process.
+    pub pid: Option<u32>,
+    /// The effective user ID of the peer process.
+    pub uid: Option<u32>,
+    /// The effective group ID of the peer process.
+    pub gid: Option<u32>,
+}
+
+/// Accepts IPC connections from uEntities on the same host, by means of a Unix domain socket on Unix platforms
+/// and a named pipe on Windows.
+pub struct IpcListener {
+    path: PathBuf,
+    #[cfg(unix)]
+    listener: std::os::unix::net::UnixListener,
+    authenticator: Option<Authenticator>,
+}
+
+impl IpcListener {
+    /// Starts listening for IPC connections.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the Unix domain socket to create, or the name of the named pipe on Windows
+    ///   (e.g. `\\.\pipe\body.access`).
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the socket cannot be created, e.g. because the path is in use.
+    pub fn bind(path: impl AsRef<Path>) -> Result<IpcListener, UStatus> {
+        let path = path.as_ref().to_path_buf();
+        Ok(IpcListener {
+            #[cfg(unix)]
+            listener: std::os::unix::net::UnixListener::bind(&path)?,
+            path,
+            authenticator: None,
+        })
+    }
+
+    /// Sets the hook that authenticates the peer of each accepted connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `authenticator` - The hook, which is passed the credentials of the connecting process, and which
+    ///   rejects the connection by returning an error.
+    ///
+    /// # Returns
+    ///
+    /// The listener, accepting connections only if the hook succeeds.
+    #[must_use]
+    pub fn with_authenticator(
+        mut self,
+        authenticator: impl Fn(&PeerCredentials) -> Result<(), UStatus> + Send + Sync + 'static,
+    ) -> IpcListener {
+        self.authenticator = Some(Box::new(authenticator));
+        self
+    }
+
+    /// Waits for a uEntity to connect.
+    ///
+    /// # Returns
+    ///
+    /// The transport to exchange messages with the connected uEntity.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if no connection can be accepted, or the error returned by the authentication
+    /// hook if it has rejected the peer. Rejected connections are closed.
+    pub fn accept(&self) -> Result<IpcTransport, UStatus> {
+        let connection = self.accept_connection()?;
+        if let Some(authenticator) = &self.authenticator {
+            authenticator(&connection.peer)?;
+        }
+        IpcTransport::from_connection(connection)
+    }
+
+    #[cfg(unix)]
+    fn accept_connection(&self) -> io::Result<Connection> {
+        let (stream, _address) = self.listener.accept()?;
+        Connection::from_stream(stream)
+    }
+
+    #[cfg(windows)]
+    fn accept_connection(&self) -> io::Result<Connection> {
+        pipe::accept(&self.path)
+    }
+}
+
+impl Drop for IpcListener {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+struct Connection {
+    reader: Box<dyn Read + Send>,
+    writer: Box<dyn Write + Send>,
+    peer: PeerCredentials,
+    #[cfg(unix)]
+    stream: std::os::unix::net::UnixStream,
+}
+
+#[cfg(unix)]
+impl Connection {
+    fn from_stream(stream: std::os::unix::net::UnixStream) -> io::Result<Connection> {
+        Ok(Connection {
+            reader: Box::new(stream.try_clone()?),
+            writer: Box::new(stream.try_clone()?),
+            peer: peer_credentials(&stream),
+            stream,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn peer_credentials(stream: &std::os::unix::net::UnixStream) -> PeerCredentials {
+    use std::os::fd::AsRawFd;
+
+    let mut credentials = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut length = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    // SAFETY: `credentials` and `length` are valid for writes, and `length` is the size of `credentials`
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            std::ptr::addr_of_mut!(credentials).cast(),
+            &mut length,
+        )
+    };
+    if result != 0 {
+        return PeerCredentials::default();
+    }
+    PeerCredentials {
+        pid: u32::try_from(credentials.pid).ok(),
+        uid: Some(credentials.uid),
+        gid: Some(credentials.gid),
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn peer_credentials(stream: &std::os::unix::net::UnixStream) -> PeerCredentials {
+    use std::os::fd::AsRawFd;
+
+    let (mut uid, mut gid) = (0, 0);
+    // SAFETY: `uid` and `gid` are valid for writes
+    if unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) } != 0 {
+        return PeerCredentials::default();
+    }
+    PeerCredentials {
+        pid: None,
+        uid: Some(uid),
+        gid: Some(gid),
+    }
+}
+
+/// `UTransport` between two uEntities on the same host, connected by means of a Unix domain socket on Unix
+/// platforms and named pipes on Windows, e.g. for splitting a vehicle application into multiple processes
+/// without a broker.
+///
+/// Messages are exchanged in the same frames as by [`SocketTransport`](crate::transport::socket::SocketTransport),
+/// and passed to the listeners registered for their topic by a background thread. One side accepts the connection
+/// by means of an [`IpcListener`], which can authenticate the connecting process, while the other side connects by
+/// means of [`IpcTransport::connect`].
+pub struct IpcTransport {
+    writer: Mutex<Box<dyn Write + Send>>,
+    peer: PeerCredentials,
+    listeners: Arc<ListenerRegistry>,
+    closed: Arc<AtomicBool>,
+    buffers: BufferPool,
+    #[cfg(unix)]
+    stream: std::os::unix::net::UnixStream,
+}
+
+impl IpcTransport {
+    /// Connects to a uEntity that listens for IPC connections.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the peer's Unix domain socket, or the name of its named pipe on Windows.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the connection cannot be established.
+    pub fn connect(path: impl AsRef<Path>) -> Result<IpcTransport, UStatus> {
+        #[cfg(unix)]
+        let connection =
+            Connection::from_stream(std::os::unix::net::UnixStream::connect(path.as_ref())?)?;
+        #[cfg(windows)]
+        let connection = pipe::connect(path.as_ref())?;
+        Self::from_connection(connection)
+    }
+
+    /// Gets the credentials of the process at the other end of the connection.
+    pub fn peer(&self) -> &PeerCredentials {
+        &self.peer
+    }
+
+    /// Gets the statistics of the pool of buffers that messages are encoded in for sending.
+    pub fn buffer_stats(&self) -> BufferPoolStats {
+        self.buffers.stats()
+    }
+
+    fn from_connection(connection: Connection) -> Result<IpcTransport, UStatus> {
+        let listeners = Arc::new(ListenerRegistry::default());
+        let closed = Arc::new(AtomicBool::new(false));
+        listeners.receive_frames(connection.reader, closed.clone());
+        Ok(IpcTransport {
+            writer: Mutex::new(connection.writer),
+            peer: connection.peer,
+            listeners,
+            closed,
+            buffers: BufferPool::default(),
+            #[cfg(unix)]
+            stream: connection.stream,
+        })
+    }
+}
+
+impl Drop for IpcTransport {
+    // On Windows, the receiving thread ends as soon as the peer closes its side of the connection.
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+        #[cfg(unix)]
+        let _ = self.stream.shutdown(std::net::Shutdown::Both);
+    }
+}
+
+#[async_trait]
+impl UTransport for IpcTransport {
+    // Peers are authenticated per connection, by the hook of the accepting `IpcListener`.
+    async fn authenticate(&self, _entity: UEntity) -> Result<(), UStatus> {
+        Err(UStatus::fail_with_code(
+            UCode::Unimplemented,
+            "IPC connections are authenticated by the accepting listener",
+        ))
+    }
+
+    async fn send(
+        &self,
+        topic: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+    ) -> Result<(), UStatus> {
+        let message = to_frame_message(topic, payload, attributes)?;
+        write_frame(&mut *self.writer.lock().unwrap(), &message, &self.buffers)?;
+        Ok(())
+    }
+
+    async fn send_validated(&self, message: Validated<UMessage>) -> Result<(), UStatus> {
+        let message = to_unvalidated_frame_message(message.into_inner())?;
+        write_frame(&mut *self.writer.lock().unwrap(), &message, &self.buffers)?;
+        Ok(())
+    }
+
+    async fn register_listener(
+        &self,
+        topic: UUri,
+        listener: Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>,
+    ) -> Result<String, UStatus> {
+        self.listeners.register(&topic, listener)
+    }
+
+    async fn unregister_listener(&self, topic: UUri, listener: &str) -> Result<(), UStatus> {
+        self.listeners.unregister(&topic, listener)
+    }
+}
+
+// Synchronous I/O on a pipe handle is serialized, so that a thread blocked in reading would keep others
+// from writing. Each connection therefore consists of two pipes, one per direction: the listener's pipe,
+// which the client writes to, and a pipe dedicated to the connection, which the client reads from. The
+// listener tells the client the name of the dedicated pipe right after the client has connected.
+#[cfg(windows)]
+mod pipe {
+    use std::ffi::OsStr;
+    use std::fs::{File, OpenOptions};
+    use std::io::{self, Read, Write};
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::io::{AsRawHandle, FromRawHandle};
+    use std::path::{Path, PathBuf};
+
+    use windows_sys::Win32::Foundation::{ERROR_PIPE_CONNECTED, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::PIPE_ACCESS_DUPLEX;
+    use windows_sys::Win32::System::Pipes::{
+        ConnectNamedPipe, CreateNamedPipeW, GetNamedPipeClientProcessId,
+        GetNamedPipeServerProcessId, PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS,
+        PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+    };
+
+    use super::{Connection, PeerCredentials};
+    use crate::uuid::builder::UUIDv8Builder;
+
+    const BUFFER_SIZE: u32 = 64 * 1024;
+
+    pub(super) fn accept(path: &Path) -> io::Result<Connection> {
+        let mut inbound = create(path.as_os_str())?;
+        wait_for_client(&inbound)?;
+        let mut pid = 0u32;
+        // SAFETY: `inbound` is a valid pipe handle, and `pid` is valid for writes
+        let peer = if unsafe { GetNamedPipeClientProcessId(inbound.as_raw_handle(), &mut pid) } != 0
+        {
+            Some(pid)
+        } else {
+            None
+        };
+
+        let outbound_path = format!(
+            "{}-{}",
+            path.display(),
+            UUIDv8Builder::new().build().to_hyphenated_string()
+        );
+        let outbound = create(OsStr::new(&outbound_path))?;
+        let name = outbound_path.as_bytes();
+        inbound.write_all(&(name.len() as u32).to_be_bytes())?;
+        inbound.write_all(name)?;
+        wait_for_client(&outbound)?;
+
+        Ok(Connection {
+            reader: Box::new(inbound),
+            writer: Box::new(outbound),
+            peer: PeerCredentials {
+                pid: peer,
+                ..Default::default()
+            },
+        })
+    }
+
+    pub(super) fn connect(path: &Path) -> io::Result<Connection> {
+        let mut outbound = OpenOptions::new().read(true).write(true).open(path)?;
+        let mut length = [0u8; 4];
+        outbound.read_exact(&mut length)?;
+        let mut name = vec![0u8; u32::from_be_bytes(length) as usize];
+        outbound.read_exact(&mut name)?;
+        let inbound_path = String::from_utf8(name)
+            .map(PathBuf::from)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let inbound = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(inbound_path)?;
+
+        let mut pid = 0u32;
+        // SAFETY: `outbound` is a valid pipe handle, and `pid` is valid for writes
+        let peer =
+            if unsafe { GetNamedPipeServerProcessId(outbound.as_raw_handle(), &mut pid) } != 0 {
+                Some(pid)
+            } else {
+                None
+            };
+        Ok(Connection {
+            reader: Box::new(inbound),
+            writer: Box::new(outbound),
+            peer: PeerCredentials {
+                pid: peer,
+                ..Default::default()
+            },
+        })
+    }
+
+    fn create(name: &OsStr) -> io::Result<File> {
+        let name = name.encode_wide().chain(Some(0)).collect::<Vec<_>>();
+        // SAFETY: `name` is a valid, nul-terminated wide string that outlives the call
+        let handle = unsafe {
+            CreateNamedPipeW(
+                name.as_ptr(),
+                PIPE_ACCESS_DUPLEX,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                PIPE_UNLIMITED_INSTANCES,
+                BUFFER_SIZE,
+                BUFFER_SIZE,
+                0,
+                std::ptr::null(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `handle` has just been created and is not owned by anything else
+        Ok(unsafe { File::from_raw_handle(handle) })
+    }
+
+    fn wait_for_client(pipe: &File) -> io::Result<()> {
+        // SAFETY: `pipe` is a valid pipe handle, which has not been opened for overlapped I/O
+        if unsafe { ConnectNamedPipe(pipe.as_raw_handle(), std::ptr::null_mut()) } == 0 {
+            let error = io::Error::last_os_error();
+            // the client has connected in between creating the pipe and waiting for it
+            if error.raw_os_error() != Some(ERROR_PIPE_CONNECTED as i32) {
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::transport::builder::{UAttributesBuilder, UPayloadBuilder};
+    use crate::uprotocol::UPriority;
+    use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+    use crate::uuid::builder::UUIDv8Builder;
+
+    fn socket_path() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "uprotocol-{}.sock",
+            UUIDv8Builder::new().build().to_hyphenated_string()
+        ))
+    }
+
+    fn connect(
+        listener: IpcListener,
+        path: &Path,
+    ) -> (Result<IpcTransport, UStatus>, IpcTransport) {
+        let accepting = thread::spawn(move || listener.accept());
+        let client = IpcTransport::connect(path).unwrap();
+        (accepting.join().unwrap(), client)
+    }
+
+    #[tokio::test]
+    async fn test_exchange_messages() {
+        let path = socket_path();
+        let (server, client) = connect(IpcListener::bind(&path).unwrap(), &path);
+        let server = server.unwrap();
+
+        let topic =
+            LongUriSerializer::deserialize("/body.access/1/door.front_left".to_string()).unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        server
+            .register_listener(
+                topic.clone(),
+                Box::new(move |message| sender.lock().unwrap().send(message).unwrap()),
+            )
+            .await
+            .unwrap();
+        let payload = UPayloadBuilder::from_text("open").build().unwrap();
+        client
+            .send(
+                topic.clone(),
+                payload.clone(),
+                UAttributesBuilder::publish(UPriority::UpriorityCs1).build(),
+            )
+            .await
+            .unwrap();
+
+        let message = receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.source, Some(topic));
+        assert_eq!(message.payload, Some(payload));
+    }
+
+    #[test]
+    fn test_peer_credentials() {
+        let path = socket_path();
+        let (server, client) = connect(IpcListener::bind(&path).unwrap(), &path);
+
+        // SAFETY: getuid has no preconditions
+        let uid = unsafe { libc::getuid() };
+        assert_eq!(server.unwrap().peer().uid, Some(uid));
+        assert_eq!(client.peer().uid, Some(uid));
+        #[cfg(target_os = "linux")]
+        assert_eq!(client.peer().pid, Some(std::process::id()));
+    }
+
+    #[test]
+    fn test_authenticator_rejects_peer() {
+        let path = socket_path();
+        let listener = IpcListener::bind(&path)
+            .unwrap()
+            .with_authenticator(|peer| {
+                if peer.uid == Some(0) && peer.pid.is_none() {
+                    Ok(())
+                } else {
+                    Err(UStatus::fail_with_code(
+                        UCode::PermissionDenied,
+                        "unknown process",
+                    ))
+                }
+            });
+
+        let (server, _client) = connect(listener, &path);
+
+        assert!(matches!(server, Err(status) if status.get_code() == UCode::PermissionDenied));
+    }
+
+    #[test]
+    fn test_socket_is_removed_with_listener() {
+        let path = socket_path();
+        drop(IpcListener::bind(&path).unwrap());
+
+        assert!(!path.exists());
+    }
+}
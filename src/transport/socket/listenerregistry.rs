@@ -0,0 +1,155 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::transport::datamodel::ListenerIndex;
+use crate::transport::socket::messageframe::read_frame;
+use crate::uprotocol::{UCode, UMessage, UStatus, UUri};
+use crate::uuid::builder::UUIDv8Builder;
+
+type Listener = Arc<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>;
+
+/// The listeners registered with a point-to-point transport, which receive the messages sent to their topic.
+///
+/// Messages are addressed by their source, which senders set to the topic that has been passed to
+/// `UTransport::send`, and looked up by means of a [`ListenerIndex`].
+#[derive(Default)]
+pub(crate) struct ListenerRegistry {
+    listeners: Mutex<ListenerIndex<Listener>>,
+    uuid_builder: UUIDv8Builder,
+}
+
+impl ListenerRegistry {
+    pub(crate) fn register(
+        &self,
+        topic: &UUri,
+        listener: Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>,
+    ) -> Result<String, UStatus> {
+        let id = self.uuid_builder.build().to_hyphenated_string();
+        self.listeners
+            .lock()
+            .unwrap()
+            .insert(id.clone(), topic, Arc::from(listener));
+        Ok(id)
+    }
+
+    pub(crate) fn unregister(&self, topic: &UUri, id: &str) -> Result<(), UStatus> {
+        self.listeners
+            .lock()
+            .unwrap()
+            .remove(topic, id)
+            .map(|_| ())
+            .ok_or_else(|| {
+                UStatus::fail_with_code(UCode::NotFound, &format!("No listener with ID {id}"))
+            })
+    }
+
+    /// Passes a received message to the listeners of its topic, or a failure to receive a message to all listeners.
+    pub(crate) fn dispatch(&self, message: Result<UMessage, UStatus>) {
+        // listeners are called without holding the lock, so that they can (un)register listeners themselves
+        let listeners = {
+            let listeners = self.listeners.lock().unwrap();
+            match &message {
+                Ok(UMessage {
+                    source: Some(source),
+                    ..
+                }) => listeners.matching(source).cloned().collect(),
+                Ok(_) => Vec::new(),
+                Err(_) => listeners.iter().cloned().collect::<Vec<_>>(),
+            }
+        };
+        for listener in listeners {
+            listener(message.clone());
+        }
+    }
+
+    /// Spawns a thread that reads frames from a stream and dispatches the messages, until the stream fails or ends.
+    ///
+    /// The failure is dispatched as well, unless `closed` has been set, i.e. the stream has been closed on purpose.
+    pub(crate) fn receive_frames(
+        self: &Arc<Self>,
+        mut reader: impl Read + Send + 'static,
+        closed: Arc<AtomicBool>,
+    ) {
+        let listeners = self.clone();
+        thread::spawn(move || loop {
+            match read_frame(&mut reader) {
+                Ok(message) => listeners.dispatch(Ok(message)),
+                Err(e) => {
+                    if !closed.load(Ordering::Relaxed) {
+                        listeners.dispatch(Err(UStatus::from(e)));
+                    }
+                    break;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc;
+
+    use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+
+    fn uri(uri: &str) -> UUri {
+        LongUriSerializer::deserialize(uri.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_dispatch_to_listeners_of_topic() {
+        let registry = ListenerRegistry::default();
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        registry
+            .register(
+                &uri("/body.access/1/door.front_left"),
+                Box::new(move |message| sender.lock().unwrap().send(message).unwrap()),
+            )
+            .unwrap();
+
+        registry.dispatch(Ok(UMessage {
+            source: Some(uri("/body.access/1/door.rear")),
+            ..Default::default()
+        }));
+        registry.dispatch(Ok(UMessage {
+            source: Some(uri("/body.access/1/door.front_left")),
+            ..Default::default()
+        }));
+        registry.dispatch(Err(UStatus::fail("connection lost")));
+
+        assert!(receiver.try_recv().unwrap().is_ok());
+        assert!(receiver.try_recv().unwrap().is_err());
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_unregister() {
+        let registry = ListenerRegistry::default();
+        let topic = uri("/body.access/1/door");
+        let id = registry.register(&topic, Box::new(|_| {})).unwrap();
+
+        assert!(registry.unregister(&topic, "unknown").is_err());
+        assert!(registry.unregister(&topic, &id).is_ok());
+        assert_eq!(
+            registry.unregister(&topic, &id).unwrap_err().get_code(),
+            UCode::NotFound
+        );
+    }
+}
@@ -0,0 +1,196 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::io::{Error, ErrorKind, Read, Write};
+
+use prost::Message;
+
+use crate::transport::datamodel::{BufferPool, PooledBuffer};
+use crate::transport::validator::Validators;
+use crate::uprotocol::{Data, UAttributes, UMessage, UPayload, UStatus, UUri};
+
+const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// The maximum size of a frame's message, which protects receivers from allocating huge buffers for corrupt frames.
+pub(crate) const MAX_FRAME_LENGTH: usize = 16 * 1024 * 1024;
+
+/// Creates the message to send in a frame, with the topic it is sent to as its source.
+///
+/// Fails if the attributes are invalid, or if the payload refers to shared memory, which is meaningless to the peer.
+pub(crate) fn to_frame_message(
+    topic: UUri,
+    payload: UPayload,
+    attributes: UAttributes,
+) -> Result<UMessage, UStatus> {
+    Validators::get_validator(&attributes)
+        .validate(&attributes)
+        .map_err(UStatus::from)?;
+    to_unvalidated_frame_message(UMessage {
+        source: Some(topic),
+        attributes: Some(attributes),
+        payload: Some(payload),
+    })
+}
+
+/// Creates the message to send in a frame without validating it, e.g. as it has been validated already.
+///
+/// Fails if the payload refers to shared memory, which is meaningless to the peer.
+pub(crate) fn to_unvalidated_frame_message(message: UMessage) -> Result<UMessage, UStatus> {
+    let payload = message.payload.unwrap_or_default();
+    let data = payload.resolve_data(None)?.map(|data| data.to_vec());
+    Ok(UMessage {
+        source: message.source,
+        attributes: message.attributes,
+        payload: Some(UPayload {
+            data: data.map(Data::Value),
+            ..payload
+        }),
+    })
+}
+
+/// Encodes a message into a frame, made up of the message's length as 32-bit big endian integer, followed by the
+/// message in its protobuf encoding, in a buffer of a pool.
+pub(crate) fn encode_frame<'a>(
+    message: &UMessage,
+    buffers: &'a BufferPool,
+) -> Result<PooledBuffer<'a>, Error> {
+    let length = message.encoded_len();
+    if length > MAX_FRAME_LENGTH {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Message of {length} bytes exceeds maximum frame length"),
+        ));
+    }
+    let mut frame = buffers.acquire();
+    frame.reserve(LENGTH_PREFIX_SIZE + length);
+    frame.extend_from_slice(&(length as u32).to_be_bytes());
+    message.encode(&mut *frame)?;
+    Ok(frame)
+}
+
+/// Decodes a frame that has been received as a whole, e.g. in a datagram.
+pub(crate) fn decode_frame(frame: &[u8]) -> Result<UMessage, Error> {
+    if frame.len() < LENGTH_PREFIX_SIZE {
+        return Err(Error::new(ErrorKind::InvalidData, "Frame is truncated"));
+    }
+    let (prefix, message) = frame.split_at(LENGTH_PREFIX_SIZE);
+    let mut length = [0u8; LENGTH_PREFIX_SIZE];
+    length.copy_from_slice(prefix);
+    if u32::from_be_bytes(length) as usize != message.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Frame length does not match its message",
+        ));
+    }
+    UMessage::decode(message).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Writes a message as frame to a stream.
+pub(crate) fn write_frame(
+    writer: &mut impl Write,
+    message: &UMessage,
+    buffers: &BufferPool,
+) -> Result<(), Error> {
+    writer.write_all(&encode_frame(message, buffers)?)?;
+    writer.flush()
+}
+
+/// Reads the next frame from a stream.
+///
+/// Fails with `ErrorKind::UnexpectedEof` if the stream ends, also in between frames.
+pub(crate) fn read_frame(reader: &mut impl Read) -> Result<UMessage, Error> {
+    let mut prefix = [0u8; LENGTH_PREFIX_SIZE];
+    reader.read_exact(&mut prefix)?;
+    let length = u32::from_be_bytes(prefix) as usize;
+    if length > MAX_FRAME_LENGTH {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Frame of {length} bytes exceeds maximum frame length"),
+        ));
+    }
+    let mut message = vec![0u8; length];
+    reader.read_exact(&mut message)?;
+    UMessage::decode(message.as_slice()).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use crate::transport::builder::{UAttributesBuilder, UPayloadBuilder};
+    use crate::uprotocol::UPriority;
+
+    fn message() -> UMessage {
+        UMessage {
+            source: Some(UUri::default()),
+            attributes: Some(UAttributesBuilder::publish(UPriority::UpriorityCs1).build()),
+            payload: Some(UPayloadBuilder::from_text("open").build().unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_frame_round_trip() {
+        let message = message();
+        let buffers = BufferPool::default();
+        let frame = encode_frame(&message, &buffers).unwrap();
+
+        assert_eq!(
+            u32::from_be_bytes(frame[..4].try_into().unwrap()) as usize,
+            frame.len() - 4
+        );
+        assert_eq!(decode_frame(&frame).unwrap(), message);
+    }
+
+    #[test]
+    fn test_stream_round_trip() {
+        let message = message();
+        let buffers = BufferPool::default();
+        let mut stream = Vec::new();
+        write_frame(&mut stream, &message, &buffers).unwrap();
+        write_frame(&mut stream, &UMessage::default(), &buffers).unwrap();
+        assert_eq!(buffers.stats().reused, 1);
+
+        let mut reader = Cursor::new(stream);
+        assert_eq!(read_frame(&mut reader).unwrap(), message);
+        assert_eq!(read_frame(&mut reader).unwrap(), UMessage::default());
+        assert_eq!(
+            read_frame(&mut reader).unwrap_err().kind(),
+            ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_decode_frame_fails_for_wrong_length() {
+        let buffers = BufferPool::default();
+        let mut frame = encode_frame(&message(), &buffers).unwrap();
+        frame.pop();
+
+        assert_eq!(
+            decode_frame(&frame).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        assert!(decode_frame(&[0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_read_frame_rejects_oversized_length() {
+        let mut reader = Cursor::new(u32::MAX.to_be_bytes().to_vec());
+
+        assert_eq!(
+            read_frame(&mut reader).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+    }
+}
@@ -0,0 +1,373 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::io::ErrorKind;
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::transport::datamodel::{BufferPool, BufferPoolStats, UTransport};
+use crate::transport::socket::listenerregistry::ListenerRegistry;
+use crate::transport::socket::messageframe::{
+    decode_frame, encode_frame, to_frame_message, to_unvalidated_frame_message, write_frame,
+};
+use crate::transport::validator::Validated;
+use crate::uprotocol::{UAttributes, UCode, UEntity, UMessage, UPayload, UStatus, UUri};
+
+/// The maximum payload of a UDP datagram.
+const MAX_DATAGRAM_SIZE: usize = 65507;
+/// The interval in which the receiving thread of a UDP transport checks if the transport has been dropped.
+const UDP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+enum Connection {
+    Tcp(Mutex<TcpStream>),
+    Udp(Arc<UdpSocket>),
+}
+
+/// Point-to-point `UTransport` that exchanges messages with a single peer over a TCP connection or UDP socket,
+/// e.g. for running two uEntities on different machines during development and integration testing.
+///
+/// Each message is sent as frame, made up of the length of the message as 32-bit big endian integer followed
+/// by the protobuf encoded `UMessage`, whose source is set to the topic that the message has been sent to. A
+/// UDP datagram carries exactly one frame, which limits messages to about 64 KiB. Received messages are passed
+/// to the listeners registered for their topic by a background thread.
+///
+/// The transport does not route or filter messages, so RPC is done by registering a listener for the method on
+/// the one side and a listener for the invoker's response topic (the sink of the request) on the other side.
+pub struct SocketTransport {
+    connection: Connection,
+    listeners: Arc<ListenerRegistry>,
+    closed: Arc<AtomicBool>,
+    buffers: BufferPool,
+}
+
+impl SocketTransport {
+    /// Connects to a peer that accepts a TCP connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address of the peer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the connection cannot be established.
+    pub fn connect_tcp(address: impl ToSocketAddrs) -> Result<SocketTransport, UStatus> {
+        Self::from_tcp_stream(TcpStream::connect(address)?)
+    }
+
+    /// Waits for a peer to connect by means of TCP.
+    ///
+    /// # Arguments
+    ///
+    /// * `listener` - The listener to accept the peer's connection from.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if no connection can be accepted.
+    pub fn accept_tcp(listener: &TcpListener) -> Result<SocketTransport, UStatus> {
+        let (stream, _address) = listener.accept()?;
+        Self::from_tcp_stream(stream)
+    }
+
+    /// Creates a transport on top of an established TCP connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - The connection to the peer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the stream cannot be shared with the receiving thread.
+    pub fn from_tcp_stream(stream: TcpStream) -> Result<SocketTransport, UStatus> {
+        stream.set_nodelay(true)?;
+        let listeners = Arc::new(ListenerRegistry::default());
+        let closed = Arc::new(AtomicBool::new(false));
+        // the connection is shut down when the transport is dropped, which ends the receiving thread
+        listeners.receive_frames(stream.try_clone()?, closed.clone());
+
+        Ok(SocketTransport {
+            connection: Connection::Tcp(Mutex::new(stream)),
+            listeners,
+            closed,
+            buffers: BufferPool::default(),
+        })
+    }
+
+    /// Binds a UDP socket that exchanges messages with a single peer.
+    ///
+    /// # Arguments
+    ///
+    /// * `local_address` - The address to receive messages on.
+    /// * `peer_address` - The address of the peer to send messages to.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the socket cannot be bound or connected.
+    pub fn bind_udp(
+        local_address: impl ToSocketAddrs,
+        peer_address: impl ToSocketAddrs,
+    ) -> Result<SocketTransport, UStatus> {
+        let socket = UdpSocket::bind(local_address)?;
+        socket.connect(peer_address)?;
+        Self::from_udp_socket(socket)
+    }
+
+    /// Creates a transport on top of a UDP socket.
+    ///
+    /// # Arguments
+    ///
+    /// * `socket` - The socket, connected to the peer to send messages to.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the socket cannot be configured for the receiving thread.
+    pub fn from_udp_socket(socket: UdpSocket) -> Result<SocketTransport, UStatus> {
+        socket.set_read_timeout(Some(UDP_POLL_INTERVAL))?;
+        let socket = Arc::new(socket);
+        let listeners = Arc::new(ListenerRegistry::default());
+        let closed = Arc::new(AtomicBool::new(false));
+
+        let (receiving_socket, receiving_listeners, receiving_closed) =
+            (socket.clone(), listeners.clone(), closed.clone());
+        thread::spawn(move || {
+            let mut buffer = vec![0u8; MAX_DATAGRAM_SIZE];
+            while !receiving_closed.load(Ordering::Relaxed) {
+                match receiving_socket.recv(&mut buffer) {
+                    Ok(length) => receiving_listeners
+                        .dispatch(decode_frame(&buffer[..length]).map_err(UStatus::from)),
+                    Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+                    // e.g. the peer's port is not open (yet), which must not stop receiving
+                    Err(e) => receiving_listeners.dispatch(Err(UStatus::from(e))),
+                }
+            }
+        });
+
+        Ok(SocketTransport {
+            connection: Connection::Udp(socket),
+            listeners,
+            closed,
+            buffers: BufferPool::default(),
+        })
+    }
+
+    /// Gets the statistics of the pool of buffers that messages are encoded in for sending.
+    pub fn buffer_stats(&self) -> BufferPoolStats {
+        self.buffers.stats()
+    }
+
+    fn write_message(&self, message: &UMessage) -> Result<(), UStatus> {
+        match &self.connection {
+            Connection::Tcp(stream) => {
+                write_frame(&mut *stream.lock().unwrap(), message, &self.buffers)?;
+            }
+            Connection::Udp(socket) => {
+                let frame = encode_frame(message, &self.buffers)?;
+                if frame.len() > MAX_DATAGRAM_SIZE {
+                    return Err(UStatus::fail_with_code(
+                        UCode::ResourceExhausted,
+                        &format!("Message of {} bytes exceeds UDP datagram", frame.len()),
+                    ));
+                }
+                socket.send(&frame)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SocketTransport {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::Relaxed);
+        if let Connection::Tcp(stream) = &self.connection {
+            let _ = stream.lock().unwrap().shutdown(Shutdown::Both);
+        }
+    }
+}
+
+#[async_trait]
+impl UTransport for SocketTransport {
+    // The peer is trusted, as the transport is meant for development and testing.
+    async fn authenticate(&self, _entity: UEntity) -> Result<(), UStatus> {
+        Err(UStatus::fail_with_code(
+            UCode::Unimplemented,
+            "Socket transport does not authenticate entities",
+        ))
+    }
+
+    async fn send(
+        &self,
+        topic: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+    ) -> Result<(), UStatus> {
+        let message = to_frame_message(topic, payload, attributes)?;
+        self.write_message(&message)
+    }
+
+    async fn send_validated(&self, message: Validated<UMessage>) -> Result<(), UStatus> {
+        let message = to_unvalidated_frame_message(message.into_inner())?;
+        self.write_message(&message)
+    }
+
+    async fn register_listener(
+        &self,
+        topic: UUri,
+        listener: Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>,
+    ) -> Result<String, UStatus> {
+        self.listeners.register(&topic, listener)
+    }
+
+    async fn unregister_listener(&self, topic: UUri, listener: &str) -> Result<(), UStatus> {
+        self.listeners.unregister(&topic, listener)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc;
+
+    use crate::transport::builder::{UAttributesBuilder, UMessageBuilder, UPayloadBuilder};
+    use crate::uprotocol::UPriority;
+    use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+
+    fn uri(uri: &str) -> UUri {
+        LongUriSerializer::deserialize(uri.to_string()).unwrap()
+    }
+
+    async fn assert_message_passed(sender: &SocketTransport, receiver: &SocketTransport) {
+        let topic = uri("/body.access/1/door.front_left#Door");
+        let (message_sender, message_receiver) = mpsc::channel();
+        let message_sender = Mutex::new(message_sender);
+        receiver
+            .register_listener(
+                topic.clone(),
+                Box::new(move |message| message_sender.lock().unwrap().send(message).unwrap()),
+            )
+            .await
+            .unwrap();
+
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        let payload = UPayloadBuilder::from_text("open").build().unwrap();
+        sender
+            .send(topic.clone(), payload.clone(), attributes.clone())
+            .await
+            .unwrap();
+
+        let message = message_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.source, Some(topic));
+        assert_eq!(message.attributes, Some(attributes));
+        assert_eq!(message.payload, Some(payload));
+    }
+
+    #[tokio::test]
+    async fn test_tcp_transport() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let accepting = thread::spawn(move || SocketTransport::accept_tcp(&listener).unwrap());
+        let client = SocketTransport::connect_tcp(address).unwrap();
+        let server = accepting.join().unwrap();
+
+        assert_message_passed(&client, &server).await;
+        assert_message_passed(&server, &client).await;
+    }
+
+    #[tokio::test]
+    async fn test_udp_transport() {
+        let first = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let second = UdpSocket::bind("127.0.0.1:0").unwrap();
+        first.connect(second.local_addr().unwrap()).unwrap();
+        second.connect(first.local_addr().unwrap()).unwrap();
+        let first = SocketTransport::from_udp_socket(first).unwrap();
+        let second = SocketTransport::from_udp_socket(second).unwrap();
+
+        assert_message_passed(&first, &second).await;
+    }
+
+    #[tokio::test]
+    async fn test_udp_transport_rejects_oversized_message() {
+        let transport = SocketTransport::bind_udp("127.0.0.1:0", "127.0.0.1:9").unwrap();
+
+        let result = transport
+            .send(
+                uri("/body.access/1/door"),
+                UPayloadBuilder::from_raw(vec![0; MAX_DATAGRAM_SIZE])
+                    .build()
+                    .unwrap(),
+                UAttributesBuilder::publish(UPriority::UpriorityCs1).build(),
+            )
+            .await;
+
+        assert_eq!(result.unwrap_err().get_code(), UCode::ResourceExhausted);
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_for_invalid_attributes() {
+        let transport = SocketTransport::bind_udp("127.0.0.1:0", "127.0.0.1:9").unwrap();
+
+        let result = transport
+            .send(
+                uri("/body.access/1/door"),
+                UPayload::default(),
+                UAttributes::default(),
+            )
+            .await;
+
+        assert_eq!(result.unwrap_err().get_code(), UCode::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_send_validated_message() {
+        let first = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let second = UdpSocket::bind("127.0.0.1:0").unwrap();
+        first.connect(second.local_addr().unwrap()).unwrap();
+        second.connect(first.local_addr().unwrap()).unwrap();
+        let first = SocketTransport::from_udp_socket(first).unwrap();
+        let second = SocketTransport::from_udp_socket(second).unwrap();
+
+        let topic = uri("/body.access/1/door.front_left#Door");
+        let (message_sender, message_receiver) = mpsc::channel();
+        let message_sender = Mutex::new(message_sender);
+        second
+            .register_listener(
+                topic.clone(),
+                Box::new(move |message| message_sender.lock().unwrap().send(message).unwrap()),
+            )
+            .await
+            .unwrap();
+
+        let message = UMessageBuilder::new(
+            topic,
+            UAttributesBuilder::publish(UPriority::UpriorityCs1).build(),
+        )
+        .with_payload(UPayloadBuilder::from_text("open").build().unwrap())
+        .build_validated()
+        .unwrap();
+        first.send_validated(message.clone()).await.unwrap();
+
+        let received = message_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(received, message.into_inner());
+        assert_eq!(first.buffer_stats().returned, 1);
+    }
+}
@@ -0,0 +1,214 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::transport::someip::SerializationError;
+
+/// The version of the SOME/IP protocol that headers are encoded with.
+pub const SOMEIP_PROTOCOL_VERSION: u8 = 0x01;
+
+/// The size of an encoded SOME/IP header in bytes.
+pub const SOMEIP_HEADER_SIZE: usize = 16;
+
+// The part of the header that is covered by the length field.
+const LENGTH_COVERED_HEADER_SIZE: usize = 8;
+
+/// The type of a SOME/IP message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SomeIpMessageType {
+    Request = 0x00,
+    RequestNoReturn = 0x01,
+    Notification = 0x02,
+    Response = 0x80,
+    Error = 0x81,
+}
+
+impl TryFrom<u8> for SomeIpMessageType {
+    type Error = SerializationError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(SomeIpMessageType::Request),
+            0x01 => Ok(SomeIpMessageType::RequestNoReturn),
+            0x02 => Ok(SomeIpMessageType::Notification),
+            0x80 => Ok(SomeIpMessageType::Response),
+            0x81 => Ok(SomeIpMessageType::Error),
+            _ => Err(SerializationError::new(format!(
+                "Unsupported SOME/IP message type [{value:#04x}]"
+            ))),
+        }
+    }
+}
+
+/// The fields of a SOME/IP header that carry information about a message, as described in the
+/// [SOME/IP Protocol Specification](https://www.autosar.org/fileadmin/standards/R22-11/FO/AUTOSAR_PRS_SOMEIPProtocol.pdf).
+///
+/// The length and protocol version fields are derived when encoding the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SomeIpHeader {
+    /// The ID of the service that offers the method or event.
+    pub service_id: u16,
+    /// The ID of the method or event, with the most significant bit set for events.
+    pub method_id: u16,
+    /// The ID of the client that has sent a request, or that a response is sent to.
+    pub client_id: u16,
+    /// The ID that correlates a response with its request.
+    pub session_id: u16,
+    /// The major version of the service's interface.
+    pub interface_version: u8,
+    /// The type of the message.
+    pub message_type: SomeIpMessageType,
+    /// The outcome of a request, `0x00` (`E_OK`) for all other messages.
+    pub return_code: u8,
+}
+
+impl SomeIpHeader {
+    /// Encodes the header as it precedes a payload on the wire.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload_length` - The length of the payload following the header.
+    ///
+    /// # Returns
+    ///
+    /// The encoded header.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload is too large for the header's length field.
+    pub fn to_bytes(
+        &self,
+        payload_length: usize,
+    ) -> Result<[u8; SOMEIP_HEADER_SIZE], SerializationError> {
+        let length = payload_length
+            .checked_add(LENGTH_COVERED_HEADER_SIZE)
+            .and_then(|length| u32::try_from(length).ok())
+            .ok_or_else(|| {
+                SerializationError::new(format!(
+                    "Payload of {payload_length} bytes exceeds maximum SOME/IP message length"
+                ))
+            })?;
+
+        let mut bytes = [0u8; SOMEIP_HEADER_SIZE];
+        bytes[0..2].copy_from_slice(&self.service_id.to_be_bytes());
+        bytes[2..4].copy_from_slice(&self.method_id.to_be_bytes());
+        bytes[4..8].copy_from_slice(&length.to_be_bytes());
+        bytes[8..10].copy_from_slice(&self.client_id.to_be_bytes());
+        bytes[10..12].copy_from_slice(&self.session_id.to_be_bytes());
+        bytes[12] = SOMEIP_PROTOCOL_VERSION;
+        bytes[13] = self.interface_version;
+        bytes[14] = self.message_type as u8;
+        bytes[15] = self.return_code;
+        Ok(bytes)
+    }
+
+    /// Decodes a header received on the wire.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The received bytes, starting with the header.
+    ///
+    /// # Returns
+    ///
+    /// The header, along with the length of the payload that follows it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the bytes are too short to contain a header, or if the header has an
+    /// unsupported protocol version, an invalid length or an unsupported message type.
+    pub fn from_bytes(bytes: &[u8]) -> Result<(SomeIpHeader, usize), SerializationError> {
+        // the header is taken as array, whose fields are at constant offsets within its bounds
+        let Some(bytes) = bytes
+            .get(..SOMEIP_HEADER_SIZE)
+            .and_then(|header| <[u8; SOMEIP_HEADER_SIZE]>::try_from(header).ok())
+        else {
+            return Err(SerializationError::new(format!(
+                "SOME/IP header requires {SOMEIP_HEADER_SIZE} bytes, got {}",
+                bytes.len()
+            )));
+        };
+        if bytes[12] != SOMEIP_PROTOCOL_VERSION {
+            return Err(SerializationError::new(format!(
+                "Unsupported SOME/IP protocol version [{:#04x}]",
+                bytes[12]
+            )));
+        }
+        let length = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+        let payload_length = length
+            .checked_sub(LENGTH_COVERED_HEADER_SIZE)
+            .ok_or_else(|| SerializationError::new(format!("Invalid SOME/IP length [{length}]")))?;
+
+        let header = SomeIpHeader {
+            service_id: u16::from_be_bytes([bytes[0], bytes[1]]),
+            method_id: u16::from_be_bytes([bytes[2], bytes[3]]),
+            client_id: u16::from_be_bytes([bytes[8], bytes[9]]),
+            session_id: u16::from_be_bytes([bytes[10], bytes[11]]),
+            interface_version: bytes[13],
+            message_type: SomeIpMessageType::try_from(bytes[14])?,
+            return_code: bytes[15],
+        };
+        Ok((header, payload_length))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> SomeIpHeader {
+        SomeIpHeader {
+            service_id: 0x1234,
+            method_id: 0x8001,
+            client_id: 0x0042,
+            session_id: 0x0007,
+            interface_version: 0x01,
+            message_type: SomeIpMessageType::Notification,
+            return_code: 0x00,
+        }
+    }
+
+    #[test]
+    fn test_encode_header() {
+        assert_eq!(
+            header().to_bytes(4).unwrap(),
+            [
+                0x12, 0x34, 0x80, 0x01, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x42, 0x00, 0x07, 0x01, 0x01,
+                0x02, 0x00
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_encoded_header() {
+        let bytes = header().to_bytes(1024).unwrap();
+        assert_eq!(SomeIpHeader::from_bytes(&bytes).unwrap(), (header(), 1024));
+    }
+
+    #[test]
+    fn test_decode_header_fails_for_invalid_bytes() {
+        let bytes = header().to_bytes(0).unwrap();
+        assert!(SomeIpHeader::from_bytes(&bytes[..15]).is_err());
+
+        let mut wrong_version = bytes;
+        wrong_version[12] = 0x02;
+        assert!(SomeIpHeader::from_bytes(&wrong_version).is_err());
+
+        let mut wrong_length = bytes;
+        wrong_length[7] = 0x07;
+        assert!(SomeIpHeader::from_bytes(&wrong_length).is_err());
+
+        let mut wrong_type = bytes;
+        wrong_type[14] = 0x40;
+        assert!(SomeIpHeader::from_bytes(&wrong_type).is_err());
+    }
+}
@@ -0,0 +1,395 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::transport::someip::{
+    SerializationError, SomeIpHeader, SomeIpMessageType, SOMEIP_HEADER_SIZE,
+};
+use crate::uprotocol::{UAttributes, UCode, UEntity, UMessageType, UPayload, UPriority, UUri};
+use crate::uri::builder::resourcebuilder::UResourceBuilder;
+use crate::uuid::builder::UUIDv8Builder;
+
+const E_OK: u8 = 0x00;
+const E_NOT_OK: u8 = 0x01;
+const E_UNKNOWN_SERVICE: u8 = 0x02;
+const E_UNKNOWN_METHOD: u8 = 0x03;
+const E_NOT_READY: u8 = 0x04;
+const E_NOT_REACHABLE: u8 = 0x05;
+const E_TIMEOUT: u8 = 0x06;
+const E_WRONG_PROTOCOL_VERSION: u8 = 0x07;
+const E_WRONG_INTERFACE_VERSION: u8 = 0x08;
+const E_MALFORMED_MESSAGE: u8 = 0x09;
+const E_WRONG_MESSAGE_TYPE: u8 = 0x0a;
+
+/// Maps uProtocol message attributes and addresses to SOME/IP header fields, independent of any particular
+/// SOME/IP stack.
+///
+/// The ID of a `UUri`'s entity is mapped to the SOME/IP service ID, its major version to the interface version,
+/// and the ID of its resource to the method ID. uProtocol reserves resource IDs from `0x8000` on for topics,
+/// which matches the event IDs of SOME/IP. Published messages become notifications, and a response carrying a
+/// communication status other than `UCode::Ok` becomes an error message with the corresponding return code.
+///
+/// Request IDs do not fit into SOME/IP headers, which correlate requests and responses by means of client and
+/// session IDs. Keeping track of them is up to the transport, see [`SomeIpTransport`](super::SomeIpTransport).
+pub struct SomeIpMapping;
+
+impl SomeIpMapping {
+    /// Maps a `UUri` to a SOME/IP service ID and method ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The address of a method or topic.
+    ///
+    /// # Returns
+    ///
+    /// The service ID and method ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the `UUri` lacks the entity or resource ID, or if they exceed 16 bits.
+    pub fn to_message_id(uri: &UUri) -> Result<(u16, u16), SerializationError> {
+        let service_id = uri
+            .entity
+            .as_ref()
+            .and_then(|entity| entity.id)
+            .ok_or_else(|| SerializationError::new("UUri has no entity ID"))?;
+        let method_id = uri
+            .resource
+            .as_ref()
+            .and_then(|resource| resource.id)
+            .ok_or_else(|| SerializationError::new("UUri has no resource ID"))?;
+        let service_id = u16::try_from(service_id).map_err(|_| {
+            SerializationError::new(format!(
+                "Entity ID [{service_id}] exceeds SOME/IP service ID"
+            ))
+        })?;
+        let method_id = u16::try_from(method_id).map_err(|_| {
+            SerializationError::new(format!(
+                "Resource ID [{method_id}] exceeds SOME/IP method ID"
+            ))
+        })?;
+        Ok((service_id, method_id))
+    }
+
+    /// Maps the address and attributes of a message to a SOME/IP header.
+    ///
+    /// The client ID and session ID of the header are `0`, to be set by the transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The address of the method or topic the message is sent to.
+    /// * `attributes` - The message's attributes.
+    ///
+    /// # Returns
+    ///
+    /// The SOME/IP header.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the `UUri` cannot be mapped to a message ID, lacks a major version fitting into
+    /// 8 bits, or if the attributes have an unknown message type.
+    pub fn to_header(
+        uri: &UUri,
+        attributes: &UAttributes,
+    ) -> Result<SomeIpHeader, SerializationError> {
+        let (service_id, method_id) = Self::to_message_id(uri)?;
+        let interface_version = uri
+            .entity
+            .as_ref()
+            .and_then(|entity| entity.version_major)
+            .and_then(|version| u8::try_from(version).ok())
+            .ok_or_else(|| {
+                SerializationError::new("UUri has no major version fitting into 8 bits")
+            })?;
+        let message_type = match UMessageType::try_from(attributes.r#type) {
+            Ok(UMessageType::UmessageTypePublish) => SomeIpMessageType::Notification,
+            Ok(UMessageType::UmessageTypeRequest) => SomeIpMessageType::Request,
+            Ok(UMessageType::UmessageTypeResponse) => Self::response_type(attributes),
+            _ => {
+                return Err(SerializationError::new(format!(
+                    "Unsupported message type [{}]",
+                    attributes.r#type
+                )))
+            }
+        };
+
+        Ok(SomeIpHeader {
+            service_id,
+            method_id,
+            client_id: 0,
+            session_id: 0,
+            interface_version,
+            message_type,
+            return_code: Self::to_return_code(Self::commstatus(attributes)),
+        })
+    }
+
+    /// Derives the header of a response from the header of the request it responds to.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The header of the received request.
+    /// * `attributes` - The attributes of the response.
+    ///
+    /// # Returns
+    ///
+    /// The request's header, with the message type and return code reflecting the response's communication status.
+    pub fn to_response_header(request: &SomeIpHeader, attributes: &UAttributes) -> SomeIpHeader {
+        SomeIpHeader {
+            message_type: Self::response_type(attributes),
+            return_code: Self::to_return_code(Self::commstatus(attributes)),
+            ..*request
+        }
+    }
+
+    /// Maps a SOME/IP header back to the address and attributes of a message.
+    ///
+    /// The `UUri` is in micro form, i.e. it only contains the IDs of the entity and resource. The attributes get a new
+    /// message ID, and the priority that uProtocol demands for the kind of message: `UPriority::UpriorityCs4` for
+    /// RPC messages and `UPriority::UpriorityCs1` for notifications. They have no request ID, which is left to the
+    /// transport to restore.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The header of a received SOME/IP message.
+    ///
+    /// # Returns
+    ///
+    /// The address of the method or topic the message has been sent to, and the message's attributes.
+    pub fn from_header(header: &SomeIpHeader) -> (UUri, UAttributes) {
+        let uri = UUri {
+            entity: Some(UEntity {
+                id: Some(u32::from(header.service_id)),
+                version_major: Some(u32::from(header.interface_version)),
+                ..Default::default()
+            }),
+            resource: Some(UResourceBuilder::from_id(u32::from(header.method_id))),
+            ..Default::default()
+        };
+
+        let (message_type, priority) = match header.message_type {
+            SomeIpMessageType::Request | SomeIpMessageType::RequestNoReturn => {
+                (UMessageType::UmessageTypeRequest, UPriority::UpriorityCs4)
+            }
+            SomeIpMessageType::Response | SomeIpMessageType::Error => {
+                (UMessageType::UmessageTypeResponse, UPriority::UpriorityCs4)
+            }
+            SomeIpMessageType::Notification => {
+                (UMessageType::UmessageTypePublish, UPriority::UpriorityCs1)
+            }
+        };
+        let commstatus = match header.message_type {
+            SomeIpMessageType::Response => Some(UCode::Ok.into()),
+            SomeIpMessageType::Error => Some(Self::from_return_code(header.return_code).into()),
+            _ => None,
+        };
+
+        let attributes = UAttributes {
+            id: Some(UUIDv8Builder::new().build()),
+            r#type: message_type.into(),
+            priority: priority.into(),
+            commstatus,
+            ..Default::default()
+        };
+        (uri, attributes)
+    }
+
+    fn commstatus(attributes: &UAttributes) -> UCode {
+        // a status that is not a uProtocol code cannot tell success
+        attributes.commstatus.map_or(UCode::Ok, |code| {
+            UCode::try_from_value(code).unwrap_or(UCode::Unknown)
+        })
+    }
+
+    fn response_type(attributes: &UAttributes) -> SomeIpMessageType {
+        if Self::commstatus(attributes) == UCode::Ok {
+            SomeIpMessageType::Response
+        } else {
+            SomeIpMessageType::Error
+        }
+    }
+
+    /// Maps a `UCode` to a SOME/IP return code.
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - The communication status of a response.
+    ///
+    /// # Returns
+    ///
+    /// The return code that comes closest to the `UCode`, `E_NOT_OK` if there is none.
+    pub fn to_return_code(code: UCode) -> u8 {
+        match code {
+            UCode::Ok => E_OK,
+            UCode::NotFound => E_UNKNOWN_SERVICE,
+            UCode::Unimplemented => E_UNKNOWN_METHOD,
+            UCode::Unavailable => E_NOT_READY,
+            UCode::DeadlineExceeded => E_TIMEOUT,
+            UCode::InvalidArgument => E_MALFORMED_MESSAGE,
+            _ => E_NOT_OK,
+        }
+    }
+
+    /// Gets the size of the SOME/IP message that a payload is sent in, so that transports can decide on
+    /// segmenting it, e.g. by means of SOME/IP-TP, before encoding it.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to send.
+    ///
+    /// # Returns
+    ///
+    /// The size of the header and the payload's data, as passed on as they are.
+    pub fn message_len(payload: &UPayload) -> usize {
+        SOMEIP_HEADER_SIZE + payload.data_len()
+    }
+
+    /// Maps a SOME/IP return code to a `UCode`.
+    ///
+    /// # Arguments
+    ///
+    /// * `return_code` - The return code of a received response or error message.
+    ///
+    /// # Returns
+    ///
+    /// The corresponding `UCode`, `UCode::Unknown` for `E_NOT_OK` and reserved or service specific return codes.
+    pub fn from_return_code(return_code: u8) -> UCode {
+        match return_code {
+            E_OK => UCode::Ok,
+            E_UNKNOWN_SERVICE => UCode::NotFound,
+            E_UNKNOWN_METHOD => UCode::Unimplemented,
+            E_NOT_READY | E_NOT_REACHABLE => UCode::Unavailable,
+            E_TIMEOUT => UCode::DeadlineExceeded,
+            E_WRONG_PROTOCOL_VERSION
+            | E_WRONG_INTERFACE_VERSION
+            | E_MALFORMED_MESSAGE
+            | E_WRONG_MESSAGE_TYPE => UCode::InvalidArgument,
+            _ => UCode::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::UResource;
+
+    fn uri(entity_id: u32, version: u32, resource_id: u32) -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                id: Some(entity_id),
+                version_major: Some(version),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                name: "door".to_string(),
+                id: Some(resource_id),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_to_message_id() {
+        assert_eq!(
+            SomeIpMapping::to_message_id(&uri(0x1234, 1, 0x8001)).unwrap(),
+            (0x1234, 0x8001)
+        );
+        assert!(SomeIpMapping::to_message_id(&uri(0x10000, 1, 0x8001)).is_err());
+        assert!(SomeIpMapping::to_message_id(&uri(0x1234, 1, 0x10000)).is_err());
+        assert!(SomeIpMapping::to_message_id(&UUri::default()).is_err());
+    }
+
+    #[test]
+    fn test_message_len() {
+        let payload = UPayload {
+            data: Some(crate::uprotocol::Data::Value(vec![0; 100])),
+            ..Default::default()
+        };
+        assert_eq!(SomeIpMapping::message_len(&payload), 116);
+        assert_eq!(
+            SomeIpMapping::message_len(&UPayload::default()),
+            SOMEIP_HEADER_SIZE
+        );
+    }
+
+    #[test]
+    fn test_publish_maps_to_notification() {
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+
+        let header = SomeIpMapping::to_header(&uri(0x1234, 2, 0x8001), &attributes).unwrap();
+        assert_eq!(header.message_type, SomeIpMessageType::Notification);
+        assert_eq!(header.interface_version, 2);
+        assert_eq!(header.return_code, E_OK);
+
+        let (received_uri, received) = SomeIpMapping::from_header(&header);
+        assert_eq!(received.r#type(), UMessageType::UmessageTypePublish);
+        assert_eq!(received.priority(), UPriority::UpriorityCs1);
+        assert_eq!(
+            SomeIpMapping::to_message_id(&received_uri).unwrap(),
+            (0x1234, 0x8001)
+        );
+    }
+
+    #[test]
+    fn test_failed_response_maps_to_error() {
+        let request =
+            UAttributesBuilder::request(UPriority::UpriorityCs4, uri(1, 1, 3), 1000).build();
+        let attributes = UAttributesBuilder::response(
+            UPriority::UpriorityCs4,
+            uri(2, 1, 0),
+            request.id.unwrap(),
+        )
+        .with_commstatus(UCode::Unimplemented.into())
+        .build();
+
+        let header = SomeIpMapping::to_header(&uri(1, 1, 3), &attributes).unwrap();
+        assert_eq!(header.message_type, SomeIpMessageType::Error);
+        assert_eq!(header.return_code, E_UNKNOWN_METHOD);
+
+        let (_uri, received) = SomeIpMapping::from_header(&header);
+        assert_eq!(received.r#type(), UMessageType::UmessageTypeResponse);
+        assert_eq!(received.commstatus, Some(UCode::Unimplemented.into()));
+    }
+
+    #[test]
+    fn test_to_header_fails_without_version() {
+        let mut topic = uri(0x1234, 1, 0x8001);
+        topic.entity.as_mut().unwrap().version_major = None;
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+
+        assert!(SomeIpMapping::to_header(&topic, &attributes).is_err());
+    }
+
+    #[test_case(UCode::Ok, E_OK; "ok")]
+    #[test_case(UCode::NotFound, E_UNKNOWN_SERVICE; "not found")]
+    #[test_case(UCode::Unavailable, E_NOT_READY; "unavailable")]
+    #[test_case(UCode::DeadlineExceeded, E_TIMEOUT; "deadline exceeded")]
+    #[test_case(UCode::InvalidArgument, E_MALFORMED_MESSAGE; "invalid argument")]
+    fn test_return_code_round_trip(code: UCode, return_code: u8) {
+        assert_eq!(SomeIpMapping::to_return_code(code), return_code);
+        assert_eq!(SomeIpMapping::from_return_code(return_code), code);
+    }
+
+    #[test]
+    fn test_unknown_return_code() {
+        assert_eq!(SomeIpMapping::to_return_code(UCode::Aborted), E_NOT_OK);
+        assert_eq!(SomeIpMapping::from_return_code(E_NOT_OK), UCode::Unknown);
+        assert_eq!(SomeIpMapping::from_return_code(0x20), UCode::Unknown);
+    }
+}
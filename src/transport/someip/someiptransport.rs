@@ -0,0 +1,525 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::transport::datamodel::UTransport;
+use crate::transport::someip::{SomeIpHeader, SomeIpMapping, SomeIpMessageType};
+use crate::transport::validator::Validators;
+use crate::uprotocol::{
+    Data, UAttributes, UCode, UEntity, UMessage, UMessageType, UPayload, UPayloadFormat, UStatus,
+    UUri, Uuid,
+};
+use crate::uuid::builder::UUIDv8Builder;
+
+type Listener = Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>;
+
+/// The glue between a [`SomeIpTransport`] and a SOME/IP stack, e.g. vsomeip by means of its C++ API, or an
+/// implementation in Rust.
+///
+/// The stack is expected to take care of service discovery, as well as of offering services and subscribing to
+/// event groups as configured for the application. Messages received by the stack are handed to the transport by
+/// means of [`SomeIpTransport::receive`].
+pub trait SomeIpBinding: Send + Sync {
+    /// Sends a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The header of the message.
+    /// * `payload` - The payload of the message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the stack fails to send the message.
+    fn send(&self, header: &SomeIpHeader, payload: &[u8]) -> Result<(), UStatus>;
+
+    /// Asks the stack to hand messages for a method or event to the transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_id` - The ID of the service.
+    /// * `method_id` - The ID of the method or event.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the stack cannot receive messages for the method or event.
+    fn register(&self, service_id: u16, method_id: u16) -> Result<(), UStatus>;
+
+    /// Tells the stack that messages for a method or event are no longer of interest.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_id` - The ID of the service.
+    /// * `method_id` - The ID of the method or event.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the stack fails to stop receiving messages for the method or event.
+    fn unregister(&self, service_id: u16, method_id: u16) -> Result<(), UStatus>;
+}
+
+/// `UTransport` adapter that sends and receives messages by means of a SOME/IP stack, plugged in as [`SomeIpBinding`].
+///
+/// Addresses and attributes are mapped to SOME/IP headers as described for [`SomeIpMapping`], and payloads are passed
+/// on as they are. Listeners are registered for the service ID and method ID that their topic maps to, and are passed
+/// the requests, responses and notifications received for them.
+///
+/// SOME/IP correlates responses with requests by means of client and session IDs, which the transport keeps track of:
+/// requests are sent with the transport's client ID and a new session ID, and the request ID is restored for their
+/// responses. Received requests are remembered by their new message ID, so that responses referring to it are sent
+/// to the requesting client.
+pub struct SomeIpTransport<B: SomeIpBinding> {
+    binding: B,
+    client_id: u16,
+    session_id: AtomicU16,
+    listeners: Mutex<HashMap<(u16, u16), Vec<(String, Listener)>>>,
+    sent_requests: Mutex<HashMap<u16, Uuid>>,
+    received_requests: Mutex<HashMap<String, SomeIpHeader>>,
+    uuid_builder: UUIDv8Builder,
+}
+
+impl<B: SomeIpBinding> SomeIpTransport<B> {
+    /// Creates a transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `binding` - The SOME/IP stack to send and receive messages with.
+    /// * `client_id` - The SOME/IP client ID of the application.
+    pub fn new(binding: B, client_id: u16) -> SomeIpTransport<B> {
+        SomeIpTransport {
+            binding,
+            client_id,
+            session_id: AtomicU16::new(0),
+            listeners: Mutex::new(HashMap::new()),
+            sent_requests: Mutex::new(HashMap::new()),
+            received_requests: Mutex::new(HashMap::new()),
+            uuid_builder: UUIDv8Builder::new(),
+        }
+    }
+
+    /// Gets the SOME/IP stack this transport sends and receives messages with.
+    pub fn binding(&self) -> &B {
+        &self.binding
+    }
+
+    /// Passes a message received by the SOME/IP stack to the listeners registered for it.
+    ///
+    /// Responses to requests that have not been sent by this transport are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The header of the received message.
+    /// * `payload` - The payload of the received message.
+    pub fn receive(&self, header: &SomeIpHeader, payload: &[u8]) {
+        let (source, mut attributes) = SomeIpMapping::from_header(header);
+        match header.message_type {
+            SomeIpMessageType::Request | SomeIpMessageType::RequestNoReturn => {
+                if let Some(id) = &attributes.id {
+                    self.received_requests
+                        .lock()
+                        .unwrap()
+                        .insert(id.to_hyphenated_string(), *header);
+                }
+            }
+            SomeIpMessageType::Response | SomeIpMessageType::Error => {
+                let Some(reqid) = self
+                    .sent_requests
+                    .lock()
+                    .unwrap()
+                    .remove(&header.session_id)
+                else {
+                    return;
+                };
+                attributes.reqid = Some(reqid);
+            }
+            SomeIpMessageType::Notification => {}
+        }
+
+        let message = UMessage {
+            source: Some(source),
+            attributes: Some(attributes),
+            payload: Some(UPayload {
+                length: i32::try_from(payload.len()).ok(),
+                data: Some(Data::Value(payload.to_vec())),
+                format: UPayloadFormat::UpayloadFormatSomeip.into(),
+            }),
+        };
+        let listeners = self.listeners.lock().unwrap();
+        for (_id, listener) in listeners
+            .get(&(header.service_id, header.method_id))
+            .into_iter()
+            .flatten()
+        {
+            listener(Ok(message.clone()));
+        }
+    }
+
+    // SOME/IP session IDs start at 1 and skip 0 when wrapping around.
+    fn next_session_id(&self) -> u16 {
+        loop {
+            let session_id = self
+                .session_id
+                .fetch_add(1, Ordering::Relaxed)
+                .wrapping_add(1);
+            if session_id != 0 {
+                return session_id;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<B: SomeIpBinding> UTransport for SomeIpTransport<B> {
+    // SOME/IP does not authenticate applications.
+    async fn authenticate(&self, _entity: UEntity) -> Result<(), UStatus> {
+        Err(UStatus::fail_with_code(
+            UCode::Unimplemented,
+            "SOME/IP does not support authentication",
+        ))
+    }
+
+    async fn send(
+        &self,
+        topic: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+    ) -> Result<(), UStatus> {
+        Validators::get_validator(&attributes)
+            .validate(&attributes)
+            .map_err(UStatus::from)?;
+        let data = payload.resolve_data(None)?.unwrap_or_default();
+
+        let header = if attributes.r#type() == UMessageType::UmessageTypeResponse {
+            let request_id = attributes
+                .reqid
+                .as_ref()
+                .map(Uuid::to_hyphenated_string)
+                .unwrap_or_default();
+            let Some(request) = self.received_requests.lock().unwrap().remove(&request_id) else {
+                return Err(UStatus::fail_with_code(
+                    UCode::NotFound,
+                    &format!("No pending request with ID {request_id}"),
+                ));
+            };
+            SomeIpMapping::to_response_header(&request, &attributes)
+        } else {
+            let mut header = SomeIpMapping::to_header(&topic, &attributes)
+                .map_err(|e| UStatus::fail_with_code(UCode::InvalidArgument, &e.to_string()))?;
+            header.session_id = self.next_session_id();
+            if header.message_type == SomeIpMessageType::Request {
+                header.client_id = self.client_id;
+                if let Some(id) = attributes.id {
+                    self.sent_requests
+                        .lock()
+                        .unwrap()
+                        .insert(header.session_id, id);
+                }
+            }
+            header
+        };
+        self.binding.send(&header, data)
+    }
+
+    async fn register_listener(&self, topic: UUri, listener: Listener) -> Result<String, UStatus> {
+        let message_id = SomeIpMapping::to_message_id(&topic)
+            .map_err(|e| UStatus::fail_with_code(UCode::InvalidArgument, &e.to_string()))?;
+        let mut listeners = self.listeners.lock().unwrap();
+        let registered = listeners.entry(message_id).or_default();
+        if registered.is_empty() {
+            self.binding.register(message_id.0, message_id.1)?;
+        }
+        let id = self.uuid_builder.build().to_hyphenated_string();
+        registered.push((id.clone(), listener));
+        Ok(id)
+    }
+
+    async fn unregister_listener(&self, topic: UUri, listener: &str) -> Result<(), UStatus> {
+        let message_id = SomeIpMapping::to_message_id(&topic)
+            .map_err(|e| UStatus::fail_with_code(UCode::InvalidArgument, &e.to_string()))?;
+        let mut listeners = self.listeners.lock().unwrap();
+        let registered = listeners.entry(message_id).or_default();
+        let Some(index) = registered.iter().position(|(id, _)| id == listener) else {
+            return Err(UStatus::fail_with_code(
+                UCode::NotFound,
+                &format!("No listener with ID {listener}"),
+            ));
+        };
+        registered.remove(index);
+        if registered.is_empty() {
+            listeners.remove(&message_id);
+            self.binding.unregister(message_id.0, message_id.1)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    use crate::transport::builder::{UAttributesBuilder, UPayloadBuilder};
+    use crate::uprotocol::uresource::UResourceId;
+    use crate::uprotocol::{UPriority, UResource};
+    use crate::uri::builder::resourcebuilder::UResourceBuilder;
+
+    #[derive(Default)]
+    struct RecordingBinding {
+        sent: Mutex<Vec<(SomeIpHeader, Vec<u8>)>>,
+        registered: Mutex<Vec<(u16, u16)>>,
+    }
+
+    impl SomeIpBinding for RecordingBinding {
+        fn send(&self, header: &SomeIpHeader, payload: &[u8]) -> Result<(), UStatus> {
+            self.sent.lock().unwrap().push((*header, payload.to_vec()));
+            Ok(())
+        }
+
+        fn register(&self, service_id: u16, method_id: u16) -> Result<(), UStatus> {
+            self.registered
+                .lock()
+                .unwrap()
+                .push((service_id, method_id));
+            Ok(())
+        }
+
+        fn unregister(&self, service_id: u16, method_id: u16) -> Result<(), UStatus> {
+            self.registered
+                .lock()
+                .unwrap()
+                .retain(|id| *id != (service_id, method_id));
+            Ok(())
+        }
+    }
+
+    fn uri(entity_id: u32, resource: UResource) -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                id: Some(entity_id),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(resource),
+            ..Default::default()
+        }
+    }
+
+    fn method(entity_id: u32) -> UUri {
+        uri(
+            entity_id,
+            UResourceBuilder::for_rpc_request(Some("open".to_string()), Some(UResourceId::new(3))),
+        )
+    }
+
+    fn topic() -> UUri {
+        uri(
+            0x1234,
+            UResource {
+                name: "door".to_string(),
+                id: Some(0x8001),
+                ..Default::default()
+            },
+        )
+    }
+
+    fn recording_listener(received: &Arc<Mutex<Vec<UMessage>>>) -> Listener {
+        let received = received.clone();
+        Box::new(move |message| received.lock().unwrap().push(message.unwrap()))
+    }
+
+    #[tokio::test]
+    async fn test_request_and_response_are_correlated() {
+        let transport = SomeIpTransport::new(RecordingBinding::default(), 0x0042);
+        let method = method(0x1234);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        transport
+            .register_listener(method.clone(), recording_listener(&received))
+            .await
+            .unwrap();
+
+        let request =
+            UAttributesBuilder::request(UPriority::UpriorityCs4, method.clone(), 1000).build();
+        let payload = UPayloadBuilder::from_raw(vec![1, 2, 3]).build().unwrap();
+        transport
+            .send(method.clone(), payload, request.clone())
+            .await
+            .unwrap();
+
+        let (header, data) = transport.binding().sent.lock().unwrap()[0].clone();
+        assert_eq!(header.message_type, SomeIpMessageType::Request);
+        assert_eq!(header.client_id, 0x0042);
+        assert_eq!(header.session_id, 1);
+        assert_eq!(data, vec![1, 2, 3]);
+
+        transport.receive(
+            &SomeIpHeader {
+                message_type: SomeIpMessageType::Response,
+                ..header
+            },
+            &[4, 5],
+        );
+        let response = received.lock().unwrap().pop().unwrap();
+        let attributes = response.attributes.unwrap();
+        assert_eq!(attributes.r#type(), UMessageType::UmessageTypeResponse);
+        assert_eq!(attributes.reqid, request.id);
+        assert_eq!(
+            response.payload.unwrap().data,
+            Some(Data::Value(vec![4, 5]))
+        );
+
+        // responses are only passed on once
+        transport.receive(
+            &SomeIpHeader {
+                message_type: SomeIpMessageType::Response,
+                ..header
+            },
+            &[],
+        );
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_response_is_sent_to_requesting_client() {
+        let transport = SomeIpTransport::new(RecordingBinding::default(), 0x0001);
+        let method = method(0x1234);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        transport
+            .register_listener(method.clone(), recording_listener(&received))
+            .await
+            .unwrap();
+
+        let request_header = SomeIpHeader {
+            service_id: 0x1234,
+            method_id: 0x0003,
+            client_id: 0x0077,
+            session_id: 0x0009,
+            interface_version: 1,
+            message_type: SomeIpMessageType::Request,
+            return_code: 0,
+        };
+        transport.receive(&request_header, &[1]);
+        let request = received.lock().unwrap().pop().unwrap();
+
+        let response = UAttributesBuilder::response(
+            UPriority::UpriorityCs4,
+            method.clone(),
+            request.attributes.unwrap().id.unwrap(),
+        )
+        .with_commstatus(UCode::Unavailable.into())
+        .build();
+        transport
+            .send(method.clone(), UPayload::default(), response.clone())
+            .await
+            .unwrap();
+
+        let (header, _data) = transport.binding().sent.lock().unwrap()[0].clone();
+        assert_eq!(
+            header,
+            SomeIpHeader {
+                message_type: SomeIpMessageType::Error,
+                return_code: SomeIpMapping::to_return_code(UCode::Unavailable),
+                ..request_header
+            }
+        );
+        assert_eq!(
+            transport
+                .send(method, UPayload::default(), response)
+                .await
+                .unwrap_err()
+                .get_code(),
+            UCode::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn test_register_and_unregister_listener() {
+        let transport = SomeIpTransport::new(RecordingBinding::default(), 0x0001);
+        let topic = topic();
+        let received = Arc::new(Mutex::new(Vec::new()));
+
+        let first = transport
+            .register_listener(topic.clone(), recording_listener(&received))
+            .await
+            .unwrap();
+        let second = transport
+            .register_listener(topic.clone(), recording_listener(&received))
+            .await
+            .unwrap();
+        assert_eq!(
+            *transport.binding().registered.lock().unwrap(),
+            vec![(0x1234, 0x8001)]
+        );
+
+        transport.receive(
+            &SomeIpHeader {
+                service_id: 0x1234,
+                method_id: 0x8001,
+                client_id: 0,
+                session_id: 1,
+                interface_version: 1,
+                message_type: SomeIpMessageType::Notification,
+                return_code: 0,
+            },
+            &[],
+        );
+        assert_eq!(received.lock().unwrap().len(), 2);
+
+        transport
+            .unregister_listener(topic.clone(), &first)
+            .await
+            .unwrap();
+        transport
+            .unregister_listener(topic.clone(), &second)
+            .await
+            .unwrap();
+        assert!(transport.binding().registered.lock().unwrap().is_empty());
+        assert_eq!(
+            transport
+                .unregister_listener(topic, &first)
+                .await
+                .unwrap_err()
+                .get_code(),
+            UCode::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_for_uri_without_ids() {
+        let transport = SomeIpTransport::new(RecordingBinding::default(), 0x0001);
+        let topic = UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from("door")),
+            ..Default::default()
+        };
+
+        let status = transport
+            .send(
+                topic,
+                UPayload::default(),
+                UAttributesBuilder::publish(UPriority::UpriorityCs1).build(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(status.get_code(), UCode::InvalidArgument);
+    }
+}
@@ -0,0 +1,191 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::{Arc, RwLock};
+
+use prost_reflect::{DescriptorPool, MessageDescriptor};
+
+use crate::transport::transcoder::SerializationError;
+
+// descriptors of the protoc-generated types, see build.rs
+const FILE_DESCRIPTOR_SET: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/file_descriptor_set.bin"));
+
+/// `SchemaRegistry` resolves the schemas of payloads to the descriptors of their message types, for transcoding
+/// payloads with [`UPayloadTranscoder::transcode_with_registry`] and validating them with a
+/// [`PayloadSchemaValidator`](crate::transport::validator::PayloadSchemaValidator), e.g. at the boundaries to
+/// uEntities that are not trusted to send well-formed payloads.
+///
+/// Schemas are identified by the type URLs of `google.protobuf.Any`, like
+/// `type.googleapis.com/uprotocol.v1.UStatus`, or by the full names of the message types, like `uprotocol.v1.UStatus`.
+/// Implementations backed by a remote schema registry cache the descriptors they have fetched, as schemas are
+/// resolved for each message.
+///
+/// [`UPayloadTranscoder::transcode_with_registry`]:
+///     crate::transport::transcoder::UPayloadTranscoder::transcode_with_registry
+pub trait SchemaRegistry: Send + Sync {
+    /// Resolves a schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `schema` - The type URL or the full name of a message type.
+    ///
+    /// # Returns
+    ///
+    /// The descriptor of the message type, or `None` if the schema is unknown.
+    fn resolve(&self, schema: &str) -> Option<MessageDescriptor>;
+}
+
+impl<S: SchemaRegistry + ?Sized> SchemaRegistry for Arc<S> {
+    fn resolve(&self, schema: &str) -> Option<MessageDescriptor> {
+        (**self).resolve(schema)
+    }
+}
+
+// the full name of the message type that a schema refers to, i.e. the part of a type URL after its last `/`
+fn message_name(schema: &str) -> &str {
+    schema.rsplit('/').next().unwrap_or(schema)
+}
+
+/// `InMemorySchemaRegistry` resolves schemas from the descriptors of the message types it has been given, as
+/// compiled by `protoc` into file descriptor sets.
+///
+/// A registry starts out with the well-known types of protobuf, like `google.protobuf.Duration`, and can be seeded
+/// with the uProtocol types and the file descriptor sets of an application, e.g. as written by `compile_protos` of
+/// the `build` module at build time and included with `include_bytes!`.
+pub struct InMemorySchemaRegistry {
+    pool: RwLock<DescriptorPool>,
+}
+
+impl Default for InMemorySchemaRegistry {
+    fn default() -> Self {
+        InMemorySchemaRegistry::new()
+    }
+}
+
+impl InMemorySchemaRegistry {
+    /// Creates a registry of the well-known types of protobuf.
+    pub fn new() -> Self {
+        InMemorySchemaRegistry {
+            pool: RwLock::new(DescriptorPool::global()),
+        }
+    }
+
+    /// Creates a registry of the well-known types of protobuf and of the uProtocol types, like
+    /// `uprotocol.v1.UStatus` and the messages of the core services.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the descriptors of the uProtocol types that have been compiled into the SDK
+    /// are invalid.
+    pub fn with_uprotocol_types() -> Result<Self, SerializationError> {
+        let registry = InMemorySchemaRegistry::new();
+        registry.add_file_descriptor_set(FILE_DESCRIPTOR_SET)?;
+        Ok(registry)
+    }
+
+    /// Adds the message types of an encoded `google.protobuf.FileDescriptorSet`.
+    ///
+    /// The set must contain the files that its files import, unless they have been added before, as written by
+    /// `protoc` with option `--include_imports`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The encoded file descriptor set.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the bytes are not a valid file descriptor set, or conflict with the files
+    /// added before. The registry is left unchanged then.
+    pub fn add_file_descriptor_set(&self, bytes: &[u8]) -> Result<(), SerializationError> {
+        let mut pool = self.pool.write().unwrap();
+        // the pool is cloned so that it stays unchanged if some of the files are invalid
+        let mut extended = pool.clone();
+        extended
+            .decode_file_descriptor_set(bytes)
+            .map_err(|e| SerializationError::new(format!("Invalid file descriptor set: {e}")))?;
+        *pool = extended;
+        Ok(())
+    }
+
+    /// Gets the full names of the message types in this registry.
+    pub fn message_names(&self) -> Vec<String> {
+        self.pool
+            .read()
+            .unwrap()
+            .all_messages()
+            .map(|descriptor| descriptor.full_name().to_string())
+            .collect()
+    }
+}
+
+impl SchemaRegistry for InMemorySchemaRegistry {
+    fn resolve(&self, schema: &str) -> Option<MessageDescriptor> {
+        self.pool
+            .read()
+            .unwrap()
+            .get_message_by_name(message_name(schema))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prost::Message;
+    use prost_types::{DescriptorProto, FileDescriptorProto, FileDescriptorSet};
+
+    #[test]
+    fn test_resolve_by_type_url_and_name() {
+        let registry = InMemorySchemaRegistry::with_uprotocol_types().unwrap();
+
+        let descriptor = registry
+            .resolve("type.googleapis.com/uprotocol.v1.UStatus")
+            .unwrap();
+        assert_eq!(descriptor.full_name(), "uprotocol.v1.UStatus");
+        assert!(registry.resolve("google.protobuf.Duration").is_some());
+        assert!(registry
+            .resolve("type.googleapis.com/example.Unknown")
+            .is_none());
+        assert!(InMemorySchemaRegistry::new()
+            .resolve("uprotocol.v1.UStatus")
+            .is_none());
+    }
+
+    #[test]
+    fn test_add_file_descriptor_set() {
+        let registry = InMemorySchemaRegistry::new();
+        let set = FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("example/door.proto".to_string()),
+                package: Some("example".to_string()),
+                message_type: vec![DescriptorProto {
+                    name: Some("Door".to_string()),
+                    ..Default::default()
+                }],
+                syntax: Some("proto3".to_string()),
+                ..Default::default()
+            }],
+        };
+
+        registry
+            .add_file_descriptor_set(&set.encode_to_vec())
+            .unwrap();
+        assert!(registry.resolve("example.Door").is_some());
+        assert!(registry
+            .message_names()
+            .contains(&"example.Door".to_string()));
+        assert!(registry.add_file_descriptor_set(&[0xff]).is_err());
+        assert!(registry.resolve("example.Door").is_some());
+    }
+}
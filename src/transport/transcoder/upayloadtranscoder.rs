@@ -0,0 +1,307 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use prost::Message;
+use prost_reflect::{DynamicMessage, MessageDescriptor, ReflectMessage};
+
+use crate::transport::builder::UPayloadBuilder;
+use crate::transport::transcoder::{SchemaRegistry, SerializationError};
+use crate::uprotocol::{UPayload, UPayloadFormat};
+
+const TYPE_URL_PREFIX: &str = "type.googleapis.com/";
+
+/// Converts `UPayload`s between the JSON and protobuf formats, based on the descriptor of the contained message type.
+///
+/// Protobuf payloads are expected to contain the message wrapped in a `google.protobuf.Any`, as created by
+/// [`UPayloadBuilder::from_protobuf`]. JSON payloads contain the message in its
+/// [canonical JSON mapping](https://protobuf.dev/programming-guides/proto3/#json).
+pub struct UPayloadTranscoder;
+
+impl UPayloadTranscoder {
+    /// Converts a payload into another format.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to convert.
+    /// * `descriptor` - The descriptor of the message type contained in the payload.
+    /// * `format` - The format to convert the payload into, either `UpayloadFormatJson` or `UpayloadFormatProtobuf`.
+    ///
+    /// # Returns
+    ///
+    /// A payload containing the same message in the target format. If the payload already has the target format,
+    /// a copy of it is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if
+    /// - the payload or the target format is neither JSON nor protobuf,
+    /// - the payload does not contain a message of the described type,
+    /// - the payload refers to shared memory instead of carrying its data by value.
+    pub fn transcode(
+        payload: &UPayload,
+        descriptor: &MessageDescriptor,
+        format: UPayloadFormat,
+    ) -> Result<UPayload, SerializationError> {
+        let source_format = match payload.format() {
+            UPayloadFormat::UpayloadFormatUnspecified => UPayloadFormat::UpayloadFormatProtobuf,
+            source_format => source_format,
+        };
+        if source_format == format {
+            return Ok(payload.clone());
+        }
+
+        let message = match source_format {
+            UPayloadFormat::UpayloadFormatProtobuf => Self::decode_protobuf(payload, descriptor)?,
+            UPayloadFormat::UpayloadFormatJson => Self::decode_json(payload, descriptor)?,
+            _ => {
+                return Err(SerializationError::new(format!(
+                    "Cannot transcode from payload format {}",
+                    source_format.as_str_name()
+                )))
+            }
+        };
+        match format {
+            UPayloadFormat::UpayloadFormatProtobuf => Self::encode_protobuf(&message),
+            UPayloadFormat::UpayloadFormatJson => UPayloadBuilder::from_json(&message)?.build(),
+            _ => Err(SerializationError::new(format!(
+                "Cannot transcode to payload format {}",
+                format.as_str_name()
+            ))),
+        }
+    }
+
+    /// Converts a payload into another format, based on the descriptor of the contained message type that a
+    /// registry resolves.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to convert.
+    /// * `registry` - The registry to resolve the schema with.
+    /// * `schema` - The type URL or the full name of the message type contained in the payload.
+    /// * `format` - The format to convert the payload into, either `UpayloadFormatJson` or `UpayloadFormatProtobuf`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the registry does not know the schema, or for the reasons that
+    /// [`UPayloadTranscoder::transcode`] fails for.
+    pub fn transcode_with_registry(
+        payload: &UPayload,
+        registry: &dyn SchemaRegistry,
+        schema: &str,
+        format: UPayloadFormat,
+    ) -> Result<UPayload, SerializationError> {
+        let descriptor = registry
+            .resolve(schema)
+            .ok_or_else(|| SerializationError::new(format!("Unknown schema {schema}")))?;
+        Self::transcode(payload, &descriptor, format)
+    }
+
+    /// Decodes the message contained in a payload of format JSON or protobuf, e.g. for checking that the payload
+    /// matches the schema it is expected to have.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload.
+    /// * `descriptor` - The descriptor of the message type contained in the payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the payload is neither JSON nor protobuf, does not contain a message of the
+    /// described type, or refers to shared memory instead of carrying its data by value.
+    pub fn decode(
+        payload: &UPayload,
+        descriptor: &MessageDescriptor,
+    ) -> Result<DynamicMessage, SerializationError> {
+        match payload.format() {
+            UPayloadFormat::UpayloadFormatUnspecified | UPayloadFormat::UpayloadFormatProtobuf => {
+                Self::decode_protobuf(payload, descriptor)
+            }
+            UPayloadFormat::UpayloadFormatJson => Self::decode_json(payload, descriptor),
+            format => Err(SerializationError::new(format!(
+                "Cannot decode payload format {}",
+                format.as_str_name()
+            ))),
+        }
+    }
+
+    fn decode_protobuf(
+        payload: &UPayload,
+        descriptor: &MessageDescriptor,
+    ) -> Result<DynamicMessage, SerializationError> {
+        let (type_url, message) = payload.message_bytes()?;
+        if type_url.strip_prefix(TYPE_URL_PREFIX) != Some(descriptor.full_name()) {
+            return Err(SerializationError::new(format!(
+                "Payload contains {} instead of {}",
+                type_url,
+                descriptor.full_name()
+            )));
+        }
+        DynamicMessage::decode(descriptor.clone(), message).map_err(|e| {
+            SerializationError::decode(format!("Invalid message {}", descriptor.full_name()), e)
+        })
+    }
+
+    fn decode_json(
+        payload: &UPayload,
+        descriptor: &MessageDescriptor,
+    ) -> Result<DynamicMessage, SerializationError> {
+        let data = payload.resolve_data(None)?.unwrap_or_default();
+        let mut deserializer = serde_json::Deserializer::from_slice(data);
+        let message = DynamicMessage::deserialize(descriptor.clone(), &mut deserializer)
+            .and_then(|message| deserializer.end().map(|()| message))
+            .map_err(|e| {
+                SerializationError::json(
+                    format!("Invalid JSON for message {}", descriptor.full_name()),
+                    e,
+                )
+            })?;
+        Ok(message)
+    }
+
+    fn encode_protobuf(message: &DynamicMessage) -> Result<UPayload, SerializationError> {
+        UPayload::from_message_bytes(
+            &format!("{TYPE_URL_PREFIX}{}", message.descriptor().full_name()),
+            &message.encode_to_vec(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prost_reflect::DescriptorPool;
+    use prost_types::{Any, Duration, Timestamp};
+    use serde_json::json;
+
+    use crate::transport::transcoder::InMemorySchemaRegistry;
+
+    fn duration_descriptor() -> MessageDescriptor {
+        DescriptorPool::global()
+            .get_message_by_name("google.protobuf.Duration")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_transcode_protobuf_to_json() {
+        let message = Duration {
+            seconds: 1,
+            nanos: 500_000_000,
+        };
+        let payload = UPayloadBuilder::from_protobuf(&message)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let json = UPayloadTranscoder::transcode(
+            &payload,
+            &duration_descriptor(),
+            UPayloadFormat::UpayloadFormatJson,
+        )
+        .unwrap();
+        assert_eq!(json.format(), UPayloadFormat::UpayloadFormatJson);
+        let data = json.resolve_data(None).unwrap().unwrap();
+        assert_eq!(data, b"\"1.500s\"");
+    }
+
+    #[test]
+    fn test_transcode_json_to_protobuf() {
+        let payload = UPayloadBuilder::from_json(&json!("1.5s"))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let protobuf = UPayloadTranscoder::transcode(
+            &payload,
+            &duration_descriptor(),
+            UPayloadFormat::UpayloadFormatProtobuf,
+        )
+        .unwrap();
+        assert_eq!(protobuf.format(), UPayloadFormat::UpayloadFormatProtobuf);
+        let any = Any::try_from(protobuf).unwrap();
+        assert_eq!(
+            any.to_msg::<Duration>().unwrap(),
+            Duration {
+                seconds: 1,
+                nanos: 500_000_000,
+            }
+        );
+    }
+
+    #[test]
+    fn test_transcode_fails_for_mismatched_descriptor() {
+        let payload = UPayloadBuilder::from_protobuf(&Timestamp::default())
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let result = UPayloadTranscoder::transcode(
+            &payload,
+            &duration_descriptor(),
+            UPayloadFormat::UpayloadFormatJson,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_transcode_fails_for_invalid_json() {
+        let payload = UPayloadBuilder::from_json(&json!({"seconds": 1}))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let result = UPayloadTranscoder::transcode(
+            &payload,
+            &duration_descriptor(),
+            UPayloadFormat::UpayloadFormatProtobuf,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_transcode_with_registry() {
+        let registry = InMemorySchemaRegistry::new();
+        let payload = UPayloadBuilder::from_json(&json!("1.5s"))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let protobuf = UPayloadTranscoder::transcode_with_registry(
+            &payload,
+            &registry,
+            "type.googleapis.com/google.protobuf.Duration",
+            UPayloadFormat::UpayloadFormatProtobuf,
+        )
+        .unwrap();
+        assert!(UPayloadTranscoder::decode(&protobuf, &duration_descriptor()).is_ok());
+        assert!(UPayloadTranscoder::transcode_with_registry(
+            &payload,
+            &registry,
+            "example.Unknown",
+            UPayloadFormat::UpayloadFormatProtobuf,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_transcode_fails_for_unsupported_format() {
+        let payload = UPayloadBuilder::from_text("1.5s").build().unwrap();
+
+        let result = UPayloadTranscoder::transcode(
+            &payload,
+            &duration_descriptor(),
+            UPayloadFormat::UpayloadFormatJson,
+        );
+        assert!(result.is_err());
+    }
+}
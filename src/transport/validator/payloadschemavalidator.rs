@@ -0,0 +1,146 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::Arc;
+
+use crate::transport::transcoder::{SchemaRegistry, UPayloadTranscoder};
+use crate::transport::validator::ValidationError;
+use crate::uprotocol::{UCode, UPayload};
+
+/// Validates `UPayload`s against the schemas of the messages they are expected to contain, as resolved by a
+/// [`SchemaRegistry`], e.g. for rejecting malformed requests from untrusted uEntities before they are handled.
+///
+/// Payloads of format JSON or protobuf are validated, by decoding the contained message. Protobuf payloads must also
+/// carry the type URL of the expected message type.
+pub struct PayloadSchemaValidator {
+    registry: Arc<dyn SchemaRegistry>,
+}
+
+impl PayloadSchemaValidator {
+    /// Creates a validator.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - The registry to resolve schemas with.
+    pub fn new<R>(registry: R) -> Self
+    where
+        R: SchemaRegistry + 'static,
+    {
+        PayloadSchemaValidator {
+            registry: Arc::new(registry),
+        }
+    }
+
+    /// Validates a payload against an expected schema.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to validate.
+    /// * `schema` - The type URL or the full name of the message type that the payload is expected to contain.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` with code `UCode::NotFound` if the registry does not know the schema, or with code
+    /// `UCode::InvalidArgument` if the payload does not contain a message of the schema.
+    pub fn validate(&self, payload: &UPayload, schema: &str) -> Result<(), ValidationError> {
+        let descriptor = self.registry.resolve(schema).ok_or_else(|| {
+            ValidationError::new(format!("Unknown schema {schema}")).with_code(UCode::NotFound)
+        })?;
+        UPayloadTranscoder::decode(payload, &descriptor)
+            .map(|_message| ())
+            .map_err(|e| ValidationError::new(e.to_string()).with_field("payload"))
+    }
+
+    /// Validates a protobuf payload against the schema that its type URL refers to, for payloads whose message type
+    /// is not known in advance.
+    ///
+    /// # Arguments
+    ///
+    /// * `payload` - The payload to validate.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` with code `UCode::NotFound` if the registry does not know the schema, or with code
+    /// `UCode::InvalidArgument` if the payload does not contain a `google.protobuf.Any` or its message does not match
+    /// its schema.
+    pub fn validate_declared(&self, payload: &UPayload) -> Result<(), ValidationError> {
+        let (type_url, _message) = payload
+            .message_bytes()
+            .map_err(|e| ValidationError::new(e.to_string()).with_field("payload"))?;
+        self.validate(payload, type_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use prost_types::{Duration, Timestamp};
+    use serde_json::json;
+
+    use crate::transport::builder::UPayloadBuilder;
+    use crate::transport::transcoder::InMemorySchemaRegistry;
+    use crate::uprotocol::UStatus;
+
+    fn build_validator_for_test() -> PayloadSchemaValidator {
+        PayloadSchemaValidator::new(InMemorySchemaRegistry::with_uprotocol_types().unwrap())
+    }
+
+    #[test]
+    fn test_validate_payloads_of_expected_schema() {
+        let validator = build_validator_for_test();
+        let protobuf = UPayloadBuilder::from_protobuf(&UStatus::ok())
+            .unwrap()
+            .build()
+            .unwrap();
+        let json = UPayloadBuilder::from_json(&json!({"code": "NOT_FOUND"}))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert!(validator
+            .validate(&protobuf, "type.googleapis.com/uprotocol.v1.UStatus")
+            .is_ok());
+        assert!(validator.validate(&json, "uprotocol.v1.UStatus").is_ok());
+        assert!(validator.validate_declared(&protobuf).is_ok());
+    }
+
+    #[test]
+    fn test_validate_fails_for_other_schema() {
+        let validator = build_validator_for_test();
+        let payload = UPayloadBuilder::from_protobuf(&Timestamp::default())
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let error = validator
+            .validate(&payload, "google.protobuf.Duration")
+            .unwrap_err();
+        assert_eq!(error.code(), UCode::InvalidArgument);
+        assert_eq!(error.field(), Some("payload"));
+        let error = validator.validate(&payload, "example.Unknown").unwrap_err();
+        assert_eq!(error.code(), UCode::NotFound);
+        assert!(validator
+            .validate_declared(&UPayloadBuilder::from_text("1s").build().unwrap())
+            .is_err());
+        assert!(validator
+            .validate(
+                &UPayloadBuilder::from_protobuf(&Duration::default())
+                    .unwrap()
+                    .build()
+                    .unwrap(),
+                "google.protobuf.Duration"
+            )
+            .is_ok());
+    }
+}
@@ -11,11 +11,11 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use std::time::SystemTime;
-
-use crate::transport::validator::ValidationError;
-use crate::uprotocol::{UAttributes, UCode, UMessageType, Uuid};
+use crate::transport::datamodel::{emit, LogLevel};
+use crate::transport::validator::{ValidationError, ValidationReport};
+use crate::uprotocol::{UAttributes, UCode, UMessageType, UPriority, Uuid};
 use crate::uri::validator::UriValidator;
+use crate::uuid::builder::{SystemClock, UClock};
 
 /// `UAttributes` is the struct that defines the Payload. It serves as the configuration for various aspects
 /// like time to live, priority, security tokens, and more. Each variant of `UAttributes` defines a different
@@ -35,7 +35,7 @@ pub trait UAttributesValidator {
     /// all validation errors for invalid configurations.
     /// # Errors
     ///
-    /// Returns a `ValidationError` when one or more validations fail. The error will contain a concatenated message of all the validation errors separated by a semicolon (`;`). Each part of the message corresponds to a failure from one of the specific validation functions called within `validate`. These may include errors from:
+    /// Returns a `ValidationError` when one or more validations fail. The error combines the errors of all failed validations, so that its message is a concatenation of their messages separated by a semicolon (`;`), and its field paths name the invalid attributes. Each part of the message corresponds to a failure from one of the specific validation functions called within `validate`. These may include errors from:
     ///
     /// - `validate_type` if the message type in `UAttributes` fails validation.
     /// - `validate_ttl` if the time-to-live value is invalid.
@@ -46,7 +46,7 @@ pub trait UAttributesValidator {
     ///
     /// If all validations pass, the function returns `Ok(())`, indicating no errors were found.
     fn validate(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
-        let error_message = vec![
+        let errors = [
             self.validate_type(attributes),
             self.validate_ttl(attributes),
             self.validate_sink(attributes),
@@ -55,26 +55,57 @@ pub trait UAttributesValidator {
             self.validate_reqid(attributes),
         ]
         .into_iter()
-        .filter_map(Result::err)
-        .map(|e| e.to_string())
-        .collect::<Vec<_>>()
-        .join("; ");
+        .filter_map(Result::err);
+
+        ValidationError::combine(errors).map_or(Ok(()), |error| {
+            emit(
+                LogLevel::Debug,
+                module_path!(),
+                format_args!("Invalid attributes: {error}"),
+            );
+            Err(error)
+        })
+    }
 
-        if error_message.is_empty() {
-            Ok(())
-        } else {
-            Err(ValidationError::new(error_message))
+    /// Takes a `UAttributes` object and reports all violations, i.e. the errors that
+    /// [`UAttributesValidator::validate`] fails with, and the warnings of [`UAttributesValidator::validate_priority`].
+    ///
+    /// # Arguments
+    /// * `attributes` - The `UAttributes` to validate.
+    fn report(&self, attributes: &UAttributes) -> ValidationReport {
+        let mut report = ValidationReport::from(self.validate(attributes));
+        if let Err(warning) = self.validate_priority(attributes) {
+            report.push_warning(warning);
         }
+        report
     }
 
     /// Return the name of the specific Validator implementation.
     fn type_name(&self) -> &'static str;
 
-    /// Indicates whether the payload with these [`UAttributes`] has expired.
+    /// Indicates whether the payload with these [`UAttributes`] has expired, according to the system clock.
+    ///
+    /// # Parameters
+    ///
+    /// * `attributes`: Reference to a [`UAttributes`] struct containing the time-to-live value.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ValidationResult` that is success or failed with a failure message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` in the cases described for [`UAttributesValidator::is_expired_at`].
+    fn is_expired(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
+        self.is_expired_at(attributes, &SystemClock)
+    }
+
+    /// Indicates whether the payload with these [`UAttributes`] has expired, according to the given clock.
     ///
     /// # Parameters
     ///
     /// * `attributes`: Reference to a [`UAttributes`] struct containing the time-to-live value.
+    /// * `clock`: The clock that provides the current time.
     ///
     /// # Returns
     ///
@@ -84,16 +115,19 @@ pub trait UAttributesValidator {
     ///
     /// Returns a `ValidationError` in the following cases:
     ///
-    /// - "Payload is expired": If the `ttl` (time-to-live) is present, valid, and greater than 0, but the payload has expired. This is determined by comparing the current time duration since the UNIX epoch against the timestamp extracted from the UUID and the `ttl` value.
+    /// - "Payload is expired": If the `ttl` (time-to-live) is present, valid, and greater than 0, but the payload has expired. This is determined by comparing the clock's current time against the timestamp extracted from the UUID and the `ttl` value.
     ///
-    /// - "Invalid duration": If there is an error in converting the current time duration to a valid `u64` format, indicating an issue with the duration calculation.
+    /// - "Invalid system time": If the clock is set to a point in time before UNIX epoch.
     ///
     /// - "Invalid TTL": If the `ttl` value is present but cannot be converted to a valid `u64`, suggesting an invalid `ttl` value.
     ///
-    /// - System error message: If there is an error in calculating the current time duration since the UNIX epoch, possibly due to a system time error.
-    ///
     /// The function returns `Ok(())` (indicating no error) in cases where `ttl` is not present, is less than or equal to 0, or if no UUID is present, or if the UUID does not contain a valid time component.
-    fn is_expired(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
+    /// A UUID whose time is later than the clock's current time (e.g. due to clock skew between devices) is not considered expired.
+    fn is_expired_at(
+        &self,
+        attributes: &UAttributes,
+        clock: &dyn UClock,
+    ) -> Result<(), ValidationError> {
         let ttl = match attributes.ttl {
             Some(t) if t > 0 => t,
             Some(_) => return Ok(()),
@@ -101,15 +135,13 @@ pub trait UAttributesValidator {
         };
 
         if let Some(time) = attributes.id.as_ref().and_then(Uuid::get_time) {
-            let delta = match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
-                Ok(duration) => {
-                    if let Ok(duration) = u64::try_from(duration.as_millis()) {
-                        duration - time
-                    } else {
-                        return Err(ValidationError::new("Invalid duration"));
-                    }
+            let delta = match clock.now() {
+                Some(now) => now.saturating_sub(time),
+                None => {
+                    return Err(
+                        ValidationError::new("Invalid system time").with_code(UCode::Internal)
+                    )
                 }
-                Err(e) => return Err(ValidationError::new(e.to_string())),
             };
 
             if ttl <= 0 {
@@ -118,10 +150,12 @@ pub trait UAttributesValidator {
 
             if let Ok(ttl) = u64::try_from(ttl) {
                 if delta >= ttl {
-                    return Err(ValidationError::new("Payload is expired"));
+                    return Err(ValidationError::new("Payload is expired")
+                        .with_field("ttl")
+                        .with_code(UCode::DeadlineExceeded));
                 }
             } else {
-                return Err(ValidationError::new("Invalid TTL"));
+                return Err(ValidationError::new("Invalid TTL").with_field("ttl"));
             }
         }
         Ok(())
@@ -148,7 +182,7 @@ pub trait UAttributesValidator {
     fn validate_ttl(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
         if let Some(ttl) = attributes.ttl {
             if ttl < 1 {
-                return Err(ValidationError::new(format!("Invalid TTL [{ttl}]")));
+                return Err(ValidationError::new(format!("Invalid TTL [{ttl}]")).with_field("ttl"));
             }
         }
         Ok(())
@@ -174,7 +208,7 @@ pub trait UAttributesValidator {
     /// The function does not return an error if the `UAttributes` object does not contain a `sink`, considering it a valid case.
     fn validate_sink(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
         if let Some(sink) = &attributes.sink {
-            return UriValidator::validate(sink);
+            return UriValidator::validate(sink).map_err(|e| e.within("sink"));
         }
         Ok(())
     }
@@ -200,7 +234,9 @@ pub trait UAttributesValidator {
     fn validate_permission_level(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
         if let Some(plevel) = &attributes.permission_level {
             if *plevel < 1 {
-                return Err(ValidationError::new("Invalid Permission Level"));
+                return Err(
+                    ValidationError::new("Invalid Permission Level").with_field("permission_level")
+                );
             }
         }
         Ok(())
@@ -227,10 +263,11 @@ pub trait UAttributesValidator {
     /// The function does not return an error if the `UAttributes` object does not contain a `reqid`, considering it a valid case.
     fn validate_commstatus(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
         if let Some(cs) = attributes.commstatus {
-            if UCode::try_from(cs).is_err() {
+            if !UCode::is_valid_code(cs) {
                 return Err(ValidationError::new(format!(
                     "Invalid Communication Status Code [{cs}]"
-                )));
+                ))
+                .with_field("commstatus"));
             }
         }
         Ok(())
@@ -257,7 +294,7 @@ pub trait UAttributesValidator {
     fn validate_reqid(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
         if let Some(reqid) = &attributes.reqid {
             if !reqid.is_uprotocol_uuid() {
-                return Err(ValidationError::new("Invalid UUID"));
+                return Err(ValidationError::new("Invalid UUID").with_field("reqid"));
             }
         }
         Ok(())
@@ -280,6 +317,35 @@ pub trait UAttributesValidator {
     /// - The `MessageType` in `UAttributes` does not conform to the expected format or value.
     /// - The `UAttributes` object contains inconsistent or invalid data that fails the validation criteria.
     fn validate_type(&self, attributes: &UAttributes) -> Result<(), ValidationError>;
+
+    /// Validates the priority of `UAttributes`, which is only reported as a warning rather than failing
+    /// [`UAttributesValidator::validate`]. By default, any priority defined by the uProtocol specification is
+    /// accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `attributes` - `UAttributes` object containing the priority to validate.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` if the priority is not defined by the specification, or is lower than recommended
+    /// for the message type.
+    fn validate_priority(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
+        UPriority::try_from_value(attributes.priority).map(|_| ())
+    }
+}
+
+// RPC messages are supposed to be sent with priority UPRIORITY_CS4 or higher
+fn validate_rpc_priority(attributes: &UAttributes) -> Result<(), ValidationError> {
+    let priority = UPriority::try_from_value(attributes.priority)?;
+    if priority < UPriority::UpriorityCs4 {
+        return Err(ValidationError::new(format!(
+            "Priority [{}] is lower than UPRIORITY_CS4 for RPC messages",
+            priority.as_str_name()
+        ))
+        .with_field("priority"));
+    }
+    Ok(())
 }
 
 /// Enum that hold the implementations of uattributesValidator according to type.
@@ -336,14 +402,15 @@ impl UAttributesValidator for PublishValidator {
                     return Err(ValidationError::new(format!(
                         "Wrong Attribute Type [{}]",
                         mt.as_str_name()
-                    )));
+                    ))
+                    .with_field("type"));
                 }
             }
         }
-        Err(ValidationError::new(format!(
-            "Unknown Attribute Type [{}]",
-            attributes.r#type
-        )))
+        Err(
+            ValidationError::new(format!("Unknown Attribute Type [{}]", attributes.r#type))
+                .with_field("type"),
+        )
     }
 }
 
@@ -372,14 +439,15 @@ impl UAttributesValidator for RequestValidator {
                     return Err(ValidationError::new(format!(
                         "Wrong Attribute Type [{}]",
                         mt.as_str_name()
-                    )));
+                    ))
+                    .with_field("type"));
                 }
             }
         }
-        Err(ValidationError::new(format!(
-            "Unknown Attribute Type [{}]",
-            attributes.r#type
-        )))
+        Err(
+            ValidationError::new(format!("Unknown Attribute Type [{}]", attributes.r#type))
+                .with_field("type"),
+        )
     }
 
     /// Validates that attributes for a message meant for an RPC request has a destination sink.
@@ -394,9 +462,9 @@ impl UAttributesValidator for RequestValidator {
     /// Returns a `ValidationResult` that is success or failed with a failure message.
     fn validate_sink(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
         if let Some(sink) = &attributes.sink {
-            UriValidator::validate_rpc_response(sink)
+            UriValidator::validate_rpc_response(sink).map_err(|e| e.within("sink"))
         } else {
-            Err(ValidationError::new("Missing Sink"))
+            Err(ValidationError::new("Missing Sink").with_field("sink"))
         }
     }
 
@@ -415,12 +483,16 @@ impl UAttributesValidator for RequestValidator {
             if ttl > 0 {
                 Ok(())
             } else {
-                Err(ValidationError::new(format!("Invalid TTL [{ttl}]")))
+                Err(ValidationError::new(format!("Invalid TTL [{ttl}]")).with_field("ttl"))
             }
         } else {
-            Err(ValidationError::new("Missing TTL"))
+            Err(ValidationError::new("Missing TTL").with_field("ttl"))
         }
     }
+
+    fn validate_priority(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
+        validate_rpc_priority(attributes)
+    }
 }
 
 /// Validate `UAttributes` with type `UMessageType::Response`
@@ -448,14 +520,15 @@ impl UAttributesValidator for ResponseValidator {
                     return Err(ValidationError::new(format!(
                         "Wrong Attribute Type [{}]",
                         mt.as_str_name()
-                    )));
+                    ))
+                    .with_field("type"));
                 }
             }
         }
-        Err(ValidationError::new(format!(
-            "Unknown Attribute Type [{}]",
-            attributes.r#type
-        )))
+        Err(
+            ValidationError::new(format!("Unknown Attribute Type [{}]", attributes.r#type))
+                .with_field("type"),
+        )
     }
 
     /// Validates that attributes for a message meant for an RPC response has a destination sink.
@@ -470,9 +543,9 @@ impl UAttributesValidator for ResponseValidator {
     /// Returns a `ValidationResult` that is success or failed with a failure message.
     fn validate_sink(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
         if let Some(sink) = &attributes.sink {
-            UriValidator::validate_rpc_method(sink)
+            UriValidator::validate_rpc_method(sink).map_err(|e| e.within("sink"))
         } else {
-            Err(ValidationError::new("Missing Sink"))
+            Err(ValidationError::new("Missing Sink").with_field("sink"))
         }
     }
 
@@ -488,13 +561,17 @@ impl UAttributesValidator for ResponseValidator {
     fn validate_reqid(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
         if let Some(reqid) = &attributes.reqid {
             if *reqid == Uuid::default() {
-                return Err(ValidationError::new("Missing correlation Id"));
+                return Err(ValidationError::new("Missing correlation Id").with_field("reqid"));
             }
             if reqid.is_uprotocol_uuid() {
                 return Ok(());
             }
         }
-        Err(ValidationError::new("Missing correlation Id"))
+        Err(ValidationError::new("Missing correlation Id").with_field("reqid"))
+    }
+
+    fn validate_priority(&self, attributes: &UAttributes) -> Result<(), ValidationError> {
+        validate_rpc_priority(attributes)
     }
 }
 
@@ -504,7 +581,7 @@ mod tests {
     use crate::transport::builder::UAttributesBuilder;
     use crate::uprotocol::{Remote, UAuthority, UEntity, UPriority, UUri, Uuid};
     use crate::uri::builder::resourcebuilder::UResourceBuilder;
-    use crate::uuid::builder::UUIDv8Builder;
+    use crate::uuid::builder::{SimulatedClock, SystemRandomSource, UUIDv8Builder};
 
     #[test]
     fn test_fetching_validator_for_valid_types() {
@@ -907,18 +984,84 @@ mod tests {
 
     #[test]
     fn test_validate_attributes_for_publish_message_payload_expired() {
+        let clock = SimulatedClock::starting_now();
+        let id = UUIDv8Builder::with_sources(clock.clone(), &mut SystemRandomSource).build();
         let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs0)
+            .with_id(id)
             .with_ttl(1)
             .build();
 
-        std::thread::sleep(std::time::Duration::from_millis(800));
+        clock.advance(std::time::Duration::from_millis(800));
 
         let validator = Validators::Publish.validator();
-        let status = validator.is_expired(&attributes);
+        let status = validator.is_expired_at(&attributes, &clock);
         assert!(status.is_err());
         assert_eq!(status.unwrap_err().to_string(), "Payload is expired");
     }
 
+    #[test]
+    fn test_validate_attributes_for_publish_message_payload_expired_at() {
+        struct FixedClock(u64);
+        impl UClock for FixedClock {
+            fn now(&self) -> Option<u64> {
+                Some(self.0)
+            }
+        }
+
+        let created = 0x18C684468F8; // Thu, 14 Dec 2023 12:19:23 GMT
+        let id = UUIDv8Builder::with_sources(FixedClock(created), &mut SystemRandomSource).build();
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs0)
+            .with_id(id)
+            .with_ttl(100)
+            .build();
+
+        let validator = Validators::Publish.validator();
+        assert!(validator
+            .is_expired_at(&attributes, &FixedClock(created - 1))
+            .is_ok());
+        assert!(validator
+            .is_expired_at(&attributes, &FixedClock(created + 99))
+            .is_ok());
+        let status = validator.is_expired_at(&attributes, &FixedClock(created + 100));
+        let error = status.unwrap_err();
+        assert_eq!(error.to_string(), "Payload is expired");
+        assert_eq!(error.field(), Some("ttl"));
+        assert_eq!(error.code(), UCode::DeadlineExceeded);
+    }
+
+    #[test]
+    fn test_validate_reports_fields_of_all_errors() {
+        let mut sink = build_sink();
+        if let Some(entity) = sink.entity.as_mut() {
+            entity.name = String::new();
+        }
+        let attributes = UAttributesBuilder::request(UPriority::UpriorityCs4, sink, 0)
+            .with_permission_level(0)
+            .build();
+
+        let error = Validators::Request
+            .validator()
+            .validate(&attributes)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid TTL [0]; Uri is missing uSoftware Entity name; Invalid Permission Level"
+        );
+        let fields = error
+            .errors()
+            .iter()
+            .map(ValidationError::field)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                Some("ttl"),
+                Some("sink.entity.name"),
+                Some("permission_level")
+            ]
+        );
+    }
+
     #[test]
     fn test_validating_request_containing_token() {
         let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs0)
@@ -931,6 +1074,53 @@ mod tests {
         assert!(status.is_ok());
     }
 
+    #[test]
+    fn test_report_warns_about_low_rpc_priority() {
+        let attributes =
+            UAttributesBuilder::request(UPriority::UpriorityCs2, build_sink(), 1000).build();
+
+        let report = Validators::Request.validator().report(&attributes);
+        assert!(report.is_valid());
+        let warnings = report.warnings().collect::<Vec<_>>();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field(), Some("priority"));
+        assert_eq!(
+            warnings[0].message(),
+            "Priority [UPRIORITY_CS2] is lower than UPRIORITY_CS4 for RPC messages"
+        );
+
+        let publish = UAttributesBuilder::publish(UPriority::UpriorityCs0).build();
+        assert!(Validators::Publish
+            .validator()
+            .report(&publish)
+            .violations()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_report_warns_about_unknown_priority() {
+        let mut attributes = UAttributesBuilder::publish(UPriority::UpriorityCs0).build();
+        attributes.priority = 42;
+
+        let report = Validators::Publish.validator().report(&attributes);
+        assert!(report.is_valid());
+        let warnings = report.warnings().collect::<Vec<_>>();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message(), "Unknown priority [42]");
+    }
+
+    #[test]
+    fn test_report_lists_all_errors() {
+        let attributes = UAttributesBuilder::request(UPriority::UpriorityCs4, UUri::default(), 0)
+            .with_permission_level(0)
+            .build();
+
+        let report = Validators::Request.validator().report(&attributes);
+        assert!(!report.is_valid());
+        assert_eq!(report.errors().count(), 3);
+        assert_eq!(report.into_ustatus().get_code(), UCode::InvalidArgument);
+    }
+
     fn build_sink() -> UUri {
         UUri {
             authority: Some(UAuthority {
@@ -0,0 +1,124 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::transport::validator::{ValidationError, ValidationReport, Validators};
+use crate::uprotocol::UMessage;
+
+/// Validates a `UMessage`, i.e. that it has a source and attributes, and that its attributes pass the
+/// [`UAttributesValidator`](crate::transport::validator::UAttributesValidator) for their message type.
+///
+/// The source URI is not validated any further, as the source of a received message may be in micro form only.
+pub struct UMessageValidator;
+
+impl UMessageValidator {
+    /// Validates a `UMessage`.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to validate.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` combining the errors of [`UMessageValidator::report`], with field paths like
+    /// `attributes.ttl`.
+    pub fn validate(message: &UMessage) -> Result<(), ValidationError> {
+        Self::report(message).into_result()
+    }
+
+    /// Validates a `UMessage`, reporting all violations including the warnings about its attributes.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to validate.
+    ///
+    /// # Returns
+    ///
+    /// The report, whose violations have field paths relative to the message, like `attributes.ttl`.
+    pub fn report(message: &UMessage) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        if message.source.is_none() {
+            report.push_error(ValidationError::new("Message has no source").with_field("source"));
+        }
+        match &message.attributes {
+            Some(attributes) => report.extend(
+                Validators::get_validator(attributes)
+                    .report(attributes)
+                    .within("attributes"),
+            ),
+            None => report.push_error(
+                ValidationError::new("Message has no attributes").with_field("attributes"),
+            ),
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::{UAttributes, UEntity, UPriority, UUri};
+
+    fn build_source_for_test() -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_message() {
+        let message = UMessage {
+            source: Some(build_source_for_test()),
+            attributes: Some(UAttributesBuilder::publish(UPriority::UpriorityCs1).build()),
+            payload: None,
+        };
+
+        assert!(UMessageValidator::validate(&message).is_ok());
+        assert!(UMessageValidator::report(&message).violations().is_empty());
+    }
+
+    #[test]
+    fn test_report_missing_source_and_attributes() {
+        let report = UMessageValidator::report(&UMessage::default());
+
+        let fields = report
+            .errors()
+            .map(|violation| violation.field())
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec![Some("source"), Some("attributes")]);
+        assert_eq!(
+            report.into_ustatus().message(),
+            "Message has no source; Message has no attributes"
+        );
+    }
+
+    #[test]
+    fn test_report_prefixes_attribute_fields() {
+        let message = UMessage {
+            source: Some(build_source_for_test()),
+            attributes: Some(UAttributes {
+                ttl: Some(0),
+                ..UAttributesBuilder::publish(UPriority::UpriorityCs1).build()
+            }),
+            payload: None,
+        };
+
+        let error = UMessageValidator::validate(&message).unwrap_err();
+        assert_eq!(error.field(), Some("attributes.ttl"));
+    }
+}
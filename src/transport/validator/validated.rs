@@ -0,0 +1,134 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::ops::Deref;
+
+use crate::transport::validator::UMessageValidator;
+use crate::uprotocol::{UMessage, UStatus};
+
+/// A value that has passed validation, which spares the components it is passed to validating it again.
+///
+/// `Validated<UMessage>` is produced by [`UMessageBuilder::build_validated`](crate::transport::builder::UMessageBuilder::build_validated),
+/// and accepted by [`UTransport::send_validated`](crate::transport::datamodel::UTransport::send_validated), which
+/// transports implement without validating the message's attributes again.
+///
+/// The value cannot be changed while it is wrapped, so that it stays valid. Messages from untrusted sources, e.g.
+/// ones that have been received or deserialized, are validated by [`Validated::new`], or passed to
+/// [`UTransport::send`](crate::transport::datamodel::UTransport::send), which always validates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Validated<T>(T);
+
+impl<T> Validated<T> {
+    /// Gets the value, e.g. for changing it, after which it needs to be validated again.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Validated<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> AsRef<T> for Validated<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl Validated<UMessage> {
+    /// Validates a message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    ///
+    /// # Returns
+    ///
+    /// The message marked as validated.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with the code of the failed validation, usually `UCode::InvalidArgument`, if the message
+    /// fails the [`UMessageValidator`], i.e. if it has no source or attributes, or if the attributes fail the
+    /// validator for their message type.
+    pub fn new(message: UMessage) -> Result<Self, UStatus> {
+        UMessageValidator::validate(&message).map_err(UStatus::from)?;
+        Ok(Validated(message))
+    }
+}
+
+impl TryFrom<UMessage> for Validated<UMessage> {
+    type Error = UStatus;
+
+    fn try_from(message: UMessage) -> Result<Self, Self::Error> {
+        Validated::new(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::{UAttributes, UCode, UEntity, UPriority, UUri};
+
+    fn build_source_for_test() -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_message() {
+        let message = UMessage {
+            source: Some(build_source_for_test()),
+            attributes: Some(UAttributesBuilder::publish(UPriority::UpriorityCs1).build()),
+            payload: None,
+        };
+
+        let validated = Validated::new(message.clone()).unwrap();
+        assert_eq!(*validated, message);
+        assert_eq!(validated.into_inner(), message);
+    }
+
+    #[test]
+    fn test_validate_message_with_invalid_attributes() {
+        let message = UMessage {
+            source: Some(build_source_for_test()),
+            attributes: Some(UAttributes::default()),
+            payload: None,
+        };
+
+        let result = Validated::try_from(message);
+        assert_eq!(result.unwrap_err().get_code(), UCode::InvalidArgument);
+    }
+
+    #[test]
+    fn test_validate_message_without_attributes() {
+        let message = UMessage {
+            source: Some(build_source_for_test()),
+            ..Default::default()
+        };
+
+        let result = Validated::new(message);
+        assert_eq!(result.unwrap_err().get_code(), UCode::InvalidArgument);
+    }
+}
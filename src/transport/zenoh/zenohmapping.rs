@@ -0,0 +1,322 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use prost::Message;
+use zenoh::prelude::Priority;
+use zenoh::sample::{Attachment, AttachmentBuilder};
+
+use crate::transport::zenoh::SerializationError;
+use crate::uprotocol::{
+    Remote, UAttributes, UAuthority, UEntity, UPayloadFormat, UPriority, UResource, UUri,
+};
+
+const PREFIX_LOCAL: &str = "upl";
+const PREFIX_REMOTE: &str = "upr";
+const ATTACHMENT_ATTRIBUTES: &str = "uattributes";
+const ATTACHMENT_PAYLOAD_FORMAT: &str = "upayloadformat";
+
+/// Maps uProtocol message attributes and addresses to Zenoh concepts.
+///
+/// `UAttributes` are carried in the attachment of Zenoh samples and queries in their protobuf encoding,
+/// along with the format of the payload. The priority is additionally reflected in the Zenoh priority,
+/// so that the Zenoh network can prioritize messages accordingly.
+///
+/// `UUri`s are mapped to key expressions consisting of the entity, entity version and resource segments,
+/// prefixed by `upl` for local and by `upr` and the authority name for remote `UUri`s, e.g.
+/// `upl/body.access/1/door.front_left` or `upr/vcu.vin/body.access/1/door.front_left`.
+pub struct ZenohMapping;
+
+impl ZenohMapping {
+    /// Maps a `UUri` to a Zenoh key expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The `UUri` to map.
+    ///
+    /// # Returns
+    ///
+    /// The key expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if
+    /// - the `UUri` does not contain an entity with name and major version, or a resource with name,
+    /// - the `UUri` refers to a remote authority by other means than its name,
+    /// - any of the segments contains a character that is not allowed in key expressions (`/`, `*`, `$`, `?`, `#`).
+    pub fn to_key_expr(uri: &UUri) -> Result<String, SerializationError> {
+        let mut segments = match &uri.authority {
+            Some(UAuthority {
+                remote: Some(Remote::Name(name)),
+            }) => vec![PREFIX_REMOTE.to_string(), name.clone()],
+            Some(UAuthority { remote: Some(_) }) => {
+                return Err(SerializationError::new(
+                    "Key expressions require the authority to have a name",
+                ))
+            }
+            _ => vec![PREFIX_LOCAL.to_string()],
+        };
+        let Some(UEntity {
+            name,
+            version_major: Some(version),
+            ..
+        }) = &uri.entity
+        else {
+            return Err(SerializationError::new(
+                "UUri has no entity with name and major version",
+            ));
+        };
+        let Some(resource) = uri
+            .resource
+            .as_ref()
+            .filter(|resource| !resource.name.is_empty())
+        else {
+            return Err(SerializationError::new("UUri has no resource"));
+        };
+        segments.push(name.clone());
+        segments.push(version.to_string());
+        segments.push(match &resource.instance {
+            Some(instance) => format!("{}.{}", resource.name, instance),
+            None => resource.name.clone(),
+        });
+
+        if segments.iter().skip(1).any(|segment| {
+            segment.is_empty() || segment.contains(|c| matches!(c, '/' | '*' | '$' | '?' | '#'))
+        }) {
+            return Err(SerializationError::new(
+                "UUri contains characters not allowed in key expressions",
+            ));
+        }
+        Ok(segments.join("/"))
+    }
+
+    /// Maps a Zenoh key expression back to a `UUri`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_expr` - The key expression, as created by [`ZenohMapping::to_key_expr`].
+    ///
+    /// # Returns
+    ///
+    /// The `UUri`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the key expression does not have the structure of a mapped `UUri`,
+    /// or contains an invalid entity version.
+    pub fn from_key_expr(key_expr: &str) -> Result<UUri, SerializationError> {
+        let segments = key_expr.split('/').collect::<Vec<_>>();
+        let (authority, entity, version, resource) = match segments.as_slice() {
+            [PREFIX_LOCAL, entity, version, resource] => (None, entity, version, resource),
+            [PREFIX_REMOTE, authority, entity, version, resource] => {
+                (Some(authority), entity, version, resource)
+            }
+            _ => {
+                return Err(SerializationError::new(format!(
+                    "Invalid key expression [{key_expr}]"
+                )))
+            }
+        };
+        let version_major = version
+            .parse::<u32>()
+            .map_err(|_e| SerializationError::new(format!("Invalid entity version [{version}]")))?;
+
+        Ok(UUri {
+            authority: authority.map(|authority| UAuthority {
+                remote: Some(Remote::Name(authority.to_string())),
+            }),
+            entity: Some(UEntity {
+                name: entity.to_string(),
+                version_major: Some(version_major),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from(*resource)),
+        })
+    }
+
+    /// Maps the attributes of a message and the format of its payload to a Zenoh attachment.
+    ///
+    /// # Arguments
+    ///
+    /// * `attributes` - The attributes to map.
+    /// * `format` - The format of the message's payload.
+    ///
+    /// # Returns
+    ///
+    /// The attachment to put or query the message with.
+    pub fn to_attachment(attributes: &UAttributes, format: UPayloadFormat) -> Attachment {
+        let mut attachment = AttachmentBuilder::new();
+        attachment.insert(ATTACHMENT_ATTRIBUTES, &attributes.encode_to_vec());
+        attachment.insert(ATTACHMENT_PAYLOAD_FORMAT, &(format as i32).to_le_bytes());
+        attachment.build()
+    }
+
+    /// Maps a Zenoh attachment back to the attributes of a message and the format of its payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `attachment` - The attachment of a received sample or query.
+    ///
+    /// # Returns
+    ///
+    /// The message's attributes and payload format.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if there is no attachment, or if it does not contain valid attributes, or
+    /// contains a payload format that the uProtocol specification does not define.
+    pub fn from_attachment(
+        attachment: Option<&Attachment>,
+    ) -> Result<(UAttributes, UPayloadFormat), SerializationError> {
+        let Some(attachment) = attachment else {
+            return Err(SerializationError::new("Message has no attachment"));
+        };
+        let attributes = attachment
+            .get(&ATTACHMENT_ATTRIBUTES)
+            .ok_or_else(|| SerializationError::new("Attachment contains no attributes"))
+            .and_then(|attributes| {
+                UAttributes::decode(attributes.as_slice())
+                    .map_err(|e| SerializationError::decode("Invalid attributes", e))
+            })?;
+        let format = match attachment.get(&ATTACHMENT_PAYLOAD_FORMAT) {
+            None => UPayloadFormat::default(),
+            Some(format) => <[u8; 4]>::try_from(format.as_slice())
+                .map_err(|_e| SerializationError::new("Invalid payload format"))
+                .and_then(|format| {
+                    UPayloadFormat::try_from_value(i32::from_le_bytes(format))
+                        .map_err(|e| SerializationError::new(e.message()))
+                })?,
+        };
+        Ok((attributes, format))
+    }
+
+    /// Maps the priority of a message to the Zenoh priority to send it with.
+    ///
+    /// # Arguments
+    ///
+    /// * `priority` - The priority of the message.
+    ///
+    /// # Returns
+    ///
+    /// The Zenoh priority, ranging from `Priority::Background` for `UPriority::UpriorityCs0` to
+    /// `Priority::RealTime` for `UPriority::UpriorityCs6`.
+    pub fn to_priority(priority: UPriority) -> Priority {
+        match priority {
+            UPriority::UpriorityUnspecified | UPriority::UpriorityCs0 => Priority::Background,
+            UPriority::UpriorityCs1 => Priority::DataLow,
+            UPriority::UpriorityCs2 => Priority::Data,
+            UPriority::UpriorityCs3 => Priority::DataHigh,
+            UPriority::UpriorityCs4 => Priority::InteractiveLow,
+            UPriority::UpriorityCs5 => Priority::InteractiveHigh,
+            UPriority::UpriorityCs6 => Priority::RealTime,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    use crate::uprotocol::{UMessageType, Uuid};
+    use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+
+    #[test_case("/body.access/1/door.front_left#Door", "upl/body.access/1/door.front_left"; "local uri")]
+    #[test_case("//vcu.vin/body.access/1/door.front_left#Door", "upr/vcu.vin/body.access/1/door.front_left"; "remote uri")]
+    #[test_case("/body.access/1/rpc.UpdateDoor", "upl/body.access/1/rpc.UpdateDoor"; "rpc method uri")]
+    fn test_to_key_expr(uri: &str, expected: &str) {
+        let uri = LongUriSerializer::deserialize(uri.to_string()).unwrap();
+
+        assert_eq!(ZenohMapping::to_key_expr(&uri).unwrap(), expected);
+    }
+
+    #[test_case("/body.access//door.front_left"; "no entity version")]
+    #[test_case("/body.access/1"; "no resource")]
+    #[test_case("/body.access/1/door.*"; "wildcard")]
+    fn test_to_key_expr_fails(uri: &str) {
+        let uri = LongUriSerializer::deserialize(uri.to_string()).unwrap();
+
+        assert!(ZenohMapping::to_key_expr(&uri).is_err());
+    }
+
+    #[test_case("upl/body.access/1/door.front_left", "/body.access/1/door.front_left"; "local key expression")]
+    #[test_case("upr/vcu.vin/body.access/1/door.front_left", "//vcu.vin/body.access/1/door.front_left"; "remote key expression")]
+    fn test_from_key_expr(key_expr: &str, expected: &str) {
+        let uri = ZenohMapping::from_key_expr(key_expr).unwrap();
+
+        assert_eq!(LongUriSerializer::serialize(&uri).unwrap(), expected);
+    }
+
+    #[test_case("upl/body.access/door.front_left"; "missing segment")]
+    #[test_case("upx/body.access/1/door.front_left"; "unknown prefix")]
+    #[test_case("upl/body.access/one/door.front_left"; "invalid version")]
+    fn test_from_key_expr_fails(key_expr: &str) {
+        assert!(ZenohMapping::from_key_expr(key_expr).is_err());
+    }
+
+    #[test]
+    fn test_attachment_round_trip() {
+        let attributes = UAttributes {
+            id: Some(Uuid { msb: 1, lsb: 2 }),
+            r#type: UMessageType::UmessageTypePublish.into(),
+            priority: UPriority::UpriorityCs2.into(),
+            ttl: Some(100),
+            ..Default::default()
+        };
+
+        let attachment =
+            ZenohMapping::to_attachment(&attributes, UPayloadFormat::UpayloadFormatJson);
+        let (mapped_attributes, format) = ZenohMapping::from_attachment(Some(&attachment)).unwrap();
+
+        assert_eq!(mapped_attributes, attributes);
+        assert_eq!(format, UPayloadFormat::UpayloadFormatJson);
+    }
+
+    #[test]
+    fn test_from_attachment_fails_without_attributes() {
+        assert!(ZenohMapping::from_attachment(None).is_err());
+        assert!(ZenohMapping::from_attachment(Some(&AttachmentBuilder::new().build())).is_err());
+    }
+
+    #[test]
+    fn test_from_attachment_fails_for_unknown_payload_format() {
+        let mut attachment = AttachmentBuilder::new();
+        attachment.insert(
+            ATTACHMENT_ATTRIBUTES,
+            &UAttributes::default().encode_to_vec(),
+        );
+        attachment.insert(ATTACHMENT_PAYLOAD_FORMAT, &42_i32.to_le_bytes());
+        assert_eq!(
+            ZenohMapping::from_attachment(Some(&attachment.build()))
+                .unwrap_err()
+                .to_string(),
+            "Unknown payload format [42]"
+        );
+    }
+
+    #[test]
+    fn test_to_priority() {
+        assert_eq!(
+            ZenohMapping::to_priority(UPriority::UpriorityCs0),
+            Priority::Background
+        );
+        assert_eq!(
+            ZenohMapping::to_priority(UPriority::UpriorityCs4),
+            Priority::InteractiveLow
+        );
+        assert_eq!(
+            ZenohMapping::to_priority(UPriority::UpriorityCs6),
+            Priority::RealTime
+        );
+    }
+}
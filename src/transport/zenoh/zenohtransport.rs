@@ -0,0 +1,477 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use zenoh::prelude::r#async::AsyncResolve;
+use zenoh::prelude::{Config, Sample, SessionDeclarations, SplitBuffer, Value};
+use zenoh::query::Reply;
+use zenoh::queryable::{Query, Queryable};
+use zenoh::subscriber::Subscriber;
+use zenoh::Session;
+
+use crate::transport::datamodel::UTransport;
+use crate::transport::validator::Validators;
+use crate::transport::zenoh::ZenohMapping;
+use crate::uprotocol::{
+    Data, UAttributes, UCode, UEntity, UMessage, UMessageType, UPayload, UStatus, UUri,
+};
+use crate::uri::validator::UriValidator;
+use crate::uuid::builder::UUIDv8Builder;
+
+type Listener = Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static>;
+
+enum Registration {
+    Subscriber(Subscriber<'static, ()>),
+    Queryable(Queryable<'static, ()>),
+}
+
+/// `UTransport` on top of [Zenoh](https://zenoh.io), also serving as reference for other transport implementations.
+///
+/// Messages are published as Zenoh samples, with their addresses and attributes mapped as described for
+/// [`ZenohMapping`]. Listeners registered for an RPC method are served by a Zenoh queryable: each request
+/// is passed to the listener, and the response is sent back to the invoker by means of [`UTransport::send`],
+/// with the request ID referring to the request. Methods are invoked by means of [`ZenohTransport::invoke`].
+pub struct ZenohTransport {
+    session: Arc<Session>,
+    registrations: Mutex<HashMap<String, Registration>>,
+    pending_requests: Arc<Mutex<HashMap<String, Query>>>,
+    uuid_builder: UUIDv8Builder,
+}
+
+impl ZenohTransport {
+    /// Creates a transport with a new Zenoh session.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration of the Zenoh session.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::Unavailable` if the session cannot be opened.
+    pub async fn new(config: Config) -> Result<ZenohTransport, UStatus> {
+        let session = zenoh::open(config).res().await.map_err(|e| {
+            UStatus::fail_with_code(
+                UCode::Unavailable,
+                &format!("Failed to open Zenoh session: {e}"),
+            )
+        })?;
+        Ok(Self::with_session(Arc::new(session)))
+    }
+
+    /// Creates a transport on top of an existing Zenoh session.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The Zenoh session to send and receive messages with.
+    pub fn with_session(session: Arc<Session>) -> ZenohTransport {
+        ZenohTransport {
+            session,
+            registrations: Mutex::new(HashMap::new()),
+            pending_requests: Arc::new(Mutex::new(HashMap::new())),
+            uuid_builder: UUIDv8Builder::new(),
+        }
+    }
+
+    /// Invokes an RPC method by means of a Zenoh query.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The URI of the method to invoke.
+    /// * `payload` - The request payload.
+    /// * `attributes` - The request attributes. The query times out after the attributes' time-to-live.
+    ///
+    /// # Returns
+    ///
+    /// The response message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code
+    /// - `UCode::InvalidArgument` if the attributes are no valid request attributes, or if the method cannot be mapped,
+    /// - `UCode::DeadlineExceeded` if no response has been received before the query timed out,
+    /// - `UCode::Unavailable` if the query cannot be sent, or if the method replied with an error.
+    pub async fn invoke(
+        &self,
+        method: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+    ) -> Result<UMessage, UStatus> {
+        if attributes.r#type() != UMessageType::UmessageTypeRequest {
+            return Err(UStatus::fail_with_code(
+                UCode::InvalidArgument,
+                "Methods can only be invoked with request attributes",
+            ));
+        }
+        Self::validate(&attributes)?;
+        let key_expr = ZenohMapping::to_key_expr(&method)?;
+
+        let mut query = self
+            .session
+            .get(&key_expr)
+            .with_attachment(ZenohMapping::to_attachment(&attributes, payload.format()))
+            .with_value(Self::to_value(&payload)?);
+        if let Some(ttl) = attributes.ttl.and_then(|ttl| u64::try_from(ttl).ok()) {
+            query = query.timeout(Duration::from_millis(ttl));
+        }
+        let replies = query.res().await.map_err(|e| {
+            UStatus::fail_with_code(UCode::Unavailable, &format!("Failed to send query: {e}"))
+        })?;
+
+        match replies.recv_async().await {
+            Ok(Reply {
+                sample: Ok(sample), ..
+            }) => Self::to_message(&sample.key_expr, &sample.value, sample.attachment()),
+            Ok(Reply {
+                sample: Err(value), ..
+            }) => Err(UStatus::fail_with_code(
+                UCode::Unavailable,
+                &format!("Method replied with error: {value}"),
+            )),
+            Err(_) => Err(UStatus::fail_with_code(
+                UCode::DeadlineExceeded,
+                "No response received for request",
+            )),
+        }
+    }
+
+    fn validate(attributes: &UAttributes) -> Result<(), UStatus> {
+        Validators::get_validator(attributes)
+            .validate(attributes)
+            .map_err(UStatus::from)
+    }
+
+    fn to_value(payload: &UPayload) -> Result<Value, UStatus> {
+        let data = payload.resolve_data(None)?.unwrap_or_default();
+        Ok(Value::from(data.to_vec()))
+    }
+
+    fn to_message(
+        key_expr: &str,
+        value: &Value,
+        attachment: Option<&zenoh::sample::Attachment>,
+    ) -> Result<UMessage, UStatus> {
+        let source = ZenohMapping::from_key_expr(key_expr)?;
+        let (attributes, format) = ZenohMapping::from_attachment(attachment)?;
+        let data = value.payload.contiguous().to_vec();
+        Ok(UMessage {
+            source: Some(source),
+            payload: Some(UPayload {
+                length: i32::try_from(data.len()).ok(),
+                data: Some(Data::Value(data)),
+                format: format.into(),
+            }),
+            attributes: Some(attributes),
+        })
+    }
+
+    async fn reply(&self, payload: UPayload, attributes: UAttributes) -> Result<(), UStatus> {
+        let request_id = attributes
+            .reqid
+            .as_ref()
+            .map(|reqid| reqid.to_hyphenated_string())
+            .unwrap_or_default();
+        let Some(query) = self.pending_requests.lock().unwrap().remove(&request_id) else {
+            return Err(UStatus::fail_with_code(
+                UCode::NotFound,
+                &format!("No pending request with ID {request_id}"),
+            ));
+        };
+
+        let sample = Sample::new(query.key_expr().clone(), Self::to_value(&payload)?);
+        let reply = match query
+            .reply(Ok(sample))
+            .with_attachment(ZenohMapping::to_attachment(&attributes, payload.format()))
+        {
+            Ok(reply) | Err((reply, _)) => reply,
+        };
+        reply.res().await.map_err(|e| {
+            UStatus::fail_with_code(UCode::Unavailable, &format!("Failed to send reply: {e}"))
+        })
+    }
+}
+
+#[async_trait]
+impl UTransport for ZenohTransport {
+    // Zenoh leaves authentication to the configuration of the session.
+    async fn authenticate(&self, _entity: UEntity) -> Result<(), UStatus> {
+        Err(UStatus::fail_with_code(
+            UCode::Unimplemented,
+            "Authentication is configured for the Zenoh session",
+        ))
+    }
+
+    async fn send(
+        &self,
+        topic: UUri,
+        payload: UPayload,
+        attributes: UAttributes,
+    ) -> Result<(), UStatus> {
+        Self::validate(&attributes)?;
+        match attributes.r#type() {
+            UMessageType::UmessageTypeResponse => self.reply(payload, attributes).await,
+            UMessageType::UmessageTypeRequest => Err(UStatus::fail_with_code(
+                UCode::Unimplemented,
+                "Requests are sent by means of ZenohTransport::invoke",
+            )),
+            _ => {
+                let key_expr = ZenohMapping::to_key_expr(&topic)?;
+                self.session
+                    .put(&key_expr, Self::to_value(&payload)?)
+                    .priority(ZenohMapping::to_priority(attributes.priority()))
+                    .with_attachment(ZenohMapping::to_attachment(&attributes, payload.format()))
+                    .res()
+                    .await
+                    .map_err(|e| {
+                        UStatus::fail_with_code(
+                            UCode::Unavailable,
+                            &format!("Failed to publish message: {e}"),
+                        )
+                    })
+            }
+        }
+    }
+
+    async fn register_listener(&self, topic: UUri, listener: Listener) -> Result<String, UStatus> {
+        let key_expr = ZenohMapping::to_key_expr(&topic)?;
+
+        let registration = if UriValidator::is_rpc_method(&topic) {
+            let pending_requests = self.pending_requests.clone();
+            let queryable = self
+                .session
+                .declare_queryable(&key_expr)
+                .callback(move |query: Query| {
+                    let empty = Value::empty();
+                    let value = query.value().unwrap_or(&empty);
+                    let message = Self::to_message(query.key_expr(), value, query.attachment());
+                    if let Ok(UMessage {
+                        attributes: Some(UAttributes { id: Some(id), .. }),
+                        ..
+                    }) = &message
+                    {
+                        pending_requests
+                            .lock()
+                            .unwrap()
+                            .insert(id.to_hyphenated_string(), query.clone());
+                    }
+                    listener(message);
+                })
+                .res()
+                .await
+                .map_err(|e| {
+                    UStatus::fail_with_code(
+                        UCode::Unavailable,
+                        &format!("Failed to declare queryable: {e}"),
+                    )
+                })?;
+            Registration::Queryable(queryable)
+        } else {
+            let subscriber = self
+                .session
+                .declare_subscriber(&key_expr)
+                .callback(move |sample: Sample| {
+                    listener(Self::to_message(
+                        &sample.key_expr,
+                        &sample.value,
+                        sample.attachment(),
+                    ));
+                })
+                .res()
+                .await
+                .map_err(|e| {
+                    UStatus::fail_with_code(
+                        UCode::Unavailable,
+                        &format!("Failed to declare subscriber: {e}"),
+                    )
+                })?;
+            Registration::Subscriber(subscriber)
+        };
+
+        let id = self.uuid_builder.build().to_hyphenated_string();
+        self.registrations
+            .lock()
+            .unwrap()
+            .insert(id.clone(), registration);
+        Ok(id)
+    }
+
+    async fn unregister_listener(&self, _topic: UUri, listener: &str) -> Result<(), UStatus> {
+        let Some(registration) = self.registrations.lock().unwrap().remove(listener) else {
+            return Err(UStatus::fail_with_code(
+                UCode::NotFound,
+                &format!("No listener with ID {listener}"),
+            ));
+        };
+        let result = match registration {
+            Registration::Subscriber(subscriber) => subscriber.undeclare().res().await,
+            Registration::Queryable(queryable) => queryable.undeclare().res().await,
+        };
+        result.map_err(|e| {
+            UStatus::fail_with_code(
+                UCode::Unavailable,
+                &format!("Failed to unregister listener: {e}"),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc;
+
+    use crate::transport::builder::{UAttributesBuilder, UPayloadBuilder};
+    use crate::uprotocol::UPriority;
+    use crate::uri::serializer::{LongUriSerializer, UriSerializer};
+
+    async fn transport() -> ZenohTransport {
+        let mut config = zenoh::config::peer();
+        config.scouting.multicast.set_enabled(Some(false)).unwrap();
+        config.listen.endpoints.clear();
+        ZenohTransport::new(config).await.unwrap()
+    }
+
+    fn uri(uri: &str) -> UUri {
+        LongUriSerializer::deserialize(uri.to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_publish_and_receive() {
+        let transport = transport().await;
+        let topic = uri("/body.access/1/door.front_left#Door");
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let listener = transport
+            .register_listener(
+                topic.clone(),
+                Box::new(move |message| sender.lock().unwrap().send(message).unwrap()),
+            )
+            .await
+            .unwrap();
+
+        let attributes = UAttributesBuilder::publish(UPriority::UpriorityCs1).build();
+        let payload = UPayloadBuilder::from_text("open").build().unwrap();
+        transport
+            .send(topic.clone(), payload.clone(), attributes.clone())
+            .await
+            .unwrap();
+
+        let message = receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.attributes, Some(attributes));
+        assert_eq!(message.payload.unwrap().data, payload.data);
+        assert_eq!(
+            LongUriSerializer::serialize(&message.source.unwrap()).unwrap(),
+            "/body.access/1/door.front_left"
+        );
+
+        transport
+            .unregister_listener(topic.clone(), &listener)
+            .await
+            .unwrap();
+        assert_eq!(
+            transport
+                .unregister_listener(topic, &listener)
+                .await
+                .unwrap_err()
+                .get_code(),
+            UCode::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method() {
+        let transport = Arc::new(transport().await);
+        let method = uri("/body.access/1/rpc.UpdateDoor");
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        transport
+            .register_listener(
+                method.clone(),
+                Box::new(move |request| sender.lock().unwrap().send(request).unwrap()),
+            )
+            .await
+            .unwrap();
+
+        // the service answers requests as they are passed to its listener
+        let service = transport.clone();
+        let responder = tokio::task::spawn_blocking(move || {
+            let request = receiver
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+                .unwrap();
+            let attributes = request.attributes.unwrap();
+            let response = UAttributesBuilder::response(
+                UPriority::UpriorityCs4,
+                request.source.unwrap(),
+                attributes.id.unwrap(),
+            )
+            .build();
+            futures::executor::block_on(service.send(
+                UUri::default(),
+                UPayloadBuilder::from_text("done").build().unwrap(),
+                response,
+            ))
+        });
+
+        let attributes = UAttributesBuilder::request(
+            UPriority::UpriorityCs4,
+            uri("/door.client/1/rpc.response"),
+            5000,
+        )
+        .build();
+        let response = transport
+            .invoke(
+                method,
+                UPayloadBuilder::from_text("open").build().unwrap(),
+                attributes.clone(),
+            )
+            .await
+            .unwrap();
+
+        responder.await.unwrap().unwrap();
+        let response_attributes = response.attributes.unwrap();
+        assert_eq!(
+            response_attributes.r#type(),
+            UMessageType::UmessageTypeResponse
+        );
+        assert_eq!(response_attributes.reqid, attributes.id);
+        assert_eq!(
+            response.payload.unwrap().data,
+            Some(Data::Value(b"done".to_vec()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_response_fails_without_request() {
+        let transport = transport().await;
+        let attributes = UAttributesBuilder::response(
+            UPriority::UpriorityCs4,
+            uri("/body.access/1/rpc.UpdateDoor"),
+            UUIDv8Builder::new().build(),
+        )
+        .build();
+
+        let result = transport
+            .send(UUri::default(), UPayload::default(), attributes)
+            .await;
+
+        assert_eq!(result.unwrap_err().get_code(), UCode::NotFound);
+    }
+}
@@ -11,9 +11,51 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+use alloc::string::{FromUtf8Error, String};
+use core::str::Utf8Error;
+
+use crate::uprotocol::uauthority::AuthorityIdError;
+
+/// The failure to serialize or deserialize data, like a `UUri`, a `UPayload` or a `CloudEvent`.
+///
+/// Failures that are caused by an error of an underlying library, like a protobuf decoding error, keep that error
+/// as their [source](std::error::Error::source), so that the whole chain of causes can be reported. The
+/// `Display` output is the message of the failure only, without the message of its source.
 #[derive(Debug)]
-pub struct SerializationError {
-    message: String,
+#[non_exhaustive]
+pub enum SerializationError {
+    /// The data is invalid, without an underlying error.
+    Invalid(String),
+    /// Protobuf data could not be decoded.
+    Decode {
+        message: String,
+        source: prost::DecodeError,
+    },
+    /// A protobuf message could not be encoded.
+    Encode {
+        message: String,
+        source: prost::EncodeError,
+    },
+    /// Text is not valid UTF-8.
+    Utf8 { message: String, source: Utf8Error },
+    /// The ID of a uAuthority does not fit into a URI in micro form.
+    AuthorityId {
+        message: String,
+        source: AuthorityIdError,
+    },
+    /// JSON could not be serialized or deserialized.
+    Json {
+        message: String,
+        source: serde_json::Error,
+    },
+    /// Any other underlying error, e.g. of a transport's or a binding's library.
+    #[cfg(feature = "std")]
+    Other {
+        message: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
 }
 
 impl SerializationError {
@@ -21,16 +63,181 @@ impl SerializationError {
     where
         T: Into<String>,
     {
-        SerializationError {
+        SerializationError::Invalid(message.into())
+    }
+
+    /// Creates an error caused by protobuf data that could not be decoded.
+    pub fn decode<T>(message: T, source: prost::DecodeError) -> SerializationError
+    where
+        T: Into<String>,
+    {
+        SerializationError::Decode {
+            message: message.into(),
+            source,
+        }
+    }
+
+    /// Creates an error caused by a protobuf message that could not be encoded.
+    pub fn encode<T>(message: T, source: prost::EncodeError) -> SerializationError
+    where
+        T: Into<String>,
+    {
+        SerializationError::Encode {
+            message: message.into(),
+            source,
+        }
+    }
+
+    /// Creates an error caused by text that is not valid UTF-8.
+    pub fn utf8<T>(message: T, source: Utf8Error) -> SerializationError
+    where
+        T: Into<String>,
+    {
+        SerializationError::Utf8 {
+            message: message.into(),
+            source,
+        }
+    }
+
+    /// Creates an error caused by JSON that could not be serialized or deserialized.
+    pub fn json<T>(message: T, source: serde_json::Error) -> SerializationError
+    where
+        T: Into<String>,
+    {
+        SerializationError::Json {
             message: message.into(),
+            source,
+        }
+    }
+
+    /// Creates an error caused by any other error.
+    #[cfg(feature = "std")]
+    pub fn other<T, E>(message: T, source: E) -> SerializationError
+    where
+        T: Into<String>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        SerializationError::Other {
+            message: message.into(),
+            source: source.into(),
+        }
+    }
+
+    /// Gets the message describing the failure, without the message of its source.
+    pub fn message(&self) -> &str {
+        match self {
+            SerializationError::Invalid(message)
+            | SerializationError::Decode { message, .. }
+            | SerializationError::Encode { message, .. }
+            | SerializationError::Utf8 { message, .. }
+            | SerializationError::AuthorityId { message, .. }
+            | SerializationError::Json { message, .. } => message,
+            #[cfg(feature = "std")]
+            SerializationError::Other { message, .. } => message,
         }
     }
 }
 
-impl std::fmt::Display for SerializationError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.message)
+impl core::fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.message())
     }
 }
 
-impl std::error::Error for SerializationError {}
+#[cfg(feature = "std")]
+impl std::error::Error for SerializationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SerializationError::Invalid(_) => None,
+            SerializationError::Decode { source, .. } => Some(source),
+            SerializationError::Encode { source, .. } => Some(source),
+            SerializationError::Utf8 { source, .. } => Some(source),
+            SerializationError::AuthorityId { source, .. } => Some(source),
+            SerializationError::Json { source, .. } => Some(source),
+            SerializationError::Other { source, .. } => Some(source.as_ref()),
+        }
+    }
+}
+
+impl From<prost::DecodeError> for SerializationError {
+    fn from(error: prost::DecodeError) -> Self {
+        SerializationError::decode("Failed to decode protobuf data", error)
+    }
+}
+
+impl From<prost::EncodeError> for SerializationError {
+    fn from(error: prost::EncodeError) -> Self {
+        SerializationError::encode("Failed to encode protobuf message", error)
+    }
+}
+
+impl From<Utf8Error> for SerializationError {
+    fn from(error: Utf8Error) -> Self {
+        SerializationError::utf8("Text is not valid UTF-8", error)
+    }
+}
+
+impl From<FromUtf8Error> for SerializationError {
+    fn from(error: FromUtf8Error) -> Self {
+        SerializationError::from(error.utf8_error())
+    }
+}
+
+impl From<AuthorityIdError> for SerializationError {
+    fn from(error: AuthorityIdError) -> Self {
+        SerializationError::AuthorityId {
+            message: "Invalid authority ID".into(),
+            source: error,
+        }
+    }
+}
+
+impl From<serde_json::Error> for SerializationError {
+    fn from(error: serde_json::Error) -> Self {
+        SerializationError::json("Failed to process JSON", error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error::Error;
+
+    use prost::Message;
+
+    #[test]
+    fn test_display_omits_source() {
+        let source = prost_types::Any::decode(&[0xff_u8][..]).unwrap_err();
+        let error = SerializationError::decode("UPayload does not contain Any", source);
+
+        assert_eq!(error.to_string(), "UPayload does not contain Any");
+        assert_eq!(error.message(), "UPayload does not contain Any");
+        assert!(error.source().unwrap().is::<prost::DecodeError>());
+    }
+
+    #[test]
+    fn test_invalid_has_no_source() {
+        let error = SerializationError::new("URI is empty");
+
+        assert_eq!(error.to_string(), "URI is empty");
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn test_from_utf8_error() {
+        let error = SerializationError::from(String::from_utf8(vec![0xc3, 0x28]).unwrap_err());
+
+        assert!(matches!(error, SerializationError::Utf8 { .. }));
+        assert!(error.source().unwrap().is::<Utf8Error>());
+    }
+
+    #[test]
+    fn test_other_keeps_source_chain() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::InvalidData, "bad header");
+        let error = SerializationError::other("Invalid header", io_error);
+
+        assert_eq!(error.to_string(), "Invalid header");
+        assert_eq!(error.source().unwrap().to_string(), "bad header");
+    }
+}
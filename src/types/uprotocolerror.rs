@@ -0,0 +1,195 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+#[cfg(feature = "cloudevents")]
+use crate::cloudevent::builder::ConversionError;
+use crate::rpc::RpcMapperError;
+use crate::types::serializationerror::SerializationError;
+use crate::types::validationerror::ValidationError;
+use crate::uprotocol::uauthority::AuthorityIdError;
+use crate::uprotocol::uuid::{UuidConversionError, UuidParseError};
+use crate::uprotocol::{UCode, UStatus};
+
+/// The error type of the crate's higher-level APIs, which covers the failures of all layers.
+///
+/// Each of the crate's error types converts into a `UProtocolError`, so that the `?` operator can be used
+/// across layers, and a `UProtocolError` converts into a [`UStatus`] for reporting it to peers.
+#[derive(Debug)]
+pub enum UProtocolError {
+    /// A failure to serialize or deserialize data.
+    Serialization(SerializationError),
+    /// A failure to validate data.
+    Validation(ValidationError),
+    /// A failure to invoke a method, or to map its response.
+    Rpc(RpcMapperError),
+    /// A failure to convert a UUID.
+    UuidConversion(UuidConversionError),
+    /// A failure reported as status, e.g. by a transport or a service.
+    Status(UStatus),
+}
+
+impl UProtocolError {
+    /// Gets the `UCode` that best describes the error.
+    ///
+    /// Malformed data maps to `UCode::InvalidArgument`, while validation failures and failures reported as status
+    /// keep their code.
+    /// RPC failures map to the same codes as when converting a [`RpcMapperError`] into a [`UStatus`].
+    pub fn code(&self) -> UCode {
+        match self {
+            UProtocolError::Serialization(_) | UProtocolError::UuidConversion(_) => {
+                UCode::InvalidArgument
+            }
+            UProtocolError::Validation(e) => e.code(),
+            UProtocolError::Rpc(RpcMapperError::ResourceExhausted(_)) => UCode::ResourceExhausted,
+            UProtocolError::Rpc(RpcMapperError::Cancelled(_)) => UCode::Cancelled,
+            UProtocolError::Rpc(RpcMapperError::UnexpectedError(_)) => UCode::Unavailable,
+            UProtocolError::Rpc(_) => UCode::DataLoss,
+            UProtocolError::Status(status) => status.get_code(),
+        }
+    }
+}
+
+impl std::fmt::Display for UProtocolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UProtocolError::Serialization(e) => write!(f, "Serialization error: {e}"),
+            UProtocolError::Validation(e) => write!(f, "Validation error: {e}"),
+            UProtocolError::Rpc(e) => write!(f, "RPC error: {e}"),
+            UProtocolError::UuidConversion(e) => write!(f, "{e}"),
+            UProtocolError::Status(status) => write!(f, "{status}"),
+        }
+    }
+}
+
+impl std::error::Error for UProtocolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            UProtocolError::Serialization(e) => Some(e),
+            UProtocolError::Validation(e) => Some(e),
+            UProtocolError::Rpc(e) => Some(e),
+            UProtocolError::UuidConversion(e) => Some(e),
+            UProtocolError::Status(status) => Some(status),
+        }
+    }
+}
+
+impl From<SerializationError> for UProtocolError {
+    fn from(error: SerializationError) -> Self {
+        UProtocolError::Serialization(error)
+    }
+}
+
+impl From<ValidationError> for UProtocolError {
+    fn from(error: ValidationError) -> Self {
+        UProtocolError::Validation(error)
+    }
+}
+
+impl From<RpcMapperError> for UProtocolError {
+    fn from(error: RpcMapperError) -> Self {
+        UProtocolError::Rpc(error)
+    }
+}
+
+impl From<UuidConversionError> for UProtocolError {
+    fn from(error: UuidConversionError) -> Self {
+        UProtocolError::UuidConversion(error)
+    }
+}
+
+impl From<AuthorityIdError> for UProtocolError {
+    fn from(error: AuthorityIdError) -> Self {
+        UProtocolError::Serialization(error.into())
+    }
+}
+
+impl From<UuidParseError> for UProtocolError {
+    fn from(error: UuidParseError) -> Self {
+        UProtocolError::UuidConversion(error.into())
+    }
+}
+
+#[cfg(feature = "cloudevents")]
+impl From<ConversionError> for UProtocolError {
+    fn from(error: ConversionError) -> Self {
+        UProtocolError::Serialization(SerializationError::other("Invalid CloudEvent", error))
+    }
+}
+
+impl From<UStatus> for UProtocolError {
+    fn from(status: UStatus) -> Self {
+        UProtocolError::Status(status)
+    }
+}
+
+impl From<UProtocolError> for UStatus {
+    fn from(error: UProtocolError) -> Self {
+        match error {
+            UProtocolError::Status(status) => status,
+            _ => UStatus::fail_with_code(error.code(), &error.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::error::Error;
+
+    use test_case::test_case;
+
+    #[test_case(SerializationError::new("bad").into(), UCode::InvalidArgument; "serialization error")]
+    #[test_case(ValidationError::new("bad").into(), UCode::InvalidArgument; "validation error")]
+    #[test_case(ValidationError::new("expired").with_code(UCode::DeadlineExceeded).into(), UCode::DeadlineExceeded; "validation error with code")]
+    #[test_case(UuidConversionError::new("bad").into(), UCode::InvalidArgument; "uuid conversion error")]
+    #[test_case(UuidParseError::NotHyphenated.into(), UCode::InvalidArgument; "uuid parse error")]
+    #[test_case(AuthorityIdError::TooLong(300).into(), UCode::InvalidArgument; "authority id error")]
+    #[test_case(RpcMapperError::ResourceExhausted("full".to_string()).into(), UCode::ResourceExhausted; "resource exhausted")]
+    #[test_case(RpcMapperError::Cancelled("aborted".to_string()).into(), UCode::Cancelled; "cancelled")]
+    #[test_case(RpcMapperError::InvalidPayload("bad".to_string()).into(), UCode::DataLoss; "invalid payload")]
+    #[test_case(RpcMapperError::UnexpectedError("gone".to_string()).into(), UCode::Unavailable; "unexpected error")]
+    #[test_case(UStatus::fail_with_code(UCode::PermissionDenied, "denied").into(), UCode::PermissionDenied; "status")]
+    fn test_code(error: UProtocolError, expected: UCode) {
+        assert_eq!(error.code(), expected);
+        assert_eq!(UStatus::from(error).get_code(), expected);
+    }
+
+    #[test]
+    fn test_into_status_keeps_message() {
+        let status = UStatus::fail_with_code(UCode::NotFound, "no such topic");
+        assert_eq!(UStatus::from(UProtocolError::from(status.clone())), status);
+
+        let status = UStatus::from(UProtocolError::from(ValidationError::new("Uri is empty.")));
+        assert_eq!(status.message(), "Validation error: Uri is empty.");
+    }
+
+    #[test]
+    fn test_question_mark_across_layers() {
+        fn validate() -> Result<(), ValidationError> {
+            Err(ValidationError::new("Uri is empty."))
+        }
+        fn send() -> Result<(), UStatus> {
+            Ok(())
+        }
+        fn publish() -> Result<(), UProtocolError> {
+            send()?;
+            validate()?;
+            Ok(())
+        }
+
+        let error = publish().unwrap_err();
+        assert!(matches!(error, UProtocolError::Validation(_)));
+        assert_eq!(error.source().unwrap().to_string(), "Uri is empty.");
+    }
+}
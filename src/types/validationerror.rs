@@ -11,9 +11,24 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-#[derive(Debug)]
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::uprotocol::UCode;
+
+/// The failure to validate data, like a `UUri`, `UAttributes` or a `CloudEvent`.
+///
+/// Besides its message, the error tells about the field that failed validation, as a path like `sink.entity.name`,
+/// and the `UCode` that a service responds with when a request fails validation, which is
+/// `UCode::InvalidArgument` unless given otherwise. Validations that check several fields combine their
+/// failures into a single error by means of [`ValidationError::combine`].
+#[derive(Debug, Clone, PartialEq)]
 pub struct ValidationError {
     message: String,
+    field: Option<String>,
+    code: UCode,
+    errors: Vec<ValidationError>,
 }
 
 impl ValidationError {
@@ -23,14 +38,183 @@ impl ValidationError {
     {
         ValidationError {
             message: message.into(),
+            field: None,
+            code: UCode::InvalidArgument,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Sets the path of the field that failed validation, like `entity.name`.
+    #[must_use]
+    pub fn with_field<T>(mut self, field: T) -> ValidationError
+    where
+        T: Into<String>,
+    {
+        self.field = Some(field.into());
+        self
+    }
+
+    /// Sets the `UCode` that the failure maps to.
+    #[must_use]
+    pub fn with_code(mut self, code: UCode) -> ValidationError {
+        self.code = code;
+        self
+    }
+
+    /// Prepends the path of the field that contains the validated data to the field paths of this error, e.g. for
+    /// turning the path `entity.name` of an invalid URI into `sink.entity.name` of the attributes containing it.
+    #[must_use]
+    pub fn within(mut self, parent: &str) -> ValidationError {
+        self.field = Some(match self.field.take() {
+            Some(field) => format!("{parent}.{field}"),
+            None => String::from(parent),
+        });
+        self.errors = self
+            .errors
+            .into_iter()
+            .map(|error| error.within(parent))
+            .collect();
+        self
+    }
+
+    /// Combines a number of errors into one, e.g. the failures of validating different fields.
+    ///
+    /// The message of the combined error joins the messages of the errors, separated by a semicolon (`;`). Its
+    /// code is the code of the errors if all of them have the same, `UCode::InvalidArgument` otherwise.
+    ///
+    /// # Returns
+    ///
+    /// The combined error, the error itself if there is a single one, or `None` if there are no errors.
+    pub fn combine<I>(errors: I) -> Option<ValidationError>
+    where
+        I: IntoIterator<Item = ValidationError>,
+    {
+        let mut errors = errors
+            .into_iter()
+            .flat_map(ValidationError::into_errors)
+            .collect::<Vec<_>>();
+        match errors.len() {
+            0 => None,
+            1 => errors.pop(),
+            _ => {
+                let code = errors[0].code;
+                Some(ValidationError {
+                    message: errors
+                        .iter()
+                        .map(|error| error.message.as_str())
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                    field: None,
+                    code: if errors.iter().all(|error| error.code == code) {
+                        code
+                    } else {
+                        UCode::InvalidArgument
+                    },
+                    errors,
+                })
+            }
+        }
+    }
+
+    /// Gets the message describing the failure.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Gets the path of the field that failed validation, if known.
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    /// Gets the `UCode` that the failure maps to.
+    pub fn code(&self) -> UCode {
+        self.code
+    }
+
+    /// Gets the errors that this error combines, or an empty slice if it is a single error.
+    pub fn errors(&self) -> &[ValidationError] {
+        &self.errors
+    }
+
+    pub(crate) fn into_errors(self) -> Vec<ValidationError> {
+        if self.errors.is_empty() {
+            alloc::vec![self]
+        } else {
+            self.errors
         }
     }
 }
 
-impl std::fmt::Display for ValidationError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.message)
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for ValidationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let error = ValidationError::new("Uri is empty");
+        assert_eq!(error.message(), "Uri is empty");
+        assert_eq!(error.field(), None);
+        assert_eq!(error.code(), UCode::InvalidArgument);
+        assert!(error.errors().is_empty());
+    }
+
+    #[test]
+    fn test_within() {
+        let error = ValidationError::new("Uri is missing uSoftware Entity name")
+            .with_field("entity.name")
+            .within("sink");
+        assert_eq!(error.field(), Some("sink.entity.name"));
+        assert_eq!(
+            ValidationError::new("Uri is empty").within("sink").field(),
+            Some("sink")
+        );
+    }
+
+    #[test]
+    fn test_combine() {
+        assert!(ValidationError::combine([]).is_none());
+
+        let single = ValidationError::new("Missing TTL").with_field("ttl");
+        assert_eq!(
+            ValidationError::combine([single.clone()]),
+            Some(single.clone())
+        );
+
+        let combined = ValidationError::combine([
+            single.clone(),
+            ValidationError::new("Payload is expired").with_code(UCode::DeadlineExceeded),
+        ])
+        .unwrap();
+        assert_eq!(combined.to_string(), "Missing TTL; Payload is expired");
+        assert_eq!(combined.field(), None);
+        assert_eq!(combined.code(), UCode::InvalidArgument);
+        assert_eq!(combined.errors().len(), 2);
+        assert_eq!(combined.errors()[0], single);
+
+        // combined errors are flattened, and keep their code if all parts share it
+        let nested = ValidationError::combine([combined.clone(), single]).unwrap();
+        assert_eq!(nested.errors().len(), 3);
+        let within = combined.within("attributes");
+        assert_eq!(within.errors()[0].field(), Some("attributes.ttl"));
+        assert_eq!(within.errors()[1].field(), Some("attributes"));
+    }
+
+    #[test]
+    fn test_combine_keeps_common_code() {
+        let combined = ValidationError::combine([
+            ValidationError::new("first").with_code(UCode::PermissionDenied),
+            ValidationError::new("second").with_code(UCode::PermissionDenied),
+        ])
+        .unwrap();
+        assert_eq!(combined.code(), UCode::PermissionDenied);
+    }
+}
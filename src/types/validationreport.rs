@@ -0,0 +1,243 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use alloc::vec::Vec;
+
+use crate::types::validationerror::ValidationError;
+use crate::uprotocol::{UCode, UStatus};
+
+/// The severity of a [`Violation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The data is valid, but does not follow a recommendation, e.g. of the uProtocol specification.
+    Warning,
+    /// The data is invalid.
+    Error,
+}
+
+/// A single finding of a validation, i.e. a failure or a warning about a field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    severity: Severity,
+    error: ValidationError,
+}
+
+impl Violation {
+    /// Gets the severity of the violation.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Gets the path of the field that the violation refers to, if known.
+    pub fn field(&self) -> Option<&str> {
+        self.error.field()
+    }
+
+    /// Gets the message describing the violation.
+    pub fn message(&self) -> &str {
+        self.error.message()
+    }
+
+    /// Gets the `UCode` that the violation maps to.
+    pub fn code(&self) -> UCode {
+        self.error.code()
+    }
+
+    /// Gets the violation as a `ValidationError`, regardless of its severity.
+    pub fn into_error(self) -> ValidationError {
+        self.error
+    }
+}
+
+/// The outcome of a validation, which lists all violations that were found rather than failing at the first one.
+///
+/// Each of the crate's validators creates a report, i.e. [`UriValidator::report`](crate::uri::validator::UriValidator::report),
+/// [`UAttributesValidator::report`](crate::transport::validator::UAttributesValidator::report),
+/// [`UMessageValidator::report`](crate::transport::validator::UMessageValidator::report) and
+/// `CloudEventValidator::report`, so that their outcomes can be handled the same way. Data is valid if the report
+/// has no violations of [`Severity::Error`], whereas warnings can be logged or ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        ValidationReport::default()
+    }
+
+    /// Adds an error, or all the errors that a combined error consists of, to this report.
+    pub fn push_error(&mut self, error: ValidationError) {
+        self.push(Severity::Error, error);
+    }
+
+    /// Adds a warning, or all the warnings that a combined error consists of, to this report.
+    pub fn push_warning(&mut self, warning: ValidationError) {
+        self.push(Severity::Warning, warning);
+    }
+
+    fn push(&mut self, severity: Severity, error: ValidationError) {
+        self.violations.extend(
+            error
+                .into_errors()
+                .into_iter()
+                .map(|error| Violation { severity, error }),
+        );
+    }
+
+    /// Adds the violations of another report to this report.
+    pub fn extend(&mut self, other: ValidationReport) {
+        self.violations.extend(other.violations);
+    }
+
+    /// Prepends the path of the field that contains the validated data to the field paths of the violations, e.g.
+    /// `attributes` for the report of the attributes of a message.
+    #[must_use]
+    pub fn within(mut self, parent: &str) -> ValidationReport {
+        self.violations = self
+            .violations
+            .into_iter()
+            .map(|Violation { severity, error }| Violation {
+                severity,
+                error: error.within(parent),
+            })
+            .collect();
+        self
+    }
+
+    /// Gets all violations, in the order they were found.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// Gets the violations of [`Severity::Error`].
+    pub fn errors(&self) -> impl Iterator<Item = &Violation> {
+        self.violations
+            .iter()
+            .filter(|violation| violation.severity == Severity::Error)
+    }
+
+    /// Gets the violations of [`Severity::Warning`].
+    pub fn warnings(&self) -> impl Iterator<Item = &Violation> {
+        self.violations
+            .iter()
+            .filter(|violation| violation.severity == Severity::Warning)
+    }
+
+    /// Checks if the validated data is valid, i.e. if the report has no violations of [`Severity::Error`].
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Converts this report into the result of a validation, dropping its warnings.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of the report combined by [`ValidationError::combine`], if there are any.
+    pub fn into_result(self) -> Result<(), ValidationError> {
+        let errors = self
+            .violations
+            .into_iter()
+            .filter(|violation| violation.severity == Severity::Error)
+            .map(Violation::into_error);
+        ValidationError::combine(errors).map_or(Ok(()), Err)
+    }
+
+    /// Converts this report into a `UStatus`, e.g. for responding to a request that failed validation.
+    ///
+    /// # Returns
+    ///
+    /// `UStatus::ok()` if the report has no errors, otherwise a failed status with the message and the code of the
+    /// combined errors.
+    pub fn into_ustatus(self) -> UStatus {
+        match self.into_result() {
+            Ok(()) => UStatus::ok(),
+            Err(error) => UStatus::from(error),
+        }
+    }
+}
+
+impl From<Result<(), ValidationError>> for ValidationReport {
+    fn from(result: Result<(), ValidationError>) -> Self {
+        let mut report = ValidationReport::new();
+        if let Err(error) = result {
+            report.push_error(error);
+        }
+        report
+    }
+}
+
+impl From<ValidationError> for ValidationReport {
+    fn from(error: ValidationError) -> Self {
+        ValidationReport::from(Err(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_report_is_valid() {
+        let report = ValidationReport::new();
+        assert!(report.is_valid());
+        assert!(report.violations().is_empty());
+        assert_eq!(report.into_ustatus(), UStatus::ok());
+    }
+
+    #[test]
+    fn test_report_flattens_combined_errors() {
+        let combined = ValidationError::combine([
+            ValidationError::new("Invalid TTL [0]").with_field("ttl"),
+            ValidationError::new("Missing Sink").with_field("sink"),
+        ])
+        .unwrap();
+        let report = ValidationReport::from(combined).within("attributes");
+
+        assert!(!report.is_valid());
+        let fields = report
+            .violations()
+            .iter()
+            .map(Violation::field)
+            .collect::<Vec<_>>();
+        assert_eq!(fields, [Some("attributes.ttl"), Some("attributes.sink")]);
+    }
+
+    #[test]
+    fn test_warnings_do_not_fail_validation() {
+        let mut report = ValidationReport::new();
+        report.push_warning(ValidationError::new("Priority is low").with_field("priority"));
+
+        assert!(report.is_valid());
+        assert_eq!(report.warnings().count(), 1);
+        assert_eq!(report.errors().count(), 0);
+        assert!(report.clone().into_result().is_ok());
+        assert!(report.into_ustatus().is_success());
+    }
+
+    #[test]
+    fn test_into_ustatus_keeps_code_and_messages() {
+        let mut report = ValidationReport::new();
+        report.push_warning(ValidationError::new("Priority is low"));
+        report.push_error(
+            ValidationError::new("Payload is expired")
+                .with_field("ttl")
+                .with_code(UCode::DeadlineExceeded),
+        );
+
+        let status = report.into_ustatus();
+        assert_eq!(status.get_code(), UCode::DeadlineExceeded);
+        assert_eq!(status.message(), "Payload is expired");
+    }
+}
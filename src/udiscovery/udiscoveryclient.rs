@@ -0,0 +1,381 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::coreservices::{udiscovery_entity, UDiscoveryMethod};
+use crate::rpc::{RpcClient, RpcInvoker, RpcMapper};
+use crate::transport::builder::EntityProfile;
+use crate::uprotocol::core::udiscovery::v3::{
+    FindNodesRequest, FindNodesResponse, LookupUriResponse, Node, UpdateNodeRequest,
+};
+use crate::uprotocol::{UCode, UEntity, UStatus, UUri, UUriBatch};
+use crate::uri::serializer::{MicroUriSerializer, UriSerializer};
+use crate::uri::validator::UriValidator;
+use crate::UProtocolError;
+
+const DEFAULT_TTL: u32 = 10_000;
+
+/// `UDiscoveryClient` invokes the methods of the
+/// [uDiscovery service](https://github.com/eclipse-uprotocol/uprotocol-spec/blob/main/up-l3/udiscovery/v3/README.adoc)
+/// on the local device.
+///
+/// Besides querying and updating the service's database of nodes, the client helps with resolving long form URIs,
+/// which identify uEntities and resources by name, into micro form URIs, which use the numeric identifiers
+/// that the uDiscovery service knows them by.
+#[derive(Clone, Copy)]
+pub struct UDiscoveryClient {
+    invoker: RpcInvoker,
+}
+
+impl UDiscoveryClient {
+    /// Creates a client that invokes the service's methods with a time-to-live of 10 seconds.
+    ///
+    /// # Type Parameters
+    ///
+    /// * `R` - The `RpcClient` to invoke the service's methods with.
+    pub fn new<R: RpcClient + 'static>() -> UDiscoveryClient {
+        UDiscoveryClient {
+            invoker: RpcInvoker::new::<R>(DEFAULT_TTL),
+        }
+    }
+
+    /// Sets the time-to-live of requests to the service.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - The time-to-live in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// The client.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: u32) -> UDiscoveryClient {
+        self.invoker.set_ttl(ttl);
+        self
+    }
+
+    /// Sets the profile of the uEntity invoking the service's methods, whose defaults for requests are applied to
+    /// the requests to the service. The profile's time-to-live of requests, if it has one, replaces the one set by
+    /// [`UDiscoveryClient::with_ttl`] before.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The profile.
+    ///
+    /// # Returns
+    ///
+    /// The client.
+    #[must_use]
+    pub fn with_profile(mut self, profile: EntityProfile) -> UDiscoveryClient {
+        self.invoker.set_profile(profile);
+        self
+    }
+
+    /// Gets the uEntity of the uDiscovery service.
+    pub fn entity() -> UEntity {
+        udiscovery_entity()
+    }
+
+    /// Looks up the URIs of the uEntities and resources that match a URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI to look up.
+    ///
+    /// # Returns
+    ///
+    /// The matching URIs, with both names and numeric identifiers set.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` if the method could not be invoked, its response could not be unpacked,
+    /// or with the failure reported by the service.
+    pub async fn lookup_uri(&self, uri: UUri) -> Result<UUriBatch, UProtocolError> {
+        let response = self
+            .invoker
+            .invoke(UDiscoveryMethod::LookupUri.uri(), &uri)
+            .await;
+        let response: LookupUriResponse = RpcMapper::map_response(response)?;
+        check_status(response.status)?;
+        Ok(response.uris.unwrap_or_default())
+    }
+
+    /// Finds the nodes matching a URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The URI of the nodes, and the depth of the node tree to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// The nodes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` if the method could not be invoked, its response could not be unpacked,
+    /// or with the failure reported by the service.
+    pub async fn find_nodes(&self, request: FindNodesRequest) -> Result<Vec<Node>, UProtocolError> {
+        let response = self
+            .invoker
+            .invoke(UDiscoveryMethod::FindNodes.uri(), &request)
+            .await;
+        let response: FindNodesResponse = RpcMapper::map_response(response)?;
+        check_status(response.status)?;
+        Ok(response.nodes)
+    }
+
+    /// Updates a node.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The node, and the time-to-live of the node's entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` if the method could not be invoked, or the failure reported by the service.
+    pub async fn update_node(&self, request: UpdateNodeRequest) -> Result<(), UProtocolError> {
+        self.invoker
+            .invoke_for_status(UDiscoveryMethod::UpdateNode.uri(), &request)
+            .await
+    }
+
+    /// Resolves a URI into its micro form by looking up the numeric identifiers that it lacks.
+    ///
+    /// URIs that already are in micro form are returned unchanged, without invoking the service.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI to resolve.
+    ///
+    /// # Returns
+    ///
+    /// The URI, with the numeric identifiers of its authority, uEntity and resource set.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` with code `UCode::NotFound` if the service does not know the uEntity or resource,
+    /// or any of the errors returned by [`UDiscoveryClient::lookup_uri`].
+    pub async fn resolve(&self, uri: UUri) -> Result<UUri, UProtocolError> {
+        if UriValidator::is_micro_form(&uri) {
+            return Ok(uri);
+        }
+        let batch = self.lookup_uri(uri.clone()).await?;
+        batch
+            .uris
+            .iter()
+            .find(|resolved| is_same_entity(&uri, resolved))
+            .map(|resolved| merge_identifiers(&uri, resolved))
+            .filter(UriValidator::is_micro_form)
+            .ok_or_else(|| {
+                UStatus::fail_with_code(UCode::NotFound, "Failed to resolve URI into micro form")
+                    .into()
+            })
+    }
+
+    /// Resolves a URI and serializes it into micro form.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI to resolve.
+    ///
+    /// # Returns
+    ///
+    /// The serialized micro form URI.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError::Serialization` if the resolved URI cannot be serialized,
+    /// or any of the errors returned by [`UDiscoveryClient::resolve`].
+    pub async fn resolve_to_micro_uri(&self, uri: UUri) -> Result<Vec<u8>, UProtocolError> {
+        let resolved = self.resolve(uri).await?;
+        Ok(MicroUriSerializer::serialize(&resolved)?)
+    }
+}
+
+fn check_status(status: Option<UStatus>) -> Result<(), UProtocolError> {
+    match status {
+        Some(status) if status.is_failed() => Err(status.into()),
+        _ => Ok(()),
+    }
+}
+
+// Checks if a URI returned by the service refers to the uEntity of the URI being resolved.
+fn is_same_entity(uri: &UUri, resolved: &UUri) -> bool {
+    let name = |uri: &UUri| uri.entity.as_ref().map(|entity| entity.name.clone());
+    name(uri) == name(resolved)
+}
+
+// Sets the numeric identifiers that a URI lacks from the URI returned by the service, keeping the names.
+fn merge_identifiers(uri: &UUri, resolved: &UUri) -> UUri {
+    let mut merged = uri.clone();
+    if let (Some(authority), Some(resolved_authority)) =
+        (&mut merged.authority, &resolved.authority)
+    {
+        if !authority.has_ip() && !authority.has_id() {
+            if let Some(ip) = resolved_authority.get_ip() {
+                authority.set_ip(ip.to_vec());
+            } else if let Some(id) = resolved_authority.get_id() {
+                authority.set_id(id.to_vec());
+            }
+        }
+    }
+    if let (Some(entity), Some(resolved_entity)) = (&mut merged.entity, &resolved.entity) {
+        entity.id = entity.id.or(resolved_entity.id);
+        entity.version_major = entity.version_major.or(resolved_entity.version_major);
+    }
+    match (&mut merged.resource, &resolved.resource) {
+        (Some(resource), Some(resolved_resource)) => {
+            resource.id = resource.id.or(resolved_resource.id);
+        }
+        (None, Some(resolved_resource)) => merged.resource = Some(resolved_resource.clone()),
+        _ => {}
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use prost::Message;
+    use prost_types::Any;
+
+    use crate::rpc::{RpcClientResult, RpcMapperError};
+    use crate::uprotocol::{Data, UAttributes, UPayload, UResource};
+
+    fn build_long_uri_for_test() -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from("door.front_left#Door")),
+            ..Default::default()
+        }
+    }
+
+    fn build_resolved_uri_for_test() -> UUri {
+        let mut uri = build_long_uri_for_test();
+        uri.entity.as_mut().unwrap().id = Some(0x1234);
+        uri.resource.as_mut().unwrap().id = Some(0x8001);
+        uri
+    }
+
+    // Knows the `body.access` uEntity only.
+    struct UDiscoveryRpcClient;
+
+    #[async_trait]
+    impl RpcClient for UDiscoveryRpcClient {
+        async fn invoke_method(
+            topic: UUri,
+            payload: UPayload,
+            _attributes: UAttributes,
+        ) -> RpcClientResult {
+            let Some(Data::Value(data)) = payload.data else {
+                return Err(RpcMapperError::InvalidPayload("no data".to_string()));
+            };
+            let (type_url, value) = match topic.resource.unwrap().id {
+                Some(1) => {
+                    let uri = UUri::decode(data.as_slice()).unwrap();
+                    let response = if uri.entity.unwrap().name == "body.access" {
+                        LookupUriResponse {
+                            uris: Some(UUriBatch {
+                                uris: vec![build_resolved_uri_for_test()],
+                            }),
+                            status: Some(UStatus::ok()),
+                        }
+                    } else {
+                        LookupUriResponse {
+                            uris: None,
+                            status: Some(UStatus::fail_with_code(UCode::NotFound, "unknown")),
+                        }
+                    };
+                    ("LookupUriResponse", response.encode_to_vec())
+                }
+                Some(3) => {
+                    let request = FindNodesRequest::decode(data.as_slice()).unwrap();
+                    let response = FindNodesResponse {
+                        nodes: vec![Node {
+                            uri: request.uri,
+                            ..Default::default()
+                        }],
+                        status: Some(UStatus::ok()),
+                    };
+                    ("FindNodesResponse", response.encode_to_vec())
+                }
+                _ => {
+                    return Err(RpcMapperError::UnexpectedError(
+                        "unknown method".to_string(),
+                    ))
+                }
+            };
+            Ok(Any {
+                type_url: format!("type.googleapis.com/uprotocol.core.udiscovery.v3.{type_url}"),
+                value,
+            }
+            .try_into()
+            .unwrap())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let client = UDiscoveryClient::new::<UDiscoveryRpcClient>();
+
+        let resolved = client.resolve(build_long_uri_for_test()).await.unwrap();
+        assert_eq!(resolved, build_resolved_uri_for_test());
+        assert!(UriValidator::is_micro_form(&resolved));
+
+        let micro_uri = client
+            .resolve_to_micro_uri(build_long_uri_for_test())
+            .await
+            .unwrap();
+        let deserialized = MicroUriSerializer::deserialize(micro_uri).unwrap();
+        assert_eq!(deserialized.entity.unwrap().id, Some(0x1234));
+        assert_eq!(deserialized.resource.unwrap().id, Some(0x8001));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_fails_for_unknown_entity() {
+        let client = UDiscoveryClient::new::<UDiscoveryRpcClient>();
+        let mut uri = build_long_uri_for_test();
+        uri.entity.as_mut().unwrap().name = "hartley".to_string();
+
+        let error = client.resolve(uri).await.unwrap_err();
+        assert_eq!(error.code(), UCode::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_returns_micro_form_uri_unchanged() {
+        let client = UDiscoveryClient::new::<UDiscoveryRpcClient>();
+
+        let resolved = client.resolve(build_resolved_uri_for_test()).await.unwrap();
+        assert_eq!(resolved, build_resolved_uri_for_test());
+    }
+
+    #[tokio::test]
+    async fn test_find_nodes() {
+        let client = UDiscoveryClient::new::<UDiscoveryRpcClient>();
+
+        let nodes = client
+            .find_nodes(FindNodesRequest {
+                uri: "//vcu.vin".to_string(),
+                depth: Some(1),
+            })
+            .await
+            .unwrap();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].uri, "//vcu.vin");
+    }
+}
@@ -0,0 +1,532 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use prost::Message;
+
+use crate::rpc::{RpcClient, RpcClientResult, RpcMapperError};
+use crate::udiscovery::UDiscoveryClient;
+use crate::uprotocol::{UAttributes, UCode, UPayload, UStatus, UUri};
+use crate::uri::serializer::UriMetadata;
+use crate::uri::validator::UriValidator;
+use crate::uuid::builder::{SystemClock, UClock};
+use crate::UProtocolError;
+
+const DEFAULT_CAPACITY: usize = 256;
+const DEFAULT_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug)]
+struct CacheEntry {
+    uri: UUri,
+    expires_at: u64,
+    last_used: u64,
+}
+
+// A cache of resolved URIs, keyed by the encoded URIs they have been resolved from, which forgets entries
+// once they have expired and the least recently used entries once it is full.
+#[derive(Debug)]
+struct Cache {
+    entries: HashMap<Vec<u8>, CacheEntry>,
+    capacity: usize,
+    ttl: u64,
+    uses: u64,
+}
+
+impl Cache {
+    fn new(capacity: usize, ttl: Duration) -> Cache {
+        Cache {
+            entries: HashMap::new(),
+            capacity,
+            ttl: u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX),
+            uses: 0,
+        }
+    }
+
+    fn get(&mut self, key: &[u8], now: u64) -> Option<UUri> {
+        self.uses += 1;
+        let uses = self.uses;
+        match self.entries.get_mut(key) {
+            Some(entry) if entry.expires_at > now => {
+                entry.last_used = uses;
+                Some(entry.uri.clone())
+            }
+            Some(_) => {
+                self.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&mut self, key: Vec<u8>, uri: UUri, now: u64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            self.entries.retain(|_, entry| entry.expires_at > now);
+        }
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let least_recently_used = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(key) = least_recently_used {
+                self.entries.remove(&key);
+            }
+        }
+        self.uses += 1;
+        self.entries.insert(
+            key,
+            CacheEntry {
+                uri,
+                expires_at: now.saturating_add(self.ttl),
+                last_used: self.uses,
+            },
+        );
+    }
+}
+
+/// `UriResolver` resolves long form URIs into micro form URIs and vice versa by means of the uDiscovery service,
+/// caching the results so that the service is not asked for every message.
+///
+/// Results are cached for a time-to-live, after which they are looked up again, so that changes to the service's
+/// database take effect eventually. The cache holds a bounded number of results, forgetting the least recently
+/// used ones first. Failures to resolve a URI are not cached.
+///
+/// A resolver that has been given [`UriMetadata`] records the names of the URIs it resolves in it, so that URIs that
+/// are received in micro form later can be shown with their names.
+pub struct UriResolver {
+    client: UDiscoveryClient,
+    to_micro: Mutex<Cache>,
+    to_long: Mutex<Cache>,
+    clock: Box<dyn UClock>,
+    metadata: Option<Arc<UriMetadata>>,
+}
+
+impl UriResolver {
+    /// Creates a resolver that caches up to 256 results in each direction for 5 minutes.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to look up URIs with.
+    pub fn new(client: UDiscoveryClient) -> UriResolver {
+        Self::with_clock(client, DEFAULT_CAPACITY, DEFAULT_TTL, SystemClock)
+    }
+
+    /// Creates a resolver that takes the current time from the given clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to look up URIs with.
+    /// * `capacity` - The maximum number of results to cache in each direction.
+    /// * `ttl` - The time for which results are cached.
+    /// * `clock` - The clock to expire results by.
+    pub fn with_clock<C>(
+        client: UDiscoveryClient,
+        capacity: usize,
+        ttl: Duration,
+        clock: C,
+    ) -> UriResolver
+    where
+        C: UClock + 'static,
+    {
+        UriResolver {
+            client,
+            to_micro: Mutex::new(Cache::new(capacity, ttl)),
+            to_long: Mutex::new(Cache::new(capacity, ttl)),
+            clock: Box::new(clock),
+            metadata: None,
+        }
+    }
+
+    /// Sets the metadata to record the names of resolved URIs in.
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata` - The metadata, which is shared with the code that serializes URIs, e.g. for logging.
+    #[must_use]
+    pub fn with_metadata(mut self, metadata: Arc<UriMetadata>) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Gets the metadata that the names of resolved URIs are recorded in, if any.
+    pub fn metadata(&self) -> Option<&Arc<UriMetadata>> {
+        self.metadata.as_ref()
+    }
+
+    fn now(&self) -> u64 {
+        self.clock.now().unwrap_or_default()
+    }
+
+    /// Resolves a URI into its micro form, like [`UDiscoveryClient::resolve`].
+    ///
+    /// URIs that already are in micro form are returned unchanged, without invoking the service.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI to resolve.
+    ///
+    /// # Returns
+    ///
+    /// The URI, with the numeric identifiers of its authority, uEntity and resource set.
+    ///
+    /// # Errors
+    ///
+    /// Returns any of the errors returned by [`UDiscoveryClient::resolve`].
+    pub async fn to_micro_form(&self, uri: UUri) -> Result<UUri, UProtocolError> {
+        if UriValidator::is_micro_form(&uri) {
+            return Ok(uri);
+        }
+        let key = uri.encode_to_vec();
+        if let Some(resolved) = self.to_micro.lock().unwrap().get(&key, self.now()) {
+            return Ok(resolved);
+        }
+        let resolved = self.client.resolve(uri).await?;
+        if let Some(metadata) = &self.metadata {
+            metadata.record(&resolved);
+        }
+        self.to_micro
+            .lock()
+            .unwrap()
+            .insert(key, resolved.clone(), self.now());
+        Ok(resolved)
+    }
+
+    /// Resolves a URI into its long form by looking up the names that it lacks.
+    ///
+    /// URIs that already are in long form are returned unchanged, without invoking the service.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI to resolve.
+    ///
+    /// # Returns
+    ///
+    /// The URI, with both the names and the numeric identifiers of its authority, uEntity and resource set.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` with code `UCode::NotFound` if the service does not know the uEntity or resource,
+    /// or any of the errors returned by [`UDiscoveryClient::lookup_uri`].
+    pub async fn to_long_form(&self, uri: UUri) -> Result<UUri, UProtocolError> {
+        if UriValidator::is_long_form(&uri) {
+            return Ok(uri);
+        }
+        let key = uri.encode_to_vec();
+        if let Some(resolved) = self.to_long.lock().unwrap().get(&key, self.now()) {
+            return Ok(resolved);
+        }
+        let entity_id = |uri: &UUri| uri.entity.as_ref().and_then(|entity| entity.id);
+        let batch = self.client.lookup_uri(uri.clone()).await?;
+        let resolved = batch
+            .uris
+            .into_iter()
+            .find(|resolved| entity_id(resolved) == entity_id(&uri))
+            .filter(UriValidator::is_long_form)
+            .ok_or_else(|| {
+                UStatus::fail_with_code(UCode::NotFound, "Failed to resolve URI into long form")
+            })?;
+        if let Some(metadata) = &self.metadata {
+            metadata.record(&resolved);
+            let name = resolved
+                .authority
+                .as_ref()
+                .and_then(|authority| authority.get_name());
+            if let (Some(authority), Some(name)) = (uri.authority.as_ref(), name) {
+                metadata.record_authority(authority, name);
+            }
+        }
+        self.to_long
+            .lock()
+            .unwrap()
+            .insert(key, resolved.clone(), self.now());
+        Ok(resolved)
+    }
+
+    /// Forgets the cached results for a URI, e.g. after a uEntity has been found to have moved.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI as it has been passed to the resolver.
+    pub fn invalidate(&self, uri: &UUri) {
+        let key = uri.encode_to_vec();
+        self.to_micro.lock().unwrap().entries.remove(&key);
+        self.to_long.lock().unwrap().entries.remove(&key);
+    }
+
+    /// Forgets all cached results.
+    pub fn clear(&self) {
+        self.to_micro.lock().unwrap().entries.clear();
+        self.to_long.lock().unwrap().entries.clear();
+    }
+
+    /// Invokes a method by means of an `RpcClient`, resolving the URI of the method into micro form first if only
+    /// its names are known.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The URI of the method to invoke.
+    /// * `payload` - The payload to send.
+    /// * `attributes` - The attributes to send, whose sink is replaced with the resolved URI if it is the method.
+    ///
+    /// # Returns
+    ///
+    /// The result of [`RpcClient::invoke_method`], an `RpcMapperError::InvalidPayload` if the URI cannot be
+    /// resolved, or an `RpcMapperError::ResourceExhausted` if the payload exceeds [`RpcClient::payload_limits`].
+    pub async fn invoke_method<R: RpcClient>(
+        &self,
+        method: UUri,
+        payload: UPayload,
+        mut attributes: UAttributes,
+    ) -> RpcClientResult {
+        R::payload_limits().check(&payload)?;
+        let resolved = self.to_micro_form(method.clone()).await.map_err(|e| {
+            RpcMapperError::InvalidPayload(format!("Failed to resolve method URI: {e}"))
+        })?;
+        if attributes.sink.as_ref() == Some(&method) {
+            attributes.sink = Some(resolved.clone());
+        }
+        R::invoke_method(resolved, payload, attributes).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use prost_types::Any;
+
+    use crate::transport::builder::UAttributesBuilder;
+    use crate::uprotocol::core::udiscovery::v3::LookupUriResponse;
+    use crate::uprotocol::{Data, UEntity, UPriority, UResource, UUriBatch};
+
+    static LOOKUPS_BY_NAME: AtomicUsize = AtomicUsize::new(0);
+
+    struct TestClock(AtomicU64);
+    impl UClock for TestClock {
+        fn now(&self) -> Option<u64> {
+            Some(self.0.load(Ordering::Relaxed))
+        }
+    }
+
+    fn build_long_uri_for_test() -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from("door.front_left#Door")),
+            ..Default::default()
+        }
+    }
+
+    fn build_resolved_uri_for_test() -> UUri {
+        let mut uri = build_long_uri_for_test();
+        uri.entity.as_mut().unwrap().id = Some(0x1234);
+        uri.resource.as_mut().unwrap().id = Some(0x8001);
+        uri
+    }
+
+    fn build_micro_uri_for_test() -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                id: Some(0x1234),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                id: Some(0x8001),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    // Knows the `body.access` uEntity only, and serves as the uEntity's RPC client as well.
+    struct UDiscoveryRpcClient;
+
+    #[async_trait]
+    impl RpcClient for UDiscoveryRpcClient {
+        async fn invoke_method(
+            topic: UUri,
+            payload: UPayload,
+            _attributes: UAttributes,
+        ) -> RpcClientResult {
+            if topic.entity.as_ref().map(|entity| entity.id) == Some(Some(0x1234)) {
+                return Ok(payload);
+            }
+            let Some(Data::Value(data)) = payload.data else {
+                return Err(RpcMapperError::InvalidPayload("no data".to_string()));
+            };
+            let uri = UUri::decode(data.as_slice()).unwrap();
+            let entity = uri.entity.unwrap();
+            if !entity.name.is_empty() {
+                LOOKUPS_BY_NAME.fetch_add(1, Ordering::Relaxed);
+            }
+            let response = if entity.name == "body.access" || entity.id == Some(0x1234) {
+                LookupUriResponse {
+                    uris: Some(UUriBatch {
+                        uris: vec![build_resolved_uri_for_test()],
+                    }),
+                    status: Some(UStatus::ok()),
+                }
+            } else {
+                LookupUriResponse {
+                    uris: None,
+                    status: Some(UStatus::fail_with_code(UCode::NotFound, "unknown")),
+                }
+            };
+            Ok(Any {
+                type_url: "type.googleapis.com/uprotocol.core.udiscovery.v3.LookupUriResponse"
+                    .to_string(),
+                value: response.encode_to_vec(),
+            }
+            .try_into()
+            .unwrap())
+        }
+    }
+
+    fn build_resolver_for_test() -> (UriResolver, Arc<TestClock>) {
+        let clock = Arc::new(TestClock(AtomicU64::new(0)));
+        let resolver = UriResolver::with_clock(
+            UDiscoveryClient::new::<UDiscoveryRpcClient>(),
+            16,
+            Duration::from_secs(60),
+            Arc::clone(&clock),
+        );
+        (resolver, clock)
+    }
+
+    #[tokio::test]
+    async fn test_to_micro_form_is_cached_until_expired() {
+        let (resolver, clock) = build_resolver_for_test();
+        let lookups = LOOKUPS_BY_NAME.load(Ordering::Relaxed);
+
+        for _ in 0..2 {
+            let resolved = resolver
+                .to_micro_form(build_long_uri_for_test())
+                .await
+                .unwrap();
+            assert_eq!(resolved, build_resolved_uri_for_test());
+        }
+        assert_eq!(LOOKUPS_BY_NAME.load(Ordering::Relaxed), lookups + 1);
+
+        clock.0.store(60_000, Ordering::Relaxed);
+        resolver
+            .to_micro_form(build_long_uri_for_test())
+            .await
+            .unwrap();
+        assert_eq!(LOOKUPS_BY_NAME.load(Ordering::Relaxed), lookups + 2);
+
+        resolver.invalidate(&build_long_uri_for_test());
+        resolver
+            .to_micro_form(build_long_uri_for_test())
+            .await
+            .unwrap();
+        assert_eq!(LOOKUPS_BY_NAME.load(Ordering::Relaxed), lookups + 3);
+    }
+
+    #[tokio::test]
+    async fn test_to_long_form() {
+        let (resolver, _) = build_resolver_for_test();
+
+        let resolved = resolver
+            .to_long_form(build_micro_uri_for_test())
+            .await
+            .unwrap();
+        assert_eq!(resolved, build_resolved_uri_for_test());
+        assert!(UriValidator::is_long_form(&resolved));
+
+        let mut unknown = build_micro_uri_for_test();
+        unknown.entity.as_mut().unwrap().id = Some(0x4321);
+        let error = resolver.to_long_form(unknown).await.unwrap_err();
+        assert_eq!(error.code(), UCode::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_to_long_form_records_metadata() {
+        let metadata = Arc::new(UriMetadata::new());
+        let (resolver, _) = build_resolver_for_test();
+        let resolver = resolver.with_metadata(Arc::clone(&metadata));
+        assert!(metadata
+            .annotate(&build_micro_uri_for_test())
+            .entity
+            .unwrap()
+            .name
+            .is_empty());
+
+        resolver
+            .to_long_form(build_micro_uri_for_test())
+            .await
+            .unwrap();
+        assert_eq!(
+            metadata.annotate(&build_micro_uri_for_test()),
+            build_resolved_uri_for_test()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invoke_method_resolves_method_uri() {
+        let (resolver, _) = build_resolver_for_test();
+        let method = build_long_uri_for_test();
+        resolver.to_micro.lock().unwrap().insert(
+            method.encode_to_vec(),
+            build_resolved_uri_for_test(),
+            0,
+        );
+        let attributes =
+            UAttributesBuilder::request(UPriority::UpriorityCs4, method.clone(), 1000).build();
+
+        let payload = UPayload {
+            data: Some(Data::Value(vec![1, 2, 3])),
+            ..Default::default()
+        };
+        let response = resolver
+            .invoke_method::<UDiscoveryRpcClient>(method, payload.clone(), attributes)
+            .await
+            .unwrap();
+        assert_eq!(response, payload);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let mut cache = Cache::new(2, Duration::from_secs(60));
+        let uri = |name: &str| UUri {
+            entity: Some(UEntity {
+                name: name.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        cache.insert(vec![1], uri("one"), 0);
+        cache.insert(vec![2], uri("two"), 0);
+        assert!(cache.get(&[1], 0).is_some());
+        cache.insert(vec![3], uri("three"), 0);
+
+        assert_eq!(cache.get(&[1], 0), Some(uri("one")));
+        assert_eq!(cache.get(&[2], 0), None);
+        assert_eq!(cache.get(&[3], 0), Some(uri("three")));
+        assert_eq!(cache.get(&[3], 60_000), None);
+    }
+}
@@ -11,9 +11,12 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+use alloc::string::String;
+
+use crate::uprotocol::uresource::UResourceId;
 use crate::uprotocol::UResource;
 
-const MAX_RPC_ID: u32 = 1000;
+const MAX_RPC_ID: u16 = 1000;
 
 pub struct UResourceBuilder {}
 
@@ -39,11 +42,11 @@ impl UResourceBuilder {
     ///
     /// # Returns
     /// Returns a `UResource` for an RPC request.
-    pub fn for_rpc_request(method: Option<String>, id: Option<u32>) -> UResource {
+    pub fn for_rpc_request(method: Option<String>, id: Option<UResourceId>) -> UResource {
         UResource {
             name: String::from("rpc"),
             instance: method,
-            id,
+            id: id.map(u32::from),
             message: None,
         }
     }
@@ -60,15 +63,15 @@ impl UResourceBuilder {
     ///
     /// # Returns
     /// Returns a `UResource` instance corresponding to the given ID.
-    pub fn from_id(id: u32) -> UResource {
-        if id < MAX_RPC_ID {
+    pub fn from_id(id: UResourceId) -> UResource {
+        if id.value() < MAX_RPC_ID {
             return Self::for_rpc_request(None, Some(id));
         }
 
         UResource {
             name: String::new(),
             instance: None,
-            id: Some(id),
+            id: Some(id.into()),
             message: None,
         }
     }
@@ -0,0 +1,182 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::uprotocol::UUri;
+use crate::uri::serializer::{MicroUriSerializer, SerializationError, UriSerializer};
+
+/// `AuthorityIdStrategy` maps the names of uAuthorities to the IDs that address them in URIs in micro form, for
+/// deployments that address devices by name and cannot distribute numeric IDs for them.
+///
+/// [`MicroUriSerializer::serialize_with`] takes the ID of a uAuthority that only has a name from the strategy, and
+/// [`MicroUriSerializer::deserialize_with`] turns the IDs the strategy knows back into names. A strategy has to map
+/// names to the same IDs on all devices that exchange URIs, e.g. by deriving the IDs from the names like
+/// [`HashedAuthorityId`](crate::uri::serializer::HashedAuthorityId) does.
+pub trait AuthorityIdStrategy {
+    /// Gets the ID of a uAuthority.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the uAuthority.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the uAuthority cannot be given an ID, e.g. because another uAuthority has the
+    /// same one.
+    fn id_of(&self, name: &str) -> Result<Vec<u8>, SerializationError>;
+
+    /// Gets the name of the uAuthority with an ID.
+    ///
+    /// # Returns
+    ///
+    /// The name, or `None` if the ID is unknown.
+    fn name_of(&self, id: &[u8]) -> Option<String>;
+}
+
+impl MicroUriSerializer {
+    /// Serializes a `UUri` into micro form, taking the ID of a uAuthority that has a name from a strategy.
+    ///
+    /// # Parameters
+    /// * `uri`: A reference to the `UUri` data object.
+    /// * `strategy`: The strategy to map the name of the uAuthority with.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the strategy fails to map the name of the uAuthority, or for the URIs that
+    /// [`MicroUriSerializer::serialize`] fails for.
+    pub fn serialize_with<S>(uri: &UUri, strategy: &S) -> Result<Vec<u8>, SerializationError>
+    where
+        S: AuthorityIdStrategy + ?Sized,
+    {
+        let Some(name) = uri
+            .authority
+            .as_ref()
+            .and_then(|authority| authority.get_name())
+        else {
+            return MicroUriSerializer::serialize(uri);
+        };
+        let mut uri = uri.clone();
+        let id = strategy.id_of(name)?;
+        if let Some(authority) = uri.authority.as_mut() {
+            authority.set_id(id);
+        }
+        MicroUriSerializer::serialize(&uri)
+    }
+
+    /// Creates a `UUri` from a URI in micro form, taking the name of a uAuthority that has an ID from a strategy.
+    ///
+    /// # Parameters
+    /// * `micro_uri`: The URI in micro form.
+    /// * `strategy`: The strategy to map the ID of the uAuthority with.
+    ///
+    /// # Returns
+    /// The `UUri`, whose uAuthority keeps its ID if the strategy does not know it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` for the URIs that [`MicroUriSerializer::deserialize`] fails for.
+    pub fn deserialize_with<S>(micro_uri: Vec<u8>, strategy: &S) -> Result<UUri, SerializationError>
+    where
+        S: AuthorityIdStrategy + ?Sized,
+    {
+        let mut uri = MicroUriSerializer::deserialize(micro_uri)?;
+        if let Some(authority) = uri.authority.as_mut() {
+            if let Some(name) = authority.get_id().and_then(|id| strategy.name_of(id)) {
+                authority.set_name(name);
+            }
+        }
+        Ok(uri)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use crate::uprotocol::{UAuthority, UEntity, UResource};
+
+    // Knows a single uAuthority, `vcu.my_car_vin`, with ID 0x2A.
+    struct SingleAuthority;
+
+    impl AuthorityIdStrategy for SingleAuthority {
+        fn id_of(&self, name: &str) -> Result<Vec<u8>, SerializationError> {
+            match name {
+                "vcu.my_car_vin" => Ok(vec![0x2A]),
+                _ => Err(SerializationError::new("Unknown authority")),
+            }
+        }
+
+        fn name_of(&self, id: &[u8]) -> Option<String> {
+            (id == [0x2A]).then(|| "vcu.my_car_vin".to_string())
+        }
+    }
+
+    fn build_uri_for_test(authority: UAuthority) -> UUri {
+        UUri {
+            authority: Some(authority),
+            entity: Some(UEntity {
+                id: Some(29999),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                id: Some(19999),
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn test_serialize_with_strategy() {
+        let mut authority = UAuthority::default();
+        authority.set_name("vcu.my_car_vin");
+        let uri = build_uri_for_test(authority);
+
+        let micro_uri = MicroUriSerializer::serialize_with(&uri, &SingleAuthority).unwrap();
+        assert_eq!(&micro_uri[8..], &[1, 0x2A]);
+        let deserialized =
+            MicroUriSerializer::deserialize_with(micro_uri, &SingleAuthority).unwrap();
+        assert_eq!(
+            deserialized.authority.unwrap().get_name(),
+            Some("vcu.my_car_vin")
+        );
+    }
+
+    #[test]
+    fn test_deserialize_with_strategy_keeps_unknown_id() {
+        let mut authority = UAuthority::default();
+        authority.set_id(vec![1, 2, 3]);
+        let uri = build_uri_for_test(authority);
+
+        let micro_uri = MicroUriSerializer::serialize_with(&uri, &SingleAuthority).unwrap();
+        let deserialized =
+            MicroUriSerializer::deserialize_with(micro_uri, &SingleAuthority).unwrap();
+        assert_eq!(
+            deserialized.authority.unwrap().get_id(),
+            Some([1, 2, 3].as_slice())
+        );
+
+        let mut authority = UAuthority::default();
+        authority.set_name("unknown");
+        assert!(MicroUriSerializer::serialize_with(
+            &build_uri_for_test(authority),
+            &SingleAuthority
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,208 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use sha2::{Digest, Sha256};
+
+use crate::uri::serializer::{AuthorityIdStrategy, SerializationError};
+
+type CollisionHandler = Box<dyn Fn(&str, &str, &[u8]) + Send + Sync + 'static>;
+
+/// An [`AuthorityIdStrategy`] that derives the ID of a uAuthority from its name, as the first bytes of the SHA-256
+/// hash of the name in lowercase, so that devices agree on the IDs without distributing them.
+///
+/// This profile is not part of the uProtocol specification, so all devices exchanging URIs in micro form need to use
+/// it with the same ID length. Names are compared ignoring ASCII case, like host names.
+///
+/// Different names may hash to the same ID, which is the more likely the shorter the IDs are: with IDs of 8 bytes,
+/// a collision among 100,000 names has a probability of about 3 * 10^-10. Names that are known in advance are
+/// registered with [`HashedAuthorityId::register`], so that collisions among them are detected at startup and their
+/// IDs can be mapped back to names when deserializing. Mapping a name whose ID is the one of a registered name fails,
+/// and is reported to the handler set with [`HashedAuthorityId::on_collision`], e.g. for alerting the operators of
+/// the deployment. Only registered names are kept, so that the memory taken does not grow with the names of
+/// received messages.
+pub struct HashedAuthorityId {
+    length: usize,
+    // the names by their IDs
+    names: Mutex<HashMap<Vec<u8>, String>>,
+    on_collision: Option<CollisionHandler>,
+}
+
+impl Default for HashedAuthorityId {
+    fn default() -> Self {
+        HashedAuthorityId::new(HashedAuthorityId::DEFAULT_LENGTH)
+    }
+}
+
+impl HashedAuthorityId {
+    /// The default length of IDs in bytes.
+    pub const DEFAULT_LENGTH: usize = 8;
+
+    /// Creates a strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The length of IDs in bytes, which is between 1 and 32, the length of a SHA-256 hash.
+    pub fn new(length: usize) -> Self {
+        HashedAuthorityId {
+            length: length.clamp(1, 32),
+            names: Mutex::new(HashMap::new()),
+            on_collision: None,
+        }
+    }
+
+    /// Sets a handler that is called for each name whose ID is the one of another name.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - The handler to call with the known name, the colliding name and their ID.
+    #[must_use]
+    pub fn on_collision<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&str, &str, &[u8]) + Send + Sync + 'static,
+    {
+        self.on_collision = Some(Box::new(handler));
+        self
+    }
+
+    /// Derives the ID of a name, without checking for collisions.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of a uAuthority.
+    pub fn hash(&self, name: &str) -> Vec<u8> {
+        let digest = Sha256::digest(name.to_ascii_lowercase().as_bytes());
+        digest.iter().take(self.length).copied().collect()
+    }
+
+    /// Registers the names of uAuthorities, so that their IDs are mapped back to them.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - The names.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if one of the names has the ID of another name. The names before it are
+    /// registered then.
+    pub fn register<I, N>(&self, names: I) -> Result<(), SerializationError>
+    where
+        I: IntoIterator<Item = N>,
+        N: AsRef<str>,
+    {
+        names
+            .into_iter()
+            .try_for_each(|name| self.checked_id(name.as_ref(), true).map(|_id| ()))
+    }
+
+    // derives the ID of a name and checks it against the registered names, registering the name if asked to; the
+    // collision handler is called without holding the lock, so that it can use this strategy itself
+    fn checked_id(&self, name: &str, register: bool) -> Result<Vec<u8>, SerializationError> {
+        let id = self.hash(name);
+        let collision = {
+            let mut names = self.names();
+            match names.get(&id) {
+                Some(known) if !known.eq_ignore_ascii_case(name) => Some(known.clone()),
+                Some(_) => None,
+                None => {
+                    if register {
+                        names.insert(id.clone(), name.to_string());
+                    }
+                    None
+                }
+            }
+        };
+        match collision {
+            Some(known) => {
+                if let Some(on_collision) = &self.on_collision {
+                    on_collision(&known, name, &id);
+                }
+                Err(SerializationError::new(format!(
+                    "Authority names {known} and {name} have the same ID"
+                )))
+            }
+            None => Ok(id),
+        }
+    }
+
+    // the names by their IDs, which stay consistent even if a collision handler has panicked
+    fn names(&self) -> MutexGuard<'_, HashMap<Vec<u8>, String>> {
+        self.names.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl AuthorityIdStrategy for HashedAuthorityId {
+    fn id_of(&self, name: &str) -> Result<Vec<u8>, SerializationError> {
+        self.checked_id(name, false)
+    }
+
+    fn name_of(&self, id: &[u8]) -> Option<String> {
+        self.names().get(id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn test_ids_are_derived_from_names() {
+        let strategy = HashedAuthorityId::default();
+        let id = strategy.id_of("vcu.my_car_vin").unwrap();
+        assert_eq!(id.len(), HashedAuthorityId::DEFAULT_LENGTH);
+        assert_eq!(HashedAuthorityId::default().hash("VCU.My_Car_VIN"), id);
+        assert_eq!(strategy.name_of(&id), None);
+        strategy.register(["vcu.my_car_vin"]).unwrap();
+        assert_eq!(strategy.name_of(&id), Some("vcu.my_car_vin".to_string()));
+        assert_eq!(HashedAuthorityId::new(64).hash("vcu").len(), 32);
+    }
+
+    #[test]
+    fn test_collisions_are_detected() {
+        let collisions = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&collisions);
+        // with IDs of a single byte, some of 20 names collide
+        let strategy = HashedAuthorityId::new(1).on_collision(move |known, name, _id| {
+            sink.lock()
+                .unwrap()
+                .push((known.to_string(), name.to_string()));
+        });
+
+        let names = (0..20).map(|index| format!("vcu.car_{index}"));
+        assert!(strategy.register(names).is_err());
+        let collisions = collisions.lock().unwrap();
+        assert_eq!(collisions.len(), 1);
+        let (known, name) = &collisions[0];
+        assert_eq!(strategy.hash(known), strategy.hash(name));
+        assert!(strategy.id_of(name).is_err());
+        assert!(strategy.id_of(known).is_ok());
+    }
+
+    #[test]
+    fn test_collision_handler_can_use_strategy() {
+        let strategy = Arc::new_cyclic(|strategy: &std::sync::Weak<HashedAuthorityId>| {
+            let strategy = strategy.clone();
+            HashedAuthorityId::new(1).on_collision(move |known, _name, id| {
+                let strategy = strategy.upgrade().unwrap();
+                assert_eq!(strategy.name_of(id).as_deref(), Some(known));
+            })
+        });
+
+        let names = (0..20).map(|index| format!("vcu.car_{index}"));
+        assert!(strategy.register(names).is_err());
+    }
+}
@@ -11,8 +11,6 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use regex::Regex;
-
 use crate::uprotocol::{Remote, UAuthority, UEntity, UResource, UUri};
 use crate::uri::serializer::{SerializationError, UriSerializer};
 use crate::uri::validator::UriValidator;
@@ -37,10 +35,7 @@ impl UriSerializer<String> for LongUriSerializer {
         }
         output.push_str(&Self::build_resource_part_of_uri(uri));
 
-        Ok(Regex::new(r"/+$")
-            .unwrap()
-            .replace_all(&output, "")
-            .into_owned())
+        Ok(output.trim_end_matches('/').to_string())
     }
 
     /// Create an  URI data object from a uProtocol string (long or short).
@@ -53,6 +48,8 @@ impl UriSerializer<String> for LongUriSerializer {
     /// # Returns
     ///
     /// Returns an `UUri` data object created from the given uProtocol URI string.
+    // the parts are indexed only after checking their number, and ':' takes a single byte
+    #[allow(clippy::indexing_slicing)]
     fn deserialize(uprotocol_uri: String) -> Result<UUri, SerializationError> {
         if uprotocol_uri.is_empty() {
             return Err(SerializationError::new("URI is empty"));
@@ -64,7 +61,7 @@ impl UriSerializer<String> for LongUriSerializer {
             uprotocol_uri.replace('\\', "/")
         };
         let is_local: bool = !uri.starts_with("//");
-        let uri_parts = Self::java_split(&uri, "/");
+        let uri_parts = Self::java_split(&uri, '/');
 
         if uri_parts.len() < 2 {
             return Err(SerializationError::new("URI is invalid"));
@@ -201,11 +198,10 @@ impl LongUriSerializer {
     }
 
     // This function is meant to replicate the behavior of the Java
-    // `String[] java.lang.String.split(String regex)` method.
-    fn java_split(input: &str, pattern: &str) -> Vec<String> {
-        let re = Regex::new(pattern).unwrap();
-        let mut result: Vec<String> = re
-            .split(input)
+    // `String[] java.lang.String.split(String regex)` method, for a single separator character.
+    fn java_split(input: &str, separator: char) -> Vec<String> {
+        let mut result: Vec<String> = input
+            .split(separator)
             .map(std::string::ToString::to_string)
             .collect();
 
@@ -11,139 +11,245 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use byteorder::WriteBytesExt;
-use std::io::Cursor;
-use std::io::Write;
-
-use crate::uprotocol::{Remote, UAuthority, UEntity, UUri};
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::transport::datamodel::{BufferPool, PooledBuffer};
+use crate::uprotocol::uauthority::{AddressType, AuthorityIdError, MAX_AUTHORITY_ID_LENGTH};
+use crate::uprotocol::uentity::{UEntityId, VersionMajor};
+use crate::uprotocol::uresource::UResourceId;
+use crate::uprotocol::{Remote, UAuthority, UCode, UEntity, UUri};
 use crate::uri::builder::resourcebuilder::UResourceBuilder;
-use crate::uri::serializer::{SerializationError, UriSerializer};
+use crate::uri::serializer::{SerializationError, UAuthorityRef, UriSerializer};
 use crate::uri::validator::UriValidator;
 
-const LOCAL_MICRO_URI_LENGTH: usize = 8; // local micro URI length
-const IPV4_MICRO_URI_LENGTH: usize = 12; // IPv4 micro URI length
-const IPV6_MICRO_URI_LENGTH: usize = 24; // IPv6 micro URI length
+/// The length of a local URI in micro form.
+pub const LOCAL_MICRO_URI_LENGTH: usize = 8;
+/// The length of a URI in micro form whose authority has an IPv4 address.
+pub const IPV4_MICRO_URI_LENGTH: usize = 12;
+/// The length of a URI in micro form whose authority has an IPv6 address.
+pub const IPV6_MICRO_URI_LENGTH: usize = 24;
 const UP_VERSION: u8 = 0x1; // UP version
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-enum AddressType {
-    Local = 0,
-    IPv4 = 1,
-    IPv6 = 2,
-    ID = 3,
-}
-
-impl AddressType {
-    fn value(self) -> u8 {
-        self as u8
-    }
-
-    fn from(value: u8) -> Option<AddressType> {
-        match value {
-            0 => Some(AddressType::Local),
-            1 => Some(AddressType::IPv4),
-            2 => Some(AddressType::IPv6),
-            3 => Some(AddressType::ID),
-            _ => None,
-        }
-    }
-}
-
-impl TryFrom<i32> for AddressType {
-    type Error = ();
-
-    fn try_from(value: i32) -> Result<Self, Self::Error> {
-        if let Ok(v) = u8::try_from(value) {
-            AddressType::from(v).ok_or(())
-        } else {
-            Err(())
-        }
-    }
-}
+/// The maximum length of a URI in micro form, as taken by one with an ID-based authority of 255 bytes.
+pub const MAX_MICRO_URI_LENGTH: usize = LOCAL_MICRO_URI_LENGTH + 1 + MAX_AUTHORITY_ID_LENGTH;
 
 /// `UriSerializer` that serializes a `UUri` to byte[] (micro format) per
 ///  <https://github.com/eclipse-uprotocol/uprotocol-spec/blob/main/basics/uri.adoc>
 pub struct MicroUriSerializer;
 
-impl UriSerializer<Vec<u8>> for MicroUriSerializer {
-    /// Serializes a `UUri` into a `Vec<u8>` following the Micro-URI specifications.
+impl MicroUriSerializer {
+    /// Gets the length of a `UUri` in micro form, e.g. for transports with a limited frame size that need to know
+    /// the size of a message before serializing it.
     ///
     /// # Parameters
     /// * `uri`: A reference to the `UUri` data object.
     ///
     /// # Returns
-    /// A `Vec<u8>` representing the serialized `UUri`.
-    #[allow(clippy::cast_possible_truncation)]
-    fn serialize(uri: &UUri) -> Result<Vec<u8>, SerializationError> {
-        if UriValidator::is_empty(uri) || !UriValidator::is_micro_form(uri) {
-            return Err(SerializationError::new("URI is empty or not in micro form"));
-        }
+    /// The number of bytes that [`MicroUriSerializer::serialize`] yields for the URI.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` for the URIs that [`MicroUriSerializer::serialize`] fails for.
+    pub fn serialized_len(uri: &UUri) -> Result<usize, SerializationError> {
+        let (address_type, address) = micro_uri_address(uri)?;
+        Ok(micro_uri_length(address_type, address))
+    }
 
-        let mut cursor = Cursor::new(Vec::new());
-        let mut address_type = AddressType::Local;
-        let mut authority_id: Option<Vec<u8>> = None;
-        let mut remote_ip: Option<Vec<u8>> = None;
+    /// Serializes a `UUri` into a buffer following the Micro-URI specifications, which spares the heap
+    /// allocation of [`MicroUriSerializer::serialize`], e.g. on constrained targets.
+    ///
+    /// # Parameters
+    /// * `uri`: A reference to the `UUri` data object.
+    /// * `buffer`: The buffer to write to, which can hold any URI if it is [`MAX_MICRO_URI_LENGTH`] bytes long.
+    ///
+    /// # Returns
+    /// The number of bytes written to the start of the buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` if the URI is not in micro form, its IDs or version are too large for it, its
+    /// authority has an IP address of neither 4 nor 16 bytes or an ID that is empty or longer than 255 bytes, or if
+    /// the buffer is too small. IDs are never truncated; an invalid ID fails with `SerializationError::AuthorityId`.
+    // bytes holds exactly the local part, the length of an ID and the address, as calculated by micro_uri_length
+    #[allow(clippy::indexing_slicing)]
+    pub fn serialize_to_slice(uri: &UUri, buffer: &mut [u8]) -> Result<usize, SerializationError> {
+        let (address_type, address) = micro_uri_address(uri)?;
+        let length = micro_uri_length(address_type, address);
+        let Some(bytes) = buffer.get_mut(..length) else {
+            return Err(SerializationError::new("Buffer is too small for micro URI"));
+        };
 
-        // UP_VERSION
-        cursor.write_u8(UP_VERSION).unwrap();
+        // both have been validated above
+        let (entity_id, version_major) = uri
+            .entity
+            .as_ref()
+            .and_then(|entity| entity.micro_form().ok())
+            .unwrap_or_default();
+        let resource_id = uri
+            .resource
+            .as_ref()
+            .and_then(|resource| resource.micro_id().ok())
+            .unwrap_or_default();
 
-        // ADDRESS_TYPE
-        if let Some(authority) = &uri.authority {
-            if authority.remote.is_none() {
-                address_type = AddressType::Local;
-            } else if let Some(id) = UAuthority::get_id(authority) {
-                authority_id = Some(id.to_vec());
-                address_type = AddressType::ID;
-            } else if let Some(ip) = UAuthority::get_ip(authority) {
-                match ip.len() {
-                    4 => address_type = AddressType::IPv4,
-                    16 => address_type = AddressType::IPv6,
-                    _ => return Err(SerializationError::new("Invalid IP address")),
-                }
-                remote_ip = Some(ip.to_vec());
-            }
-        }
+        // UP_VERSION, URESOURCE_ID, UENTITY_ID, UENTITY_VERSION and UNUSED
+        bytes[..LOCAL_MICRO_URI_LENGTH].copy_from_slice(&MicroUriSerializer::serialize_local(
+            entity_id,
+            version_major,
+            resource_id,
+        ));
 
-        cursor.write_u8(address_type.value()).unwrap();
+        // ADDRESS_TYPE
+        bytes[1] = address_type.value();
 
-        // URESOURCE_ID
-        if let Some(id) = uri.resource.as_ref().and_then(|resource| resource.id) {
-            cursor.write_all(&[(id >> 8) as u8]).unwrap();
-            cursor.write_all(&[id as u8]).unwrap();
+        // UAUTHORITY
+        if address_type == AddressType::ID {
+            bytes[8] = u8::try_from(address.len())
+                .map_err(|_| AuthorityIdError::TooLong(address.len()))?;
+            bytes[9..].copy_from_slice(address);
+        } else {
+            bytes[8..].copy_from_slice(address);
         }
 
-        // UENTITY_ID
-        if let Some(id) = uri.entity.as_ref().and_then(|entity| entity.id) {
-            cursor.write_all(&[(id >> 8) as u8]).unwrap();
-            cursor.write_all(&[id as u8]).unwrap();
-        }
+        Ok(length)
+    }
 
-        // UENTITY_VERSION
-        let version = uri
-            .entity
-            .as_ref()
-            .and_then(|entity| entity.version_major)
-            .unwrap_or(0);
-        cursor.write_u8(version as u8).unwrap();
+    /// Serializes a `UUri` following the Micro-URI specifications into a buffer of a pool, which spares the heap
+    /// allocation of [`MicroUriSerializer::serialize`] when publishing continuously.
+    ///
+    /// # Parameters
+    /// * `uri`: A reference to the `UUri` data object.
+    /// * `buffers`: The pool to take the buffer from.
+    ///
+    /// # Returns
+    /// The buffer holding the serialized `UUri`, which is put back into the pool when it is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` for the URIs that [`MicroUriSerializer::serialize`] fails for.
+    #[cfg(feature = "std")]
+    pub fn serialize_pooled<'a>(
+        uri: &UUri,
+        buffers: &'a BufferPool,
+    ) -> Result<PooledBuffer<'a>, SerializationError> {
+        let mut buffer = buffers.acquire();
+        buffer.resize(MAX_MICRO_URI_LENGTH, 0);
+        let length = MicroUriSerializer::serialize_to_slice(uri, &mut buffer)?;
+        buffer.truncate(length);
+        Ok(buffer)
+    }
 
-        // UNUSED
-        cursor.write_u8(0).unwrap();
+    /// Serializes a local URI into its micro form at compile time, sparing the topics that are known in advance
+    /// the serialization at startup, like in
+    /// `const DOOR: [u8; 8] = MicroUriSerializer::serialize_local(BODY_ACCESS, VERSION_1, DOOR_FRONT_LEFT);`.
+    /// The IDs are of types that cannot hold values too large for the micro form, so that this cannot fail.
+    ///
+    /// # Parameters
+    /// * `entity_id`: The ID of the uEntity.
+    /// * `version_major`: The major version of the uEntity.
+    /// * `resource_id`: The ID of the uResource.
+    ///
+    /// # Returns
+    /// The URI in micro form, equal to what [`MicroUriSerializer::serialize`] yields for the same IDs.
+    pub const fn serialize_local(
+        entity_id: UEntityId,
+        version_major: VersionMajor,
+        resource_id: UResourceId,
+    ) -> [u8; LOCAL_MICRO_URI_LENGTH] {
+        let resource_id = resource_id.value().to_be_bytes();
+        let entity_id = entity_id.value().to_be_bytes();
+        [
+            UP_VERSION,
+            AddressType::Local as u8,
+            resource_id[0],
+            resource_id[1],
+            entity_id[0],
+            entity_id[1],
+            version_major.value(),
+            0,
+        ]
+    }
 
-        // UAUTHORITY
-        if address_type != AddressType::Local {
-            if address_type == AddressType::ID && authority_id.is_some() {
-                let len = authority_id.as_ref().unwrap().len() as u8;
-                cursor.write_u8(len).unwrap();
-            }
+    /// Serializes a URI whose authority has an IPv4 address into its micro form at compile time.
+    ///
+    /// # Parameters
+    /// * `ip`: The IPv4 address of the uAuthority.
+    /// * `entity_id`: The ID of the uEntity.
+    /// * `version_major`: The major version of the uEntity.
+    /// * `resource_id`: The ID of the uResource.
+    ///
+    /// # Returns
+    /// The URI in micro form, equal to what [`MicroUriSerializer::serialize`] yields for the same address and IDs.
+    // the loop keeps the indices within the lengths of the arrays, and const functions cannot use get()
+    #[allow(clippy::indexing_slicing)]
+    pub const fn serialize_ipv4(
+        ip: [u8; 4],
+        entity_id: UEntityId,
+        version_major: VersionMajor,
+        resource_id: UResourceId,
+    ) -> [u8; IPV4_MICRO_URI_LENGTH] {
+        let local = MicroUriSerializer::serialize_local(entity_id, version_major, resource_id);
+        let mut bytes = [0u8; IPV4_MICRO_URI_LENGTH];
+        let mut index = 0;
+        while index < IPV4_MICRO_URI_LENGTH {
+            bytes[index] = if index < LOCAL_MICRO_URI_LENGTH {
+                local[index]
+            } else {
+                ip[index - LOCAL_MICRO_URI_LENGTH]
+            };
+            index += 1;
+        }
+        bytes[1] = AddressType::IPv4 as u8;
+        bytes
+    }
 
-            if let Some(id) = authority_id {
-                cursor.write_all(&id).unwrap();
-            } else if let Some(ip) = remote_ip {
-                cursor.write_all(&ip).unwrap();
-            }
+    /// Serializes a URI whose authority has an IPv6 address into its micro form at compile time.
+    ///
+    /// # Parameters
+    /// * `ip`: The IPv6 address of the uAuthority.
+    /// * `entity_id`: The ID of the uEntity.
+    /// * `version_major`: The major version of the uEntity.
+    /// * `resource_id`: The ID of the uResource.
+    ///
+    /// # Returns
+    /// The URI in micro form, equal to what [`MicroUriSerializer::serialize`] yields for the same address and IDs.
+    // the loop keeps the indices within the lengths of the arrays, and const functions cannot use get()
+    #[allow(clippy::indexing_slicing)]
+    pub const fn serialize_ipv6(
+        ip: [u8; 16],
+        entity_id: UEntityId,
+        version_major: VersionMajor,
+        resource_id: UResourceId,
+    ) -> [u8; IPV6_MICRO_URI_LENGTH] {
+        let local = MicroUriSerializer::serialize_local(entity_id, version_major, resource_id);
+        let mut bytes = [0u8; IPV6_MICRO_URI_LENGTH];
+        let mut index = 0;
+        while index < IPV6_MICRO_URI_LENGTH {
+            bytes[index] = if index < LOCAL_MICRO_URI_LENGTH {
+                local[index]
+            } else {
+                ip[index - LOCAL_MICRO_URI_LENGTH]
+            };
+            index += 1;
         }
+        bytes[1] = AddressType::IPv6 as u8;
+        bytes
+    }
+}
 
-        Ok(cursor.into_inner())
+impl UriSerializer<Vec<u8>> for MicroUriSerializer {
+    /// Serializes a `UUri` into a `Vec<u8>` following the Micro-URI specifications.
+    ///
+    /// # Parameters
+    /// * `uri`: A reference to the `UUri` data object.
+    ///
+    /// # Returns
+    /// A `Vec<u8>` representing the serialized `UUri`.
+    fn serialize(uri: &UUri) -> Result<Vec<u8>, SerializationError> {
+        let mut buffer = alloc::vec![0u8; MAX_MICRO_URI_LENGTH];
+        let length = MicroUriSerializer::serialize_to_slice(uri, &mut buffer)?;
+        buffer.truncate(length);
+        Ok(buffer)
     }
 
     /// Creates a `UUri` data object from a uProtocol micro URI.
@@ -156,80 +262,133 @@ impl UriSerializer<Vec<u8>> for MicroUriSerializer {
     ///
     /// Returns a `UUri` data object.
     fn deserialize(micro_uri: Vec<u8>) -> Result<UUri, SerializationError> {
-        if micro_uri.len() < LOCAL_MICRO_URI_LENGTH {
-            return Err(SerializationError::new("URI is empty or not in micro form"));
-        }
+        // The offset of the authority's address and whether it is an ID, if there is an authority
+        let address = match micro_uri_authority(&micro_uri)? {
+            Some(UAuthorityRef::Id(_)) => Some((LOCAL_MICRO_URI_LENGTH + 1, true)),
+            Some(_) => Some((LOCAL_MICRO_URI_LENGTH, false)),
+            None => None,
+        };
 
-        // Need to be version 1
-        if micro_uri[0] != 0x1 {
-            return Err(SerializationError::new("URI is not version 1"));
-        }
+        // URESOURCE_ID, UENTITY_ID and UENTITY_VERSION
+        let (uresource_id, ue_id, ue_version) = micro_uri_ids(&micro_uri)?;
+
+        // Calculate uAuthority, reusing the allocation of the micro URI for its address
+        let authority = address.map(|(offset, is_id)| {
+            let mut address = micro_uri;
+            address.drain(..offset);
+            let remote = if is_id {
+                Remote::Id(address)
+            } else {
+                Remote::Ip(address)
+            };
+            UAuthority {
+                remote: Some(remote),
+            }
+        });
 
-        // RESOURCE_ID
-        let uresource_id = u16::from_be_bytes(micro_uri[2..4].try_into().unwrap());
+        Ok(UUri {
+            authority,
+            entity: Some(UEntity::from_id(ue_id, ue_version)),
+            resource: Some(UResourceBuilder::from_id(uresource_id)),
+        })
+    }
+}
 
-        let address_type = AddressType::from(micro_uri[1]);
-        if address_type.is_none() {
-            return Err(SerializationError::new("Invalid address type"));
+// Validates that a URI can be serialized into micro form, and gets the address of its uAuthority.
+fn micro_uri_address(uri: &UUri) -> Result<(AddressType, &[u8]), SerializationError> {
+    UriValidator::validate_micro_form(uri).map_err(|e| match e.code() {
+        UCode::OutOfRange => SerializationError::new(e.message()),
+        _ => SerializationError::new("URI is empty or not in micro form"),
+    })?;
+
+    match uri.authority.as_ref() {
+        Some(authority) if authority.has_id() => {
+            authority.validate_remote_id()?;
+            Ok((AddressType::ID, authority.get_id().unwrap_or_default()))
         }
-
-        match address_type.unwrap() {
-            AddressType::Local => {
-                if micro_uri.len() != LOCAL_MICRO_URI_LENGTH {
-                    return Err(SerializationError::new("Invalid micro URI length"));
-                }
-            }
-            AddressType::IPv4 => {
-                if micro_uri.len() != IPV4_MICRO_URI_LENGTH {
-                    return Err(SerializationError::new("Invalid micro URI length"));
-                }
-            }
-            AddressType::IPv6 => {
-                if micro_uri.len() != IPV6_MICRO_URI_LENGTH {
-                    return Err(SerializationError::new("Invalid micro URI length"));
-                }
+        Some(authority) if authority.has_ip() => {
+            let ip = authority.get_ip().unwrap_or_default();
+            match ip.len() {
+                4 => Ok((AddressType::IPv4, ip)),
+                16 => Ok((AddressType::IPv6, ip)),
+                _ => Err(SerializationError::new("Invalid IP address")),
             }
-            AddressType::ID => {}
         }
+        _ => Ok((AddressType::Local, [].as_slice())),
+    }
+}
 
-        // UENTITY_ID
-        let ue_id = u16::from_be_bytes(micro_uri[4..6].try_into().unwrap());
+fn micro_uri_length(address_type: AddressType, address: &[u8]) -> usize {
+    if address_type == AddressType::ID {
+        LOCAL_MICRO_URI_LENGTH + 1 + address.len()
+    } else {
+        LOCAL_MICRO_URI_LENGTH + address.len()
+    }
+}
 
-        // VERSION_ID
-        let ue_version = u32::from(micro_uri[6]);
+/// Checks the header and the length of a URI in micro form.
+///
+/// # Returns
+/// The borrowed address of the URI's authority, or `None` for a local URI.
+///
+/// # Errors
+///
+/// Returns a `SerializationError` if the URI is not a valid URI in micro form.
+pub(crate) fn micro_uri_authority(
+    micro_uri: &[u8],
+) -> Result<Option<UAuthorityRef<'_>>, SerializationError> {
+    if micro_uri.len() < LOCAL_MICRO_URI_LENGTH {
+        return Err(SerializationError::new("URI is empty or not in micro form"));
+    }
 
-        // Calculate uAuthority
-        let mut authority: Option<UAuthority> = None;
-        match address_type.unwrap() {
-            AddressType::IPv4 => {
-                let slice: [u8; 4] = micro_uri[8..12].try_into().expect("Wrong slice length");
-                authority = Some(UAuthority {
-                    remote: Some(Remote::Ip(slice.to_vec())),
-                });
-            }
-            AddressType::IPv6 => {
-                let slice: [u8; 16] = micro_uri[8..24].try_into().expect("Wrong slice length");
-                authority = Some(UAuthority {
-                    remote: Some(Remote::Ip(slice.to_vec())),
-                });
-            }
-            AddressType::ID => {
-                authority = Some(UAuthority {
-                    remote: Some(Remote::Id(micro_uri[9..].to_vec())),
-                });
-            }
-            AddressType::Local => {}
+    // Need to be version 1
+    if micro_uri.first() != Some(&UP_VERSION) {
+        return Err(SerializationError::new("URI is not version 1"));
+    }
+
+    let Some(address_type) = AddressType::of_micro_uri(micro_uri) else {
+        return Err(SerializationError::new("Invalid address type"));
+    };
+
+    let expected_length = match address_type {
+        AddressType::Local => LOCAL_MICRO_URI_LENGTH,
+        AddressType::IPv4 => IPV4_MICRO_URI_LENGTH,
+        AddressType::IPv6 => IPV6_MICRO_URI_LENGTH,
+        AddressType::ID => match micro_uri.get(LOCAL_MICRO_URI_LENGTH) {
+            Some(id_length) => LOCAL_MICRO_URI_LENGTH + 1 + usize::from(*id_length),
+            None => return Err(SerializationError::new("Invalid micro URI length")),
+        },
+    };
+    if micro_uri.len() != expected_length {
+        return Err(SerializationError::new("Invalid micro URI length"));
+    }
+
+    // the length has been checked against the address type, so that the address is always there
+    let address = |offset: usize| micro_uri.get(offset..).unwrap_or_default();
+    Ok(match address_type {
+        AddressType::Local => None,
+        AddressType::IPv4 | AddressType::IPv6 => {
+            Some(UAuthorityRef::Ip(address(LOCAL_MICRO_URI_LENGTH)))
         }
+        AddressType::ID => Some(UAuthorityRef::Id(address(LOCAL_MICRO_URI_LENGTH + 1))),
+    })
+}
 
-        Ok(UUri {
-            authority,
-            entity: Some(UEntity {
-                id: Some(ue_id.into()),
-                version_major: Some(ue_version),
-                ..Default::default()
-            }),
-            resource: Some(UResourceBuilder::from_id(u32::from(uresource_id))),
-        })
+/// Gets the IDs of a URI in micro form, i.e. the resource ID, the entity ID and the entity's major version.
+///
+/// # Errors
+///
+/// Returns a `SerializationError` if the URI is shorter than a local URI in micro form.
+pub(crate) fn micro_uri_ids(
+    micro_uri: &[u8],
+) -> Result<(UResourceId, UEntityId, VersionMajor), SerializationError> {
+    match micro_uri {
+        [_, _, resource_msb, resource_lsb, entity_msb, entity_lsb, version_major, _, ..] => Ok((
+            UResourceId::new(u16::from_be_bytes([*resource_msb, *resource_lsb])),
+            UEntityId::new(u16::from_be_bytes([*entity_msb, *entity_lsb])),
+            VersionMajor::new(*version_major),
+        )),
+        _ => Err(SerializationError::new("URI is empty or not in micro form")),
     }
 }
 
@@ -332,6 +491,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_serialize_fails_for_oversized_ids() {
+        let uri = UUri {
+            entity: Some(UEntity {
+                id: Some(0x1_1102),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResourceBuilder::from_id(UResourceId::new(0x8000))),
+            ..Default::default()
+        };
+        assert_eq!(
+            MicroUriSerializer::serialize(&uri).unwrap_err().to_string(),
+            "uEntity ID [69890] does not fit into micro form"
+        );
+    }
+
     #[test]
     fn test_deserialize_bad_microuri_length() {
         let bad_uri: Vec<u8> = vec![0x1, 0x0, 0x0, 0x0, 0x0];
@@ -403,7 +579,10 @@ mod tests {
                 version_major: Some(254),
                 ..Default::default()
             }),
-            resource: Some(UResourceBuilder::for_rpc_request(None, Some(99))),
+            resource: Some(UResourceBuilder::for_rpc_request(
+                None,
+                Some(UResourceId::new(99)),
+            )),
         };
 
         let uprotocol_uri = MicroUriSerializer::serialize(&uri);
@@ -488,7 +667,10 @@ mod tests {
                 version_major: Some(254),
                 ..Default::default()
             }),
-            resource: Some(UResourceBuilder::for_rpc_request(None, Some(99))),
+            resource: Some(UResourceBuilder::for_rpc_request(
+                None,
+                Some(UResourceId::new(99)),
+            )),
         };
         let uprotocol_uri = MicroUriSerializer::serialize(&uri);
         assert!(uprotocol_uri.is_err());
@@ -524,4 +706,201 @@ mod tests {
         assert!(UriValidator::is_micro_form(uri2.as_ref().unwrap()));
         assert_eq!(uri, uri2.unwrap());
     }
+
+    #[test]
+    fn test_serialize_to_slice() {
+        let uri = UUri {
+            authority: Some(UAuthority {
+                remote: Some(Remote::Ip(vec![10, 0, 3, 3])),
+            }),
+            entity: Some(UEntity {
+                id: Some(29999),
+                version_major: Some(254),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                id: Some(19999),
+                ..Default::default()
+            }),
+        };
+
+        let mut buffer = [0u8; MAX_MICRO_URI_LENGTH];
+        let length = MicroUriSerializer::serialize_to_slice(&uri, &mut buffer).unwrap();
+        assert_eq!(length, IPV4_MICRO_URI_LENGTH);
+        assert_eq!(
+            &buffer[..length],
+            MicroUriSerializer::serialize(&uri).unwrap().as_slice()
+        );
+
+        let mut too_small = [0u8; IPV4_MICRO_URI_LENGTH - 1];
+        assert_eq!(
+            MicroUriSerializer::serialize_to_slice(&uri, &mut too_small)
+                .unwrap_err()
+                .to_string(),
+            "Buffer is too small for micro URI"
+        );
+    }
+
+    #[test]
+    fn test_serialized_len() {
+        let mut uri = UUri {
+            entity: Some(UEntity::from_id(
+                UEntityId::new(0x1102),
+                VersionMajor::new(1),
+            )),
+            resource: Some(UResourceBuilder::from_id(UResourceId::new(0x8000))),
+            ..Default::default()
+        };
+        assert_eq!(
+            MicroUriSerializer::serialized_len(&uri).unwrap(),
+            LOCAL_MICRO_URI_LENGTH
+        );
+
+        uri.authority = Some(UAuthority {
+            remote: Some(Remote::Id(vec![1, 2, 3])),
+        });
+        assert_eq!(
+            MicroUriSerializer::serialized_len(&uri).unwrap(),
+            MicroUriSerializer::serialize(&uri).unwrap().len()
+        );
+
+        uri.entity = None;
+        assert!(MicroUriSerializer::serialized_len(&uri).is_err());
+    }
+
+    #[test]
+    fn test_serialize_pooled() {
+        let uri = UUri {
+            entity: Some(UEntity {
+                id: Some(29999),
+                version_major: Some(254),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                id: Some(19999),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let buffers = BufferPool::default();
+        for _ in 0..2 {
+            let buffer = MicroUriSerializer::serialize_pooled(&uri, &buffers).unwrap();
+            assert_eq!(*buffer, MicroUriSerializer::serialize(&uri).unwrap());
+        }
+        assert_eq!(buffers.stats().reused, 1);
+    }
+
+    #[test]
+    fn test_serialize_too_long_id_based_authority() {
+        let uri = UUri {
+            authority: Some(UAuthority {
+                remote: Some(Remote::Id(vec![0; MAX_AUTHORITY_ID_LENGTH + 1])),
+            }),
+            entity: Some(UEntity {
+                id: Some(29999),
+                version_major: Some(254),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                id: Some(19999),
+                ..Default::default()
+            }),
+        };
+        let uprotocol_uri = MicroUriSerializer::serialize(&uri);
+        assert_eq!(
+            uprotocol_uri.unwrap_err().to_string(),
+            "Invalid authority ID"
+        );
+    }
+
+    #[test]
+    fn test_serialize_empty_id_based_authority() {
+        let uri = UUri {
+            authority: Some(UAuthority {
+                remote: Some(Remote::Id(vec![])),
+            }),
+            entity: Some(UEntity {
+                id: Some(29999),
+                version_major: Some(254),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                id: Some(19999),
+                ..Default::default()
+            }),
+        };
+        assert!(matches!(
+            MicroUriSerializer::serialize(&uri),
+            Err(SerializationError::AuthorityId {
+                source: AuthorityIdError::Empty,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_bad_microuri_id_length() {
+        let bad_uri: Vec<u8> = vec![0x1, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0];
+        let uprotocol_uri = MicroUriSerializer::deserialize(bad_uri);
+        assert_eq!(
+            uprotocol_uri.unwrap_err().to_string(),
+            "Invalid micro URI length"
+        );
+
+        let bad_uri: Vec<u8> = vec![0x1, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x3, 0x1, 0x2];
+        let uprotocol_uri = MicroUriSerializer::deserialize(bad_uri);
+        assert_eq!(
+            uprotocol_uri.unwrap_err().to_string(),
+            "Invalid micro URI length"
+        );
+    }
+
+    const DOOR_ENTITY: UEntityId = UEntityId::new(0x1102);
+    const DOOR_VERSION: VersionMajor = VersionMajor::new(1);
+    const DOOR_RESOURCE: UResourceId = UResourceId::new(0x8000);
+    const LOCAL_TOPIC: [u8; LOCAL_MICRO_URI_LENGTH] =
+        MicroUriSerializer::serialize_local(DOOR_ENTITY, DOOR_VERSION, DOOR_RESOURCE);
+    const IPV4_TOPIC: [u8; IPV4_MICRO_URI_LENGTH] = MicroUriSerializer::serialize_ipv4(
+        [192, 168, 1, 100],
+        DOOR_ENTITY,
+        DOOR_VERSION,
+        DOOR_RESOURCE,
+    );
+    const IPV6_TOPIC: [u8; IPV6_MICRO_URI_LENGTH] = MicroUriSerializer::serialize_ipv6(
+        Ipv6Addr::LOCALHOST.octets(),
+        DOOR_ENTITY,
+        DOOR_VERSION,
+        DOOR_RESOURCE,
+    );
+
+    #[test]
+    fn test_serialize_at_compile_time() {
+        let mut uri = UUri {
+            entity: Some(UEntity {
+                id: Some(0x1102),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResourceBuilder::from_id(UResourceId::new(0x8000))),
+            ..Default::default()
+        };
+        assert_eq!(MicroUriSerializer::serialize(&uri).unwrap(), LOCAL_TOPIC);
+
+        uri.authority = Some(UAuthority {
+            remote: Some(Remote::Ip(
+                Ipv4Addr::new(192, 168, 1, 100).octets().to_vec(),
+            )),
+        });
+        assert_eq!(MicroUriSerializer::serialize(&uri).unwrap(), IPV4_TOPIC);
+
+        uri.authority = Some(UAuthority {
+            remote: Some(Remote::Ip(Ipv6Addr::LOCALHOST.octets().to_vec())),
+        });
+        assert_eq!(MicroUriSerializer::serialize(&uri).unwrap(), IPV6_TOPIC);
+        assert_eq!(
+            MicroUriSerializer::deserialize(IPV6_TOPIC.to_vec()).unwrap(),
+            uri
+        );
+    }
 }
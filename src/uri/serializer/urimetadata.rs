@@ -0,0 +1,247 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+
+use crate::uprotocol::{UAuthority, UUri};
+use crate::uri::serializer::{
+    LongUriSerializer, MicroUriSerializer, SerializationError, UriSerializer,
+};
+
+/// `UriMetadata` keeps the names of the uAuthorities, uEntities and resources that URIs in micro form refer to, so
+/// that URIs received in micro form can be shown with their names, e.g. in logs, although the micro form loses them.
+///
+/// The names are hints, keyed by the micro form of what they name: the address of a uAuthority, and the URI of a
+/// resource without its uAuthority. They are recorded from URIs that carry both names and numeric identifiers, e.g.
+/// by a [`UriResolver`](crate::udiscovery::UriResolver) that has been given the metadata with `with_metadata`, and
+/// consulted by [`LongUriSerializer::serialize_with_metadata`]. The metadata holds a hint for each distinct resource
+/// and uAuthority recorded, until it is cleared.
+#[derive(Debug, Default)]
+pub struct UriMetadata {
+    authorities: RwLock<HashMap<Vec<u8>, String>>,
+    uris: RwLock<HashMap<Vec<u8>, UUri>>,
+}
+
+// the key of a uAuthority, i.e. its address type followed by its address
+fn authority_key(authority: &UAuthority) -> Option<Vec<u8>> {
+    let address = authority.get_id().or_else(|| authority.get_ip())?;
+    let mut key = vec![authority.address_type()?.value()];
+    key.extend_from_slice(address);
+    Some(key)
+}
+
+// the key of a URI, i.e. the micro form of the URI without its uAuthority
+fn uri_key(uri: &UUri) -> Option<Vec<u8>> {
+    let local = UUri {
+        authority: None,
+        ..uri.clone()
+    };
+    MicroUriSerializer::serialize(&local).ok()
+}
+
+impl UriMetadata {
+    /// Creates empty metadata.
+    pub fn new() -> Self {
+        UriMetadata::default()
+    }
+
+    /// Records the names of the uEntity and resource of a URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI, which is ignored unless it has both the names and the numeric identifiers of its uEntity
+    ///   and resource.
+    pub fn record(&self, uri: &UUri) {
+        let has_names = uri
+            .entity
+            .as_ref()
+            .map_or(false, |entity| !entity.name.is_empty())
+            && uri
+                .resource
+                .as_ref()
+                .map_or(false, |resource| !resource.name.is_empty());
+        if !has_names {
+            return;
+        }
+        if let Some(key) = uri_key(uri) {
+            let local = UUri {
+                authority: None,
+                ..uri.clone()
+            };
+            self.uris
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(key, local);
+        }
+    }
+
+    /// Records the name of a uAuthority.
+    ///
+    /// # Arguments
+    ///
+    /// * `authority` - The uAuthority, which is ignored unless it has an IP address or ID.
+    /// * `name` - The name of the uAuthority.
+    pub fn record_authority<T>(&self, authority: &UAuthority, name: T)
+    where
+        T: Into<String>,
+    {
+        if let Some(key) = authority_key(authority) {
+            self.authorities
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(key, name.into());
+        }
+    }
+
+    /// Fills in the names that a URI lacks, as far as they have been recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `uri` - The URI, e.g. as received in micro form.
+    ///
+    /// # Returns
+    ///
+    /// The URI, whose uAuthority has its name instead of its address if the name is known, and whose uEntity and
+    /// resource have their names in addition to their numeric identifiers if the names are known.
+    pub fn annotate(&self, uri: &UUri) -> UUri {
+        let mut annotated = uri.clone();
+        if let Some(authority) = annotated.authority.as_mut() {
+            let name = authority_key(authority).and_then(|key| {
+                self.authorities
+                    .read()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .get(&key)
+                    .cloned()
+            });
+            if let Some(name) = name {
+                authority.set_name(name);
+            }
+        }
+        let known = uri_key(uri).and_then(|key| {
+            self.uris
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get(&key)
+                .cloned()
+        });
+        if let Some(known) = known {
+            if let (Some(entity), Some(known)) = (annotated.entity.as_mut(), known.entity) {
+                if entity.name.is_empty() {
+                    entity.name = known.name;
+                }
+            }
+            if let (Some(resource), Some(known)) = (annotated.resource.as_mut(), known.resource) {
+                if resource.name.is_empty() {
+                    resource.name = known.name;
+                    resource.instance = known.instance;
+                    resource.message = known.message;
+                }
+            }
+        }
+        annotated
+    }
+
+    /// Forgets all recorded names.
+    pub fn clear(&self) {
+        self.authorities
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+        self.uris
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+impl LongUriSerializer {
+    /// Serializes a `UUri` into long form, filling in the names that it lacks from metadata.
+    ///
+    /// # Parameters
+    /// * `uri`: A reference to the `UUri` data object, e.g. as received in micro form.
+    /// * `metadata`: The metadata to take the names from, see [`UriMetadata::annotate`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` for the URIs that [`LongUriSerializer::serialize`] fails for.
+    pub fn serialize_with_metadata(
+        uri: &UUri,
+        metadata: &UriMetadata,
+    ) -> Result<String, SerializationError> {
+        LongUriSerializer::serialize(&metadata.annotate(uri))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uprotocol::{UEntity, UResource};
+
+    fn build_resolved_uri_for_test() -> UUri {
+        let mut authority = UAuthority::default();
+        authority.set_id(vec![0x2A]);
+        UUri {
+            authority: Some(authority),
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                id: Some(0x1234),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                id: Some(0x8001),
+                ..UResource::from("door.front_left#Door")
+            }),
+        }
+    }
+
+    #[test]
+    fn test_names_survive_micro_form() {
+        let resolved = build_resolved_uri_for_test();
+        let metadata = UriMetadata::new();
+        metadata.record(&resolved);
+        metadata.record_authority(resolved.authority.as_ref().unwrap(), "vcu.my_car_vin");
+
+        let micro_uri = MicroUriSerializer::serialize(&resolved).unwrap();
+        let received = MicroUriSerializer::deserialize(micro_uri).unwrap();
+        assert_eq!(
+            LongUriSerializer::serialize_with_metadata(&received, &metadata).unwrap(),
+            "//vcu.my_car_vin/body.access/1/door.front_left#Door"
+        );
+
+        let annotated = metadata.annotate(&received);
+        assert_eq!(annotated.entity.unwrap().id, Some(0x1234));
+        assert_eq!(annotated.resource.unwrap().id, Some(0x8001));
+    }
+
+    #[test]
+    fn test_unknown_uris_are_left_unchanged() {
+        let metadata = UriMetadata::new();
+        let mut resolved = build_resolved_uri_for_test();
+        metadata.record(&resolved);
+        metadata.clear();
+        assert_eq!(metadata.annotate(&resolved), resolved);
+
+        // a resource without name is not recorded
+        resolved.resource = Some(UResource {
+            id: Some(0x8002),
+            ..Default::default()
+        });
+        metadata.record(&resolved);
+        let mut received = resolved.clone();
+        received.entity.as_mut().unwrap().name = String::new();
+        assert_eq!(metadata.annotate(&received), received);
+    }
+}
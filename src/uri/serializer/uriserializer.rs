@@ -11,8 +11,11 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use crate::uprotocol::{Remote, UUri};
+#[cfg(feature = "std")]
+use crate::uprotocol::Remote;
+use crate::uprotocol::UUri;
 use crate::uri::serializer::SerializationError;
+#[cfg(feature = "std")]
 use crate::uri::validator::UriValidator;
 
 /// `UUri`s are used in transport layers and hence need to be serialized.
@@ -61,6 +64,7 @@ pub trait UriSerializer<T> {
     /// # Returns
     /// Returns an `Option<UUri>` object serialized from one of the forms. Returns `None` if the URI
     /// cannot be resolved.
+    #[cfg(feature = "std")]
     fn build_resolved(long_uri: &str, micro_uri: &[u8]) -> Option<UUri> {
         if long_uri.is_empty() && micro_uri.is_empty() {
             return Some(UUri {
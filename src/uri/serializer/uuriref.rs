@@ -0,0 +1,501 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use alloc::string::ToString;
+
+use crate::uprotocol::uentity::UEntityId;
+use crate::uprotocol::uresource::UResourceId;
+use crate::uprotocol::{Remote, UAuthority, UEntity, UResource, UUri};
+use crate::uri::serializer::microuriserializer::{micro_uri_authority, micro_uri_ids};
+use crate::uri::serializer::SerializationError;
+
+// The maximum number of path segments that the long form of a URI is made of, as in `//authority/name/1/resource`.
+const MAX_LONG_URI_PARTS: usize = 6;
+
+// Resource IDs below this value are the IDs of RPC methods, see `UResourceBuilder::from_id`.
+const MAX_RPC_ID: u16 = 1000;
+
+/// A borrowed view of the address of a remote uAuthority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UAuthorityRef<'a> {
+    Name(&'a str),
+    Ip(&'a [u8]),
+    Id(&'a [u8]),
+}
+
+/// A borrowed view of a `UEntity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UEntityRef<'a> {
+    pub name: &'a str,
+    pub id: Option<u32>,
+    pub version_major: Option<u32>,
+}
+
+/// A borrowed view of a `UResource`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UResourceRef<'a> {
+    pub name: &'a str,
+    pub instance: Option<&'a str>,
+    pub message: Option<&'a str>,
+    pub id: Option<u32>,
+}
+
+/// A borrowed view of a `UUri`, which refers to the serialized URI it has been parsed from instead of
+/// copying its names and addresses.
+///
+/// Parsing a URI into a view does not allocate, which spares components that only inspect URIs, like routers
+/// and dispatchers, the cost of a `UUri`. Views are converted to a `UUri` by means of [`UUriRef::to_uri`] if
+/// an owned URI is needed after all.
+///
+/// The views are created by the same rules as the `UUri`s of the [`LongUriSerializer`](crate::uri::serializer::LongUriSerializer)
+/// and the [`MicroUriSerializer`](crate::uri::serializer::MicroUriSerializer), so that
+/// `UUriRef::from_long_form(uri)?.to_uri()` equals `LongUriSerializer::deserialize(uri.to_string())?`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct UUriRef<'a> {
+    pub authority: Option<UAuthorityRef<'a>>,
+    pub entity: Option<UEntityRef<'a>>,
+    pub resource: Option<UResourceRef<'a>>,
+}
+
+impl<'a> UUriRef<'a> {
+    /// Creates the view of a local URI.
+    ///
+    /// Like the constructors of [`UEntityRef`] and [`UResourceRef`], this is a `const fn`, so that the URIs of
+    /// topics and methods that are known at compile time are defined as constants rather than parsed at startup:
+    ///
+    /// `const DOOR: UUriRef = UUriRef::local(UEntityRef::new("body.access", 1), UResourceRef::new("door", Some("front_left"), Some("Door")));`
+    ///
+    /// # Arguments
+    /// * `entity` - The uEntity of the URI.
+    /// * `resource` - The uResource of the URI.
+    pub const fn local(entity: UEntityRef<'a>, resource: UResourceRef<'a>) -> Self {
+        UUriRef {
+            authority: None,
+            entity: Some(entity),
+            resource: Some(resource),
+        }
+    }
+
+    /// Creates the view of a remote URI.
+    ///
+    /// # Arguments
+    /// * `authority` - The uAuthority of the URI.
+    /// * `entity` - The uEntity of the URI.
+    /// * `resource` - The uResource of the URI.
+    pub const fn remote(
+        authority: UAuthorityRef<'a>,
+        entity: UEntityRef<'a>,
+        resource: UResourceRef<'a>,
+    ) -> Self {
+        UUriRef {
+            authority: Some(authority),
+            entity: Some(entity),
+            resource: Some(resource),
+        }
+    }
+
+    /// Parses a URI in long form, like `//vcu.my_car_vin/body.access/1/door.front_left#Door`.
+    ///
+    /// # Arguments
+    /// * `uri` - The URI in long form.
+    ///
+    /// # Returns
+    /// The view of the URI, referring to `uri`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` for the URIs that the `LongUriSerializer` fails to deserialize.
+    pub fn from_long_form(uri: &'a str) -> Result<Self, SerializationError> {
+        if uri.is_empty() {
+            return Err(SerializationError::new("URI is empty"));
+        }
+
+        // Without a scheme, backslashes are taken as path separators, as the LongUriSerializer does
+        let (uri, is_separator): (&str, fn(char) -> bool) = match uri.split_once(':') {
+            Some((_, uri)) => (uri, is_slash),
+            None => (uri, is_slash_or_backslash),
+        };
+        let is_local = uri.chars().take(2).filter(|c| is_separator(*c)).count() < 2;
+
+        // Only the first segments are of interest, trailing empty segments are ignored like by Java's String.split()
+        let mut parts = [""; MAX_LONG_URI_PARTS];
+        let mut len = 0;
+        for (index, part) in uri.split(is_separator).enumerate() {
+            if let Some(slot) = parts.get_mut(index) {
+                *slot = part;
+            }
+            if !part.is_empty() {
+                len = index + 1;
+            }
+        }
+
+        if len < 2 {
+            return Err(SerializationError::new("URI is invalid"));
+        }
+
+        let (authority, entity_parts) = if is_local {
+            (
+                None,
+                parts
+                    .get(1..len.min(MAX_LONG_URI_PARTS))
+                    .unwrap_or_default(),
+            )
+        } else {
+            if parts[2].trim().is_empty() {
+                return Err(SerializationError::new("URI is invalid"));
+            }
+            let authority = Some(UAuthorityRef::Name(parts[2]));
+            if len < 4 {
+                return Ok(UUriRef {
+                    authority,
+                    ..Default::default()
+                });
+            }
+            (
+                authority,
+                parts
+                    .get(3..len.min(MAX_LONG_URI_PARTS))
+                    .unwrap_or_default(),
+            )
+        };
+
+        let version_major = match entity_parts.get(1) {
+            Some(version) if !version.is_empty() => Some(
+                version
+                    .parse::<u32>()
+                    .map_err(|_| SerializationError::new("URI is invalid"))?,
+            ),
+            _ => None,
+        };
+
+        Ok(UUriRef {
+            authority,
+            entity: Some(UEntityRef {
+                name: entity_parts.first().copied().unwrap_or_default(),
+                id: None,
+                version_major,
+            }),
+            resource: entity_parts
+                .get(2)
+                .map(|resource| UResourceRef::from(*resource)),
+        })
+    }
+
+    /// Parses a URI in micro form.
+    ///
+    /// # Arguments
+    /// * `uri` - The URI in micro form.
+    ///
+    /// # Returns
+    /// The view of the URI, referring to `uri` for the address of its uAuthority.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `SerializationError` for the URIs that the `MicroUriSerializer` fails to deserialize.
+    pub fn from_micro_form(uri: &'a [u8]) -> Result<Self, SerializationError> {
+        let authority = micro_uri_authority(uri)?;
+        let (resource_id, entity_id, version_major) = micro_uri_ids(uri)?;
+
+        Ok(UUriRef {
+            authority,
+            entity: Some(UEntityRef {
+                name: "",
+                id: Some(entity_id.into()),
+                version_major: Some(version_major.into()),
+            }),
+            resource: Some(UResourceRef::from_id(resource_id)),
+        })
+    }
+
+    /// Creates an owned `UUri` from the view, copying the names and addresses it refers to.
+    pub fn to_uri(&self) -> UUri {
+        UUri::from(self)
+    }
+}
+
+fn is_slash(c: char) -> bool {
+    c == '/'
+}
+
+fn is_slash_or_backslash(c: char) -> bool {
+    c == '/' || c == '\\'
+}
+
+impl<'a> UEntityRef<'a> {
+    /// Creates the view of a uEntity that is identified by its name.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the uEntity.
+    /// * `version_major` - The major version of the uEntity.
+    pub const fn new(name: &'a str, version_major: u32) -> Self {
+        UEntityRef {
+            name,
+            id: None,
+            version_major: Some(version_major),
+        }
+    }
+
+    /// Adds the ID to the view of a uEntity, which is needed for serializing its URIs to micro form.
+    pub const fn with_id(self, id: UEntityId) -> Self {
+        UEntityRef {
+            id: Some(id.value() as u32),
+            ..self
+        }
+    }
+}
+
+impl<'a> UResourceRef<'a> {
+    /// Creates the view of a resource that is identified by its name, like the topics in URIs in long form.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the resource.
+    /// * `instance` - The instance of the resource, like `front_left` of a door.
+    /// * `message` - The name of the protobuf message of the resource.
+    pub const fn new(name: &'a str, instance: Option<&'a str>, message: Option<&'a str>) -> Self {
+        UResourceRef {
+            name,
+            instance,
+            message,
+            id: None,
+        }
+    }
+
+    /// Creates the view of an RPC method, like
+    /// [`UResourceBuilder::for_rpc_request`](crate::uri::builder::resourcebuilder::UResourceBuilder::for_rpc_request) does.
+    ///
+    /// # Arguments
+    /// * `method` - The name of the method.
+    /// * `id` - The ID of the method.
+    pub const fn for_rpc_request(method: &'a str, id: UResourceId) -> Self {
+        UResourceRef {
+            name: "rpc",
+            instance: Some(method),
+            message: None,
+            id: Some(id.value() as u32),
+        }
+    }
+
+    /// Adds the ID to the view of a resource, which is needed for serializing its URIs to micro form.
+    pub const fn with_id(self, id: UResourceId) -> Self {
+        UResourceRef {
+            id: Some(id.value() as u32),
+            ..self
+        }
+    }
+
+    /// Creates the view of a resource that is identified by its ID only, like in URIs in micro form.
+    ///
+    /// IDs below 1000 identify RPC methods, which results in a resource named `rpc`, like
+    /// [`UResourceBuilder::from_id`](crate::uri::builder::resourcebuilder::UResourceBuilder::from_id) does.
+    pub const fn from_id(id: UResourceId) -> Self {
+        UResourceRef {
+            name: if id.value() < MAX_RPC_ID { "rpc" } else { "" },
+            instance: None,
+            message: None,
+            id: Some(id.value() as u32),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for UResourceRef<'a> {
+    /// Parses the resource part of a URI in long form, like `door.front_left#Door`.
+    fn from(value: &'a str) -> Self {
+        let mut parts = value.split('#');
+        let name_and_instance = parts.next().unwrap_or_default();
+        let message = parts.next();
+
+        let mut name_and_instance_parts = name_and_instance.split('.');
+        let name = name_and_instance_parts.next().unwrap_or_default();
+        let instance = name_and_instance_parts.next();
+
+        UResourceRef {
+            name,
+            instance,
+            message,
+            id: None,
+        }
+    }
+}
+
+impl From<&UAuthorityRef<'_>> for UAuthority {
+    fn from(authority: &UAuthorityRef<'_>) -> Self {
+        let remote = match authority {
+            UAuthorityRef::Name(name) => Remote::Name(name.to_string()),
+            UAuthorityRef::Ip(ip) => Remote::Ip(ip.to_vec()),
+            UAuthorityRef::Id(id) => Remote::Id(id.to_vec()),
+        };
+        UAuthority {
+            remote: Some(remote),
+        }
+    }
+}
+
+impl From<&UEntityRef<'_>> for UEntity {
+    fn from(entity: &UEntityRef<'_>) -> Self {
+        UEntity {
+            name: entity.name.to_string(),
+            id: entity.id,
+            version_major: entity.version_major,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&UResourceRef<'_>> for UResource {
+    fn from(resource: &UResourceRef<'_>) -> Self {
+        UResource {
+            name: resource.name.to_string(),
+            instance: resource.instance.map(ToString::to_string),
+            message: resource.message.map(ToString::to_string),
+            id: resource.id,
+        }
+    }
+}
+
+impl From<&UUriRef<'_>> for UUri {
+    fn from(uri: &UUriRef<'_>) -> Self {
+        UUri {
+            authority: uri.authority.as_ref().map(UAuthority::from),
+            entity: uri.entity.as_ref().map(UEntity::from),
+            resource: uri.resource.as_ref().map(UResource::from),
+        }
+    }
+}
+
+impl From<UUriRef<'_>> for UUri {
+    fn from(uri: UUriRef<'_>) -> Self {
+        UUri::from(&uri)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_case::test_case;
+
+    use crate::uprotocol::uentity::VersionMajor;
+    use crate::uri::serializer::{LongUriSerializer, MicroUriSerializer, UriSerializer};
+
+    #[test_case("/hartley//rpc.raise"; "local rpc")]
+    #[test_case("/body.access/1/door.front_left#Door"; "local with resource")]
+    #[test_case("up:/body.access/1"; "with scheme")]
+    #[test_case("\\body.access\\1\\door"; "backslashes")]
+    #[test_case("up:\\body.access\\1"; "backslashes with scheme")]
+    #[test_case("/body.access"; "without version")]
+    #[test_case("/body.access//"; "trailing slashes")]
+    #[test_case("//vcu.my_car_vin"; "authority only")]
+    #[test_case("//vcu.my_car_vin/body.access/1/door.front_left#Door"; "remote")]
+    #[test_case("//vcu.my_car_vin/body.access/1/door/more/parts"; "additional parts")]
+    #[test_case("///body.access/1"; "empty authority")]
+    #[test_case("/body.access/one"; "invalid version")]
+    #[test_case("/"; "slash only")]
+    #[test_case(""; "empty")]
+    fn test_from_long_form_matches_long_uri_serializer(uri: &str) {
+        let expected = LongUriSerializer::deserialize(uri.to_string());
+        let uri_ref = UUriRef::from_long_form(uri);
+        match expected {
+            Ok(expected) => assert_eq!(uri_ref.unwrap().to_uri(), expected),
+            Err(expected) => assert_eq!(uri_ref.unwrap_err().to_string(), expected.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_from_long_form_refers_to_input() {
+        let uri = "//vcu.my_car_vin/body.access/1/door.front_left#Door";
+        let uri_ref = UUriRef::from_long_form(uri).unwrap();
+        assert_eq!(
+            uri_ref,
+            UUriRef {
+                authority: Some(UAuthorityRef::Name("vcu.my_car_vin")),
+                entity: Some(UEntityRef {
+                    name: "body.access",
+                    id: None,
+                    version_major: Some(1),
+                }),
+                resource: Some(UResourceRef {
+                    name: "door",
+                    instance: Some("front_left"),
+                    message: Some("Door"),
+                    id: None,
+                }),
+            }
+        );
+    }
+
+    #[test_case(&[0x1, 0x0, 0x0, 0x5, 0x10, 0x0, 0x1, 0x0]; "local rpc")]
+    #[test_case(&[0x1, 0x0, 0x80, 0x0, 0x10, 0x0, 0x1, 0x0]; "local topic")]
+    #[test_case(&[0x1, 0x1, 0x0, 0x5, 0x10, 0x0, 0x1, 0x0, 192, 168, 1, 100]; "ipv4")]
+    #[test_case(&[0x1, 0x2, 0x0, 0x5, 0x10, 0x0, 0x1, 0x0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]; "ipv6")]
+    #[test_case(&[0x1, 0x3, 0x0, 0x5, 0x10, 0x0, 0x1, 0x0, 0x3, 0x1, 0x2, 0x3]; "id")]
+    #[test_case(&[0x1, 0x3, 0x0, 0x5, 0x10, 0x0, 0x1, 0x0, 0x4, 0x1, 0x2, 0x3]; "id of wrong length")]
+    #[test_case(&[0x1, 0x1, 0x0, 0x5, 0x10, 0x0, 0x1, 0x0]; "ipv4 without address")]
+    #[test_case(&[0x1, 0x5, 0x0, 0x5, 0x10, 0x0, 0x1, 0x0]; "invalid address type")]
+    #[test_case(&[0x2, 0x0, 0x0, 0x5, 0x10, 0x0, 0x1, 0x0]; "invalid version")]
+    #[test_case(&[0x1, 0x0, 0x0]; "too short")]
+    fn test_from_micro_form_matches_micro_uri_serializer(uri: &[u8]) {
+        let expected = MicroUriSerializer::deserialize(uri.to_vec());
+        let uri_ref = UUriRef::from_micro_form(uri);
+        match expected {
+            Ok(expected) => assert_eq!(uri_ref.unwrap().to_uri(), expected),
+            Err(expected) => assert_eq!(uri_ref.unwrap_err().to_string(), expected.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_from_micro_form_refers_to_input() {
+        let uri = [0x1, 0x1, 0x0, 0x5, 0x10, 0x0, 0x1, 0x0, 192, 168, 1, 100];
+        let uri_ref = UUriRef::from_micro_form(&uri).unwrap();
+        assert_eq!(uri_ref.authority, Some(UAuthorityRef::Ip(&uri[8..])));
+        assert_eq!(
+            uri_ref.resource,
+            Some(UResourceRef::from_id(UResourceId::new(5)))
+        );
+    }
+
+    const DOOR: UUriRef<'static> = UUriRef::local(
+        UEntityRef::new("body.access", 1).with_id(UEntityId::new(0x1102)),
+        UResourceRef::new("door", Some("front_left"), Some("Door"))
+            .with_id(UResourceId::new(0x8000)),
+    );
+    const UPDATE_DOOR: UUriRef<'static> = UUriRef::remote(
+        UAuthorityRef::Name("vcu.my_car_vin"),
+        UEntityRef::new("body.access", 1),
+        UResourceRef::for_rpc_request("UpdateDoor", UResourceId::new(1)),
+    );
+
+    #[test]
+    fn test_const_uris() {
+        let mut door = UUriRef::from_long_form("/body.access/1/door.front_left#Door").unwrap();
+        door.entity = door
+            .entity
+            .map(|entity| entity.with_id(UEntityId::new(0x1102)));
+        door.resource = door
+            .resource
+            .map(|resource| resource.with_id(UResourceId::new(0x8000)));
+        assert_eq!(DOOR, door);
+        assert_eq!(
+            MicroUriSerializer::serialize(&DOOR.to_uri()).unwrap(),
+            MicroUriSerializer::serialize_local(
+                UEntityId::new(0x1102),
+                VersionMajor::new(1),
+                UResourceId::new(0x8000)
+            )
+        );
+
+        assert_eq!(
+            LongUriSerializer::serialize(&UPDATE_DOOR.to_uri()).unwrap(),
+            "//vcu.my_car_vin/body.access/1/rpc.UpdateDoor"
+        );
+    }
+}
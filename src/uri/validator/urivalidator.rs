@@ -11,8 +11,10 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
+use alloc::string::{String, ToString};
+
 use crate::uprotocol::{UAuthority, UEntity, UResource, UUri};
-use crate::uri::validator::ValidationError;
+use crate::uri::validator::{ValidationError, ValidationReport};
 
 /// Struct to encapsulate Uri validation logic.
 pub struct UriValidator;
@@ -38,18 +40,33 @@ impl UriValidator {
             return Err(ValidationError::new("Uri is empty"));
         }
         if uri.authority.is_some() && !Self::is_remote(uri) {
-            return Err(ValidationError::new("Uri is remote missing uAuthority"));
+            return Err(
+                ValidationError::new("Uri is remote missing uAuthority").with_field("authority")
+            );
         }
         if uri
             .entity
             .as_ref()
             .map_or(true, |entity| entity.name.trim().is_empty())
         {
-            return Err(ValidationError::new("Uri is missing uSoftware Entity name"));
+            return Err(ValidationError::new("Uri is missing uSoftware Entity name")
+                .with_field("entity.name"));
         }
         Ok(())
     }
 
+    /// Validates a `UUri` like [`UriValidator::validate`], reporting the outcome in the same way as the other
+    /// validators do.
+    ///
+    /// # Arguments
+    /// * `uri` - The `UUri` to validate.
+    ///
+    /// # Returns
+    /// Returns a `ValidationReport` containing the error that [`UriValidator::validate`] fails with, if any.
+    pub fn report(uri: &UUri) -> ValidationReport {
+        ValidationReport::from(Self::validate(uri))
+    }
+
     /// Validates a `UUri` that is meant to be used as an RPC method URI.
     /// Used in Request sink values and Response source values.
     ///
@@ -68,7 +85,7 @@ impl UriValidator {
     pub fn validate_rpc_method(uri: &UUri) -> Result<(), ValidationError> {
         Self::validate(uri)?;
         if !Self::is_rpc_method(uri) {
-            return Err(ValidationError::new("Invalid RPC method uri. Uri should be the method to be called, or method from response"));
+            return Err(ValidationError::new("Invalid RPC method uri. Uri should be the method to be called, or method from response").with_field("resource"));
         }
         Ok(())
     }
@@ -93,7 +110,7 @@ impl UriValidator {
     pub fn validate_rpc_response(uri: &UUri) -> Result<(), ValidationError> {
         Self::validate(uri)?;
         if !Self::is_rpc_response(uri) {
-            return Err(ValidationError::new("Invalid RPC response type"));
+            return Err(ValidationError::new("Invalid RPC response type").with_field("resource"));
         }
         Ok(())
     }
@@ -179,9 +196,46 @@ impl UriValidator {
     ///
     /// # Returns
     /// Returns `true` if the `UAuthority` is of type remote.
-    #[allow(clippy::missing_panics_doc)]
     pub fn is_remote(uri: &UUri) -> bool {
-        uri.authority.is_some() && uri.authority.as_ref().unwrap().remote.is_some()
+        uri.authority
+            .as_ref()
+            .map_or(false, |authority| authority.remote.is_some())
+    }
+
+    /// Validates that a `UUri` contains numbers that fit into micro format, so that it can be serialized into it.
+    ///
+    /// # Arguments
+    /// * `uri` - The `UUri` to validate.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ValidationError` in the following cases:
+    ///
+    /// - If the `UUri` is empty, or its uAuthority has neither an IP address nor an ID, with code `UCode::InvalidArgument`.
+    /// - If the uEntity or the uResource is missing, or is missing its ID, with code `UCode::InvalidArgument`.
+    /// - If the ID of the uEntity or the uResource, or the major version of the uEntity, are too large for micro format, with code `UCode::OutOfRange`. See [`UEntity::validate_micro_form`] and [`UResource::validate_micro_form`].
+    pub fn validate_micro_form(uri: &UUri) -> Result<(), ValidationError> {
+        if Self::is_empty(uri) {
+            return Err(ValidationError::new("Uri is empty"));
+        }
+        if let Some(authority) = uri.authority.as_ref() {
+            if !authority.has_ip() && !authority.has_id() {
+                return Err(
+                    ValidationError::new("Uri is missing uAuthority IP address or ID")
+                        .with_field("authority"),
+                );
+            }
+        }
+        uri.entity
+            .as_ref()
+            .ok_or_else(|| ValidationError::new("Uri is missing uSoftware Entity"))
+            .and_then(UEntity::validate_micro_form)
+            .map_err(|e| e.within("entity"))?;
+        uri.resource
+            .as_ref()
+            .ok_or_else(|| ValidationError::new("Uri is missing uResource"))
+            .and_then(UResource::validate_micro_form)
+            .map_err(|e| e.within("resource"))
     }
 
     /// Checks if the URI contains numbers so that it can be serialized into micro format.
@@ -190,15 +244,9 @@ impl UriValidator {
     /// * `uri` - The `UUri` to check.
     ///
     /// # Returns
-    /// Returns `true` if the URI contains numbers, allowing it to be serialized into micro format.
-    #[allow(clippy::missing_panics_doc)]
+    /// Returns `true` if the URI contains numbers that fit into micro format, see [`UriValidator::validate_micro_form`].
     pub fn is_micro_form(uri: &UUri) -> bool {
-        !Self::is_empty(uri)
-            && uri.entity.as_ref().map_or(false, UEntity::has_id)
-            && uri.resource.as_ref().map_or(false, UResource::has_id)
-            && (uri.authority.is_none()
-                || UAuthority::has_ip(uri.authority.as_ref().unwrap())
-                || UAuthority::has_id(uri.authority.as_ref().unwrap()))
+        Self::validate_micro_form(uri).is_ok()
     }
 
     /// Checks if the URI contains names so that it can be serialized into long format.
@@ -241,10 +289,49 @@ mod tests {
     use std::fs;
 
     use crate::{
-        uprotocol::{UEntity, UResource},
+        uprotocol::{UCode, UEntity, UResource},
         uri::serializer::{LongUriSerializer, UriSerializer},
     };
 
+    #[test]
+    fn test_report() {
+        let uri = LongUriSerializer::deserialize("/body.access".to_string()).unwrap();
+        assert!(UriValidator::report(&uri).violations().is_empty());
+
+        let report = UriValidator::report(&UUri::default());
+        assert!(!report.is_valid());
+        assert_eq!(report.violations()[0].message(), "Uri is empty");
+    }
+
+    #[test]
+    fn test_validate_micro_form_tells_missing_from_oversized_ids() {
+        let mut uri = UUri {
+            entity: Some(UEntity {
+                id: Some(0x1102),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource {
+                id: Some(0x8000),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(UriValidator::validate_micro_form(&uri).is_ok());
+
+        uri.resource.as_mut().unwrap().id = None;
+        let missing = UriValidator::validate_micro_form(&uri).unwrap_err();
+        assert_eq!(missing.code(), UCode::InvalidArgument);
+        assert_eq!(missing.field(), Some("resource.id"));
+        assert!(!UriValidator::is_micro_form(&uri));
+
+        uri.resource.as_mut().unwrap().id = Some(0x1_0000);
+        let oversized = UriValidator::validate_micro_form(&uri).unwrap_err();
+        assert_eq!(oversized.code(), UCode::OutOfRange);
+        assert_eq!(oversized.field(), Some("resource.id"));
+        assert!(!UriValidator::is_micro_form(&uri));
+    }
+
     #[test]
     fn test_validate_blank_uri() {
         let uri = LongUriSerializer::deserialize("".to_string());
@@ -272,6 +359,20 @@ mod tests {
         assert_eq!(status.unwrap_err().to_string(), "Uri is empty");
     }
 
+    #[test]
+    fn test_validate_reports_field() {
+        let uri = UUri {
+            entity: Some(UEntity::default()),
+            ..Default::default()
+        };
+        let error = UriValidator::validate(&uri).unwrap_err();
+        assert_eq!(error.field(), Some("entity.name"));
+
+        let uri = LongUriSerializer::deserialize("/hartley/1/door.front_left".to_string()).unwrap();
+        let error = UriValidator::validate_rpc_method(&uri).unwrap_err();
+        assert_eq!(error.field(), Some("resource"));
+    }
+
     #[test]
     fn test_validate_rpc_method_with_valid_uri() {
         let uri = LongUriSerializer::deserialize("/hartley//rpc.echo".to_string()).unwrap();
@@ -0,0 +1,185 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::rpc::RpcMapper;
+use crate::uprotocol::core::usubscription::v3::{subscription_status::State, Update};
+use crate::uprotocol::{UMessage, UStatus, UUri};
+
+/// `SubscriptionChangeListener` is informed about changes to the subscriptions to a topic, as notified by the
+/// uSubscription service.
+///
+/// Listeners are registered with [`crate::pubsub::Subscriber::register_change_listener`]. Any closure taking an
+/// `Update` can be used as a listener.
+pub trait SubscriptionChangeListener: Send + Sync {
+    /// Called with each `Update` that the uSubscription service sends about the topic the listener is
+    /// registered for.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The topic and subscriber whose subscription has changed, along with its new status.
+    fn on_change(&self, update: Update);
+}
+
+impl<F> SubscriptionChangeListener for F
+where
+    F: Fn(Update) + Send + Sync,
+{
+    fn on_change(&self, update: Update) {
+        self(update);
+    }
+}
+
+/// The availability of a topic, as conveyed by an `Update` from the uSubscription service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopicAvailability {
+    /// The topic is published, and the subscription to it is active.
+    Published,
+    /// The subscription is waiting for the topic to be published, e.g. by a uEntity on a remote device.
+    Pending,
+    /// The topic is not available, e.g. because its producer has gone away or the subscription has been revoked.
+    Unavailable,
+}
+
+impl TopicAvailability {
+    /// Gets the availability of the topic that an `Update` is about.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - The update received from the uSubscription service.
+    ///
+    /// # Returns
+    ///
+    /// `TopicAvailability::Unavailable` if the update carries no status, or an unknown state.
+    pub fn of(update: &Update) -> TopicAvailability {
+        match update
+            .status
+            .as_ref()
+            .and_then(|status| State::try_from(status.state).ok())
+        {
+            Some(State::Subscribed) => TopicAvailability::Published,
+            Some(State::SubscribePending) => TopicAvailability::Pending,
+            _ => TopicAvailability::Unavailable,
+        }
+    }
+}
+
+// Wraps a change listener into a transport listener for the uSubscription notifications topic, which hands the
+// listener those updates that concern `topic`. Messages that cannot be unpacked into an `Update` are dropped.
+pub(crate) fn into_message_listener(
+    topic: UUri,
+    listener: Box<dyn SubscriptionChangeListener>,
+) -> Box<dyn Fn(Result<UMessage, UStatus>) + Send + Sync + 'static> {
+    Box::new(move |message| {
+        let Some(payload) = message.ok().and_then(|message| message.payload) else {
+            return;
+        };
+        let Ok(update) = RpcMapper::unpack_payload::<Update>(payload) else {
+            return;
+        };
+        if update.topic.as_ref() == Some(&topic) {
+            listener.on_change(update);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use prost::Message;
+    use prost_types::Any;
+
+    use crate::uprotocol::core::usubscription::v3::SubscriptionStatus;
+    use crate::uprotocol::{UCode, UEntity, UResource};
+
+    fn build_topic_for_test(resource: &str) -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from(resource)),
+            ..Default::default()
+        }
+    }
+
+    fn build_update_for_test(topic: UUri, state: State) -> Update {
+        Update {
+            topic: Some(topic),
+            status: Some(SubscriptionStatus {
+                state: state.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn build_message_for_test(update: &Update) -> UMessage {
+        UMessage {
+            payload: Some(
+                Any {
+                    type_url: "type.googleapis.com/uprotocol.core.usubscription.v3.Update"
+                        .to_string(),
+                    value: update.encode_to_vec(),
+                }
+                .try_into()
+                .unwrap(),
+            ),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_topic_availability() {
+        let topic = build_topic_for_test("door.front_left#Door");
+
+        let update = build_update_for_test(topic.clone(), State::Subscribed);
+        assert_eq!(TopicAvailability::of(&update), TopicAvailability::Published);
+        let update = build_update_for_test(topic.clone(), State::SubscribePending);
+        assert_eq!(TopicAvailability::of(&update), TopicAvailability::Pending);
+        let update = build_update_for_test(topic, State::Unsubscribed);
+        assert_eq!(
+            TopicAvailability::of(&update),
+            TopicAvailability::Unavailable
+        );
+        assert_eq!(
+            TopicAvailability::of(&Update::default()),
+            TopicAvailability::Unavailable
+        );
+    }
+
+    #[test]
+    fn test_message_listener_only_passes_on_updates_for_topic() {
+        let topic = build_topic_for_test("door.front_left#Door");
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let updates = received.clone();
+        let listener = into_message_listener(
+            topic.clone(),
+            Box::new(move |update: Update| updates.lock().unwrap().push(update)),
+        );
+
+        let update = build_update_for_test(topic, State::Unsubscribed);
+        listener(Ok(build_message_for_test(&update)));
+        listener(Ok(build_message_for_test(&build_update_for_test(
+            build_topic_for_test("door.front_right#Door"),
+            State::Subscribed,
+        ))));
+        listener(Ok(UMessage::default()));
+        listener(Err(UStatus::fail_with_code(UCode::Internal, "failure")));
+
+        assert_eq!(*received.lock().unwrap(), vec![update]);
+    }
+}
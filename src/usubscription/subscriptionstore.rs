@@ -0,0 +1,668 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::collections::HashMap;
+
+use prost::Message;
+use prost_types::Any;
+
+use crate::transport::builder::UAttributesBuilder;
+use crate::transport::datamodel::UTransport;
+use crate::uprotocol::core::usubscription::v3::{
+    fetch_subscriptions_request, subscription_status::State, FetchSubscriptionsRequest,
+    FetchSubscriptionsResponse, NotificationsRequest, SubscriberInfo, Subscription,
+    SubscriptionRequest, SubscriptionStatus, UnsubscribeRequest, Update,
+};
+use crate::uprotocol::{UCode, UPayload, UPriority, UStatus, UUri};
+use crate::usubscription::USubscriptionClient;
+
+/// A backend for persisting the subscriptions kept by a [`SubscriptionStore`] across restarts.
+pub trait SubscriptionPersistence: Send {
+    /// Loads all persisted subscriptions.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the subscriptions cannot be loaded.
+    fn load(&self) -> Result<Vec<Subscription>, UStatus>;
+
+    /// Persists a new or changed subscription.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscription` - The subscription.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the subscription cannot be persisted.
+    fn save(&mut self, subscription: &Subscription) -> Result<(), UStatus>;
+
+    /// Removes a subscription.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic of the subscription.
+    /// * `subscriber` - The URI of the subscriber.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` if the subscription cannot be removed.
+    fn delete(&mut self, topic: &UUri, subscriber: &UUri) -> Result<(), UStatus>;
+}
+
+/// `SubscriptionStore` keeps track of the subscriptions that a uSubscription service manages, and of the
+/// uEntities that have registered for notifications about changes to them.
+///
+/// The store only does the bookkeeping: deciding whether a subscription is `SUBSCRIBED` right away or remains
+/// `SUBSCRIBE_PENDING` until a remote uSubscription service confirms it is up to the service implementation.
+/// Each change to a subscription yields an `Update`, which can be handed to [`SubscriptionStore::notify`].
+#[derive(Default)]
+pub struct SubscriptionStore {
+    // keyed by the protobuf encoding of the topic, as UUri does not implement Hash
+    subscriptions: HashMap<Vec<u8>, Vec<Subscription>>,
+    observers: HashMap<Vec<u8>, Vec<SubscriberInfo>>,
+    persistence: Option<Box<dyn SubscriptionPersistence>>,
+}
+
+impl SubscriptionStore {
+    /// Creates an empty store that does not persist subscriptions.
+    pub fn new() -> SubscriptionStore {
+        SubscriptionStore::default()
+    }
+
+    /// Creates a store that persists subscriptions, initialized with the subscriptions that have been persisted before.
+    ///
+    /// # Arguments
+    ///
+    /// * `persistence` - The backend to persist subscriptions with.
+    ///
+    /// # Errors
+    ///
+    /// Returns the `UStatus` reported by the backend if the subscriptions cannot be loaded.
+    pub fn with_persistence(
+        persistence: Box<dyn SubscriptionPersistence>,
+    ) -> Result<SubscriptionStore, UStatus> {
+        let mut store = SubscriptionStore::default();
+        for subscription in persistence.load()? {
+            let key = topic_key(subscription.topic.as_ref());
+            store
+                .subscriptions
+                .entry(key)
+                .or_default()
+                .push(subscription);
+        }
+        store.persistence = Some(persistence);
+        Ok(store)
+    }
+
+    /// Adds a subscription, or changes the state of an existing one.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request to subscribe to a topic.
+    /// * `state` - The state of the subscription, `SUBSCRIBED` or `SUBSCRIBE_PENDING`.
+    ///
+    /// # Returns
+    ///
+    /// The update describing the subscription.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::InvalidArgument` if the request lacks a topic or subscriber,
+    /// or the state is neither `SUBSCRIBED` nor `SUBSCRIBE_PENDING`, or the failure reported by the persistence backend.
+    pub fn subscribe(
+        &mut self,
+        request: &SubscriptionRequest,
+        state: State,
+    ) -> Result<Update, UStatus> {
+        if !matches!(state, State::Subscribed | State::SubscribePending) {
+            return Err(UStatus::fail_with_code(
+                UCode::InvalidArgument,
+                "Subscription state must be SUBSCRIBED or SUBSCRIBE_PENDING",
+            ));
+        }
+        let (topic, subscriber) =
+            require_topic_and_subscriber(request.topic.as_ref(), request.subscriber.as_ref())?;
+
+        let subscription = Subscription {
+            topic: Some(topic.clone()),
+            subscriber: Some(subscriber.clone()),
+            status: Some(status_of(state)),
+            attributes: request.attributes.clone(),
+        };
+        if let Some(persistence) = &mut self.persistence {
+            persistence.save(&subscription)?;
+        }
+        let subscriptions = self
+            .subscriptions
+            .entry(topic_key(Some(topic)))
+            .or_default();
+        match subscriptions
+            .iter_mut()
+            .find(|existing| is_subscriber(existing, subscriber.uri.as_ref()))
+        {
+            Some(existing) => *existing = subscription.clone(),
+            None => subscriptions.push(subscription.clone()),
+        }
+        Ok(update_of(subscription))
+    }
+
+    /// Changes the state of an existing subscription, e.g. once a remote uSubscription service has confirmed it.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic of the subscription.
+    /// * `subscriber` - The URI of the subscriber.
+    /// * `state` - The new state, `SUBSCRIBED` or `SUBSCRIBE_PENDING`.
+    ///
+    /// # Returns
+    ///
+    /// The update describing the subscription.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::NotFound` if there is no such subscription, or any of the errors
+    /// returned by [`SubscriptionStore::subscribe`].
+    pub fn set_state(
+        &mut self,
+        topic: &UUri,
+        subscriber: &UUri,
+        state: State,
+    ) -> Result<Update, UStatus> {
+        let subscription = self
+            .subscription(topic, subscriber)
+            .ok_or_else(|| UStatus::fail_with_code(UCode::NotFound, "No such subscription"))?;
+        let request = SubscriptionRequest {
+            topic: subscription.topic.clone(),
+            subscriber: subscription.subscriber.clone(),
+            attributes: subscription.attributes.clone(),
+        };
+        self.subscribe(&request, state)
+    }
+
+    /// Removes a subscription.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request to unsubscribe from a topic.
+    ///
+    /// # Returns
+    ///
+    /// The update describing the subscription, in state `UNSUBSCRIBED`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::InvalidArgument` if the request lacks a topic or subscriber,
+    /// `UCode::NotFound` if there is no such subscription, or the failure reported by the persistence backend.
+    pub fn unsubscribe(&mut self, request: &UnsubscribeRequest) -> Result<Update, UStatus> {
+        let (topic, subscriber) =
+            require_topic_and_subscriber(request.topic.as_ref(), request.subscriber.as_ref())?;
+        let key = topic_key(Some(topic));
+        let index = self
+            .subscriptions
+            .get(&key)
+            .and_then(|subscriptions| {
+                subscriptions
+                    .iter()
+                    .position(|existing| is_subscriber(existing, subscriber.uri.as_ref()))
+            })
+            .ok_or_else(|| UStatus::fail_with_code(UCode::NotFound, "No such subscription"))?;
+
+        if let Some(persistence) = &mut self.persistence {
+            persistence.delete(topic, subscriber.uri.as_ref().unwrap_or(&UUri::default()))?;
+        }
+        let subscriptions = self.subscriptions.get_mut(&key).unwrap();
+        let mut subscription = subscriptions.remove(index);
+        if subscriptions.is_empty() {
+            self.subscriptions.remove(&key);
+        }
+        subscription.status = Some(status_of(State::Unsubscribed));
+        Ok(update_of(subscription))
+    }
+
+    /// Gets a subscription.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic of the subscription.
+    /// * `subscriber` - The URI of the subscriber.
+    ///
+    /// # Returns
+    ///
+    /// The subscription, or `None` if the subscriber has not subscribed to the topic.
+    pub fn subscription(&self, topic: &UUri, subscriber: &UUri) -> Option<&Subscription> {
+        self.subscriptions
+            .get(&topic_key(Some(topic)))?
+            .iter()
+            .find(|existing| is_subscriber(existing, Some(subscriber)))
+    }
+
+    /// Gets the subscriptions to a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic.
+    ///
+    /// # Returns
+    ///
+    /// The subscriptions, in the order in which they have been added.
+    pub fn subscribers(&self, topic: &UUri) -> &[Subscription] {
+        self.subscriptions
+            .get(&topic_key(Some(topic)))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Handles a `FetchSubscriptions` request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request, which selects the subscriptions by topic or by subscriber.
+    /// * `max_records` - The maximum number of subscriptions to return.
+    ///
+    /// # Returns
+    ///
+    /// The selected subscriptions, starting at the request's offset, ordered by topic.
+    pub fn fetch_subscriptions(
+        &self,
+        request: &FetchSubscriptionsRequest,
+        max_records: usize,
+    ) -> FetchSubscriptionsResponse {
+        let mut selected: Vec<&Subscription> = match &request.request {
+            Some(fetch_subscriptions_request::Request::Topic(topic)) => {
+                self.subscribers(topic).iter().collect()
+            }
+            Some(fetch_subscriptions_request::Request::Subscriber(subscriber)) => {
+                let mut keys: Vec<&Vec<u8>> = self.subscriptions.keys().collect();
+                keys.sort();
+                keys.into_iter()
+                    .flat_map(|key| &self.subscriptions[key])
+                    .filter(|existing| is_subscriber(existing, subscriber.uri.as_ref()))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        let offset = request.offset.unwrap_or_default() as usize;
+        let has_more_records = selected.len() > offset.saturating_add(max_records);
+        selected = selected
+            .into_iter()
+            .skip(offset)
+            .take(max_records)
+            .collect();
+        FetchSubscriptionsResponse {
+            subscriptions: selected.into_iter().cloned().collect(),
+            has_more_records: Some(has_more_records),
+            status: Some(UStatus::ok()),
+        }
+    }
+
+    /// Registers a uEntity for notifications about changes to the subscriptions to a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The topic and the uEntity to notify.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UStatus` with code `UCode::InvalidArgument` if the request lacks a topic or subscriber.
+    pub fn register_for_notifications(
+        &mut self,
+        request: &NotificationsRequest,
+    ) -> Result<(), UStatus> {
+        let (topic, subscriber) =
+            require_topic_and_subscriber(request.topic.as_ref(), request.subscriber.as_ref())?;
+        let observers = self.observers.entry(topic_key(Some(topic))).or_default();
+        if !observers
+            .iter()
+            .any(|observer| observer.uri == subscriber.uri)
+        {
+            observers.push(subscriber.clone());
+        }
+        Ok(())
+    }
+
+    /// Unregisters a uEntity from notifications about changes to the subscriptions to a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The topic and the uEntity that no longer wants to be notified.
+    pub fn unregister_for_notifications(&mut self, request: &NotificationsRequest) {
+        let key = topic_key(request.topic.as_ref());
+        let subscriber = request.subscriber.as_ref().and_then(|s| s.uri.as_ref());
+        if let Some(observers) = self.observers.get_mut(&key) {
+            observers.retain(|observer| observer.uri.as_ref() != subscriber);
+            if observers.is_empty() {
+                self.observers.remove(&key);
+            }
+        }
+    }
+
+    /// Gets the uEntities that have registered for notifications about changes to the subscriptions to a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic.
+    pub fn observers(&self, topic: &UUri) -> &[SubscriberInfo] {
+        self.observers
+            .get(&topic_key(Some(topic)))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Fans out an update of a subscription.
+    ///
+    /// The update is published to [`USubscriptionClient::notifications_topic`], and sent as a notification to each
+    /// of the uEntities that have registered for notifications about the subscription's topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - The transport to send the update with.
+    /// * `update` - The update, as returned by [`SubscriptionStore::subscribe`] and the like.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first failure reported by the transport. The update is sent to all recipients regardless.
+    pub async fn notify<T: UTransport>(
+        &self,
+        transport: &T,
+        update: &Update,
+    ) -> Result<(), UStatus> {
+        let payload = UPayload::try_from(Any {
+            type_url: "type.googleapis.com/uprotocol.core.usubscription.v3.Update".to_string(),
+            value: update.encode_to_vec(),
+        })
+        .map_err(|e| UStatus::from_code_and_source(UCode::Internal, e))?;
+
+        let mut result = transport
+            .send(
+                USubscriptionClient::notifications_topic(),
+                payload.clone(),
+                UAttributesBuilder::publish(UPriority::UpriorityCs0).build(),
+            )
+            .await;
+        for observer in self.observers(update.topic.as_ref().unwrap_or(&UUri::default())) {
+            let Some(sink) = &observer.uri else {
+                continue;
+            };
+            let attributes =
+                UAttributesBuilder::notification(UPriority::UpriorityCs0, sink.clone()).build();
+            let sent = transport
+                .send(
+                    USubscriptionClient::notifications_topic(),
+                    payload.clone(),
+                    attributes,
+                )
+                .await;
+            result = result.and(sent);
+        }
+        result
+    }
+}
+
+fn topic_key(topic: Option<&UUri>) -> Vec<u8> {
+    topic.map(UUri::encode_to_vec).unwrap_or_default()
+}
+
+fn is_subscriber(subscription: &Subscription, subscriber: Option<&UUri>) -> bool {
+    subscription
+        .subscriber
+        .as_ref()
+        .and_then(|s| s.uri.as_ref())
+        == subscriber
+}
+
+fn require_topic_and_subscriber<'a>(
+    topic: Option<&'a UUri>,
+    subscriber: Option<&'a SubscriberInfo>,
+) -> Result<(&'a UUri, &'a SubscriberInfo), UStatus> {
+    match (topic, subscriber) {
+        (Some(topic), Some(subscriber)) if subscriber.uri.is_some() => Ok((topic, subscriber)),
+        _ => Err(UStatus::fail_with_code(
+            UCode::InvalidArgument,
+            "Request must contain a topic and a subscriber URI",
+        )),
+    }
+}
+
+fn status_of(state: State) -> SubscriptionStatus {
+    SubscriptionStatus {
+        state: state.into(),
+        code: UCode::Ok.into(),
+        message: String::new(),
+    }
+}
+
+fn update_of(subscription: Subscription) -> Update {
+    Update {
+        topic: subscription.topic,
+        subscriber: subscription.subscriber,
+        status: subscription.status,
+        attributes: subscription.attributes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use crate::fixtures::RecordingTransport;
+    use crate::uprotocol::{UEntity, UResource};
+
+    fn build_uri_for_test(entity: &str, resource: &str) -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: entity.to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from(resource)),
+            ..Default::default()
+        }
+    }
+
+    fn build_subscriber_for_test(entity: &str) -> SubscriberInfo {
+        SubscriberInfo {
+            uri: Some(build_uri_for_test(entity, "rpc.response")),
+            ..Default::default()
+        }
+    }
+
+    fn build_request_for_test(topic: &str, subscriber: &str) -> SubscriptionRequest {
+        SubscriptionRequest {
+            topic: Some(build_uri_for_test("body.access", topic)),
+            subscriber: Some(build_subscriber_for_test(subscriber)),
+            ..Default::default()
+        }
+    }
+
+    // Keeps the persisted subscriptions in memory, shared with the test.
+    #[derive(Clone, Default)]
+    struct SharedPersistence {
+        subscriptions: Arc<Mutex<Vec<Subscription>>>,
+    }
+
+    impl SubscriptionPersistence for SharedPersistence {
+        fn load(&self) -> Result<Vec<Subscription>, UStatus> {
+            Ok(self.subscriptions.lock().unwrap().clone())
+        }
+
+        fn save(&mut self, subscription: &Subscription) -> Result<(), UStatus> {
+            let mut subscriptions = self.subscriptions.lock().unwrap();
+            subscriptions.retain(|s| {
+                s.topic != subscription.topic || s.subscriber != subscription.subscriber
+            });
+            subscriptions.push(subscription.clone());
+            Ok(())
+        }
+
+        fn delete(&mut self, topic: &UUri, subscriber: &UUri) -> Result<(), UStatus> {
+            self.subscriptions
+                .lock()
+                .unwrap()
+                .retain(|s| s.topic.as_ref() != Some(topic) || !is_subscriber(s, Some(subscriber)));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_subscribe_and_unsubscribe() {
+        let mut store = SubscriptionStore::new();
+        let topic = build_uri_for_test("body.access", "door.front_left#Door");
+
+        let update = store
+            .subscribe(
+                &build_request_for_test("door.front_left#Door", "app.one"),
+                State::SubscribePending,
+            )
+            .unwrap();
+        assert_eq!(update.status.unwrap().state, State::SubscribePending as i32);
+        store
+            .subscribe(
+                &build_request_for_test("door.front_left#Door", "app.two"),
+                State::Subscribed,
+            )
+            .unwrap();
+        assert_eq!(store.subscribers(&topic).len(), 2);
+
+        let subscriber = build_subscriber_for_test("app.one").uri.unwrap();
+        let update = store
+            .set_state(&topic, &subscriber, State::Subscribed)
+            .unwrap();
+        assert_eq!(update.status.unwrap().state, State::Subscribed as i32);
+        assert_eq!(store.subscribers(&topic).len(), 2);
+
+        let update = store
+            .unsubscribe(&UnsubscribeRequest {
+                topic: Some(topic.clone()),
+                subscriber: Some(build_subscriber_for_test("app.one")),
+            })
+            .unwrap();
+        assert_eq!(update.status.unwrap().state, State::Unsubscribed as i32);
+        assert!(store.subscription(&topic, &subscriber).is_none());
+        assert_eq!(store.subscribers(&topic).len(), 1);
+    }
+
+    #[test]
+    fn test_subscribe_fails_for_invalid_request() {
+        let mut store = SubscriptionStore::new();
+
+        let request = SubscriptionRequest {
+            topic: Some(build_uri_for_test("body.access", "door.front_left#Door")),
+            ..Default::default()
+        };
+        let status = store.subscribe(&request, State::Subscribed).unwrap_err();
+        assert_eq!(status.get_code(), UCode::InvalidArgument);
+
+        let request = build_request_for_test("door.front_left#Door", "app.one");
+        let status = store.subscribe(&request, State::Unsubscribed).unwrap_err();
+        assert_eq!(status.get_code(), UCode::InvalidArgument);
+    }
+
+    #[test]
+    fn test_unsubscribe_fails_for_unknown_subscription() {
+        let mut store = SubscriptionStore::new();
+
+        let status = store
+            .unsubscribe(&UnsubscribeRequest {
+                topic: Some(build_uri_for_test("body.access", "door.front_left#Door")),
+                subscriber: Some(build_subscriber_for_test("app.one")),
+            })
+            .unwrap_err();
+        assert_eq!(status.get_code(), UCode::NotFound);
+    }
+
+    #[test]
+    fn test_subscriptions_are_persisted() {
+        let persistence = SharedPersistence::default();
+        let mut store = SubscriptionStore::with_persistence(Box::new(persistence.clone())).unwrap();
+        for subscriber in ["app.one", "app.two"] {
+            store
+                .subscribe(
+                    &build_request_for_test("door.front_left#Door", subscriber),
+                    State::Subscribed,
+                )
+                .unwrap();
+        }
+        store
+            .unsubscribe(&UnsubscribeRequest {
+                topic: Some(build_uri_for_test("body.access", "door.front_left#Door")),
+                subscriber: Some(build_subscriber_for_test("app.one")),
+            })
+            .unwrap();
+
+        let restored = SubscriptionStore::with_persistence(Box::new(persistence)).unwrap();
+        let topic = build_uri_for_test("body.access", "door.front_left#Door");
+        assert_eq!(restored.subscribers(&topic), store.subscribers(&topic));
+        assert_eq!(restored.subscribers(&topic).len(), 1);
+    }
+
+    #[test]
+    fn test_fetch_subscriptions_of_subscriber() {
+        let mut store = SubscriptionStore::new();
+        for topic in [
+            "door.front_left#Door",
+            "door.front_right#Door",
+            "window.front_left#Window",
+        ] {
+            store
+                .subscribe(&build_request_for_test(topic, "app.one"), State::Subscribed)
+                .unwrap();
+        }
+        let mut request = FetchSubscriptionsRequest {
+            offset: None,
+            request: Some(fetch_subscriptions_request::Request::Subscriber(
+                build_subscriber_for_test("app.one"),
+            )),
+        };
+
+        let response = store.fetch_subscriptions(&request, 2);
+        assert_eq!(response.subscriptions.len(), 2);
+        assert_eq!(response.has_more_records, Some(true));
+
+        request.offset = Some(2);
+        let last_page = store.fetch_subscriptions(&request, 2);
+        assert_eq!(last_page.subscriptions.len(), 1);
+        assert_eq!(last_page.has_more_records, Some(false));
+        assert!(!response.subscriptions.contains(&last_page.subscriptions[0]));
+    }
+
+    #[tokio::test]
+    async fn test_notify_fans_out_update_to_observers() {
+        let mut store = SubscriptionStore::new();
+        let topic = build_uri_for_test("body.access", "door.front_left#Door");
+        let observer = build_subscriber_for_test("body.access");
+        let request = NotificationsRequest {
+            topic: Some(topic.clone()),
+            subscriber: Some(observer.clone()),
+        };
+        store.register_for_notifications(&request).unwrap();
+        store.register_for_notifications(&request).unwrap();
+        assert_eq!(store.observers(&topic).len(), 1);
+
+        let update = store
+            .subscribe(
+                &build_request_for_test("door.front_left#Door", "app.one"),
+                State::Subscribed,
+            )
+            .unwrap();
+        let transport = RecordingTransport::default();
+        store.notify(&transport, &update).await.unwrap();
+
+        let sent = transport.sent();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].2.sink, None);
+        assert_eq!(sent[1].2.sink, observer.uri);
+
+        store.unregister_for_notifications(&request);
+        assert!(store.observers(&topic).is_empty());
+    }
+}
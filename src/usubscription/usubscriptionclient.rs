@@ -0,0 +1,351 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::coreservices::{
+    usubscription_entity, usubscription_notifications_topic, USubscriptionMethod,
+};
+use crate::rpc::{RpcClient, RpcInvoker, RpcMapper};
+use crate::transport::builder::EntityProfile;
+use crate::uprotocol::core::usubscription::v3::{
+    FetchSubscriptionsRequest, FetchSubscriptionsResponse, NotificationsRequest,
+    SubscriptionRequest, SubscriptionResponse, UnsubscribeRequest,
+};
+use crate::uprotocol::{UEntity, UUri};
+use crate::UProtocolError;
+
+const DEFAULT_TTL: u32 = 10_000;
+
+/// `USubscriptionClient` invokes the methods of the
+/// [uSubscription service](https://github.com/eclipse-uprotocol/uprotocol-spec/blob/main/up-l3/usubscription/v3/README.adoc)
+/// on the local device, taking care of packing requests into and unpacking responses from payloads.
+#[derive(Clone, Copy)]
+pub struct USubscriptionClient {
+    invoker: RpcInvoker,
+}
+
+impl USubscriptionClient {
+    /// Creates a client that invokes the service's methods with a time-to-live of 10 seconds.
+    ///
+    /// # Type Parameters
+    ///
+    /// * `R` - The `RpcClient` to invoke the service's methods with.
+    pub fn new<R: RpcClient + 'static>() -> USubscriptionClient {
+        USubscriptionClient {
+            invoker: RpcInvoker::new::<R>(DEFAULT_TTL),
+        }
+    }
+
+    /// Sets the time-to-live of requests to the service.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - The time-to-live in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// The client.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: u32) -> USubscriptionClient {
+        self.invoker.set_ttl(ttl);
+        self
+    }
+
+    /// Sets the profile of the uEntity invoking the service's methods, whose defaults for requests are applied to
+    /// the requests to the service. The profile's time-to-live of requests, if it has one, replaces the one set by
+    /// [`USubscriptionClient::with_ttl`] before.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The profile.
+    ///
+    /// # Returns
+    ///
+    /// The client.
+    #[must_use]
+    pub fn with_profile(mut self, profile: EntityProfile) -> USubscriptionClient {
+        self.invoker.set_profile(profile);
+        self
+    }
+
+    /// Gets the uEntity of the uSubscription service.
+    pub fn entity() -> UEntity {
+        usubscription_entity()
+    }
+
+    /// Gets the topic that the uSubscription service publishes subscription change notifications to.
+    pub fn notifications_topic() -> UUri {
+        usubscription_notifications_topic()
+    }
+
+    /// Subscribes to a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The topic and subscriber.
+    ///
+    /// # Returns
+    ///
+    /// The status of the subscription.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` if the method could not be invoked, or its response could not be unpacked.
+    pub async fn subscribe(
+        &self,
+        request: SubscriptionRequest,
+    ) -> Result<SubscriptionResponse, UProtocolError> {
+        let response = self
+            .invoker
+            .invoke(USubscriptionMethod::Subscribe.uri(), &request)
+            .await;
+        Ok(RpcMapper::map_response(response)?)
+    }
+
+    /// Unsubscribes from a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The topic and subscriber.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` if the method could not be invoked, or the failure reported by the service.
+    pub async fn unsubscribe(&self, request: UnsubscribeRequest) -> Result<(), UProtocolError> {
+        self.invoker
+            .invoke_for_status(USubscriptionMethod::Unsubscribe.uri(), &request)
+            .await
+    }
+
+    /// Fetches the subscriptions to a topic, or of a subscriber.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The topic or subscriber, and the offset of the first subscription to fetch.
+    ///
+    /// # Returns
+    ///
+    /// The subscriptions.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` if the method could not be invoked, or its response could not be unpacked.
+    pub async fn fetch_subscriptions(
+        &self,
+        request: FetchSubscriptionsRequest,
+    ) -> Result<FetchSubscriptionsResponse, UProtocolError> {
+        let response = self
+            .invoker
+            .invoke(USubscriptionMethod::FetchSubscriptions.uri(), &request)
+            .await;
+        Ok(RpcMapper::map_response(response)?)
+    }
+
+    /// Registers for notifications about changes to the subscriptions to a topic.
+    ///
+    /// The notifications are published to [`USubscriptionClient::notifications_topic`].
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The topic and the subscriber to notify.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` if the method could not be invoked, or the failure reported by the service.
+    pub async fn register_for_notifications(
+        &self,
+        request: NotificationsRequest,
+    ) -> Result<(), UProtocolError> {
+        self.invoker
+            .invoke_for_status(
+                USubscriptionMethod::RegisterForNotifications.uri(),
+                &request,
+            )
+            .await
+    }
+
+    /// Unregisters from notifications about changes to the subscriptions to a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The topic and the subscriber that no longer wants to be notified.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` if the method could not be invoked, or the failure reported by the service.
+    pub async fn unregister_for_notifications(
+        &self,
+        request: NotificationsRequest,
+    ) -> Result<(), UProtocolError> {
+        self.invoker
+            .invoke_for_status(
+                USubscriptionMethod::UnregisterForNotifications.uri(),
+                &request,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use prost::Message;
+    use prost_types::Any;
+
+    use crate::rpc::{RpcClientResult, RpcMapperError};
+    use crate::uprotocol::core::usubscription::v3::{
+        subscription_status::State, Subscription, SubscriptionStatus,
+    };
+    use crate::uprotocol::UStatus;
+    use crate::uprotocol::{Data, UAttributes, UCode, UMessageType, UPayload, UResource};
+
+    // Echoes the topic of a request in the response, and rejects requests for topics with resource `denied`.
+    struct USubscriptionRpcClient;
+
+    #[async_trait]
+    impl RpcClient for USubscriptionRpcClient {
+        async fn invoke_method(
+            topic: UUri,
+            payload: UPayload,
+            attributes: UAttributes,
+        ) -> RpcClientResult {
+            assert_eq!(attributes.r#type, UMessageType::UmessageTypeRequest as i32);
+            assert_eq!(attributes.sink.as_ref(), Some(&topic));
+            let Some(Data::Value(data)) = payload.data else {
+                return Err(RpcMapperError::InvalidPayload("no data".to_string()));
+            };
+            let resource = topic.resource.unwrap();
+            let (type_url, value) = match resource.instance.as_deref() {
+                Some("Subscribe") => {
+                    let request = SubscriptionRequest::decode(data.as_slice()).unwrap();
+                    let response = SubscriptionResponse {
+                        status: Some(SubscriptionStatus {
+                            state: State::Subscribed.into(),
+                            ..Default::default()
+                        }),
+                        topic: request.topic,
+                        ..Default::default()
+                    };
+                    ("SubscriptionResponse", response.encode_to_vec())
+                }
+                Some("FetchSubscriptions") => {
+                    let response = FetchSubscriptionsResponse {
+                        subscriptions: vec![Subscription::default(); 2],
+                        has_more_records: Some(false),
+                        ..Default::default()
+                    };
+                    ("FetchSubscriptionsResponse", response.encode_to_vec())
+                }
+                Some("Unsubscribe") => {
+                    let request = UnsubscribeRequest::decode(data.as_slice()).unwrap();
+                    let status = if request.topic.unwrap().resource.unwrap().name == "denied" {
+                        UStatus::fail_with_code(UCode::PermissionDenied, "denied")
+                    } else {
+                        UStatus::ok()
+                    };
+                    return Ok(RpcMapper::pack_any(&status)?.try_into().unwrap());
+                }
+                _ => {
+                    return Err(RpcMapperError::UnexpectedError(
+                        "unknown method".to_string(),
+                    ))
+                }
+            };
+            Ok(Any {
+                type_url: format!("type.googleapis.com/uprotocol.core.usubscription.v3.{type_url}"),
+                value,
+            }
+            .try_into()
+            .unwrap())
+        }
+    }
+
+    fn build_topic_for_test(resource: &str) -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from(resource)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_method_uri() {
+        let uri = USubscriptionMethod::FetchSubscriptions.uri();
+        assert_eq!(uri.entity, Some(USubscriptionClient::entity()));
+        let resource = uri.resource.unwrap();
+        assert_eq!(resource.name, "rpc");
+        assert_eq!(resource.instance.as_deref(), Some("FetchSubscriptions"));
+        assert_eq!(resource.id, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let client = USubscriptionClient::new::<USubscriptionRpcClient>();
+        let topic = build_topic_for_test("door.front_left#Door");
+
+        let response = client
+            .subscribe(SubscriptionRequest {
+                topic: Some(topic.clone()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.topic, Some(topic));
+        assert_eq!(response.status.unwrap().state, State::Subscribed as i32);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_subscriptions() {
+        let client = USubscriptionClient::new::<USubscriptionRpcClient>().with_ttl(1000);
+
+        let response = client
+            .fetch_subscriptions(FetchSubscriptionsRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(response.subscriptions.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe_returns_failure_reported_by_service() {
+        let client = USubscriptionClient::new::<USubscriptionRpcClient>();
+
+        let request = UnsubscribeRequest {
+            topic: Some(build_topic_for_test("door.front_left#Door")),
+            ..Default::default()
+        };
+        assert!(client.unsubscribe(request).await.is_ok());
+
+        let request = UnsubscribeRequest {
+            topic: Some(build_topic_for_test("denied")),
+            ..Default::default()
+        };
+        let error = client.unsubscribe(request).await.unwrap_err();
+        assert_eq!(error.code(), UCode::PermissionDenied);
+    }
+
+    #[tokio::test]
+    async fn test_register_for_notifications_fails_if_method_cannot_be_invoked() {
+        let client = USubscriptionClient::new::<USubscriptionRpcClient>();
+
+        let error = client
+            .register_for_notifications(NotificationsRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), UCode::Unavailable);
+    }
+}
@@ -0,0 +1,331 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use crate::coreservices::{utwin_entity, UTwinMethod};
+use crate::rpc::{RpcClient, RpcInvoker, RpcMapper};
+use crate::transport::builder::EntityProfile;
+use crate::uprotocol::core::utwin::v1::{GetLastMessagesResponse, MessageResponse};
+use crate::uprotocol::{UCode, UEntity, UMessage, UStatus, UUri, UUriBatch};
+use crate::UProtocolError;
+
+const DEFAULT_TTL: u32 = 10_000;
+
+/// The last message published to a topic, as retrieved from the uTwin service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastMessage {
+    /// The topic.
+    pub topic: UUri,
+    /// The last message published to the topic, or the reason why it could not be retrieved.
+    pub message: Result<UMessage, UStatus>,
+}
+
+impl From<MessageResponse> for LastMessage {
+    fn from(response: MessageResponse) -> Self {
+        let status = response.status.unwrap_or_else(UStatus::ok);
+        let message = match response.message {
+            Some(message) if status.is_success() => Ok(message),
+            None if status.is_success() => Err(UStatus::fail_with_code(
+                UCode::NotFound,
+                "No message has been published to the topic yet",
+            )),
+            _ => Err(status),
+        };
+        LastMessage {
+            topic: response.topic.unwrap_or_default(),
+            message,
+        }
+    }
+}
+
+/// `UTwinClient` invokes the methods of the
+/// [uTwin service](https://github.com/eclipse-uprotocol/uprotocol-spec/blob/main/up-l3/utwin/v1/README.adoc)
+/// on the local device.
+///
+/// The uTwin service keeps the last message published to each topic, which allows applications to learn
+/// about the current state of a topic at startup, instead of waiting for the next message to be published.
+#[derive(Clone, Copy)]
+pub struct UTwinClient {
+    invoker: RpcInvoker,
+}
+
+impl UTwinClient {
+    /// Creates a client that invokes the service's methods with a time-to-live of 10 seconds.
+    ///
+    /// # Type Parameters
+    ///
+    /// * `R` - The `RpcClient` to invoke the service's methods with.
+    pub fn new<R: RpcClient + 'static>() -> UTwinClient {
+        UTwinClient {
+            invoker: RpcInvoker::new::<R>(DEFAULT_TTL),
+        }
+    }
+
+    /// Sets the time-to-live of requests to the service.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - The time-to-live in milliseconds.
+    ///
+    /// # Returns
+    ///
+    /// The client.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: u32) -> UTwinClient {
+        self.invoker.set_ttl(ttl);
+        self
+    }
+
+    /// Sets the profile of the uEntity invoking the service's methods, whose defaults for requests are applied to
+    /// the requests to the service. The profile's time-to-live of requests, if it has one, replaces the one set by
+    /// [`UTwinClient::with_ttl`] before.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The profile.
+    ///
+    /// # Returns
+    ///
+    /// The client.
+    #[must_use]
+    pub fn with_profile(mut self, profile: EntityProfile) -> UTwinClient {
+        self.invoker.set_profile(profile);
+        self
+    }
+
+    /// Gets the uEntity of the uTwin service.
+    pub fn entity() -> UEntity {
+        utwin_entity()
+    }
+
+    /// Gets the last messages published to topics.
+    ///
+    /// # Arguments
+    ///
+    /// * `topics` - The topics.
+    ///
+    /// # Returns
+    ///
+    /// The last message of each of the topics.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` if the method could not be invoked, or its response could not be unpacked.
+    pub async fn get_last_messages(
+        &self,
+        topics: Vec<UUri>,
+    ) -> Result<Vec<LastMessage>, UProtocolError> {
+        let response = self
+            .invoker
+            .invoke(
+                UTwinMethod::GetLastMessages.uri(),
+                &UUriBatch { uris: topics },
+            )
+            .await;
+        let response: GetLastMessagesResponse = RpcMapper::map_response(response)?;
+        Ok(response
+            .responses
+            .into_iter()
+            .map(LastMessage::from)
+            .collect())
+    }
+
+    /// Gets the last message published to a topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `topic` - The topic.
+    ///
+    /// # Returns
+    ///
+    /// The last message published to the topic.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` with code `UCode::NotFound` if no message has been published to the topic yet,
+    /// or any of the errors returned by [`UTwinClient::get_last_messages`].
+    pub async fn get_last_message(&self, topic: UUri) -> Result<UMessage, UProtocolError> {
+        self.get_last_messages(vec![topic.clone()])
+            .await?
+            .into_iter()
+            .find(|last_message| last_message.topic == topic)
+            .ok_or_else(|| {
+                UStatus::fail_with_code(UCode::NotFound, "uTwin service did not return topic")
+            })?
+            .message
+            .map_err(UProtocolError::from)
+    }
+
+    /// Sets the last message published to the message's topic.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `UProtocolError` if the method could not be invoked, or the failure reported by the service.
+    pub async fn set_last_message(&self, message: UMessage) -> Result<(), UProtocolError> {
+        self.invoker
+            .invoke_for_status(UTwinMethod::SetLastMessage.uri(), &message)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use prost::Message;
+    use prost_types::Any;
+
+    use crate::rpc::{RpcClientResult, RpcMapperError};
+    use crate::transport::builder::{UAttributesBuilder, UPayloadBuilder};
+    use crate::uprotocol::{Data, UAttributes, UPayload, UPriority, UResource};
+
+    fn build_topic_for_test(resource: &str) -> UUri {
+        UUri {
+            entity: Some(UEntity {
+                name: "body.access".to_string(),
+                version_major: Some(1),
+                ..Default::default()
+            }),
+            resource: Some(UResource::from(resource)),
+            ..Default::default()
+        }
+    }
+
+    fn build_message_for_test() -> UMessage {
+        UMessage {
+            source: Some(build_topic_for_test("door.front_left#Door")),
+            attributes: Some(UAttributesBuilder::publish(UPriority::UpriorityCs1).build()),
+            payload: Some(UPayloadBuilder::from_raw(vec![1]).build().unwrap()),
+        }
+    }
+
+    // Knows the last message of the `door.front_left` topic only, and refuses to set any messages.
+    struct UTwinRpcClient;
+
+    #[async_trait]
+    impl RpcClient for UTwinRpcClient {
+        async fn invoke_method(
+            topic: UUri,
+            payload: UPayload,
+            _attributes: UAttributes,
+        ) -> RpcClientResult {
+            let Some(Data::Value(data)) = payload.data else {
+                return Err(RpcMapperError::InvalidPayload("no data".to_string()));
+            };
+            match topic.resource.unwrap().id {
+                Some(1) => {
+                    let batch = UUriBatch::decode(data.as_slice()).unwrap();
+                    let responses = batch
+                        .uris
+                        .into_iter()
+                        .map(|topic| {
+                            if topic == build_topic_for_test("door.front_left#Door") {
+                                MessageResponse {
+                                    topic: Some(topic),
+                                    status: Some(UStatus::ok()),
+                                    message: Some(build_message_for_test()),
+                                }
+                            } else {
+                                MessageResponse {
+                                    topic: Some(topic),
+                                    status: Some(UStatus::fail_with_code(
+                                        UCode::NotFound,
+                                        "unknown topic",
+                                    )),
+                                    message: None,
+                                }
+                            }
+                        })
+                        .collect();
+                    Ok(Any {
+                        type_url:
+                            "type.googleapis.com/uprotocol.core.utwin.v1.GetLastMessagesResponse"
+                                .to_string(),
+                        value: GetLastMessagesResponse { responses }.encode_to_vec(),
+                    }
+                    .try_into()
+                    .unwrap())
+                }
+                Some(2) => {
+                    let status = UStatus::fail_with_code(UCode::PermissionDenied, "read only");
+                    Ok(RpcMapper::pack_any(&status)?.try_into().unwrap())
+                }
+                _ => Err(RpcMapperError::UnexpectedError(
+                    "unknown method".to_string(),
+                )),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_last_messages() {
+        let client = UTwinClient::new::<UTwinRpcClient>();
+
+        let last_messages = client
+            .get_last_messages(vec![
+                build_topic_for_test("door.front_left#Door"),
+                build_topic_for_test("door.front_right#Door"),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(last_messages.len(), 2);
+        assert_eq!(
+            last_messages[0].message.as_ref().unwrap().payload,
+            build_message_for_test().payload
+        );
+        assert_eq!(
+            last_messages[1].message.as_ref().unwrap_err().get_code(),
+            UCode::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_last_message() {
+        let client = UTwinClient::new::<UTwinRpcClient>();
+
+        let message = client
+            .get_last_message(build_topic_for_test("door.front_left#Door"))
+            .await
+            .unwrap();
+        assert_eq!(message.source, build_message_for_test().source);
+        assert_eq!(message.payload, build_message_for_test().payload);
+    }
+
+    #[tokio::test]
+    async fn test_set_last_message_returns_failure_reported_by_service() {
+        let client = UTwinClient::new::<UTwinRpcClient>();
+
+        let error = client
+            .set_last_message(build_message_for_test())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), UCode::PermissionDenied);
+    }
+
+    #[test]
+    fn test_last_message_from_response_without_message() {
+        let last_message = LastMessage::from(MessageResponse {
+            topic: Some(build_topic_for_test("door.front_left#Door")),
+            status: None,
+            message: None,
+        });
+        assert_eq!(
+            last_message.message.unwrap_err().get_code(),
+            UCode::NotFound
+        );
+    }
+}
@@ -0,0 +1,83 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::Arc;
+
+/// A source of the current time, e.g. for the timestamps of the UUIDs created by
+/// [`UUIDv8Builder`](crate::uuid::builder::UUIDv8Builder) and for checking whether messages have expired.
+///
+/// Implementations other than [`SystemClock`] allow for testing and simulating time-based behavior
+/// deterministically.
+pub trait UClock: Send + Sync {
+    /// Gets the current time.
+    ///
+    /// # Returns
+    ///
+    /// The number of milliseconds since UNIX Epoch, or `None` if the clock is set to a point in time
+    /// before UNIX Epoch.
+    fn now(&self) -> Option<u64>;
+}
+
+/// The clock of the platform.
+///
+/// On `wasm32-unknown-unknown`, which does not provide a system time, this is the clock of the
+/// JavaScript host, e.g. the browser.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl UClock for SystemClock {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    fn now(&self) -> Option<u64> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?;
+        u64::try_from(now.as_millis()).ok()
+    }
+
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn now(&self) -> Option<u64> {
+        let now = js_sys::Date::now();
+        (now >= 0.0).then_some(now as u64)
+    }
+}
+
+impl<C: UClock + ?Sized> UClock for Arc<C> {
+    fn now(&self) -> Option<u64> {
+        (**self).now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_clock_is_after_epoch() {
+        let instant = 0x18C684468F8u64; // Thu, 14 Dec 2023 12:19:23 GMT
+        assert!(SystemClock.now().unwrap() > instant);
+    }
+
+    #[test]
+    fn test_shared_clock_is_clock() {
+        struct FixedClock;
+        impl UClock for FixedClock {
+            fn now(&self) -> Option<u64> {
+                Some(0x18C684468F8)
+            }
+        }
+
+        let clock: Arc<dyn UClock> = Arc::new(FixedClock);
+        assert_eq!(UClock::now(&clock), Some(0x18C684468F8));
+    }
+}
@@ -0,0 +1,33 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use rand::Rng;
+
+/// A source of random numbers, e.g. for the random part of the UUIDs created by
+/// [`UUIDv8Builder`](crate::uuid::builder::UUIDv8Builder).
+pub trait URng {
+    /// Gets the next random number.
+    fn next_u64(&mut self) -> u64;
+}
+
+/// The random number generator of the platform, as provided by the operating system.
+///
+/// On `wasm32-unknown-unknown`, random numbers are taken from the JavaScript host's `crypto.getRandomValues`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRandomSource;
+
+impl URng for SystemRandomSource {
+    fn next_u64(&mut self) -> u64 {
+        rand::thread_rng().gen()
+    }
+}
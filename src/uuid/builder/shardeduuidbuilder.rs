@@ -0,0 +1,227 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use crate::uprotocol::Uuid as uproto_Uuid;
+use crate::uuid::builder::uuidbuilder::{next_msb, uuid_from_parts};
+use crate::uuid::builder::{SystemClock, SystemRandomSource, UClock, URng};
+
+/// The maximum number of shards, as the index of a shard takes the lowest 16 bits of its random part.
+pub const MAX_UUID_SHARDS: usize = 0x1_0000;
+
+const SHARD_INDEX_MASK: u64 = 0xffff;
+
+// The index of the current thread, which selects the shard that the thread creates its UUIDs with
+static NEXT_THREAD_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_INDEX: usize = NEXT_THREAD_INDEX.fetch_add(1, Ordering::Relaxed);
+}
+
+// Each shard takes a cache line of its own, so that threads updating their counters do not contend
+// for the same cache line.
+#[repr(align(64))]
+struct Shard {
+    msb: AtomicU64,
+    lsb: [u8; 8],
+}
+
+/// `ShardedUUIDv8Builder` creates uProtocol UUIDs like the [`UUIDv8Builder`](crate::uuid::builder::UUIDv8Builder),
+/// for uEntities that create them from many threads at once.
+///
+/// The monotonic counter of the UUIDs is kept per shard rather than per builder, and each thread creates its UUIDs
+/// with a shard of its own, as long as there are at least as many shards as threads. The random part of the UUIDs,
+/// i.e. the node ID, differs between shards, so that UUIDs of different shards never collide even though their
+/// counters are independent: the lowest 16 bits of a shard's random part are replaced with the index of the shard.
+///
+/// As for throughput:
+/// - creating a UUID takes a single atomic compare-and-swap on the counter of the shard, without taking a lock
+/// - threads that have a shard of their own do not contend with each other, so the throughput grows with the
+///   number of threads
+/// - threads that share a shard, because there are more threads than shards, contend for its counter, but still
+///   get distinct UUIDs
+/// - each shard can create up to 4095 UUIDs per millisecond, so the builder can create up to 4095 times as many
+///   as it has shards
+///
+/// The UUIDs of a single thread are monotonic, whereas the UUIDs of different threads are ordered by their
+/// timestamps only.
+pub struct ShardedUUIDv8Builder {
+    shards: Box<[Shard]>,
+    clock: Box<dyn UClock>,
+}
+
+impl ShardedUUIDv8Builder {
+    /// Creates a builder that takes timestamps and random numbers from the platform.
+    ///
+    /// # Arguments
+    ///
+    /// * `shards` - The number of shards, usually the number of threads that create UUIDs. The number is
+    ///              limited to between 1 and [`MAX_UUID_SHARDS`].
+    pub fn new(shards: usize) -> Self {
+        Self::with_sources(shards, SystemClock, &mut SystemRandomSource)
+    }
+
+    /// Creates a builder that takes timestamps and random numbers from the given sources.
+    ///
+    /// # Arguments
+    ///
+    /// * `shards` - The number of shards, limited to between 1 and [`MAX_UUID_SHARDS`].
+    /// * `clock` - The clock for the timestamps of the UUIDs.
+    /// * `random` - The source of the random part of the UUIDs, which is drawn once for all shards.
+    pub fn with_sources<C, R>(shards: usize, clock: C, random: &mut R) -> Self
+    where
+        C: UClock + 'static,
+        R: URng,
+    {
+        let random = random.next_u64() & !SHARD_INDEX_MASK;
+        let shards = (0..shards.clamp(1, MAX_UUID_SHARDS))
+            .map(|index| Shard {
+                msb: AtomicU64::new(0),
+                lsb: (random | index as u64).to_be_bytes(),
+            })
+            .collect();
+        ShardedUUIDv8Builder {
+            shards,
+            clock: Box::new(clock),
+        }
+    }
+
+    /// Gets the number of shards of the builder.
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Creates a new UUID for the current time of the builder's clock, with the shard of the current thread.
+    ///
+    /// # Panics
+    ///
+    /// if the clock
+    /// * is set to a point in time before UNIX Epoch, or
+    /// * is set to a point in time later than UNIX Epoch + 0xFFFFFFFFFFFF milliseconds
+    pub fn build(&self) -> uproto_Uuid {
+        if let Some(now) = self.clock.now() {
+            let shard = THREAD_INDEX.with(|index| *index % self.shards.len());
+            self.build_with_shard(shard, now)
+        } else {
+            panic!("current time is set to a point in time before UNIX Epoch");
+        }
+    }
+
+    fn build_with_shard(&self, shard: usize, timestamp: u64) -> uproto_Uuid {
+        let shard = &self.shards[shard];
+        uuid_from_parts(next_msb(&shard.msb, timestamp), shard.lsb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::thread;
+
+    struct FixedClock;
+    impl UClock for FixedClock {
+        fn now(&self) -> Option<u64> {
+            Some(0x18C684468F8) // Thu, 14 Dec 2023 12:19:23 GMT
+        }
+    }
+
+    struct FixedRandom;
+    impl URng for FixedRandom {
+        fn next_u64(&mut self) -> u64 {
+            0x0123_4567_89ab_cdef
+        }
+    }
+
+    #[test]
+    fn test_shards_have_distinct_node_ids() {
+        let builder = ShardedUUIDv8Builder::with_sources(3, FixedClock, &mut FixedRandom);
+        assert_eq!(builder.shards(), 3);
+
+        let uuids = (0..3)
+            .map(|shard| builder.build_with_shard(shard, 0x18C684468F8))
+            .collect::<Vec<_>>();
+        assert!(uuids.iter().all(uproto_Uuid::is_uprotocol_uuid));
+        assert_eq!(
+            uuids[0].to_hyphenated_string(),
+            "018c6844-68f8-8000-8123-456789ab0000"
+        );
+        assert_eq!(
+            uuids[2].to_hyphenated_string(),
+            "018c6844-68f8-8000-8123-456789ab0002"
+        );
+    }
+
+    #[test]
+    fn test_shard_counters_are_independent() {
+        let builder = ShardedUUIDv8Builder::with_sources(2, FixedClock, &mut FixedRandom);
+        builder.build_with_shard(0, 0x18C684468F8);
+        let second = builder.build_with_shard(0, 0x18C684468F8);
+        let other = builder.build_with_shard(1, 0x18C684468F8);
+
+        assert!(second
+            .to_hyphenated_string()
+            .starts_with("018c6844-68f8-8001-"));
+        assert!(other
+            .to_hyphenated_string()
+            .starts_with("018c6844-68f8-8000-"));
+    }
+
+    #[test]
+    fn test_number_of_shards_is_limited() {
+        assert_eq!(ShardedUUIDv8Builder::new(0).shards(), 1);
+        assert_eq!(
+            ShardedUUIDv8Builder::new(MAX_UUID_SHARDS + 1).shards(),
+            MAX_UUID_SHARDS
+        );
+    }
+
+    #[test]
+    fn test_uuids_of_threads_are_unique() {
+        const THREADS: usize = 8;
+        // the fixed clock lets each shard create up to 4095 distinct UUIDs, which suffices even if threads
+        // of other tests take indexes in between, so that threads share shards
+        const UUIDS_PER_THREAD: usize = 500;
+
+        let builder = Arc::new(ShardedUUIDv8Builder::with_sources(
+            THREADS,
+            FixedClock,
+            &mut FixedRandom,
+        ));
+        let handles = (0..THREADS)
+            .map(|_| {
+                let builder = builder.clone();
+                thread::spawn(move || {
+                    (0..UUIDS_PER_THREAD)
+                        .map(|_| builder.build())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut uuids = HashSet::new();
+        for handle in handles {
+            uuids.extend(
+                handle
+                    .join()
+                    .unwrap()
+                    .into_iter()
+                    .map(|uuid| (uuid.msb, uuid.lsb)),
+            );
+        }
+        assert_eq!(uuids.len(), THREADS * UUIDS_PER_THREAD);
+    }
+}
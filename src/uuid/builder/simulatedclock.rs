@@ -0,0 +1,140 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future;
+
+use crate::uuid::builder::{SystemClock, UClock};
+
+/// A clock whose time only passes when a test says so, for testing expiry and retry behavior deterministically
+/// instead of with `sleep`s.
+///
+/// Clones of a clock share their time, so a test keeps a clone to advance the time of the clock it has passed to the
+/// component under test, e.g. to `with_clock` constructors, [`UUIDv8Builder::with_sources`] or
+/// [`UAttributesValidator::is_expired_at`]. Functions that wait between retries by means of a `sleep` function that
+/// is passed in can be given [`SimulatedClock::sleep`], which advances the time instead of waiting.
+///
+/// [`UUIDv8Builder::with_sources`]: crate::uuid::builder::UUIDv8Builder::with_sources
+/// [`UAttributesValidator::is_expired_at`]: crate::transport::validator::UAttributesValidator::is_expired_at
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedClock {
+    now: Arc<AtomicU64>,
+}
+
+impl SimulatedClock {
+    /// Creates a clock.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time the clock starts at, in milliseconds since UNIX Epoch.
+    pub fn new(now: u64) -> SimulatedClock {
+        SimulatedClock {
+            now: Arc::new(AtomicU64::new(now)),
+        }
+    }
+
+    /// Creates a clock that starts at the current time of the platform.
+    pub fn starting_now() -> SimulatedClock {
+        SimulatedClock::new(SystemClock.now().unwrap_or(0))
+    }
+
+    /// Gets the time of the clock, in milliseconds since UNIX Epoch.
+    pub fn millis(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+
+    /// Sets the time of the clock, which may also turn it back.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time, in milliseconds since UNIX Epoch.
+    pub fn set(&self, now: u64) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    /// Lets time pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - The time to pass, which is counted in whole milliseconds.
+    pub fn advance(&self, duration: Duration) {
+        let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        // the closure always returns Some, so the update cannot fail
+        let _ = self
+            .now
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |now| {
+                Some(now.saturating_add(millis))
+            });
+    }
+
+    /// Lets time pass, as a replacement for the `sleep` function of an async runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - The time to pass.
+    ///
+    /// # Returns
+    ///
+    /// A future that is ready right away.
+    pub fn sleep(&self, duration: Duration) -> future::Ready<()> {
+        self.advance(duration);
+        future::ready(())
+    }
+}
+
+impl UClock for SimulatedClock {
+    fn now(&self) -> Option<u64> {
+        Some(self.millis())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uuid::builder::{SystemRandomSource, UUIDv8Builder};
+
+    const NOW: u64 = 0x18C684468F8; // Thu, 14 Dec 2023 12:19:23 GMT
+
+    #[test]
+    fn test_clones_share_time() {
+        let clock = SimulatedClock::new(NOW);
+        let clone = clock.clone();
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(clone.now(), Some(NOW + 2000));
+
+        clone.set(NOW);
+        assert_eq!(clock.millis(), NOW);
+        assert!(SimulatedClock::starting_now().millis() > NOW);
+    }
+
+    #[tokio::test]
+    async fn test_sleep_advances_time() {
+        let clock = SimulatedClock::new(NOW);
+        clock.sleep(Duration::from_millis(250)).await;
+        assert_eq!(clock.millis(), NOW + 250);
+    }
+
+    #[test]
+    fn test_uuids_take_time_of_clock() {
+        let clock = SimulatedClock::new(NOW);
+        let builder = UUIDv8Builder::with_sources(clock.clone(), &mut SystemRandomSource);
+        assert_eq!(builder.build().get_time(), Some(NOW));
+
+        clock.advance(Duration::from_millis(10));
+        assert_eq!(builder.build().get_time(), Some(NOW + 10));
+    }
+}
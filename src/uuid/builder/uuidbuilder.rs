@@ -11,12 +11,11 @@
  * SPDX-License-Identifier: Apache-2.0
  ********************************************************************************/
 
-use rand::Rng;
 use std::convert::Into;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::uprotocol::Uuid as uproto_Uuid;
+use crate::uuid::builder::{SystemClock, SystemRandomSource, UClock, URng};
 
 const MAX_COUNT: u64 = 0xfff;
 const MAX_TIMESTAMP_BITS: u8 = 48;
@@ -53,10 +52,13 @@ const MAX_TIMESTAMP_MASK: u64 = 0xffff << MAX_TIMESTAMP_BITS;
 /// | counter    | MUST be a 12 bit counter field that is reset at each unix_ts_ms tick, and incremented for each UUID generated within the 1ms precision of unix_ts_ms The counter provides the ability to generate 4096 events within 1ms however the precision of the clock is still 1ms accuracy |
 /// | var        | MUST be the The 2 bit variant defined by Section 4.1 of RFC |
 /// | rand_b     | MUST be a 62 bits random number that is generated at initialization time of the uE only and reused otherwise |
-
+///
+/// The timestamps and random numbers are taken from the platform, unless other sources are passed to
+/// [`UUIDv8Builder::with_sources`].
 pub struct UUIDv8Builder {
     msb: AtomicU64,
     lsb: [u8; 8],
+    clock: Box<dyn UClock>,
 }
 
 impl Default for UUIDv8Builder {
@@ -67,37 +69,77 @@ impl Default for UUIDv8Builder {
 
 impl UUIDv8Builder {
     pub fn new() -> Self {
+        Self::with_sources(SystemClock, &mut SystemRandomSource)
+    }
+
+    /// Creates a builder that takes timestamps and random numbers from the given sources, e.g. on platforms
+    /// without system time or random number generator.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - The clock for the timestamps of the UUIDs.
+    /// * `random` - The source of the random part of the UUIDs, which is drawn once.
+    pub fn with_sources<C, R>(clock: C, random: &mut R) -> Self
+    where
+        C: UClock + 'static,
+        R: URng,
+    {
         UUIDv8Builder {
             // we do not need to explicitly set the version and variant bits
             // because this will be done implicitly by the
             // call to uuid::builder::Builder::from_custom_bytes
             // when creating a UUID using one of the build functions
             msb: AtomicU64::new(0),
-            lsb: rand::thread_rng().gen::<u64>().to_be_bytes(),
+            lsb: random.next_u64().to_be_bytes(),
+            clock: Box::new(clock),
         }
     }
 
-    /// Creates a new UUID for the current system time.
+    /// Creates a new UUID for the current time of the builder's clock.
+    ///
+    /// # Panics
+    ///
+    /// if the clock
+    /// * is set to a point in time before UNIX Epoch, or
+    /// * is set to a point in time later than UNIX Epoch + 0xFFFFFFFFFFFF milliseconds
+    pub fn build(&self) -> uproto_Uuid {
+        if let Some(now) = self.clock.now() {
+            self.build_with_instant(now)
+        } else {
+            panic!("current time is set to a point in time before UNIX Epoch");
+        }
+    }
+
+    /// Creates new UUIDs for the current time of the builder's clock, like calling [`UUIDv8Builder::build`] `count`
+    /// times, but reserving the counter values of all UUIDs of a millisecond with a single compare-and-swap, e.g. for
+    /// publishers that stamp bursts of thousands of messages at once.
+    ///
+    /// The UUIDs are sequential, and no other UUID of the builder is created in between the UUIDs of a millisecond.
+    /// Like with [`UUIDv8Builder::build`], creating more than 4096 UUIDs within a millisecond repeats the last counter
+    /// value of the millisecond.
     ///
     /// # Arguments
     ///
-    /// * `timestamp` - The timestamp (in milliseconds since UNIX EPOCH) to use.
+    /// * `count` - The number of UUIDs to create.
     ///
     /// # Panics
     ///
-    /// if the system time
+    /// if the clock
     /// * is set to a point in time before UNIX Epoch, or
-    /// * is set to a point in time later than UNIX Epoch + 0xFFFFFFFFFFFF seconds
-    pub fn build(&self) -> uproto_Uuid {
-        if let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) {
-            if let Ok(now) = u64::try_from(now.as_millis()) {
-                self.build_with_instant(now)
-            } else {
-                panic!("current system time is set to a point in time too far in the future");
-            }
-        } else {
-            panic!("current system time is set to a point in time before UNIX Epoch");
+    /// * is set to a point in time later than UNIX Epoch + 0xFFFFFFFFFFFF milliseconds
+    pub fn build_many(&self, count: usize) -> Vec<uproto_Uuid> {
+        let mut uuids = Vec::with_capacity(count);
+        while uuids.len() < count {
+            let Some(now) = self.clock.now() else {
+                panic!("current time is set to a point in time before UNIX Epoch");
+            };
+            let remaining = u64::try_from(count - uuids.len()).unwrap_or(u64::MAX);
+            let (first_msb, reserved) = reserve_msbs(&self.msb, now, remaining);
+            uuids.extend(
+                (first_msb..first_msb + reserved).map(|msb| uuid_from_parts(msb, self.lsb)),
+            );
         }
+        uuids
     }
 
     /// Creates a new UUID for a given timestamp.
@@ -110,41 +152,82 @@ impl UUIDv8Builder {
     ///
     /// * if the given timestamp is greater than 2^48 - 1.
     fn build_with_instant(&self, timestamp: u64) -> uproto_Uuid {
-        assert!(
-            timestamp & MAX_TIMESTAMP_MASK == 0,
-            "Timestamp of UUID must not exceed 48 bits"
-        );
+        let msb = next_msb(&self.msb, timestamp);
+        uuid_from_parts(msb, self.lsb)
+    }
+}
 
-        let new_msb = {
-            let current_msb = self.msb.load(Ordering::SeqCst);
-
-            if timestamp == (current_msb >> 16) {
-                if (current_msb & MAX_COUNT) < MAX_COUNT {
-                    self.msb.fetch_add(1, Ordering::SeqCst);
-                } else {
-                    // this should never happen in practice because we
-                    // do not expect any uEntity to emit more than
-                    // 4095 messages/ms
-                    // so we simply keep the current counter at MAX_COUNT
-                }
-            } else {
-                self.msb.store(timestamp << 16, Ordering::SeqCst);
-            }
+/// Advances the most significant bits of the last UUID, i.e. its timestamp and counter, to those of the next UUID.
+///
+/// The update is a single compare-and-swap, so that concurrent callers never get the same counter value for a
+/// timestamp, short of the counter being exhausted.
+///
+/// # Panics
+///
+/// * if the given timestamp is greater than 2^48 - 1.
+pub(crate) fn next_msb(msb: &AtomicU64, timestamp: u64) -> u64 {
+    reserve_msbs(msb, timestamp, 1).0
+}
 
-            self.msb.load(Ordering::SeqCst)
-        };
+/// Advances the most significant bits of the last UUID by up to `count` UUIDs at once, with a single
+/// compare-and-swap like [`next_msb`].
+///
+/// # Returns
+///
+/// The most significant bits of the first of the reserved UUIDs, and the number of reserved UUIDs, whose counter
+/// values follow the first one. Fewer than `count` UUIDs, but at least one, are reserved if the counter is exhausted
+/// for the timestamp.
+///
+/// # Panics
+///
+/// * if the given timestamp is greater than 2^48 - 1.
+pub(crate) fn reserve_msbs(msb: &AtomicU64, timestamp: u64, count: u64) -> (u64, u64) {
+    assert!(
+        timestamp & MAX_TIMESTAMP_MASK == 0,
+        "Timestamp of UUID must not exceed 48 bits"
+    );
 
-        let mut bytes = [0u8; 16];
-        bytes[..8].copy_from_slice(&new_msb.to_be_bytes());
-        bytes[8..].copy_from_slice(&self.lsb);
-        uuid::Builder::from_custom_bytes(bytes).into_uuid().into()
+    let count = count.max(1);
+    let update = |current_msb: u64| {
+        if timestamp != (current_msb >> 16) {
+            Some((timestamp << 16) + count.min(MAX_COUNT + 1) - 1)
+        } else if (current_msb & MAX_COUNT) < MAX_COUNT {
+            Some(current_msb + count.min(MAX_COUNT - (current_msb & MAX_COUNT)))
+        } else {
+            // this should never happen in practice because we
+            // do not expect any uEntity to emit more than
+            // 4095 messages/ms
+            // so we simply keep the current counter at MAX_COUNT
+            None
+        }
+    };
+    match msb.fetch_update(Ordering::SeqCst, Ordering::SeqCst, update) {
+        Ok(previous_msb) => {
+            let last_msb = update(previous_msb).unwrap_or(previous_msb);
+            let first_msb = if timestamp == (previous_msb >> 16) {
+                previous_msb + 1
+            } else {
+                timestamp << 16
+            };
+            (first_msb, last_msb - first_msb + 1)
+        }
+        Err(current_msb) => (current_msb, 1),
     }
 }
 
+pub(crate) fn uuid_from_parts(msb: u64, lsb: [u8; 8]) -> uproto_Uuid {
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&msb.to_be_bytes());
+    bytes[8..].copy_from_slice(&lsb);
+    uuid::Builder::from_custom_bytes(bytes).into_uuid().into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::uuid::builder::SimulatedClock;
+
     #[test]
     fn test_build_creates_valid_uprotocol_uuid() {
         let uuid = UUIDv8Builder::new().build();
@@ -194,6 +277,107 @@ mod tests {
         assert_eq!(uuid1.lsb, uuid2.lsb);
     }
 
+    #[test]
+    fn test_build_with_sources() {
+        struct FixedClock;
+        impl UClock for FixedClock {
+            fn now(&self) -> Option<u64> {
+                Some(0x18C684468F8) // Thu, 14 Dec 2023 12:19:23 GMT
+            }
+        }
+        struct FixedRandom;
+        impl URng for FixedRandom {
+            fn next_u64(&mut self) -> u64 {
+                0x0123_4567_89ab_cdef
+            }
+        }
+
+        let uuid = UUIDv8Builder::with_sources(FixedClock, &mut FixedRandom).build();
+        assert!(uuid.is_uprotocol_uuid());
+        // the variant bits replace the two most significant bits of the random part
+        assert_eq!(
+            uuid.to_hyphenated_string(),
+            "018c6844-68f8-8000-8123-456789abcdef"
+        );
+    }
+
+    #[test]
+    fn test_uuids_of_threads_are_unique() {
+        let builder = std::sync::Arc::new(UUIDv8Builder::new());
+        let handles = (0..4)
+            .map(|_| {
+                let builder = builder.clone();
+                std::thread::spawn(move || {
+                    (0..1000)
+                        .map(|_| builder.build_with_instant(0x18C684468F8))
+                        .map(|uuid| uuid.msb)
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut counters = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+        counters.sort_unstable();
+        counters.dedup();
+        assert_eq!(counters.len(), 4000);
+    }
+
+    #[test]
+    fn test_build_many_creates_sequential_uuids() {
+        let clock = SimulatedClock::default();
+        clock.set(0x18C684468F8); // Thu, 14 Dec 2023 12:19:23 GMT
+
+        let builder = UUIDv8Builder::with_sources(clock, &mut SystemRandomSource);
+        let first = builder.build();
+        let uuids = builder.build_many(3);
+        assert_eq!(uuids.len(), 3);
+        assert!(uuids.iter().all(uproto_Uuid::is_uprotocol_uuid));
+        let counters = uuids
+            .iter()
+            .map(|uuid| uuid.msb & MAX_COUNT)
+            .collect::<Vec<_>>();
+        assert_eq!(counters, vec![1, 2, 3]);
+        assert_eq!(builder.build().msb, first.msb + 4);
+
+        // the counter is exhausted after 4096 UUIDs, and its last value is repeated then
+        let uuids = builder.build_many(5000);
+        assert_eq!(uuids.len(), 5000);
+        assert_eq!(uuids[0].msb & MAX_COUNT, 5);
+        assert!(uuids[4090..]
+            .iter()
+            .all(|uuid| uuid.msb & MAX_COUNT == MAX_COUNT));
+        assert!(builder.build_many(0).is_empty());
+    }
+
+    #[test]
+    fn test_reserve_msbs_of_threads_do_not_overlap() {
+        let msb = std::sync::Arc::new(AtomicU64::new(0));
+        let handles = (0..4)
+            .map(|_| {
+                let msb = msb.clone();
+                std::thread::spawn(move || {
+                    (0..100)
+                        .flat_map(|_| {
+                            let (first_msb, reserved) = reserve_msbs(&msb, 0x18C684468F8, 10);
+                            first_msb..first_msb + reserved
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut counters = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+        counters.sort_unstable();
+        counters.dedup();
+        assert_eq!(counters.len(), 4000);
+    }
+
     #[test]
     #[should_panic]
     fn test_uuid_panics_for_invalid_timestamp() {
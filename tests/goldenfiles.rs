@@ -0,0 +1,179 @@
+/********************************************************************************
+ * Copyright (c) 2023 Contributors to the Eclipse Foundation
+ *
+ * See the NOTICE file(s) distributed with this work for additional
+ * information regarding copyright ownership.
+ *
+ * This program and the accompanying materials are made available under the
+ * terms of the Apache License Version 2.0 which is available at
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * SPDX-License-Identifier: Apache-2.0
+ ********************************************************************************/
+
+// Golden-file tests pinning how uProtocol artifacts are serialized, see tests/golden/README.md. The output of this
+// crate needs to be equal to these artifacts byte for byte.
+
+use prost::Message;
+use serde::Deserialize;
+
+use uprotocol_sdk::transport::builder::UPayloadBuilder;
+use uprotocol_sdk::transport::validator::{UAttributesValidator, Validators};
+use uprotocol_sdk::uprotocol::{Data, UAttributes, UCode, UStatus, Uuid};
+use uprotocol_sdk::uri::serializer::{LongUriSerializer, MicroUriSerializer, UriSerializer};
+
+const GOLDEN_FILES: [&str; 1] = [include_str!("golden/uprotocol-rust.json")];
+
+#[derive(Deserialize)]
+struct GoldenFile {
+    sdk: String,
+    long_uris: Vec<String>,
+    micro_uris: Vec<String>,
+    uuids: Vec<String>,
+    packed_statuses: Vec<PackedStatus>,
+    attributes: Vec<GoldenAttributes>,
+}
+
+#[derive(Deserialize)]
+struct PackedStatus {
+    code: i32,
+    message: String,
+    packed: String,
+}
+
+#[derive(Deserialize)]
+struct GoldenAttributes {
+    name: String,
+    protobuf: String,
+    #[cfg_attr(not(feature = "protojson"), allow(dead_code))]
+    json: Option<serde_json::Value>,
+}
+
+fn golden_files() -> Vec<GoldenFile> {
+    GOLDEN_FILES
+        .iter()
+        .map(|json| serde_json::from_str(json).expect("golden file needs to be valid"))
+        .collect()
+}
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).expect("invalid hex string"))
+        .collect()
+}
+
+#[test]
+fn test_long_uris() {
+    for golden in golden_files() {
+        for long_form in golden.long_uris {
+            let uri = LongUriSerializer::deserialize(long_form.clone()).unwrap();
+            assert_eq!(
+                LongUriSerializer::serialize(&uri).unwrap(),
+                long_form,
+                "{}",
+                golden.sdk
+            );
+        }
+    }
+}
+
+#[test]
+fn test_micro_uris() {
+    for golden in golden_files() {
+        for micro_form in golden.micro_uris.iter().map(|hex| from_hex(hex)) {
+            let uri = MicroUriSerializer::deserialize(micro_form.clone()).unwrap();
+            assert_eq!(
+                MicroUriSerializer::serialize(&uri).unwrap(),
+                micro_form,
+                "{}",
+                golden.sdk
+            );
+        }
+    }
+}
+
+#[test]
+fn test_uuids() {
+    for golden in golden_files() {
+        for hyphenated in golden.uuids {
+            let uuid = hyphenated.parse::<Uuid>().unwrap();
+            assert!(uuid.is_uprotocol_uuid(), "{}: {hyphenated}", golden.sdk);
+            assert_eq!(uuid.to_hyphenated_string(), hyphenated, "{}", golden.sdk);
+        }
+    }
+}
+
+#[test]
+fn test_packed_statuses() {
+    for golden in golden_files() {
+        for packed in golden.packed_statuses {
+            let status =
+                UStatus::fail_with_code(UCode::try_from(packed.code).unwrap(), &packed.message);
+            let payload = UPayloadBuilder::from_protobuf(&status)
+                .unwrap()
+                .build()
+                .unwrap();
+            assert_eq!(
+                payload.data,
+                Some(Data::Value(from_hex(&packed.packed))),
+                "{}: {}",
+                golden.sdk,
+                packed.message
+            );
+        }
+    }
+}
+
+#[test]
+fn test_attributes() {
+    for golden in golden_files() {
+        for golden_attributes in golden.attributes {
+            let encoded = from_hex(&golden_attributes.protobuf);
+            let attributes = UAttributes::decode(encoded.as_slice()).unwrap();
+            assert!(
+                Validators::get_validator(&attributes)
+                    .validate(&attributes)
+                    .is_ok(),
+                "{}: {}",
+                golden.sdk,
+                golden_attributes.name
+            );
+            assert_eq!(
+                attributes.encode_to_vec(),
+                encoded,
+                "{}: {}",
+                golden.sdk,
+                golden_attributes.name
+            );
+        }
+    }
+}
+
+#[cfg(feature = "protojson")]
+#[test]
+fn test_attributes_json() {
+    for golden in golden_files() {
+        for golden_attributes in golden.attributes {
+            let Some(json) = golden_attributes.json else {
+                continue;
+            };
+            let attributes =
+                UAttributes::decode(from_hex(&golden_attributes.protobuf).as_slice()).unwrap();
+            assert_eq!(
+                UAttributes::from_json_str(&json.to_string()).unwrap(),
+                attributes,
+                "{}: {}",
+                golden.sdk,
+                golden_attributes.name
+            );
+            let serialized: serde_json::Value =
+                serde_json::from_str(&attributes.to_json_string().unwrap()).unwrap();
+            assert_eq!(
+                serialized, json,
+                "{}: {}",
+                golden.sdk, golden_attributes.name
+            );
+        }
+    }
+}